@@ -1,6 +1,8 @@
 use thiserror::Error;
 use tornado_common_api::Event;
 
+pub mod timestamp;
+
 /// A Collector is a source of Events.
 /// It collects information from one or more unstructured sources (e.g. emails, log files, etc.)
 ///   and produces structured Events to be sent to the Tornado engine.
@@ -18,4 +20,6 @@ pub enum CollectorError {
     JsonParsingError { message: String },
     #[error("CollectorCreationError: [{message}]")]
     CollectorCreationError { message: String },
+    #[error("SourceReadError: [{message}]")]
+    SourceReadError { message: String },
 }
@@ -0,0 +1,121 @@
+use crate::CollectorError;
+use chrono::{DateTime, NaiveDateTime, TimeZone};
+pub use chrono_tz::Tz;
+use std::convert::TryFrom;
+
+/// The supported formats of the timestamps without timezone information
+const ZONELESS_TIMESTAMP_FORMATS: [&str; 4] =
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%a, %d %b %Y %H:%M:%S", "%d %b %Y %H:%M:%S"];
+
+/// Parses the name of a timezone of the IANA database (e.g. 'Europe/Rome').
+/// Returns UTC if the timezone is not provided.
+pub fn parse_timezone(timezone: Option<&str>) -> Result<Tz, CollectorError> {
+    match timezone {
+        Some(timezone) => {
+            timezone.trim().parse::<Tz>().map_err(|_| CollectorError::CollectorCreationError {
+                message: format!("Invalid timezone [{}]", timezone),
+            })
+        }
+        None => Ok(Tz::UTC),
+    }
+}
+
+/// Parses a timestamp and returns the milliseconds since the Unix epoch.
+/// The RFC 3339 and RFC 2822 timestamps are parsed with their own timezone,
+/// while the timestamps without timezone information are interpreted in the `default_timezone`.
+pub fn parse_timestamp_ms(timestamp: &str, default_timezone: &Tz) -> Result<u64, CollectorError> {
+    let timestamp = timestamp.trim();
+    let timestamp_ms = match DateTime::parse_from_rfc3339(timestamp)
+        .or_else(|_| DateTime::parse_from_rfc2822(timestamp))
+    {
+        Ok(datetime) => datetime.timestamp_millis(),
+        Err(_) => parse_zoneless_timestamp_ms(timestamp, default_timezone)?,
+    };
+    u64::try_from(timestamp_ms).map_err(|_| CollectorError::EventCreationError {
+        message: format!("The timestamp [{}] is before the Unix epoch", timestamp),
+    })
+}
+
+fn parse_zoneless_timestamp_ms(timestamp: &str, timezone: &Tz) -> Result<i64, CollectorError> {
+    for format in ZONELESS_TIMESTAMP_FORMATS.iter() {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(timestamp, format) {
+            return timezone
+                .from_local_datetime(&datetime)
+                .earliest()
+                .map(|datetime| datetime.timestamp_millis())
+                .ok_or_else(|| CollectorError::EventCreationError {
+                    message: format!(
+                        "The timestamp [{}] does not exist in the timezone [{}]",
+                        timestamp, timezone
+                    ),
+                });
+        }
+    }
+    Err(CollectorError::EventCreationError {
+        message: format!("Cannot parse the timestamp [{}]", timestamp),
+    })
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn should_parse_a_zoneless_timestamp_in_the_default_timezone() {
+        // Arrange
+        let timestamp = "2023-06-01T10:00:00";
+
+        // Act
+        let utc_ms = parse_timestamp_ms(timestamp, &Tz::UTC).unwrap();
+        let rome_ms = parse_timestamp_ms(timestamp, &Tz::Europe__Rome).unwrap();
+
+        // Assert
+        assert_eq!(1_685_613_600_000, utc_ms);
+        // Europe/Rome is two hours ahead of UTC in June
+        assert_eq!(2 * 60 * 60 * 1000, utc_ms - rome_ms);
+    }
+
+    #[test]
+    fn should_parse_a_zoneless_rfc2822_like_timestamp_in_the_default_timezone() {
+        // Arrange
+        let timestamp = "Fri, 01 Dec 2023 10:00:00";
+
+        // Act
+        let utc_ms = parse_timestamp_ms(timestamp, &Tz::UTC).unwrap();
+        let new_york_ms = parse_timestamp_ms(timestamp, &Tz::America__New_York).unwrap();
+
+        // Assert
+        // America/New_York is five hours behind UTC in December
+        assert_eq!(5 * 60 * 60 * 1000, new_york_ms - utc_ms);
+    }
+
+    #[test]
+    fn should_honor_the_timezone_of_the_timestamp() {
+        // Act
+        let rfc3339_ms = parse_timestamp_ms("2023-06-01T10:00:00+02:00", &Tz::Asia__Tokyo).unwrap();
+        let rfc2822_ms =
+            parse_timestamp_ms("Thu, 01 Jun 2023 10:00:00 +0200", &Tz::Asia__Tokyo).unwrap();
+
+        // Assert
+        assert_eq!(1_685_606_400_000, rfc3339_ms);
+        assert_eq!(1_685_606_400_000, rfc2822_ms);
+    }
+
+    #[test]
+    fn should_fail_to_parse_an_invalid_timestamp() {
+        // Act
+        let result = parse_timestamp_ms("yesterday", &Tz::UTC);
+
+        // Assert
+        assert!(matches!(result, Err(CollectorError::EventCreationError { .. })));
+    }
+
+    #[test]
+    fn should_parse_the_timezone() {
+        // Assert
+        assert_eq!(Tz::UTC, parse_timezone(None).unwrap());
+        assert_eq!(Tz::Europe__Rome, parse_timezone(Some("Europe/Rome")).unwrap());
+        assert!(parse_timezone(Some("Mars/Olympus")).is_err());
+    }
+}
@@ -2,26 +2,59 @@ use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
 use log::*;
 use mailparse::body::Body;
 use mailparse::{
-    dateparse, parse_mail, DispositionType, MailHeaderMap, MailParseError, ParsedMail,
+    dateparse, parse_content_type, parse_headers, parse_mail, DispositionType, MailHeaderMap,
+    MailParseError, ParsedMail,
 };
 use serde_json::json;
+use std::borrow::Cow;
+use tornado_collector_common::timestamp::{parse_timestamp_ms, Tz};
 use tornado_collector_common::{Collector, CollectorError};
 use tornado_common_api::{Event, Payload, Value};
 
+/// The default max number of MIME subparts of an email processed by the collector
+pub const DEFAULT_MAX_MIME_PARTS: usize = 1000;
+
 /// The Email Collector receives a MIME email message as input, parses it and produces a Tornado Event.
-#[derive(Default)]
-pub struct EmailEventCollector {}
+pub struct EmailEventCollector {
+    default_timezone: Tz,
+    max_mime_parts: usize,
+}
+
+impl Default for EmailEventCollector {
+    fn default() -> Self {
+        EmailEventCollector::new()
+    }
+}
 
 impl EmailEventCollector {
     pub fn new() -> EmailEventCollector {
-        Default::default()
+        EmailEventCollector::new_with_default_timezone(Tz::UTC)
+    }
+
+    /// Creates an EmailEventCollector that interprets the email dates
+    /// without timezone information in the `default_timezone`.
+    pub fn new_with_default_timezone(default_timezone: Tz) -> EmailEventCollector {
+        EmailEventCollector::new_with_default_timezone_and_max_mime_parts(
+            default_timezone,
+            DEFAULT_MAX_MIME_PARTS,
+        )
+    }
+
+    /// Creates an EmailEventCollector like `new_with_default_timezone` that parses
+    /// at most `max_mime_parts` subparts of each email. The subparts after the limit are ignored.
+    pub fn new_with_default_timezone_and_max_mime_parts(
+        default_timezone: Tz,
+        max_mime_parts: usize,
+    ) -> EmailEventCollector {
+        EmailEventCollector { default_timezone, max_mime_parts }
     }
 }
 
 impl<'a> Collector<&'a [u8]> for EmailEventCollector {
     fn to_event(&self, input: &'a [u8]) -> Result<Event, CollectorError> {
         trace!("EmailEventCollector - Received a new email");
-        let email = parse_mail(input).map_err(into_err)?;
+        let input = bound_mime_parts(input, self.max_mime_parts)?;
+        let email = parse_mail(&input).map_err(into_err)?;
 
         trace!("EmailEventCollector - Parsed email: \n {:?}", email);
 
@@ -30,20 +63,29 @@ impl<'a> Collector<&'a [u8]> for EmailEventCollector {
         let to = get_first_header_value_or_empty(&email, "To")?;
         let cc = get_first_header_value_or_empty(&email, "Cc")?;
 
-        let date = dateparse(get_first_header_value_or_empty(&email, "Date")?.as_str())
-            .map_err(|err| CollectorError::EventCreationError { message: err.to_string() })?;
+        let date = get_first_header_value_or_empty(&email, "Date")?;
+        let date_ms = match parse_timestamp_ms(&date, &self.default_timezone) {
+            Ok(date_ms) => date_ms,
+            Err(_) => {
+                let date = dateparse(date.as_str()).map_err(|err| {
+                    CollectorError::EventCreationError { message: err.to_string() }
+                })?;
+                date as u64 * 1000
+            }
+        };
 
         let mut body = None;
         let mut attachments = vec![];
 
         extract_body_and_attachments(&email, &mut body, &mut attachments)?;
 
-        for subpart in email.subparts {
-            extract_body_and_attachments(&subpart, &mut body, &mut attachments)?;
+        for subpart in email.subparts.iter() {
+            extract_body_and_attachments(subpart, &mut body, &mut attachments)?;
         }
 
         let mut event = Event::new("email");
-        event.payload.insert("date".to_owned(), json!(date as u64));
+        event.created_ms = date_ms;
+        event.payload.insert("date".to_owned(), json!(date_ms / 1000));
         event.payload.insert("subject".to_owned(), Value::String(subject));
         event.payload.insert("from".to_owned(), Value::String(from));
         event.payload.insert("to".to_owned(), Value::String(to));
@@ -57,6 +99,47 @@ impl<'a> Collector<&'a [u8]> for EmailEventCollector {
     }
 }
 
+/// Returns the email truncated after its first `max_mime_parts` root level MIME parts,
+/// so that the following parts are never parsed.
+/// The parts nested in a multipart subpart are not counted.
+fn bound_mime_parts(input: &[u8], max_mime_parts: usize) -> Result<Cow<[u8]>, CollectorError> {
+    let (headers, body_offset) = parse_headers(input).map_err(into_err)?;
+    let content_type = match headers.get_first_value("Content-Type") {
+        Some(content_type) => parse_content_type(&content_type),
+        None => return Ok(Cow::Borrowed(input)),
+    };
+    let delimiter = match content_type.params.get("boundary") {
+        Some(boundary) if content_type.mimetype.starts_with("multipart/") => {
+            format!("--{}", boundary)
+        }
+        _ => return Ok(Cow::Borrowed(input)),
+    };
+    let delimiter = delimiter.as_bytes();
+
+    let mut parts = 0;
+    let mut offset = body_offset;
+    for line in input[body_offset..].split_inclusive(|byte| *byte == b'\n') {
+        if line.starts_with(delimiter) {
+            if line[delimiter.len()..].starts_with(b"--") {
+                break;
+            }
+            if parts == max_mime_parts {
+                warn!(
+                    "EmailEventCollector - The email has more than {} MIME parts. Only the first {} will be parsed. The parts nested in multipart subparts are not counted.",
+                    max_mime_parts, max_mime_parts
+                );
+                let mut bounded_input = input[..offset].to_vec();
+                bounded_input.extend_from_slice(delimiter);
+                bounded_input.extend_from_slice(b"--\n");
+                return Ok(Cow::Owned(bounded_input));
+            }
+            parts += 1;
+        }
+        offset += line.len();
+    }
+    Ok(Cow::Borrowed(input))
+}
+
 fn into_err(err: MailParseError) -> CollectorError {
     CollectorError::EventCreationError { message: format!("{}", err) }
 }
@@ -165,6 +248,25 @@ mod test {
         assert_eq!(expected_event, event);
     }
 
+    #[test]
+    fn should_use_the_default_timezone_for_a_date_without_timezone() {
+        // Arrange
+        let email = get_email("./test_resources/email_01_input.txt")
+            .replace("Sun, 02 Oct 2016 07:06:22 -0700 (PDT)", "Sun, 02 Oct 2016 07:06:22");
+        let utc_collector = EmailEventCollector::new();
+        let rome_collector = EmailEventCollector::new_with_default_timezone(Tz::Europe__Rome);
+
+        // Act
+        let utc_event = utc_collector.to_event(email.as_bytes()).unwrap();
+        let rome_event = rome_collector.to_event(email.as_bytes()).unwrap();
+
+        // Assert
+        assert_eq!(1_475_391_982_000, utc_event.created_ms);
+        // Europe/Rome is two hours ahead of UTC in October
+        assert_eq!(2 * 60 * 60 * 1000, utc_event.created_ms - rome_event.created_ms);
+        assert_eq!(&json!(1_475_384_782), rome_event.payload.get("date").unwrap());
+    }
+
     #[test]
     fn should_should_parse_sender_and_recipients() {
         // Arrange
@@ -253,6 +355,78 @@ mod test {
         );
     }
 
+    #[test]
+    fn should_stop_processing_the_mime_parts_at_the_limit() {
+        // Arrange
+        let mut email = "Subject: Many attachments\n\
+            Date: Sun, 02 Oct 2016 07:06:22 -0700 (PDT)\n\
+            Content-Type: multipart/mixed; boundary=\"boundary\"\n\n"
+            .to_owned();
+        for i in 0..10 {
+            email.push_str(&format!(
+                "--boundary\n\
+                Content-Type: text/plain\n\
+                Content-Disposition: attachment; filename=\"file_{}.txt\"\n\n\
+                content {}\n",
+                i, i
+            ));
+        }
+        email.push_str("--boundary--\n");
+
+        let collector =
+            EmailEventCollector::new_with_default_timezone_and_max_mime_parts(Tz::UTC, 3);
+
+        // Act
+        let event = collector.to_event(email.as_bytes()).unwrap();
+
+        // Assert
+        assert_eq!("Many attachments", event.payload.get("subject").unwrap());
+        let attachments = event.payload.get("attachments").unwrap().get_array().unwrap();
+        assert_eq!(3, attachments.len());
+        for (i, attachment) in attachments.iter().enumerate() {
+            assert_eq!(
+                &json!(format!("file_{}.txt", i)),
+                attachment.get_map().unwrap().get("filename").unwrap()
+            );
+        }
+
+        let all_attachments = EmailEventCollector::new().to_event(email.as_bytes()).unwrap();
+        assert_eq!(
+            10,
+            all_attachments.payload.get("attachments").unwrap().get_array().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn should_not_parse_the_mime_parts_after_the_limit() {
+        // Arrange
+        let email = "Subject: Many attachments\n\
+            Content-Type: multipart/mixed; boundary=\"boundary\"\n\n\
+            --boundary\n\
+            Content-Type: text/plain\n\n\
+            first\n\
+            --boundary\n\
+            Content-Type: text/plain\n\n\
+            second\n\
+            --boundary--\n";
+
+        // Act
+        let bounded_email = bound_mime_parts(email.as_bytes(), 1).unwrap();
+        let unbounded_email = bound_mime_parts(email.as_bytes(), 2).unwrap();
+
+        // Assert
+        assert_eq!(
+            "Subject: Many attachments\n\
+            Content-Type: multipart/mixed; boundary=\"boundary\"\n\n\
+            --boundary\n\
+            Content-Type: text/plain\n\n\
+            first\n\
+            --boundary--\n",
+            String::from_utf8_lossy(&bounded_email)
+        );
+        assert!(matches!(unbounded_email, Cow::Borrowed(_)));
+    }
+
     fn get_email(path: &str) -> String {
         fs::read_to_string(path).unwrap_or_else(|_| panic!("Unable to open the file [{}]", path))
     }
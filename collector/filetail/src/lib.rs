@@ -0,0 +1,60 @@
+use log::trace;
+use serde_json::Value;
+use tornado_collector_common::{Collector, CollectorError};
+use tornado_common_api::{Event, Payload};
+
+pub mod tail;
+
+/// The payload field that contains the line read from the file
+pub const LINE_FIELD: &str = "line";
+/// The payload field that contains the path of the file
+pub const PATH_FIELD: &str = "path";
+
+/// A collector that creates an Event for each line of a log file.
+/// The payload of the Event contains the raw line and the path of the file.
+pub struct FileTailCollector {
+    event_type: String,
+    path: String,
+}
+
+impl FileTailCollector {
+    pub fn new<S: Into<String>, P: Into<String>>(event_type: S, path: P) -> FileTailCollector {
+        FileTailCollector { event_type: event_type.into(), path: path.into() }
+    }
+}
+
+impl<'a> Collector<&'a str> for FileTailCollector {
+    fn to_event(&self, input: &'a str) -> Result<Event, CollectorError> {
+        trace!("FileTailCollector - received line: {}", input);
+        let mut payload = Payload::new();
+        payload.insert(LINE_FIELD.to_owned(), Value::String(input.to_owned()));
+        payload.insert(PATH_FIELD.to_owned(), Value::String(self.path.clone()));
+        Ok(Event::new_with_payload(self.event_type.clone(), payload))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn should_create_an_event_with_the_line_in_the_payload() {
+        // Arrange
+        let collector = FileTailCollector::new("filetail", "/var/log/app.log");
+
+        // Act
+        let event = collector.to_event("ERROR connection refused").unwrap();
+
+        // Assert
+        assert_eq!("filetail", event.event_type);
+        assert_eq!(
+            &Value::String("ERROR connection refused".to_owned()),
+            event.payload.get(LINE_FIELD).unwrap()
+        );
+        assert_eq!(
+            &Value::String("/var/log/app.log".to_owned()),
+            event.payload.get(PATH_FIELD).unwrap()
+        );
+    }
+}
@@ -0,0 +1,488 @@
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use tornado_collector_common::CollectorError;
+
+/// The default maximum number of bytes read from the file by each call to `read_lines`.
+pub const DEFAULT_MAX_READ_BYTES: u64 = 1024 * 1024;
+
+/// The position reached in a file, that is persisted to resume the reading after a restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TailPosition {
+    /// The inode of the file
+    pub inode: u64,
+    /// The number of bytes of the file already read
+    pub offset: u64,
+}
+
+struct OpenFile {
+    file: File,
+    inode: u64,
+    offset: u64,
+}
+
+/// Reads the lines appended to a file, in the same way as `tail -F`.
+/// The file is followed across its rotations: when the path points to a new file, identified by
+///   its inode, the remaining lines of the previous file are read before switching to the new one.
+/// If the file is truncated, it is read again from the beginning.
+pub struct FileTail {
+    path: PathBuf,
+    position_path: Option<PathBuf>,
+    resume_position: Option<TailPosition>,
+    persisted_position: Option<TailPosition>,
+    current: Option<OpenFile>,
+    max_read_bytes: u64,
+}
+
+impl FileTail {
+    pub fn new<P: Into<PathBuf>>(path: P) -> FileTail {
+        FileTail::new_with_position_path(path, None)
+    }
+
+    /// Creates a FileTail that persists the reached position to the file at `position_path`.
+    /// If the position file exists and refers to the current file, the reading resumes
+    ///   from the persisted position; otherwise, the file is read from the beginning.
+    pub fn new_with_position_path<P: Into<PathBuf>>(
+        path: P,
+        position_path: Option<PathBuf>,
+    ) -> FileTail {
+        let resume_position = position_path.as_deref().and_then(read_position);
+        FileTail {
+            path: path.into(),
+            position_path,
+            resume_position,
+            persisted_position: resume_position,
+            current: None,
+            max_read_bytes: DEFAULT_MAX_READ_BYTES,
+        }
+    }
+
+    /// Sets the maximum number of bytes read from the file by each call to `read_lines`.
+    /// A line longer than this limit is returned split in more lines.
+    pub fn with_max_read_bytes(mut self, max_read_bytes: u64) -> FileTail {
+        self.max_read_bytes = max_read_bytes.max(1);
+        self
+    }
+
+    /// Returns the position reached in the file, if the file was opened.
+    pub fn position(&self) -> Option<TailPosition> {
+        self.current
+            .as_ref()
+            .map(|current| TailPosition { inode: current.inode, offset: current.offset })
+    }
+
+    /// Returns the complete lines appended to the file since the previous call, reading at most
+    ///   `max_read_bytes` bytes; the remaining lines are returned by the next calls.
+    /// A trailing line not terminated by a newline is returned only when it is completed.
+    /// Empty lines are skipped.
+    /// The reached position is not persisted: `persist_position` has to be called once the lines
+    ///   are processed.
+    pub fn read_lines(&mut self) -> Result<Vec<String>, CollectorError> {
+        let mut lines = vec![];
+
+        if self.current.is_none() {
+            self.current = self.open()?;
+        }
+        let reached_end = match &mut self.current {
+            Some(current) => {
+                read_available_lines(&self.path, current, self.max_read_bytes, &mut lines)?
+            }
+            None => true,
+        };
+
+        // The previous file was read to the end, so it is now safe to switch to the rotated one
+        if reached_end {
+            if let Some(inode) = inode_of(&self.path)? {
+                if self.current.as_ref().map(|current| current.inode) != Some(inode) {
+                    info!("FileTail - The file [{}] was rotated", self.path.display());
+                    self.current = self.open()?;
+                    if let Some(current) = &mut self.current {
+                        read_available_lines(&self.path, current, self.max_read_bytes, &mut lines)?;
+                    }
+                }
+            }
+        }
+
+        Ok(lines)
+    }
+
+    fn open(&mut self) -> Result<Option<OpenFile>, CollectorError> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                debug!("FileTail - The file [{}] does not exist", self.path.display());
+                return Ok(None);
+            }
+            Err(err) => return Err(read_error(&self.path, err)),
+        };
+        let metadata = file.metadata().map_err(|err| read_error(&self.path, err))?;
+        let inode = metadata.ino();
+        let offset = match self.resume_position.take() {
+            Some(position) if position.inode == inode && position.offset <= metadata.len() => {
+                info!(
+                    "FileTail - Resume reading the file [{}] from offset {}",
+                    self.path.display(),
+                    position.offset
+                );
+                position.offset
+            }
+            _ => 0,
+        };
+        Ok(Some(OpenFile { file, inode, offset }))
+    }
+
+    /// Persists the position reached by the last call to `read_lines` to the position file,
+    ///   if configured.
+    pub fn persist_position(&mut self) -> Result<(), CollectorError> {
+        let (position_path, position) = match (&self.position_path, self.position()) {
+            (Some(position_path), Some(position)) => (position_path, position),
+            _ => return Ok(()),
+        };
+        if self.persisted_position == Some(position) {
+            return Ok(());
+        }
+
+        let content =
+            serde_json::to_vec(&position).map_err(|err| CollectorError::SourceReadError {
+                message: format!("Cannot serialize the position of the file tail: {}", err),
+            })?;
+        // The position is written to a temporary file and then renamed,
+        // so a crash cannot leave a partially written position file
+        let temp_path = position_path.with_extension("tmp");
+        fs::write(&temp_path, content)
+            .and_then(|_| fs::rename(&temp_path, position_path))
+            .map_err(|err| CollectorError::SourceReadError {
+                message: format!(
+                    "Cannot write the position of the file tail to [{}]: {}",
+                    position_path.display(),
+                    err
+                ),
+            })?;
+        self.persisted_position = Some(position);
+        Ok(())
+    }
+}
+
+/// Reads the complete lines between the current offset and the end of the file, reading at most
+///   `max_read_bytes` bytes.
+/// Returns whether the end of the file was reached.
+fn read_available_lines(
+    path: &Path,
+    current: &mut OpenFile,
+    max_read_bytes: u64,
+    lines: &mut Vec<String>,
+) -> Result<bool, CollectorError> {
+    let len = current.file.metadata().map_err(|err| read_error(path, err))?.len();
+    if len < current.offset {
+        info!(
+            "FileTail - The file [{}] was truncated. It is read from the beginning",
+            path.display()
+        );
+        current.offset = 0;
+    }
+    if len == current.offset {
+        return Ok(true);
+    }
+
+    let read_len = (len - current.offset).min(max_read_bytes);
+    let mut buffer = Vec::with_capacity(read_len as usize);
+    current.file.seek(SeekFrom::Start(current.offset)).map_err(|err| read_error(path, err))?;
+    (&current.file).take(read_len).read_to_end(&mut buffer).map_err(|err| read_error(path, err))?;
+    let reached_end = current.offset + buffer.len() as u64 >= len;
+
+    let complete_len = match buffer.iter().rposition(|byte| *byte == b'\n') {
+        Some(last_newline) => last_newline + 1,
+        // The buffer is full and it contains no newline, so the line does not fit in it
+        None if !reached_end && !buffer.is_empty() => {
+            warn!(
+                "FileTail - A line of the file [{}] is longer than {} bytes. It is split in more lines",
+                path.display(),
+                max_read_bytes
+            );
+            buffer.len()
+        }
+        None => 0,
+    };
+    for line in buffer[..complete_len].split(|byte| *byte == b'\n') {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim_end_matches('\r');
+        if !line.is_empty() {
+            lines.push(line.to_owned());
+        }
+    }
+    current.offset += complete_len as u64;
+    Ok(reached_end)
+}
+
+fn inode_of(path: &Path) -> Result<Option<u64>, CollectorError> {
+    match fs::metadata(path) {
+        Ok(metadata) => Ok(Some(metadata.ino())),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(read_error(path, err)),
+    }
+}
+
+fn read_position(position_path: &Path) -> Option<TailPosition> {
+    match fs::read(position_path) {
+        Ok(content) => match serde_json::from_slice(&content) {
+            Ok(position) => Some(position),
+            Err(err) => {
+                warn!(
+                    "FileTail - Cannot parse the position file [{}]. The file is read from the beginning. Err: {}",
+                    position_path.display(),
+                    err
+                );
+                None
+            }
+        },
+        Err(err) if err.kind() == ErrorKind::NotFound => None,
+        Err(err) => {
+            warn!(
+                "FileTail - Cannot read the position file [{}]. The file is read from the beginning. Err: {}",
+                position_path.display(),
+                err
+            );
+            None
+        }
+    }
+}
+
+fn read_error(path: &Path, err: std::io::Error) -> CollectorError {
+    CollectorError::SourceReadError {
+        message: format!("Cannot read the file [{}]: {}", path.display(), err),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    fn append(path: &Path, content: &str) {
+        let mut file = OpenOptions::new().create(true).append(true).open(path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn should_return_one_line_for_each_appended_line() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let mut tail = FileTail::new(&path);
+
+        // Act
+        append(&path, "first\nsecond\n");
+        let first_lines = tail.read_lines().unwrap();
+        append(&path, "third\n");
+        let second_lines = tail.read_lines().unwrap();
+        let third_lines = tail.read_lines().unwrap();
+
+        // Assert
+        assert_eq!(vec!["first", "second"], first_lines);
+        assert_eq!(vec!["third"], second_lines);
+        assert!(third_lines.is_empty());
+    }
+
+    #[test]
+    fn should_return_an_incomplete_line_only_when_completed() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let mut tail = FileTail::new(&path);
+
+        // Act
+        append(&path, "first\nsec");
+        let first_lines = tail.read_lines().unwrap();
+        append(&path, "ond\r\n\n");
+        let second_lines = tail.read_lines().unwrap();
+
+        // Assert
+        assert_eq!(vec!["first"], first_lines);
+        assert_eq!(vec!["second"], second_lines);
+    }
+
+    #[test]
+    fn should_return_no_lines_if_the_file_does_not_exist() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let mut tail = FileTail::new(&path);
+
+        // Act
+        let lines = tail.read_lines().unwrap();
+
+        // Assert
+        assert!(lines.is_empty());
+        assert_eq!(None, tail.position());
+    }
+
+    #[test]
+    fn should_follow_the_file_after_a_rotation() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let mut tail = FileTail::new(&path);
+
+        append(&path, "first\n");
+        assert_eq!(vec!["first"], tail.read_lines().unwrap());
+
+        // Act
+        append(&path, "second\n");
+        fs::rename(&path, tempdir.path().join("app.log.1")).unwrap();
+        append(&path, "third\n");
+        let lines_after_rotation = tail.read_lines().unwrap();
+
+        append(&path, "fourth\n");
+        let lines_of_the_new_file = tail.read_lines().unwrap();
+
+        // Assert
+        assert_eq!(vec!["second", "third"], lines_after_rotation);
+        assert_eq!(vec!["fourth"], lines_of_the_new_file);
+    }
+
+    #[test]
+    fn should_read_the_file_from_the_beginning_after_a_truncation() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let mut tail = FileTail::new(&path);
+
+        append(&path, "first\nsecond\n");
+        assert_eq!(vec!["first", "second"], tail.read_lines().unwrap());
+
+        // Act
+        fs::write(&path, "third\n").unwrap();
+        let lines = tail.read_lines().unwrap();
+
+        // Assert
+        assert_eq!(vec!["third"], lines);
+    }
+
+    #[test]
+    fn should_resume_from_the_persisted_position() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let position_path = tempdir.path().join("position.json");
+
+        let mut tail = FileTail::new_with_position_path(&path, Some(position_path.clone()));
+        append(&path, "first\n");
+        assert_eq!(vec!["first"], tail.read_lines().unwrap());
+        tail.persist_position().unwrap();
+        drop(tail);
+
+        // Act
+        append(&path, "second\n");
+        let mut restarted_tail = FileTail::new_with_position_path(&path, Some(position_path));
+        let lines = restarted_tail.read_lines().unwrap();
+
+        // Assert
+        assert_eq!(vec!["second"], lines);
+    }
+
+    #[test]
+    fn should_read_the_new_file_from_the_beginning_if_rotated_while_stopped() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let position_path = tempdir.path().join("position.json");
+
+        let mut tail = FileTail::new_with_position_path(&path, Some(position_path.clone()));
+        append(&path, "first\nsecond\n");
+        assert_eq!(vec!["first", "second"], tail.read_lines().unwrap());
+        tail.persist_position().unwrap();
+        drop(tail);
+
+        // Act
+        fs::rename(&path, tempdir.path().join("app.log.1")).unwrap();
+        append(&path, "third\nfourth\nfifth\n");
+        let mut restarted_tail = FileTail::new_with_position_path(&path, Some(position_path));
+        let lines = restarted_tail.read_lines().unwrap();
+
+        // Assert
+        assert_eq!(vec!["third", "fourth", "fifth"], lines);
+    }
+
+    #[test]
+    fn should_not_persist_the_position_until_requested() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let position_path = tempdir.path().join("position.json");
+
+        let mut tail = FileTail::new_with_position_path(&path, Some(position_path.clone()));
+        append(&path, "first\n");
+        assert_eq!(vec!["first"], tail.read_lines().unwrap());
+        drop(tail);
+
+        // Act
+        let mut restarted_tail = FileTail::new_with_position_path(&path, Some(position_path));
+        let lines = restarted_tail.read_lines().unwrap();
+
+        // Assert
+        assert_eq!(vec!["first"], lines);
+    }
+
+    #[test]
+    fn should_read_at_most_max_read_bytes_for_each_call() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let mut tail = FileTail::new(&path).with_max_read_bytes(8);
+
+        // Act
+        append(&path, "first\nsecond\nthird\n");
+        let first_lines = tail.read_lines().unwrap();
+        let second_lines = tail.read_lines().unwrap();
+        let third_lines = tail.read_lines().unwrap();
+
+        // Assert
+        assert_eq!(vec!["first"], first_lines);
+        assert_eq!(vec!["second"], second_lines);
+        assert_eq!(vec!["third"], third_lines);
+    }
+
+    #[test]
+    fn should_split_a_line_longer_than_max_read_bytes() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let mut tail = FileTail::new(&path).with_max_read_bytes(4);
+
+        // Act
+        append(&path, "abcdefg\n");
+        let first_lines = tail.read_lines().unwrap();
+        let second_lines = tail.read_lines().unwrap();
+
+        // Assert
+        assert_eq!(vec!["abcd"], first_lines);
+        assert_eq!(vec!["efg"], second_lines);
+    }
+
+    #[test]
+    fn should_read_the_previous_file_to_the_end_before_following_the_rotation() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let mut tail = FileTail::new(&path).with_max_read_bytes(8);
+
+        append(&path, "first\n");
+        assert_eq!(vec!["first"], tail.read_lines().unwrap());
+
+        // Act
+        append(&path, "second\nthird\n");
+        fs::rename(&path, tempdir.path().join("app.log.1")).unwrap();
+        append(&path, "fourth\n");
+        let first_lines = tail.read_lines().unwrap();
+        let second_lines = tail.read_lines().unwrap();
+
+        // Assert
+        assert_eq!(vec!["second"], first_lines);
+        assert_eq!(vec!["third", "fourth"], second_lines);
+    }
+}
@@ -7,3 +7,18 @@ pub struct JMESPathEventCollectorConfig {
     pub event_type: String,
     pub payload: HashMap<String, Value>,
 }
+
+/// The declarative mapping used by the `MappingEventCollector`.
+/// Each value is a JMESPath expression that selects the field from the incoming JSON.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct EventMappingConfig {
+    /// The path of the Event type. It is required.
+    pub event_type: String,
+    /// The optional path of the Event creation timestamp in milliseconds.
+    /// If not configured, the Event is created with the current time.
+    #[serde(default)]
+    pub created_ms: Option<String>,
+    /// The paths of the Event payload entries.
+    #[serde(default)]
+    pub payload: HashMap<String, String>,
+}
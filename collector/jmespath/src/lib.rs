@@ -10,6 +10,7 @@ use tornado_common_api::Value;
 use tornado_common_api::ValueExt;
 
 pub mod config;
+pub mod mapping;
 
 /// A Collector that receives an input in JSON format and allows the creation of Events
 ///   using the JMESPath JSON query language.
@@ -0,0 +1,192 @@
+use crate::config::EventMappingConfig;
+use crate::variable_to_value;
+use log::trace;
+use std::collections::HashMap;
+use tornado_collector_common::{Collector, CollectorError};
+use tornado_common_api::{Event, Value};
+
+/// A Collector that maps the fields of a JSON input, which is already close to the structure
+/// of an Event but with different field names, to the type, created_ms and payload of an Event.
+pub struct MappingEventCollector {
+    event_type: jmespath::Expression<'static>,
+    created_ms: Option<jmespath::Expression<'static>>,
+    payload: HashMap<String, jmespath::Expression<'static>>,
+}
+
+impl MappingEventCollector {
+    /// Builds a new Collector instance.
+    pub fn build(config: EventMappingConfig) -> Result<MappingEventCollector, CollectorError> {
+        let event_type = compile(&config.event_type)?;
+        let created_ms = config.created_ms.as_deref().map(compile).transpose()?;
+        let mut payload = HashMap::new();
+        for (key, path) in config.payload {
+            payload.insert(key, compile(&path)?);
+        }
+        Ok(MappingEventCollector { event_type, created_ms, payload })
+    }
+
+    fn process(&self, var: &jmespath::Variable) -> Result<Event, CollectorError> {
+        let event_type = match search(&self.event_type, var)?.as_ref() {
+            jmespath::Variable::String(event_type) => event_type.to_owned(),
+            jmespath::Variable::Null => {
+                return Err(CollectorError::EventCreationError {
+                    message: format!(
+                        "The Event type is missing. Expected at path [{}]",
+                        self.event_type
+                    ),
+                })
+            }
+            other => {
+                return Err(CollectorError::EventCreationError {
+                    message: format!("Event type must be a string. Found: {:?}", other),
+                })
+            }
+        };
+        let mut event = Event::new(event_type);
+
+        if let Some(created_ms_exp) = &self.created_ms {
+            let created_ms = variable_to_value(&search(created_ms_exp, var)?)?;
+            event.created_ms = match &created_ms {
+                Value::Null => {
+                    return Err(CollectorError::EventCreationError {
+                        message: format!(
+                            "The Event created_ms is missing. Expected at path [{}]",
+                            created_ms_exp
+                        ),
+                    })
+                }
+                value => to_millis(value).ok_or_else(|| CollectorError::EventCreationError {
+                    message: format!(
+                        "Event created_ms must be a positive integer. Found: {}",
+                        value
+                    ),
+                })?,
+            };
+        }
+
+        // Payload entries are optional: the ones not found in the input are not added
+        for (key, exp) in &self.payload {
+            let value = search(exp, var)?;
+            if !value.is_null() {
+                event.payload.insert(key.clone(), variable_to_value(&value)?);
+            }
+        }
+
+        Ok(event)
+    }
+}
+
+impl<'a> Collector<&'a str> for MappingEventCollector {
+    fn to_event(&self, input: &'a str) -> Result<Event, CollectorError> {
+        trace!("MappingEventCollector - received event: {}", input);
+
+        let data = jmespath::Variable::from_json(input).map_err(|err| {
+            CollectorError::EventCreationError {
+                message: format!("Cannot parse received json. Err: {:?} - Json: {}.", err, input),
+            }
+        })?;
+        self.process(&data)
+    }
+}
+
+fn to_millis(value: &Value) -> Option<u64> {
+    value.as_u64().or_else(|| {
+        value.as_f64().filter(|number| *number >= 0.0 && number.fract() == 0.0).map(|n| n as u64)
+    })
+}
+
+fn compile(path: &str) -> Result<jmespath::Expression<'static>, CollectorError> {
+    jmespath::compile(path).map_err(|err| CollectorError::CollectorCreationError {
+        message: format!("Not valid jmespath expression: [{}]. Err: {:?}", path, err),
+    })
+}
+
+fn search(
+    exp: &jmespath::Expression<'static>,
+    var: &jmespath::Variable,
+) -> Result<jmespath::Rcvar, CollectorError> {
+    exp.search(var).map_err(|e| CollectorError::EventCreationError {
+        message: format!("Expression failed to execute. Exp: {}. Error: {}", exp, e),
+    })
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+
+    fn mapping_config() -> EventMappingConfig {
+        let mut payload = HashMap::new();
+        payload.insert("hostname".to_owned(), "source.host".to_owned());
+        payload.insert("state".to_owned(), "status".to_owned());
+        payload.insert("tags".to_owned(), "labels".to_owned());
+        EventMappingConfig {
+            event_type: "kind".to_owned(),
+            created_ms: Some("timestamp".to_owned()),
+            payload,
+        }
+    }
+
+    #[test]
+    fn should_map_renamed_fields_into_an_event() {
+        // Arrange
+        let collector = MappingEventCollector::build(mapping_config()).unwrap();
+        let input = r#"
+        {
+            "kind": "monitoring",
+            "timestamp": 1554130814854,
+            "source": {
+                "host": "host1"
+            },
+            "status": "DOWN",
+            "labels": ["critical", "network"]
+        }
+        "#;
+
+        // Act
+        let event = collector.to_event(input).unwrap();
+
+        // Assert
+        assert_eq!("monitoring", event.event_type);
+        assert_eq!(1554130814854, event.created_ms);
+        assert_eq!(3, event.payload.len());
+        assert_eq!(Some(&Value::String("host1".to_owned())), event.payload.get("hostname"));
+        assert_eq!(Some(&Value::String("DOWN".to_owned())), event.payload.get("state"));
+        assert_eq!(Some(&json!(["critical", "network"])), event.payload.get("tags"));
+    }
+
+    #[test]
+    fn should_fail_if_the_type_is_missing() {
+        // Arrange
+        let collector = MappingEventCollector::build(mapping_config()).unwrap();
+        let input = r#"
+        {
+            "timestamp": 1554130814854,
+            "source": {
+                "host": "host1"
+            },
+            "status": "DOWN"
+        }
+        "#;
+
+        // Act
+        let result = collector.to_event(input);
+
+        // Assert
+        assert!(matches!(result, Err(CollectorError::EventCreationError { .. })));
+    }
+
+    #[test]
+    fn should_fail_to_build_with_an_invalid_path() {
+        // Arrange
+        let mut config = mapping_config();
+        config.event_type = "kind[".to_owned();
+
+        // Act
+        let result = MappingEventCollector::build(config);
+
+        // Assert
+        assert!(matches!(result, Err(CollectorError::CollectorCreationError { .. })));
+    }
+}
@@ -2,24 +2,79 @@ use log::trace;
 use tornado_collector_common::{Collector, CollectorError};
 use tornado_common_api::{Event, Payload};
 
+/// The default max nesting level of the arrays and objects of a JSON input.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 100;
+
 /// A collector that receives an input JSON and unmarshalls/deserializes it directly into an Event struct
-#[derive(Default)]
-pub struct JsonEventCollector {}
+pub struct JsonEventCollector {
+    max_nesting_depth: usize,
+}
+
+impl Default for JsonEventCollector {
+    fn default() -> Self {
+        JsonEventCollector { max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH }
+    }
+}
 
 impl JsonEventCollector {
     pub fn new() -> JsonEventCollector {
         Default::default()
     }
+
+    /// Creates a JsonEventCollector that rejects the inputs whose arrays and objects
+    /// are nested deeper than `max_nesting_depth`.
+    pub fn new_with_max_nesting_depth(max_nesting_depth: usize) -> JsonEventCollector {
+        JsonEventCollector { max_nesting_depth }
+    }
 }
 
 impl<'a> Collector<&'a str> for JsonEventCollector {
     fn to_event(&self, input: &'a str) -> Result<Event, CollectorError> {
         trace!("JsonEventCollector - received event: {}", input);
+        check_nesting_depth(input, self.max_nesting_depth)?;
         serde_json::from_str::<tornado_common_api::Event>(input)
             .map_err(|e| CollectorError::EventCreationError { message: format!("{}", e) })
     }
 }
 
+/// Verifies that the arrays and objects of the JSON input are not nested deeper than `max_depth`.
+/// The check is performed with a single iterative scan, before the recursive deserialization,
+/// so that a deeply nested input cannot exhaust the stack.
+fn check_nesting_depth(input: &str, max_depth: usize) -> Result<(), CollectorError> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for byte in input.bytes() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > max_depth {
+                    return Err(CollectorError::JsonParsingError {
+                        message: format!(
+                            "The JSON input exceeds the max nesting depth of {}",
+                            max_depth
+                        ),
+                    });
+                }
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 /// A collector that receives an input JSON and creates an Event whose payload is the JSON input
 pub struct JsonPayloadCollector {
     event_type: String,
@@ -61,6 +116,67 @@ mod test {
         assert_eq!(event.created_ms, from_json.created_ms);
     }
 
+    fn nested_json_event(depth: usize) -> String {
+        format!(
+            r#"{{"type": "email", "created_ms": 0, "payload": {{"value": {}"leaf"{}}}}}"#,
+            "[".repeat(depth),
+            "]".repeat(depth)
+        )
+    }
+
+    #[test]
+    fn should_accept_an_event_nested_up_to_the_max_depth() {
+        // Arrange
+        // The event object and the payload object count as two nesting levels
+        let json = nested_json_event(8);
+        let collector = JsonEventCollector::new_with_max_nesting_depth(10);
+
+        // Act
+        let result = collector.to_event(&json);
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn should_reject_an_event_nested_beyond_the_max_depth() {
+        // Arrange
+        let json = nested_json_event(9);
+        let collector = JsonEventCollector::new_with_max_nesting_depth(10);
+
+        // Act
+        let result = collector.to_event(&json);
+
+        // Assert
+        assert!(matches!(result, Err(CollectorError::JsonParsingError { .. })));
+    }
+
+    #[test]
+    fn should_reject_a_deeply_nested_event_without_exhausting_the_stack() {
+        // Arrange
+        let json = nested_json_event(1_000_000);
+        let collector = JsonEventCollector::new();
+
+        // Act
+        let result = collector.to_event(&json);
+
+        // Assert
+        assert!(matches!(result, Err(CollectorError::JsonParsingError { .. })));
+    }
+
+    #[test]
+    fn should_ignore_brackets_in_strings_when_checking_the_nesting_depth() {
+        // Arrange
+        let json = r#"{"type": "email", "created_ms": 0, "payload": {"value": "[[[[{{{{\"[["}}"#;
+        let collector = JsonEventCollector::new_with_max_nesting_depth(2);
+
+        // Act
+        let result = collector.to_event(json);
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn should_return_an_error() {
         // Arrange
@@ -0,0 +1,116 @@
+use log::*;
+use tornado_collector_common::{Collector, CollectorError};
+use tornado_common_api::{Event, Value};
+
+pub const DEFAULT_EVENT_TYPE: &str = "mqtt";
+pub const TOPIC_KEY: &str = "topic";
+pub const DATA_KEY: &str = "data";
+
+/// A message received from an MQTT broker.
+pub struct MqttMessage<'a> {
+    pub topic: &'a str,
+    pub payload: &'a [u8],
+}
+
+/// The MQTT Collector receives a message published on an MQTT topic and produces a Tornado Event.
+/// The topic of the message is copied in the `topic` entry of the Event payload, while the message
+/// body is copied in the `data` entry. If the body is a valid JSON document, it is parsed;
+/// otherwise, it is added to the Event as a plain text string.
+pub struct MqttEventCollector {
+    event_type: String,
+}
+
+impl Default for MqttEventCollector {
+    fn default() -> Self {
+        MqttEventCollector::new(DEFAULT_EVENT_TYPE)
+    }
+}
+
+impl MqttEventCollector {
+    pub fn new<S: Into<String>>(event_type: S) -> MqttEventCollector {
+        MqttEventCollector { event_type: event_type.into() }
+    }
+}
+
+impl<'a> Collector<MqttMessage<'a>> for MqttEventCollector {
+    fn to_event(&self, input: MqttMessage<'a>) -> Result<Event, CollectorError> {
+        trace!("MqttEventCollector - Received a new message on topic [{}]", input.topic);
+
+        let data = match serde_json::from_slice::<Value>(input.payload) {
+            Ok(value) => value,
+            Err(_) => {
+                let text = std::str::from_utf8(input.payload).map_err(|err| {
+                    CollectorError::EventCreationError {
+                        message: format!(
+                            "The message received on topic [{}] is neither JSON nor valid UTF-8 text. Err: {}",
+                            input.topic, err
+                        ),
+                    }
+                })?;
+                Value::String(text.to_owned())
+            }
+        };
+
+        let mut event = Event::new(self.event_type.clone());
+        event.payload.insert(TOPIC_KEY.to_owned(), Value::String(input.topic.to_owned()));
+        event.payload.insert(DATA_KEY.to_owned(), data);
+
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_produce_event_from_json_message() {
+        // Arrange
+        let collector = MqttEventCollector::default();
+        let message = MqttMessage {
+            topic: "sensors/room1/temperature",
+            payload: r#"{"value": 21.5, "unit": "C"}"#.as_bytes(),
+        };
+
+        // Act
+        let event = collector.to_event(message).unwrap();
+
+        // Assert
+        assert_eq!("mqtt", event.event_type);
+        assert_eq!(
+            Some(&Value::String("sensors/room1/temperature".to_owned())),
+            event.payload.get("topic")
+        );
+        assert_eq!(Some(&json!({"value": 21.5, "unit": "C"})), event.payload.get("data"));
+    }
+
+    #[test]
+    fn should_produce_event_from_text_message() {
+        // Arrange
+        let collector = MqttEventCollector::new("telemetry");
+        let message = MqttMessage { topic: "devices/status", payload: "online".as_bytes() };
+
+        // Act
+        let event = collector.to_event(message).unwrap();
+
+        // Assert
+        assert_eq!("telemetry", event.event_type);
+        assert_eq!(Some(&Value::String("devices/status".to_owned())), event.payload.get("topic"));
+        assert_eq!(Some(&Value::String("online".to_owned())), event.payload.get("data"));
+    }
+
+    #[test]
+    fn should_fail_if_message_is_not_valid_utf8() {
+        // Arrange
+        let collector = MqttEventCollector::default();
+        let message = MqttMessage { topic: "devices/raw", payload: &[0xff, 0xfe, 0xfd] };
+
+        // Act
+        let result = collector.to_event(message);
+
+        // Assert
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//! The keys that identify a group of Events or Actions, e.g. the duplicated ones,
+//! derived from the values of some of their fields.
+
+use crate::Value;
+use serde::{Deserialize, Serialize};
+
+/// The text that replaces a field of a composite key that cannot be resolved
+pub const MISSING_FIELD_PLACEHOLDER: &str = "<missing>";
+
+/// The key that identifies a group of Events, e.g. the duplicated ones.
+/// The syntax of the fields depends on the feature using the key: for example, the keys of
+///   the rules use the accessors of the matcher, e.g. `${event.payload.host}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+#[serde(deny_unknown_fields)]
+pub enum DedupKey {
+    /// A single accessor or interpolated text, e.g. `${_variables.hostname}`
+    Template(String),
+    /// The values of several fields, e.g. host, check and severity, joined by a separator.
+    /// A field that cannot be resolved is replaced by the `<missing>` placeholder.
+    Fields {
+        fields: Vec<String>,
+        #[serde(default = "default_dedup_key_separator")]
+        separator: String,
+    },
+}
+
+impl From<&str> for DedupKey {
+    fn from(template: &str) -> Self {
+        DedupKey::Template(template.to_owned())
+    }
+}
+
+pub fn default_dedup_key_separator() -> String {
+    "::".to_owned()
+}
+
+impl DedupKey {
+    /// Builds the resolver of the key, using the given function to build the resolver of
+    ///   each field, e.g. an accessor or a parser of the field expression.
+    pub fn build<F, E>(
+        &self,
+        build_field: impl Fn(&str) -> Result<F, E>,
+    ) -> Result<DedupKeyResolver<F>, E> {
+        match self {
+            DedupKey::Template(template) => Ok(DedupKeyResolver::Template(build_field(template)?)),
+            DedupKey::Fields { fields, separator } => Ok(DedupKeyResolver::Fields {
+                fields: fields
+                    .iter()
+                    .map(|field| build_field(field))
+                    .collect::<Result<Vec<_>, _>>()?,
+                separator: separator.to_owned(),
+            }),
+        }
+    }
+}
+
+/// A DedupKey whose fields are resolved by resolvers of type F
+#[derive(Debug)]
+pub enum DedupKeyResolver<F> {
+    Template(F),
+    Fields { fields: Vec<F>, separator: String },
+}
+
+impl<F> DedupKeyResolver<F> {
+    /// Returns the key, resolving the text of each field with the given function.
+    /// A template key is None if it cannot be resolved, while a composite key always
+    ///   has a value, where each missing field is replaced by the MISSING_FIELD_PLACEHOLDER.
+    pub fn derive(&self, resolve: impl Fn(&F) -> Option<String>) -> Option<String> {
+        match self {
+            DedupKeyResolver::Template(field) => resolve(field),
+            DedupKeyResolver::Fields { fields, separator } => Some(
+                fields
+                    .iter()
+                    .map(|field| {
+                        resolve(field).unwrap_or_else(|| MISSING_FIELD_PLACEHOLDER.to_owned())
+                    })
+                    .collect::<Vec<_>>()
+                    .join(separator),
+            ),
+        }
+    }
+}
+
+/// Returns the text of a value in a key: the strings are used as they are,
+///   the other values are serialized as JSON.
+pub fn to_key_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.to_owned(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+
+    fn derive(key: &DedupKey, payload: &Value) -> Option<String> {
+        let resolver = key.build(|field| Ok::<_, ()>(field.to_owned())).unwrap();
+        resolver.derive(|field| payload.get(field).map(to_key_text))
+    }
+
+    #[test]
+    fn should_join_the_fields_with_the_separator() {
+        // Arrange
+        let key = DedupKey::Fields {
+            fields: vec!["host".to_owned(), "check".to_owned(), "severity".to_owned()],
+            separator: "|".to_owned(),
+        };
+        let payload = json!({ "host": "server-1", "check": "ping", "severity": 2 });
+
+        // Act & Assert
+        assert_eq!(Some("server-1|ping|2".to_owned()), derive(&key, &payload));
+    }
+
+    #[test]
+    fn should_replace_a_missing_field_with_the_placeholder() {
+        // Arrange
+        let key = DedupKey::Fields {
+            fields: vec!["host".to_owned(), "check".to_owned()],
+            separator: default_dedup_key_separator(),
+        };
+
+        // Act & Assert
+        assert_eq!(
+            Some("server-1::<missing>".to_owned()),
+            derive(&key, &json!({ "host": "server-1" }))
+        );
+    }
+
+    #[test]
+    fn should_not_derive_a_template_key_if_it_cannot_be_resolved() {
+        // Arrange
+        let key = DedupKey::from("host");
+
+        // Act & Assert
+        assert_eq!(Some("server-1".to_owned()), derive(&key, &json!({ "host": "server-1" })));
+        assert_eq!(None, derive(&key, &json!({})));
+    }
+
+    #[test]
+    fn should_fail_if_a_field_cannot_be_built() {
+        // Arrange
+        let key = DedupKey::Fields {
+            fields: vec!["host".to_owned(), "".to_owned()],
+            separator: default_dedup_key_separator(),
+        };
+
+        // Act
+        let result =
+            key.build(|field| if field.is_empty() { Err("empty") } else { Ok(field.to_owned()) });
+
+        // Assert
+        assert!(matches!(result, Err("empty")));
+    }
+
+    #[test]
+    fn should_deserialize_both_forms_of_the_key() {
+        // Act
+        let template: DedupKey = serde_json::from_value(json!("${event.payload.host}")).unwrap();
+        let fields: DedupKey = serde_json::from_value(json!({
+            "fields": ["${event.payload.host}", "${event.payload.check}"]
+        }))
+        .unwrap();
+
+        // Assert
+        assert_eq!(DedupKey::from("${event.payload.host}"), template);
+        assert_eq!(
+            DedupKey::Fields {
+                fields: vec![
+                    "${event.payload.host}".to_owned(),
+                    "${event.payload.check}".to_owned()
+                ],
+                separator: "::".to_owned(),
+            },
+            fields
+        );
+    }
+}
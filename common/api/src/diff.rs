@@ -0,0 +1,235 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// A segment of the path of a value nested in objects and arrays
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// A single difference between two values
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum ValueChange {
+    /// A key or an array element present only in the new value
+    Added { path: Vec<PathSegment>, value: Value },
+    /// A key or an array element present only in the old value
+    Removed { path: Vec<PathSegment>, value: Value },
+    /// A value that is present in both, but that differs
+    Changed { path: Vec<PathSegment>, old_value: Value, new_value: Value },
+}
+
+/// The structured differences between an old and a new value.
+/// The objects are compared key by key and the arrays element by element;
+///   any other difference is reported as a change of the whole value at its path.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ValueDiff {
+    pub changes: Vec<ValueChange>,
+}
+
+impl ValueDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+pub trait Diff {
+    /// Returns the differences between `self`, the old value, and `other`, the new value.
+    fn diff(&self, other: &Self) -> ValueDiff;
+}
+
+impl Diff for Value {
+    fn diff(&self, other: &Value) -> ValueDiff {
+        let mut diff = ValueDiff::default();
+        let mut path = vec![];
+        diff_values(self, other, &mut path, &mut diff.changes);
+        diff
+    }
+}
+
+fn diff_values(
+    old: &Value,
+    new: &Value,
+    path: &mut Vec<PathSegment>,
+    changes: &mut Vec<ValueChange>,
+) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                path.push(PathSegment::Key(key.to_owned()));
+                match new_map.get(key) {
+                    Some(new_value) => diff_values(old_value, new_value, path, changes),
+                    None => changes.push(ValueChange::Removed {
+                        path: path.clone(),
+                        value: old_value.clone(),
+                    }),
+                }
+                path.pop();
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    path.push(PathSegment::Key(key.to_owned()));
+                    changes
+                        .push(ValueChange::Added { path: path.clone(), value: new_value.clone() });
+                    path.pop();
+                }
+            }
+        }
+        (Value::Array(old_array), Value::Array(new_array)) => {
+            for index in 0..old_array.len().max(new_array.len()) {
+                path.push(PathSegment::Index(index));
+                match (old_array.get(index), new_array.get(index)) {
+                    (Some(old_value), Some(new_value)) => {
+                        diff_values(old_value, new_value, path, changes)
+                    }
+                    (Some(old_value), None) => changes.push(ValueChange::Removed {
+                        path: path.clone(),
+                        value: old_value.clone(),
+                    }),
+                    (None, Some(new_value)) => changes
+                        .push(ValueChange::Added { path: path.clone(), value: new_value.clone() }),
+                    (None, None) => {}
+                }
+                path.pop();
+            }
+        }
+        _ => {
+            if old != new {
+                changes.push(ValueChange::Changed {
+                    path: path.clone(),
+                    old_value: old.clone(),
+                    new_value: new.clone(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn key(key: &str) -> PathSegment {
+        PathSegment::Key(key.to_owned())
+    }
+
+    #[test]
+    fn identical_values_should_produce_an_empty_diff() {
+        // Arrange
+        let value = json!({ "host": "host1", "services": [ { "name": "ping", "state": 0 } ] });
+
+        // Act
+        let diff = value.diff(&value.clone());
+
+        // Assert
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn should_detect_the_nested_object_changes() {
+        // Arrange
+        let old = json!({
+            "host": "host1",
+            "state": { "current": "UP", "since": 10, "acknowledged": false }
+        });
+        let new = json!({
+            "host": "host1",
+            "state": { "current": "DOWN", "since": 10, "output": "timeout" }
+        });
+
+        // Act
+        let diff = old.diff(&new);
+
+        // Assert
+        assert_eq!(
+            vec![
+                ValueChange::Removed {
+                    path: vec![key("state"), key("acknowledged")],
+                    value: json!(false)
+                },
+                ValueChange::Changed {
+                    path: vec![key("state"), key("current")],
+                    old_value: json!("UP"),
+                    new_value: json!("DOWN")
+                },
+                ValueChange::Added {
+                    path: vec![key("state"), key("output")],
+                    value: json!("timeout")
+                },
+            ],
+            diff.changes
+        );
+    }
+
+    #[test]
+    fn should_detect_the_array_element_changes() {
+        // Arrange
+        let old = json!({ "services": [ { "name": "ping", "state": 0 }, "http", "ssh" ] });
+        let new = json!({ "services": [ { "name": "ping", "state": 2 }, "https" ], "tags": [] });
+
+        // Act
+        let diff = old.diff(&new);
+
+        // Assert
+        assert_eq!(
+            vec![
+                ValueChange::Changed {
+                    path: vec![key("services"), PathSegment::Index(0), key("state")],
+                    old_value: json!(0),
+                    new_value: json!(2)
+                },
+                ValueChange::Changed {
+                    path: vec![key("services"), PathSegment::Index(1)],
+                    old_value: json!("http"),
+                    new_value: json!("https")
+                },
+                ValueChange::Removed {
+                    path: vec![key("services"), PathSegment::Index(2)],
+                    value: json!("ssh")
+                },
+                ValueChange::Added { path: vec![key("tags")], value: json!([]) },
+            ],
+            diff.changes
+        );
+    }
+
+    #[test]
+    fn should_report_a_type_change_as_a_change_of_the_whole_value() {
+        // Arrange
+        let old = json!({ "value": { "nested": 1 } });
+        let new = json!({ "value": [1] });
+
+        // Act
+        let diff = old.diff(&new);
+
+        // Assert
+        assert_eq!(
+            vec![ValueChange::Changed {
+                path: vec![key("value")],
+                old_value: json!({ "nested": 1 }),
+                new_value: json!([1])
+            }],
+            diff.changes
+        );
+    }
+
+    #[test]
+    fn should_serialize_the_diff() {
+        // Arrange
+        let diff = json!({ "list": [1] }).diff(&json!({ "list": [2] }));
+
+        // Act
+        let json = serde_json::to_value(&diff).unwrap();
+
+        // Assert
+        assert_eq!(
+            json!({ "changes": [
+                { "type": "Changed", "path": ["list", 0], "old_value": 1, "new_value": 2 }
+            ]}),
+            json
+        );
+    }
+}
@@ -3,14 +3,19 @@ use error::CommonError;
 use opentelemetry::trace::TraceContextExt;
 use opentelemetry::Context;
 use partial_ordering::PartialOrdering;
+use retry::RetryStrategy;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::Span;
 
+pub mod dedup_key;
+pub mod diff;
 pub mod error;
 pub mod partial_ordering;
+pub mod retry;
 
 pub type Value = serde_json::Value;
 pub type Map<K, V> = serde_json::Map<K, V>;
@@ -155,6 +160,14 @@ pub struct Action {
     pub id: String,
     pub payload: Payload,
     pub created_ms: u64,
+    /// The trace id of the Event that originated this Action.
+    /// It is used to correlate the logs of the Action execution with the Event processing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    /// The RetryStrategy of this Action.
+    /// If set, it overrides the global RetryStrategy used when the Action execution fails.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_strategy: Option<RetryStrategy>,
 }
 
 #[derive(Debug, Clone)]
@@ -183,7 +196,7 @@ impl Action {
         payload: Payload,
         created_ms: u64,
     ) -> Action {
-        Action { id: id.into(), payload, created_ms }
+        Action { id: id.into(), payload, created_ms, trace_id: None, retry_strategy: None }
     }
 }
 
@@ -199,6 +212,12 @@ pub trait ValueExt {
     fn get_bool(&self) -> Option<&bool>;
 
     fn get_number(&self) -> Option<&Number>;
+
+    /// Flattens the nested objects and arrays into a map of the leaf values.
+    /// The keys are the paths of the leaves, where the object keys are joined by the `separator`
+    ///   and the array indexes are enclosed in brackets, e.g. `host.interfaces[0].name`.
+    /// Empty objects and arrays produce no entries, while a leaf at the root has an empty key.
+    fn flatten(&self, separator: &str) -> HashMap<String, Value>;
 }
 
 impl ValueExt for Value {
@@ -243,6 +262,40 @@ impl ValueExt for Value {
             _ => None,
         }
     }
+
+    fn flatten(&self, separator: &str) -> HashMap<String, Value> {
+        let mut flattened = HashMap::new();
+        flatten_into(self, String::new(), separator, &mut flattened);
+        flattened
+    }
+}
+
+fn flatten_into(
+    value: &Value,
+    path: String,
+    separator: &str,
+    flattened: &mut HashMap<String, Value>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}{}{}", path, separator, key)
+                };
+                flatten_into(value, path, separator, flattened);
+            }
+        }
+        Value::Array(array) => {
+            for (index, value) in array.iter().enumerate() {
+                flatten_into(value, format!("{}[{}]", path, index), separator, flattened);
+            }
+        }
+        _ => {
+            flattened.insert(path, value.clone());
+        }
+    }
 }
 
 pub fn cow_to_str<'o>(value: &'o Option<Cow<'o, Value>>) -> Option<&'o str> {
@@ -374,6 +427,61 @@ mod test {
         assert_eq!("text_value", text.unwrap());
     }
 
+    #[test]
+    fn should_flatten_nested_objects_and_arrays() {
+        // Arrange
+        let value = json!({
+            "host": {
+                "name": "web01",
+                "interfaces": [
+                    { "name": "eth0", "up": true },
+                    { "name": "eth1", "up": false }
+                ],
+                "tags": ["linux", 42],
+                "labels": {},
+                "checks": []
+            },
+            "severity": null
+        });
+
+        // Act
+        let flattened = value.flatten(".");
+
+        // Assert
+        let mut expected = HashMap::new();
+        expected.insert("host.name".to_owned(), json!("web01"));
+        expected.insert("host.interfaces[0].name".to_owned(), json!("eth0"));
+        expected.insert("host.interfaces[0].up".to_owned(), json!(true));
+        expected.insert("host.interfaces[1].name".to_owned(), json!("eth1"));
+        expected.insert("host.interfaces[1].up".to_owned(), json!(false));
+        expected.insert("host.tags[0]".to_owned(), json!("linux"));
+        expected.insert("host.tags[1]".to_owned(), json!(42));
+        expected.insert("severity".to_owned(), Value::Null);
+        assert_eq!(expected, flattened);
+    }
+
+    #[test]
+    fn should_flatten_with_a_custom_separator() {
+        // Arrange
+        let value = json!({ "host": { "address": { "ip": "127.0.0.1" } }, "ports": [[80, 443]] });
+
+        // Act
+        let flattened = value.flatten("_");
+
+        // Assert
+        assert_eq!(2, flattened.len());
+        assert_eq!(Some(&json!("127.0.0.1")), flattened.get("host_address_ip"));
+        assert_eq!(Some(&json!(443)), flattened.get("ports[0][1]"));
+    }
+
+    #[test]
+    fn should_flatten_a_leaf_to_an_empty_key() {
+        // Assert
+        assert_eq!(Some(&json!("text")), json!("text").flatten(".").get(""));
+        assert!(json!({}).flatten(".").is_empty());
+        assert!(json!([]).flatten(".").is_empty());
+    }
+
     #[test]
     fn should_compare_value_with_str() {
         // Arrange
@@ -204,6 +204,7 @@ mod test {
     use serial_test::serial;
 
     #[tokio::test]
+    #[serial]
     async fn should_get_opentelemetry_tracer() {
         let tracing_config = ApmTracingConfig {
             apm_output: true,
@@ -218,6 +219,32 @@ mod test {
         assert!(tracer.is_ok());
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn should_get_opentelemetry_tracer_with_custom_batch_exporter_config() {
+        // Arrange
+        let tracing_config = ApmTracingConfig {
+            apm_output: true,
+            apm_server_url: "apm.example.com".to_string(),
+            apm_server_api_credentials: None,
+            exporter: ExporterConfig {
+                max_queue_size: 1000,
+                scheduled_delay_ms: Some(200),
+                max_export_batch_size: Some(100),
+                max_export_timeout_ms: Some(5000),
+            },
+        };
+
+        // Act
+        let tracer = get_opentelemetry_tracer(&tracing_config, Arc::new(AtomicBool::new(true)));
+
+        // Assert
+        assert!(tracer.is_ok());
+        let batch_config = format!("{:?}", BatchConfig::default());
+        assert_eq!(batch_config.as_str(), "BatchConfig { max_queue_size: 1000, scheduled_delay: 200ms, max_export_batch_size: 100, max_export_timeout: 5s }");
+        remove_otel_env_vars()
+    }
+
     #[tokio::test]
     #[serial]
     async fn should_set_opentelemetry_batch_exporter_config_if_batch_size_undefined() {
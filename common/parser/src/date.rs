@@ -0,0 +1,207 @@
+use crate::parser::ParserError;
+use chrono::format::{Item, StrftimeItems};
+use chrono::TimeZone;
+use chrono_tz::Tz;
+use serde_json::Value;
+
+pub const DATE_FUNCTION_NAME: &str = "date";
+const FUNCTION_ARGUMENTS_START_DELIMITER: &str = "(";
+const FUNCTION_ARGUMENTS_END_DELIMITER: &str = ")";
+const FUNCTION_ARGUMENTS_SEPARATOR: char = ',';
+const STRING_ARGUMENT_DELIMITER: &str = "\"";
+
+/// Formats epoch milliseconds timestamps as text in a given timezone.
+#[derive(Debug)]
+pub struct DateFormatter {
+    format: String,
+    timezone: Tz,
+}
+
+impl DateFormatter {
+    /// Builds a DateFormatter for a `strftime` like format string.
+    /// If no timezone is provided, the dates are formatted in UTC.
+    pub fn build(format: &str, timezone: Option<&str>) -> Result<Self, ParserError> {
+        if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+            return Err(invalid_date_function(format!("Invalid date format [{}]", format)));
+        }
+
+        let timezone = match timezone {
+            Some(timezone) => timezone.trim().parse::<Tz>().map_err(|_| {
+                invalid_date_function(format!(
+                    "Unknown timezone [{}]. Expected timezone from the IANA timezone database.",
+                    timezone
+                ))
+            })?,
+            None => Tz::UTC,
+        };
+
+        Ok(DateFormatter { format: format.to_owned(), timezone })
+    }
+
+    /// Formats a value containing an epoch timestamp in milliseconds.
+    /// Returns None if the value is not an integer or it is not a valid timestamp.
+    pub fn format(&self, value: &Value) -> Option<String> {
+        let timestamp_ms = value.as_i64()?;
+        let date = self.timezone.timestamp_millis_opt(timestamp_ms).single()?;
+        Some(date.format(&self.format).to_string())
+    }
+}
+
+/// Parses an expression in the form `date(<accessor>, "<format>")` or
+/// `date(<accessor>, "<format>", "<timezone>")`.
+/// It returns the accessor along with the DateFormatter,
+/// or None if the expression is not a call to the date function.
+pub(crate) fn parse_date_function(
+    expression: &str,
+) -> Result<Option<(&str, DateFormatter)>, ParserError> {
+    let arguments = match expression
+        .trim()
+        .strip_prefix(DATE_FUNCTION_NAME)
+        .and_then(|rest| rest.strip_prefix(FUNCTION_ARGUMENTS_START_DELIMITER))
+        .and_then(|rest| rest.strip_suffix(FUNCTION_ARGUMENTS_END_DELIMITER))
+    {
+        Some(arguments) => split_arguments(arguments),
+        None => return Ok(None),
+    };
+
+    match arguments.as_slice() {
+        [target, format] => {
+            Ok(Some((*target, DateFormatter::build(string_argument(format)?, None)?)))
+        }
+        [target, format, timezone] => Ok(Some((
+            *target,
+            DateFormatter::build(string_argument(format)?, Some(string_argument(timezone)?))?,
+        ))),
+        _ => Err(invalid_date_function(format!(
+            "Expected 2 or 3 arguments, found {}. Usage: date(<accessor>, \"<format>\"[, \"<timezone>\"])",
+            arguments.len()
+        ))),
+    }
+}
+
+/// Splits the function arguments on the separators that are not enclosed in double quotes.
+fn split_arguments(arguments: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (index, character) in arguments.char_indices() {
+        match character {
+            '"' => in_quotes = !in_quotes,
+            FUNCTION_ARGUMENTS_SEPARATOR if !in_quotes => {
+                result.push(arguments[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    result.push(arguments[start..].trim());
+    result
+}
+
+fn string_argument(argument: &str) -> Result<&str, ParserError> {
+    argument
+        .strip_prefix(STRING_ARGUMENT_DELIMITER)
+        .and_then(|rest| rest.strip_suffix(STRING_ARGUMENT_DELIMITER))
+        .ok_or_else(|| {
+            invalid_date_function(format!(
+                "The argument [{}] must be enclosed in double quotes",
+                argument
+            ))
+        })
+}
+
+fn invalid_date_function(message: String) -> ParserError {
+    ParserError::InvalidFunctionError { function: DATE_FUNCTION_NAME.to_owned(), message }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    // 2024-06-15T00:00:00Z
+    const TIMESTAMP_MS: i64 = 1_718_409_600_000;
+
+    #[test]
+    fn should_format_the_date_in_utc() {
+        // Arrange
+        let formatter = DateFormatter::build("%Y/%m/%d %H:%M", None).unwrap();
+
+        // Act
+        let result = formatter.format(&json!(TIMESTAMP_MS));
+
+        // Assert
+        assert_eq!(Some("2024/06/15 00:00".to_owned()), result);
+    }
+
+    #[test]
+    fn should_format_the_date_in_a_named_timezone() {
+        // Arrange
+        let formatter = DateFormatter::build("%Y/%m/%d %H:%M", Some("America/New_York")).unwrap();
+
+        // Act
+        let result = formatter.format(&json!(TIMESTAMP_MS));
+
+        // Assert
+        assert_eq!(Some("2024/06/14 20:00".to_owned()), result);
+    }
+
+    #[test]
+    fn should_return_none_if_the_value_is_not_a_timestamp() {
+        // Arrange
+        let formatter = DateFormatter::build("%Y/%m/%d", None).unwrap();
+
+        // Assert
+        assert_eq!(None, formatter.format(&json!("2024-06-15")));
+        assert_eq!(None, formatter.format(&json!(1.5)));
+    }
+
+    #[test]
+    fn should_fail_with_an_invalid_format() {
+        // Act
+        let result = DateFormatter::build("%Y/%m/%Q", None);
+
+        // Assert
+        assert!(matches!(result, Err(ParserError::InvalidFunctionError { .. })));
+    }
+
+    #[test]
+    fn should_fail_with_an_unknown_timezone() {
+        // Act
+        let result = DateFormatter::build("%Y/%m/%d", Some("Europe/Atlantis"));
+
+        // Assert
+        assert!(matches!(result, Err(ParserError::InvalidFunctionError { .. })));
+    }
+
+    #[test]
+    fn should_parse_the_date_function() {
+        // Act
+        let (target, formatter) =
+            parse_date_function(r#"date(event.payload."a,b", "%d, %m", "Europe/Rome")"#)
+                .unwrap()
+                .unwrap();
+
+        // Assert
+        assert_eq!(r#"event.payload."a,b""#, target);
+        assert_eq!("%d, %m", formatter.format);
+        assert_eq!(Tz::Europe__Rome, formatter.timezone);
+    }
+
+    #[test]
+    fn should_ignore_expressions_that_are_not_date_functions() {
+        assert!(parse_date_function("event.payload.date").unwrap().is_none());
+        assert!(parse_date_function("dates(event.created_ms)").unwrap().is_none());
+    }
+
+    #[test]
+    fn should_fail_with_a_wrong_number_of_arguments() {
+        assert!(parse_date_function("date(event.created_ms)").is_err());
+        assert!(parse_date_function(r#"date(event.created_ms, "%Y", "UTC", "x")"#).is_err());
+    }
+
+    #[test]
+    fn should_fail_if_the_format_is_not_quoted() {
+        assert!(parse_date_function("date(event.created_ms, %Y)").is_err());
+    }
+}
@@ -1,4 +1,6 @@
+mod date;
 mod interpolator;
+mod now;
 mod parser;
 
 use lazy_static::lazy_static;
@@ -8,6 +10,7 @@ use std::borrow::Cow;
 use std::fmt::Debug;
 use tornado_common_types::ValueGet;
 
+pub use crate::now::Clock;
 pub use crate::parser::{
     AccessorExpression, Parser, ParserBuilder, ParserError, EXTRACTED_VARIABLES_KEY,
 };
@@ -16,6 +19,7 @@ pub const EXPRESSION_START_DELIMITER: &str = "${";
 pub const EXPRESSION_END_DELIMITER: &str = "}";
 pub const FOREACH_ITEM_KEY: &str = "item";
 pub const EVENT_KEY: &str = "event";
+pub const CONTEXT_KEY: &str = "context";
 
 // This regex is used to match the `${event.something}` pattern.
 // It literally matches the sequence `${` followed by one or more chars followed by `}`.
@@ -101,6 +105,7 @@ pub fn is_valid_matcher_root(keys: &[ValueGetter]) -> bool {
         {
             true
         }
+        [ValueGetter::Map { key }, ValueGetter::Map { .. }, ..] if key == CONTEXT_KEY => true,
         _ => false,
     }
 }
@@ -0,0 +1,108 @@
+use crate::parser::ParserError;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const NOW_FUNCTION_NAME: &str = "now";
+const FUNCTION_ARGUMENTS_START_DELIMITER: &str = "(";
+const FUNCTION_ARGUMENTS_END_DELIMITER: &str = ")";
+
+/// The source of the current time used by the `now()` function.
+/// By default it reads the system clock; a different source can be injected,
+///   for example, to obtain deterministic values in tests.
+#[derive(Clone)]
+pub struct Clock {
+    now_ms: Arc<dyn Fn() -> u64 + Send + Sync>,
+}
+
+impl Clock {
+    /// Builds a Clock that returns the epoch milliseconds timestamp produced by the given function.
+    pub fn new<F: Fn() -> u64 + Send + Sync + 'static>(now_ms: F) -> Self {
+        Clock { now_ms: Arc::new(now_ms) }
+    }
+
+    /// Builds a Clock that reads the system time.
+    pub fn system() -> Self {
+        Clock::new(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or_default()
+        })
+    }
+
+    /// Returns the current time in milliseconds since the epoch.
+    pub fn now_ms(&self) -> u64 {
+        (self.now_ms)()
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock::system()
+    }
+}
+
+impl Debug for Clock {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.write_str("Clock")
+    }
+}
+
+/// Returns whether the expression is a call to the `now()` function.
+/// The function does not accept arguments.
+pub(crate) fn is_now_function(expression: &str) -> Result<bool, ParserError> {
+    match expression
+        .trim()
+        .strip_prefix(NOW_FUNCTION_NAME)
+        .and_then(|rest| rest.trim_start().strip_prefix(FUNCTION_ARGUMENTS_START_DELIMITER))
+        .and_then(|rest| rest.strip_suffix(FUNCTION_ARGUMENTS_END_DELIMITER))
+    {
+        Some(arguments) if arguments.trim().is_empty() => Ok(true),
+        Some(arguments) => Err(ParserError::InvalidFunctionError {
+            function: NOW_FUNCTION_NAME.to_owned(),
+            message: format!("Expected no arguments, found [{}]. Usage: now()", arguments.trim()),
+        }),
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_parse_the_now_function() {
+        assert!(is_now_function("now()").unwrap());
+        assert!(is_now_function(" now( ) ").unwrap());
+    }
+
+    #[test]
+    fn should_ignore_expressions_that_are_not_now_functions() {
+        assert!(!is_now_function("now").unwrap());
+        assert!(!is_now_function("event.payload.now").unwrap());
+        assert!(!is_now_function("nowhere()").unwrap());
+    }
+
+    #[test]
+    fn should_fail_if_arguments_are_provided() {
+        assert!(matches!(
+            is_now_function("now(event.created_ms)"),
+            Err(ParserError::InvalidFunctionError { .. })
+        ));
+    }
+
+    #[test]
+    fn system_clock_should_return_the_current_time() {
+        // Arrange
+        let clock = Clock::system();
+        let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+
+        // Act
+        let now_ms = clock.now_ms();
+
+        // Assert
+        let after = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        assert!(before <= now_ms && now_ms <= after);
+    }
+}
@@ -1,4 +1,6 @@
+use crate::date::{parse_date_function, DateFormatter};
 use crate::interpolator::StringInterpolator;
+use crate::now::{is_now_function, Clock};
 use crate::{is_valid_matcher_root, CustomParser, Template, ValueGetter, FOREACH_ITEM_KEY};
 use lazy_static::*;
 use regex::Regex;
@@ -15,6 +17,8 @@ const PAYLOAD_KEY_PARSE_REGEX: &str = r#"("[^"]+"|[^\.^\[]+|\[[^\]]+\])"#;
 const PAYLOAD_MAP_KEY_PARSE_TRAILING_DELIMITER: &str = "\"";
 const PAYLOAD_ARRAY_KEY_START_DELIMITER: &str = "[";
 const PAYLOAD_ARRAY_KEY_END_DELIMITER: &str = "]";
+const COALESCE_OPERATOR: &str = "??";
+const STRING_LITERAL_DELIMITER: char = '"';
 pub const EXTRACTED_VARIABLES_KEY: &str = "_variables";
 
 lazy_static! {
@@ -31,6 +35,8 @@ pub enum ParserError {
     InvalidCharacterError { key: String, character: String },
     #[error("EmptyAccessorError")]
     EmptyAccessorError,
+    #[error("InvalidFunctionError: [{function}]. {message}")]
+    InvalidFunctionError { function: String, message: String },
 }
 
 pub trait ParserFactory {
@@ -47,9 +53,16 @@ impl<F: Fn(&[ValueGetter]) -> Result<Box<dyn CustomParser>, ParserError>> Parser
 pub struct ParserBuilder {
     custom_parser_factories: HashMap<String, Box<dyn ParserFactory>>,
     ignored_expressions: Vec<String>,
+    clock: Clock,
 }
 
 impl ParserBuilder {
+    /// Sets the clock used to resolve the `now()` function.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     fn add_parser_factory(mut self, key: String, factory: Box<dyn ParserFactory>) -> Self {
         self.custom_parser_factories.insert(key, factory);
         self
@@ -81,21 +94,7 @@ impl ParserBuilder {
             )
             .add_ignored_expression(FOREACH_ITEM_KEY.to_owned());
 
-        match parser_builder.build_parser(input) {
-            Ok(Parser::Exp(AccessorExpression { keys })) if is_valid_matcher_root(&keys) => {
-                Ok(Parser::Exp(AccessorExpression { keys }))
-            }
-            Ok(Parser::Exp(AccessorExpression { mut keys })) => match keys.first_mut() {
-                Some(ValueGetter::Array { index }) => {
-                    Err(ParserError::UnknownKeyError { key: format!("{}", index) })
-                }
-                Some(ValueGetter::Map { key }) => {
-                    Err(ParserError::UnknownKeyError { key: std::mem::take(key) })
-                }
-                None => Err(ParserError::EmptyAccessorError),
-            },
-            res => res,
-        }
+        validate_matcher_parser(parser_builder.build_parser(input)?)
     }
 
     pub fn build_parser(&self, template_string: &str) -> Result<Parser, ParserError> {
@@ -113,6 +112,24 @@ impl ParserBuilder {
     fn parse_expression(&self, keys: &str) -> Result<Parser, ParserError> {
         let expression = &keys[2..keys.len() - 1];
 
+        if let Some((target, default)) = split_coalesce_expression(expression) {
+            let target = self.parse_expression(&format!("${{{}}}", target.trim()))?;
+            let default = match serde_json::from_str::<Value>(default.trim()) {
+                Ok(literal) => Parser::Val(literal),
+                Err(_) => self.parse_expression(&format!("${{{}}}", default.trim()))?,
+            };
+            return Ok(Parser::Coalesce { target: Box::new(target), default: Box::new(default) });
+        }
+
+        if is_now_function(expression)? {
+            return Ok(Parser::Now { clock: self.clock.clone() });
+        }
+
+        if let Some((target, formatter)) = parse_date_function(expression)? {
+            let target = self.parse_expression(&format!("${{{}}}", target))?;
+            return Ok(Parser::Date { target: Box::new(target), formatter });
+        }
+
         let getters = Parser::parse_keys(expression)?;
         let (head, tail) = match getters.as_slice() {
             [] => return Err(ParserError::EmptyAccessorError), // "${}"
@@ -140,9 +157,28 @@ impl ParserBuilder {
 #[derive(Debug)]
 pub enum Parser {
     Exp(AccessorExpression),
-    Interpolator { interpolator: StringInterpolator },
+    Interpolator {
+        interpolator: StringInterpolator,
+    },
     Val(Value),
-    Custom { key: ValueGetter, parser: Box<dyn CustomParser> },
+    Custom {
+        key: ValueGetter,
+        parser: Box<dyn CustomParser>,
+    },
+    /// Formats the epoch milliseconds timestamp returned by the target parser
+    Date {
+        target: Box<Parser>,
+        formatter: DateFormatter,
+    },
+    /// Returns the current epoch milliseconds timestamp read from the clock
+    Now {
+        clock: Clock,
+    },
+    /// Returns the value of the target parser or, if it is none, the value of the default parser
+    Coalesce {
+        target: Box<Parser>,
+        default: Box<Parser>,
+    },
 }
 
 #[derive(Debug)]
@@ -212,10 +248,49 @@ impl Parser {
             Parser::Custom { key, parser } => {
                 key.get(value).and_then(|val| parser.parse_value(val, context))
             }
+            Parser::Date { target, formatter } => target
+                .parse_value(value, context)
+                .and_then(|timestamp| formatter.format(timestamp.as_ref()))
+                .map(|text| Cow::Owned(Value::String(text))),
+            Parser::Now { clock } => Some(Cow::Owned(Value::from(clock.now_ms()))),
+            Parser::Coalesce { target, default } => {
+                target.parse_value(value, context).or_else(|| default.parse_value(value, context))
+            }
         }
     }
 }
 
+/// Validates the root of the accessor expressions used by the parser
+fn validate_matcher_parser(parser: Parser) -> Result<Parser, ParserError> {
+    match parser {
+        Parser::Exp(exp) => validate_matcher_root(exp).map(Parser::Exp),
+        Parser::Date { target, formatter } => {
+            Ok(Parser::Date { target: Box::new(validate_matcher_parser(*target)?), formatter })
+        }
+        Parser::Coalesce { target, default } => Ok(Parser::Coalesce {
+            target: Box::new(validate_matcher_parser(*target)?),
+            default: Box::new(validate_matcher_parser(*default)?),
+        }),
+        parser => Ok(parser),
+    }
+}
+
+fn validate_matcher_root(exp: AccessorExpression) -> Result<AccessorExpression, ParserError> {
+    if is_valid_matcher_root(&exp.keys) {
+        return Ok(exp);
+    }
+    let AccessorExpression { mut keys } = exp;
+    match keys.first_mut() {
+        Some(ValueGetter::Array { index }) => {
+            Err(ParserError::UnknownKeyError { key: format!("{}", index) })
+        }
+        Some(ValueGetter::Map { key }) => {
+            Err(ParserError::UnknownKeyError { key: std::mem::take(key) })
+        }
+        None => Err(ParserError::EmptyAccessorError),
+    }
+}
+
 fn get_key_between_delimiters<'input_string>(
     full_string: &'input_string str,
     start_delimiter: &str,
@@ -224,6 +299,21 @@ fn get_key_between_delimiters<'input_string>(
     full_string.strip_prefix(start_delimiter)?.strip_suffix(end_delimiter)
 }
 
+/// Splits an expression in the form `<target> ?? <default>` at the first coalesce operator
+/// that is not enclosed in double quotes.
+/// Returns None if the expression does not contain the coalesce operator.
+fn split_coalesce_expression(expression: &str) -> Option<(&str, &str)> {
+    let mut in_string_literal = false;
+    for (index, character) in expression.char_indices() {
+        if character == STRING_LITERAL_DELIMITER {
+            in_string_literal = !in_string_literal;
+        } else if !in_string_literal && expression[index..].starts_with(COALESCE_OPERATOR) {
+            return Some((&expression[..index], &expression[index + COALESCE_OPERATOR.len()..]));
+        }
+    }
+    None
+}
+
 /// Determines if a key is the first part of an expression.
 ///
 /// # Example:
@@ -275,6 +365,8 @@ mod test {
     use super::*;
     use crate::ValueGetter::Array;
     use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
 
     #[test]
     fn parser_builder_should_return_value_type() {
@@ -722,6 +814,138 @@ mod test {
         assert_eq!(&json!(1), result.as_ref());
     }
 
+    #[test]
+    fn builder_should_format_dates_in_interpolated_strings() {
+        // Arrange
+        let parser = ParserBuilder::default()
+            .build_parser(
+                r#"/archive/${date(event.created_ms, "%Y/%m/%d", "Europe/Rome")}/${event.type}.log"#,
+            )
+            .unwrap();
+
+        let map = json!({
+            "event": {
+                "type": "trap",
+                "created_ms": 1718409600000_u64
+            }
+        });
+
+        // Act
+        let result = parser.parse_value(&map, "").unwrap();
+
+        // Assert
+        assert_eq!(&json!("/archive/2024/06/15/trap.log"), result.as_ref());
+    }
+
+    #[test]
+    fn builder_should_fail_if_the_date_format_is_not_valid() {
+        // Act
+        let result = ParserBuilder::default().build_parser(r#"${date(event.created_ms, "%Q")}"#);
+
+        // Assert
+        assert!(matches!(result, Err(ParserError::InvalidFunctionError { .. })));
+    }
+
+    #[test]
+    fn engine_matcher_should_validate_the_root_of_the_date_target() {
+        // Act
+        let valid = ParserBuilder::engine_matcher(r#"${date(event.created_ms, "%Y")}"#);
+        let invalid = ParserBuilder::engine_matcher(r#"${date(evnt.created_ms, "%Y")}"#);
+
+        // Assert
+        assert!(matches!(valid, Ok(Parser::Date { .. })));
+        assert!(matches!(invalid, Err(ParserError::UnknownKeyError { .. })));
+    }
+
+    #[test]
+    fn builder_should_resolve_now_with_the_injected_clock() {
+        // Arrange
+        let now_ms = 1_718_409_600_000_u64;
+        let parser = ParserBuilder::default()
+            .with_clock(Clock::new(move || now_ms))
+            .build_parser("${now()}")
+            .unwrap();
+
+        let event = json!({});
+
+        // Act
+        let result = parser.parse_value(&event, "").unwrap();
+
+        // Assert
+        assert_eq!(&json!(now_ms), result.as_ref());
+    }
+
+    #[test]
+    fn builder_should_resolve_now_at_each_evaluation() {
+        // Arrange
+        let now_ms = Arc::new(AtomicU64::new(1_000));
+        let clock = {
+            let now_ms = now_ms.clone();
+            Clock::new(move || now_ms.load(Ordering::SeqCst))
+        };
+        let parser = ParserBuilder::default()
+            .with_clock(clock)
+            .build_parser(r#"${date(now(), "%Y")}-${now()}"#)
+            .unwrap();
+
+        // Act
+        let first = parser.parse_value(&json!({}), "").unwrap().into_owned();
+        now_ms.store(1_718_409_600_000, Ordering::SeqCst);
+        let second = parser.parse_value(&json!({}), "").unwrap().into_owned();
+
+        // Assert
+        assert_eq!(json!("1970-1000"), first);
+        assert_eq!(json!("2024-1718409600000"), second);
+    }
+
+    #[test]
+    fn engine_matcher_should_accept_the_now_function() {
+        // Act
+        let result = ParserBuilder::engine_matcher("${now()}");
+
+        // Assert
+        assert!(matches!(result, Ok(Parser::Now { .. })));
+    }
+
+    #[test]
+    fn engine_matcher_should_accept_the_context_root() {
+        // Act
+        let valid = ParserBuilder::engine_matcher("${context.matched_count}");
+        let invalid = ParserBuilder::engine_matcher("${context}");
+
+        // Assert
+        assert!(matches!(valid, Ok(Parser::Exp(_))));
+        assert!(matches!(invalid, Err(ParserError::UnknownKeyError { key }) if key == "context"));
+    }
+
+    #[test]
+    fn builder_should_not_split_on_the_coalesce_operator_in_a_string_literal() {
+        // Arrange
+        let parser = ParserBuilder::default().build_parser(r#"${event.host ?? "a ?? b"}"#).unwrap();
+        let event = json!({ "event": {} });
+
+        // Act
+        let result = parser.parse_value(&event, "").unwrap();
+
+        // Assert
+        assert_eq!(&json!("a ?? b"), result.as_ref());
+    }
+
+    #[test]
+    fn builder_should_coalesce_in_interpolated_strings() {
+        // Arrange
+        let parser = ParserBuilder::default()
+            .build_parser(r#"host: ${event.host ?? "unknown"}, type: ${event.type}"#)
+            .unwrap();
+        let event = json!({ "event": { "type": "trap" } });
+
+        // Act
+        let result = parser.parse_value(&event, "").unwrap();
+
+        // Assert
+        assert_eq!(&json!("host: unknown, type: trap"), result.as_ref());
+    }
+
     #[derive(Debug)]
     pub struct MyParser {
         pub expression: Vec<ValueGetter>,
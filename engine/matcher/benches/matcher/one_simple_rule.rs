@@ -27,13 +27,19 @@ pub fn bench(c: &mut Criterion) {
                     regex: String::from(r"[0-9]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
         );
 
         // Add action
-        let mut action = ConfigAction { id: "log".to_owned(), payload: Map::new() };
+        let mut action = ConfigAction {
+            id: "log".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
 
         action
             .payload
@@ -69,5 +75,6 @@ fn new_rule(name: &str, operator: Operator) -> Rule {
         actions: vec![],
         description: "".to_owned(),
         constraint,
+        deduplication: None,
     }
 }
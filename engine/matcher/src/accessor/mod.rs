@@ -341,6 +341,35 @@ mod test {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn should_return_value_from_metadata_if_exists() {
+        let accessor = AccessorBuilder::new().build("rule", "${event.metadata.tenant_id}").unwrap();
+
+        let mut event = json!(Event::new("event_type_string"));
+        event.add_to_metadata("tenant_id".to_owned(), Value::String("alpha".to_owned())).unwrap();
+
+        let mut extracted_vars = Value::Null;
+        let internal_event: InternalEvent = (&event, &mut extracted_vars).into();
+        let result = accessor.get(&internal_event).unwrap();
+
+        assert_eq!("alpha", result.as_ref());
+        assert!(accessor.dynamic_value());
+    }
+
+    #[test]
+    fn should_return_none_from_metadata_if_not_exists() {
+        let accessor = AccessorBuilder::new().build("rule", "${event.metadata.tenant_id}").unwrap();
+
+        let mut event = json!(Event::new("event_type_string"));
+        event.add_to_metadata("region".to_owned(), Value::String("eu".to_owned())).unwrap();
+
+        let mut extracted_vars = Value::Null;
+        let internal_event: InternalEvent = (&event, &mut extracted_vars).into();
+        let result = accessor.get(&internal_event);
+
+        assert!(result.is_none());
+    }
+
     #[test]
     fn should_return_the_entire_event() {
         let accessor = AccessorBuilder::new().build("", "${event}").unwrap();
@@ -754,10 +783,7 @@ mod test {
         // Arrange
         let builder = AccessorBuilder::new();
         let value = "${item.body}".to_owned();
-        let internal_event = InternalEvent {
-            event: &Default::default(),
-            extracted_variables: &mut Default::default(),
-        };
+        let internal_event = InternalEvent::new(&Default::default(), &mut Default::default());
 
         // Act
         let accessor = builder.build("rule_name", &value).unwrap();
@@ -772,10 +798,7 @@ mod test {
         // Arrange
         let builder = AccessorBuilder::new();
         let value = "my body is ${item.body}!".to_owned();
-        let internal_event = InternalEvent {
-            event: &Default::default(),
-            extracted_variables: &mut Default::default(),
-        };
+        let internal_event = InternalEvent::new(&Default::default(), &mut Default::default());
 
         // Act
         let accessor = builder.build("rule_name", &value).unwrap();
@@ -790,10 +813,7 @@ mod test {
         // Arrange
         let builder = AccessorBuilder::new();
         let value = "my body is ${item[0].body}!".to_owned();
-        let internal_event = InternalEvent {
-            event: &Default::default(),
-            extracted_variables: &mut Default::default(),
-        };
+        let internal_event = InternalEvent::new(&Default::default(), &mut Default::default());
 
         // Act
         let accessor = builder.build("rule_name", &value).unwrap();
@@ -889,4 +909,76 @@ mod test {
         assert_eq!("   event_type_string   ", result_1.as_ref());
         assert_eq!("  CONSTANT  ", result_2.as_ref());
     }
+
+    #[test]
+    fn should_ignore_the_coalesce_default_if_the_value_is_present() {
+        // Arrange
+        let accessor =
+            AccessorBuilder::new().build("", r#"${event.payload.host ?? "unknown"}"#).unwrap();
+
+        let mut payload = Map::new();
+        payload.insert("host".to_owned(), Value::String("web01".to_owned()));
+        let event = json!(Event::new_with_payload("event_type_string", payload));
+
+        // Act
+        let mut extracted_vars = Value::Null;
+        let internal_event: InternalEvent = (&event, &mut extracted_vars).into();
+        let result = accessor.get(&internal_event).unwrap();
+
+        // Assert
+        assert_eq!("web01", result.as_ref());
+        assert!(accessor.dynamic_value());
+    }
+
+    #[test]
+    fn should_return_the_coalesce_default_if_the_value_is_absent() {
+        // Arrange
+        let accessor =
+            AccessorBuilder::new().build("", r#"${event.payload.host ?? "unknown"}"#).unwrap();
+
+        let event = json!(Event::new("event_type_string"));
+
+        // Act
+        let mut extracted_vars = Value::Null;
+        let internal_event: InternalEvent = (&event, &mut extracted_vars).into();
+        let result = accessor.get(&internal_event).unwrap();
+
+        // Assert
+        assert_eq!("unknown", result.as_ref());
+    }
+
+    #[test]
+    fn should_return_the_value_of_the_coalesce_default_accessor_if_the_value_is_absent() {
+        // Arrange
+        let accessor = AccessorBuilder::new()
+            .build("", r#"${event.payload.host ?? event.payload.ip ?? "unknown"}"#)
+            .unwrap();
+
+        let mut payload = Map::new();
+        payload.insert("ip".to_owned(), Value::String("10.0.0.1".to_owned()));
+        let event_with_ip = json!(Event::new_with_payload("event_type_string", payload));
+        let event_without_ip = json!(Event::new("event_type_string"));
+
+        // Act
+        let mut extracted_vars = Value::Null;
+        let internal_event: InternalEvent = (&event_with_ip, &mut extracted_vars).into();
+        let result_with_ip = accessor.get(&internal_event).unwrap().into_owned();
+
+        let mut extracted_vars = Value::Null;
+        let internal_event: InternalEvent = (&event_without_ip, &mut extracted_vars).into();
+        let result_without_ip = accessor.get(&internal_event).unwrap().into_owned();
+
+        // Assert
+        assert_eq!("10.0.0.1", result_with_ip);
+        assert_eq!("unknown", result_without_ip);
+    }
+
+    #[test]
+    fn should_fail_if_the_coalesce_default_accessor_is_not_valid() {
+        // Act
+        let result = AccessorBuilder::new().build("", "${event.payload.host ?? evnt.payload.ip}");
+
+        // Assert
+        assert!(result.is_err());
+    }
 }
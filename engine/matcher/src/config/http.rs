@@ -0,0 +1,230 @@
+use crate::config::{MatcherConfig, MatcherConfigReader};
+use crate::error::MatcherError;
+use log::*;
+use reqwest::header::{ACCEPT, AUTHORIZATION, ETAG, IF_NONE_MATCH};
+use reqwest::{Client, StatusCode};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The default timeout of the requests to the remote configuration source
+pub const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 10;
+
+/// A MatcherConfigReader that fetches the whole configuration, serialized as JSON,
+/// from a remote HTTP endpoint.
+/// When the server returns an `ETag` header, the configuration is cached and the following
+/// requests are conditional: a `304 Not Modified` response returns the cached configuration.
+pub struct HttpMatcherConfigManager {
+    url: String,
+    auth_header: Option<String>,
+    client: Client,
+    cache: Mutex<Option<CachedConfig>>,
+}
+
+struct CachedConfig {
+    etag: String,
+    config: MatcherConfig,
+}
+
+impl HttpMatcherConfigManager {
+    /// Creates a HttpMatcherConfigManager that reads the configuration from the `url`.
+    /// If present, the `auth_header` is sent as value of the `Authorization` header.
+    pub fn new<S: Into<String>>(
+        url: S,
+        auth_header: Option<String>,
+    ) -> Result<HttpMatcherConfigManager, MatcherError> {
+        Self::new_with_timeout(url, auth_header, Duration::from_secs(DEFAULT_HTTP_TIMEOUT_SECS))
+    }
+
+    pub fn new_with_timeout<S: Into<String>>(
+        url: S,
+        auth_header: Option<String>,
+        timeout: Duration,
+    ) -> Result<HttpMatcherConfigManager, MatcherError> {
+        let client =
+            Client::builder().use_rustls_tls().timeout(timeout).build().map_err(|err| {
+                MatcherError::ConfigurationError {
+                    message: format!(
+                        "Cannot build the HTTP client of the config source. Err: {:?}",
+                        err
+                    ),
+                }
+            })?;
+        Ok(HttpMatcherConfigManager {
+            url: url.into(),
+            auth_header,
+            client,
+            cache: Mutex::new(None),
+        })
+    }
+
+    fn cached_etag(&self) -> Option<String> {
+        self.lock_cache().as_ref().map(|cached| cached.etag.clone())
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, Option<CachedConfig>> {
+        match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl MatcherConfigReader for HttpMatcherConfigManager {
+    async fn get_config(&self) -> Result<MatcherConfig, MatcherError> {
+        let mut request = self.client.get(&self.url).header(ACCEPT, "application/json");
+        if let Some(auth_header) = &self.auth_header {
+            request = request.header(AUTHORIZATION, auth_header);
+        }
+        if let Some(etag) = self.cached_etag() {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        debug!("HttpMatcherConfigManager - Fetching the configuration from [{}]", self.url);
+        let response = request.send().await.map_err(|err| MatcherError::ConfigurationError {
+            message: format!("Cannot fetch the configuration from [{}]. Err: {:?}", self.url, err),
+        })?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            debug!("HttpMatcherConfigManager - The configuration is not modified, using the cached one");
+            return match self.lock_cache().as_ref() {
+                Some(cached) => Ok(cached.config.clone()),
+                None => Err(MatcherError::ConfigurationError {
+                    message: format!(
+                        "The config source [{}] returned {} but there is no cached configuration",
+                        self.url, status
+                    ),
+                }),
+            };
+        }
+
+        if !status.is_success() {
+            return Err(MatcherError::ConfigurationError {
+                message: format!(
+                    "Cannot fetch the configuration from [{}]. Response status: {}",
+                    self.url, status
+                ),
+            });
+        }
+
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(|etag| etag.to_owned());
+        let body = response.bytes().await.map_err(|err| MatcherError::ConfigurationError {
+            message: format!(
+                "Cannot read the configuration received from [{}]. Err: {:?}",
+                self.url, err
+            ),
+        })?;
+        let config: MatcherConfig = serde_json::from_slice(&body).map_err(|err| {
+            MatcherError::JsonDeserializationError {
+                message: format!(
+                    "Cannot parse the configuration received from [{}]. Err: {:?}",
+                    self.url, err
+                ),
+            }
+        })?;
+
+        *self.lock_cache() = etag.map(|etag| CachedConfig { etag, config: config.clone() });
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+    use serde_json::json;
+
+    fn config_json() -> serde_json::Value {
+        json!({ "Ruleset": { "name": "remote", "rules": [] } })
+    }
+
+    #[tokio::test]
+    async fn should_fetch_the_config_from_the_remote_source() {
+        // Arrange
+        let server = MockServer::start_async().await;
+        let mock = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/config").header("authorization", "Bearer secret");
+                then.status(200).json_body(config_json());
+            })
+            .await;
+        let manager =
+            HttpMatcherConfigManager::new(server.url("/config"), Some("Bearer secret".to_owned()))
+                .unwrap();
+
+        // Act
+        let config = manager.get_config().await.unwrap();
+
+        // Assert
+        mock.assert_async().await;
+        assert_eq!(MatcherConfig::Ruleset { name: "remote".to_owned(), rules: vec![] }, config);
+    }
+
+    #[tokio::test]
+    async fn should_return_the_cached_config_when_not_modified() {
+        // Arrange
+        let server = MockServer::start_async().await;
+        let mut first_mock = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/config");
+                then.status(200).header("ETag", "\"v1\"").json_body(config_json());
+            })
+            .await;
+        let manager = HttpMatcherConfigManager::new(server.url("/config"), None).unwrap();
+        let first_config = manager.get_config().await.unwrap();
+        first_mock.delete_async().await;
+
+        let not_modified_mock = server
+            .mock_async(|when, then| {
+                when.method(GET).path("/config").header("If-None-Match", "\"v1\"");
+                then.status(304);
+            })
+            .await;
+
+        // Act
+        let second_config = manager.get_config().await.unwrap();
+
+        // Assert
+        not_modified_mock.assert_async().await;
+        assert_eq!(first_config, second_config);
+    }
+
+    #[tokio::test]
+    async fn should_fail_if_the_remote_source_returns_an_error() {
+        // Arrange
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/config");
+                then.status(500);
+            })
+            .await;
+        let manager = HttpMatcherConfigManager::new(server.url("/config"), None).unwrap();
+
+        // Act
+        let result = manager.get_config().await;
+
+        // Assert
+        assert!(matches!(result, Err(MatcherError::ConfigurationError { .. })));
+    }
+
+    #[tokio::test]
+    async fn should_fail_if_the_remote_source_is_not_reachable() {
+        // Arrange
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let url = format!("http://127.0.0.1:{}/config", port);
+        let manager = HttpMatcherConfigManager::new(url, None).unwrap();
+
+        // Act
+        let result = manager.get_config().await;
+
+        // Assert
+        assert!(matches!(result, Err(MatcherError::ConfigurationError { .. })));
+    }
+}
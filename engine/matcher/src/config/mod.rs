@@ -4,9 +4,11 @@ use crate::config::v2::{ConfigNodeDir, ConfigType};
 use crate::error::MatcherError;
 use crate::matcher;
 use crate::matcher::Matcher;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
+pub mod http;
 pub mod nodes;
 pub mod operation;
 pub mod rule;
@@ -29,13 +31,23 @@ pub struct MatcherConfigDraftData {
     pub draft_id: String,
 }
 
+/// The audit record of the deployment of a draft
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MatcherConfigDeployRecord {
+    pub user: String,
+    pub draft_id: String,
+    /// The hash of the deployed configuration
+    pub config_hash: String,
+    pub deployed_ts_ms: i64,
+}
+
 impl ConfigNodeDir for MatcherConfigDraftData {
     fn config_type() -> ConfigType {
         ConfigType::Draft
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub enum MatcherConfig {
     Filter { name: String, filter: Filter, nodes: Vec<MatcherConfig> },
@@ -44,6 +56,21 @@ pub enum MatcherConfig {
 }
 
 impl MatcherConfig {
+    /// Returns a hash of the configuration that does not depend on the order of the map keys.
+    pub fn config_hash(&self) -> Result<String, MatcherError> {
+        // Without the preserve_order feature, the keys of a serde_json Map are sorted
+        let canonical_json = serde_json::to_value(self)
+            .and_then(|value| serde_json::to_string(&value))
+            .map_err(|err| MatcherError::InternalSystemError {
+                message: format!("Cannot serialize the configuration to compute its hash: {}", err),
+            })?;
+        // 64-bit FNV-1a
+        let hash = canonical_json.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+        Ok(format!("{:016x}", hash))
+    }
+
     pub fn get_name(&self) -> &str {
         match self {
             MatcherConfig::Filter { name, .. }
@@ -456,9 +483,118 @@ impl MatcherConfig {
 
         Ok(())
     }
+
+    /// Merges two configuration trees into a single one.
+    /// The two root nodes must have the same name. The nodes with the same name and path are
+    /// merged recursively: the children of filters and iterators are united, while the rules
+    /// of the rulesets are concatenated, keeping first the rules of `self`.
+    /// Returns an error if two nodes with the same name and path are not compatible, i.e. they
+    /// are of different types, they are filters or iterators with a different definition or
+    /// they are rulesets with different rules with the same name.
+    pub fn merge(self, other: MatcherConfig) -> Result<MatcherConfig, MatcherError> {
+        self.merge_in_path(other, &[])
+    }
+
+    fn merge_in_path(
+        self,
+        other: MatcherConfig,
+        parent_path: &[&str],
+    ) -> Result<MatcherConfig, MatcherError> {
+        if self.get_name() != other.get_name() {
+            return Err(MatcherError::ConfigurationError {
+                message: format!(
+                    "Cannot merge the node {} with the node {} in path {:?}",
+                    self.get_name(),
+                    other.get_name(),
+                    parent_path
+                ),
+            });
+        }
+
+        match (self, other) {
+            (
+                MatcherConfig::Filter { name, filter, nodes },
+                MatcherConfig::Filter { filter: other_filter, nodes: other_nodes, .. },
+            ) => {
+                if filter != other_filter {
+                    return Err(MatcherError::ConfigurationError {
+                        message: format!(
+                            "Filter {} in path {:?} has two different definitions",
+                            name, parent_path
+                        ),
+                    });
+                }
+                let nodes = MatcherConfig::merge_nodes(nodes, other_nodes, parent_path, &name)?;
+                Ok(MatcherConfig::Filter { name, filter, nodes })
+            }
+            (
+                MatcherConfig::Iterator { name, iterator, nodes },
+                MatcherConfig::Iterator { iterator: other_iterator, nodes: other_nodes, .. },
+            ) => {
+                if iterator != other_iterator {
+                    return Err(MatcherError::ConfigurationError {
+                        message: format!(
+                            "Iterator {} in path {:?} has two different definitions",
+                            name, parent_path
+                        ),
+                    });
+                }
+                let nodes = MatcherConfig::merge_nodes(nodes, other_nodes, parent_path, &name)?;
+                Ok(MatcherConfig::Iterator { name, iterator, nodes })
+            }
+            (
+                MatcherConfig::Ruleset { name, mut rules },
+                MatcherConfig::Ruleset { rules: other_rules, .. },
+            ) => {
+                for other_rule in other_rules {
+                    match rules.iter().find(|rule| rule.name == other_rule.name) {
+                        None => rules.push(other_rule),
+                        Some(rule) if *rule == other_rule => {}
+                        Some(_) => {
+                            return Err(MatcherError::ConfigurationError {
+                                message: format!(
+                                    "Rule {} in ruleset {} in path {:?} has two different definitions",
+                                    other_rule.name, name, parent_path
+                                ),
+                            });
+                        }
+                    }
+                }
+                Ok(MatcherConfig::Ruleset { name, rules })
+            }
+            (node, _) => Err(MatcherError::ConfigurationError {
+                message: format!(
+                    "Cannot merge the node {} in path {:?} because it has two different types",
+                    node.get_name(),
+                    parent_path
+                ),
+            }),
+        }
+    }
+
+    fn merge_nodes(
+        mut nodes: Vec<MatcherConfig>,
+        other_nodes: Vec<MatcherConfig>,
+        parent_path: &[&str],
+        name: &str,
+    ) -> Result<Vec<MatcherConfig>, MatcherError> {
+        let mut path = parent_path.to_vec();
+        path.push(name);
+
+        for other_node in other_nodes {
+            match nodes.iter().position(|node| node.get_name() == other_node.get_name()) {
+                None => nodes.push(other_node),
+                Some(index) => {
+                    let node = nodes.remove(index);
+                    nodes.insert(index, node.merge_in_path(other_node, &path)?);
+                }
+            }
+        }
+        Ok(nodes)
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
 #[serde(untagged)]
 #[serde(deny_unknown_fields)]
 pub enum Defaultable<T: Serialize + Clone> {
@@ -518,8 +654,22 @@ pub trait MatcherConfigEditor: MatcherConfigReader + Sync + Send {
         config: &MatcherConfig,
     ) -> Result<(), MatcherError>;
 
-    /// Deploy a draft by id replacing the current tornado configuration
-    async fn deploy_draft(&self, draft_id: &str) -> Result<MatcherConfig, MatcherError>;
+    /// Deploy a draft by id replacing the current tornado configuration.
+    /// An audit record of the deployment performed by the user is appended to the deploy history.
+    async fn deploy_draft(
+        &self,
+        draft_id: &str,
+        user: String,
+    ) -> Result<MatcherConfig, MatcherError>;
+
+    /// Appends an audit record to the deploy history
+    async fn append_deploy_record(
+        &self,
+        record: &MatcherConfigDeployRecord,
+    ) -> Result<(), MatcherError>;
+
+    /// Returns the audit records of the deployed drafts, from the oldest to the most recent
+    async fn get_deploy_history(&self) -> Result<Vec<MatcherConfigDeployRecord>, MatcherError>;
 
     /// Deletes a draft by id
     async fn delete_draft(&self, draft_id: &str) -> Result<(), MatcherError>;
@@ -534,6 +684,7 @@ pub trait MatcherConfigEditor: MatcherConfigReader + Sync + Send {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::nodes::FilterMode;
     use crate::config::rule::{Constraint, Operator};
     use serde_json::json;
 
@@ -618,6 +769,7 @@ mod tests {
                     active: false,
                     constraint: Constraint { where_operator: None, with: Default::default() },
                     actions: vec![],
+                    deduplication: None,
                 }],
             }],
         };
@@ -742,6 +894,8 @@ mod tests {
                                     description: "Filter at last level".to_string(),
                                     active: false,
                                     filter: Defaultable::Default {},
+                                    mode: FilterMode::AllMatches,
+                                    continue_on_match: true,
                                 },
                                 nodes: vec![],
                             },
@@ -1153,6 +1307,8 @@ mod tests {
                     description: "".to_string(),
                     active: false,
                     filter: Defaultable::Default {},
+                    mode: FilterMode::AllMatches,
+                    continue_on_match: true,
                 },
                 nodes: vec![],
             }],
@@ -1182,6 +1338,7 @@ mod tests {
             active: true,
             constraint: Constraint { where_operator: None, with: Default::default() },
             actions: vec![],
+            deduplication: None,
         };
 
         // Act
@@ -1217,6 +1374,7 @@ mod tests {
             active: true,
             constraint: Constraint { where_operator: None, with: Default::default() },
             actions: vec![],
+            deduplication: None,
         };
 
         // Act
@@ -1312,6 +1470,7 @@ mod tests {
                     active: true,
                     constraint: Constraint { where_operator: None, with: Default::default() },
                     actions: vec![],
+                    deduplication: None,
                 }],
             }],
         };
@@ -1418,6 +1577,7 @@ mod tests {
                         active: true,
                         constraint: Constraint { where_operator: None, with: Default::default() },
                         actions: vec![],
+                        deduplication: None,
                     },
                     Rule {
                         name: "my-rule2".to_string(),
@@ -1512,6 +1672,7 @@ mod tests {
             active: true,
             constraint: Constraint { where_operator: None, with: Default::default() },
             actions: vec![],
+            deduplication: None,
         };
 
         MatcherConfig::Ruleset {
@@ -1615,8 +1776,13 @@ mod tests {
 
     #[test]
     fn should_refuse_missformated_names() {
-        let filter =
-            Filter { description: "".to_string(), active: false, filter: Defaultable::Default {} };
+        let filter = Filter {
+            description: "".to_string(),
+            active: false,
+            filter: Defaultable::Default {},
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
+        };
 
         let old_config = MatcherConfig::Filter {
             name: "root".to_string(),
@@ -1646,8 +1812,13 @@ mod tests {
 
     #[test]
     fn should_refuse_missformated_names_on_edit() {
-        let filter =
-            Filter { description: "".to_string(), active: false, filter: Defaultable::Default {} };
+        let filter = Filter {
+            description: "".to_string(),
+            active: false,
+            filter: Defaultable::Default {},
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
+        };
 
         let old_config = MatcherConfig::Filter {
             name: "root".to_string(),
@@ -1788,6 +1959,8 @@ mod tests {
                         first: json!("${event.metadata.tenant}"),
                         second: json!("master"),
                     }),
+                    mode: FilterMode::AllMatches,
+                    continue_on_match: true,
                 },
                 nodes: vec![
                     MatcherConfig::Filter {
@@ -1810,6 +1983,8 @@ mod tests {
                 description: "imported root filter".to_string(),
                 active: false,
                 filter: Defaultable::Default {},
+                mode: FilterMode::AllMatches,
+                continue_on_match: true,
             },
             nodes: vec![],
         };
@@ -1952,4 +2127,222 @@ mod tests {
             result => panic!("{:?}", result),
         }
     }
+
+    fn merge_test_rule(name: &str) -> Rule {
+        Rule {
+            name: name.to_string(),
+            description: "".to_string(),
+            do_continue: true,
+            active: true,
+            constraint: Constraint { where_operator: None, with: Default::default() },
+            actions: vec![],
+            deduplication: None,
+        }
+    }
+
+    fn merge_test_filter(type_value: &str) -> Filter {
+        Filter {
+            description: "".to_string(),
+            active: true,
+            filter: Defaultable::Value(Operator::Equals {
+                first: json!("${event.type}"),
+                second: json!(type_value),
+            }),
+            mode: FilterMode::default(),
+            continue_on_match: true,
+        }
+    }
+
+    #[test]
+    fn should_merge_two_disjoint_trees() {
+        // Arrange
+        let config = MatcherConfig::Filter {
+            name: "root".to_string(),
+            filter: Default::default(),
+            nodes: vec![MatcherConfig::Ruleset {
+                name: "team_a".to_string(),
+                rules: vec![merge_test_rule("rule_a")],
+            }],
+        };
+        let other = MatcherConfig::Filter {
+            name: "root".to_string(),
+            filter: Default::default(),
+            nodes: vec![MatcherConfig::Filter {
+                name: "team_b".to_string(),
+                filter: merge_test_filter("email"),
+                nodes: vec![MatcherConfig::Ruleset {
+                    name: "rules".to_string(),
+                    rules: vec![merge_test_rule("rule_b")],
+                }],
+            }],
+        };
+
+        // Act
+        let result = config.merge(other).unwrap();
+
+        // Assert
+        assert_eq!(
+            MatcherConfig::Filter {
+                name: "root".to_string(),
+                filter: Default::default(),
+                nodes: vec![
+                    MatcherConfig::Ruleset {
+                        name: "team_a".to_string(),
+                        rules: vec![merge_test_rule("rule_a")],
+                    },
+                    MatcherConfig::Filter {
+                        name: "team_b".to_string(),
+                        filter: merge_test_filter("email"),
+                        nodes: vec![MatcherConfig::Ruleset {
+                            name: "rules".to_string(),
+                            rules: vec![merge_test_rule("rule_b")],
+                        }],
+                    },
+                ],
+            },
+            result
+        );
+    }
+
+    #[test]
+    fn should_merge_the_overlapping_compatible_nodes() {
+        // Arrange
+        let config = MatcherConfig::Filter {
+            name: "root".to_string(),
+            filter: Default::default(),
+            nodes: vec![MatcherConfig::Filter {
+                name: "emails".to_string(),
+                filter: merge_test_filter("email"),
+                nodes: vec![
+                    MatcherConfig::Ruleset {
+                        name: "rules".to_string(),
+                        rules: vec![merge_test_rule("rule_a"), merge_test_rule("shared_rule")],
+                    },
+                    MatcherConfig::Ruleset { name: "team_a".to_string(), rules: vec![] },
+                ],
+            }],
+        };
+        let other = MatcherConfig::Filter {
+            name: "root".to_string(),
+            filter: Default::default(),
+            nodes: vec![MatcherConfig::Filter {
+                name: "emails".to_string(),
+                filter: merge_test_filter("email"),
+                nodes: vec![
+                    MatcherConfig::Ruleset {
+                        name: "rules".to_string(),
+                        rules: vec![merge_test_rule("shared_rule"), merge_test_rule("rule_b")],
+                    },
+                    MatcherConfig::Ruleset { name: "team_b".to_string(), rules: vec![] },
+                ],
+            }],
+        };
+
+        // Act
+        let result = config.merge(other).unwrap();
+
+        // Assert
+        assert_eq!(
+            MatcherConfig::Filter {
+                name: "root".to_string(),
+                filter: Default::default(),
+                nodes: vec![MatcherConfig::Filter {
+                    name: "emails".to_string(),
+                    filter: merge_test_filter("email"),
+                    nodes: vec![
+                        MatcherConfig::Ruleset {
+                            name: "rules".to_string(),
+                            rules: vec![
+                                merge_test_rule("rule_a"),
+                                merge_test_rule("shared_rule"),
+                                merge_test_rule("rule_b"),
+                            ],
+                        },
+                        MatcherConfig::Ruleset { name: "team_a".to_string(), rules: vec![] },
+                        MatcherConfig::Ruleset { name: "team_b".to_string(), rules: vec![] },
+                    ],
+                }],
+            },
+            result
+        );
+    }
+
+    #[test]
+    fn should_return_error_when_merging_filters_with_the_same_name_and_different_definitions() {
+        // Arrange
+        let config = MatcherConfig::Filter {
+            name: "root".to_string(),
+            filter: Default::default(),
+            nodes: vec![MatcherConfig::Filter {
+                name: "events".to_string(),
+                filter: merge_test_filter("email"),
+                nodes: vec![],
+            }],
+        };
+        let other = MatcherConfig::Filter {
+            name: "root".to_string(),
+            filter: Default::default(),
+            nodes: vec![MatcherConfig::Filter {
+                name: "events".to_string(),
+                filter: merge_test_filter("syslog"),
+                nodes: vec![],
+            }],
+        };
+
+        // Act
+        let result = config.merge(other);
+
+        // Assert
+        match result {
+            Err(MatcherError::ConfigurationError { message }) => {
+                assert!(message.contains("events"));
+                assert!(message.contains("root"));
+            }
+            result => unreachable!("{:?}", result),
+        }
+    }
+
+    #[test]
+    fn should_return_error_when_merging_nodes_of_different_types() {
+        // Arrange
+        let config = MatcherConfig::Filter {
+            name: "root".to_string(),
+            filter: Default::default(),
+            nodes: vec![MatcherConfig::Ruleset { name: "team".to_string(), rules: vec![] }],
+        };
+        let other = MatcherConfig::Filter {
+            name: "root".to_string(),
+            filter: Default::default(),
+            nodes: vec![MatcherConfig::Filter {
+                name: "team".to_string(),
+                filter: Default::default(),
+                nodes: vec![],
+            }],
+        };
+
+        // Act
+        let result = config.merge(other);
+
+        // Assert
+        assert!(matches!(result, Err(MatcherError::ConfigurationError { .. })));
+    }
+
+    #[test]
+    fn should_return_error_when_merging_rules_with_the_same_name_and_different_definitions() {
+        // Arrange
+        let config = MatcherConfig::Ruleset {
+            name: "root".to_string(),
+            rules: vec![merge_test_rule("rule")],
+        };
+        let other = MatcherConfig::Ruleset {
+            name: "root".to_string(),
+            rules: vec![Rule { active: false, ..merge_test_rule("rule") }],
+        };
+
+        // Act
+        let result = config.merge(other);
+
+        // Assert
+        assert!(matches!(result, Err(MatcherError::ConfigurationError { .. })));
+    }
 }
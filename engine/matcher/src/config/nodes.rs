@@ -1,14 +1,46 @@
 use crate::config::rule::Operator;
 use crate::config::Defaultable;
 use crate::error::MatcherError;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Filter {
     pub description: String,
     pub active: bool,
     pub filter: Defaultable<Operator>,
+    #[serde(default)]
+    pub mode: FilterMode,
+    /// Whether the sibling nodes that follow this Filter are processed when it matches.
+    #[serde(default = "default_continue_on_match")]
+    pub continue_on_match: bool,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter {
+            description: Default::default(),
+            active: Default::default(),
+            filter: Default::default(),
+            mode: Default::default(),
+            continue_on_match: default_continue_on_match(),
+        }
+    }
+}
+
+fn default_continue_on_match() -> bool {
+    true
+}
+
+/// Defines how the child nodes of a matching Filter are processed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+pub enum FilterMode {
+    /// All the child nodes are processed.
+    #[default]
+    AllMatches,
+    /// The child nodes are processed in order and the processing stops at the first matching one.
+    FirstMatch,
 }
 
 impl Filter {
@@ -17,9 +49,15 @@ impl Filter {
             message: format!("Cannot deserialize Filter. Error [{}]", e),
         })
     }
+
+    pub fn from_yaml(yaml: &str) -> Result<Filter, MatcherError> {
+        serde_yaml::from_str(yaml).map_err(|e| MatcherError::YamlDeserializationError {
+            message: format!("Cannot deserialize Filter. Error [{}]", e),
+        })
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct MatcherIterator {
     pub(crate) description: String,
@@ -68,6 +106,26 @@ mod test {
         );
     }
 
+    #[test]
+    fn should_deserialize_the_same_filter_from_yaml_and_json() {
+        let filename = "./test_resources/v1/filter/filter_01.json";
+        let json = fs::read_to_string(filename)
+            .unwrap_or_else(|_| panic!("Unable to open the file [{}]", filename));
+        let yaml = r##"
+description: This filter allows only events with type email
+active: true
+filter:
+  type: equal
+  first: ${event.type}
+  second: email
+"##;
+
+        let json_filter = Filter::from_json(&json).unwrap();
+        let yaml_filter = Filter::from_yaml(yaml).unwrap();
+
+        assert_eq!(json_filter, yaml_filter);
+    }
+
     #[test]
     fn should_deserialize_with_empty_filter_type_field() {
         let json = r##"{
@@ -79,6 +137,37 @@ mod test {
         let filter = Filter::from_json(json).unwrap();
 
         assert_eq!(Defaultable::Default {}, filter.filter);
+        assert_eq!(FilterMode::AllMatches, filter.mode);
+        assert!(filter.continue_on_match);
+    }
+
+    #[test]
+    fn should_deserialize_filter_mode() {
+        let json = r##"{
+          "description": "This filter forwards the events only to the first matching tenant",
+          "active": true,
+          "filter": {},
+          "mode": "FirstMatch"
+        }"##;
+
+        let filter = Filter::from_json(json).unwrap();
+
+        assert_eq!(FilterMode::FirstMatch, filter.mode);
+    }
+
+    #[test]
+    fn should_deserialize_filter_continue_on_match() {
+        let json = r##"{
+          "description": "This filter stops the processing of the following nodes if it matches",
+          "active": true,
+          "filter": {},
+          "continue_on_match": false
+        }"##;
+
+        let filter = Filter::from_json(json).unwrap();
+
+        assert!(!filter.continue_on_match);
+        assert!(Filter::default().continue_on_match);
     }
 
     #[test]
@@ -99,7 +99,7 @@ pub fn matcher_config_filter(
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::config::nodes::Filter;
+    use crate::config::nodes::{Filter, FilterMode};
     use crate::config::Defaultable;
     use maplit::hashmap;
 
@@ -553,6 +553,12 @@ mod test {
     }
 
     fn filter_definition() -> Filter {
-        Filter { description: "desc".to_owned(), active: true, filter: Defaultable::Default {} }
+        Filter {
+            description: "desc".to_owned(),
+            active: true,
+            filter: Defaultable::Default {},
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
+        }
     }
 }
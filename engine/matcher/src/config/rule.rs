@@ -3,12 +3,16 @@
 //! serialize/deserialize them to/from json format.
 
 use crate::error::MatcherError;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use tornado_common_api::retry::RetryStrategy;
 use tornado_common_api::Payload;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub use tornado_common_api::dedup_key::DedupKey;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Rule {
     #[serde(default)]
@@ -19,9 +23,24 @@ pub struct Rule {
     pub active: bool,
     pub constraint: Constraint,
     pub actions: Vec<ConfigAction>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deduplication: Option<Deduplication>,
+}
+
+/// Limits the execution of the actions of a Rule to the first matching Event with a given key;
+///   the actions of the following Events with the same key are suppressed until the TTL expires.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Deduplication {
+    /// The key that identifies the duplicated Events
+    #[schemars(with = "Value")]
+    pub key: DedupKey,
+    /// The number of seconds after which the key is forgotten
+    pub ttl_secs: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Constraint {
     #[serde(rename = "WHERE")]
@@ -30,7 +49,7 @@ pub struct Constraint {
     pub with: HashMap<String, Extractor>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Extractor {
     pub from: String,
@@ -39,7 +58,7 @@ pub struct Extractor {
     pub modifiers_post: Vec<Modifier>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(tag = "type")]
 #[serde(deny_unknown_fields)]
 pub enum Modifier {
@@ -56,12 +75,13 @@ pub enum Modifier {
     },
     ToNumber {},
     Trim {},
+    Uppercase {},
     DateAndTime {
         timezone: String,
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(untagged)]
 #[serde(deny_unknown_fields)]
 pub enum ExtractorRegex {
@@ -70,6 +90,7 @@ pub enum ExtractorRegex {
         regex: String,
         group_match_idx: Option<usize>,
         all_matches: Option<bool>,
+        empty_if_no_match: Option<bool>,
     },
     RegexNamedGroups {
         #[serde(rename = "named_match")]
@@ -82,7 +103,7 @@ pub enum ExtractorRegex {
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(tag = "type")]
 #[serde(deny_unknown_fields)]
 pub enum Operator {
@@ -118,12 +139,72 @@ pub enum Operator {
     NotEquals { first: Value, second: Value },
     #[serde(rename = "regex")]
     Regex { regex: String, target: String },
+    #[serde(rename = "similar")]
+    Similar { first: Value, second: Value, threshold: f64 },
+    #[serde(rename = "changed")]
+    Changed { entity: Value, target: Value },
+    #[serde(rename = "isType")]
+    IsType { target: Value, expected_type: ValueType },
+    /// An operator registered with `register_operator`.
+    /// The `params` are passed as they are to the operator factory.
+    #[serde(rename = "custom")]
+    Custom {
+        name: String,
+        #[serde(default)]
+        params: Value,
+    },
+}
+
+impl Operator {
+    /// Returns the name under which the operator is registered in the operator registry.
+    pub fn name(&self) -> &str {
+        match self {
+            Operator::And { .. } => "AND",
+            Operator::Or { .. } => "OR",
+            Operator::Not { .. } => "NOT",
+            Operator::Contains { .. } => "contains",
+            Operator::ContainsIgnoreCase { .. } => "containsIgnoreCase",
+            Operator::Equals { .. } => "equals",
+            Operator::EqualsIgnoreCase { .. } => "equalsIgnoreCase",
+            Operator::GreaterEqualThan { .. } => "ge",
+            Operator::GreaterThan { .. } => "gt",
+            Operator::LessEqualThan { .. } => "le",
+            Operator::LessThan { .. } => "lt",
+            Operator::NotEquals { .. } => "ne",
+            Operator::Regex { .. } => "regex",
+            Operator::Similar { .. } => "similar",
+            Operator::Changed { .. } => "changed",
+            Operator::IsType { .. } => "isType",
+            Operator::Custom { name, .. } => name,
+        }
+    }
+}
+
+/// The JSON types of a value checked by the `isType` operator
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    String,
+    Number,
+    Bool,
+    Array,
+    Object,
+    Null,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct ConfigAction {
     pub id: String,
     pub payload: Payload,
+    /// If set, the action is produced only if this operator matches the processed event.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<Operator>,
+    /// If set, it overrides the global RetryStrategy when the execution of the action fails.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<Value>")]
+    pub retry_strategy: Option<RetryStrategy>,
 }
 
 impl Rule {
@@ -132,6 +213,12 @@ impl Rule {
             message: format!("Cannot deserialize Rule. Error [{}]", e),
         })
     }
+
+    pub fn from_yaml(yaml: &str) -> Result<Rule, MatcherError> {
+        serde_yaml::from_str(yaml).map_err(|e| MatcherError::YamlDeserializationError {
+            message: format!("Cannot deserialize Rule. Error [{}]", e),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -164,7 +251,7 @@ mod test {
         let extractor1 = &rule.constraint.with["extracted_temp"];
         assert_eq!("${event.payload.body}", extractor1.from);
         match &extractor1.regex {
-            ExtractorRegex::Regex { regex, all_matches, group_match_idx } => {
+            ExtractorRegex::Regex { regex, all_matches, group_match_idx, .. } => {
                 assert_eq!("([0-9]+\\sDegrees)", regex);
                 assert_eq!(&Some(2), group_match_idx);
                 assert_eq!(all_matches, &None);
@@ -175,7 +262,7 @@ mod test {
         let extractor2 = &rule.constraint.with["all_temperatures"];
         assert_eq!("${event.payload.body}", extractor1.from);
         match &extractor2.regex {
-            ExtractorRegex::Regex { regex, group_match_idx, all_matches } => {
+            ExtractorRegex::Regex { regex, group_match_idx, all_matches, .. } => {
                 assert_eq!("([0-9]+\\sDegrees)", regex);
                 assert_eq!(&None, group_match_idx);
                 assert_eq!(all_matches, &Some(true));
@@ -206,6 +293,30 @@ mod test {
         assert!(rule.constraint.where_operator.is_none())
     }
 
+    #[test]
+    fn should_deserialize_the_same_rule_from_yaml_and_json() {
+        // Arrange
+        let json =
+            file_to_string("./test_resources/v1/config_yaml/001_all_emails_and_syslogs.json");
+        let yaml = file_to_string(
+            "./test_resources/v1/config_yaml/002_all_emails_and_syslogs_in_yaml.yaml",
+        );
+
+        // Act
+        let json_rule = Rule::from_json(&json).unwrap();
+        let yaml_rule = Rule::from_yaml(&yaml).unwrap();
+
+        // Assert
+        assert_eq!(json_rule, yaml_rule);
+    }
+
+    #[test]
+    fn should_return_error_if_invalid_yaml() {
+        let yaml = "hello: world";
+        let rule = Rule::from_yaml(yaml);
+        assert!(rule.is_err())
+    }
+
     fn file_to_string(filename: &str) -> String {
         fs::read_to_string(filename)
             .unwrap_or_else(|_| panic!("Unable to open the file [{}]", filename))
@@ -228,7 +339,7 @@ mod test {
         let extractor1 = &rule.constraint.with["extracted_temp"];
         assert_eq!("${event.payload.body}", extractor1.from);
         match &extractor1.regex {
-            ExtractorRegex::Regex { regex, all_matches, group_match_idx: _ } => {
+            ExtractorRegex::Regex { regex, all_matches, .. } => {
                 assert_eq!("([0-9]+\\sDegrees)", regex);
                 assert_eq!(all_matches, &None);
             }
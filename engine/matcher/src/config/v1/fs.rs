@@ -1,4 +1,4 @@
-use crate::config::nodes::Filter;
+use crate::config::nodes::{Filter, FilterMode};
 use crate::config::rule::Rule;
 use crate::config::{Defaultable, MatcherConfig, MatcherConfigReader};
 use crate::error::MatcherError;
@@ -24,11 +24,36 @@ impl FsMatcherConfigManager {
     }
 }
 
+/// The formats of the rule and filter configuration files.
+/// Files in different formats can be mixed in the same directory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ConfigFileFormat {
+    Json,
+    Yaml,
+}
+
+pub(crate) const CONFIG_FILE_EXTENSIONS: [(&str, ConfigFileFormat); 3] = [
+    (".json", ConfigFileFormat::Json),
+    (".yaml", ConfigFileFormat::Yaml),
+    (".yml", ConfigFileFormat::Yaml),
+];
+
+impl ConfigFileFormat {
+    /// Returns the format of a configuration file and the length of its extension.
+    /// Returns None if the file is not a configuration file.
+    pub(crate) fn from_filename(filename: &str) -> Option<(ConfigFileFormat, usize)> {
+        CONFIG_FILE_EXTENSIONS
+            .iter()
+            .find(|(extension, _)| filename.ends_with(extension))
+            .map(|(extension, format)| (*format, extension.len()))
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum DirType {
     Filter,
     Ruleset,
-    // If there is a single JSON file in a folder, we are not able to detect whether it is a filter or a rule without parsing its content.
+    // If there is a single configuration file in a folder, we are not able to detect whether it is a filter or a rule without parsing its content.
     // In this case we parse it as a rule and, if it fails, we try again to parse it as a filter.
     FilterOrRuleset,
 }
@@ -76,14 +101,14 @@ impl FsMatcherConfigManager {
 
     // Returns whether the directory contains a filter. Otherwise it contains rules.
     // These logic is used to determine the folder content:
-    // - It contains a filter if there max one json file AND at least one subdirectory. The result is true.
+    // - It contains a filter if there max one configuration file AND at least one subdirectory. The result is true.
     // - It contains a rule set if there are no subdirectories. The result is false.
     // - It returns an error in every other case.
     async fn detect_dir_type<P: AsRef<Path>>(dir: P) -> Result<DirType, MatcherError> {
         let paths = FsMatcherConfigManager::read_dir_entries(dir.as_ref()).await?;
 
         let mut subdirectories_count = 0;
-        let mut json_files_count = 0;
+        let mut config_files_count = 0;
 
         for entry in paths {
             let path = entry.path();
@@ -92,34 +117,34 @@ impl FsMatcherConfigManager {
                 subdirectories_count += 1;
             } else {
                 let filename = FsMatcherConfigManager::filename(&path)?;
-                if filename.ends_with(".json") {
-                    json_files_count += 1;
+                if ConfigFileFormat::from_filename(filename).is_some() {
+                    config_files_count += 1;
                 }
             }
         }
         debug!(
             "Path {} contains {} file(s) and {} directories",
             dir.as_ref().display(),
-            json_files_count,
+            config_files_count,
             subdirectories_count
         );
 
         if subdirectories_count > 0 {
-            if json_files_count <= 1 {
+            if config_files_count <= 1 {
                 return Ok(DirType::Filter);
             }
             return Err(MatcherError::ConfigurationError {
                 message: format!(
                     r#"Path {} contains {} file(s) and {} directories. Expected:\n
-                 for a valid filter: max one json file and at least one directory;\n
-                 for a valid rule set: zero or more json files and no directories."#,
+                 for a valid filter: max one json or yaml file and at least one directory;\n
+                 for a valid rule set: zero or more json or yaml files and no directories."#,
                     dir.as_ref().display(),
-                    json_files_count,
+                    config_files_count,
                     subdirectories_count
                 ),
             });
         }
-        if json_files_count == 1 {
+        if config_files_count == 1 {
             Ok(DirType::FilterOrRuleset)
         } else {
             Ok(DirType::Ruleset)
@@ -138,12 +163,14 @@ impl FsMatcherConfigManager {
             let path = entry.path();
 
             let filename = FsMatcherConfigManager::filename(&path)?;
-            let extension = ".json";
 
-            if !filename.ends_with(extension) {
-                warn!("Configuration file [{}] is ignored.", path.display());
-                continue;
-            }
+            let (format, extension_len) = match ConfigFileFormat::from_filename(filename) {
+                Some(format_and_extension_len) => format_and_extension_len,
+                None => {
+                    warn!("Configuration file [{}] is ignored.", path.display());
+                    continue;
+                }
+            };
 
             debug!("Loading rule from file: [{}]", path.display());
             let rule_body =
@@ -152,16 +179,19 @@ impl FsMatcherConfigManager {
                 })?;
 
             trace!("Rule body: \n{}", rule_body);
-            let mut rule =
-                Rule::from_json(&rule_body).map_err(|e| MatcherError::ConfigurationError {
-                    message: format!(
-                        "Error building Rule from file [{}]. Err: {:?}",
-                        path.display(),
-                        e
-                    ),
-                })?;
+            let rule = match format {
+                ConfigFileFormat::Json => Rule::from_json(&rule_body),
+                ConfigFileFormat::Yaml => Rule::from_yaml(&rule_body),
+            };
+            let mut rule = rule.map_err(|e| MatcherError::ConfigurationError {
+                message: format!(
+                    "Error building Rule from file [{}]. Err: {:?}",
+                    path.display(),
+                    e
+                ),
+            })?;
             rule.name = FsMatcherConfigManager::rule_name_from_filename(
-                &FsMatcherConfigManager::truncate(filename, extension.len()),
+                &FsMatcherConfigManager::truncate(filename, extension_len),
             )?
             .to_owned();
             rules.push(rule);
@@ -197,11 +227,13 @@ impl FsMatcherConfigManager {
                 continue;
             }
 
-            let extension = ".json";
-            if !filename.ends_with(extension) {
-                info!("Configuration file [{}] is ignored.", path.display());
-                continue;
-            }
+            let format = match ConfigFileFormat::from_filename(filename) {
+                Some((format, _)) => format,
+                None => {
+                    info!("Configuration file [{}] is ignored.", path.display());
+                    continue;
+                }
+            };
 
             info!("Loading filter from file: [{}]", path.display());
             let filter_body =
@@ -210,14 +242,17 @@ impl FsMatcherConfigManager {
                 })?;
 
             trace!("Filter [{}] body: \n{}", filename, filter_body);
-            let filter =
-                Filter::from_json(&filter_body).map_err(|e| MatcherError::ConfigurationError {
-                    message: format!(
-                        "Error building Filter from file [{}]. Err: {:?}",
-                        path.display(),
-                        e
-                    ),
-                })?;
+            let filter = match format {
+                ConfigFileFormat::Json => Filter::from_json(&filter_body),
+                ConfigFileFormat::Yaml => Filter::from_yaml(&filter_body),
+            };
+            let filter = filter.map_err(|e| MatcherError::ConfigurationError {
+                message: format!(
+                    "Error building Filter from file [{}]. Err: {:?}",
+                    path.display(),
+                    e
+                ),
+            })?;
             filters.push(filter);
         }
 
@@ -228,6 +263,8 @@ impl FsMatcherConfigManager {
                 active: true,
                 description: "An implicit filter that allows all events".to_owned(),
                 filter: Defaultable::Default {},
+                mode: FilterMode::AllMatches,
+                continue_on_match: true,
             };
             return Ok(MatcherConfig::Filter { name, filter, nodes });
         }
@@ -238,7 +275,7 @@ impl FsMatcherConfigManager {
         }
 
         Err(MatcherError::ConfigurationError {
-            message: format!("Config path [{}] contains {} json files and {} subdirectories. Expected exactly one json filter file or at least one subdirectory.",
+            message: format!("Config path [{}] contains {} json or yaml files and {} subdirectories. Expected exactly one json or yaml filter file or at least one subdirectory.",
                              dir.as_ref().display(), filters.len(), nodes.len()),
         })
     }
@@ -384,6 +421,33 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn should_read_json_and_yaml_rules_from_the_same_folder() {
+        // Arrange
+        let path = "./test_resources/v1/config_yaml";
+
+        // Act
+        let config = FsMatcherConfigManager::new(path, "").get_config().await.unwrap();
+
+        // Assert
+        match config {
+            MatcherConfig::Ruleset { name, rules } => {
+                assert_eq!("root", name);
+                assert_eq!(3, rules.len());
+
+                let json_rule = &rules[0];
+                let yaml_rule = &rules[1];
+                assert_eq!("all_emails_and_syslogs", json_rule.name);
+                assert_eq!("all_emails_and_syslogs_in_yaml", yaml_rule.name);
+                assert_eq!(&Rule { name: json_rule.name.clone(), ..yaml_rule.clone() }, json_rule);
+
+                assert_eq!("rule_without_where", rules[2].name);
+                assert!(rules[2].constraint.where_operator.is_none());
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[tokio::test]
     async fn should_read_rules_from_empty_folder() {
         let path = "./test_resources/v1/config_empty";
@@ -7,7 +7,8 @@ use crate::config::v2::{
     MatcherConfigRuleset, Version,
 };
 use crate::config::{
-    v1, MatcherConfig, MatcherConfigDraft, MatcherConfigDraftData, MatcherConfigEditor,
+    v1, MatcherConfig, MatcherConfigDeployRecord, MatcherConfigDraft, MatcherConfigDraftData,
+    MatcherConfigEditor,
 };
 use crate::error::MatcherError;
 use crate::matcher::Matcher;
@@ -20,6 +21,8 @@ use std::path::{Path, PathBuf};
 use tokio::io::AsyncWriteExt;
 
 const DRAFT_ID: &str = "draft_001";
+/// The file, in the drafts directory, where a JSON audit record is appended for each deployed draft
+const DEPLOY_HISTORY_FILENAME: &str = "deploy_history.jsonl";
 
 #[async_trait::async_trait(?Send)]
 impl MatcherConfigEditor for FsMatcherConfigManagerV2 {
@@ -95,7 +98,11 @@ impl MatcherConfigEditor for FsMatcherConfigManagerV2 {
         Ok(())
     }
 
-    async fn deploy_draft(&self, draft_id: &str) -> Result<MatcherConfig, MatcherError> {
+    async fn deploy_draft(
+        &self,
+        draft_id: &str,
+        user: String,
+    ) -> Result<MatcherConfig, MatcherError> {
         // ToDo: Do proper sanitation of the draft_id when multitenancy is added to avoid path-traversal vulnerabilities.
         if draft_id != DRAFT_ID {
             return Err(MatcherError::DraftNotFoundError { draft_id: draft_id.to_string() });
@@ -103,9 +110,39 @@ impl MatcherConfigEditor for FsMatcherConfigManagerV2 {
 
         let draft = self.get_draft(draft_id).await?;
         atomic_deploy_config(&self.root_path, &draft.config).await?;
+
+        let record = MatcherConfigDeployRecord {
+            user,
+            draft_id: draft_id.to_owned(),
+            config_hash: draft.config.config_hash()?,
+            deployed_ts_ms: Local::now().timestamp_millis(),
+        };
+        info!(
+            "User {} deployed draft {} with config hash {}",
+            record.user, record.draft_id, record.config_hash
+        );
+        // The config is already deployed: a failure in writing its record must not be
+        //   reported as a failed deploy
+        if let Err(err) = append_deploy_record(&self.drafts_path, &record).await {
+            error!(
+                "Cannot write the deploy history record of draft {} with config hash {}: {:?}",
+                record.draft_id, record.config_hash, err
+            );
+        }
         Ok(draft.config)
     }
 
+    async fn append_deploy_record(
+        &self,
+        record: &MatcherConfigDeployRecord,
+    ) -> Result<(), MatcherError> {
+        Ok(append_deploy_record(&self.drafts_path, record).await?)
+    }
+
+    async fn get_deploy_history(&self) -> Result<Vec<MatcherConfigDeployRecord>, MatcherError> {
+        Ok(read_deploy_history(&self.drafts_path).await?)
+    }
+
     async fn delete_draft(&self, draft_id: &str) -> Result<(), MatcherError> {
         // ToDo: Do proper sanitation of the draft_id when multitenancy is added to avoid path-traversal vulnerabilities.
         if draft_id != DRAFT_ID {
@@ -392,6 +429,61 @@ async fn create_sub_directory(dir: &Path, child_dir: &str) -> Result<PathBuf, De
     Ok(sub_dir_path)
 }
 
+async fn append_deploy_record(
+    drafts_dir: &Path,
+    record: &MatcherConfigDeployRecord,
+) -> Result<(), DeploymentError> {
+    let path = drafts_dir.join(DEPLOY_HISTORY_FILENAME);
+    let mut line = match serde_json::to_vec(record) {
+        Ok(line) => line,
+        Err(error) => {
+            return Err(DeploymentError::Serialization {
+                error,
+                data_type: std::any::type_name::<MatcherConfigDeployRecord>(),
+            })
+        }
+    };
+    line.push(b'\n');
+
+    let mut file = match tokio::fs::File::options().append(true).create(true).open(&path).await {
+        Ok(file) => file,
+        Err(error) => return Err(DeploymentError::FileIo { path, error }),
+    };
+
+    if let Err(error) = file.write_all(&line).await {
+        return Err(DeploymentError::FileIo { path, error });
+    }
+
+    if let Err(error) = file.sync_all().await {
+        return Err(DeploymentError::FileIo { path, error });
+    }
+
+    Ok(())
+}
+
+async fn read_deploy_history(
+    drafts_dir: &Path,
+) -> Result<Vec<MatcherConfigDeployRecord>, MatcherConfigError> {
+    let path = drafts_dir.join(DEPLOY_HISTORY_FILENAME);
+    if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return Ok(vec![]);
+    }
+
+    let content = match tokio::fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(error) => return Err(MatcherConfigError::FileIoError { path, error }),
+    };
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .map_err(|error| MatcherConfigError::FormatError { file: path.clone(), error })
+        })
+        .collect()
+}
+
 async fn get_drafts(drafts_dir: &Path) -> Result<Vec<String>, MatcherConfigError> {
     debug!("Trying to read draft entries from {}", drafts_dir.display());
     if !tokio::fs::try_exists(drafts_dir).await.unwrap_or(false) {
@@ -408,6 +500,10 @@ async fn get_drafts(drafts_dir: &Path) -> Result<Vec<String>, MatcherConfigError
             Err(error) => return Err(MatcherConfigError::DirIoError { path: entry.path(), error }),
         };
 
+        if entry.file_name() == DEPLOY_HISTORY_FILENAME {
+            continue;
+        }
+
         if !entry_type.is_dir() {
             warn!("Found a directory entry in the drafts directory, that is not itself a directory {}", entry.path().display());
             continue;
@@ -504,6 +600,7 @@ mod tests {
 
     const TEST_CONFIG_DIR: &str = "./test_resources/v2/test_config/";
     const TEST_DRAFT_DIR: &str = "./test_resources/v2/test_drafts/";
+    const TEST_YAML_CONFIG_DIR: &str = "./test_resources/v2/test_config_yaml/";
 
     #[tokio::test]
     async fn should_load_draft_data_from_file() {
@@ -664,7 +761,7 @@ mod tests {
         }
 
         // Act
-        config_manager.deploy_draft(DRAFT_ID).await.unwrap();
+        config_manager.deploy_draft(DRAFT_ID, "root".to_owned()).await.unwrap();
 
         // Assert
         let config = config_manager.get_config().await.unwrap();
@@ -677,6 +774,57 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn matcher_config_editor_should_append_a_deploy_record_when_a_draft_is_deployed() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let draft_temp_dir = temp_dir.path().join("drafts");
+        let config_temp_dir = temp_dir.path().join("rules.d");
+
+        let config_manager =
+            FsMatcherConfigManagerV2::new(config_temp_dir.as_path(), draft_temp_dir.as_path());
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), config_temp_dir.clone()).await.unwrap();
+        copy_recursive(PathBuf::from(TEST_DRAFT_DIR), draft_temp_dir.clone()).await.unwrap();
+        assert!(config_manager.get_deploy_history().await.unwrap().is_empty());
+
+        // Act
+        let deployed_config =
+            config_manager.deploy_draft(DRAFT_ID, "deployer".to_owned()).await.unwrap();
+
+        // Assert
+        let history = config_manager.get_deploy_history().await.unwrap();
+        assert_eq!(1, history.len());
+        assert_eq!("deployer", history[0].user);
+        assert_eq!(DRAFT_ID, history[0].draft_id);
+        assert_eq!(deployed_config.config_hash().unwrap(), history[0].config_hash);
+        assert!(history[0].deployed_ts_ms > 0);
+
+        assert_eq!(vec![DRAFT_ID.to_owned()], config_manager.get_drafts().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn matcher_config_editor_should_deploy_a_draft_even_if_the_deploy_record_cannot_be_written(
+    ) {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let draft_temp_dir = temp_dir.path().join("drafts");
+        let config_temp_dir = temp_dir.path().join("rules.d");
+
+        let config_manager =
+            FsMatcherConfigManagerV2::new(config_temp_dir.as_path(), draft_temp_dir.as_path());
+        copy_recursive(PathBuf::from(TEST_CONFIG_DIR), config_temp_dir.clone()).await.unwrap();
+        copy_recursive(PathBuf::from(TEST_DRAFT_DIR), draft_temp_dir.clone()).await.unwrap();
+        // A directory in place of the history file makes every append fail
+        tokio::fs::create_dir(draft_temp_dir.join(DEPLOY_HISTORY_FILENAME)).await.unwrap();
+
+        // Act
+        let result = config_manager.deploy_draft(DRAFT_ID, "deployer".to_owned()).await;
+
+        // Assert
+        let deployed_config = result.unwrap();
+        assert_eq!(deployed_config, config_manager.get_config().await.unwrap());
+    }
+
     #[tokio::test]
     async fn matcher_config_editor_should_take_over_draft() {
         // Arrange
@@ -698,6 +846,30 @@ mod tests {
         assert_eq!("root", config.data.user);
     }
 
+    #[tokio::test]
+    async fn matcher_config_editor_should_round_trip_a_yaml_config() {
+        // Arrange
+        let temp_dir = TempDir::new().unwrap();
+        let draft_temp_dir = temp_dir.path().join("drafts");
+        let config_temp_dir = temp_dir.path().join("rules.d");
+        copy_recursive(PathBuf::from(TEST_YAML_CONFIG_DIR), config_temp_dir.clone()).await.unwrap();
+
+        let config_manager =
+            FsMatcherConfigManagerV2::new(config_temp_dir.as_path(), draft_temp_dir.as_path());
+        let config = config_manager.get_config().await.unwrap();
+
+        // Act
+        let draft_id = config_manager.create_draft(String::from("pippo")).await.unwrap();
+        let draft = config_manager.get_draft(&draft_id).await.unwrap();
+        config_manager.update_draft(&draft_id, String::from("pippo"), &draft.config).await.unwrap();
+        config_manager.deploy_draft(&draft_id, String::from("pippo")).await.unwrap();
+
+        // Assert
+        assert_eq!(config, draft.config);
+        assert_eq!(config, config_manager.get_draft(&draft_id).await.unwrap().config);
+        assert_eq!(config, config_manager.get_config().await.unwrap());
+    }
+
     #[tokio::test]
     async fn should_deploy_and_load_iterator_node() {
         let temp_dir = TempDir::new().unwrap();
@@ -13,6 +13,7 @@ pub enum MatcherConfigError {
     FileNotFound { path: PathBuf },
     FileIoError { path: PathBuf, error: io::Error },
     DeserializationError { file: PathBuf, object_path: String, error: serde_json::Error },
+    YamlDeserializationError { file: PathBuf, object_path: String, error: serde_yaml::Error },
     FormatError { file: PathBuf, error: serde_json::Error },
     FileNameError { path: PathBuf },
     DuplicateName { name: String, previous: PathBuf, next: PathBuf },
@@ -55,6 +56,12 @@ impl Display for MatcherConfigError {
                 object_path,
                 error
             )),
+            MatcherConfigError::YamlDeserializationError { file, object_path, error } => f.write_fmt(format_args!(
+                "Could not deserialize YAML config file {}. Field {} contains erroneous data: {}",
+                file.display(),
+                object_path,
+                error
+            )),
             MatcherConfigError::DuplicateName { name, previous, next } => f.write_fmt(format_args!(
                 "Duplicate name {} in config detected. The node was first declared here: {} and then redelared here: {}",
                 name,
@@ -81,6 +88,7 @@ impl Error for MatcherConfigError {
             MatcherConfigError::DirIoError { error, .. } => Some(error as &dyn Error),
             MatcherConfigError::FileIoError { error, .. } => Some(error as &dyn Error),
             MatcherConfigError::DeserializationError { error, .. } => Some(error as &dyn Error),
+            MatcherConfigError::YamlDeserializationError { error, .. } => Some(error as &dyn Error),
             MatcherConfigError::FormatError { error, .. } => Some(error as &dyn Error),
             MatcherConfigError::UnexpectedFile { .. } => None,
             MatcherConfigError::UnknownNodeDir { .. } => None,
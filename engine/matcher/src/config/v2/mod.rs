@@ -1,8 +1,9 @@
 mod editor;
 mod error;
 
-use crate::config::nodes::{Filter, MatcherIterator};
+use crate::config::nodes::{Filter, FilterMode, MatcherIterator};
 use crate::config::rule::Rule;
+use crate::config::v1::fs::{ConfigFileFormat, CONFIG_FILE_EXTENSIONS};
 pub use crate::config::v2::error::MatcherConfigError;
 use crate::config::{Defaultable, MatcherConfig, MatcherConfigReader};
 use crate::error::MatcherError;
@@ -59,6 +60,7 @@ pub trait ConfigNodeDir {
 }
 
 impl ConfigType {
+    /// Returns the name of the node configuration file written by the editor.
     pub fn filename(&self) -> &'static str {
         match self {
             ConfigType::Root => "version.json",
@@ -68,6 +70,29 @@ impl ConfigType {
             ConfigType::Iterator => "iterator.json",
         }
     }
+
+    /// Returns the name of the node configuration file without its extension.
+    /// The file can be in JSON or in YAML format.
+    fn file_stem(&self) -> &'static str {
+        match self {
+            ConfigType::Root => "version",
+            ConfigType::Filter => "filter",
+            ConfigType::Ruleset => "ruleset",
+            ConfigType::Draft => "data",
+            ConfigType::Iterator => "iterator",
+        }
+    }
+
+    /// Returns the names that the node configuration file can have, one per supported extension.
+    fn filenames(&self) -> impl Iterator<Item = String> {
+        let file_stem = self.file_stem();
+        let extensions: &'static [(&str, ConfigFileFormat)] = &CONFIG_FILE_EXTENSIONS;
+        extensions.iter().map(move |(extension, _)| format!("{}{}", file_stem, extension))
+    }
+
+    fn is_config_filename(&self, filename: &OsStr) -> bool {
+        self.filenames().any(|config_filename| filename == OsStr::new(&config_filename))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -189,6 +214,8 @@ async fn read_config_from_root_dir(root_dir: &Path) -> Result<MatcherConfig, Mat
             active: true,
             description: "An implicit filter that allows all events".to_owned(),
             filter: Defaultable::Default {},
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
         },
         nodes,
     })
@@ -218,15 +245,15 @@ async fn read_child_nodes_from_dir(
         };
 
         if file_type.is_file() {
-            if dir_entry.file_name() == config_type.filename() {
+            if config_type.is_config_filename(&dir_entry.file_name()) {
                 debug!(
                     "Skipping file {} because it is a node configuration.",
-                    config_type.filename()
+                    dir_entry.path().display()
                 );
                 continue;
             }
 
-            if dir_entry.path().extension() != Some(OsStr::new("json")) {
+            if config_file_format(&dir_entry.path()).is_none() {
                 info!("Ignoring file [{}] as it is not a config file.", dir_entry.path().display());
                 continue;
             }
@@ -366,10 +393,31 @@ async fn read_rules_from_dir(dir: &Path) -> Result<Vec<Rule>, MatcherConfigError
 async fn parse_node_config_from_file<Data: DeserializeOwned + ConfigNodeDir>(
     dir: &Path,
 ) -> Result<Data, MatcherConfigError> {
-    let config_file_path = {
-        let mut path = PathBuf::from(dir);
-        path.push(Data::config_type().filename());
-        path
+    let config_type = Data::config_type();
+    let mut config_file_paths = vec![];
+    for filename in config_type.filenames() {
+        let path = dir.join(filename);
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            config_file_paths.push(path);
+        }
+    }
+
+    let config_file_path = match config_file_paths.len() {
+        0 => {
+            return Err(MatcherConfigError::FileNotFound { path: dir.join(config_type.filename()) })
+        }
+        1 => config_file_paths.remove(0),
+        _ => {
+            error!(
+                "Found more than one {} configuration file in directory {}",
+                config_type,
+                dir.display()
+            );
+            return Err(MatcherConfigError::UnexpectedFile {
+                path: config_file_paths.remove(1),
+                config_type,
+            });
+        }
     };
 
     trace!(
@@ -381,6 +429,8 @@ async fn parse_node_config_from_file<Data: DeserializeOwned + ConfigNodeDir>(
     parse_from_file(&config_file_path).await
 }
 
+/// Parses a config file. The files with a `yaml` or `yml` extension are parsed as YAML,
+///   all the others as JSON.
 pub(crate) async fn parse_from_file<Data: DeserializeOwned>(
     path: &Path,
 ) -> Result<Data, MatcherConfigError> {
@@ -395,6 +445,18 @@ pub(crate) async fn parse_from_file<Data: DeserializeOwned>(
         }
     };
 
+    if config_file_format(path) == Some(ConfigFileFormat::Yaml) {
+        let yd = serde_yaml::Deserializer::from_str(&content);
+        return serde_path_to_error::deserialize(yd).map_err(|error| {
+            error!("Could not parse config from file {}. {}", path.display(), error);
+            MatcherConfigError::YamlDeserializationError {
+                file: path.to_path_buf(),
+                object_path: error.path().to_string(),
+                error: error.into_inner(),
+            }
+        });
+    }
+
     let json = content.trim();
     let jd = &mut serde_json::Deserializer::from_str(json);
     match serde_path_to_error::deserialize(jd) {
@@ -418,6 +480,14 @@ pub(crate) async fn parse_from_file<Data: DeserializeOwned>(
     }
 }
 
+/// Returns the format of a config file from its extension, if it is a known one.
+fn config_file_format(path: &Path) -> Option<ConfigFileFormat> {
+    path.file_name()
+        .and_then(OsStr::to_str)
+        .and_then(ConfigFileFormat::from_filename)
+        .map(|(format, _)| format)
+}
+
 pub async fn gather_dir_entries(dir: &Path) -> Result<Vec<DirEntry>, MatcherConfigError> {
     let mut root_dir_iter = match tokio::fs::read_dir(dir).await {
         Ok(root_dir_iter) => root_dir_iter,
@@ -474,6 +544,7 @@ mod tests {
 
     const TEST_CONFIG_DIR: &str = "./test_resources/v2/test_config/";
     const TEST_BROKEN_CONFIG_DIR: &str = "./test_resources/v2/erroneous_configs/";
+    const TEST_YAML_CONFIG_DIR: &str = "./test_resources/v2/test_config_yaml/";
 
     #[tokio::test]
     async fn should_parse_filter_from_file() {
@@ -544,7 +615,7 @@ mod tests {
         };
 
         match actions.as_slice() {
-            [ConfigAction { id, payload }] => {
+            [ConfigAction { id, payload, .. }] => {
                 assert_eq!("logger", id);
                 assert!(payload.contains_key("event"))
             }
@@ -811,6 +882,35 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn should_read_yaml_config_files_mixed_with_json_ones() {
+        // Arrange
+        let json_path = String::from(TEST_CONFIG_DIR) + "tenant_a/";
+        let yaml_path = String::from(TEST_YAML_CONFIG_DIR) + "tenant_a/";
+
+        // Act
+        let json_config = read_filter_from_dir(Path::new(&json_path)).await.unwrap();
+        let yaml_config = read_filter_from_dir(Path::new(&yaml_path)).await.unwrap();
+
+        // Assert
+        assert_eq!(json_config, yaml_config);
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_node_file_in_more_than_one_format() {
+        let test_file_path = String::from(TEST_BROKEN_CONFIG_DIR) + "filter_json_and_yaml/";
+        let error = read_filter_from_dir(Path::new(&test_file_path)).await.unwrap_err();
+
+        match dbg!(error) {
+            MatcherConfigError::UnexpectedFile { path, config_type } => {
+                let path = format!("{}", path.display());
+                assert!(path.starts_with(&test_file_path));
+                assert_eq!(ConfigType::Filter, config_type);
+            }
+            result => panic!("{:#?}", result),
+        }
+    }
+
     #[tokio::test]
     async fn should_parse_empty_config_dir() {
         let dir = tempfile::tempdir().unwrap();
@@ -1,10 +1,12 @@
 use crate::error::MatcherError;
 use crate::model::{ProcessedNode, ProcessedRuleStatus};
 use log::*;
+use opentelemetry::trace::TraceContextExt;
 use std::sync::Arc;
 use tornado_common::actors::message::ActionMessage;
 use tornado_common_api::{Action, TracedAction};
 use tornado_network_common::EventBus;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 /// The dispatcher is in charge of dispatching the Actions defined in a ProcessedEvent.
 pub struct Dispatcher {
@@ -74,7 +76,7 @@ impl Dispatcher {
     }
 
     fn dispatch(&self, actions: Vec<Action>) -> Result<(), MatcherError> {
-        for (index, action) in actions.into_iter().enumerate() {
+        for (index, mut action) in actions.into_iter().enumerate() {
             let _span = tracing::error_span!(
                 "dispatch_action",
                 action = index,
@@ -82,17 +84,30 @@ impl Dispatcher {
                 otel.name = format!("Emit Action: {}", &action.id).as_str(),
             )
             .entered();
-            let action_message = ActionMessage(TracedAction {
-                span: tracing::Span::current(),
-                action: Arc::new(action),
-            });
+            if action.trace_id.is_none() {
+                action.trace_id = current_trace_id();
+            }
+            let action_message = ActionMessage(
+                TracedAction { span: tracing::Span::current(), action: Arc::new(action) },
+                None,
+            );
 
-            self.event_bus.publish_action(action_message)
+            if let Err(err) = self.event_bus.publish_action(action_message) {
+                error!("Dispatcher - Cannot publish the action. Err: {}", err);
+            }
         }
         Ok(())
     }
 }
 
+/// Returns the trace id of the current span, if it belongs to a valid trace.
+fn current_trace_id() -> Option<String> {
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    span_context.is_valid().then(|| span_context.trace_id().to_hex())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -205,7 +220,7 @@ mod test {
 
         let node = ProcessedNode::Filter {
             name: "".to_owned(),
-            filter: ProcessedFilter { status: ProcessedFilterStatus::Matched },
+            filter: ProcessedFilter { status: ProcessedFilterStatus::Matched, explanation: None },
             nodes: vec![
                 ProcessedNode::Ruleset {
                     name: "node0".to_owned(),
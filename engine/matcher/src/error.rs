@@ -35,6 +35,9 @@ pub enum MatcherError {
     #[error("JsonDeserializationError: [{message}]")]
     JsonDeserializationError { message: String },
 
+    #[error("YamlDeserializationError: [{message}]")]
+    YamlDeserializationError { message: String },
+
     #[error("ConfigurationError: [{message}]")]
     ConfigurationError { message: String },
 
@@ -42,6 +45,10 @@ pub enum MatcherError {
     )]
     NotUniqueNameError { name: String },
 
+    #[error("NotUniqueRuleNameError: The rule name [{name}] is used more than once in the paths {paths:?} but it must be unique."
+    )]
+    NotUniqueRuleNameError { name: String, paths: Vec<String> },
+
     #[error("NotValidIdOrNameError: {message}")]
     NotValidIdOrNameError { message: String },
 
@@ -53,6 +60,10 @@ pub enum MatcherError {
     )]
     InterpolatorRenderError { template: String, rule_name: String, cause: String },
 
+    #[error("MissingDeduplicationKeyError: Cannot resolve the deduplication key [{key}] for rule [{rule_name}]"
+    )]
+    MissingDeduplicationKeyError { key: String, rule_name: String },
+
     #[error("InternalSystemError: [{message}]")]
     InternalSystemError { message: String },
 
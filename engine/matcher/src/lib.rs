@@ -6,6 +6,7 @@ pub mod dispatcher;
 pub mod error;
 pub mod matcher;
 pub mod model;
+pub mod normalizer;
 pub mod regex;
 pub mod validator;
 
@@ -7,13 +7,18 @@
 use crate::accessor::{Accessor, AccessorBuilder};
 use crate::config::rule::ConfigAction;
 use crate::error::MatcherError;
+use crate::matcher::operator::{Operator, OperatorBuilder};
 use crate::model::{
     ActionMetaData, EnrichedValue, EnrichedValueContent, InternalEvent, ValueMetaData,
 };
 use log::warn;
 use serde_json::{Map, Number, Value};
 use std::collections::HashMap;
-use tornado_common_api::{Action, WithEventData};
+use tornado_common_api::retry::RetryStrategy;
+use tornado_common_api::{Action, ValueExt, WithEventData};
+
+/// The Event field with the trace id set by the collectors that do not send a trace context
+const EVENT_TRACE_ID: &str = "trace_id";
 
 #[derive(Default)]
 pub struct ActionResolverBuilder {
@@ -33,10 +38,25 @@ impl ActionResolverBuilder {
         &self,
         rule_name: &str,
         actions: &[ConfigAction],
+    ) -> Result<Vec<ActionResolver>, MatcherError> {
+        self.build_all_with_operator_builder(rule_name, actions, &OperatorBuilder::new())
+    }
+
+    /// Like `build_all` but the `when` conditions of the Actions are built with the given
+    ///   OperatorBuilder, so that their stateful operators share its state.
+    pub fn build_all_with_operator_builder(
+        &self,
+        rule_name: &str,
+        actions: &[ConfigAction],
+        operator_builder: &OperatorBuilder,
     ) -> Result<Vec<ActionResolver>, MatcherError> {
         let mut matcher_actions = vec![];
         for action in actions {
-            matcher_actions.push(self.build(rule_name, action)?);
+            matcher_actions.push(self.build_with_operator_builder(
+                rule_name,
+                action,
+                operator_builder,
+            )?);
         }
         Ok(matcher_actions)
     }
@@ -47,11 +67,27 @@ impl ActionResolverBuilder {
         &self,
         rule_name: &str,
         action: &ConfigAction,
+    ) -> Result<ActionResolver, MatcherError> {
+        self.build_with_operator_builder(rule_name, action, &OperatorBuilder::new())
+    }
+
+    /// Like `build` but the `when` condition of the Action is built with the given OperatorBuilder.
+    pub fn build_with_operator_builder(
+        &self,
+        rule_name: &str,
+        action: &ConfigAction,
+        operator_builder: &OperatorBuilder,
     ) -> Result<ActionResolver, MatcherError> {
         let mut matcher_action = ActionResolver {
             rule_name: rule_name.to_owned(),
             id: action.id.to_owned(),
             payload: HashMap::new(),
+            when: action
+                .when
+                .as_ref()
+                .map(|operator| operator_builder.build(rule_name, operator))
+                .transpose()?,
+            retry_strategy: action.retry_strategy.clone(),
         };
 
         for (payload_key, payload_value) in &action.payload {
@@ -113,9 +149,20 @@ pub struct ActionResolver {
     rule_name: String,
     pub id: String,
     payload: HashMap<String, ActionValueProcessor>,
+    when: Option<Box<dyn Operator>>,
+    retry_strategy: Option<RetryStrategy>,
 }
 
 impl ActionResolver {
+    /// Returns whether the Action should be produced for the InternalEvent.
+    /// Actions without a `when` condition are always produced.
+    pub fn is_triggered_by(&self, data: &InternalEvent) -> bool {
+        match &self.when {
+            Some(operator) => operator.evaluate(data),
+            None => true,
+        }
+    }
+
     /// Builds an Action by extracting the required data from the InternalEvent.
     /// The outcome is a fully resolved Action ready to be processed by the executors.
     pub fn resolve(&self, data: &InternalEvent) -> Result<Action, MatcherError> {
@@ -160,6 +207,12 @@ impl ActionResolver {
                 );
                 default_created_ms
             }),
+            trace_id: event
+                .event
+                .get(EVENT_TRACE_ID)
+                .and_then(|trace_id| trace_id.get_text())
+                .map(|trace_id| trace_id.to_owned()),
+            retry_strategy: self.retry_strategy.clone(),
         }
     }
 }
@@ -319,12 +372,18 @@ mod test {
     use super::*;
     use maplit::*;
     use serde_json::json;
+    use tornado_common_api::retry::{BackoffPolicy, RetryPolicy};
     use tornado_common_api::{Event, Payload, ValueExt, WithEventData};
 
     #[test]
     fn should_build_a_matcher_action() {
         // Arrange
-        let mut action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         let value = "constant value".to_owned();
         action.payload.insert("key".to_owned(), Value::String(value));
 
@@ -345,7 +404,12 @@ mod test {
     #[test]
     fn should_build_an_action() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         config_action.payload.insert("type".to_owned(), Value::String("${event.type}".to_owned()));
         config_action
             .payload
@@ -402,10 +466,75 @@ mod test {
         assert_eq!(&"var_test_2_value", &result.payload.get("var_test_2").unwrap());
     }
 
+    #[test]
+    fn should_build_an_action_with_the_trace_id_of_the_event() {
+        // Arrange
+        let config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
+        let matcher_actions =
+            ActionResolverBuilder::new().build_all("rule_for_test", &[config_action]).unwrap();
+
+        let mut event_with_trace_id = Event::new("event_type_value");
+        event_with_trace_id.trace_id = Some("a_trace_id".to_owned());
+        let event_with_trace_id = json!(event_with_trace_id);
+        let event_without_trace_id = json!(Event::new("event_type_value"));
+
+        // Act
+        let action_with_trace_id = matcher_actions[0]
+            .resolve(&(&event_with_trace_id, &mut Value::Object(Map::new())).into())
+            .unwrap();
+        let action_without_trace_id = matcher_actions[0]
+            .resolve(&(&event_without_trace_id, &mut Value::Object(Map::new())).into())
+            .unwrap();
+
+        // Assert
+        assert_eq!(Some("a_trace_id".to_owned()), action_with_trace_id.trace_id);
+        assert_eq!(None, action_without_trace_id.trace_id);
+    }
+
+    #[test]
+    fn should_build_an_action_with_the_retry_strategy_of_the_config() {
+        // Arrange
+        let config_action: ConfigAction = serde_json::from_value(json!({
+            "id": "an_action_id",
+            "payload": {},
+            "retry_strategy": {
+                "retry_policy": { "type": "MaxRetries", "retries": 5 },
+                "backoff_policy": { "type": "None" }
+            }
+        }))
+        .unwrap();
+        let matcher_actions =
+            ActionResolverBuilder::new().build_all("rule_for_test", &[config_action]).unwrap();
+        let event = json!(Event::new("event_type_value"));
+
+        // Act
+        let action =
+            matcher_actions[0].resolve(&(&event, &mut Value::Object(Map::new())).into()).unwrap();
+
+        // Assert
+        assert_eq!(
+            Some(RetryStrategy {
+                retry_policy: RetryPolicy::MaxRetries { retries: 5 },
+                backoff_policy: BackoffPolicy::None,
+            }),
+            action.retry_strategy
+        );
+    }
+
     #[test]
     fn should_build_an_action_with_text_to_be_interpolated_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         config_action.payload.insert(
             "type".to_owned(),
             Value::String("The event type is: ${event.type}".to_owned()),
@@ -438,7 +567,12 @@ mod test {
     #[test]
     fn should_build_an_action_with_bool_type_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         config_action.payload.insert("type".to_owned(), Value::Bool(true));
 
         let rule_name = "rule_for_test";
@@ -462,7 +596,12 @@ mod test {
     #[test]
     fn should_build_an_action_with_null_type_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         config_action.payload.insert("type".to_owned(), Value::Null);
 
         let rule_name = "rule_for_test";
@@ -486,7 +625,12 @@ mod test {
     #[test]
     fn should_build_an_action_with_number_type_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         config_action.payload.insert("type".to_owned(), json!(123456));
 
         let rule_name = "rule_for_test";
@@ -510,7 +654,12 @@ mod test {
     #[test]
     fn should_build_an_action_with_array_type_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         config_action.payload.insert(
             "type".to_owned(),
             Value::Array(vec![
@@ -550,7 +699,12 @@ mod test {
     #[test]
     fn should_build_an_action_with_map_type_in_config() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         config_action.payload.insert("type".to_owned(),
                                      json!(hashmap![
                                          "one".to_owned() => json!(123456.0),
@@ -584,7 +738,12 @@ mod test {
     #[test]
     fn should_build_an_action_with_maps_in_payload() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         config_action
             .payload
             .insert("payload_body".to_owned(), Value::String("${event.payload.body}".to_owned()));
@@ -618,7 +777,12 @@ mod test {
     #[test]
     fn should_put_the_whole_event_in_the_payload() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         config_action.payload.insert("event".to_owned(), Value::String("${event}".to_owned()));
 
         let rule_name = "rule_for_test";
@@ -645,7 +809,12 @@ mod test {
     #[test]
     fn should_put_the_whole_event_payload_in_the_action_payload() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         config_action
             .payload
             .insert("event_payload".to_owned(), Value::String("${event.payload}".to_owned()));
@@ -671,7 +840,12 @@ mod test {
     #[test]
     fn should_return_action_metadata_for_simple_action() {
         // Arrange
-        let mut config_action = ConfigAction { id: "an_action_id".to_owned(), payload: Map::new() };
+        let mut config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
         config_action
             .payload
             .insert("event_payload".to_owned(), Value::String("${event.payload}".to_owned()));
@@ -745,7 +919,12 @@ mod test {
             }),
         );
 
-        let config_action = ConfigAction { id: "an_action_id".to_owned(), payload };
+        let config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload,
+            when: None,
+            retry_strategy: None,
+        };
 
         let rule_name = "rule_for_test";
         let action_resolver =
@@ -830,7 +1009,12 @@ mod test {
             })]),
         );
 
-        let config_action = ConfigAction { id: "an_action_id".to_owned(), payload };
+        let config_action = ConfigAction {
+            id: "an_action_id".to_owned(),
+            payload,
+            when: None,
+            retry_strategy: None,
+        };
 
         let rule_name = "rule_for_test";
         let action_resolver =
@@ -0,0 +1,117 @@
+//! The key module derives the keys that identify a group of Events, e.g. the duplicated ones,
+//! from their fields.
+
+use crate::accessor::{Accessor, AccessorBuilder};
+use crate::config::rule::DedupKey;
+use crate::error::MatcherError;
+use crate::model::InternalEvent;
+use tornado_common_api::dedup_key::{to_key_text, DedupKeyResolver};
+
+/// The Matcher's internal representation of a DedupKey, whose fields are resolved by accessors.
+pub struct MatcherDedupKey(DedupKeyResolver<Accessor>);
+
+impl MatcherDedupKey {
+    pub fn build(rule_name: &str, key: &DedupKey) -> Result<MatcherDedupKey, MatcherError> {
+        let builder = AccessorBuilder::new();
+        Ok(MatcherDedupKey(key.build(|field| builder.build(rule_name, field))?))
+    }
+
+    /// Returns the key of the event.
+    /// A template key is None if it cannot be resolved, while a composite key always
+    ///   has a value, where each missing field is replaced by the MISSING_FIELD_PLACEHOLDER.
+    pub fn derive(&self, event: &InternalEvent) -> Option<String> {
+        self.0.derive(|accessor| accessor.get(event).map(|value| to_key_text(value.as_ref())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn derive(key: &DedupKey, event: &Value) -> Option<String> {
+        let mut extracted_variables = json!({});
+        let internal_event = InternalEvent::new(event, &mut extracted_variables);
+        MatcherDedupKey::build("rule", key).unwrap().derive(&internal_event)
+    }
+
+    fn host_check_severity_key(separator: &str) -> DedupKey {
+        DedupKey::Fields {
+            fields: vec![
+                "${event.payload.host}".to_owned(),
+                "${event.payload.check}".to_owned(),
+                "${event.payload.severity}".to_owned(),
+            ],
+            separator: separator.to_owned(),
+        }
+    }
+
+    #[test]
+    fn should_join_the_fields_with_the_separator() {
+        // Arrange
+        let key = host_check_severity_key("|");
+        let event = json!({
+            "type": "check_result",
+            "created_ms": 0,
+            "payload": { "host": "server-1", "check": "ping", "severity": 2, "output": "timeout" }
+        });
+
+        // Act
+        let first = derive(&key, &event);
+        let second = derive(&key, &event);
+
+        // Assert
+        assert_eq!(Some("server-1|ping|2".to_owned()), first);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn should_derive_the_same_key_if_only_the_other_fields_differ() {
+        // Arrange
+        let key = host_check_severity_key("::");
+        let first_event = json!({
+            "type": "check_result",
+            "created_ms": 0,
+            "payload": { "host": "server-1", "check": "ping", "severity": 2, "output": "timeout" }
+        });
+        let second_event = json!({
+            "type": "check_result",
+            "created_ms": 1000,
+            "payload": { "output": "lost", "severity": 2, "check": "ping", "host": "server-1" }
+        });
+
+        // Act & Assert
+        assert_eq!(derive(&key, &first_event), derive(&key, &second_event));
+    }
+
+    #[test]
+    fn should_replace_a_missing_field_with_the_placeholder() {
+        // Arrange
+        let key = host_check_severity_key("::");
+        let event = json!({
+            "type": "check_result",
+            "created_ms": 0,
+            "payload": { "host": "server-1", "severity": "critical" }
+        });
+
+        // Act
+        let result = derive(&key, &event);
+
+        // Assert
+        assert_eq!(Some("server-1::<missing>::critical".to_owned()), result);
+    }
+
+    #[test]
+    fn should_not_derive_a_template_key_if_it_cannot_be_resolved() {
+        // Arrange
+        let key = DedupKey::from("${event.payload.host}");
+
+        // Act & Assert
+        assert_eq!(
+            Some("server-1".to_owned()),
+            derive(&key, &json!({ "payload": { "host": "server-1" } }))
+        );
+        assert_eq!(None, derive(&key, &json!({ "payload": {} })));
+    }
+}
@@ -0,0 +1,181 @@
+//! The deduplication module contains the state required to execute the actions of a Rule
+//! only for the first Event with a given key received within a time to live.
+
+pub mod key;
+
+use crate::config::rule::{DedupKey, Deduplication};
+use crate::error::MatcherError;
+use crate::matcher::deduplication::key::MatcherDedupKey;
+use crate::model::InternalEvent;
+use log::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tornado_common_parser::Clock;
+
+/// The number of keys above which the expired keys are removed from the InMemoryDeduplicationStore
+const MIN_PURGE_THRESHOLD: usize = 1024;
+
+/// A store that keeps track of the deduplication keys already seen by the Matcher.
+pub trait DeduplicationStore: Send + Sync {
+    /// Records the key and returns true if the key was not already recorded
+    ///   within its time to live; otherwise, it returns false.
+    fn insert_if_absent(&self, key: &str, ttl: Duration) -> bool;
+}
+
+/// A DeduplicationStore that keeps the keys in memory.
+/// The keys are lost when the application is restarted.
+pub struct InMemoryDeduplicationStore {
+    clock: Clock,
+    state: Mutex<InMemoryState>,
+}
+
+struct InMemoryState {
+    expiration_ms_by_key: HashMap<String, u64>,
+    purge_threshold: usize,
+}
+
+impl Default for InMemoryDeduplicationStore {
+    fn default() -> Self {
+        InMemoryDeduplicationStore::new_with_clock(Clock::system())
+    }
+}
+
+impl InMemoryDeduplicationStore {
+    pub fn new_with_clock(clock: Clock) -> Self {
+        InMemoryDeduplicationStore {
+            clock,
+            state: Mutex::new(InMemoryState {
+                expiration_ms_by_key: HashMap::new(),
+                purge_threshold: MIN_PURGE_THRESHOLD,
+            }),
+        }
+    }
+}
+
+impl DeduplicationStore for InMemoryDeduplicationStore {
+    fn insert_if_absent(&self, key: &str, ttl: Duration) -> bool {
+        let now_ms = self.clock.now_ms();
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if state.expiration_ms_by_key.get(key).is_some_and(|expiration_ms| *expiration_ms > now_ms)
+        {
+            return false;
+        }
+
+        if state.expiration_ms_by_key.len() >= state.purge_threshold {
+            state.expiration_ms_by_key.retain(|_, expiration_ms| *expiration_ms > now_ms);
+            state.purge_threshold = MIN_PURGE_THRESHOLD.max(state.expiration_ms_by_key.len() * 2);
+        }
+
+        state.expiration_ms_by_key.insert(key.to_owned(), now_ms + ttl.as_millis() as u64);
+        true
+    }
+}
+
+/// The Matcher's internal representation of the deduplication configuration of a Rule.
+pub struct MatcherDeduplication {
+    rule_name: String,
+    rule_path: String,
+    key_template: String,
+    key: MatcherDedupKey,
+    ttl: Duration,
+}
+
+impl MatcherDeduplication {
+    /// The `rule_path` is the full path of the rule in the processing tree, so that the rules
+    ///   with the same name in different nodes do not share their keys.
+    pub fn build(
+        rule_name: &str,
+        rule_path: &str,
+        deduplication: &Deduplication,
+    ) -> Result<MatcherDeduplication, MatcherError> {
+        Ok(MatcherDeduplication {
+            rule_name: rule_name.to_owned(),
+            rule_path: rule_path.to_owned(),
+            key_template: match &deduplication.key {
+                DedupKey::Template(template) => template.to_owned(),
+                DedupKey::Fields { fields, separator } => fields.join(separator),
+            },
+            key: MatcherDedupKey::build(rule_name, &deduplication.key)?,
+            ttl: Duration::from_secs(deduplication.ttl_secs),
+        })
+    }
+
+    /// Returns true if the event is the first one with its key in the store
+    ///   within the time to live.
+    pub fn is_first_occurrence(
+        &self,
+        event: &InternalEvent,
+        store: &dyn DeduplicationStore,
+    ) -> Result<bool, MatcherError> {
+        let key = match self.key.derive(event) {
+            Some(key) => key,
+            None => {
+                return Err(MatcherError::MissingDeduplicationKeyError {
+                    key: self.key_template.to_owned(),
+                    rule_name: self.rule_name.to_owned(),
+                })
+            }
+        };
+        let scoped_key = format!("{}::{}", self.rule_path, key);
+        trace!("Matcher process - checking deduplication key [{}]", scoped_key);
+        Ok(store.insert_if_absent(&scoped_key, self.ttl))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn should_accept_a_key_only_once_within_the_ttl() {
+        // Arrange
+        let now_ms = Arc::new(AtomicU64::new(1_000));
+        let clock_now_ms = now_ms.clone();
+        let store = InMemoryDeduplicationStore::new_with_clock(Clock::new(move || {
+            clock_now_ms.load(Ordering::SeqCst)
+        }));
+        let ttl = Duration::from_secs(10);
+
+        // Act & Assert
+        assert!(store.insert_if_absent("host1", ttl));
+        assert!(!store.insert_if_absent("host1", ttl));
+        assert!(store.insert_if_absent("host2", ttl));
+
+        now_ms.store(10_999, Ordering::SeqCst);
+        assert!(!store.insert_if_absent("host1", ttl));
+
+        now_ms.store(11_000, Ordering::SeqCst);
+        assert!(store.insert_if_absent("host1", ttl));
+        assert!(!store.insert_if_absent("host1", ttl));
+    }
+
+    #[test]
+    fn should_remove_the_expired_keys() {
+        // Arrange
+        let now_ms = Arc::new(AtomicU64::new(0));
+        let clock_now_ms = now_ms.clone();
+        let store = InMemoryDeduplicationStore::new_with_clock(Clock::new(move || {
+            clock_now_ms.load(Ordering::SeqCst)
+        }));
+        let ttl = Duration::from_millis(10);
+
+        for index in 0..MIN_PURGE_THRESHOLD {
+            store.insert_if_absent(&index.to_string(), ttl);
+        }
+
+        // Act
+        now_ms.store(100, Ordering::SeqCst);
+        store.insert_if_absent("new_key", ttl);
+
+        // Assert
+        assert_eq!(1, store.state.lock().unwrap().expiration_ms_by_key.len());
+    }
+}
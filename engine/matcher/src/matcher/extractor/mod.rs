@@ -9,7 +9,7 @@ use crate::config::rule::{Extractor, ExtractorRegex};
 use crate::error::MatcherError;
 use crate::matcher::modifier::ValueModifier;
 use crate::model::InternalEvent;
-use crate::regex::RegexWrapper;
+use crate::regex::{RegexLimits, RegexWrapper};
 use log::*;
 use regex::{Captures, Regex as RustRegex};
 use serde_json::{Map, Value};
@@ -20,12 +20,22 @@ use tornado_common_api::ValueExt;
 #[derive(Default)]
 pub struct MatcherExtractorBuilder {
     accessor: AccessorBuilder,
+    regex_limits: RegexLimits,
 }
 
 impl MatcherExtractorBuilder {
     /// Returns a new MatcherExtractorBuilder instance.
     pub fn new() -> MatcherExtractorBuilder {
-        MatcherExtractorBuilder { accessor: AccessorBuilder::new() }
+        MatcherExtractorBuilder {
+            accessor: AccessorBuilder::new(),
+            regex_limits: RegexLimits::default(),
+        }
+    }
+
+    /// Sets the limits applied when the regexes of the extractors are compiled.
+    pub fn with_regex_limits(mut self, regex_limits: RegexLimits) -> MatcherExtractorBuilder {
+        self.regex_limits = regex_limits;
+        self
     }
 
     /// Returns a specific MatcherExtractor instance based on the matcher.extractor rule configuration.
@@ -51,6 +61,7 @@ impl MatcherExtractorBuilder {
     ///                regex: String::from(r"[0-9]+"),
     ///                group_match_idx: Some(0),
     ///                all_matches: None,
+    ///                empty_if_no_match: None,
     ///            },
     ///            modifiers_post: vec![],
     ///        },
@@ -83,7 +94,13 @@ impl MatcherExtractorBuilder {
         for (key, extractor) in config.iter() {
             matcher_extractor.extractors.insert(
                 key.to_owned(),
-                ValueExtractor::build(rule_name, key, extractor, &self.accessor)?,
+                ValueExtractor::build(
+                    rule_name,
+                    key,
+                    extractor,
+                    &self.accessor,
+                    &self.regex_limits,
+                )?,
             );
         }
 
@@ -148,11 +165,22 @@ impl ValueExtractor {
         key: &str,
         extractor: &Extractor,
         accessor: &AccessorBuilder,
+        regex_limits: &RegexLimits,
     ) -> Result<ValueExtractor, MatcherError> {
         Ok(Self {
             key: key.to_owned(),
-            regex_extractor: RegexValueExtractor::build(rule_name, extractor, accessor)?,
-            modifiers_post: ValueModifier::build(rule_name, accessor, &extractor.modifiers_post)?,
+            regex_extractor: RegexValueExtractor::build(
+                rule_name,
+                extractor,
+                accessor,
+                regex_limits,
+            )?,
+            modifiers_post: ValueModifier::build_with_regex_limits(
+                rule_name,
+                accessor,
+                &extractor.modifiers_post,
+                regex_limits,
+            )?,
         })
     }
 
@@ -163,7 +191,15 @@ impl ValueExtractor {
     ) -> Result<Value, MatcherError> {
         let mut extracted_value = self.regex_extractor.extract(variable_name, event)?;
         for modifier in &self.modifiers_post {
-            modifier.apply(variable_name, &mut extracted_value, event)?;
+            if let Err(err) = modifier.apply(variable_name, &mut extracted_value, event) {
+                if !modifier.is_lenient() {
+                    return Err(err);
+                }
+                warn!(
+                    "The modifier {:?} cannot be applied to the variable [{}]. The value is left unchanged. Err: {:?}",
+                    modifier, variable_name, err
+                );
+            }
         }
         Ok(extracted_value)
     }
@@ -171,13 +207,38 @@ impl ValueExtractor {
 
 #[derive(Debug)]
 enum RegexValueExtractor {
-    SingleMatchSingleGroup { regex: RegexWrapper, group_match_idx: usize, target: Accessor },
-    AllMatchesSingleGroup { regex: RegexWrapper, group_match_idx: usize, target: Accessor },
-    SingleMatchAllGroups { regex: RegexWrapper, target: Accessor },
-    AllMatchesAllGroups { regex: RegexWrapper, target: Accessor },
-    SingleMatchNamedGroups { regex: RegexWrapper, target: Accessor },
-    AllMatchesNamedGroups { regex: RegexWrapper, target: Accessor },
-    SingleKeyMatch { regex: RegexWrapper, target: Accessor },
+    SingleMatchSingleGroup {
+        regex: RegexWrapper,
+        group_match_idx: usize,
+        target: Accessor,
+    },
+    AllMatchesSingleGroup {
+        regex: RegexWrapper,
+        group_match_idx: usize,
+        target: Accessor,
+        empty_if_no_match: bool,
+    },
+    SingleMatchAllGroups {
+        regex: RegexWrapper,
+        target: Accessor,
+    },
+    AllMatchesAllGroups {
+        regex: RegexWrapper,
+        target: Accessor,
+        empty_if_no_match: bool,
+    },
+    SingleMatchNamedGroups {
+        regex: RegexWrapper,
+        target: Accessor,
+    },
+    AllMatchesNamedGroups {
+        regex: RegexWrapper,
+        target: Accessor,
+    },
+    SingleKeyMatch {
+        regex: RegexWrapper,
+        target: Accessor,
+    },
 }
 
 impl RegexValueExtractor {
@@ -185,14 +246,23 @@ impl RegexValueExtractor {
         rule_name: &str,
         extractor: &Extractor,
         accessor: &AccessorBuilder,
+        regex_limits: &RegexLimits,
     ) -> Result<RegexValueExtractor, MatcherError> {
         let target = accessor.build(rule_name, &extractor.from)?;
 
         match &extractor.regex {
-            ExtractorRegex::Regex { regex, group_match_idx, all_matches } => {
-                let rust_regex = RegexWrapper::new(regex)?;
+            ExtractorRegex::Regex { regex, group_match_idx, all_matches, empty_if_no_match } => {
+                let rust_regex = RegexWrapper::new_with_limits(regex, regex_limits)?;
 
                 let all_matches = all_matches.unwrap_or(false);
+                let empty_if_no_match = empty_if_no_match.unwrap_or(false);
+
+                if empty_if_no_match && !all_matches {
+                    warn!(
+                        "The regex [{}] has 'empty_if_no_match' set but 'all_matches' is not enabled. The option is ignored.",
+                        regex
+                    );
+                }
 
                 if has_named_groups(&rust_regex) {
                     warn!(
@@ -208,6 +278,7 @@ impl RegexValueExtractor {
                                 target,
                                 group_match_idx: *group_match_idx,
                                 regex: rust_regex,
+                                empty_if_no_match,
                             })
                         } else {
                             Ok(RegexValueExtractor::SingleMatchSingleGroup {
@@ -222,6 +293,7 @@ impl RegexValueExtractor {
                             Ok(RegexValueExtractor::AllMatchesAllGroups {
                                 target,
                                 regex: rust_regex,
+                                empty_if_no_match,
                             })
                         } else {
                             Ok(RegexValueExtractor::SingleMatchAllGroups {
@@ -233,7 +305,7 @@ impl RegexValueExtractor {
                 }
             }
             ExtractorRegex::RegexNamedGroups { regex, all_matches } => {
-                let rust_regex = RegexWrapper::new(regex)?;
+                let rust_regex = RegexWrapper::new_with_limits(regex, regex_limits)?;
 
                 if !has_named_groups(&rust_regex) {
                     return Err(MatcherError::ConfigurationError {
@@ -251,7 +323,7 @@ impl RegexValueExtractor {
                 }
             }
             ExtractorRegex::SingleKeyRegex { regex } => {
-                let rust_regex = RegexWrapper::new(regex)?;
+                let rust_regex = RegexWrapper::new_with_limits(regex, regex_limits)?;
                 Ok(RegexValueExtractor::SingleKeyMatch { regex: rust_regex, target })
             }
         }
@@ -288,7 +360,12 @@ impl RegexValueExtractor {
                         variable_name: variable_name.to_owned(),
                     })
             }
-            RegexValueExtractor::AllMatchesSingleGroup { regex, group_match_idx, target } => {
+            RegexValueExtractor::AllMatchesSingleGroup {
+                regex,
+                group_match_idx,
+                target,
+                empty_if_no_match,
+            } => {
                 let cow_value = target.get(event).ok_or_else(|| {
                     MatcherError::MissingExtractedVariableError {
                         variable_name: variable_name.to_owned(),
@@ -313,7 +390,7 @@ impl RegexValueExtractor {
                         });
                     }
                 }
-                if !result.is_empty() {
+                if !result.is_empty() || *empty_if_no_match {
                     Ok(Value::Array(result))
                 } else {
                     Err(MatcherError::MissingExtractedVariableError {
@@ -346,7 +423,7 @@ impl RegexValueExtractor {
                     })
                 }
             }
-            RegexValueExtractor::AllMatchesAllGroups { regex, target } => {
+            RegexValueExtractor::AllMatchesAllGroups { regex, target, empty_if_no_match } => {
                 let cow_value = target.get(event).ok_or_else(|| {
                     MatcherError::MissingExtractedVariableError {
                         variable_name: variable_name.to_owned(),
@@ -368,7 +445,7 @@ impl RegexValueExtractor {
                         });
                     }
                 }
-                if !result.is_empty() {
+                if !result.is_empty() || *empty_if_no_match {
                     Ok(Value::Array(result))
                 } else {
                     Err(MatcherError::MissingExtractedVariableError {
@@ -503,7 +580,7 @@ mod test {
     use maplit::*;
     use serde_json::json;
     use std::collections::HashMap;
-    use tornado_common_api::{Event, ValueGet};
+    use tornado_common_api::{Event, ValueGet, WithEventData};
 
     #[test]
     fn should_build_an_extractor() {
@@ -516,6 +593,7 @@ mod test {
                     regex: "".to_string(),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -533,6 +611,7 @@ mod test {
                 regex: "".to_string(),
                 group_match_idx: Some(0),
                 all_matches: None,
+                empty_if_no_match: None,
             },
             modifiers_post: vec![Modifier::Trim {}],
         };
@@ -561,6 +640,7 @@ mod test {
                     regex: "[".to_string(),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -580,6 +660,7 @@ mod test {
                     regex: r"(https?|ftp)://([^/\r\n]+)(/[^\r\n]*)?".to_string(),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -606,6 +687,7 @@ mod test {
                     regex: r"(https?|ftp)://([^/\r\n]+)(/[^\r\n]*)?".to_string(),
                     group_match_idx: Some(1),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -632,6 +714,7 @@ mod test {
                     regex: r"(https?|ftp)://([^/\r\n]+)(/[^\r\n]*)?".to_string(),
                     group_match_idx: Some(1),
                     all_matches: Some(true),
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -658,6 +741,7 @@ mod test {
                     regex: r"(https?|ftp)://([^/\r\n]+)(/[^\r\n]*)?".to_string(),
                     group_match_idx: Some(2),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -684,6 +768,7 @@ mod test {
                     regex: r"(https?|ftp)://([^/\r\n]+)(/[^\r\n]*)?".to_string(),
                     group_match_idx: Some(10000),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -707,6 +792,7 @@ mod test {
                     regex: r"(https?|ftp)://([^/\r\n]+)(/[^\r\n]*)?".to_string(),
                     group_match_idx: Some(10000),
                     all_matches: Some(true),
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -719,6 +805,82 @@ mod test {
         assert!(extractor.extract("", &(&event, &mut Value::Null).into()).is_err());
     }
 
+    #[test]
+    fn should_extract_all_the_matches_of_a_group_into_an_array() {
+        let extractor = ValueExtractor::build(
+            "rule_name",
+            "key",
+            &Extractor {
+                from: "${event.type}".to_string(),
+                regex: ExtractorRegex::Regex {
+                    regex: r"(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3})".to_string(),
+                    group_match_idx: Some(1),
+                    all_matches: Some(true),
+                    empty_if_no_match: None,
+                },
+                modifiers_post: vec![],
+            },
+            &AccessorBuilder::new(),
+        )
+        .unwrap();
+
+        let event =
+            new_event("blocked connections from 10.0.0.1, 192.168.1.20 and 172.16.254.3 to web01");
+
+        assert_eq!(
+            json!(["10.0.0.1", "192.168.1.20", "172.16.254.3"]),
+            extractor.extract("", &(&event, &mut Value::Null).into()).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_return_error_if_no_matches_and_not_empty_if_no_match() {
+        let extractor = ValueExtractor::build(
+            "rule_name",
+            "key",
+            &Extractor {
+                from: "${event.type}".to_string(),
+                regex: ExtractorRegex::Regex {
+                    regex: r"(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3})".to_string(),
+                    group_match_idx: Some(1),
+                    all_matches: Some(true),
+                    empty_if_no_match: Some(false),
+                },
+                modifiers_post: vec![],
+            },
+            &AccessorBuilder::new(),
+        )
+        .unwrap();
+
+        let event = new_event("no blocked connections");
+
+        assert!(extractor.extract("", &(&event, &mut Value::Null).into()).is_err());
+    }
+
+    #[test]
+    fn should_return_empty_array_if_no_matches_and_empty_if_no_match() {
+        let extractor = ValueExtractor::build(
+            "rule_name",
+            "key",
+            &Extractor {
+                from: "${event.type}".to_string(),
+                regex: ExtractorRegex::Regex {
+                    regex: r"(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3})".to_string(),
+                    group_match_idx: Some(1),
+                    all_matches: Some(true),
+                    empty_if_no_match: Some(true),
+                },
+                modifiers_post: vec![],
+            },
+            &AccessorBuilder::new(),
+        )
+        .unwrap();
+
+        let event = new_event("no blocked connections");
+
+        assert_eq!(json!([]), extractor.extract("", &(&event, &mut Value::Null).into()).unwrap());
+    }
+
     #[test]
     fn should_match_and_return_none_if_not_value_from_event() {
         let extractor = ValueExtractor::build(
@@ -730,6 +892,7 @@ mod test {
                     regex: r"(https?|ftp)://([^/\r\n]+)(/[^\r\n]*)?".to_string(),
                     group_match_idx: Some(1),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -742,6 +905,34 @@ mod test {
         assert!(extractor.extract("", &(&event, &mut Value::Null).into()).is_err());
     }
 
+    #[test]
+    fn should_extract_a_value_from_the_event_metadata() {
+        let extractor = ValueExtractor::build(
+            "rule_name",
+            "key",
+            &Extractor {
+                from: "${event.metadata.tenant_id}".to_string(),
+                regex: ExtractorRegex::Regex {
+                    regex: r"[a-z]+".to_string(),
+                    group_match_idx: Some(0),
+                    all_matches: None,
+                    empty_if_no_match: None,
+                },
+                modifiers_post: vec![],
+            },
+            &AccessorBuilder::new(),
+        )
+        .unwrap();
+
+        let mut event = new_event("");
+        event.add_to_metadata("tenant_id".to_owned(), json!("alpha")).unwrap();
+
+        assert_eq!(
+            Value::String("alpha".to_owned()),
+            extractor.extract("", &(&event, &mut Value::Null).into()).unwrap()
+        );
+    }
+
     #[test]
     fn should_extract_all_variables_and_return_true() {
         let mut from_config = HashMap::new();
@@ -754,6 +945,7 @@ mod test {
                     regex: String::from(r"[0-9]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -767,6 +959,7 @@ mod test {
                     regex: String::from(r"[a-z]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -803,6 +996,7 @@ mod test {
                     regex: String::from(r"[0-9]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -816,6 +1010,7 @@ mod test {
                     regex: String::from(r"[a-z]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -840,6 +1035,7 @@ mod test {
                     regex: r"(https?|ftp)://([^/\r\n]+)(/[^\r\n]*)?".to_string(),
                     group_match_idx: None,
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -871,6 +1067,7 @@ mod test {
                     regex: r"(https?|ftp)://([^.\n]+).([^.\n]*)?".to_string(),
                     group_match_idx: None,
                     all_matches: Some(true),
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -910,6 +1107,7 @@ mod test {
                     regex: r"(https?|ftp)://([^.\n]+).(/[^.\n]*)?".to_string(),
                     group_match_idx: None,
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -933,6 +1131,7 @@ mod test {
                     regex: r"ab(c)*d".to_string(),
                     group_match_idx: None,
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -956,6 +1155,7 @@ mod test {
                     regex: r"(https?|ftp)://([^.\n]+).(/[^.\n]*)?".to_string(),
                     group_match_idx: None,
                     all_matches: Some(true),
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -980,6 +1180,7 @@ mod test {
                         .to_string(),
                     group_match_idx: None,
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -1496,6 +1697,7 @@ mod test {
                 regex: ExtractorRegex::Regex {
                     regex: r#".*"#.to_string(),
                     all_matches: Some(false),
+                    empty_if_no_match: None,
                     group_match_idx: Some(0),
                 },
                 modifiers_post: vec![Modifier::Trim {}],
@@ -1532,6 +1734,7 @@ mod test {
                 regex: ExtractorRegex::Regex {
                     regex: r#".*"#.to_string(),
                     all_matches: Some(true),
+                    empty_if_no_match: None,
                     group_match_idx: None,
                 },
                 modifiers_post: vec![Modifier::Trim {}],
@@ -1569,6 +1772,7 @@ mod test {
                 regex: ExtractorRegex::Regex {
                     regex: r#".*"#.to_string(),
                     all_matches: Some(false),
+                    empty_if_no_match: None,
                     group_match_idx: Some(0),
                 },
                 modifiers_post: vec![
@@ -1594,6 +1798,112 @@ mod test {
         assert_eq!(Value::String("hello to be trimmed replaced_and lowercased".to_owned()), result);
     }
 
+    fn build_value_extractor(modifiers_post: Vec<Modifier>) -> ValueExtractor {
+        ValueExtractor::build(
+            "rule_name",
+            "key",
+            &Extractor {
+                from: "${event.payload.value}".to_string(),
+                regex: ExtractorRegex::Regex {
+                    regex: r#".*"#.to_string(),
+                    all_matches: Some(false),
+                    empty_if_no_match: None,
+                    group_match_idx: Some(0),
+                },
+                modifiers_post,
+            },
+            &AccessorBuilder::new(),
+        )
+        .unwrap()
+    }
+
+    fn new_event_with_value(value: &str) -> Value {
+        let mut payload = Map::new();
+        payload.insert("value".to_owned(), Value::String(value.to_owned()));
+        json!(Event::new_with_payload("event", payload))
+    }
+
+    #[test]
+    fn should_apply_the_lowercase_modifier_to_the_extracted_value() {
+        // Arrange
+        let extractor = build_value_extractor(vec![Modifier::Lowercase {}]);
+        let event = new_event_with_value("Host-ONE");
+
+        // Act
+        let result = extractor.extract("var", &(&event, &mut Value::Null).into()).unwrap();
+
+        // Assert
+        assert_eq!(Value::String("host-one".to_owned()), result);
+    }
+
+    #[test]
+    fn should_apply_the_uppercase_modifier_to_the_extracted_value() {
+        // Arrange
+        let extractor = build_value_extractor(vec![Modifier::Uppercase {}]);
+        let event = new_event_with_value("Host-one");
+
+        // Act
+        let result = extractor.extract("var", &(&event, &mut Value::Null).into()).unwrap();
+
+        // Assert
+        assert_eq!(Value::String("HOST-ONE".to_owned()), result);
+    }
+
+    #[test]
+    fn should_apply_the_trim_modifier_to_the_extracted_value() {
+        // Arrange
+        let extractor = build_value_extractor(vec![Modifier::Trim {}]);
+        let event = new_event_with_value("  host-one ");
+
+        // Act
+        let result = extractor.extract("var", &(&event, &mut Value::Null).into()).unwrap();
+
+        // Assert
+        assert_eq!(Value::String("host-one".to_owned()), result);
+    }
+
+    #[test]
+    fn should_apply_the_to_number_modifier_to_the_extracted_value() {
+        // Arrange
+        let extractor = build_value_extractor(vec![Modifier::Trim {}, Modifier::ToNumber {}]);
+        let event = new_event_with_value(" 42 ");
+
+        // Act
+        let result = extractor.extract("var", &(&event, &mut Value::Null).into()).unwrap();
+
+        // Assert
+        assert_eq!(json!(42), result);
+    }
+
+    #[test]
+    fn should_leave_the_value_unchanged_if_a_lenient_modifier_cannot_be_applied() {
+        // Arrange
+        let extractor = build_value_extractor(vec![Modifier::ToNumber {}, Modifier::Uppercase {}]);
+        let event = new_event_with_value("not a number");
+
+        // Act
+        let result = extractor.extract("var", &(&event, &mut Value::Null).into()).unwrap();
+
+        // Assert
+        assert_eq!(Value::String("NOT A NUMBER".to_owned()), result);
+    }
+
+    #[test]
+    fn should_fail_if_a_map_modifier_cannot_be_applied() {
+        // Arrange
+        let extractor = build_value_extractor(vec![Modifier::Map {
+            mapping: HashMap::new(),
+            default_value: None,
+        }]);
+        let event = new_event_with_value("not mapped");
+
+        // Act
+        let result = extractor.extract("var", &(&event, &mut Value::Null).into());
+
+        // Assert
+        assert!(result.is_err());
+    }
+
     fn new_event(event_type: &str) -> Value {
         json!(Event::new(event_type))
     }
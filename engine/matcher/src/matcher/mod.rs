@@ -1,23 +1,33 @@
 pub mod action;
+pub mod deduplication;
 pub mod extractor;
 pub mod modifier;
 pub mod operator;
+pub mod previous_value;
 
 use tracing::instrument;
 
 use crate::accessor::AccessorBuilder;
+use crate::config::nodes::{Filter, FilterMode, MatcherIterator};
 use crate::config::rule::Rule;
 use crate::config::MatcherConfig;
 use crate::error::MatcherError;
+use crate::matcher::deduplication::{
+    DeduplicationStore, InMemoryDeduplicationStore, MatcherDeduplication,
+};
 use crate::matcher::extractor::{MatcherExtractor, MatcherExtractorBuilder};
+use crate::matcher::operator::OperatorBuilder;
+use crate::matcher::previous_value::{InMemoryPreviousValueStore, PreviousValueStore};
 use crate::model::{
     InternalEvent, ProcessedEvent, ProcessedFilter, ProcessedFilterStatus, ProcessedIteration,
     ProcessedIterator, ProcessedNode, ProcessedRule, ProcessedRuleMetaData, ProcessedRuleStatus,
     ProcessedRules,
 };
+use crate::regex::RegexLimits;
 use crate::validator::MatcherConfigValidator;
 use log::*;
 use serde_json::{json, Map, Value};
+use std::sync::Arc;
 use tornado_common_parser::AccessorExpression;
 
 /// The Matcher's internal Rule representation, which contains the operators and executors built
@@ -28,32 +38,71 @@ pub struct MatcherRule {
     operator: Box<dyn operator::Operator>,
     extractor: MatcherExtractor,
     actions: Vec<action::ActionResolver>,
+    deduplication: Option<MatcherDeduplication>,
 }
 
 /// The Matcher's internal Filter representation, which contains the operators and executors built
 ///   from the config::filter::Filter.
 pub struct MatcherFilter {
     pub active: bool,
+    pub mode: FilterMode,
+    pub continue_on_match: bool,
     pub filter: Box<dyn operator::Operator>,
 }
 
 pub enum ProcessingNode {
-    Filter { name: String, filter: MatcherFilter, nodes: Vec<ProcessingNode> },
-    Iterator { name: String, target: AccessorExpression, nodes: Vec<ProcessingNode> },
+    Filter { name: String, filter: MatcherFilter, nodes: Vec<Arc<ProcessingNode>> },
+    Iterator { name: String, target: AccessorExpression, nodes: Vec<Arc<ProcessingNode>> },
     Ruleset { name: String, rules: Vec<MatcherRule> },
 }
 
+impl ProcessingNode {
+    /// Returns whether the sibling nodes that follow this node are processed when it matches.
+    fn continue_on_match(&self) -> bool {
+        match self {
+            ProcessingNode::Filter { filter, .. } => filter.continue_on_match,
+            ProcessingNode::Iterator { .. } | ProcessingNode::Ruleset { .. } => true,
+        }
+    }
+}
+
 /// The Matcher contains the core logic of the Tornado Engine.
 /// It matches incoming Events against the defined Rules.
-/// A Matcher instance is stateless and thread-safe; consequently, a single instance can serve the entire application.
+/// A Matcher instance is thread-safe; consequently, a single instance can serve the entire application.
+/// The only state kept by the Matcher is the set of deduplication keys of the Rules
+///   that have a deduplication configuration and the previous values recorded by the
+///   stateful operators.
 pub struct Matcher {
-    node: ProcessingNode,
+    node: Arc<ProcessingNode>,
+    deduplication_store: Arc<dyn DeduplicationStore>,
+    previous_value_store: Arc<dyn PreviousValueStore>,
+    settings: MatcherSettings,
+}
+
+/// The settings applied when a Matcher is built from a configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatcherSettings {
+    /// The limits applied when the regexes of the Rules are compiled
+    pub regex_limits: RegexLimits,
+    /// Whether the names of the active Rules must be unique in the whole configuration tree
+    ///   instead of only within their ruleset
+    pub globally_unique_rule_names: bool,
+}
+
+impl MatcherSettings {
+    fn validator(&self) -> MatcherConfigValidator {
+        MatcherConfigValidator::new()
+            .with_globally_unique_rule_names(self.globally_unique_rule_names)
+    }
 }
 
-fn build_matcher_rule(rule: &Rule) -> Result<MatcherRule, MatcherError> {
+fn build_matcher_rule(
+    rule: &Rule,
+    operator_builder: &OperatorBuilder,
+) -> Result<MatcherRule, MatcherError> {
     let action_builder = action::ActionResolverBuilder::new();
-    let operator_builder = operator::OperatorBuilder::new();
-    let extractor_builder = MatcherExtractorBuilder::new();
+    let extractor_builder =
+        MatcherExtractorBuilder::new().with_regex_limits(*operator_builder.regex_limits());
 
     debug!("Matcher build - Processing rule: [{}]", &rule.name);
     trace!("Matcher build - Processing rule definition:\n{:?}", rule);
@@ -63,83 +112,313 @@ fn build_matcher_rule(rule: &Rule) -> Result<MatcherRule, MatcherError> {
         do_continue: rule.do_continue,
         operator: operator_builder.build_option(&rule.name, &rule.constraint.where_operator)?,
         extractor: extractor_builder.build(&rule.name, &rule.constraint.with)?,
-        actions: action_builder.build_all(&rule.name, &rule.actions)?,
+        actions: action_builder.build_all_with_operator_builder(
+            &rule.name,
+            &rule.actions,
+            operator_builder,
+        )?,
+        deduplication: rule
+            .deduplication
+            .as_ref()
+            .map(|deduplication| {
+                MatcherDeduplication::build(
+                    &rule.name,
+                    &operator_builder.path_of(&rule.name),
+                    deduplication,
+                )
+            })
+            .transpose()?,
+    })
+}
+
+fn build_matcher_filter(
+    name: &str,
+    filter: &Filter,
+    operator_builder: &OperatorBuilder,
+) -> Result<MatcherFilter, MatcherError> {
+    Ok(MatcherFilter {
+        active: filter.active,
+        mode: filter.mode,
+        continue_on_match: filter.continue_on_match,
+        filter: operator_builder.build_option(name, &filter.filter.clone().into())?,
     })
 }
 
+fn build_iterator_target(
+    name: &str,
+    iterator: &MatcherIterator,
+) -> Result<AccessorExpression, MatcherError> {
+    AccessorBuilder::new().build(name, iterator.target())?.try_as_expression()
+}
+
 pub fn validate_rule(rule: &Rule) -> Result<(), MatcherError> {
-    let _ = build_matcher_rule(rule)?;
+    let _ = build_matcher_rule(rule, &OperatorBuilder::new())?;
     Ok(())
 }
 
 impl Matcher {
     /// Builds a new Matcher and configures it to operate with a set of Rules.
     pub fn build(config: &MatcherConfig) -> Result<Matcher, MatcherError> {
+        Matcher::build_with_settings(config, &MatcherSettings::default())
+    }
+
+    /// Builds a new Matcher like `build` applying the given settings.
+    pub fn build_with_settings(
+        config: &MatcherConfig,
+        settings: &MatcherSettings,
+    ) -> Result<Matcher, MatcherError> {
+        Matcher::build_with_stores(
+            config,
+            Arc::new(InMemoryDeduplicationStore::default()),
+            Arc::new(InMemoryPreviousValueStore::default()),
+            settings,
+        )
+    }
+
+    /// Builds a new Matcher like `build` that keeps the deduplication keys of the Rules
+    ///   in the given store.
+    pub fn build_with_deduplication_store(
+        config: &MatcherConfig,
+        deduplication_store: Arc<dyn DeduplicationStore>,
+    ) -> Result<Matcher, MatcherError> {
+        Matcher::build_with_stores(
+            config,
+            deduplication_store,
+            Arc::new(InMemoryPreviousValueStore::default()),
+            &MatcherSettings::default(),
+        )
+    }
+
+    /// Builds a new Matcher like `build_with_settings` that keeps the deduplication keys of
+    ///   the Rules and the previous values recorded by the stateful operators in the given stores.
+    pub fn build_with_stores(
+        config: &MatcherConfig,
+        deduplication_store: Arc<dyn DeduplicationStore>,
+        previous_value_store: Arc<dyn PreviousValueStore>,
+        settings: &MatcherSettings,
+    ) -> Result<Matcher, MatcherError> {
         info!("Matcher build start");
-        MatcherConfigValidator::new().validate(config)?;
-        Matcher::build_processing_tree(config).map(|node| Matcher { node })
+        settings.validator().validate(config)?;
+        let operator_builder =
+            OperatorBuilder::new_with_previous_value_store(previous_value_store.clone())
+                .with_regex_limits(settings.regex_limits);
+        Matcher::build_processing_tree(config, &operator_builder).map(|node| Matcher {
+            node,
+            deduplication_store,
+            previous_value_store,
+            settings: *settings,
+        })
+    }
+
+    /// Builds a new Matcher for the `config` reusing the processing nodes of this Matcher
+    ///   whose configuration is unchanged compared to the `current_config`, which must be the
+    ///   configuration this Matcher was built from.
+    /// Only the changed subtrees are rebuilt; the result is equivalent to `Matcher::build(config)`.
+    /// The new Matcher shares the deduplication and previous value stores and the settings
+    ///   of this Matcher.
+    pub fn rebuild(
+        &self,
+        current_config: &MatcherConfig,
+        config: &MatcherConfig,
+    ) -> Result<Matcher, MatcherError> {
+        info!("Matcher incremental build start");
+        self.settings.validator().validate(config)?;
+        let operator_builder =
+            OperatorBuilder::new_with_previous_value_store(self.previous_value_store.clone())
+                .with_regex_limits(self.settings.regex_limits);
+        Matcher::rebuild_processing_tree(&self.node, current_config, config, &operator_builder).map(
+            |node| Matcher {
+                node,
+                deduplication_store: self.deduplication_store.clone(),
+                previous_value_store: self.previous_value_store.clone(),
+                settings: self.settings,
+            },
+        )
     }
 
-    fn build_processing_tree(config: &MatcherConfig) -> Result<ProcessingNode, MatcherError> {
+    fn build_processing_tree(
+        config: &MatcherConfig,
+        operator_builder: &OperatorBuilder,
+    ) -> Result<Arc<ProcessingNode>, MatcherError> {
         match config {
             MatcherConfig::Ruleset { name, rules } => {
                 info!("Start processing {} Matcher Config Rules", rules.len());
+                let rules_operator_builder = operator_builder.for_child_node(name);
                 let processed_rules = rules
                     .iter()
                     .filter(|rule| rule.active)
-                    .map(build_matcher_rule)
+                    .map(|rule| build_matcher_rule(rule, &rules_operator_builder))
                     .collect::<Result<_, _>>()?;
 
                 info!("Matcher Rules build completed");
 
-                Ok(ProcessingNode::Ruleset { name: name.to_owned(), rules: processed_rules })
+                Ok(Arc::new(ProcessingNode::Ruleset {
+                    name: name.to_owned(),
+                    rules: processed_rules,
+                }))
             }
             MatcherConfig::Filter { name, filter, nodes } => {
                 debug!("Start processing Matcher Filter [{}] Config", name);
-                let operator_builder = operator::OperatorBuilder::new();
-
-                let matcher_filter = MatcherFilter {
-                    active: filter.active,
-                    filter: operator_builder.build_option(name, &filter.filter.clone().into())?,
-                };
+                let matcher_filter = build_matcher_filter(name, filter, operator_builder)?;
 
+                let children_operator_builder = operator_builder.for_child_node(name);
                 let mut matcher_nodes = vec![];
                 if matcher_filter.active {
                     for node in nodes {
-                        matcher_nodes.push(Matcher::build_processing_tree(node)?);
+                        matcher_nodes.push(Matcher::build_processing_tree(
+                            node,
+                            &children_operator_builder,
+                        )?);
                     }
                 };
 
                 debug!("Matcher Filter [{}] build completed", name);
-                Ok(ProcessingNode::Filter {
+                Ok(Arc::new(ProcessingNode::Filter {
                     name: name.to_owned(),
                     filter: matcher_filter,
                     nodes: matcher_nodes,
-                })
+                }))
             }
             MatcherConfig::Iterator { name, iterator, nodes } => {
-                let builder = AccessorBuilder::new();
-                let exp = builder.build(name, iterator.target())?.try_as_expression()?;
+                let exp = build_iterator_target(name, iterator)?;
+                let children_operator_builder = operator_builder.for_child_node(name);
+                let children = nodes
+                    .iter()
+                    .filter(|_| iterator.is_active())
+                    .map(|node| Matcher::build_processing_tree(node, &children_operator_builder))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(Arc::new(ProcessingNode::Iterator {
+                    name: name.clone(),
+                    target: exp,
+                    nodes: children,
+                }))
+            }
+        }
+    }
+
+    fn rebuild_processing_tree(
+        current_node: &Arc<ProcessingNode>,
+        current_config: &MatcherConfig,
+        config: &MatcherConfig,
+        operator_builder: &OperatorBuilder,
+    ) -> Result<Arc<ProcessingNode>, MatcherError> {
+        if current_config == config {
+            debug!("Matcher build - Reusing unchanged node [{}]", config.get_name());
+            return Ok(current_node.clone());
+        }
+
+        match (current_node.as_ref(), current_config, config) {
+            (
+                ProcessingNode::Filter { nodes: current_nodes, .. },
+                MatcherConfig::Filter { nodes: current_children, .. },
+                MatcherConfig::Filter { name, filter, nodes },
+            ) => {
+                debug!("Start processing changed Matcher Filter [{}] Config", name);
+                let matcher_filter = build_matcher_filter(name, filter, operator_builder)?;
+
+                let children_operator_builder = operator_builder.for_child_node(name);
+                let mut matcher_nodes = vec![];
+                if matcher_filter.active {
+                    for node in nodes {
+                        matcher_nodes.push(Matcher::rebuild_child_node(
+                            current_nodes,
+                            current_children,
+                            node,
+                            &children_operator_builder,
+                        )?);
+                    }
+                };
+
+                Ok(Arc::new(ProcessingNode::Filter {
+                    name: name.to_owned(),
+                    filter: matcher_filter,
+                    nodes: matcher_nodes,
+                }))
+            }
+            (
+                ProcessingNode::Iterator { nodes: current_nodes, .. },
+                MatcherConfig::Iterator { nodes: current_children, .. },
+                MatcherConfig::Iterator { name, iterator, nodes },
+            ) => {
+                let exp = build_iterator_target(name, iterator)?;
+                let children_operator_builder = operator_builder.for_child_node(name);
                 let children = nodes
                     .iter()
                     .filter(|_| iterator.is_active())
-                    .map(Matcher::build_processing_tree)
+                    .map(|node| {
+                        Matcher::rebuild_child_node(
+                            current_nodes,
+                            current_children,
+                            node,
+                            &children_operator_builder,
+                        )
+                    })
                     .collect::<Result<Vec<_>, _>>()?;
 
-                Ok(ProcessingNode::Iterator { name: name.clone(), target: exp, nodes: children })
+                Ok(Arc::new(ProcessingNode::Iterator {
+                    name: name.clone(),
+                    target: exp,
+                    nodes: children,
+                }))
+            }
+            _ => Matcher::build_processing_tree(config, operator_builder),
+        }
+    }
+
+    fn rebuild_child_node(
+        current_nodes: &[Arc<ProcessingNode>],
+        current_children: &[MatcherConfig],
+        config: &MatcherConfig,
+        operator_builder: &OperatorBuilder,
+    ) -> Result<Arc<ProcessingNode>, MatcherError> {
+        // The children of inactive filters and iterators are not built,
+        // so there is nothing to reuse if the number of nodes does not match.
+        if current_nodes.len() == current_children.len() {
+            let current = current_nodes
+                .iter()
+                .zip(current_children)
+                .find(|(_, current_config)| current_config.get_name() == config.get_name());
+            if let Some((current_node, current_config)) = current {
+                return Matcher::rebuild_processing_tree(
+                    current_node,
+                    current_config,
+                    config,
+                    operator_builder,
+                );
             }
         }
+        Matcher::build_processing_tree(config, operator_builder)
     }
 
     /// Processes an incoming Event and compares it against the set of Rules defined at the Matcher's creation time.
     /// The result is a ProcessedEvent.
     pub fn process(&self, event: Value, include_metadata: bool) -> ProcessedEvent {
+        self.process_with_explain(event, include_metadata, false)
+    }
+
+    /// Processes an incoming Event like `process`.
+    /// If `explain` is true, each processed rule contains a detailed report of the evaluation of its
+    /// WHERE operators, including the values resolved by their accessors.
+    pub fn process_with_explain(
+        &self,
+        event: Value,
+        include_metadata: bool,
+        explain: bool,
+    ) -> ProcessedEvent {
         trace!(
-            "Matcher process - processing event: [{:?}], include metadata: [{}]",
+            "Matcher process - processing event: [{:?}], include metadata: [{}], explain: [{}]",
+            &event,
+            include_metadata,
+            explain
+        );
+        let result = Matcher::process_node(
+            &self.node,
             &event,
-            include_metadata
+            include_metadata,
+            explain,
+            self.deduplication_store.as_ref(),
         );
-        let result = Matcher::process_node(&self.node, &event, include_metadata);
         ProcessedEvent { event, result }
     }
 
@@ -147,17 +426,36 @@ impl Matcher {
         node: &ProcessingNode,
         internal_event: &Value,
         include_metadata: bool,
+        explain: bool,
+        deduplication_store: &dyn DeduplicationStore,
     ) -> ProcessedNode {
         match node {
-            ProcessingNode::Filter { name, filter, nodes } => {
-                Matcher::process_filter(name, filter, nodes, internal_event, include_metadata)
-            }
-            ProcessingNode::Ruleset { name, rules } => {
-                Matcher::process_rules(name, rules, internal_event, include_metadata)
-            }
-            ProcessingNode::Iterator { name, target, nodes } => {
-                Matcher::process_iterator(name, target, nodes, internal_event, include_metadata)
-            }
+            ProcessingNode::Filter { name, filter, nodes } => Matcher::process_filter(
+                name,
+                filter,
+                nodes,
+                internal_event,
+                include_metadata,
+                explain,
+                deduplication_store,
+            ),
+            ProcessingNode::Ruleset { name, rules } => Matcher::process_rules(
+                name,
+                rules,
+                internal_event,
+                include_metadata,
+                explain,
+                deduplication_store,
+            ),
+            ProcessingNode::Iterator { name, target, nodes } => Matcher::process_iterator(
+                name,
+                target,
+                nodes,
+                internal_event,
+                include_metadata,
+                explain,
+                deduplication_store,
+            ),
         }
     }
 
@@ -165,25 +463,59 @@ impl Matcher {
     fn process_filter(
         filter_name: &str,
         filter: &MatcherFilter,
-        nodes: &[ProcessingNode],
+        nodes: &[Arc<ProcessingNode>],
         event: &Value,
         include_metadata: bool,
+        explain: bool,
+        deduplication_store: &dyn DeduplicationStore,
     ) -> ProcessedNode {
         trace!("Matcher process - check matching of filter: [{}]", filter_name);
 
         let mut result_nodes = vec![];
+        let mut explanation = None;
 
         let filter_status = if filter.active {
-            let internal_event = InternalEvent { event, extracted_variables: &mut Value::Null };
-            if filter.filter.evaluate(&internal_event) {
+            let internal_event = InternalEvent::new(event, &mut Value::Null);
+            let is_matched = if include_metadata {
+                let filter_explanation = filter.filter.explain(&internal_event);
+                let result = filter_explanation.result;
+                if !result {
+                    explanation = Some(filter_explanation);
+                }
+                result
+            } else {
+                filter.filter.evaluate(&internal_event)
+            };
+            if is_matched {
                 trace!(
                         "Matcher process - event matches filter: [{}]. Passing the Event to the nested nodes.",
                         filter_name
                     );
-                nodes.iter().for_each(|node| {
-                    let processed_node = Matcher::process_node(node, event, include_metadata);
+                for node in nodes {
+                    let processed_node = Matcher::process_node(
+                        node,
+                        event,
+                        include_metadata,
+                        explain,
+                        deduplication_store,
+                    );
+                    let is_matched = processed_node.is_matched();
                     result_nodes.push(processed_node);
-                });
+                    if is_matched && filter.mode == FilterMode::FirstMatch {
+                        trace!(
+                            "Matcher process - filter: [{}] is in FirstMatch mode. Skipping the remaining nested nodes.",
+                            filter_name
+                        );
+                        break;
+                    }
+                    if is_matched && !node.continue_on_match() {
+                        trace!(
+                            "Matcher process - a nested node of filter: [{}] matched and does not continue on match. Skipping the remaining nested nodes.",
+                            filter_name
+                        );
+                        break;
+                    }
+                }
                 ProcessedFilterStatus::Matched
             } else {
                 ProcessedFilterStatus::NotMatched
@@ -194,7 +526,7 @@ impl Matcher {
 
         ProcessedNode::Filter {
             name: filter_name.to_owned(),
-            filter: ProcessedFilter { status: filter_status },
+            filter: ProcessedFilter { status: filter_status, explanation },
             nodes: result_nodes,
         }
     }
@@ -203,12 +535,14 @@ impl Matcher {
     fn process_iterator(
         name: &str,
         target: &AccessorExpression,
-        nodes: &[ProcessingNode],
+        nodes: &[Arc<ProcessingNode>],
         event: &Value,
         include_metadata: bool,
+        explain: bool,
+        deduplication_store: &dyn DeduplicationStore,
     ) -> ProcessedNode {
         trace!("Matcher process - check matching of iterator: [{}]", name);
-        let internal_event = InternalEvent { event, extracted_variables: &mut Default::default() };
+        let internal_event = InternalEvent::new(event, &mut Default::default());
         let Some(target) = target.parse_value(&internal_event) else {
             // ToDo: Improve in NEPROD-1682
             return ProcessedNode::Iterator {
@@ -221,12 +555,28 @@ impl Matcher {
         match target.as_ref() {
             Value::Array(slice) => {
                 let iterator = slice.iter().enumerate();
-                Matcher::iterate_over(name, iterator, event, nodes, include_metadata)
+                Matcher::iterate_over(
+                    name,
+                    iterator,
+                    event,
+                    nodes,
+                    include_metadata,
+                    explain,
+                    deduplication_store,
+                )
             }
             Value::Object(map) => {
                 let iterator =
                     map.keys().flat_map(|key| map.get(key).map(|value| (key.as_str(), value)));
-                Matcher::iterate_over(name, iterator, event, nodes, include_metadata)
+                Matcher::iterate_over(
+                    name,
+                    iterator,
+                    event,
+                    nodes,
+                    include_metadata,
+                    explain,
+                    deduplication_store,
+                )
             }
             _ => {
                 return ProcessedNode::Iterator {
@@ -242,8 +592,10 @@ impl Matcher {
         name: &str,
         iterator: Iter,
         event: &Value,
-        nodes: &[ProcessingNode],
+        nodes: &[Arc<ProcessingNode>],
         include_metadata: bool,
+        explain: bool,
+        deduplication_store: &dyn DeduplicationStore,
     ) -> ProcessedNode
     where
         Key: Into<Value> + Copy,
@@ -267,8 +619,22 @@ impl Matcher {
 
             let mut processed_nodes = vec![];
             for node in nodes {
-                let processed_node = Matcher::process_node(node, &iterator_event, include_metadata);
-                processed_nodes.push(processed_node)
+                let processed_node = Matcher::process_node(
+                    node,
+                    &iterator_event,
+                    include_metadata,
+                    explain,
+                    deduplication_store,
+                );
+                let stop = !node.continue_on_match() && processed_node.is_matched();
+                processed_nodes.push(processed_node);
+                if stop {
+                    trace!(
+                        "Matcher process - a nested node of iterator: [{}] matched and does not continue on match. Skipping the remaining nested nodes.",
+                        name
+                    );
+                    break;
+                }
             }
 
             if include_metadata {
@@ -294,12 +660,15 @@ impl Matcher {
         rules: &[MatcherRule],
         event: &Value,
         include_metadata: bool,
+        explain: bool,
+        deduplication_store: &dyn DeduplicationStore,
     ) -> ProcessedNode {
         trace!("Matcher process - check matching of ruleset: [{}]", ruleset_name);
         let mut extracted_vars = Value::Object(Map::new());
-        let mut internal_event = InternalEvent { event, extracted_variables: &mut extracted_vars };
+        let mut internal_event = InternalEvent::new(event, &mut extracted_vars);
 
         let mut processed_rules = vec![];
+        let mut matched_count = 0;
 
         for rule in rules {
             let _rule_span = tracing::debug_span!(
@@ -316,13 +685,25 @@ impl Matcher {
                 actions: vec![],
                 message: None,
                 meta: None,
+                explanation: None,
             };
 
             if include_metadata {
                 processed_rule.meta = Some(ProcessedRuleMetaData { actions: vec![] })
             }
 
-            if rule.operator.evaluate(&internal_event) {
+            internal_event.set_matched_count(matched_count);
+
+            let is_matched = if explain {
+                let explanation = rule.operator.explain(&internal_event);
+                let result = explanation.result;
+                processed_rule.explanation = Some(explanation);
+                result
+            } else {
+                rule.operator.evaluate(&internal_event)
+            };
+
+            if is_matched {
                 trace!(
                     "Matcher process - event matches rule: [{}]. Checking extracted variables.",
                     &rule.name
@@ -336,9 +717,18 @@ impl Matcher {
                             &internal_event,
                             &mut processed_rule,
                             &rule.actions,
-                        ) {
+                        )
+                        .and_then(|_| {
+                            Matcher::process_deduplication(
+                                rule,
+                                &internal_event,
+                                &mut processed_rule,
+                                deduplication_store,
+                            )
+                        }) {
                             Ok(_) => {
                                 processed_rule.status = ProcessedRuleStatus::Matched;
+                                matched_count += 1;
                                 if !rule.do_continue {
                                     processed_rules.push(processed_rule);
                                     break;
@@ -372,6 +762,33 @@ impl Matcher {
         result
     }
 
+    /// Removes the resolved actions from the processed rule if the rule has a deduplication
+    ///   configuration and the event is not the first one with its key.
+    fn process_deduplication(
+        rule: &MatcherRule,
+        processed_event: &InternalEvent,
+        processed_rule: &mut ProcessedRule,
+        deduplication_store: &dyn DeduplicationStore,
+    ) -> Result<(), MatcherError> {
+        if let Some(deduplication) = &rule.deduplication {
+            if !deduplication.is_first_occurrence(processed_event, deduplication_store)? {
+                trace!(
+                    "Matcher process - event is a duplicate for rule: [{}]. Suppressing its actions.",
+                    &rule.name
+                );
+                processed_rule.actions.clear();
+                if let Some(metadata) = &mut processed_rule.meta {
+                    metadata.actions.clear();
+                }
+                processed_rule.message = Some(format!(
+                    "Matcher process - The event is a duplicate for the rule [{}]; its actions are suppressed.",
+                    &rule.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn process_actions(
         processed_event: &InternalEvent,
         processed_rule: &mut ProcessedRule,
@@ -384,6 +801,15 @@ impl Matcher {
             )
             .entered();
 
+            if !action.is_triggered_by(processed_event) {
+                trace!(
+                    "Matcher process - the when condition of action [{}] of rule [{}] is not satisfied. The action is skipped.",
+                    action.id,
+                    processed_rule.name
+                );
+                continue;
+            }
+
             if let Some(metadata) = &mut processed_rule.meta {
                 let (action, action_metadata) = action.resolve_with_meta(processed_event)?;
                 processed_rule.actions.push(action);
@@ -401,14 +827,16 @@ mod test {
     use super::*;
     use crate::config::nodes::{Filter, MatcherIterator};
     use crate::config::rule::{
-        ConfigAction, Constraint, Extractor, ExtractorRegex, Operator, Rule,
+        ConfigAction, Constraint, Deduplication, Extractor, ExtractorRegex, Operator, Rule,
     };
     use crate::config::v2::{parse_from_file, FsMatcherConfigManagerV2};
     use crate::config::{Defaultable, MatcherConfigReader};
     use serde_json::json;
     use std::collections::HashMap;
     use std::path::Path;
-    use tornado_common_api::{Action, Event, Payload, ValueExt, ValueGet};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use tornado_common_api::{Action, Event, Payload, ValueExt, ValueGet, WithEventData};
+    use tornado_common_parser::Clock;
 
     #[test]
     fn should_build_the_matcher_with_a_rule_set() {
@@ -427,7 +855,7 @@ mod test {
                 .unwrap();
 
         // Assert
-        match &matcher.node {
+        match matcher.node.as_ref() {
             ProcessingNode::Ruleset { name, rules } => {
                 assert_eq!(name, "ruleset");
                 assert_eq!(1, rules.len());
@@ -454,7 +882,7 @@ mod test {
         .unwrap();
 
         // Assert
-        match &matcher.node {
+        match matcher.node.as_ref() {
             ProcessingNode::Filter { name, filter: _filter, nodes } => {
                 assert_eq!(0, nodes.len());
                 assert_eq!("filter", name);
@@ -481,7 +909,7 @@ mod test {
         .unwrap();
 
         // Assert
-        match &matcher.node {
+        match matcher.node.as_ref() {
             ProcessingNode::Filter { name, filter: _filter, nodes } => {
                 assert_eq!(0, nodes.len());
                 assert_eq!("filter", name)
@@ -519,17 +947,17 @@ mod test {
         let matcher = new_matcher(&config).unwrap();
 
         // Assert
-        match &matcher.node {
+        match matcher.node.as_ref() {
             ProcessingNode::Filter { name, filter: _filter1, nodes: nodes1 } => {
                 assert_eq!(2, nodes1.len());
                 assert_eq!("filter", name);
 
-                match &nodes1.first().unwrap() {
+                match nodes1[0].as_ref() {
                     ProcessingNode::Filter { name, filter: _filter2, nodes: nodes2 } => {
                         assert_eq!(1, nodes2.len());
                         assert_eq!("node1", name);
 
-                        match &nodes2.first().unwrap() {
+                        match nodes2[0].as_ref() {
                             ProcessingNode::Ruleset { rules: rules2, .. } => {
                                 assert_eq!(1, rules2.len());
                                 assert_eq!("rule2", rules2.first().unwrap().name);
@@ -540,7 +968,7 @@ mod test {
                     _ => unreachable!(),
                 }
 
-                match &nodes1.get(1).unwrap() {
+                match nodes1[1].as_ref() {
                     ProcessingNode::Ruleset { name, rules: rules1 } => {
                         assert_eq!("node2", name);
                         assert_eq!(1, rules1.len());
@@ -610,9 +1038,9 @@ mod test {
             nodes: vec![],
         };
 
-        let matcher = Matcher::build_processing_tree(&config).unwrap();
+        let matcher = Matcher::build_processing_tree(&config, &OperatorBuilder::new()).unwrap();
 
-        match matcher {
+        match matcher.as_ref() {
             ProcessingNode::Iterator { target, .. } => {
                 assert_eq!(2, target.keys.len());
             }
@@ -720,7 +1148,7 @@ mod test {
         .unwrap();
 
         // Assert
-        match &matcher.node {
+        match matcher.node.as_ref() {
             ProcessingNode::Ruleset { name, rules } => {
                 assert_eq!("ruleset", name);
                 assert_eq!(4, rules.len());
@@ -758,7 +1186,7 @@ mod test {
         .unwrap();
 
         // Assert
-        match &matcher.node {
+        match matcher.node.as_ref() {
             ProcessingNode::Ruleset { name, rules } => {
                 assert_eq!("ruleset", name);
                 assert_eq!(2, rules.len());
@@ -843,12 +1271,18 @@ mod test {
                     regex: String::from(r"[ai]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
         );
 
-        let mut action = ConfigAction { id: String::from("action_id"), payload: Map::new() };
+        let mut action = ConfigAction {
+            id: String::from("action_id"),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
 
         action
             .payload
@@ -944,6 +1378,7 @@ mod test {
                     regex: String::from(r"[ai]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -994,12 +1429,18 @@ mod test {
                     regex: String::from(r"[ai]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
         );
 
-        let mut action = ConfigAction { id: String::from("action_id"), payload: Map::new() };
+        let mut action = ConfigAction {
+            id: String::from("action_id"),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
 
         action
             .payload
@@ -1125,6 +1566,105 @@ mod test {
         };
     }
 
+    #[test]
+    fn should_expose_the_count_of_the_previously_matched_rules() {
+        // Arrange
+        let email_op = Operator::Equals {
+            first: Value::String("${event.type}".to_owned()),
+            second: Value::String("email".to_owned()),
+        };
+
+        let rule_1 = new_rule_with_count_action("rule1_email", email_op.clone());
+        let rule_2 = new_rule_with_count_action(
+            "rule2_sms",
+            Operator::Equals {
+                first: Value::String("${event.type}".to_owned()),
+                second: Value::String("sms".to_owned()),
+            },
+        );
+        let rule_3 = new_rule_with_count_action("rule3_email", email_op);
+        let rule_4 = new_rule_with_count_action(
+            "rule4_aggregate",
+            Operator::GreaterEqualThan {
+                first: Value::String("${context.matched_count}".to_owned()),
+                second: json!(2),
+            },
+        );
+
+        let matcher = new_matcher(&MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![rule_1, rule_2, rule_3, rule_4],
+        })
+        .unwrap();
+
+        // Act
+        let result = matcher.process(json!(Event::new("email")), false);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                assert_eq!(4, rules.rules.len());
+
+                assert_eq!(ProcessedRuleStatus::Matched, rules.rules[0].status);
+                assert_eq!(&json!(0), rules.rules[0].actions[0].payload.get("count").unwrap());
+
+                assert_eq!(ProcessedRuleStatus::NotMatched, rules.rules[1].status);
+                assert!(rules.rules[1].actions.is_empty());
+
+                assert_eq!(ProcessedRuleStatus::Matched, rules.rules[2].status);
+                assert_eq!(&json!(1), rules.rules[2].actions[0].payload.get("count").unwrap());
+
+                assert_eq!(ProcessedRuleStatus::Matched, rules.rules[3].status);
+                assert_eq!(&json!(2), rules.rules[3].actions[0].payload.get("count").unwrap());
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn should_not_count_the_partially_matched_rules() {
+        // Arrange
+        let email_op = Operator::Equals {
+            first: Value::String("${event.type}".to_owned()),
+            second: Value::String("email".to_owned()),
+        };
+
+        let rule_1 = new_rule_with_count_action("rule1_email", email_op.clone());
+        let mut rule_2 = new_rule("rule2_partial", email_op);
+        rule_2.actions.push(ConfigAction {
+            id: String::from("action_id"),
+            payload: json!({ "missing": "${event.payload.missing}" }).as_object().cloned().unwrap(),
+            when: None,
+            retry_strategy: None,
+        });
+        let rule_3 = new_rule_with_count_action(
+            "rule3_aggregate",
+            Operator::GreaterEqualThan {
+                first: Value::String("${context.matched_count}".to_owned()),
+                second: json!(2),
+            },
+        );
+
+        let matcher = new_matcher(&MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![rule_1, rule_2, rule_3],
+        })
+        .unwrap();
+
+        // Act
+        let result = matcher.process(json!(Event::new("email")), false);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                assert_eq!(ProcessedRuleStatus::Matched, rules.rules[0].status);
+                assert_eq!(ProcessedRuleStatus::PartiallyMatched, rules.rules[1].status);
+                assert_eq!(ProcessedRuleStatus::NotMatched, rules.rules[2].status);
+            }
+            _ => unreachable!(),
+        };
+    }
+
     #[test]
     fn should_return_matching_rules_and_extracted_variables() {
         // Arrange
@@ -1144,6 +1684,7 @@ mod test {
                     regex: String::from(r"[ai]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -1180,6 +1721,66 @@ mod test {
         };
     }
 
+    #[test]
+    fn should_use_a_variable_extracted_from_the_event_metadata_in_the_action_payload() {
+        // Arrange
+        let mut rule = new_rule("rule", None);
+
+        rule.constraint.with.insert(
+            String::from("tenant"),
+            Extractor {
+                from: String::from("${event.metadata.tenant_id}"),
+                regex: ExtractorRegex::Regex {
+                    regex: String::from(r".*"),
+                    group_match_idx: Some(0),
+                    all_matches: None,
+                    empty_if_no_match: None,
+                },
+                modifiers_post: vec![],
+            },
+        );
+
+        let mut action = ConfigAction {
+            id: String::from("action_id"),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
+        action
+            .payload
+            .insert("tenant".to_owned(), Value::String("${_variables.tenant}".to_owned()));
+        rule.actions.push(action);
+
+        let matcher =
+            new_matcher(&MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![rule] })
+                .unwrap();
+
+        let mut event = json!(Event::new("email"));
+        event.add_to_metadata("tenant_id".to_owned(), json!("alpha")).unwrap();
+
+        // Act
+        let result = matcher.process(event, false);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                let rule_processed = rules.rules.first().unwrap();
+                assert_eq!(ProcessedRuleStatus::Matched, rule_processed.status);
+                assert_eq!(
+                    "alpha",
+                    rules
+                        .extracted_vars
+                        .get_from_map("rule")
+                        .unwrap()
+                        .get_from_map("tenant")
+                        .unwrap()
+                );
+                assert_eq!("alpha", rule_processed.actions[0].payload.get("tenant").unwrap());
+            }
+            _ => unreachable!(),
+        };
+    }
+
     #[test]
     fn should_return_extracted_vars_grouped_by_rule() {
         // Arrange
@@ -1199,6 +1800,7 @@ mod test {
                     regex: String::from(r"[ai]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -1220,6 +1822,7 @@ mod test {
                     regex: String::from(r"[em]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -1287,6 +1890,7 @@ mod test {
                     regex: String::from(r"[z]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -1308,6 +1912,7 @@ mod test {
                     regex: String::from(r"[ai]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -1371,6 +1976,7 @@ mod test {
                     regex: String::from(r"[z]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -1432,6 +2038,7 @@ mod test {
                     regex: String::from(r"[z]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -1611,69 +2218,264 @@ mod test {
     }
 
     #[test]
-    fn should_process_filter_rulesets_recursively() {
+    fn should_process_only_the_first_matching_node_in_first_match_mode() {
         // Arrange
-        let op = Operator::Equals {
-            first: Value::String("${event.type}".to_owned()),
-            second: Value::String("email".to_owned()),
+        let tenant_node = |name: &str, tenant: &str| MatcherConfig::Filter {
+            name: name.to_owned(),
+            filter: new_filter(Operator::Equals {
+                first: Value::String("${event.metadata.tenant_id}".to_owned()),
+                second: Value::String(tenant.to_owned()),
+            }),
+            nodes: vec![MatcherConfig::Ruleset {
+                name: format!("{}_ruleset", name),
+                rules: vec![new_rule(&format!("{}_rule", name), None)],
+            }],
         };
 
-        let filter = new_filter(op.clone());
+        let mut filter = new_filter(None);
+        filter.mode = FilterMode::FirstMatch;
 
         let nodes = vec![
-            MatcherConfig::Filter {
-                name: "node0".to_owned(),
-                filter: new_filter(op.clone()),
-                nodes: vec![MatcherConfig::Ruleset {
-                    name: "node".to_owned(),
-                    rules: vec![new_rule("rule2", None)],
-                }],
-            },
-            MatcherConfig::Filter {
-                name: "node1".to_owned(),
-                filter: new_filter(Operator::Equals {
-                    first: Value::String("${event.type}".to_owned()),
-                    second: Value::String("trap".to_owned()),
-                }),
-                nodes: vec![MatcherConfig::Ruleset {
-                    name: "node".to_owned(),
-                    rules: vec![new_rule("rule3", None)],
-                }],
-            },
-            MatcherConfig::Ruleset {
-                name: "node2".to_owned(),
-                rules: vec![new_rule("rule_a1", None), new_rule("rule_a2", op.clone())],
-            },
-            MatcherConfig::Ruleset {
-                name: "node3".to_owned(),
-                rules: vec![new_rule("rule_b1", None), new_rule("rule_b2", op)],
-            },
+            tenant_node("tenant_alpha", "alpha"),
+            tenant_node("tenant_beta", "beta"),
+            tenant_node("tenant_beta_copy", "beta"),
         ];
 
-        let config = MatcherConfig::Filter { name: "filter1".to_owned(), filter, nodes };
+        let config = MatcherConfig::Filter { name: "root".to_owned(), filter, nodes };
 
         let matcher = new_matcher(&config).unwrap();
 
+        let mut event = Event::new("email");
+        event.metadata.insert("tenant_id".to_owned(), Value::String("beta".to_owned()));
+
         // Act
-        let result = matcher.process(json!(Event::new("email")), false);
+        let result = matcher.process(json!(event), false);
 
+        // Assert
         match result.result {
             ProcessedNode::Filter { name, filter, nodes } => {
-                assert_eq!(name, "filter1");
+                assert_eq!("root", name);
                 assert_eq!(ProcessedFilterStatus::Matched, filter.status);
-                assert_eq!(4, nodes.len());
+                assert_eq!(2, nodes.len());
 
                 match nodes.first().unwrap() {
                     ProcessedNode::Filter { name, filter, nodes } => {
-                        assert_eq!(name, "node0");
+                        assert_eq!("tenant_alpha", name);
+                        assert_eq!(ProcessedFilterStatus::NotMatched, filter.status);
+                        assert!(nodes.is_empty());
+                    }
+                    _ => unreachable!(),
+                };
+
+                match nodes.get(1).unwrap() {
+                    ProcessedNode::Filter { name, filter, nodes } => {
+                        assert_eq!("tenant_beta", name);
                         assert_eq!(ProcessedFilterStatus::Matched, filter.status);
                         assert_eq!(1, nodes.len());
-
-                        match nodes.first().unwrap() {
-                            ProcessedNode::Ruleset { name, rules } => {
-                                assert_eq!(name, "node");
-                                assert_eq!(1, rules.rules.len());
-                                assert_eq!(rules.rules.first().unwrap().name, "rule2");
+                    }
+                    _ => unreachable!(),
+                };
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn should_process_all_matching_nodes_in_all_matches_mode() {
+        // Arrange
+        let op = Operator::Equals {
+            first: Value::String("${event.type}".to_owned()),
+            second: Value::String("email".to_owned()),
+        };
+
+        let filter = new_filter(None);
+        assert_eq!(FilterMode::AllMatches, filter.mode);
+
+        let nodes = vec![
+            MatcherConfig::Filter {
+                name: "node1".to_owned(),
+                filter: new_filter(op.clone()),
+                nodes: vec![],
+            },
+            MatcherConfig::Filter {
+                name: "node2".to_owned(),
+                filter: new_filter(op),
+                nodes: vec![],
+            },
+        ];
+
+        let config = MatcherConfig::Filter { name: "root".to_owned(), filter, nodes };
+
+        let matcher = new_matcher(&config).unwrap();
+
+        // Act
+        let result = matcher.process(json!(Event::new("email")), false);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Filter { nodes, .. } => {
+                assert_eq!(2, nodes.len());
+                assert!(nodes.iter().all(ProcessedNode::is_matched));
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn should_not_process_the_siblings_after_a_matching_node_that_does_not_continue_on_match() {
+        // Arrange
+        let type_node = |name: &str, event_type: &str, continue_on_match: bool| {
+            let mut filter = new_filter(Operator::Equals {
+                first: Value::String("${event.type}".to_owned()),
+                second: Value::String(event_type.to_owned()),
+            });
+            filter.continue_on_match = continue_on_match;
+            MatcherConfig::Filter {
+                name: name.to_owned(),
+                filter,
+                nodes: vec![MatcherConfig::Ruleset {
+                    name: format!("{}_ruleset", name),
+                    rules: vec![new_rule(&format!("{}_rule", name), None)],
+                }],
+            }
+        };
+
+        let nodes = vec![
+            type_node("all_emails", "email", true),
+            type_node("routed_emails", "email", false),
+            type_node("email_fallback", "email", true),
+        ];
+
+        let config =
+            MatcherConfig::Filter { name: "root".to_owned(), filter: new_filter(None), nodes };
+
+        let matcher = new_matcher(&config).unwrap();
+
+        // Act
+        let result = matcher.process(json!(Event::new("email")), false);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Filter { nodes, .. } => {
+                let names = nodes
+                    .iter()
+                    .map(|node| match node {
+                        ProcessedNode::Filter { name, .. } => name.as_str(),
+                        _ => unreachable!(),
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(vec!["all_emails", "routed_emails"], names);
+                assert!(nodes.iter().all(ProcessedNode::is_matched));
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn should_process_the_siblings_after_a_not_matching_node_that_does_not_continue_on_match() {
+        // Arrange
+        let mut routed_filter = new_filter(Operator::Equals {
+            first: Value::String("${event.type}".to_owned()),
+            second: Value::String("sms".to_owned()),
+        });
+        routed_filter.continue_on_match = false;
+
+        let nodes = vec![
+            MatcherConfig::Filter {
+                name: "routed_sms".to_owned(),
+                filter: routed_filter,
+                nodes: vec![],
+            },
+            MatcherConfig::Filter {
+                name: "fallback".to_owned(),
+                filter: new_filter(None),
+                nodes: vec![],
+            },
+        ];
+
+        let config =
+            MatcherConfig::Filter { name: "root".to_owned(), filter: new_filter(None), nodes };
+
+        let matcher = new_matcher(&config).unwrap();
+
+        // Act
+        let result = matcher.process(json!(Event::new("email")), false);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Filter { nodes, .. } => {
+                assert_eq!(2, nodes.len());
+                assert!(!nodes[0].is_matched());
+                assert!(nodes[1].is_matched());
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn should_process_filter_rulesets_recursively() {
+        // Arrange
+        let op = Operator::Equals {
+            first: Value::String("${event.type}".to_owned()),
+            second: Value::String("email".to_owned()),
+        };
+
+        let filter = new_filter(op.clone());
+
+        let nodes = vec![
+            MatcherConfig::Filter {
+                name: "node0".to_owned(),
+                filter: new_filter(op.clone()),
+                nodes: vec![MatcherConfig::Ruleset {
+                    name: "node".to_owned(),
+                    rules: vec![new_rule("rule2", None)],
+                }],
+            },
+            MatcherConfig::Filter {
+                name: "node1".to_owned(),
+                filter: new_filter(Operator::Equals {
+                    first: Value::String("${event.type}".to_owned()),
+                    second: Value::String("trap".to_owned()),
+                }),
+                nodes: vec![MatcherConfig::Ruleset {
+                    name: "node".to_owned(),
+                    rules: vec![new_rule("rule3", None)],
+                }],
+            },
+            MatcherConfig::Ruleset {
+                name: "node2".to_owned(),
+                rules: vec![new_rule("rule_a1", None), new_rule("rule_a2", op.clone())],
+            },
+            MatcherConfig::Ruleset {
+                name: "node3".to_owned(),
+                rules: vec![new_rule("rule_b1", None), new_rule("rule_b2", op)],
+            },
+        ];
+
+        let config = MatcherConfig::Filter { name: "filter1".to_owned(), filter, nodes };
+
+        let matcher = new_matcher(&config).unwrap();
+
+        // Act
+        let result = matcher.process(json!(Event::new("email")), false);
+
+        match result.result {
+            ProcessedNode::Filter { name, filter, nodes } => {
+                assert_eq!(name, "filter1");
+                assert_eq!(ProcessedFilterStatus::Matched, filter.status);
+                assert_eq!(4, nodes.len());
+
+                match nodes.first().unwrap() {
+                    ProcessedNode::Filter { name, filter, nodes } => {
+                        assert_eq!(name, "node0");
+                        assert_eq!(ProcessedFilterStatus::Matched, filter.status);
+                        assert_eq!(1, nodes.len());
+
+                        match nodes.first().unwrap() {
+                            ProcessedNode::Ruleset { name, rules } => {
+                                assert_eq!(name, "node");
+                                assert_eq!(1, rules.rules.len());
+                                assert_eq!(rules.rules.first().unwrap().name, "rule2");
                                 assert_eq!(
                                     ProcessedRuleStatus::Matched,
                                     rules.rules.first().unwrap().status
@@ -1816,6 +2618,83 @@ mod test {
         };
     }
 
+    #[test]
+    fn should_explain_why_a_filter_does_not_match_if_metadata_are_included() {
+        // Arrange
+        let filter = new_filter(Operator::And {
+            operators: vec![
+                Operator::Equals {
+                    first: Value::String("${event.type}".to_owned()),
+                    second: Value::String("email".to_owned()),
+                },
+                Operator::Equals {
+                    first: Value::String("${event.payload.host}".to_owned()),
+                    second: Value::String("host1".to_owned()),
+                },
+            ],
+        });
+        let config = MatcherConfig::Filter { name: "filter".to_owned(), filter, nodes: vec![] };
+        let matcher = new_matcher(&config).unwrap();
+
+        let mut event = Event::new("email");
+        event.payload.insert("host".to_owned(), json!("host2"));
+
+        // Act
+        let result_without_metadata = matcher.process(json!(event.clone()), false);
+        let result_with_metadata = matcher.process(json!(event), true);
+
+        // Assert
+        match result_without_metadata.result {
+            ProcessedNode::Filter { filter, .. } => {
+                assert_eq!(ProcessedFilterStatus::NotMatched, filter.status);
+                assert!(filter.explanation.is_none());
+            }
+            _ => unreachable!(),
+        };
+
+        match result_with_metadata.result {
+            ProcessedNode::Filter { filter, .. } => {
+                assert_eq!(ProcessedFilterStatus::NotMatched, filter.status);
+                let explanation = filter.explanation.expect("should contain the explanation");
+                assert_eq!("and", explanation.operator);
+                assert!(!explanation.result);
+                assert!(explanation.children[0].result);
+
+                let failed_condition = &explanation.children[1];
+                assert_eq!("equals", failed_condition.operator);
+                assert!(!failed_condition.result);
+                assert_eq!(
+                    vec![Some(json!("host2")), Some(json!("host1"))],
+                    failed_condition.values
+                );
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn should_not_explain_a_matching_filter() {
+        // Arrange
+        let filter = new_filter(Operator::Equals {
+            first: Value::String("${event.type}".to_owned()),
+            second: Value::String("email".to_owned()),
+        });
+        let config = MatcherConfig::Filter { name: "filter".to_owned(), filter, nodes: vec![] };
+        let matcher = new_matcher(&config).unwrap();
+
+        // Act
+        let result = matcher.process(json!(Event::new("email")), true);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Filter { filter, .. } => {
+                assert_eq!(ProcessedFilterStatus::Matched, filter.status);
+                assert!(filter.explanation.is_none());
+            }
+            _ => unreachable!(),
+        };
+    }
+
     #[test]
     fn should_process_rulesets_independently() {
         // Arrange
@@ -1920,6 +2799,7 @@ mod test {
                     regex: String::from(r"[a-z]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -1935,6 +2815,7 @@ mod test {
                     regex: String::from(r"[0-9]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -2517,6 +3398,7 @@ mod test {
                     regex: String::from(r"[a-z]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -2538,6 +3420,7 @@ mod test {
                     regex: String::from(r"[0-9]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -2603,12 +3486,18 @@ mod test {
                         regex: String::from(r"[a-z]+"),
                         group_match_idx: Some(0),
                         all_matches: None,
+                        empty_if_no_match: None,
                     },
                     modifiers_post: vec![],
                 },
             );
 
-            let mut action = ConfigAction { id: String::from("action_id"), payload: Map::new() };
+            let mut action = ConfigAction {
+                id: String::from("action_id"),
+                payload: Map::new(),
+                when: None,
+                retry_strategy: None,
+            };
             action
                 .payload
                 .insert("value".to_owned(), Value::String("${_variables.VALUE}".to_owned()));
@@ -2630,7 +3519,12 @@ mod test {
                 },
             );
 
-            let mut action = ConfigAction { id: String::from("action_id"), payload: Map::new() };
+            let mut action = ConfigAction {
+                id: String::from("action_id"),
+                payload: Map::new(),
+                when: None,
+                retry_strategy: None,
+            };
             action.payload.insert(
                 "value".to_owned(),
                 Value::String("${_variables.collision_name.VALUE}".to_owned()),
@@ -2646,7 +3540,12 @@ mod test {
         let rule_3 = {
             let mut rule = new_rule("rule3", None);
 
-            let mut action = ConfigAction { id: String::from("action_id"), payload: Map::new() };
+            let mut action = ConfigAction {
+                id: String::from("action_id"),
+                payload: Map::new(),
+                when: None,
+                retry_strategy: None,
+            };
             action.payload.insert(
                 "value".to_owned(),
                 Value::String("${_variables.collision_name.VALUE}".to_owned()),
@@ -2716,9 +3615,24 @@ mod test {
     fn should_return_processed_rule_metadata() {
         // Arrange
         let mut rule = new_rule("rule_name", None);
-        rule.actions.push(ConfigAction { id: String::from("action_1"), payload: Map::new() });
-        rule.actions.push(ConfigAction { id: String::from("action_2"), payload: Map::new() });
-        rule.actions.push(ConfigAction { id: String::from("action_3"), payload: Map::new() });
+        rule.actions.push(ConfigAction {
+            id: String::from("action_1"),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        });
+        rule.actions.push(ConfigAction {
+            id: String::from("action_2"),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        });
+        rule.actions.push(ConfigAction {
+            id: String::from("action_3"),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        });
 
         let matcher =
             new_matcher(&MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![rule] })
@@ -2757,6 +3671,635 @@ mod test {
         };
     }
 
+    #[test]
+    fn should_produce_only_the_actions_whose_when_condition_is_satisfied() {
+        // Arrange
+        let mut rule = new_rule("rule_name", None);
+        rule.actions.push(ConfigAction {
+            id: String::from("send_email"),
+            payload: Map::new(),
+            when: Some(Operator::GreaterEqualThan {
+                first: Value::String("${event.payload.severity}".to_owned()),
+                second: json!(3),
+            }),
+            retry_strategy: None,
+        });
+        rule.actions.push(ConfigAction {
+            id: String::from("send_sms"),
+            payload: Map::new(),
+            when: Some(Operator::Equals {
+                first: Value::String("${event.payload.severity}".to_owned()),
+                second: json!(5),
+            }),
+            retry_strategy: None,
+        });
+        rule.actions.push(ConfigAction {
+            id: String::from("log"),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        });
+
+        let matcher =
+            new_matcher(&MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![rule] })
+                .expect("should create a matcher");
+
+        let mut event = Event::new("email");
+        event.payload.insert("severity".to_owned(), json!(4));
+
+        // Act
+        let result = matcher.process(json!(event), true);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                let rule_processed = rules.rules.first().expect("should contain rule");
+                assert_eq!(ProcessedRuleStatus::Matched, rule_processed.status);
+                let action_ids = rule_processed
+                    .actions
+                    .iter()
+                    .map(|action| action.id.as_str())
+                    .collect::<Vec<_>>();
+                assert_eq!(vec!["send_email", "log"], action_ids);
+                let metadata = rule_processed.meta.as_ref().unwrap();
+                assert_eq!(2, metadata.actions.len());
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    #[test]
+    fn should_deserialize_an_action_with_a_when_condition() {
+        // Arrange
+        let json = r#"{
+            "id": "send_email",
+            "payload": {},
+            "when": { "type": "ge", "first": "${event.payload.severity}", "second": 3 }
+        }"#;
+
+        // Act
+        let action: ConfigAction = serde_json::from_str(json).unwrap();
+
+        // Assert
+        assert_eq!(
+            Some(Operator::GreaterEqualThan {
+                first: Value::String("${event.payload.severity}".to_owned()),
+                second: json!(3),
+            }),
+            action.when
+        );
+    }
+
+    #[test]
+    fn should_return_the_explanation_of_a_not_matching_rule() {
+        // Arrange
+        let rule = new_rule(
+            "rule_name",
+            Operator::Equals {
+                first: Value::String("${event.type}".to_owned()),
+                second: Value::String("trap".to_owned()),
+            },
+        );
+
+        let matcher =
+            new_matcher(&MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![rule] })
+                .expect("should create a matcher");
+
+        // Act
+        let result_without_explain = matcher.process(json!(Event::new("email")), false);
+        let result_with_explain =
+            matcher.process_with_explain(json!(Event::new("email")), false, true);
+
+        // Assert
+        match result_without_explain.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                let rule_processed = rules.rules.first().expect("should contain rule");
+                assert!(rule_processed.explanation.is_none())
+            }
+            _ => unreachable!(),
+        };
+
+        match result_with_explain.result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                let rule_processed = rules.rules.first().expect("should contain rule");
+                assert_eq!(ProcessedRuleStatus::NotMatched, rule_processed.status);
+
+                let explanation = rule_processed.explanation.as_ref().unwrap();
+                assert_eq!("equals", explanation.operator);
+                assert!(!explanation.result);
+                assert_eq!(
+                    vec![
+                        Some(Value::String("email".to_owned())),
+                        Some(Value::String("trap".to_owned()))
+                    ],
+                    explanation.values
+                );
+                assert!(explanation.children.is_empty());
+            }
+            _ => unreachable!(),
+        };
+    }
+
+    fn new_large_config(changed_rule_type: &str) -> MatcherConfig {
+        let new_ruleset = |ruleset_index: usize| MatcherConfig::Ruleset {
+            name: format!("ruleset_{}", ruleset_index),
+            rules: (0..5)
+                .map(|rule_index| {
+                    let event_type = if ruleset_index == 3 && rule_index == 2 {
+                        changed_rule_type.to_owned()
+                    } else {
+                        format!("type_{}_{}", ruleset_index, rule_index)
+                    };
+                    new_rule(
+                        &format!("rule_{}_{}", ruleset_index, rule_index),
+                        Operator::Equals {
+                            first: Value::String("${event.type}".to_owned()),
+                            second: Value::String(event_type),
+                        },
+                    )
+                })
+                .collect(),
+        };
+
+        MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: new_filter(None),
+            nodes: vec![
+                MatcherConfig::Filter {
+                    name: "filter_a".to_owned(),
+                    filter: new_filter(None),
+                    nodes: (0..3).map(new_ruleset).collect(),
+                },
+                MatcherConfig::Filter {
+                    name: "filter_b".to_owned(),
+                    filter: new_filter(None),
+                    nodes: (3..6).map(new_ruleset).collect(),
+                },
+            ],
+        }
+    }
+
+    fn child_nodes(node: &ProcessingNode) -> &[Arc<ProcessingNode>] {
+        match node {
+            ProcessingNode::Filter { nodes, .. } | ProcessingNode::Iterator { nodes, .. } => nodes,
+            ProcessingNode::Ruleset { .. } => &[],
+        }
+    }
+
+    #[test]
+    fn rebuild_should_reuse_the_unchanged_subtrees() {
+        // Arrange
+        let current_config = new_large_config("type_3_2");
+        let config = new_large_config("email");
+        let current_matcher = new_matcher(&current_config).unwrap();
+
+        // Act
+        let matcher = current_matcher.rebuild(&current_config, &config).unwrap();
+
+        // Assert
+        assert!(!Arc::ptr_eq(&current_matcher.node, &matcher.node));
+
+        let current_children = child_nodes(&current_matcher.node);
+        let children = child_nodes(&matcher.node);
+        assert!(Arc::ptr_eq(&current_children[0], &children[0]));
+        assert!(!Arc::ptr_eq(&current_children[1], &children[1]));
+
+        let current_rulesets = child_nodes(&current_children[1]);
+        let rulesets = child_nodes(&children[1]);
+        assert_eq!(3, rulesets.len());
+        // Only the ruleset containing the changed rule is rebuilt
+        assert!(!Arc::ptr_eq(&current_rulesets[0], &rulesets[0]));
+        assert!(Arc::ptr_eq(&current_rulesets[1], &rulesets[1]));
+        assert!(Arc::ptr_eq(&current_rulesets[2], &rulesets[2]));
+    }
+
+    #[test]
+    fn rebuild_should_return_the_same_result_as_a_full_build() {
+        // Arrange
+        let current_config = new_large_config("type_3_2");
+        let config = new_large_config("email");
+        let current_matcher = new_matcher(&current_config).unwrap();
+        let full_matcher = new_matcher(&config).unwrap();
+        let event = json!(Event::new("email"));
+
+        // Act
+        let matcher = current_matcher.rebuild(&current_config, &config).unwrap();
+
+        // Assert
+        fn collect_rules(result: &ProcessedNode) -> Vec<(String, ProcessedRuleStatus)> {
+            match result {
+                ProcessedNode::Filter { nodes, .. } => {
+                    nodes.iter().flat_map(collect_rules).collect()
+                }
+                ProcessedNode::Iterator { events, .. } => {
+                    events.iter().flat_map(|e| &e.result).flat_map(collect_rules).collect()
+                }
+                ProcessedNode::Ruleset { rules, .. } => {
+                    rules.rules.iter().map(|r| (r.name.clone(), r.status.clone())).collect()
+                }
+            }
+        }
+
+        let rules = collect_rules(&matcher.process(event.clone(), false).result);
+        assert_eq!(collect_rules(&full_matcher.process(event.clone(), false).result), rules);
+        assert_eq!(30, rules.len());
+        assert_eq!(
+            vec!["rule_3_2".to_owned()],
+            rules
+                .into_iter()
+                .filter(|(_, status)| *status == ProcessedRuleStatus::Matched)
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn rebuild_should_build_the_nodes_of_a_reactivated_filter() {
+        // Arrange
+        let mut current_config = new_large_config("email");
+        if let MatcherConfig::Filter { nodes, .. } = &mut current_config {
+            if let MatcherConfig::Filter { filter, .. } = &mut nodes[1] {
+                filter.active = false;
+            }
+        }
+        let config = new_large_config("email");
+        let current_matcher = new_matcher(&current_config).unwrap();
+
+        // Act
+        let matcher = current_matcher.rebuild(&current_config, &config).unwrap();
+
+        // Assert
+        assert!(child_nodes(&child_nodes(&current_matcher.node)[1]).is_empty());
+        assert_eq!(3, child_nodes(&child_nodes(&matcher.node)[1]).len());
+    }
+
+    #[test]
+    fn should_execute_the_actions_only_for_the_first_event_with_the_same_key_within_the_ttl() {
+        // Arrange
+        let now_ms = Arc::new(AtomicU64::new(1_000));
+        let clock_now_ms = now_ms.clone();
+        let deduplication_store =
+            Arc::new(InMemoryDeduplicationStore::new_with_clock(Clock::new(move || {
+                clock_now_ms.load(Ordering::SeqCst)
+            })));
+        let matcher = Matcher::build_with_deduplication_store(
+            &MatcherConfig::Ruleset {
+                name: "ruleset".to_owned(),
+                rules: vec![new_deduplicated_rule("rule1_host_down", 60)],
+            },
+            deduplication_store,
+        )
+        .unwrap();
+
+        // Act
+        let first = matcher.process(new_host_event("host1"), false);
+        let duplicate = matcher.process(new_host_event("host1"), false);
+        let other_key = matcher.process(new_host_event("host2"), false);
+        now_ms.store(61_000, Ordering::SeqCst);
+        let after_ttl = matcher.process(new_host_event("host1"), false);
+
+        // Assert
+        let first = first_processed_rule(&first.result);
+        assert_eq!(ProcessedRuleStatus::Matched, first.status);
+        assert_eq!(1, first.actions.len());
+        assert!(first.message.is_none());
+
+        let duplicate = first_processed_rule(&duplicate.result);
+        assert_eq!(ProcessedRuleStatus::Matched, duplicate.status);
+        assert!(duplicate.actions.is_empty());
+        assert!(duplicate.message.is_some());
+
+        assert_eq!(1, first_processed_rule(&other_key.result).actions.len());
+        assert_eq!(1, first_processed_rule(&after_ttl.result).actions.len());
+    }
+
+    #[test]
+    fn should_keep_the_deduplication_keys_when_the_matcher_is_rebuilt() {
+        // Arrange
+        let config = MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![new_deduplicated_rule("rule1_host_down", 60)],
+        };
+        let mut new_config = config.clone();
+        if let MatcherConfig::Ruleset { rules, .. } = &mut new_config {
+            rules.push(new_rule("rule2", None));
+        }
+        let matcher = new_matcher(&config).unwrap();
+        matcher.process(new_host_event("host1"), false);
+
+        // Act
+        let matcher = matcher.rebuild(&config, &new_config).unwrap();
+        let result = matcher.process(new_host_event("host1"), false);
+
+        // Assert
+        assert!(first_processed_rule(&result.result).actions.is_empty());
+    }
+
+    #[test]
+    fn should_return_status_partially_matched_if_the_deduplication_key_cannot_be_resolved() {
+        // Arrange
+        let matcher = new_matcher(&MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![new_deduplicated_rule("rule1_host_down", 60)],
+        })
+        .unwrap();
+
+        // Act
+        let result = matcher.process(json!(Event::new("host_down")), false);
+
+        // Assert
+        let processed_rule = first_processed_rule(&result.result);
+        assert_eq!(ProcessedRuleStatus::PartiallyMatched, processed_rule.status);
+        assert!(processed_rule.message.is_some());
+    }
+
+    #[test]
+    fn should_keep_the_deduplication_keys_of_the_rulesets_with_the_same_name_separated() {
+        // Arrange
+        let ruleset = MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![new_deduplicated_rule("rule1_host_down", 60)],
+        };
+        let matcher = new_matcher(&MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: new_filter(None),
+            nodes: vec![
+                MatcherConfig::Filter {
+                    name: "filter_a".to_owned(),
+                    filter: new_filter(None),
+                    nodes: vec![ruleset.clone()],
+                },
+                MatcherConfig::Filter {
+                    name: "filter_b".to_owned(),
+                    filter: new_filter(None),
+                    nodes: vec![ruleset],
+                },
+            ],
+        })
+        .unwrap();
+
+        // Act
+        let result = matcher.process(new_host_event("host1"), false);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Filter { nodes, .. } => {
+                assert_eq!(2, nodes.len());
+                for node in &nodes {
+                    match node {
+                        ProcessedNode::Filter { nodes, .. } => {
+                            assert_eq!(1, first_processed_rule(&nodes[0]).actions.len());
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn should_match_the_changed_operator_only_if_the_value_of_the_entity_changed() {
+        // Arrange
+        let matcher = new_matcher(&MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![new_changed_state_rule("rule1_state_changed")],
+        })
+        .unwrap();
+
+        // Act
+        let first = matcher.process(new_host_state_event("host1", "UP"), false);
+        let other_entity = matcher.process(new_host_state_event("host2", "DOWN"), false);
+        let same_value = matcher.process(new_host_state_event("host1", "UP"), false);
+        let changed_value = matcher.process(new_host_state_event("host1", "DOWN"), false);
+
+        // Assert
+        assert_eq!(ProcessedRuleStatus::NotMatched, first_processed_rule(&first.result).status);
+        assert_eq!(
+            ProcessedRuleStatus::NotMatched,
+            first_processed_rule(&other_entity.result).status
+        );
+        assert_eq!(
+            ProcessedRuleStatus::NotMatched,
+            first_processed_rule(&same_value.result).status
+        );
+        assert_eq!(
+            ProcessedRuleStatus::Matched,
+            first_processed_rule(&changed_value.result).status
+        );
+    }
+
+    #[test]
+    fn should_keep_the_previous_values_when_the_matcher_is_rebuilt() {
+        // Arrange
+        let config = MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![new_changed_state_rule("rule1_state_changed")],
+        };
+        let mut new_config = config.clone();
+        if let MatcherConfig::Ruleset { rules, .. } = &mut new_config {
+            rules[0].description = "updated".to_owned();
+        }
+        let matcher = Matcher::build_with_stores(
+            &config,
+            Arc::new(InMemoryDeduplicationStore::default()),
+            Arc::new(InMemoryPreviousValueStore::default()),
+            &MatcherSettings::default(),
+        )
+        .unwrap();
+        matcher.process(new_host_state_event("host1", "UP"), false);
+
+        // Act
+        let matcher = matcher.rebuild(&config, &new_config).unwrap();
+        let result = matcher.process(new_host_state_event("host1", "DOWN"), false);
+
+        // Assert
+        assert_eq!(ProcessedRuleStatus::Matched, first_processed_rule(&result.result).status);
+    }
+
+    #[test]
+    fn should_build_the_regexes_within_the_limits_of_the_settings() {
+        // Arrange
+        let config = MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![new_rule(
+                "rule",
+                Operator::Regex {
+                    regex: "[0-9]+[a-z]+".to_owned(),
+                    target: "${event.type}".to_owned(),
+                },
+            )],
+        };
+        let settings = MatcherSettings {
+            regex_limits: RegexLimits { max_pattern_len: 8, ..RegexLimits::default() },
+            ..MatcherSettings::default()
+        };
+
+        // Act
+        let result = Matcher::build_with_settings(&config, &settings);
+
+        // Assert
+        assert!(matches!(result, Err(MatcherError::OperatorBuildFailError { .. })));
+        assert!(Matcher::build(&config).is_ok());
+    }
+
+    #[test]
+    fn should_keep_the_settings_when_the_matcher_is_rebuilt() {
+        // Arrange
+        let config = MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![new_rule("rule", None)],
+        };
+        let mut new_config = config.clone();
+        if let MatcherConfig::Ruleset { rules, .. } = &mut new_config {
+            rules[0].constraint.with.insert(
+                "value".to_owned(),
+                Extractor {
+                    from: "${event.type}".to_owned(),
+                    regex: ExtractorRegex::Regex {
+                        regex: "[0-9]+[a-z]+".to_owned(),
+                        group_match_idx: Some(0),
+                        all_matches: None,
+                        empty_if_no_match: None,
+                    },
+                    modifiers_post: vec![],
+                },
+            );
+        }
+        let settings = MatcherSettings {
+            regex_limits: RegexLimits { max_pattern_len: 8, ..RegexLimits::default() },
+            ..MatcherSettings::default()
+        };
+        let matcher = Matcher::build_with_settings(&config, &settings).unwrap();
+
+        // Act
+        let result = matcher.rebuild(&config, &new_config);
+
+        // Assert
+        assert!(matches!(result, Err(MatcherError::ExtractorBuildFailError { .. })));
+    }
+
+    #[test]
+    fn should_validate_the_rule_names_as_set_in_the_settings() {
+        // Arrange
+        let config = MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: new_filter(None),
+            nodes: vec![
+                MatcherConfig::Ruleset {
+                    name: "ruleset_a".to_owned(),
+                    rules: vec![new_rule("rule", None)],
+                },
+                MatcherConfig::Ruleset {
+                    name: "ruleset_b".to_owned(),
+                    rules: vec![new_rule("other_rule", None)],
+                },
+            ],
+        };
+        let mut new_config = config.clone();
+        if let MatcherConfig::Filter { nodes, .. } = &mut new_config {
+            if let MatcherConfig::Ruleset { rules, .. } = &mut nodes[1] {
+                rules[0].name = "rule".to_owned();
+            }
+        }
+        let settings =
+            MatcherSettings { globally_unique_rule_names: true, ..MatcherSettings::default() };
+        let matcher = Matcher::build_with_settings(&config, &settings).unwrap();
+
+        // Act
+        let build_result = Matcher::build_with_settings(&new_config, &settings);
+        let rebuild_result = matcher.rebuild(&config, &new_config);
+
+        // Assert
+        assert!(matches!(build_result, Err(MatcherError::NotUniqueRuleNameError { .. })));
+        assert!(matches!(rebuild_result, Err(MatcherError::NotUniqueRuleNameError { .. })));
+        assert!(Matcher::build(&new_config).is_ok());
+    }
+
+    #[test]
+    fn should_keep_the_previous_values_of_each_ruleset_separated() {
+        // Arrange
+        let matcher = new_matcher(&MatcherConfig::Filter {
+            name: "root".to_owned(),
+            filter: new_filter(None),
+            nodes: vec![
+                MatcherConfig::Ruleset {
+                    name: "ruleset_a".to_owned(),
+                    rules: vec![new_changed_state_rule("state_changed")],
+                },
+                MatcherConfig::Ruleset {
+                    name: "ruleset_b".to_owned(),
+                    rules: vec![new_changed_state_rule("state_changed")],
+                },
+            ],
+        })
+        .unwrap();
+        matcher.process(new_host_state_event("host1", "UP"), false);
+
+        // Act
+        let result = matcher.process(new_host_state_event("host1", "DOWN"), false);
+
+        // Assert
+        match result.result {
+            ProcessedNode::Filter { nodes, .. } => {
+                assert_eq!(2, nodes.len());
+                for node in &nodes {
+                    assert_eq!(ProcessedRuleStatus::Matched, first_processed_rule(node).status);
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn new_changed_state_rule(name: &str) -> Rule {
+        new_rule(
+            name,
+            Operator::Changed {
+                entity: Value::String("${event.payload.hostname}".to_owned()),
+                target: Value::String("${event.payload.state}".to_owned()),
+            },
+        )
+    }
+
+    fn new_host_state_event(hostname: &str, state: &str) -> Value {
+        let mut payload = Payload::new();
+        payload.insert("hostname".to_owned(), Value::String(hostname.to_owned()));
+        payload.insert("state".to_owned(), Value::String(state.to_owned()));
+        json!(Event::new_with_payload("host_state", payload))
+    }
+
+    fn new_deduplicated_rule(name: &str, ttl_secs: u64) -> Rule {
+        let mut rule = new_rule(
+            name,
+            Operator::Equals {
+                first: Value::String("${event.type}".to_owned()),
+                second: Value::String("host_down".to_owned()),
+            },
+        );
+        rule.actions.push(ConfigAction {
+            id: String::from("notify"),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        });
+        rule.deduplication =
+            Some(Deduplication { key: "${event.payload.hostname}".into(), ttl_secs });
+        rule
+    }
+
+    fn new_host_event(hostname: &str) -> Value {
+        let mut payload = Payload::new();
+        payload.insert("hostname".to_owned(), Value::String(hostname.to_owned()));
+        json!(Event::new_with_payload("host_down", payload))
+    }
+
+    fn first_processed_rule(result: &ProcessedNode) -> &ProcessedRule {
+        match result {
+            ProcessedNode::Ruleset { rules, .. } => rules.rules.first().unwrap(),
+            _ => unreachable!(),
+        }
+    }
+
     fn new_matcher(config: &MatcherConfig) -> Result<Matcher, MatcherError> {
         //crate::test_root::start_context();
         Matcher::build(config)
@@ -2772,12 +4315,32 @@ mod test {
             actions: vec![],
             description: "".to_owned(),
             constraint,
+            deduplication: None,
         }
     }
 
+    fn new_rule_with_count_action(name: &str, operator: Operator) -> Rule {
+        let mut rule = new_rule(name, operator);
+        let mut action = ConfigAction {
+            id: String::from("action_id"),
+            payload: Map::new(),
+            when: None,
+            retry_strategy: None,
+        };
+        action.payload.insert("count".to_owned(), json!("${context.matched_count}"));
+        rule.actions.push(action);
+        rule
+    }
+
     fn new_filter<O: Into<Option<Operator>>>(filter: O) -> Filter {
         let filter =
             filter.into().map(Defaultable::Value).unwrap_or_else(|| Defaultable::Default {});
-        Filter { active: true, description: "".to_owned(), filter }
+        Filter {
+            active: true,
+            description: "".to_owned(),
+            filter,
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
+        }
     }
 }
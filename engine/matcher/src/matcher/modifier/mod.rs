@@ -2,7 +2,7 @@ use crate::accessor::{Accessor, AccessorBuilder};
 use crate::config::rule::Modifier;
 use crate::error::MatcherError;
 use crate::model::InternalEvent;
-use crate::regex::RegexWrapper;
+use crate::regex::{RegexLimits, RegexWrapper};
 use chrono_tz::Tz;
 use log::*;
 use serde_json::Value;
@@ -14,6 +14,7 @@ pub mod map;
 pub mod number;
 pub mod replace;
 pub mod trim;
+pub mod uppercase;
 
 #[derive(Debug)]
 pub enum ValueModifier {
@@ -23,14 +24,31 @@ pub enum ValueModifier {
     ReplaceAllRegex { find_regex: RegexWrapper, replace: Accessor },
     ToNumber,
     Trim,
+    Uppercase,
     DateAndTime { timezone: Tz },
 }
 
 impl ValueModifier {
+    /// Builds the modifiers compiling their regexes within the default limits.
     pub fn build(
         rule_name: &str,
         accessor_builder: &AccessorBuilder,
         modifiers: &[Modifier],
+    ) -> Result<Vec<ValueModifier>, MatcherError> {
+        ValueModifier::build_with_regex_limits(
+            rule_name,
+            accessor_builder,
+            modifiers,
+            &RegexLimits::default(),
+        )
+    }
+
+    /// Builds the modifiers compiling their regexes within the given limits.
+    pub fn build_with_regex_limits(
+        rule_name: &str,
+        accessor_builder: &AccessorBuilder,
+        modifiers: &[Modifier],
+        regex_limits: &RegexLimits,
     ) -> Result<Vec<ValueModifier>, MatcherError> {
         let mut value_modifiers = vec![];
 
@@ -55,7 +73,7 @@ impl ValueModifier {
                     trace!("Add post modifier to extractor: replace. Is it regex? {}", is_regex);
                     if *is_regex {
                         value_modifiers.push(ValueModifier::ReplaceAllRegex {
-                            find_regex: RegexWrapper::new(find)?,
+                            find_regex: RegexWrapper::new_with_limits(find, regex_limits)?,
                             replace: accessor_builder.build(rule_name, replace)?,
                         });
                     } else {
@@ -73,6 +91,10 @@ impl ValueModifier {
                     trace!("Add post modifier to extractor: trim");
                     value_modifiers.push(ValueModifier::Trim);
                 }
+                Modifier::Uppercase {} => {
+                    trace!("Add post modifier to extractor: uppercase");
+                    value_modifiers.push(ValueModifier::Uppercase);
+                }
                 Modifier::DateAndTime { timezone } => {
                     trace!("Add post modifier to extractor: DateAndTime (timezone: {})", timezone);
                     let Ok(timezone): Result<Tz, _> = timezone.trim().parse() else {
@@ -88,6 +110,18 @@ impl ValueModifier {
         Ok(value_modifiers)
     }
 
+    /// Returns whether a failure of the modifier should leave the value unchanged
+    /// instead of failing the extraction.
+    pub fn is_lenient(&self) -> bool {
+        matches!(
+            self,
+            ValueModifier::Lowercase
+                | ValueModifier::ToNumber
+                | ValueModifier::Trim
+                | ValueModifier::Uppercase
+        )
+    }
+
     pub fn apply(
         &self,
         variable_name: &str,
@@ -107,6 +141,7 @@ impl ValueModifier {
             }
             ValueModifier::ToNumber => number::to_number(variable_name, value),
             ValueModifier::Trim => trim::trim(variable_name, value),
+            ValueModifier::Uppercase => uppercase::uppercase(variable_name, value),
             ValueModifier::DateAndTime { timezone } => {
                 dateandtime::dateandtime(variable_name, value, timezone)
             }
@@ -0,0 +1,63 @@
+use serde_json::Value;
+use tornado_common_api::ValueExt;
+
+use crate::error::MatcherError;
+
+#[inline]
+pub fn uppercase(variable_name: &str, value: &mut Value) -> Result<(), MatcherError> {
+    if let Some(text) = value.get_text() {
+        *value = Value::String(text.to_uppercase());
+        Ok(())
+    } else {
+        Err(MatcherError::ExtractedVariableError {
+            message: "The 'uppercase' modifier can be used only with values of type 'string'"
+                .to_owned(),
+            variable_name: variable_name.to_owned(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tornado_common_api::Map;
+
+    #[test]
+    fn uppercase_modifier_should_uppercase_a_string() {
+        {
+            let mut input = Value::String("".to_owned());
+            uppercase("", &mut input).unwrap();
+            assert_eq!(Value::String("".to_owned()), input);
+        }
+
+        {
+            let mut input = Value::String("NOT TO UPPERCASE".to_owned());
+            uppercase("", &mut input).unwrap();
+            assert_eq!(Value::String("NOT TO UPPERCASE".to_owned()), input);
+        }
+
+        {
+            let mut input = Value::String(" To be UPpercASEd  ".to_owned());
+            uppercase("", &mut input).unwrap();
+            assert_eq!(Value::String(" TO BE UPPERCASED  ".to_owned()), input);
+        }
+    }
+
+    #[test]
+    fn uppercase_modifier_should_fail_if_value_not_a_string() {
+        {
+            let mut input = Value::Array(vec![]);
+            assert!(uppercase("", &mut input).is_err());
+        }
+
+        {
+            let mut input = Value::Object(Map::new());
+            assert!(uppercase("", &mut input).is_err());
+        }
+
+        {
+            let mut input = Value::Bool(true);
+            assert!(uppercase("", &mut input).is_err());
+        }
+    }
+}
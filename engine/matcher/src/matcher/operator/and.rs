@@ -1,7 +1,7 @@
 use crate::config;
 use crate::error::MatcherError;
 use crate::matcher::operator::{Operator, OperatorBuilder};
-use crate::model::InternalEvent;
+use crate::model::{InternalEvent, OperatorExplanation};
 
 const OPERATOR_NAME: &str = "and";
 
@@ -34,6 +34,12 @@ impl Operator for And {
     fn evaluate(&self, event: &InternalEvent) -> bool {
         self.operators.iter().all(|op| op.evaluate(event))
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        let children: Vec<_> = self.operators.iter().map(|op| op.explain(event)).collect();
+        let result = children.iter().all(|child| child.result);
+        OperatorExplanation::new(self.name(), result).with_children(children)
+    }
 }
 
 #[cfg(test)]
@@ -181,6 +187,40 @@ mod test {
         assert!(!operator.evaluate(&(&json!(event), &mut Value::Null).into()));
     }
 
+    #[test]
+    fn should_explain_the_evaluation_of_all_children() {
+        let operator = And::build(
+            "",
+            &[
+                config::rule::Operator::Equals {
+                    first: Value::String("${event.type}".to_owned()),
+                    second: Value::String("test_type".to_owned()),
+                },
+                config::rule::Operator::Equals {
+                    first: Value::String("${event.payload.value}".to_owned()),
+                    second: Value::String("1".to_owned()),
+                },
+            ],
+            &OperatorBuilder::new(),
+        )
+        .unwrap();
+
+        let event = Event::new("test_type");
+
+        let explanation = operator.explain(&(&json!(event), &mut Value::Null).into());
+
+        assert_eq!("and", explanation.operator);
+        assert!(!explanation.result);
+        assert_eq!(2, explanation.children.len());
+        assert!(explanation.children[0].result);
+        assert_eq!(
+            vec![Some(json!("test_type")), Some(json!("test_type"))],
+            explanation.children[0].values
+        );
+        assert!(!explanation.children[1].result);
+        assert_eq!(vec![None, Some(json!("1"))], explanation.children[1].values);
+    }
+
     #[test]
     fn should_evaluate_to_true_if_all_children_match_recursively() {
         let operator = And::build(
@@ -0,0 +1,161 @@
+use crate::accessor::Accessor;
+use crate::error::MatcherError;
+use crate::matcher::operator::{explain_with_accessors, Operator};
+use crate::matcher::previous_value::PreviousValueStore;
+use crate::model::{InternalEvent, OperatorExplanation};
+use log::*;
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
+
+const OPERATOR_NAME: &str = "changed";
+
+/// A matching matcher.operator that checks whether the target value differs from the one
+/// received with the previous Event of the same entity.
+/// Each evaluation records the target value in the store, keyed by the full path of the rule
+/// in the processing tree and by the entity.
+/// The first Event of an entity, and any Event where the entity or the target are missing,
+/// evaluates to false.
+pub struct Changed {
+    rule_path: String,
+    entity: Accessor,
+    target: Accessor,
+    store: Arc<dyn PreviousValueStore>,
+}
+
+impl fmt::Debug for Changed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Changed")
+            .field("rule_path", &self.rule_path)
+            .field("entity", &self.entity)
+            .field("target", &self.target)
+            .finish()
+    }
+}
+
+impl Changed {
+    pub fn build(
+        rule_path: &str,
+        entity: Accessor,
+        target: Accessor,
+        store: Arc<dyn PreviousValueStore>,
+    ) -> Result<Changed, MatcherError> {
+        Ok(Changed { rule_path: rule_path.to_owned(), entity, target, store })
+    }
+}
+
+impl Operator for Changed {
+    fn name(&self) -> &str {
+        OPERATOR_NAME
+    }
+
+    fn evaluate(&self, event: &InternalEvent) -> bool {
+        let (entity, target) = match (self.entity.get(event), self.target.get(event)) {
+            (Some(entity), Some(target)) => (entity, target),
+            (entity, target) => {
+                trace!("Changed - Both the entity and the target must be present, found instead {:#?} and {:#?}, evaluating to false", entity, target);
+                return false;
+            }
+        };
+        let entity = match entity.as_ref() {
+            Value::String(text) => text.to_owned(),
+            other => other.to_string(),
+        };
+        let key = format!("{}::{}", self.rule_path, entity);
+        let target = target.into_owned();
+        match self.store.replace(&key, target.clone()) {
+            Some(previous) => previous != target,
+            None => false,
+        }
+    }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        explain_with_accessors(self, event, &[&self.entity, &self.target])
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::accessor::AccessorBuilder;
+    use crate::matcher::previous_value::InMemoryPreviousValueStore;
+    use serde_json::json;
+
+    fn build_operator(rule_path: &str, store: Arc<dyn PreviousValueStore>) -> Changed {
+        Changed::build(
+            rule_path,
+            AccessorBuilder::new().build("", "${event.payload.host}").unwrap(),
+            AccessorBuilder::new().build("", "${event.payload.state}").unwrap(),
+            store,
+        )
+        .unwrap()
+    }
+
+    fn check_event(host: &str, state: &str) -> Value {
+        json!({
+            "type": "check",
+            "created_ms": 0,
+            "payload": { "host": host, "state": state }
+        })
+    }
+
+    #[test]
+    fn should_return_the_operator_name() {
+        let operator = build_operator("", Arc::new(InMemoryPreviousValueStore::default()));
+        assert_eq!(OPERATOR_NAME, operator.name());
+    }
+
+    #[test]
+    fn should_evaluate_to_false_for_the_first_event_of_an_entity() {
+        // Arrange
+        let operator = build_operator("rule", Arc::new(InMemoryPreviousValueStore::default()));
+
+        // Act
+        let result = operator.evaluate(&(&check_event("host1", "UP"), &mut Value::Null).into());
+
+        // Assert
+        assert!(!result);
+    }
+
+    #[test]
+    fn should_evaluate_to_true_if_the_value_changed() {
+        // Arrange
+        let operator = build_operator("rule", Arc::new(InMemoryPreviousValueStore::default()));
+
+        // Act & Assert
+        assert!(!operator.evaluate(&(&check_event("host1", "UP"), &mut Value::Null).into()));
+        assert!(!operator.evaluate(&(&check_event("host2", "DOWN"), &mut Value::Null).into()));
+        assert!(!operator.evaluate(&(&check_event("host1", "UP"), &mut Value::Null).into()));
+        assert!(operator.evaluate(&(&check_event("host1", "DOWN"), &mut Value::Null).into()));
+        assert!(!operator.evaluate(&(&check_event("host1", "DOWN"), &mut Value::Null).into()));
+    }
+
+    #[test]
+    fn should_keep_the_values_of_each_rule_separated() {
+        // Arrange
+        let store: Arc<dyn PreviousValueStore> = Arc::new(InMemoryPreviousValueStore::default());
+        let first = build_operator("first_rule", store.clone());
+        let second = build_operator("second_rule", store);
+
+        // Act & Assert
+        assert!(!first.evaluate(&(&check_event("host1", "UP"), &mut Value::Null).into()));
+        assert!(!second.evaluate(&(&check_event("host1", "DOWN"), &mut Value::Null).into()));
+        assert!(first.evaluate(&(&check_event("host1", "DOWN"), &mut Value::Null).into()));
+    }
+
+    #[test]
+    fn should_evaluate_to_false_if_the_target_is_missing() {
+        // Arrange
+        let operator = build_operator("rule", Arc::new(InMemoryPreviousValueStore::default()));
+        let event = json!({
+            "type": "check",
+            "created_ms": 0,
+            "payload": { "host": "host1" }
+        });
+
+        // Act & Assert
+        assert!(!operator.evaluate(&(&event, &mut Value::Null).into()));
+        assert!(!operator.evaluate(&(&check_event("host1", "UP"), &mut Value::Null).into()));
+    }
+}
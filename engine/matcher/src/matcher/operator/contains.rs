@@ -1,6 +1,7 @@
+use crate::accessor::Accessor;
 use crate::error::MatcherError;
-use crate::matcher::operator::Operator;
-use crate::{accessor::Accessor, model::InternalEvent};
+use crate::matcher::operator::{explain_with_accessors, Operator};
+use crate::model::{InternalEvent, OperatorExplanation};
 use tornado_common_api::{cow_to_str, Value};
 
 const OPERATOR_NAME: &str = "contains";
@@ -52,6 +53,10 @@ impl Operator for Contains {
             None => false,
         }
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        explain_with_accessors(self, event, &[&self.first, &self.second])
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,7 @@
 use crate::accessor::Accessor;
 use crate::error::MatcherError;
-use crate::matcher::operator::Operator;
-use crate::model::InternalEvent;
+use crate::matcher::operator::{explain_with_accessors, Operator};
+use crate::model::{InternalEvent, OperatorExplanation};
 
 const OPERATOR_NAME: &str = "equals";
 
@@ -28,6 +28,10 @@ impl Operator for Equals {
         let second = self.second_arg.get(event);
         first == second
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        explain_with_accessors(self, event, &[&self.first_arg, &self.second_arg])
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,7 @@
+use crate::accessor::Accessor;
 use crate::error::MatcherError;
-use crate::matcher::operator::Operator;
-use crate::{accessor::Accessor, model::InternalEvent};
+use crate::matcher::operator::{explain_with_accessors, Operator};
+use crate::model::{InternalEvent, OperatorExplanation};
 use std::cmp::Ordering;
 use tornado_common_api::partial_cmp_option_cow_value;
 
@@ -29,6 +30,10 @@ impl Operator for GreaterEqualThan {
         let cmp = partial_cmp_option_cow_value(&self.first.get(event), || self.second.get(event));
         cmp == Some(Ordering::Greater) || cmp == Some(Ordering::Equal)
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        explain_with_accessors(self, event, &[&self.first, &self.second])
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,142 @@
+use crate::accessor::Accessor;
+use crate::config::rule::ValueType;
+use crate::error::MatcherError;
+use crate::matcher::operator::{explain_with_accessors, Operator};
+use crate::model::{InternalEvent, OperatorExplanation};
+use serde_json::Value;
+
+const OPERATOR_NAME: &str = "isType";
+
+/// A matching matcher.operator that checks whether the target value is of the expected JSON type.
+/// A missing target value is treated as null, so it evaluates to true only if the expected
+/// type is `null`.
+#[derive(Debug)]
+pub struct IsType {
+    target: Accessor,
+    expected_type: ValueType,
+}
+
+impl IsType {
+    pub fn build(target: Accessor, expected_type: ValueType) -> Result<IsType, MatcherError> {
+        Ok(IsType { target, expected_type })
+    }
+}
+
+impl Operator for IsType {
+    fn name(&self) -> &str {
+        OPERATOR_NAME
+    }
+
+    fn evaluate(&self, event: &InternalEvent) -> bool {
+        match self.target.get(event) {
+            Some(value) => matches!(
+                (&self.expected_type, value.as_ref()),
+                (ValueType::String, Value::String(_))
+                    | (ValueType::Number, Value::Number(_))
+                    | (ValueType::Bool, Value::Bool(_))
+                    | (ValueType::Array, Value::Array(_))
+                    | (ValueType::Object, Value::Object(_))
+                    | (ValueType::Null, Value::Null)
+            ),
+            None => self.expected_type == ValueType::Null,
+        }
+    }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        explain_with_accessors(self, event, &[&self.target])
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::accessor::AccessorBuilder;
+    use serde_json::json;
+    use tornado_common_api::*;
+
+    fn build(target: &str, expected_type: ValueType) -> IsType {
+        IsType::build(AccessorBuilder::new().build("", target).unwrap(), expected_type).unwrap()
+    }
+
+    fn event_with_value(value: Value) -> Value {
+        let mut event = Event::new("test_type");
+        event.payload.insert("value".to_owned(), value);
+        json!(event)
+    }
+
+    fn evaluate(expected_type: ValueType, event: &Value) -> bool {
+        build("${event.payload.value}", expected_type).evaluate(&(event, &mut Value::Null).into())
+    }
+
+    const ALL_TYPES: [ValueType; 6] = [
+        ValueType::String,
+        ValueType::Number,
+        ValueType::Bool,
+        ValueType::Array,
+        ValueType::Object,
+        ValueType::Null,
+    ];
+
+    fn assert_only_type(expected_type: ValueType, value: Value) {
+        let event = event_with_value(value);
+        for value_type in ALL_TYPES {
+            assert_eq!(
+                value_type == expected_type,
+                evaluate(value_type, &event),
+                "{:?}",
+                value_type
+            );
+        }
+    }
+
+    #[test]
+    fn should_return_the_operator_name() {
+        let operator = build("", ValueType::String);
+        assert_eq!(OPERATOR_NAME, operator.name());
+    }
+
+    #[test]
+    fn should_match_only_the_string_type() {
+        assert_only_type(ValueType::String, json!("text"));
+    }
+
+    #[test]
+    fn should_match_only_the_number_type() {
+        assert_only_type(ValueType::Number, json!(12));
+        assert_only_type(ValueType::Number, json!(-1.5));
+    }
+
+    #[test]
+    fn should_match_only_the_bool_type() {
+        assert_only_type(ValueType::Bool, json!(false));
+    }
+
+    #[test]
+    fn should_match_only_the_array_type() {
+        assert_only_type(ValueType::Array, json!(["one", 2]));
+    }
+
+    #[test]
+    fn should_match_only_the_object_type() {
+        assert_only_type(ValueType::Object, json!({ "key": "value" }));
+    }
+
+    #[test]
+    fn should_match_only_the_null_type() {
+        assert_only_type(ValueType::Null, Value::Null);
+    }
+
+    #[test]
+    fn should_treat_a_missing_value_as_null() {
+        let event = json!(Event::new("test_type"));
+        for value_type in ALL_TYPES {
+            assert_eq!(
+                value_type == ValueType::Null,
+                evaluate(value_type, &event),
+                "{:?}",
+                value_type
+            );
+        }
+    }
+}
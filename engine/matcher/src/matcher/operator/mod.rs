@@ -4,26 +4,34 @@
 //! An *Operator* is linked to the "WHERE" clause of a Rule and determines whether the rule
 //! is matched by an Event.
 
-use crate::accessor::AccessorBuilder;
+use crate::accessor::{Accessor, AccessorBuilder};
 use crate::config::rule;
 use crate::error::MatcherError;
-use crate::model::InternalEvent;
+use crate::matcher::previous_value::{InMemoryPreviousValueStore, PreviousValueStore};
+use crate::model::{InternalEvent, OperatorExplanation};
+use crate::regex::RegexLimits;
 use log::*;
+use std::borrow::Cow;
 use std::fmt;
+use std::sync::Arc;
 
 pub mod and;
+pub mod changed;
 pub mod contains;
 pub mod contains_ignore_case;
 pub mod equals;
 pub mod equals_ignore_case;
 pub mod ge;
 pub mod gt;
+pub mod is_type;
 pub mod le;
 pub mod lt;
 pub mod ne;
 pub mod not;
 pub mod or;
 pub mod regex;
+pub mod registry;
+pub mod similar;
 pub mod true_operator;
 
 /// The Trait for a generic matcher.operator
@@ -33,17 +41,90 @@ pub trait Operator: fmt::Debug + Send + Sync {
 
     /// Executes the current matcher.operator on a target Event and returns whether the Event matches it.
     fn evaluate(&self, event: &InternalEvent) -> bool;
+
+    /// Executes the current matcher.operator on a target Event and returns a detailed report of the evaluation.
+    /// It is slower than `evaluate` and it is meant to be used only to debug the Rules.
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        OperatorExplanation::new(self.name(), self.evaluate(event))
+    }
+}
+
+/// Explains the evaluation of an operator recording the values resolved by its accessors.
+pub(crate) fn explain_with_accessors(
+    operator: &dyn Operator,
+    event: &InternalEvent,
+    accessors: &[&Accessor],
+) -> OperatorExplanation {
+    let values =
+        accessors.iter().map(|accessor| accessor.get(event).map(Cow::into_owned)).collect();
+    OperatorExplanation::new(operator.name(), operator.evaluate(event)).with_values(values)
 }
 
 /// The Operator instance builder
-#[derive(Default)]
 pub struct OperatorBuilder {
     accessor: AccessorBuilder,
+    previous_value_store: Arc<dyn PreviousValueStore>,
+    /// The path in the processing tree of the node that contains the built operators
+    node_path: String,
+    regex_limits: RegexLimits,
+}
+
+impl Default for OperatorBuilder {
+    fn default() -> Self {
+        OperatorBuilder::new()
+    }
 }
 
 impl OperatorBuilder {
     pub fn new() -> OperatorBuilder {
-        OperatorBuilder { accessor: AccessorBuilder::new() }
+        OperatorBuilder::new_with_previous_value_store(Arc::new(
+            InMemoryPreviousValueStore::default(),
+        ))
+    }
+
+    /// Creates a new OperatorBuilder whose stateful operators, like `changed`,
+    ///   keep their state in the given store.
+    pub fn new_with_previous_value_store(
+        previous_value_store: Arc<dyn PreviousValueStore>,
+    ) -> OperatorBuilder {
+        OperatorBuilder {
+            accessor: AccessorBuilder::new(),
+            previous_value_store,
+            node_path: "".to_owned(),
+            regex_limits: RegexLimits::default(),
+        }
+    }
+
+    /// Sets the limits applied when the regexes of the operators are compiled.
+    pub fn with_regex_limits(mut self, regex_limits: RegexLimits) -> OperatorBuilder {
+        self.regex_limits = regex_limits;
+        self
+    }
+
+    /// Returns the limits applied when the regexes of the operators are compiled.
+    pub fn regex_limits(&self) -> &RegexLimits {
+        &self.regex_limits
+    }
+
+    /// Returns a builder for the operators of the children of the node with the given name.
+    /// The stateful operators, like `changed`, keep their state by the full path of their rule,
+    ///   so that the rules with the same name in different rulesets do not share it.
+    pub fn for_child_node(&self, name: &str) -> OperatorBuilder {
+        OperatorBuilder {
+            accessor: AccessorBuilder::new(),
+            previous_value_store: self.previous_value_store.clone(),
+            node_path: self.path_of(name),
+            regex_limits: self.regex_limits,
+        }
+    }
+
+    /// Returns the path in the processing tree of the child with the given name
+    pub fn path_of(&self, name: &str) -> String {
+        if self.node_path.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}/{}", self.node_path, name)
+        }
     }
 
     pub fn build_option(
@@ -69,6 +150,8 @@ impl OperatorBuilder {
     }
 
     /// Returns a specific Operator instance based on the matcher.operator configuration.
+    /// The Operator is built by the factory registered in the operator registry
+    ///   with the name of the operator.
     ///
     /// # Example
     ///
@@ -91,15 +174,35 @@ impl OperatorBuilder {
         rule_name: &str,
         config: &rule::Operator,
     ) -> Result<Box<dyn Operator>, MatcherError> {
-        let result: Result<Box<dyn Operator>, MatcherError> = match config {
+        let factory = registry::get_operator_factory(config.name()).ok_or_else(|| {
+            MatcherError::UnknownOperatorError { operator: config.name().to_owned() }
+        })?;
+        let result = factory(rule_name, config, self);
+
+        trace!(
+            "OperatorBuilder - build: return matcher.operator [{:?}] for input value [{:?}]",
+            &result,
+            config
+        );
+        result
+    }
+
+    /// Builds the built-in Operators. It is the factory of all the built-in operators
+    ///   in the operator registry.
+    pub(crate) fn build_builtin(
+        &self,
+        rule_name: &str,
+        config: &rule::Operator,
+    ) -> Result<Box<dyn Operator>, MatcherError> {
+        match config {
             rule::Operator::And { operators } => {
-                Ok(Box::new(crate::matcher::operator::and::And::build("", operators, self)?))
+                Ok(Box::new(crate::matcher::operator::and::And::build(rule_name, operators, self)?))
             }
             rule::Operator::Or { operators } => {
-                Ok(Box::new(crate::matcher::operator::or::Or::build("", operators, self)?))
+                Ok(Box::new(crate::matcher::operator::or::Or::build(rule_name, operators, self)?))
             }
             rule::Operator::Not { operator } => {
-                Ok(Box::new(crate::matcher::operator::not::Not::build("", operator, self)?))
+                Ok(Box::new(crate::matcher::operator::not::Not::build(rule_name, operator, self)?))
             }
             rule::Operator::Equals { first, second } => {
                 Ok(Box::new(crate::matcher::operator::equals::Equals::build(
@@ -156,19 +259,37 @@ impl OperatorBuilder {
                 )?,
             )),
             rule::Operator::Regex { regex, target } => {
-                Ok(Box::new(crate::matcher::operator::regex::Regex::build(
+                Ok(Box::new(crate::matcher::operator::regex::Regex::build_with_limits(
                     regex,
                     self.accessor.build(rule_name, target)?,
+                    &self.regex_limits,
                 )?))
             }
-        };
-
-        trace!(
-            "OperatorBuilder - build: return matcher.operator [{:?}] for input value [{:?}]",
-            &result,
-            config
-        );
-        result
+            rule::Operator::Similar { first, second, threshold } => {
+                Ok(Box::new(crate::matcher::operator::similar::Similar::build(
+                    self.accessor.build_from_value(rule_name, first)?,
+                    self.accessor.build_from_value(rule_name, second)?,
+                    *threshold,
+                )?))
+            }
+            rule::Operator::Changed { entity, target } => {
+                Ok(Box::new(crate::matcher::operator::changed::Changed::build(
+                    &self.path_of(rule_name),
+                    self.accessor.build_from_value(rule_name, entity)?,
+                    self.accessor.build_from_value(rule_name, target)?,
+                    self.previous_value_store.clone(),
+                )?))
+            }
+            rule::Operator::IsType { target, expected_type } => {
+                Ok(Box::new(crate::matcher::operator::is_type::IsType::build(
+                    self.accessor.build_from_value(rule_name, target)?,
+                    *expected_type,
+                )?))
+            }
+            rule::Operator::Custom { name, .. } => {
+                Err(MatcherError::UnknownOperatorError { operator: name.to_owned() })
+            }
+        }
     }
 }
 
@@ -332,6 +453,46 @@ mod test {
         assert_eq!("not", operator.name());
     }
 
+    #[test]
+    fn build_should_return_the_similar_operator() {
+        let ops = rule::Operator::Similar {
+            first: Value::String("first_arg".to_owned()),
+            second: Value::String("second_arg".to_owned()),
+            threshold: 0.8,
+        };
+
+        let builder = OperatorBuilder::new();
+        let operator = builder.build_option("", &Some(ops)).unwrap();
+
+        assert_eq!("similar", operator.name());
+    }
+
+    #[test]
+    fn build_should_return_the_changed_operator() {
+        let ops = rule::Operator::Changed {
+            entity: Value::String("${event.payload.host}".to_owned()),
+            target: Value::String("${event.payload.state}".to_owned()),
+        };
+
+        let builder = OperatorBuilder::new();
+        let operator = builder.build_option("", &Some(ops)).unwrap();
+
+        assert_eq!("changed", operator.name());
+    }
+
+    #[test]
+    fn build_should_return_the_is_type_operator() {
+        let ops = rule::Operator::IsType {
+            target: Value::String("${event.payload.items}".to_owned()),
+            expected_type: rule::ValueType::Array,
+        };
+
+        let builder = OperatorBuilder::new();
+        let operator = builder.build_option("", &Some(ops)).unwrap();
+
+        assert_eq!("isType", operator.name());
+    }
+
     #[test]
     fn build_should_return_the_true_operator() {
         let builder = OperatorBuilder::new();
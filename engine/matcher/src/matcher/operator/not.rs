@@ -1,7 +1,7 @@
 use crate::config;
 use crate::error::MatcherError;
 use crate::matcher::operator::{Operator, OperatorBuilder};
-use crate::model::InternalEvent;
+use crate::model::{InternalEvent, OperatorExplanation};
 
 const OPERATOR_NAME: &str = "not";
 
@@ -30,6 +30,11 @@ impl Operator for Not {
     fn evaluate(&self, event: &InternalEvent) -> bool {
         !self.operator.evaluate(event)
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        let child = self.operator.explain(event);
+        OperatorExplanation::new(self.name(), !child.result).with_children(vec![child])
+    }
 }
 
 #[cfg(test)]
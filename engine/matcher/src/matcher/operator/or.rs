@@ -1,7 +1,7 @@
 use crate::config;
 use crate::error::MatcherError;
 use crate::matcher::operator::{Operator, OperatorBuilder};
-use crate::model::InternalEvent;
+use crate::model::{InternalEvent, OperatorExplanation};
 
 const OPERATOR_NAME: &str = "or";
 
@@ -34,6 +34,12 @@ impl Operator for Or {
     fn evaluate(&self, event: &InternalEvent) -> bool {
         self.operators.iter().any(|op| op.evaluate(event))
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        let children: Vec<_> = self.operators.iter().map(|op| op.explain(event)).collect();
+        let result = children.iter().any(|child| child.result);
+        OperatorExplanation::new(self.name(), result).with_children(children)
+    }
 }
 
 #[cfg(test)]
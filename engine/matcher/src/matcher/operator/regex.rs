@@ -1,6 +1,8 @@
+use crate::accessor::Accessor;
 use crate::error::MatcherError;
-use crate::matcher::operator::Operator;
-use crate::{accessor::Accessor, model::InternalEvent};
+use crate::matcher::operator::{explain_with_accessors, Operator};
+use crate::model::{InternalEvent, OperatorExplanation};
+use crate::regex::{build_regex, RegexLimits};
 use regex::Regex as RustRegex;
 use tornado_common_api::cow_to_str;
 
@@ -14,11 +16,22 @@ pub struct Regex {
 }
 
 impl Regex {
+    /// Builds the operator compiling the regex within the default limits.
     pub fn build(regex: &str, target: Accessor) -> Result<Regex, MatcherError> {
-        let regex = RustRegex::new(regex).map_err(|e| MatcherError::OperatorBuildFailError {
-            message: format!("Cannot parse regex [{}]", regex),
-            cause: e.to_string(),
-        })?;
+        Regex::build_with_limits(regex, target, &RegexLimits::default())
+    }
+
+    /// Builds the operator compiling the regex within the given limits.
+    pub fn build_with_limits(
+        regex: &str,
+        target: Accessor,
+        limits: &RegexLimits,
+    ) -> Result<Regex, MatcherError> {
+        let regex =
+            build_regex(regex, limits).map_err(|cause| MatcherError::OperatorBuildFailError {
+                message: format!("Cannot parse regex [{}]", regex),
+                cause,
+            })?;
 
         Ok(Regex { target, regex })
     }
@@ -33,6 +46,10 @@ impl Operator for Regex {
         let cow_value = self.target.get(event);
         cow_to_str(&cow_value).map_or(false, |text| self.regex.is_match(text))
     }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        explain_with_accessors(self, event, &[&self.target])
+    }
 }
 
 #[cfg(test)]
@@ -72,6 +89,24 @@ mod test {
         assert!(operator.is_err());
     }
 
+    #[test]
+    fn build_should_fail_if_the_regex_exceeds_the_max_pattern_len() {
+        let regex = "[0-9]".repeat(crate::regex::DEFAULT_MAX_PATTERN_LEN);
+        let operator = Regex::build(&regex, AccessorBuilder::new().build("", "two").unwrap());
+        assert!(matches!(operator, Err(MatcherError::OperatorBuildFailError { .. })));
+    }
+
+    #[test]
+    fn build_should_fail_if_the_regex_exceeds_the_given_limits() {
+        let limits = RegexLimits { max_pattern_len: 4, ..RegexLimits::default() };
+        let operator = Regex::build_with_limits(
+            "[0-9]",
+            AccessorBuilder::new().build("", "two").unwrap(),
+            &limits,
+        );
+        assert!(matches!(operator, Err(MatcherError::OperatorBuildFailError { .. })));
+    }
+
     #[test]
     fn should_evaluate_to_true_if_it_matches_the_regex() {
         let operator =
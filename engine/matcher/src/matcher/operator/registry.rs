@@ -0,0 +1,212 @@
+//! The registry of the operators that can be used in the Rules, keyed by operator name.
+//!
+//! The built-in operators are always registered. Additional operators can be registered
+//! at startup with `register_operator` and used in the Rules with the `custom` type:
+//!
+//! ```json
+//! {
+//!   "type": "custom",
+//!   "name": "myOperator",
+//!   "params": { "target": "${event.type}" }
+//! }
+//! ```
+
+use crate::config::rule;
+use crate::error::MatcherError;
+use crate::matcher::operator::{Operator, OperatorBuilder};
+use lazy_static::lazy_static;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Builds an Operator from the name of the Rule and the operator configuration.
+pub type OperatorFactory = Arc<
+    dyn Fn(&str, &rule::Operator, &OperatorBuilder) -> Result<Box<dyn Operator>, MatcherError>
+        + Send
+        + Sync,
+>;
+
+const BUILTIN_OPERATORS: [&str; 16] = [
+    "AND",
+    "OR",
+    "NOT",
+    "contains",
+    "containsIgnoreCase",
+    "equals",
+    "equalsIgnoreCase",
+    "ge",
+    "gt",
+    "le",
+    "lt",
+    "ne",
+    "regex",
+    "similar",
+    "changed",
+    "isType",
+];
+
+lazy_static! {
+    static ref OPERATOR_REGISTRY: RwLock<HashMap<String, OperatorFactory>> =
+        RwLock::new(builtin_operators());
+}
+
+fn builtin_operators() -> HashMap<String, OperatorFactory> {
+    let builtin_factory: OperatorFactory =
+        Arc::new(|rule_name: &str, config: &rule::Operator, builder: &OperatorBuilder| {
+            builder.build_builtin(rule_name, config)
+        });
+    BUILTIN_OPERATORS.iter().map(|name| (name.to_string(), builtin_factory.clone())).collect()
+}
+
+/// Registers a custom operator with the given name.
+/// The factory receives the name of the Rule, the `params` of the operator configuration
+///   and the OperatorBuilder, that can be used to build nested operators.
+/// Registering an operator with the name of a custom operator already registered replaces it,
+///   while the names of the built-in operators cannot be used.
+pub fn register_operator<F>(name: &str, factory: F) -> Result<(), MatcherError>
+where
+    F: Fn(&str, &Value, &OperatorBuilder) -> Result<Box<dyn Operator>, MatcherError>
+        + Send
+        + Sync
+        + 'static,
+{
+    if BUILTIN_OPERATORS.contains(&name) {
+        return Err(MatcherError::ConfigurationError {
+            message: format!("Cannot register operator [{}]: it is a built-in operator", name),
+        });
+    }
+
+    let operator_name = name.to_owned();
+    let factory: OperatorFactory =
+        Arc::new(move |rule_name: &str, config: &rule::Operator, builder: &OperatorBuilder| {
+            match config {
+                rule::Operator::Custom { params, .. } => factory(rule_name, params, builder),
+                _ => Err(MatcherError::UnknownOperatorError { operator: operator_name.clone() }),
+            }
+        });
+    let mut registry = OPERATOR_REGISTRY.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    registry.insert(name.to_owned(), factory);
+    Ok(())
+}
+
+/// Returns the factory of the operator with the given name, if registered.
+pub fn get_operator_factory(name: &str) -> Option<OperatorFactory> {
+    let registry = OPERATOR_REGISTRY.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    registry.get(name).cloned()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::accessor::{Accessor, AccessorBuilder};
+    use crate::config::rule::Rule;
+    use crate::config::MatcherConfig;
+    use crate::matcher::Matcher;
+    use crate::model::{InternalEvent, ProcessedNode, ProcessedRuleStatus};
+    use serde_json::json;
+    use tornado_common_api::Event;
+
+    #[derive(Debug)]
+    struct StartsWith {
+        target: Accessor,
+        prefix: String,
+    }
+
+    impl Operator for StartsWith {
+        fn name(&self) -> &str {
+            "test_startsWith"
+        }
+
+        fn evaluate(&self, event: &InternalEvent) -> bool {
+            self.target
+                .get(event)
+                .map(|value| value.as_str().map_or(false, |text| text.starts_with(&self.prefix)))
+                .unwrap_or(false)
+        }
+    }
+
+    fn register_starts_with() {
+        register_operator("test_startsWith", |rule_name, params, _builder| {
+            Ok(Box::new(StartsWith {
+                target: AccessorBuilder::new().build_from_value(rule_name, &params["target"])?,
+                prefix: params["prefix"].as_str().unwrap_or_default().to_owned(),
+            }))
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn should_use_a_custom_operator_in_a_rule() {
+        // Arrange
+        register_starts_with();
+        let rule = Rule::from_json(
+            r#"{
+                "name": "custom_rule",
+                "description": "",
+                "continue": true,
+                "active": true,
+                "constraint": {
+                    "WHERE": {
+                        "type": "custom",
+                        "name": "test_startsWith",
+                        "params": { "target": "${event.type}", "prefix": "em" }
+                    },
+                    "WITH": {}
+                },
+                "actions": []
+            }"#,
+        )
+        .unwrap();
+        let matcher = Matcher::build(&MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![rule],
+        })
+        .unwrap();
+
+        // Act
+        let email_result = matcher.process(json!(Event::new("email")), false);
+        let sms_result = matcher.process(json!(Event::new("sms")), false);
+
+        // Assert
+        for (result, expected_status) in [
+            (email_result, ProcessedRuleStatus::Matched),
+            (sms_result, ProcessedRuleStatus::NotMatched),
+        ] {
+            match result.result {
+                ProcessedNode::Ruleset { rules, .. } => {
+                    assert_eq!(expected_status, rules.rules[0].status);
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn should_not_register_an_operator_with_the_name_of_a_builtin_one() {
+        let result = register_operator("equals", |_rule_name, _params, _builder| {
+            Err(MatcherError::UnknownOperatorError { operator: "equals".to_owned() })
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn should_fail_to_build_an_unknown_custom_operator() {
+        let config =
+            rule::Operator::Custom { name: "test_notRegistered".to_owned(), params: Value::Null };
+
+        let result = OperatorBuilder::new().build("rule", &config);
+
+        assert!(matches!(
+            result,
+            Err(MatcherError::UnknownOperatorError { operator }) if operator == "test_notRegistered"
+        ));
+    }
+
+    #[test]
+    fn should_register_all_the_builtin_operators() {
+        for name in BUILTIN_OPERATORS {
+            assert!(get_operator_factory(name).is_some(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::accessor::Accessor;
+use crate::error::MatcherError;
+use crate::matcher::operator::{explain_with_accessors, Operator};
+use crate::model::{InternalEvent, OperatorExplanation};
+use log::*;
+use tornado_common_api::cow_to_str;
+
+const OPERATOR_NAME: &str = "similar";
+
+/// A matching matcher.operator that evaluates whether the similarity of the two strings
+/// passed as arguments is greater than or equal to a threshold.
+/// The similarity is the normalized Levenshtein distance, that goes from 0.0 for completely
+/// different strings to 1.0 for identical strings.
+/// If one or both the arguments are not strings, the operator will evaluate to false
+#[derive(Debug)]
+pub struct Similar {
+    first: Accessor,
+    second: Accessor,
+    threshold: f64,
+}
+
+impl Similar {
+    pub fn build(
+        first: Accessor,
+        second: Accessor,
+        threshold: f64,
+    ) -> Result<Similar, MatcherError> {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(MatcherError::OperatorBuildFailError {
+                message: format!("Invalid threshold [{}] of the similar operator", threshold),
+                cause: "The threshold must be a number between 0 and 1".to_owned(),
+            });
+        }
+        Ok(Similar { first, second, threshold })
+    }
+}
+
+impl Operator for Similar {
+    fn name(&self) -> &str {
+        OPERATOR_NAME
+    }
+
+    fn evaluate(&self, event: &InternalEvent) -> bool {
+        let first = self.first.get(event);
+        let second = self.second.get(event);
+        match (cow_to_str(&first), cow_to_str(&second)) {
+            (Some(first), Some(second)) => similarity(first, second) >= self.threshold,
+            _ => {
+                trace!("Similar - Both the arguments must be of type Value::Text, found instead {:#?} and {:#?}, evaluating to false", first, second);
+                false
+            }
+        }
+    }
+
+    fn explain(&self, event: &InternalEvent) -> OperatorExplanation {
+        explain_with_accessors(self, event, &[&self.first, &self.second])
+    }
+}
+
+/// Returns `1 - levenshtein_distance / max_length`, computed on the chars of the strings.
+fn similarity(first: &str, second: &str) -> f64 {
+    let first: Vec<char> = first.chars().collect();
+    let second: Vec<char> = second.chars().collect();
+    let max_len = first.len().max(second.len());
+    if max_len == 0 {
+        return 1.0;
+    }
+    1.0 - (levenshtein_distance(&first, &second) as f64 / max_len as f64)
+}
+
+fn levenshtein_distance(first: &[char], second: &[char]) -> usize {
+    // Only the previous row of the distance matrix is kept
+    let mut previous_row: Vec<usize> = (0..=second.len()).collect();
+    let mut current_row = vec![0; second.len() + 1];
+
+    for (i, first_char) in first.iter().enumerate() {
+        current_row[0] = i + 1;
+        for (j, second_char) in second.iter().enumerate() {
+            let substitution_cost = if first_char == second_char { 0 } else { 1 };
+            current_row[j + 1] = (previous_row[j] + substitution_cost)
+                .min(previous_row[j + 1] + 1)
+                .min(current_row[j] + 1);
+        }
+        std::mem::swap(&mut previous_row, &mut current_row);
+    }
+
+    previous_row[second.len()]
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::accessor::AccessorBuilder;
+    use serde_json::json;
+    use tornado_common_api::*;
+
+    fn build(first: &str, second: &str, threshold: f64) -> Similar {
+        Similar::build(
+            AccessorBuilder::new().build("", first).unwrap(),
+            AccessorBuilder::new().build("", second).unwrap(),
+            threshold,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn should_return_the_operator_name() {
+        let operator = build("", "", 0.5);
+        assert_eq!(OPERATOR_NAME, operator.name());
+    }
+
+    #[test]
+    fn should_fail_if_the_threshold_is_not_between_zero_and_one() {
+        for threshold in [-0.1, 1.1, f64::NAN] {
+            assert!(Similar::build(
+                AccessorBuilder::new().build("", "one").unwrap(),
+                AccessorBuilder::new().build("", "two").unwrap(),
+                threshold,
+            )
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn should_compute_the_similarity() {
+        assert_eq!(1.0, similarity("Disk full on host1", "Disk full on host1"));
+        assert_eq!(1.0, similarity("", ""));
+        assert_eq!(0.0, similarity("abc", ""));
+        assert_eq!(0.75, similarity("abcd", "abce"));
+        assert_eq!(1.0 - 3.0 / 7.0, similarity("kitten", "sitting"));
+        assert_eq!(0.75, similarity("àèìò", "àèìu"));
+    }
+
+    #[test]
+    fn should_evaluate_to_true_if_strings_are_identical() {
+        let operator = build("${event.payload.subject}", "${event.payload.last_subject}", 1.0);
+
+        let mut event = Event::new("test_type");
+        event.payload.insert("subject".to_owned(), json!("Disk full on host1"));
+        event.payload.insert("last_subject".to_owned(), json!("Disk full on host1"));
+
+        assert!(operator.evaluate(&(&json!(event), &mut Value::Null).into()));
+    }
+
+    #[test]
+    fn should_evaluate_to_true_if_strings_are_similar() {
+        let operator = build("${event.payload.subject}", "${event.payload.last_subject}", 0.8);
+
+        let mut event = Event::new("test_type");
+        event.payload.insert("subject".to_owned(), json!("Disk full on host1"));
+        event.payload.insert("last_subject".to_owned(), json!("Disk full on host2"));
+
+        assert!(operator.evaluate(&(&json!(event), &mut Value::Null).into()));
+    }
+
+    #[test]
+    fn should_evaluate_to_false_if_strings_are_different() {
+        let operator = build("${event.payload.subject}", "${event.payload.last_subject}", 0.8);
+
+        let mut event = Event::new("test_type");
+        event.payload.insert("subject".to_owned(), json!("Disk full on host1"));
+        event.payload.insert("last_subject".to_owned(), json!("Service ping is CRITICAL"));
+
+        assert!(!operator.evaluate(&(&json!(event), &mut Value::Null).into()));
+    }
+
+    #[test]
+    fn should_evaluate_to_true_only_if_the_similarity_reaches_the_threshold() {
+        let event = Event::new("test_type");
+
+        assert!(build("abcd", "abce", 0.75).evaluate(&(&json!(event), &mut Value::Null).into()));
+        assert!(!build("abcd", "abce", 0.76).evaluate(&(&json!(event), &mut Value::Null).into()));
+    }
+
+    #[test]
+    fn should_evaluate_to_false_if_args_are_not_strings() {
+        let operator = build("${event.payload.one}", "${event.payload.two}", 0.0);
+
+        let mut event = Event::new("test_type");
+        event.payload.insert("one".to_owned(), json!(9));
+        event.payload.insert("two".to_owned(), json!(9));
+
+        assert!(!operator.evaluate(&(&json!(event), &mut Value::Null).into()));
+    }
+}
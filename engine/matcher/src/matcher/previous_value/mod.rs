@@ -0,0 +1,104 @@
+//! The previous_value module contains the state required by the operators that compare
+//! the value of a field of an Event with the one received in a previous Event.
+
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// The default maximum number of keys kept by the InMemoryPreviousValueStore
+pub const DEFAULT_MAX_KEYS: usize = 100_000;
+
+/// A store that keeps the last value seen by the Matcher for each key.
+pub trait PreviousValueStore: Send + Sync {
+    /// Records the value for the key and returns the value previously recorded, if any.
+    fn replace(&self, key: &str, value: Value) -> Option<Value>;
+}
+
+/// A PreviousValueStore that keeps the values in memory.
+/// When the maximum number of keys is reached, the oldest key is evicted to make room for a new one.
+/// The values are lost when the application is restarted.
+pub struct InMemoryPreviousValueStore {
+    max_keys: usize,
+    state: Mutex<InMemoryState>,
+}
+
+struct InMemoryState {
+    value_by_key: HashMap<String, Value>,
+    insertion_order: VecDeque<String>,
+}
+
+impl Default for InMemoryPreviousValueStore {
+    fn default() -> Self {
+        InMemoryPreviousValueStore::new(DEFAULT_MAX_KEYS)
+    }
+}
+
+impl InMemoryPreviousValueStore {
+    pub fn new(max_keys: usize) -> Self {
+        InMemoryPreviousValueStore {
+            max_keys: max_keys.max(1),
+            state: Mutex::new(InMemoryState {
+                value_by_key: HashMap::new(),
+                insertion_order: VecDeque::new(),
+            }),
+        }
+    }
+}
+
+impl PreviousValueStore for InMemoryPreviousValueStore {
+    fn replace(&self, key: &str, value: Value) -> Option<Value> {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if let Some(previous) = state.value_by_key.get_mut(key) {
+            return Some(std::mem::replace(previous, value));
+        }
+
+        if state.value_by_key.len() >= self.max_keys {
+            if let Some(oldest_key) = state.insertion_order.pop_front() {
+                state.value_by_key.remove(&oldest_key);
+            }
+        }
+
+        state.insertion_order.push_back(key.to_owned());
+        state.value_by_key.insert(key.to_owned(), value);
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_return_the_previous_value_of_a_key() {
+        // Arrange
+        let store = InMemoryPreviousValueStore::default();
+
+        // Act & Assert
+        assert_eq!(None, store.replace("host1", json!("UP")));
+        assert_eq!(None, store.replace("host2", json!("DOWN")));
+        assert_eq!(Some(json!("UP")), store.replace("host1", json!("DOWN")));
+        assert_eq!(Some(json!("DOWN")), store.replace("host1", json!("DOWN")));
+    }
+
+    #[test]
+    fn should_evict_the_oldest_key_when_full() {
+        // Arrange
+        let store = InMemoryPreviousValueStore::new(2);
+        store.replace("host1", json!(1));
+        store.replace("host2", json!(2));
+
+        // Act
+        store.replace("host3", json!(3));
+
+        // Assert
+        assert_eq!(2, store.state.lock().unwrap().value_by_key.len());
+        assert_eq!(None, store.replace("host1", json!(1)));
+        assert_eq!(Some(json!(3)), store.replace("host3", json!(3)));
+    }
+}
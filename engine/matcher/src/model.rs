@@ -1,24 +1,42 @@
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use tornado_common_api::{Action, ValueGet};
-use tornado_common_parser::{EVENT_KEY, EXTRACTED_VARIABLES_KEY};
+use tornado_common_parser::{CONTEXT_KEY, EVENT_KEY, EXTRACTED_VARIABLES_KEY};
 use typescript_definitions::TypeScriptify;
 
+pub const MATCHED_COUNT_KEY: &str = "matched_count";
+
 pub struct InternalEvent<'o> {
     pub event: &'o Value,
     pub extracted_variables: &'o mut Value,
+    /// The state of the current processing run, exposed to the accessors as `${context}`
+    pub context: Value,
+}
+
+impl<'o> InternalEvent<'o> {
+    pub fn new(event: &'o Value, extracted_variables: &'o mut Value) -> Self {
+        Self { event, extracted_variables, context: Value::Object(Map::new()) }
+    }
+
+    /// Sets the number of rules of the current ruleset matched before the one being processed
+    pub fn set_matched_count(&mut self, matched_count: u64) {
+        self.context[MATCHED_COUNT_KEY] = Value::from(matched_count);
+    }
 }
 
 impl<'o> From<(&'o Value, &'o mut Value)> for InternalEvent<'o> {
     fn from((event, extracted_variables): (&'o Value, &'o mut Value)) -> Self {
-        Self { event, extracted_variables }
+        Self::new(event, extracted_variables)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ProcessedFilter {
     pub status: ProcessedFilterStatus,
+    /// The evaluation of the filter operator that explains why the filter did not match.
+    /// It is present only if the status is NotMatched and the metadata are included.
+    pub explanation: Option<OperatorExplanation>,
 }
 
 // ToDo: Improve in NEPROD-1682 and NEPROD-1658
@@ -34,6 +52,7 @@ impl<'o> ValueGet for InternalEvent<'o> {
         match key {
             EVENT_KEY => Some(self.event),
             EXTRACTED_VARIABLES_KEY => Some(self.extracted_variables),
+            CONTEXT_KEY => Some(&self.context),
             _ => None,
         }
     }
@@ -65,6 +84,23 @@ pub enum ProcessedNode {
     Ruleset { name: String, rules: ProcessedRules },
 }
 
+impl ProcessedNode {
+    /// Returns whether the Event matched this node.
+    /// A Filter matches if its operator matches, a Ruleset if at least one of its rules matches
+    /// and an Iterator if at least one of its iterations matches.
+    pub fn is_matched(&self) -> bool {
+        match self {
+            ProcessedNode::Filter { filter, .. } => filter.status == ProcessedFilterStatus::Matched,
+            ProcessedNode::Iterator { events, .. } => events
+                .iter()
+                .any(|iteration| iteration.result.iter().any(ProcessedNode::is_matched)),
+            ProcessedNode::Ruleset { rules, .. } => {
+                rules.rules.iter().any(|rule| rule.status == ProcessedRuleStatus::Matched)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ProcessedFilterStatus {
     Matched,
@@ -84,6 +120,7 @@ pub struct ProcessedRule {
     pub actions: Vec<Action>,
     pub message: Option<String>,
     pub meta: Option<ProcessedRuleMetaData>,
+    pub explanation: Option<OperatorExplanation>,
 }
 
 impl ProcessedRule {
@@ -94,6 +131,7 @@ impl ProcessedRule {
             actions: vec![],
             message: None,
             meta: None,
+            explanation: None,
         }
     }
 }
@@ -106,6 +144,38 @@ pub enum ProcessedRuleStatus {
     NotProcessed,
 }
 
+/// The detailed outcome of the evaluation of an Operator, used to explain why a Rule
+/// did or did not match an Event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorExplanation {
+    pub operator: String,
+    pub result: bool,
+    /// The values resolved by the operator accessors, `None` if an accessor could not be resolved.
+    pub values: Vec<Option<Value>>,
+    pub children: Vec<OperatorExplanation>,
+}
+
+impl OperatorExplanation {
+    pub fn new(operator: &str, result: bool) -> OperatorExplanation {
+        OperatorExplanation {
+            operator: operator.to_owned(),
+            result,
+            values: vec![],
+            children: vec![],
+        }
+    }
+
+    pub fn with_values(mut self, values: Vec<Option<Value>>) -> OperatorExplanation {
+        self.values = values;
+        self
+    }
+
+    pub fn with_children(mut self, children: Vec<OperatorExplanation>) -> OperatorExplanation {
+        self.children = children;
+        self
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeScriptify)]
 pub struct ProcessedRuleMetaData {
     pub actions: Vec<ActionMetaData>,
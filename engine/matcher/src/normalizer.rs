@@ -0,0 +1,153 @@
+//! Canonicalization of the Events before they are matched against the Rules.
+//!
+//! Values that are semantically equal can have different JSON representations, for example
+//! `1` and `1.0`, or `"host"` and `"host "` as object keys, and they would not be equal
+//! for the operators. The normalization rewrites them into a single canonical form:
+//! - the floating point numbers without a fractional part are converted to integers,
+//!   so `1.0` becomes `1` and `-0.0` becomes `0`;
+//! - the object keys are trimmed and their inner whitespace sequences are replaced
+//!   by a single space, so `" host  name "` becomes `"host name"`.
+//!
+//! The normalization is deterministic: if more keys of the same object have the same
+//! normalized form, the value of the key that is already normalized is kept; otherwise,
+//! the value of the first key in the object order is kept.
+
+use serde_json::{Map, Number, Value};
+
+/// Returns the normalized form of the value.
+pub fn normalize(value: Value) -> Value {
+    match value {
+        Value::Number(number) => Value::Number(normalize_number(number)),
+        Value::Array(array) => Value::Array(array.into_iter().map(normalize).collect()),
+        Value::Object(map) => Value::Object(normalize_map(map)),
+        value => value,
+    }
+}
+
+fn normalize_number(number: Number) -> Number {
+    match number.as_f64() {
+        Some(float) if number.is_f64() && float.fract() == 0.0 => {
+            if float >= i64::MIN as f64 && float < i64::MAX as f64 {
+                Number::from(float as i64)
+            } else if float >= 0.0 && float < u64::MAX as f64 {
+                Number::from(float as u64)
+            } else {
+                number
+            }
+        }
+        _ => number,
+    }
+}
+
+fn normalize_map(map: Map<String, Value>) -> Map<String, Value> {
+    let mut normalized = Map::new();
+    for (key, value) in map {
+        let normalized_key = normalize_key(&key);
+        let value = normalize(value);
+        if normalized_key == key {
+            normalized.insert(normalized_key, value);
+        } else {
+            normalized.entry(normalized_key).or_insert(value);
+        }
+    }
+    normalized
+}
+
+fn normalize_key(key: &str) -> String {
+    key.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::config::rule::Rule;
+    use crate::config::MatcherConfig;
+    use crate::matcher::Matcher;
+    use crate::model::{ProcessedNode, ProcessedRuleStatus};
+    use serde_json::json;
+
+    fn matcher_with_where(where_operator: Value) -> Matcher {
+        let rule: Rule = serde_json::from_value(json!({
+            "name": "rule",
+            "description": "",
+            "continue": true,
+            "active": true,
+            "constraint": {
+                "WHERE": where_operator,
+                "WITH": {}
+            },
+            "actions": []
+        }))
+        .unwrap();
+        Matcher::build(&MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![rule] })
+            .unwrap()
+    }
+
+    fn rule_status(matcher: &Matcher, event: Value) -> ProcessedRuleStatus {
+        match matcher.process(event, false).result {
+            ProcessedNode::Ruleset { rules, .. } => rules.rules[0].status.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn should_convert_the_floats_without_fractional_part_to_integers() {
+        assert_eq!(json!(1), normalize(json!(1.0)));
+        assert_eq!(json!(-12), normalize(json!(-12.0)));
+        assert_eq!(json!(0), normalize(json!(-0.0)));
+        assert_eq!(json!(1.5), normalize(json!(1.5)));
+        assert_eq!(json!(1), normalize(json!(1)));
+        assert_eq!(json!(u64::MAX), normalize(json!(u64::MAX)));
+        assert_eq!(json!(1e300), normalize(json!(1e300)));
+    }
+
+    #[test]
+    fn should_normalize_the_whitespace_of_the_keys() {
+        let value = json!({
+            " host  name ": "localhost",
+            "nested": [{ "key\t": 2.0 }]
+        });
+
+        assert_eq!(json!({ "host name": "localhost", "nested": [{ "key": 2 }] }), normalize(value));
+    }
+
+    #[test]
+    fn should_keep_the_value_of_the_normalized_key_if_keys_collide() {
+        let value = json!({ "key ": "with space", "key": "normalized", " key": "other" });
+
+        assert_eq!(json!({ "key": "normalized" }), normalize(value.clone()));
+        assert_eq!(normalize(value.clone()), normalize(value));
+    }
+
+    #[test]
+    fn should_match_an_integer_with_its_float_form_if_normalized() {
+        // Arrange
+        let matcher = matcher_with_where(json!({
+            "type": "equals",
+            "first": "${event.payload.value}",
+            "second": 1
+        }));
+        let event = json!({ "type": "test", "created_ms": 0, "payload": { "value": 1.0 } });
+
+        // Act & Assert
+        assert_eq!(ProcessedRuleStatus::NotMatched, rule_status(&matcher, event.clone()));
+        assert_eq!(ProcessedRuleStatus::Matched, rule_status(&matcher, normalize(event)));
+    }
+
+    #[test]
+    fn should_resolve_the_keys_with_trailing_spaces_if_normalized() {
+        // Arrange
+        let matcher = matcher_with_where(json!({
+            "type": "equals",
+            "first": "${event.payload.host}",
+            "second": "localhost"
+        }));
+        let event =
+            json!({ "type": "test", "created_ms": 0, "payload": { "host  ": "localhost" } });
+
+        // Act & Assert
+        assert_eq!(ProcessedRuleStatus::NotMatched, rule_status(&matcher, event.clone()));
+        assert_eq!(ProcessedRuleStatus::Matched, rule_status(&matcher, normalize(event)));
+    }
+}
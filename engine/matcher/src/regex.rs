@@ -1,7 +1,67 @@
 use crate::error::MatcherError;
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 
+pub const DEFAULT_MAX_PATTERN_LEN: usize = 64 * 1024;
+pub const DEFAULT_SIZE_LIMIT: usize = 10 * (1 << 20);
+pub const DEFAULT_DFA_SIZE_LIMIT: usize = 2 * (1 << 20);
+
+/// The limits applied when the regexes of the Rules are compiled.
+/// A regex that exceeds them is rejected when the Matcher is built, so that huge patterns
+///   cannot exhaust the memory or the time spent to compile and to match them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegexLimits {
+    /// The max length in bytes of a regex pattern
+    #[serde(default = "default_max_pattern_len")]
+    pub max_pattern_len: usize,
+    /// The max size in bytes of a compiled regex
+    #[serde(default = "default_size_limit")]
+    pub size_limit: usize,
+    /// The max size in bytes of the cache of the lazy DFA used to match a regex
+    #[serde(default = "default_dfa_size_limit")]
+    pub dfa_size_limit: usize,
+}
+
+impl Default for RegexLimits {
+    fn default() -> Self {
+        RegexLimits {
+            max_pattern_len: DEFAULT_MAX_PATTERN_LEN,
+            size_limit: DEFAULT_SIZE_LIMIT,
+            dfa_size_limit: DEFAULT_DFA_SIZE_LIMIT,
+        }
+    }
+}
+
+fn default_max_pattern_len() -> usize {
+    DEFAULT_MAX_PATTERN_LEN
+}
+
+fn default_size_limit() -> usize {
+    DEFAULT_SIZE_LIMIT
+}
+
+fn default_dfa_size_limit() -> usize {
+    DEFAULT_DFA_SIZE_LIMIT
+}
+
+/// Compiles the regex within the given limits.
+/// It returns the cause of the failure if the pattern is not valid or it exceeds the limits.
+pub fn build_regex(regex_string: &str, limits: &RegexLimits) -> Result<Regex, String> {
+    if regex_string.len() > limits.max_pattern_len {
+        return Err(format!(
+            "The regex is {} bytes long, while the max allowed length is {} bytes",
+            regex_string.len(),
+            limits.max_pattern_len
+        ));
+    }
+    RegexBuilder::new(regex_string)
+        .size_limit(limits.size_limit)
+        .dfa_size_limit(limits.dfa_size_limit)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
 /// A struct that allow high level operation on a basic Regex.
 /// For example, this allow a regex to be used in PartialEq checks.
 #[derive(Debug)]
@@ -11,13 +71,23 @@ pub struct RegexWrapper {
 }
 
 impl RegexWrapper {
+    /// Compiles the regex within the default limits.
     pub fn new<S: Into<String>>(regex_string: S) -> Result<Self, MatcherError> {
+        RegexWrapper::new_with_limits(regex_string, &RegexLimits::default())
+    }
+
+    /// Compiles the regex within the given limits.
+    pub fn new_with_limits<S: Into<String>>(
+        regex_string: S,
+        limits: &RegexLimits,
+    ) -> Result<Self, MatcherError> {
         let regex_string = regex_string.into();
-        let regex =
-            Regex::new(&regex_string).map_err(|e| MatcherError::ExtractorBuildFailError {
+        let regex = build_regex(&regex_string, limits).map_err(|cause| {
+            MatcherError::ExtractorBuildFailError {
                 message: format!("Cannot parse regex [{}]", regex_string),
-                cause: e.to_string(),
-            })?;
+                cause,
+            }
+        })?;
         Ok(Self { regex, regex_string })
     }
 
@@ -49,4 +119,61 @@ mod test {
         assert_eq!(RegexWrapper::new(".*").unwrap(), RegexWrapper::new(".*").unwrap());
         assert_ne!(RegexWrapper::new(".*").unwrap(), RegexWrapper::new(".*[1-9]").unwrap());
     }
+
+    #[test]
+    fn should_compile_a_regex_within_the_limits() {
+        let regex = build_regex(r"(?P<host>[a-z]+)-[0-9]{1,4}", &RegexLimits::default()).unwrap();
+        assert!(regex.is_match("server-12"));
+    }
+
+    #[test]
+    fn should_reject_a_regex_longer_than_the_max_pattern_len() {
+        // Arrange
+        let limits = RegexLimits { max_pattern_len: 10, ..RegexLimits::default() };
+
+        // Act
+        let result = build_regex("[0-9]+[a-z]+[0-9]+", &limits);
+
+        // Assert
+        assert!(result.unwrap_err().contains("max allowed length is 10 bytes"));
+        assert!(build_regex("[0-9]+", &limits).is_ok());
+    }
+
+    #[test]
+    fn should_reject_a_regex_whose_compiled_size_exceeds_the_limit() {
+        // Arrange
+        let limits = RegexLimits { size_limit: 10 * 1024, ..RegexLimits::default() };
+
+        // Act
+        let result = build_regex(r"[a-z]{10000}", &limits);
+
+        // Assert
+        assert!(result.is_err());
+        assert!(build_regex(r"[a-z]{10000}", &RegexLimits::default()).is_ok());
+    }
+
+    #[test]
+    fn should_compile_a_regex_within_the_given_limits() {
+        // Arrange
+        let limits = RegexLimits { max_pattern_len: 10, ..RegexLimits::default() };
+
+        // Act
+        let result = RegexWrapper::new_with_limits("[0-9]+[a-z]+[0-9]+", &limits);
+
+        // Assert
+        assert!(matches!(result, Err(MatcherError::ExtractorBuildFailError { .. })));
+        assert!(RegexWrapper::new("[0-9]+[a-z]+[0-9]+").is_ok());
+    }
+
+    #[test]
+    fn should_reject_an_over_large_regex_with_the_default_limits() {
+        // Arrange
+        let regex_string = "a".repeat(DEFAULT_MAX_PATTERN_LEN + 1);
+
+        // Act
+        let result = RegexWrapper::new(regex_string);
+
+        // Assert
+        assert!(matches!(result, Err(MatcherError::ExtractorBuildFailError { .. })));
+    }
 }
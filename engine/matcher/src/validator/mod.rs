@@ -5,12 +5,13 @@ use crate::config::rule::Rule;
 use crate::config::MatcherConfig;
 use crate::error::MatcherError;
 use log::*;
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
 /// A validator for a MatcherConfig
-#[derive(Default)]
 pub struct MatcherConfigValidator {
     id: id::IdValidator,
+    globally_unique_rule_names: bool,
 }
 
 pub enum NodePath<'parent> {
@@ -37,13 +38,35 @@ impl NodePath<'_> {
     }
 }
 
+impl Default for MatcherConfigValidator {
+    fn default() -> Self {
+        MatcherConfigValidator::new()
+    }
+}
+
 impl MatcherConfigValidator {
+    /// Creates a validator that requires the rule names to be unique only within their ruleset.
     pub fn new() -> MatcherConfigValidator {
-        MatcherConfigValidator { id: id::IdValidator::new() }
+        MatcherConfigValidator { id: id::IdValidator::new(), globally_unique_rule_names: false }
+    }
+
+    /// Enables the strict validation mode, in which the names of the active rules must be
+    ///   unique in the whole configuration tree instead of only within their ruleset.
+    pub fn with_globally_unique_rule_names(mut self, enabled: bool) -> MatcherConfigValidator {
+        self.globally_unique_rule_names = enabled;
+        self
     }
 
     pub fn validate(&self, config: &MatcherConfig) -> Result<(), MatcherError> {
-        self.validate_inner(config, &NodePath::Root)
+        self.validate_inner(config, &NodePath::Root)?;
+        if self.globally_unique_rule_names {
+            MatcherConfigValidator::check_globally_unique_rule_names(
+                config,
+                &NodePath::Root,
+                &mut HashMap::new(),
+            )?;
+        }
+        Ok(())
     }
 
     fn validate_inner(
@@ -154,6 +177,38 @@ impl MatcherConfigValidator {
         Ok(())
     }
 
+    /// Checks that each active rule name is used only once in the tree.
+    /// `rule_paths` contains the path of the rules already visited, by rule name.
+    fn check_globally_unique_rule_names<'config>(
+        config: &'config MatcherConfig,
+        parent: &NodePath,
+        rule_paths: &mut HashMap<&'config str, String>,
+    ) -> Result<(), MatcherError> {
+        let name = config.get_name();
+        let node_path = NodePath::Parent { name, parent, is_iterator: false };
+        match config {
+            MatcherConfig::Ruleset { rules, .. } => {
+                for rule in rules.iter().filter(|rule| rule.active) {
+                    let rule_path = format!("{}.{}", node_path, rule.name);
+                    if let Some(other_path) = rule_paths.insert(&rule.name, rule_path.clone()) {
+                        return Err(MatcherError::NotUniqueRuleNameError {
+                            name: rule.name.to_owned(),
+                            paths: vec![other_path, rule_path],
+                        });
+                    }
+                }
+            }
+            MatcherConfig::Filter { nodes, .. } | MatcherConfig::Iterator { nodes, .. } => {
+                for node in nodes {
+                    MatcherConfigValidator::check_globally_unique_rule_names(
+                        node, &node_path, rule_paths,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn check_unique_name(rule_names: &mut Vec<String>, name: &str) -> Result<(), MatcherError> {
         let name_string = name.to_owned();
         debug!(
@@ -171,6 +226,7 @@ impl MatcherConfigValidator {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::config::nodes::FilterMode;
     use crate::config::rule::{ConfigAction, Constraint, Extractor, ExtractorRegex, Operator};
     use crate::config::Defaultable;
     use serde_json::Map;
@@ -340,6 +396,7 @@ mod test {
                     regex: String::from(r"[0-9]+"),
                     group_match_idx: Some(0),
                     all_matches: None,
+                    empty_if_no_match: None,
                 },
                 modifiers_post: vec![],
             },
@@ -365,6 +422,8 @@ mod test {
         rule_1.actions.push(ConfigAction {
             id: "id.with.dot.and.question.mark?".to_owned(),
             payload: Map::new(),
+            when: None,
+            retry_strategy: None,
         });
 
         // Act
@@ -378,8 +437,13 @@ mod test {
     #[test]
     fn build_should_fail_if_wrong_filter_name() {
         // Arrange
-        let filter =
-            Filter { filter: Defaultable::Default {}, active: true, description: "".to_owned() };
+        let filter = Filter {
+            filter: Defaultable::Default {},
+            active: true,
+            description: "".to_owned(),
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
+        };
 
         // Act
         let matcher = MatcherConfigValidator::new().validate_filter(
@@ -396,8 +460,13 @@ mod test {
     #[test]
     fn should_validate_filter_name() {
         // Arrange
-        let filter =
-            Filter { filter: Defaultable::Default {}, active: true, description: "".to_owned() };
+        let filter = Filter {
+            filter: Defaultable::Default {},
+            active: true,
+            description: "".to_owned(),
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
+        };
 
         // Act
         let matcher = MatcherConfigValidator::new().validate_filter(
@@ -414,8 +483,13 @@ mod test {
     #[test]
     fn build_should_fail_if_wrong_node_name() {
         // Arrange
-        let filter =
-            Filter { filter: Defaultable::Default {}, active: true, description: "".to_owned() };
+        let filter = Filter {
+            filter: Defaultable::Default {},
+            active: true,
+            description: "".to_owned(),
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
+        };
 
         let rules = MatcherConfig::Ruleset { name: "wrong.name!".to_owned(), rules: vec![] };
 
@@ -434,8 +508,13 @@ mod test {
     #[test]
     fn should_validate_node_name() {
         // Arrange
-        let filter =
-            Filter { filter: Defaultable::Default {}, active: true, description: "".to_owned() };
+        let filter = Filter {
+            filter: Defaultable::Default {},
+            active: true,
+            description: "".to_owned(),
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
+        };
 
         let rules = MatcherConfig::Ruleset { name: "good_name".to_owned(), rules: vec![] };
 
@@ -454,8 +533,13 @@ mod test {
     #[test]
     fn should_validate_a_config_recursively() {
         // Arrange
-        let filter1 =
-            Filter { filter: Defaultable::Default {}, active: true, description: "".to_owned() };
+        let filter1 = Filter {
+            filter: Defaultable::Default {},
+            active: true,
+            description: "".to_owned(),
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
+        };
 
         let filter2 = filter1.clone();
         let rule_1 = new_rule("rule_name", None);
@@ -486,8 +570,13 @@ mod test {
     #[test]
     fn should_validate_a_config_recursively_and_fail_if_wrong_inner_rule_name() {
         // Arrange
-        let filter1 =
-            Filter { filter: Defaultable::Default {}, active: true, description: "".to_owned() };
+        let filter1 = Filter {
+            filter: Defaultable::Default {},
+            active: true,
+            description: "".to_owned(),
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
+        };
 
         let filter2 = filter1.clone();
         let rule_1 = new_rule("rule.name!", None);
@@ -512,6 +601,60 @@ mod test {
         assert!(matcher.is_err());
     }
 
+    #[test]
+    fn should_accept_the_same_rule_name_in_different_filters_by_default() {
+        // Arrange
+        let config = new_config_with_rule_in_two_filters("rule_name");
+
+        // Act
+        let result = MatcherConfigValidator::new().validate(&config);
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn should_fail_if_the_same_rule_name_is_in_different_filters_in_strict_mode() {
+        // Arrange
+        let config = new_config_with_rule_in_two_filters("rule_name");
+
+        // Act
+        let result =
+            MatcherConfigValidator::new().with_globally_unique_rule_names(true).validate(&config);
+
+        // Assert
+        assert_eq!(
+            Err(MatcherError::NotUniqueRuleNameError {
+                name: "rule_name".to_owned(),
+                paths: vec![
+                    "root.main.filter_a.ruleset.rule_name".to_owned(),
+                    "root.main.filter_b.ruleset.rule_name".to_owned(),
+                ],
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn should_ignore_the_inactive_rules_in_strict_mode() {
+        // Arrange
+        let mut config = new_config_with_rule_in_two_filters("rule_name");
+        if let MatcherConfig::Filter { nodes, .. } = &mut config {
+            if let MatcherConfig::Filter { nodes, .. } = &mut nodes[1] {
+                if let MatcherConfig::Ruleset { rules, .. } = &mut nodes[0] {
+                    rules[0].active = false;
+                }
+            }
+        }
+
+        // Act
+        let result =
+            MatcherConfigValidator::new().with_globally_unique_rule_names(true).validate(&config);
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_node_path_rendering() {
         assert_eq!("root", format!("{}", NodePath::Root));
@@ -524,6 +667,30 @@ mod test {
         );
     }
 
+    fn new_config_with_rule_in_two_filters(rule_name: &str) -> MatcherConfig {
+        let filter = Filter {
+            filter: Defaultable::Default {},
+            active: true,
+            description: "".to_owned(),
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
+        };
+        let new_filter = |name: &str| MatcherConfig::Filter {
+            name: name.to_owned(),
+            filter: filter.clone(),
+            nodes: vec![MatcherConfig::Ruleset {
+                name: "ruleset".to_owned(),
+                rules: vec![new_rule(rule_name, None)],
+            }],
+        };
+
+        MatcherConfig::Filter {
+            name: "main".to_owned(),
+            filter: filter.clone(),
+            nodes: vec![new_filter("filter_a"), new_filter("filter_b")],
+        }
+    }
+
     fn new_rule<O: Into<Option<Operator>>>(name: &str, operator: O) -> Rule {
         let constraint = Constraint { where_operator: operator.into(), with: HashMap::new() };
 
@@ -534,6 +701,7 @@ mod test {
             actions: vec![],
             description: "".to_owned(),
             constraint,
+            deduplication: None,
         }
     }
 }
@@ -0,0 +1,90 @@
+use crate::config::ArchiveCompression;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::Write;
+
+const GZIP_EXTENSION: &str = ".gz";
+
+/// Returns whether the records written to the file at the given path are compressed.
+pub fn is_compressed(compression: ArchiveCompression, path: &str) -> bool {
+    match compression {
+        ArchiveCompression::Auto => path.ends_with(GZIP_EXTENSION),
+        ArchiveCompression::Gzip => true,
+        ArchiveCompression::None => false,
+    }
+}
+
+/// Compresses the records written to a file between two flushes into a single gzip member.
+/// A sequence of gzip members is itself a valid gzip file, so the member of each flush can be
+///   appended to the file after the previous ones and the file can be read with the standard
+///   tools, e.g. `zcat`, that decompress all the members.
+#[derive(Default)]
+pub struct GzipBlock {
+    encoder: Option<GzEncoder<Vec<u8>>>,
+}
+
+impl GzipBlock {
+    /// Adds the record to the current block
+    pub fn write(&mut self, record: &[u8]) -> std::io::Result<()> {
+        self.encoder
+            .get_or_insert_with(|| GzEncoder::new(Vec::new(), Compression::default()))
+            .write_all(record)
+    }
+
+    /// Returns the gzip member containing the records written since the previous call,
+    ///   or None if no records were written.
+    pub fn finish(&mut self) -> std::io::Result<Option<Vec<u8>>> {
+        self.encoder.take().map(GzEncoder::finish).transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use flate2::read::MultiGzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn should_compress_only_the_gz_files_in_auto_mode() {
+        assert!(is_compressed(ArchiveCompression::Auto, "/archive/events.log.gz"));
+        assert!(!is_compressed(ArchiveCompression::Auto, "/archive/events.log"));
+        assert!(!is_compressed(ArchiveCompression::Auto, "/archive/events.gzip"));
+    }
+
+    #[test]
+    fn should_compress_according_to_the_explicit_setting() {
+        assert!(is_compressed(ArchiveCompression::Gzip, "/archive/events.log"));
+        assert!(!is_compressed(ArchiveCompression::None, "/archive/events.log.gz"));
+    }
+
+    #[test]
+    fn should_read_back_the_concatenated_members() {
+        // Arrange
+        let mut gzip_block = GzipBlock::default();
+        let mut file_content = vec![];
+
+        // Act
+        gzip_block.write(b"first\n").unwrap();
+        gzip_block.write(b"second\n").unwrap();
+        file_content.extend(gzip_block.finish().unwrap().unwrap());
+        gzip_block.write(b"third\n").unwrap();
+        file_content.extend(gzip_block.finish().unwrap().unwrap());
+
+        // Assert
+        let mut decompressed = String::new();
+        MultiGzDecoder::new(file_content.as_slice()).read_to_string(&mut decompressed).unwrap();
+        assert_eq!("first\nsecond\nthird\n", decompressed);
+    }
+
+    #[test]
+    fn should_not_return_a_member_if_no_records_were_written() {
+        // Arrange
+        let mut gzip_block = GzipBlock::default();
+        gzip_block.write(b"first\n").unwrap();
+        gzip_block.finish().unwrap();
+
+        // Act & Assert
+        assert!(gzip_block.finish().unwrap().is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use tornado_common_api::dedup_key::DedupKey;
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct ArchiveConfig {
@@ -18,4 +19,76 @@ pub struct ArchiveConfig {
 
     /// The file cache Time-To-Live in seconds
     pub file_cache_ttl_secs: u64,
+
+    /// The interval in milliseconds between two flushes of the buffered data to the files
+    #[serde(default = "default_file_flush_interval_ms")]
+    pub file_flush_interval_ms: u64,
+
+    /// Whether and when the written data is synced to the disk
+    #[serde(default)]
+    pub durability: ArchiveDurabilityConfig,
+
+    /// The paths of the Event fields whose values are masked before being archived,
+    /// e.g. `payload.credentials.password`
+    #[serde(default)]
+    pub redacted_fields: Vec<String>,
+
+    /// The paths of the only Event fields that are archived, e.g. `payload.hostname`.
+    /// The other fields, except the Event type, are dropped.
+    /// If not set, the whole Event is archived.
+    #[serde(default)]
+    pub include_only: Option<Vec<String>>,
+
+    /// If set, an Event that is equal to one of the most recently archived ones is not written
+    /// again to the same file, e.g. when the archive Action is retried after a failure.
+    #[serde(default)]
+    pub deduplication: Option<ArchiveDeduplicationConfig>,
+
+    /// The fraction of the Events that are archived, between 0.0 and 1.0.
+    /// Each Event is archived with this probability, e.g. 0.05 archives about 5% of them.
+    /// If not set, all the Events are archived.
+    #[serde(default)]
+    pub sample_rate: Option<f64>,
+
+    /// Whether the archived records are compressed.
+    /// By default, only the files whose path ends with `.gz` are compressed.
+    #[serde(default)]
+    pub compression: ArchiveCompression,
+}
+
+/// Defines when the records are written to the files as gzip members.
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum ArchiveCompression {
+    /// The records are compressed only if the path of the file ends with `.gz`
+    #[default]
+    Auto,
+    /// The records are always compressed
+    Gzip,
+    /// The records are never compressed
+    None,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ArchiveDeduplicationConfig {
+    /// The number of the most recently archived Events that are checked for duplicates
+    pub window_size: usize,
+    /// The key that identifies the duplicated Events, resolved from the Action payload.
+    /// If not set, or if it cannot be resolved, the Events are identified by their content.
+    #[serde(default)]
+    pub key: Option<DedupKey>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq)]
+pub struct ArchiveDurabilityConfig {
+    /// Whether each write is synced to the disk
+    #[serde(default)]
+    pub fsync_each: bool,
+
+    /// The interval in milliseconds between two syncs of the written data to the disk.
+    /// It is ignored if `fsync_each` is true.
+    pub fsync_interval_ms: Option<u64>,
+}
+
+fn default_file_flush_interval_ms() -> u64 {
+    1000
 }
@@ -0,0 +1,89 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+
+/// Returns the id of an archived Event, computed from the file it is written to
+///   and from its identity, that is, its deduplication key or its archived content.
+pub fn event_id(absolute_path: &str, event_identity: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    absolute_path.hash(&mut hasher);
+    event_identity.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Keeps the ids of the most recent archived Events.
+/// When the window is full, the oldest id is forgotten to make room for the new one.
+pub struct RecentEvents {
+    window_size: usize,
+    ids: HashSet<u64>,
+    order: VecDeque<u64>,
+}
+
+impl RecentEvents {
+    pub fn new(window_size: usize) -> RecentEvents {
+        RecentEvents { window_size, ids: HashSet::new(), order: VecDeque::new() }
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.ids.contains(&id)
+    }
+
+    pub fn insert(&mut self, id: u64) {
+        if self.window_size == 0 || !self.ids.insert(id) {
+            return;
+        }
+        self.order.push_back(id);
+        if self.order.len() > self.window_size {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn should_contain_the_inserted_ids() {
+        // Arrange
+        let mut recent_events = RecentEvents::new(10);
+
+        // Act
+        recent_events.insert(1);
+        recent_events.insert(2);
+
+        // Assert
+        assert!(recent_events.contains(1));
+        assert!(recent_events.contains(2));
+        assert!(!recent_events.contains(3));
+    }
+
+    #[test]
+    fn should_forget_the_oldest_ids_when_the_window_is_full() {
+        // Arrange
+        let mut recent_events = RecentEvents::new(2);
+
+        // Act
+        recent_events.insert(1);
+        recent_events.insert(2);
+        recent_events.insert(2);
+        recent_events.insert(3);
+
+        // Assert
+        assert!(!recent_events.contains(1));
+        assert!(recent_events.contains(2));
+        assert!(recent_events.contains(3));
+    }
+
+    #[test]
+    fn should_return_different_ids_for_different_paths_or_content() {
+        let id = event_id("/archive/one.log", b"{\"type\":\"one\"}");
+
+        assert_eq!(id, event_id("/archive/one.log", b"{\"type\":\"one\"}"));
+        assert_ne!(id, event_id("/archive/two.log", b"{\"type\":\"one\"}"));
+        assert_ne!(id, event_id("/archive/one.log", b"{\"type\":\"two\"}"));
+    }
+}
@@ -1,20 +1,22 @@
 use log::*;
-use lru_time_cache::Entry;
-use lru_time_cache::LruCache;
 use std::collections::HashMap;
-use std::path::Path;
 use std::sync::Arc;
-use tokio::fs::create_dir_all;
-use tokio::fs::File;
-use tokio::fs::OpenOptions;
-use tokio::io::AsyncWriteExt;
-use tokio::io::BufWriter;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tornado_common_api::dedup_key::{to_key_text, DedupKeyResolver};
 use tornado_common_api::Action;
+use tornado_common_parser::{Parser, ParserBuilder};
 use tornado_executor_common::{ExecutorError, StatefulExecutor};
 use tracing::instrument;
 
+mod compression;
 pub mod config;
+mod deduplication;
 mod paths;
+mod redaction;
+mod sampling;
+mod selection;
+mod writers;
 
 pub const ARCHIVE_TYPE_KEY: &str = "archive_type";
 pub const EVENT_KEY: &str = "event";
@@ -23,7 +25,21 @@ pub struct ArchiveExecutor {
     pub base_path: String,
     pub default_path: String,
     paths: HashMap<String, paths::PathMatcher>,
-    file_cache: LruCache<String, BufWriter<File>>,
+    redactor: redaction::Redactor,
+    selector: Option<selection::FieldSelector>,
+    recent_events: Option<deduplication::RecentEvents>,
+    dedup_key: Option<DedupKeyResolver<Parser>>,
+    sampler: Option<sampling::Sampler>,
+    file_writers: Arc<Mutex<writers::FileWriters>>,
+    flush_interval: Duration,
+    sync_interval: Option<Duration>,
+    periodic_tasks_started: bool,
+}
+
+#[derive(Clone, Copy)]
+enum PeriodicTask {
+    Flush,
+    Sync,
 }
 
 impl std::fmt::Display for ArchiveExecutor {
@@ -36,7 +52,16 @@ impl std::fmt::Display for ArchiveExecutor {
 }
 
 impl ArchiveExecutor {
-    pub fn new(config: &config::ArchiveConfig) -> ArchiveExecutor {
+    pub fn new(config: &config::ArchiveConfig) -> Result<ArchiveExecutor, ExecutorError> {
+        ArchiveExecutor::new_with_sample_seed(config, None)
+    }
+
+    /// Creates an ArchiveExecutor like `new` that samples the Events with a random generator
+    ///   initialized with the given seed, so that the archived Events are deterministic.
+    pub fn new_with_sample_seed(
+        config: &config::ArchiveConfig,
+        sample_seed: Option<u64>,
+    ) -> Result<ArchiveExecutor, ExecutorError> {
         let builder = paths::PathMatcherBuilder::new();
         let paths = config
             .paths
@@ -44,16 +69,74 @@ impl ArchiveExecutor {
             .map(|(key, value)| (key.to_owned(), builder.build(value.to_owned())))
             .collect::<HashMap<String, paths::PathMatcher>>();
 
-        let time_to_live = ::std::time::Duration::from_secs(config.file_cache_ttl_secs);
-        let file_cache =
-            LruCache::with_expiry_duration_and_capacity(time_to_live, config.file_cache_size);
+        let (sync_mode, sync_interval) = match &config.durability {
+            config::ArchiveDurabilityConfig { fsync_each: true, .. } => {
+                (writers::SyncMode::EachWrite, None)
+            }
+            config::ArchiveDurabilityConfig { fsync_interval_ms: Some(interval_ms), .. } => {
+                (writers::SyncMode::Batched, Some(Duration::from_millis(*interval_ms)))
+            }
+            _ => (writers::SyncMode::Never, None),
+        };
 
-        ArchiveExecutor {
+        let time_to_live = Duration::from_secs(config.file_cache_ttl_secs);
+        let file_writers = writers::FileWriters::new_with_sync_mode(
+            config.file_cache_size,
+            time_to_live,
+            sync_mode,
+            Arc::new(writers::FsFileSyncer),
+        )
+        .with_compression(config.compression);
+
+        let parser_builder = ParserBuilder::default();
+        let dedup_key = config
+            .deduplication
+            .as_ref()
+            .and_then(|deduplication| deduplication.key.as_ref())
+            .map(|key| {
+                key.build(|field| parser_builder.build_parser(field)).map_err(|err| {
+                    ExecutorError::ConfigurationError {
+                        message: format!(
+                            "ArchiveExecutor - Cannot build parser for deduplication key [{:?}]. Err: {:?}",
+                            key, err
+                        ),
+                    }
+                })
+            })
+            .transpose()?;
+
+        Ok(ArchiveExecutor {
             base_path: config.base_path.clone(),
             default_path: config.default_path.clone(),
             paths,
-            file_cache,
-        }
+            redactor: redaction::Redactor::new(&config.redacted_fields),
+            selector: config
+                .include_only
+                .as_ref()
+                .map(|include_only| selection::FieldSelector::new(include_only)),
+            recent_events: config
+                .deduplication
+                .as_ref()
+                .map(|deduplication| deduplication::RecentEvents::new(deduplication.window_size)),
+            dedup_key,
+            sampler: config
+                .sample_rate
+                .filter(|sample_rate| *sample_rate < 1.0)
+                .map(|sample_rate| sampling::Sampler::new(sample_rate, sample_seed)),
+            file_writers: Arc::new(Mutex::new(file_writers)),
+            flush_interval: Duration::from_millis(config.file_flush_interval_ms),
+            sync_interval,
+            periodic_tasks_started: false,
+        })
+    }
+
+    /// Returns the deduplication key of the Action, if configured and resolved
+    fn extract_dedup_key(&self, action: &Action) -> Option<String> {
+        self.dedup_key.as_ref().and_then(|dedup_key| {
+            dedup_key.derive(|parser| {
+                parser.parse_value(&action.payload, "").map(|value| to_key_text(value.as_ref()))
+            })
+        })
     }
 
     #[instrument(level = "debug", name = "Extract parameters for Executor", skip_all)]
@@ -91,7 +174,17 @@ impl ArchiveExecutor {
                 data: Default::default(),
             })
             .and_then(|value| {
-                serde_json::to_vec(value).map_err(|err| ExecutorError::ActionExecutionError {
+                let serialized = if self.redactor.is_empty() && self.selector.is_none() {
+                    serde_json::to_vec(value)
+                } else {
+                    let mut archived_value = match &self.selector {
+                        Some(selector) => selector.select(value),
+                        None => value.clone(),
+                    };
+                    self.redactor.redact(&mut archived_value);
+                    serde_json::to_vec(&archived_value)
+                };
+                serialized.map_err(|err| ExecutorError::ActionExecutionError {
                     can_retry: false,
                     message: format!("Cannot deserialize event:{}", err),
                     code: None,
@@ -110,59 +203,78 @@ impl ArchiveExecutor {
         absolute_path_string: String,
         buf: &[u8],
     ) -> Result<(), ExecutorError> {
-        let buf_writer = match self.file_cache.entry(absolute_path_string.clone()) {
-            Entry::Occupied(occupied) => occupied.into_mut(),
-            Entry::Vacant(vacant) => {
-                if absolute_path_string.contains(r"\..") || absolute_path_string.contains("/..") {
-                    return Err(ExecutorError::ActionExecutionError {
-                        can_retry: false,
-                        message: format!("Suspicious path [{:?}]. It could be an attempt to write outside the main directory.", &absolute_path_string),
-                        code: None,
-                        data: Default::default(),
-                    });
-                }
+        if !self.periodic_tasks_started {
+            self.periodic_tasks_started = true;
+            self.start_periodic_task(self.flush_interval, PeriodicTask::Flush);
+            if let Some(sync_interval) = self.sync_interval {
+                self.start_periodic_task(sync_interval, PeriodicTask::Sync);
+            }
+        }
+        self.file_writers.lock().await.write(&absolute_path_string, buf).await
+    }
+
+    /// Writes to disk all the data buffered by the executor.
+    pub async fn flush(&self) -> Result<(), ExecutorError> {
+        self.file_writers.lock().await.flush_all().await
+    }
 
-                let path = Path::new(&absolute_path_string);
-
-                if let Some(parent) = path.parent() {
-                    create_dir_all(&parent).await.map_err(|err| {
-                        ExecutorError::ActionExecutionError {
-                            can_retry: true,
-                            message: format!(
-                                "Cannot create required directories for path [{:?}]: {}",
-                                &path, err
-                            ),
-                            code: None,
-                            data: Default::default(),
+    /// Returns a handle to the files written by the executor, which can be used to close them
+    ///   after the executor has been moved, e.g. when the application stops.
+    pub fn files(&self) -> ArchiveFiles {
+        ArchiveFiles { file_writers: self.file_writers.clone() }
+    }
+
+    /// Starts a task that periodically flushes or syncs the buffered data.
+    /// The task stops when the executor is dropped.
+    fn start_periodic_task(&self, period: Duration, task: PeriodicTask) {
+        let file_writers = Arc::downgrade(&self.file_writers);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            // The first tick completes immediately
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match file_writers.upgrade() {
+                    Some(file_writers) => {
+                        let mut file_writers = file_writers.lock().await;
+                        let result = match task {
+                            PeriodicTask::Flush => file_writers.flush_all().await,
+                            PeriodicTask::Sync => file_writers.sync_all().await,
+                        };
+                        if let Err(err) = result {
+                            error!("ArchiveExecutor - Error while flushing files: {}", err);
                         }
-                    })?;
+                    }
+                    None => break,
                 }
+            }
+        });
+    }
+}
 
-                let file = OpenOptions::new().create(true).append(true).open(&path).await.map_err(
-                    |err| ExecutorError::ActionExecutionError {
-                        can_retry: true,
-                        message: format!("Cannot open file [{}]: {}", &absolute_path_string, err),
-                        code: None,
-                        data: Default::default(),
-                    },
-                )?;
+/// A handle to the files written by an ArchiveExecutor.
+#[derive(Clone)]
+pub struct ArchiveFiles {
+    file_writers: Arc<Mutex<writers::FileWriters>>,
+}
 
-                vacant.insert(BufWriter::new(file))
-            }
-        };
+impl ArchiveFiles {
+    /// Writes to disk all the buffered data and closes the open files.
+    /// A closed file is opened again by the next write to its path.
+    pub async fn close_all(&self) {
+        self.file_writers.lock().await.close_all().await
+    }
+}
 
-        buf_writer.write_all(buf).await.map_err(|err| ExecutorError::ActionExecutionError {
-            can_retry: true,
-            message: format!("Cannot write to file [{}]: {}", &absolute_path_string, err),
-            code: None,
-            data: Default::default(),
-        })?;
-        buf_writer.flush().await.map_err(|err| ExecutorError::ActionExecutionError {
-            can_retry: true,
-            message: format!("Cannot flush file [{}]: {}", &absolute_path_string, err),
-            code: None,
-            data: Default::default(),
-        })
+impl Drop for ArchiveExecutor {
+    fn drop(&mut self) {
+        // It is not possible to await here, so the buffered data is flushed by a new task.
+        // Nothing awaits the task: the application closes the files through `ArchiveFiles`
+        // before stopping.
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let file_writers = self.file_writers.clone();
+            runtime.spawn(async move { file_writers.lock().await.close_all().await });
+        }
     }
 }
 
@@ -173,6 +285,14 @@ impl StatefulExecutor for ArchiveExecutor {
         trace!("ArchiveExecutor - received action: \n{:?}", action);
 
         let (path, event_bytes) = self.extract_params_from_payload(&action)?;
+
+        if let Some(sampler) = &mut self.sampler {
+            if !sampler.sample() {
+                trace!("ArchiveExecutor - the event was not sampled. It will not be written.");
+                return Ok(());
+            }
+        }
+
         let absolute_path_string = format!(
             "{}{}{}",
             self.base_path,
@@ -181,8 +301,31 @@ impl StatefulExecutor for ArchiveExecutor {
                 .unwrap_or_else(|| std::borrow::Cow::Borrowed(&self.default_path))
         );
 
+        let event_id = match &self.recent_events {
+            Some(recent_events) => {
+                let dedup_key = self.extract_dedup_key(&action);
+                let event_identity =
+                    dedup_key.as_ref().map(String::as_bytes).unwrap_or(&event_bytes);
+                let event_id = deduplication::event_id(&absolute_path_string, event_identity);
+                if recent_events.contains(event_id) {
+                    debug!(
+                        "ArchiveExecutor - the event was already archived to [{}]. It will not be written again.",
+                        absolute_path_string
+                    );
+                    return Ok(());
+                }
+                Some(event_id)
+            }
+            None => None,
+        };
+
         self.write(absolute_path_string, &event_bytes).await?;
 
+        // The event is marked as archived only after a successful write, so a failed write can be retried
+        if let (Some(recent_events), Some(event_id)) = (&mut self.recent_events, event_id) {
+            recent_events.insert(event_id);
+        }
+
         Ok(())
     }
 }
@@ -194,8 +337,9 @@ mod test {
     use serde_json::json;
     use tokio::fs::{self, read_to_string};
     use tokio::io::{AsyncBufReadExt, BufReader};
+    use tornado_common_api::dedup_key::DedupKey;
     use tornado_common_api::Event;
-    use tornado_common_api::Value;
+    use tornado_common_api::{Map, Value};
 
     #[tokio::test]
     async fn should_write_to_expected_path() {
@@ -208,6 +352,13 @@ mod test {
             paths: HashMap::new(),
             file_cache_size: 10,
             file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: None,
+            deduplication: None,
+            sample_rate: None,
+            compression: Default::default(),
         };
 
         config.paths.insert("one".to_owned(), "/one/${key_one}/${key_two}.log".to_owned());
@@ -216,7 +367,7 @@ mod test {
 
         println!("Expected file path: [{}]", &expected_path);
 
-        let mut archiver = ArchiveExecutor::new(&config);
+        let mut archiver = ArchiveExecutor::new(&config).unwrap();
 
         let event = Event::new("event-name");
         let mut action = Action::new("action");
@@ -230,6 +381,7 @@ mod test {
 
         // Assert
         assert!(result.is_ok());
+        archiver.flush().await.unwrap();
 
         let file_content = read_to_string(&expected_path).await.unwrap();
         let event_from_file = serde_json::from_str::<Event>(&file_content).unwrap();
@@ -248,6 +400,13 @@ mod test {
             paths: HashMap::new(),
             file_cache_size: 10,
             file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: None,
+            deduplication: None,
+            sample_rate: None,
+            compression: Default::default(),
         };
 
         config.paths.insert("one".to_owned(), "/one/${key_one}/${key_two}.log".to_owned());
@@ -256,7 +415,7 @@ mod test {
 
         println!("Expected file path: [{}]", &expected_path);
 
-        let mut archiver = ArchiveExecutor::new(&config);
+        let mut archiver = ArchiveExecutor::new(&config).unwrap();
 
         let attempts = 10;
         let mut sent_events = vec![];
@@ -273,6 +432,7 @@ mod test {
             action.payload.insert("key_two".to_owned(), Value::String("second".to_owned()));
             archiver.execute(action.into()).await.unwrap()
         }
+        archiver.flush().await.unwrap();
 
         let file = fs::File::open(&expected_path).await.unwrap();
         let mut lines = BufReader::new(file).lines();
@@ -292,6 +452,213 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn should_keep_the_file_open_between_writes_to_the_same_path() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let config = config::ArchiveConfig {
+            base_path: dir.to_owned(),
+            default_path: "/default/file.out".to_owned(),
+            paths: HashMap::new(),
+            file_cache_size: 10,
+            file_cache_ttl_secs: 60,
+            file_flush_interval_ms: 60_000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: None,
+            deduplication: None,
+            sample_rate: None,
+            compression: Default::default(),
+        };
+
+        let expected_path = format!("{}/{}", &dir, "/default/file.out");
+
+        let mut archiver = ArchiveExecutor::new(&config).unwrap();
+
+        let attempts = 1000;
+
+        // Act
+        for i in 0..attempts {
+            let event = Event::new(format!("event-name-{}", i));
+            let mut action = Action::new("action");
+            action.payload.insert(EVENT_KEY.to_owned(), json!(event));
+            archiver.execute(action.into()).await.unwrap()
+        }
+        archiver.flush().await.unwrap();
+
+        // Assert
+        let file_content = read_to_string(&expected_path).await.unwrap();
+        let events_from_file = file_content
+            .lines()
+            .map(|line| serde_json::from_str::<Event>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(attempts, events_from_file.len());
+        for (i, event) in events_from_file.iter().enumerate() {
+            assert_eq!(format!("event-name-{}", i), event.event_type);
+        }
+        assert_eq!(1, archiver.file_writers.lock().await.opened_files());
+    }
+
+    #[tokio::test]
+    async fn should_redact_the_configured_fields() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let config = config::ArchiveConfig {
+            base_path: dir.to_owned(),
+            default_path: "/default/file.out".to_owned(),
+            paths: HashMap::new(),
+            file_cache_size: 10,
+            file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec!["payload.credentials.password".to_owned()],
+            include_only: None,
+            deduplication: None,
+            sample_rate: None,
+            compression: Default::default(),
+        };
+
+        let expected_path = format!("{}/{}", &dir, "/default/file.out");
+
+        let mut archiver = ArchiveExecutor::new(&config).unwrap();
+
+        let mut action = Action::new("action");
+        action.payload.insert(
+            EVENT_KEY.to_owned(),
+            json!({
+                "type": "login",
+                "created_ms": 0,
+                "payload": {
+                    "user": "admin",
+                    "credentials": { "domain": "local", "password": "secret" }
+                }
+            }),
+        );
+
+        // Act
+        let result = archiver.execute(action.into()).await;
+
+        // Assert
+        assert!(result.is_ok());
+        archiver.flush().await.unwrap();
+
+        let file_content = read_to_string(&expected_path).await.unwrap();
+        assert!(!file_content.contains("secret"));
+        assert_eq!(
+            format!(
+                "{}\n",
+                r#"{"created_ms":0,"payload":{"credentials":{"domain":"local","password":"***"},"user":"admin"},"type":"login"}"#
+            ),
+            file_content
+        );
+    }
+
+    #[tokio::test]
+    async fn should_write_only_the_included_fields() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let config = config::ArchiveConfig {
+            base_path: dir.to_owned(),
+            default_path: "/default/file.out".to_owned(),
+            paths: HashMap::new(),
+            file_cache_size: 10,
+            file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec!["payload.credentials.user".to_owned()],
+            include_only: Some(vec![
+                "payload.hostname".to_owned(),
+                "payload.credentials.user".to_owned(),
+            ]),
+            deduplication: None,
+            sample_rate: None,
+            compression: Default::default(),
+        };
+
+        let expected_path = format!("{}/{}", &dir, "/default/file.out");
+
+        let mut archiver = ArchiveExecutor::new(&config).unwrap();
+
+        let mut action = Action::new("action");
+        action.payload.insert(
+            EVENT_KEY.to_owned(),
+            json!({
+                "type": "login",
+                "created_ms": 0,
+                "payload": {
+                    "hostname": "web01",
+                    "debug_dump": "a very long and noisy text",
+                    "credentials": { "user": "admin", "password": "secret" }
+                }
+            }),
+        );
+
+        // Act
+        let result = archiver.execute(action.into()).await;
+
+        // Assert
+        assert!(result.is_ok());
+        archiver.flush().await.unwrap();
+
+        let file_content = read_to_string(&expected_path).await.unwrap();
+        assert_eq!(
+            format!(
+                "{}\n",
+                r#"{"payload":{"credentials":{"user":"***"},"hostname":"web01"},"type":"login"}"#
+            ),
+            file_content
+        );
+    }
+
+    #[tokio::test]
+    async fn should_write_only_the_event_type_if_no_fields_are_included() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let config = config::ArchiveConfig {
+            base_path: dir.to_owned(),
+            default_path: "/default/file.out".to_owned(),
+            paths: HashMap::new(),
+            file_cache_size: 10,
+            file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: Some(vec![]),
+            deduplication: None,
+            sample_rate: None,
+            compression: Default::default(),
+        };
+
+        let expected_path = format!("{}/{}", &dir, "/default/file.out");
+
+        let mut archiver = ArchiveExecutor::new(&config).unwrap();
+
+        let mut action = Action::new("action");
+        action.payload.insert(
+            EVENT_KEY.to_owned(),
+            json!({
+                "type": "login",
+                "created_ms": 0,
+                "payload": { "user": "admin", "password": "secret" }
+            }),
+        );
+
+        // Act
+        let result = archiver.execute(action.into()).await;
+
+        // Assert
+        assert!(result.is_ok());
+        archiver.flush().await.unwrap();
+
+        let file_content = read_to_string(&expected_path).await.unwrap();
+        assert_eq!("{\"type\":\"login\"}\n", file_content);
+    }
+
     #[tokio::test]
     async fn should_not_allow_writing_outside_the_base_path() {
         // Arrange
@@ -303,11 +670,18 @@ mod test {
             paths: HashMap::new(),
             file_cache_size: 10,
             file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: None,
+            deduplication: None,
+            sample_rate: None,
+            compression: Default::default(),
         };
 
         config.paths.insert("one".to_owned(), "/one/${key_one}/${key_two}.log".to_owned());
 
-        let mut archiver = ArchiveExecutor::new(&config);
+        let mut archiver = ArchiveExecutor::new(&config).unwrap();
 
         let event = Event::new("event-name");
         let mut action = Action::new("action");
@@ -334,11 +708,18 @@ mod test {
             paths: HashMap::new(),
             file_cache_size: 10,
             file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: None,
+            deduplication: None,
+            sample_rate: None,
+            compression: Default::default(),
         };
 
         config.paths.insert("one".to_owned(), "/one/${key_one}/${key_two}.log".to_owned());
 
-        let mut archiver = ArchiveExecutor::new(&config);
+        let mut archiver = ArchiveExecutor::new(&config).unwrap();
 
         let event = Event::new("event-name");
         let mut action = Action::new("action");
@@ -363,11 +744,18 @@ mod test {
             paths: HashMap::new(),
             file_cache_size: 10,
             file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: None,
+            deduplication: None,
+            sample_rate: None,
+            compression: Default::default(),
         };
 
         config.paths.insert("one".to_owned(), "/one/${key_one}/${key_two}.log".to_owned());
 
-        let mut archiver = ArchiveExecutor::new(&config);
+        let mut archiver = ArchiveExecutor::new(&config).unwrap();
 
         let event = Event::new("event-name");
         let mut action = Action::new("action");
@@ -392,12 +780,19 @@ mod test {
             paths: HashMap::new(),
             file_cache_size: 10,
             file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: None,
+            deduplication: None,
+            sample_rate: None,
+            compression: Default::default(),
         };
 
         let expected_path = format!("{}/{}", &dir, "/default/file.out");
         println!("Expected file path: [{}]", &expected_path);
 
-        let mut archiver = ArchiveExecutor::new(&config);
+        let mut archiver = ArchiveExecutor::new(&config).unwrap();
 
         let event = Event::new("event-name");
         let mut action = Action::new("action");
@@ -408,10 +803,413 @@ mod test {
 
         // Assert
         assert!(result.is_ok());
+        archiver.flush().await.unwrap();
 
         let file_content = read_to_string(&expected_path).await.unwrap();
         let event_from_file = serde_json::from_str::<Event>(&file_content).unwrap();
 
         assert_eq!(event, event_from_file);
     }
+
+    fn new_deduplicating_archiver(dir: &str) -> ArchiveExecutor {
+        new_deduplicating_archiver_with_key(dir, None)
+    }
+
+    fn new_deduplicating_archiver_with_key(dir: &str, key: Option<DedupKey>) -> ArchiveExecutor {
+        ArchiveExecutor::new(&config::ArchiveConfig {
+            base_path: dir.to_owned(),
+            default_path: "/default/file.out".to_owned(),
+            paths: HashMap::new(),
+            file_cache_size: 10,
+            file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: None,
+            deduplication: Some(config::ArchiveDeduplicationConfig { window_size: 10, key }),
+            sample_rate: None,
+            compression: Default::default(),
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn should_write_the_same_event_only_once_if_deduplication_is_enabled() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let expected_path = format!("{}/{}", &dir, "/default/file.out");
+
+        let mut archiver = new_deduplicating_archiver(&dir);
+
+        let event = Event::new("event-name");
+        let mut action = Action::new("action");
+        action.payload.insert(EVENT_KEY.to_owned(), json!(event.clone()));
+        let action = Arc::new(action);
+
+        // Act
+        let first_result = archiver.execute(action.clone()).await;
+        let second_result = archiver.execute(action).await;
+
+        // Assert
+        assert!(first_result.is_ok());
+        assert!(second_result.is_ok());
+        archiver.flush().await.unwrap();
+
+        let file_content = read_to_string(&expected_path).await.unwrap();
+        let lines = file_content.lines().collect::<Vec<_>>();
+        assert_eq!(1, lines.len());
+        assert_eq!(event, serde_json::from_str::<Event>(lines[0]).unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_write_distinct_events_if_deduplication_is_enabled() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let expected_path = format!("{}/{}", &dir, "/default/file.out");
+
+        let mut archiver = new_deduplicating_archiver(&dir);
+
+        let first_event = Event::new("event-one");
+        let mut first_action = Action::new("action");
+        first_action.payload.insert(EVENT_KEY.to_owned(), json!(first_event.clone()));
+
+        let second_event = Event::new("event-two");
+        let mut second_action = Action::new("action");
+        second_action.payload.insert(EVENT_KEY.to_owned(), json!(second_event.clone()));
+
+        // Act
+        archiver.execute(first_action.into()).await.unwrap();
+        archiver.execute(second_action.into()).await.unwrap();
+
+        // Assert
+        archiver.flush().await.unwrap();
+
+        let file_content = read_to_string(&expected_path).await.unwrap();
+        let lines = file_content.lines().collect::<Vec<_>>();
+        assert_eq!(2, lines.len());
+        assert_eq!(first_event, serde_json::from_str::<Event>(lines[0]).unwrap());
+        assert_eq!(second_event, serde_json::from_str::<Event>(lines[1]).unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_deduplicate_the_events_by_the_configured_key() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let expected_path = format!("{}/{}", &dir, "/default/file.out");
+
+        let mut archiver = new_deduplicating_archiver_with_key(
+            &dir,
+            Some(DedupKey::Fields {
+                fields: vec![
+                    "${event.payload.host}".to_owned(),
+                    "${event.payload.check}".to_owned(),
+                ],
+                separator: "::".to_owned(),
+            }),
+        );
+
+        let events = vec![
+            Event::new_with_payload("check", event_payload("server-1", "ping", "DOWN")),
+            Event::new_with_payload("check", event_payload("server-1", "ping", "UP")),
+            Event::new_with_payload("check", event_payload("server-1", "disk", "UP")),
+        ];
+
+        // Act
+        for event in &events {
+            let mut action = Action::new("action");
+            action.payload.insert(EVENT_KEY.to_owned(), json!(event));
+            archiver.execute(action.into()).await.unwrap();
+        }
+
+        // Assert
+        archiver.flush().await.unwrap();
+
+        let file_content = read_to_string(&expected_path).await.unwrap();
+        let lines = file_content.lines().collect::<Vec<_>>();
+        assert_eq!(2, lines.len());
+        assert_eq!(events[0], serde_json::from_str::<Event>(lines[0]).unwrap());
+        assert_eq!(events[2], serde_json::from_str::<Event>(lines[1]).unwrap());
+    }
+
+    #[test]
+    fn should_fail_if_the_deduplication_key_is_not_valid() {
+        // Arrange
+        let config = config::ArchiveConfig {
+            base_path: "/tmp".to_owned(),
+            default_path: "/default/file.out".to_owned(),
+            paths: HashMap::new(),
+            file_cache_size: 10,
+            file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: None,
+            deduplication: Some(config::ArchiveDeduplicationConfig {
+                window_size: 10,
+                key: Some("${}".into()),
+            }),
+            sample_rate: None,
+            compression: Default::default(),
+        };
+
+        // Act
+        let result = ArchiveExecutor::new(&config);
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::ConfigurationError { .. })));
+    }
+
+    fn event_payload(host: &str, check: &str, state: &str) -> Map<String, Value> {
+        let mut payload = Map::new();
+        payload.insert("host".to_owned(), json!(host));
+        payload.insert("check".to_owned(), json!(check));
+        payload.insert("state".to_owned(), json!(state));
+        payload
+    }
+
+    #[tokio::test]
+    async fn should_write_only_the_sampled_events() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let expected_path = format!("{}/{}", &dir, "/default/file.out");
+        let config = config::ArchiveConfig {
+            base_path: dir.to_owned(),
+            default_path: "/default/file.out".to_owned(),
+            paths: HashMap::new(),
+            file_cache_size: 10,
+            file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: None,
+            deduplication: None,
+            sample_rate: Some(0.5),
+            compression: Default::default(),
+        };
+
+        let mut archiver = ArchiveExecutor::new_with_sample_seed(&config, Some(1234)).unwrap();
+
+        // Act
+        for _ in 0..1_000 {
+            let mut action = Action::new("action");
+            action.payload.insert(EVENT_KEY.to_owned(), json!(Event::new("event-name")));
+            archiver.execute(action.into()).await.unwrap();
+        }
+
+        // Assert
+        archiver.flush().await.unwrap();
+
+        let file_content = read_to_string(&expected_path).await.unwrap();
+        let written_events = file_content.lines().count();
+        assert!((400..=600).contains(&written_events), "written events: {}", written_events);
+    }
+
+    fn new_archiver_with_compression(
+        dir: &str,
+        default_path: &str,
+        compression: config::ArchiveCompression,
+    ) -> ArchiveExecutor {
+        ArchiveExecutor::new(&config::ArchiveConfig {
+            base_path: dir.to_owned(),
+            default_path: default_path.to_owned(),
+            paths: HashMap::new(),
+            file_cache_size: 10,
+            file_cache_ttl_secs: 1,
+            file_flush_interval_ms: 1000,
+            durability: Default::default(),
+            redacted_fields: vec![],
+            include_only: None,
+            deduplication: None,
+            sample_rate: None,
+            compression,
+        })
+        .unwrap()
+    }
+
+    fn read_gzip_lines(path: &str) -> Vec<String> {
+        use std::io::Read;
+        let mut content = String::new();
+        flate2::read::MultiGzDecoder::new(std::fs::File::open(path).unwrap())
+            .read_to_string(&mut content)
+            .unwrap();
+        content.lines().map(|line| line.to_owned()).collect()
+    }
+
+    #[tokio::test]
+    async fn should_write_all_the_records_when_the_files_are_closed() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let expected_path = format!("{}/{}", &dir, "/default/file.out.gz");
+
+        let mut archiver = new_archiver_with_compression(
+            &dir,
+            "/default/file.out.gz",
+            config::ArchiveCompression::Auto,
+        );
+        let files = archiver.files();
+
+        let mut sent_events = vec![];
+        for i in 0..10 {
+            let event = Event::new(format!("event-name-{}", i));
+            sent_events.push(event.clone());
+            let mut action = Action::new("action");
+            action.payload.insert(EVENT_KEY.to_owned(), json!(event));
+            archiver.execute(action.into()).await.unwrap();
+        }
+
+        // Act
+        drop(archiver);
+        files.close_all().await;
+
+        // Assert
+        let read_lines = read_gzip_lines(&expected_path);
+        assert_eq!(sent_events.len(), read_lines.len());
+        for (sent_event, line) in sent_events.iter().zip(read_lines.iter()) {
+            assert_eq!(sent_event, &serde_json::from_str::<Event>(line).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn should_compress_the_records_written_to_a_gz_file() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let expected_path = format!("{}/{}", &dir, "/default/file.out.gz");
+
+        let mut archiver = new_archiver_with_compression(
+            &dir,
+            "/default/file.out.gz",
+            config::ArchiveCompression::Auto,
+        );
+
+        let mut sent_events = vec![];
+
+        // Act
+        for i in 0..10 {
+            let event = Event::new(format!("event-name-{}", i));
+            sent_events.push(event.clone());
+            let mut action = Action::new("action");
+            action.payload.insert(EVENT_KEY.to_owned(), json!(event));
+            archiver.execute(action.into()).await.unwrap();
+            // Each record is appended to the file content written by the previous flushes
+            if i % 3 == 0 {
+                archiver.flush().await.unwrap();
+            }
+        }
+        archiver.flush().await.unwrap();
+
+        // Assert
+        let raw_content = fs::read(&expected_path).await.unwrap();
+        assert_eq!(&[0x1f, 0x8b], &raw_content[0..2]);
+
+        let read_lines = read_gzip_lines(&expected_path);
+        assert_eq!(sent_events.len(), read_lines.len());
+        for (sent_event, line) in sent_events.iter().zip(read_lines.iter()) {
+            assert_eq!(sent_event, &serde_json::from_str::<Event>(line).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn should_write_a_compressed_file_smaller_than_the_plain_one() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let plain_path = format!("{}/{}", &dir, "/default/file.out");
+        let compressed_path = format!("{}/{}", &dir, "/default/file.out.gz");
+
+        let mut plain_archiver = new_archiver_with_compression(
+            &dir,
+            "/default/file.out",
+            config::ArchiveCompression::None,
+        );
+        let mut compressed_archiver = new_archiver_with_compression(
+            &dir,
+            "/default/file.out.gz",
+            config::ArchiveCompression::Auto,
+        );
+
+        // Act
+        for i in 0..100 {
+            let event = Event::new_with_payload(
+                "check_result",
+                event_payload(&format!("host-{}", i % 5), "ping", "OK"),
+            );
+            let mut action = Action::new("action");
+            action.payload.insert(EVENT_KEY.to_owned(), json!(event));
+            let action = Arc::new(action);
+            plain_archiver.execute(action.clone()).await.unwrap();
+            compressed_archiver.execute(action).await.unwrap();
+        }
+        plain_archiver.flush().await.unwrap();
+        compressed_archiver.flush().await.unwrap();
+
+        // Assert
+        let plain_size = fs::metadata(&plain_path).await.unwrap().len();
+        let compressed_size = fs::metadata(&compressed_path).await.unwrap().len();
+        assert!(
+            compressed_size * 3 < plain_size,
+            "compressed size: {}, plain size: {}",
+            compressed_size,
+            plain_size
+        );
+        assert_eq!(100, read_gzip_lines(&compressed_path).len());
+    }
+
+    #[tokio::test]
+    async fn should_compress_the_records_if_gzip_is_set() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let expected_path = format!("{}/{}", &dir, "/default/file.out");
+
+        let mut archiver = new_archiver_with_compression(
+            &dir,
+            "/default/file.out",
+            config::ArchiveCompression::Gzip,
+        );
+
+        let event = Event::new("event-name");
+        let mut action = Action::new("action");
+        action.payload.insert(EVENT_KEY.to_owned(), json!(event.clone()));
+
+        // Act
+        archiver.execute(action.into()).await.unwrap();
+        archiver.flush().await.unwrap();
+
+        // Assert
+        let read_lines = read_gzip_lines(&expected_path);
+        assert_eq!(1, read_lines.len());
+        assert_eq!(event, serde_json::from_str::<Event>(&read_lines[0]).unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_not_compress_the_records_if_compression_is_disabled() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dir = tempdir.path().to_str().unwrap().to_owned();
+        let expected_path = format!("{}/{}", &dir, "/default/file.out.gz");
+
+        let mut archiver = new_archiver_with_compression(
+            &dir,
+            "/default/file.out.gz",
+            config::ArchiveCompression::None,
+        );
+
+        let event = Event::new("event-name");
+        let mut action = Action::new("action");
+        action.payload.insert(EVENT_KEY.to_owned(), json!(event.clone()));
+
+        // Act
+        archiver.execute(action.into()).await.unwrap();
+        archiver.flush().await.unwrap();
+
+        // Assert
+        let file_content = read_to_string(&expected_path).await.unwrap();
+        assert_eq!(event, serde_json::from_str::<Event>(&file_content).unwrap());
+    }
 }
@@ -0,0 +1,186 @@
+use serde_json::Value;
+
+/// The text that replaces the redacted values
+pub const REDACTED_VALUE: &str = "***";
+
+const PATH_SEPARATOR: char = '.';
+const PATH_ROOT: &str = "$.";
+pub(crate) const ANY_FIELD: &str = "*";
+
+/// Splits the path patterns into their segments
+pub(crate) fn parse_patterns(patterns: &[String]) -> Vec<Vec<String>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            pattern
+                .strip_prefix(PATH_ROOT)
+                .unwrap_or(pattern)
+                .split(PATH_SEPARATOR)
+                .map(|segment| segment.to_owned())
+                .collect()
+        })
+        .collect()
+}
+
+/// Masks the values of the fields of an Event that match a set of path patterns.
+/// A pattern is a dot-separated path of field names relative to the Event root,
+///   optionally prefixed by `$.`, e.g. `payload.credentials.password`.
+/// The `*` segment matches any field of an object and any element of an array;
+///   a numeric segment matches also the element of an array at that index.
+pub struct Redactor {
+    patterns: Vec<Vec<String>>,
+}
+
+impl Redactor {
+    pub fn new(patterns: &[String]) -> Redactor {
+        Redactor { patterns: parse_patterns(patterns) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Replaces the values of all the matching fields with the `REDACTED_VALUE`.
+    pub fn redact(&self, value: &mut Value) {
+        for pattern in &self.patterns {
+            redact_path(value, pattern);
+        }
+    }
+}
+
+fn redact_path(value: &mut Value, path: &[String]) {
+    let (segment, remaining_path) = match path.split_first() {
+        Some(split) => split,
+        None => {
+            *value = Value::String(REDACTED_VALUE.to_owned());
+            return;
+        }
+    };
+
+    match value {
+        Value::Object(map) => {
+            if segment == ANY_FIELD {
+                for child in map.values_mut() {
+                    redact_path(child, remaining_path);
+                }
+            } else if let Some(child) = map.get_mut(segment) {
+                redact_path(child, remaining_path);
+            }
+        }
+        Value::Array(array) => {
+            if segment == ANY_FIELD {
+                for child in array.iter_mut() {
+                    redact_path(child, remaining_path);
+                }
+            } else if let Some(child) =
+                segment.parse::<usize>().ok().and_then(|index| array.get_mut(index))
+            {
+                redact_path(child, remaining_path);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_redact_a_nested_field() {
+        // Arrange
+        let redactor = Redactor::new(&["payload.credentials.password".to_owned()]);
+        let mut event = json!({
+            "type": "login",
+            "payload": {
+                "user": "admin",
+                "credentials": { "password": "secret", "domain": "local" }
+            }
+        });
+
+        // Act
+        redactor.redact(&mut event);
+
+        // Assert
+        assert_eq!(
+            json!({
+                "type": "login",
+                "payload": {
+                    "user": "admin",
+                    "credentials": { "password": "***", "domain": "local" }
+                }
+            }),
+            event
+        );
+    }
+
+    #[test]
+    fn should_redact_the_fields_matching_a_wildcard() {
+        // Arrange
+        let redactor = Redactor::new(&["$.payload.*.token".to_owned()]);
+        let mut event = json!({
+            "payload": {
+                "first": { "token": "abc", "id": 1 },
+                "second": [ { "token": "def" }, { "id": 2 } ],
+                "third": { "id": 3 }
+            }
+        });
+
+        // Act
+        redactor.redact(&mut event);
+
+        // Assert
+        assert_eq!(
+            json!({
+                "payload": {
+                    "first": { "token": "***", "id": 1 },
+                    "second": [ { "token": "def" }, { "id": 2 } ],
+                    "third": { "id": 3 }
+                }
+            }),
+            event
+        );
+    }
+
+    #[test]
+    fn should_redact_the_array_elements() {
+        // Arrange
+        let redactor =
+            Redactor::new(&["payload.users.*.password".to_owned(), "payload.keys.1".to_owned()]);
+        let mut event = json!({
+            "payload": {
+                "users": [ { "name": "a", "password": "p1" }, { "name": "b", "password": "p2" } ],
+                "keys": [ "k0", "k1" ]
+            }
+        });
+
+        // Act
+        redactor.redact(&mut event);
+
+        // Assert
+        assert_eq!(
+            json!({
+                "payload": {
+                    "users": [ { "name": "a", "password": "***" }, { "name": "b", "password": "***" } ],
+                    "keys": [ "k0", "***" ]
+                }
+            }),
+            event
+        );
+    }
+
+    #[test]
+    fn should_ignore_the_missing_fields() {
+        // Arrange
+        let redactor = Redactor::new(&["payload.password".to_owned()]);
+        let mut event = json!({ "payload": { "user": "admin" } });
+
+        // Act
+        redactor.redact(&mut event);
+
+        // Assert
+        assert_eq!(json!({ "payload": { "user": "admin" } }), event);
+    }
+}
@@ -0,0 +1,61 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Selects the Events to be archived, keeping each of them with the probability
+///   given by the sample rate.
+pub struct Sampler {
+    rate: f64,
+    rng: StdRng,
+}
+
+impl Sampler {
+    /// Creates a Sampler with the given rate, clamped between 0.0 and 1.0.
+    /// If a seed is given, the sequence of the sampled Events is deterministic.
+    pub fn new(rate: f64, seed: Option<u64>) -> Sampler {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let rate = if rate.is_nan() { 1.0 } else { rate.clamp(0.0, 1.0) };
+        Sampler { rate, rng }
+    }
+
+    /// Returns whether the next Event is archived.
+    pub fn sample(&mut self) -> bool {
+        self.rate >= 1.0 || self.rng.gen_bool(self.rate)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn sampled_count(sampler: &mut Sampler, events: usize) -> usize {
+        (0..events).filter(|_| sampler.sample()).count()
+    }
+
+    #[test]
+    fn should_sample_roughly_the_given_fraction_of_the_events() {
+        let mut sampler = Sampler::new(0.5, Some(42));
+        let sampled = sampled_count(&mut sampler, 10_000);
+        assert!((4_500..=5_500).contains(&sampled), "sampled: {}", sampled);
+    }
+
+    #[test]
+    fn should_sample_the_same_events_with_the_same_seed() {
+        let mut first_sampler = Sampler::new(0.3, Some(7));
+        let mut second_sampler = Sampler::new(0.3, Some(7));
+        for _ in 0..1_000 {
+            assert_eq!(first_sampler.sample(), second_sampler.sample());
+        }
+    }
+
+    #[test]
+    fn should_sample_all_or_no_events_at_the_bounds() {
+        assert_eq!(1_000, sampled_count(&mut Sampler::new(1.0, None), 1_000));
+        assert_eq!(1_000, sampled_count(&mut Sampler::new(2.0, None), 1_000));
+        assert_eq!(0, sampled_count(&mut Sampler::new(0.0, None), 1_000));
+        assert_eq!(0, sampled_count(&mut Sampler::new(-1.0, None), 1_000));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::redaction::{parse_patterns, ANY_FIELD};
+use serde_json::{Map, Value};
+
+/// The field of the Event that is always kept
+const EVENT_TYPE_FIELD: &str = "type";
+
+/// Keeps only the fields of an Event that match a set of path patterns, dropping all the others.
+/// The patterns have the same syntax of the ones of the `Redactor`,
+///   e.g. `payload.hostname` or `payload.checks.*.state`.
+/// The `type` field of the Event is always kept.
+pub struct FieldSelector {
+    patterns: Vec<Vec<String>>,
+}
+
+impl FieldSelector {
+    pub fn new(patterns: &[String]) -> FieldSelector {
+        let mut patterns = parse_patterns(patterns);
+        patterns.push(vec![EVENT_TYPE_FIELD.to_owned()]);
+        FieldSelector { patterns }
+    }
+
+    /// Returns a copy of the value that contains only the matching fields.
+    /// The objects and arrays left without any matching field are removed.
+    pub fn select(&self, value: &Value) -> Value {
+        let patterns: Vec<&[String]> =
+            self.patterns.iter().map(|pattern| pattern.as_slice()).collect();
+        select_paths(value, &patterns).unwrap_or_else(|| Value::Object(Map::new()))
+    }
+}
+
+/// Returns the part of the value matched by the patterns, where each pattern
+///   is the path remaining to be matched starting from this value.
+fn select_paths(value: &Value, patterns: &[&[String]]) -> Option<Value> {
+    if patterns.iter().any(|pattern| pattern.is_empty()) {
+        return Some(value.clone());
+    }
+
+    match value {
+        Value::Object(map) => {
+            let selected: Map<String, Value> = map
+                .iter()
+                .filter_map(|(key, child)| {
+                    select_paths(child, &child_patterns(patterns, key))
+                        .map(|child| (key.clone(), child))
+                })
+                .collect();
+            if selected.is_empty() {
+                None
+            } else {
+                Some(Value::Object(selected))
+            }
+        }
+        Value::Array(array) => {
+            let selected: Vec<Value> = array
+                .iter()
+                .enumerate()
+                .filter_map(|(index, child)| {
+                    select_paths(child, &child_patterns(patterns, &index.to_string()))
+                })
+                .collect();
+            if selected.is_empty() {
+                None
+            } else {
+                Some(Value::Array(selected))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns the patterns that match the key, without their first segment
+fn child_patterns<'a>(patterns: &[&'a [String]], key: &str) -> Vec<&'a [String]> {
+    patterns
+        .iter()
+        .copied()
+        .filter(|pattern| pattern[0] == ANY_FIELD || pattern[0] == key)
+        .map(|pattern| &pattern[1..])
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_keep_only_the_selected_fields() {
+        // Arrange
+        let selector = FieldSelector::new(&[
+            "payload.hostname".to_owned(),
+            "$.payload.check.state".to_owned(),
+        ]);
+        let event = json!({
+            "type": "check_result",
+            "created_ms": 1000,
+            "payload": {
+                "hostname": "web01",
+                "password": "secret",
+                "check": { "state": "DOWN", "output": "timeout" }
+            }
+        });
+
+        // Act
+        let selected = selector.select(&event);
+
+        // Assert
+        assert_eq!(
+            json!({
+                "type": "check_result",
+                "payload": {
+                    "hostname": "web01",
+                    "check": { "state": "DOWN" }
+                }
+            }),
+            selected
+        );
+    }
+
+    #[test]
+    fn should_select_the_array_elements() {
+        // Arrange
+        let selector =
+            FieldSelector::new(&["payload.checks.*.state".to_owned(), "payload.tags.1".to_owned()]);
+        let event = json!({
+            "type": "checks",
+            "payload": {
+                "checks": [ { "name": "ping", "state": "UP" }, { "name": "disk" } ],
+                "tags": [ "linux", "web" ]
+            }
+        });
+
+        // Act
+        let selected = selector.select(&event);
+
+        // Assert
+        assert_eq!(
+            json!({
+                "type": "checks",
+                "payload": {
+                    "checks": [ { "state": "UP" } ],
+                    "tags": [ "web" ]
+                }
+            }),
+            selected
+        );
+    }
+
+    #[test]
+    fn should_keep_only_the_event_type_if_no_fields_are_selected() {
+        // Arrange
+        let selector = FieldSelector::new(&[]);
+        let event = json!({
+            "type": "login",
+            "created_ms": 1000,
+            "payload": { "user": "admin" }
+        });
+
+        // Act
+        let selected = selector.select(&event);
+
+        // Assert
+        assert_eq!(json!({ "type": "login" }), selected);
+    }
+}
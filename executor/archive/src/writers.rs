@@ -0,0 +1,484 @@
+use crate::compression::{is_compressed, GzipBlock};
+use crate::config::ArchiveCompression;
+use log::*;
+use std::collections::HashMap;
+use std::fs::Metadata;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::fs::create_dir_all;
+use tokio::fs::File;
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufWriter;
+use tornado_executor_common::ExecutorError;
+
+/// Defines when the data written to the files is synced to the disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    /// The data is never explicitly synced; it is up to the OS to write it to the disk.
+    Never,
+    /// The data is flushed and synced after each write.
+    EachWrite,
+    /// The data written since the last sync is synced when `sync_all` is called
+    ///   and when a writer is closed.
+    Batched,
+}
+
+/// Syncs the data of a file to the disk.
+#[async_trait::async_trait]
+pub trait FileSyncer: Send + Sync {
+    async fn sync_data(&self, file: &File) -> std::io::Result<()>;
+}
+
+/// A FileSyncer that calls `fsync` on the file.
+pub struct FsFileSyncer;
+
+#[async_trait::async_trait]
+impl FileSyncer for FsFileSyncer {
+    async fn sync_data(&self, file: &File) -> std::io::Result<()> {
+        file.sync_data().await
+    }
+}
+
+/// A bounded cache of buffered file writers keyed by the absolute file path.
+/// The writers are not flushed at each write; instead, the buffered data is written to disk
+///   when `flush_all` is called and when a writer is evicted from the cache,
+///   either because it was not used for longer than the time to live or because the cache is full.
+/// Whether the written data is also synced to the disk depends on the SyncMode.
+/// The data written to a compressed file is buffered in a gzip block that is appended to the
+///   file, as a single gzip member, each time the writer is flushed.
+pub struct FileWriters {
+    writers: HashMap<String, CachedWriter>,
+    capacity: usize,
+    time_to_live: Duration,
+    opened_files: usize,
+    sync_mode: SyncMode,
+    syncer: Arc<dyn FileSyncer>,
+    compression: ArchiveCompression,
+}
+
+struct CachedWriter {
+    writer: BufWriter<File>,
+    gzip_block: Option<GzipBlock>,
+    last_used: Instant,
+    unsynced: bool,
+}
+
+impl FileWriters {
+    pub fn new(capacity: usize, time_to_live: Duration) -> FileWriters {
+        FileWriters::new_with_sync_mode(
+            capacity,
+            time_to_live,
+            SyncMode::Never,
+            Arc::new(FsFileSyncer),
+        )
+    }
+
+    pub fn new_with_sync_mode(
+        capacity: usize,
+        time_to_live: Duration,
+        sync_mode: SyncMode,
+        syncer: Arc<dyn FileSyncer>,
+    ) -> FileWriters {
+        FileWriters {
+            writers: HashMap::new(),
+            capacity,
+            time_to_live,
+            opened_files: 0,
+            sync_mode,
+            syncer,
+            compression: ArchiveCompression::None,
+        }
+    }
+
+    /// Sets which files are gzip-compressed
+    pub fn with_compression(mut self, compression: ArchiveCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Returns how many times a file was opened since the creation of the cache.
+    pub fn opened_files(&self) -> usize {
+        self.opened_files
+    }
+
+    /// Writes the buffer to the file at the given path. The file is created if it does not exist.
+    pub async fn write(&mut self, absolute_path: &str, buf: &[u8]) -> Result<(), ExecutorError> {
+        if !self.writers.contains_key(absolute_path) {
+            self.close_expired().await;
+            self.evict_least_recently_used().await;
+            let writer = self.open(absolute_path).await?;
+            let gzip_block =
+                is_compressed(self.compression, absolute_path).then(GzipBlock::default);
+            self.writers.insert(
+                absolute_path.to_owned(),
+                CachedWriter { writer, gzip_block, last_used: Instant::now(), unsynced: false },
+            );
+        }
+
+        let sync_mode = self.sync_mode;
+        let result = match self.writers.get_mut(absolute_path) {
+            Some(cached) => {
+                cached.last_used = Instant::now();
+                let written = match &mut cached.gzip_block {
+                    Some(gzip_block) => gzip_block.write(buf),
+                    None => cached.writer.write_all(buf).await,
+                };
+                match written {
+                    Ok(()) if sync_mode == SyncMode::EachWrite => {
+                        flush_and_sync(self.syncer.as_ref(), cached).await
+                    }
+                    Ok(()) => {
+                        cached.unsynced = sync_mode == SyncMode::Batched;
+                        Ok(())
+                    }
+                    Err(err) => Err(err),
+                }
+            }
+            None => Ok(()),
+        };
+
+        result.map_err(|err| {
+            // The writer is discarded so that a retry will open the file again
+            self.writers.remove(absolute_path);
+            ExecutorError::ActionExecutionError {
+                can_retry: true,
+                message: format!("Cannot write to file [{}]: {}", absolute_path, err),
+                code: None,
+                data: Default::default(),
+            }
+        })
+    }
+
+    /// Flushes and syncs to disk the writers that received data since their last sync.
+    /// Then, as `flush_all`, it closes the expired and the rotated writers.
+    pub async fn sync_all(&mut self) -> Result<(), ExecutorError> {
+        let mut result = Ok(());
+        let mut to_be_closed = vec![];
+
+        for (path, cached) in self.writers.iter_mut().filter(|(_, cached)| cached.unsynced) {
+            match flush_and_sync(self.syncer.as_ref(), cached).await {
+                Ok(()) => cached.unsynced = false,
+                Err(err) => {
+                    error!("ArchiveExecutor - Cannot sync file [{}]: {}", path, err);
+                    to_be_closed.push(path.clone());
+                    if result.is_ok() {
+                        result = Err(ExecutorError::ActionExecutionError {
+                            can_retry: true,
+                            message: format!("Cannot sync file [{}]: {}", path, err),
+                            code: None,
+                            data: Default::default(),
+                        });
+                    }
+                }
+            }
+        }
+
+        for path in to_be_closed {
+            self.writers.remove(&path);
+        }
+
+        let flush_result = self.flush_all().await;
+        result.and(flush_result)
+    }
+
+    /// Flushes all the buffered data to disk.
+    /// The writers that expired and the ones whose path does not point anymore to the opened file,
+    ///   e.g. because the file was rotated, are closed; the next write to their path will
+    ///   open the file again.
+    pub async fn flush_all(&mut self) -> Result<(), ExecutorError> {
+        let mut result = Ok(());
+        let mut to_be_closed = vec![];
+
+        for (path, cached) in self.writers.iter_mut() {
+            if let Err(err) = flush(cached).await {
+                error!("ArchiveExecutor - Cannot flush file [{}]: {}", path, err);
+                to_be_closed.push(path.clone());
+                if result.is_ok() {
+                    result = Err(ExecutorError::ActionExecutionError {
+                        can_retry: true,
+                        message: format!("Cannot flush file [{}]: {}", path, err),
+                        code: None,
+                        data: Default::default(),
+                    });
+                }
+            } else if cached.last_used.elapsed() > self.time_to_live
+                || is_rotated(path, cached.writer.get_ref()).await
+            {
+                to_be_closed.push(path.clone());
+            }
+        }
+
+        for path in to_be_closed {
+            if let Some(mut cached) = self.writers.remove(&path) {
+                close(self.syncer.as_ref(), &path, &mut cached).await;
+            }
+        }
+
+        result
+    }
+
+    /// Flushes and closes all the writers.
+    pub async fn close_all(&mut self) {
+        for (path, mut cached) in self.writers.drain() {
+            close(self.syncer.as_ref(), &path, &mut cached).await;
+        }
+    }
+
+    async fn close_expired(&mut self) {
+        let time_to_live = self.time_to_live;
+        let expired: Vec<String> = self
+            .writers
+            .iter()
+            .filter(|(_, cached)| cached.last_used.elapsed() > time_to_live)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in expired {
+            if let Some(mut cached) = self.writers.remove(&path) {
+                close(self.syncer.as_ref(), &path, &mut cached).await;
+            }
+        }
+    }
+
+    async fn evict_least_recently_used(&mut self) {
+        while !self.writers.is_empty() && self.writers.len() >= self.capacity {
+            let least_recently_used = self
+                .writers
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(path, _)| path.clone());
+            if let Some(path) = least_recently_used {
+                if let Some(mut cached) = self.writers.remove(&path) {
+                    close(self.syncer.as_ref(), &path, &mut cached).await;
+                }
+            }
+        }
+    }
+
+    async fn open(&mut self, absolute_path: &str) -> Result<BufWriter<File>, ExecutorError> {
+        if absolute_path.contains(r"\..") || absolute_path.contains("/..") {
+            return Err(ExecutorError::ActionExecutionError {
+                can_retry: false,
+                message: format!("Suspicious path [{:?}]. It could be an attempt to write outside the main directory.", absolute_path),
+                code: None,
+                data: Default::default(),
+            });
+        }
+
+        let path = Path::new(absolute_path);
+
+        if let Some(parent) = path.parent() {
+            create_dir_all(&parent).await.map_err(|err| ExecutorError::ActionExecutionError {
+                can_retry: true,
+                message: format!(
+                    "Cannot create required directories for path [{:?}]: {}",
+                    &path, err
+                ),
+                code: None,
+                data: Default::default(),
+            })?;
+        }
+
+        let file =
+            OpenOptions::new().create(true).append(true).open(&path).await.map_err(|err| {
+                ExecutorError::ActionExecutionError {
+                    can_retry: true,
+                    message: format!("Cannot open file [{}]: {}", absolute_path, err),
+                    code: None,
+                    data: Default::default(),
+                }
+            })?;
+        self.opened_files += 1;
+
+        Ok(BufWriter::new(file))
+    }
+}
+
+async fn close(syncer: &dyn FileSyncer, path: &str, cached: &mut CachedWriter) {
+    debug!("ArchiveExecutor - Closing file [{}]", path);
+    if cached.unsynced {
+        if let Err(err) = flush_and_sync(syncer, cached).await {
+            error!("ArchiveExecutor - Cannot sync file [{}] before closing it: {}", path, err);
+        }
+    } else if let Err(err) = flush(cached).await {
+        error!("ArchiveExecutor - Cannot flush file [{}] before closing it: {}", path, err);
+    }
+}
+
+/// Appends the pending gzip block, if any, to the file and flushes the buffered data.
+async fn flush(cached: &mut CachedWriter) -> std::io::Result<()> {
+    if let Some(gzip_block) = &mut cached.gzip_block {
+        if let Some(member) = gzip_block.finish()? {
+            cached.writer.write_all(&member).await?;
+        }
+    }
+    cached.writer.flush().await
+}
+
+async fn flush_and_sync(syncer: &dyn FileSyncer, cached: &mut CachedWriter) -> std::io::Result<()> {
+    flush(cached).await?;
+    syncer.sync_data(cached.writer.get_ref()).await
+}
+
+/// Returns whether the path does not point anymore to the opened file.
+async fn is_rotated(path: &str, file: &File) -> bool {
+    match (tokio::fs::metadata(path).await, file.metadata().await) {
+        (Ok(path_metadata), Ok(file_metadata)) => !is_same_file(&path_metadata, &file_metadata),
+        _ => true,
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(first: &Metadata, second: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    first.dev() == second.dev() && first.ino() == second.ino()
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_first: &Metadata, _second: &Metadata) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::fs::read_to_string;
+
+    #[derive(Default)]
+    struct CountingFileSyncer {
+        syncs: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl FileSyncer for CountingFileSyncer {
+        async fn sync_data(&self, file: &File) -> std::io::Result<()> {
+            self.syncs.fetch_add(1, Ordering::SeqCst);
+            file.sync_data().await
+        }
+    }
+
+    fn new_writers(sync_mode: SyncMode) -> (FileWriters, Arc<CountingFileSyncer>) {
+        let syncer = Arc::new(CountingFileSyncer::default());
+        let writers =
+            FileWriters::new_with_sync_mode(10, Duration::from_secs(60), sync_mode, syncer.clone());
+        (writers, syncer)
+    }
+
+    #[tokio::test]
+    async fn should_sync_each_write() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = format!("{}/file.log", tempdir.path().to_str().unwrap());
+        let (mut writers, syncer) = new_writers(SyncMode::EachWrite);
+
+        // Act
+        writers.write(&path, b"first\n").await.unwrap();
+        writers.write(&path, b"second\n").await.unwrap();
+        writers.write(&path, b"third\n").await.unwrap();
+
+        // Assert
+        assert_eq!(3, syncer.syncs.load(Ordering::SeqCst));
+        assert_eq!("first\nsecond\nthird\n", read_to_string(&path).await.unwrap());
+
+        writers.sync_all().await.unwrap();
+        writers.close_all().await;
+        assert_eq!(3, syncer.syncs.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn should_batch_the_syncs() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let first_path = format!("{}/first.log", tempdir.path().to_str().unwrap());
+        let second_path = format!("{}/second.log", tempdir.path().to_str().unwrap());
+        let (mut writers, syncer) = new_writers(SyncMode::Batched);
+
+        // Act
+        writers.write(&first_path, b"first\n").await.unwrap();
+        writers.write(&first_path, b"first again\n").await.unwrap();
+        writers.write(&second_path, b"second\n").await.unwrap();
+        let syncs_before_sync_all = syncer.syncs.load(Ordering::SeqCst);
+
+        writers.sync_all().await.unwrap();
+        let syncs_after_first_sync_all = syncer.syncs.load(Ordering::SeqCst);
+
+        writers.sync_all().await.unwrap();
+        let syncs_after_second_sync_all = syncer.syncs.load(Ordering::SeqCst);
+
+        writers.write(&second_path, b"second again\n").await.unwrap();
+        writers.close_all().await;
+
+        // Assert
+        assert_eq!(0, syncs_before_sync_all);
+        assert_eq!(2, syncs_after_first_sync_all);
+        assert_eq!(2, syncs_after_second_sync_all);
+        assert_eq!(3, syncer.syncs.load(Ordering::SeqCst));
+        assert_eq!("first\nfirst again\n", read_to_string(&first_path).await.unwrap());
+        assert_eq!("second\nsecond again\n", read_to_string(&second_path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_never_sync_with_the_never_sync_mode() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = format!("{}/file.log", tempdir.path().to_str().unwrap());
+        let (mut writers, syncer) = new_writers(SyncMode::Never);
+
+        // Act
+        writers.write(&path, b"first\n").await.unwrap();
+        writers.sync_all().await.unwrap();
+        writers.close_all().await;
+
+        // Assert
+        assert_eq!(0, syncer.syncs.load(Ordering::SeqCst));
+        assert_eq!("first\n", read_to_string(&path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_flush_the_evicted_writers() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let first_path = format!("{}/first.log", tempdir.path().to_str().unwrap());
+        let second_path = format!("{}/second.log", tempdir.path().to_str().unwrap());
+        let mut writers = FileWriters::new(1, Duration::from_secs(60));
+
+        // Act
+        writers.write(&first_path, b"first\n").await.unwrap();
+        writers.write(&second_path, b"second\n").await.unwrap();
+        writers.write(&first_path, b"first again\n").await.unwrap();
+
+        // Assert
+        assert_eq!("first\n", read_to_string(&first_path).await.unwrap());
+        assert_eq!("second\n", read_to_string(&second_path).await.unwrap());
+        assert_eq!(3, writers.opened_files());
+
+        writers.close_all().await;
+        assert_eq!("first\nfirst again\n", read_to_string(&first_path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn should_reopen_a_rotated_file() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = format!("{}/file.log", tempdir.path().to_str().unwrap());
+        let rotated_path = format!("{}/file.log.1", tempdir.path().to_str().unwrap());
+        let mut writers = FileWriters::new(10, Duration::from_secs(60));
+
+        writers.write(&path, b"before rotation\n").await.unwrap();
+        writers.flush_all().await.unwrap();
+
+        // Act
+        tokio::fs::rename(&path, &rotated_path).await.unwrap();
+        writers.flush_all().await.unwrap();
+        writers.write(&path, b"after rotation\n").await.unwrap();
+        writers.flush_all().await.unwrap();
+
+        // Assert
+        assert_eq!("before rotation\n", read_to_string(&rotated_path).await.unwrap());
+        assert_eq!("after rotation\n", read_to_string(&path).await.unwrap());
+        assert_eq!(2, writers.opened_files());
+    }
+}
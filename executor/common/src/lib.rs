@@ -1,10 +1,11 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::sync::Arc;
 use thiserror::Error;
-use tornado_common_api::{Action, RetriableError};
+use tornado_common_api::{Action, Payload, RetriableError};
 
 /// An executor is in charge of performing a specific Action (typically only one, but perhaps more).
 /// It receives the Action description from the Tornado engine and delivers the linked operation.
@@ -35,6 +36,9 @@ pub enum ExecutorError {
     },
     #[error("ConfigurationError: [{message}]")]
     ConfigurationError { message: String },
+    /// The template is malformed, so the execution fails in the same way at every retry.
+    #[error("InvalidTemplateError: template [{template}], error: [{message}]")]
+    InvalidTemplateError { template: String, message: String },
     #[error("JsonError: {cause}")]
     JsonError { cause: String },
     #[error("MissingArgumentError: [{message}]")]
@@ -88,3 +92,175 @@ impl From<serde_json::Error> for ExecutorError {
         ExecutorError::JsonError { cause: format!("{:?}", err) }
     }
 }
+
+/// Merges a default payload under the payload of an Action.
+/// Nested maps are merged recursively and, in case of conflict, the value of the Action payload wins.
+pub fn merge_default_payload(payload: &mut Payload, default_payload: &Payload) {
+    for (key, default_value) in default_payload {
+        match (payload.get_mut(key), default_value) {
+            (Some(Value::Object(value)), Value::Object(default_value)) => {
+                merge_default_payload(value, default_value)
+            }
+            (Some(_), _) => {}
+            (None, _) => {
+                payload.insert(key.to_owned(), default_value.clone());
+            }
+        }
+    }
+}
+
+/// Replaces the `${VARIABLE_NAME}` placeholders in a configuration value with the values of
+/// the corresponding environment variables.
+/// Returns an error if a variable is not defined or if a placeholder is not closed.
+pub fn interpolate_env_vars(value: &str) -> Result<String, ExecutorError> {
+    let mut result = String::with_capacity(value.len());
+    let mut remaining = value;
+    while let Some(start) = remaining.find("${") {
+        result.push_str(&remaining[..start]);
+        let placeholder = &remaining[start + 2..];
+        let end = placeholder.find('}').ok_or_else(|| ExecutorError::ConfigurationError {
+            message: format!("Unclosed environment variable placeholder in [{}]", value),
+        })?;
+        let variable = &placeholder[..end];
+        let variable_value =
+            std::env::var(variable).map_err(|err| ExecutorError::ConfigurationError {
+                message: format!(
+                    "Cannot read the environment variable [{}] used in [{}]. Err: {:?}",
+                    variable, value, err
+                ),
+            })?;
+        result.push_str(&variable_value);
+        remaining = &placeholder[end + 1..];
+    }
+    result.push_str(remaining);
+    Ok(result)
+}
+
+/// Builds the additional HTTP headers sent by the executors with every request.
+/// The `${VARIABLE_NAME}` placeholders in the header values are replaced with the values of
+/// the corresponding environment variables.
+pub fn build_extra_headers(
+    extra_headers: &HashMap<String, String>,
+) -> Result<HeaderMap, ExecutorError> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in extra_headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes()).map_err(|err| {
+            ExecutorError::ConfigurationError {
+                message: format!("Invalid HTTP header name [{}]. Err: {:?}", name, err),
+            }
+        })?;
+        let header_value = HeaderValue::from_str(&interpolate_env_vars(value)?).map_err(|err| {
+            ExecutorError::ConfigurationError {
+                message: format!("Invalid value of the HTTP header [{}]. Err: {:?}", name, err),
+            }
+        })?;
+        headers.insert(header_name, header_value);
+    }
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merge_default_payload_should_add_missing_keys() {
+        // Arrange
+        let mut payload = json!({ "one": 1, "nested": { "two": 2 } }).as_object().unwrap().clone();
+        let default_payload =
+            json!({ "three": 3, "nested": { "four": 4 } }).as_object().unwrap().clone();
+
+        // Act
+        merge_default_payload(&mut payload, &default_payload);
+
+        // Assert
+        assert_eq!(
+            json!({ "one": 1, "three": 3, "nested": { "two": 2, "four": 4 } }),
+            Value::Object(payload)
+        );
+    }
+
+    #[test]
+    fn merge_default_payload_should_keep_the_action_values() {
+        // Arrange
+        let mut payload = json!({ "one": 1, "nested": { "two": 2 } }).as_object().unwrap().clone();
+        let default_payload = json!({ "one": "default", "nested": { "two": "default" } })
+            .as_object()
+            .unwrap()
+            .clone();
+
+        // Act
+        merge_default_payload(&mut payload, &default_payload);
+
+        // Assert
+        assert_eq!(json!({ "one": 1, "nested": { "two": 2 } }), Value::Object(payload));
+    }
+
+    #[test]
+    fn interpolate_env_vars_should_replace_the_placeholders() {
+        // Arrange
+        std::env::set_var("TORNADO_EXECUTOR_COMMON_TEST_TENANT", "tenant_1");
+        std::env::set_var("TORNADO_EXECUTOR_COMMON_TEST_REGION", "eu");
+
+        // Act
+        let result = interpolate_env_vars(
+            "${TORNADO_EXECUTOR_COMMON_TEST_TENANT}-${TORNADO_EXECUTOR_COMMON_TEST_REGION}/api",
+        );
+
+        // Assert
+        assert_eq!("tenant_1-eu/api", result.unwrap());
+        assert_eq!("no placeholders", interpolate_env_vars("no placeholders").unwrap());
+    }
+
+    #[test]
+    fn interpolate_env_vars_should_fail_if_a_variable_is_not_defined() {
+        // Act
+        let not_defined = interpolate_env_vars("${TORNADO_EXECUTOR_COMMON_TEST_NOT_DEFINED}");
+        let not_closed = interpolate_env_vars("${TORNADO_EXECUTOR_COMMON_TEST_TENANT");
+
+        // Assert
+        assert!(matches!(not_defined, Err(ExecutorError::ConfigurationError { .. })));
+        assert!(matches!(not_closed, Err(ExecutorError::ConfigurationError { .. })));
+    }
+
+    #[test]
+    fn build_extra_headers_should_interpolate_the_header_values() {
+        // Arrange
+        std::env::set_var("TORNADO_EXECUTOR_COMMON_TEST_API_KEY", "secret");
+        let mut extra_headers = HashMap::new();
+        extra_headers
+            .insert("X-Api-Key".to_owned(), "${TORNADO_EXECUTOR_COMMON_TEST_API_KEY}".to_owned());
+
+        // Act
+        let headers = build_extra_headers(&extra_headers).unwrap();
+
+        // Assert
+        assert_eq!("secret", headers.get("x-api-key").unwrap());
+    }
+
+    #[test]
+    fn build_extra_headers_should_fail_if_a_header_name_is_not_valid() {
+        // Arrange
+        let mut extra_headers = HashMap::new();
+        extra_headers.insert("X Api Key".to_owned(), "value".to_owned());
+
+        // Act
+        let result = build_extra_headers(&extra_headers);
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::ConfigurationError { .. })));
+    }
+
+    #[test]
+    fn invalid_template_error_should_not_be_retried() {
+        // Arrange
+        let error = ExecutorError::InvalidTemplateError {
+            template: "${}".to_owned(),
+            message: "EmptyAccessorError".to_owned(),
+        };
+
+        // Act & Assert
+        assert!(!error.can_retry());
+    }
+}
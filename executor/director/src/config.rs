@@ -1,8 +1,10 @@
 use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::Duration;
-use tornado_executor_common::ExecutorError;
+use tornado_common_api::Payload;
+use tornado_executor_common::{build_extra_headers, ExecutorError};
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct DirectorClientConfig {
@@ -20,6 +22,24 @@ pub struct DirectorClientConfig {
 
     /// The call timeout in seconds. Default is 10 seconds
     pub timeout_secs: Option<u64>,
+
+    /// The maximum number of idle connections kept open for each host.
+    /// If not set, the number of idle connections is not limited
+    pub pool_max_idle_per_host: Option<usize>,
+
+    /// The time in seconds after which an idle connection is closed. Default is 90 seconds
+    pub pool_idle_timeout_secs: Option<u64>,
+
+    /// A payload merged under the payload of every received Action.
+    /// In case of conflict, the values of the Action payload win.
+    #[serde(default)]
+    pub default_payload: Payload,
+
+    /// Additional HTTP headers sent with every request, e.g. the ones required by an API gateway.
+    /// The values can contain `${VARIABLE_NAME}` placeholders, replaced by the values
+    /// of the corresponding environment variables when the client is created.
+    #[serde(default)]
+    pub extra_headers: HashMap<String, String>,
 }
 
 #[derive(Clone)]
@@ -42,6 +62,17 @@ impl DirectorClientConfig {
             client_builder = client_builder.danger_accept_invalid_certs(true)
         }
 
+        if let Some(pool_max_idle_per_host) = self.pool_max_idle_per_host {
+            client_builder = client_builder.pool_max_idle_per_host(pool_max_idle_per_host)
+        }
+
+        if let Some(pool_idle_timeout_secs) = self.pool_idle_timeout_secs {
+            client_builder =
+                client_builder.pool_idle_timeout(Duration::from_secs(pool_idle_timeout_secs))
+        }
+
+        client_builder = client_builder.default_headers(build_extra_headers(&self.extra_headers)?);
+
         let client = client_builder.build().map_err(|err| ExecutorError::ConfigurationError {
             message: format!("Error while building DirectorClient. Err: {:?}", err),
         })?;
@@ -49,3 +80,30 @@ impl DirectorClientConfig {
         Ok(ApiClient { server_api_url: self.server_api_url.clone(), http_auth_header, client })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_build_a_client_with_custom_pool_settings() {
+        // Arrange
+        let config = DirectorClientConfig {
+            server_api_url: "http://localhost:8080".to_owned(),
+            username: "".to_owned(),
+            password: "".to_owned(),
+            disable_ssl_verification: false,
+            timeout_secs: None,
+            pool_max_idle_per_host: Some(4),
+            pool_idle_timeout_secs: Some(30),
+            default_payload: Default::default(),
+            extra_headers: Default::default(),
+        };
+
+        // Act
+        let client = config.new_client();
+
+        // Assert
+        assert!(client.is_ok());
+    }
+}
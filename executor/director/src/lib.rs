@@ -6,7 +6,7 @@ use std::sync::Arc;
 use tornado_common_api::Action;
 use tornado_common_api::Payload;
 use tornado_common_api::ValueExt;
-use tornado_executor_common::{ExecutorError, StatelessExecutor};
+use tornado_executor_common::{merge_default_payload, ExecutorError, StatelessExecutor};
 use tracing::instrument;
 
 pub mod config;
@@ -46,6 +46,7 @@ impl DirectorActionName {
 #[derive(Clone)]
 pub struct DirectorExecutor {
     api_client: ApiClient,
+    default_payload: Payload,
 }
 
 impl std::fmt::Display for DirectorExecutor {
@@ -57,7 +58,10 @@ impl std::fmt::Display for DirectorExecutor {
 
 impl DirectorExecutor {
     pub fn new(config: DirectorClientConfig) -> Result<DirectorExecutor, ExecutorError> {
-        Ok(DirectorExecutor { api_client: config.new_client()? })
+        Ok(DirectorExecutor {
+            api_client: config.new_client()?,
+            default_payload: config.default_payload,
+        })
     }
 
     fn get_payload<'a>(&self, payload: &'a Payload) -> Result<&'a Payload, ExecutorError> {
@@ -195,9 +199,13 @@ impl DirectorExecutor {
 #[async_trait::async_trait(?Send)]
 impl StatelessExecutor for DirectorExecutor {
     #[tracing::instrument(level = "info", skip_all, err, fields(otel.name = format!("Execute Action: {}", &action.id).as_str(), otel.kind = "Consumer"))]
-    async fn execute(&self, action: Arc<Action>) -> Result<(), ExecutorError> {
+    async fn execute(&self, mut action: Arc<Action>) -> Result<(), ExecutorError> {
         trace!("DirectorExecutor - received action: \n[{:?}]", action);
 
+        if !self.default_payload.is_empty() {
+            merge_default_payload(&mut Arc::make_mut(&mut action).payload, &self.default_payload);
+        }
+
         let action = self.parse_action(&action)?;
 
         self.perform_request(action).await
@@ -222,10 +230,14 @@ mod test {
         // Arrange
         let executor = DirectorExecutor::new(DirectorClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: "".to_owned(),
+            default_payload: Default::default(),
+            extra_headers: Default::default(),
         })
         .unwrap();
 
@@ -248,10 +260,14 @@ mod test {
         // Arrange
         let executor = DirectorExecutor::new(DirectorClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: "".to_owned(),
+            default_payload: Default::default(),
+            extra_headers: Default::default(),
         })
         .unwrap();
 
@@ -274,10 +290,14 @@ mod test {
         // Arrange
         let executor = DirectorExecutor::new(DirectorClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: "".to_owned(),
+            default_payload: Default::default(),
+            extra_headers: Default::default(),
         })
         .unwrap();
 
@@ -47,6 +47,10 @@ async fn should_perform_a_post_request() {
                 password: "".to_owned(),
                 username: "".to_owned(),
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
+                default_payload: Default::default(),
+                extra_headers: Default::default(),
             };
 
             actix_rt::spawn(async move {
@@ -107,10 +111,14 @@ async fn should_return_object_already_existing_error_in_case_of_422_status_code(
 
     let executor = DirectorExecutor::new(DirectorClientConfig {
         timeout_secs: None,
+        pool_max_idle_per_host: None,
+        pool_idle_timeout_secs: None,
         username: "".to_owned(),
         password: "".to_owned(),
         disable_ssl_verification: true,
         server_api_url: server.url(""),
+        default_payload: Default::default(),
+        extra_headers: Default::default(),
     })
     .unwrap();
 
@@ -134,3 +142,163 @@ async fn should_return_object_already_existing_error_in_case_of_422_status_code(
     // Assert
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn should_apply_the_default_payload_if_not_set_in_the_action() {
+    // Arrange
+    let server = MockServer::start();
+
+    let mock = server.mock(|when, then| {
+        when.method(POST).path("/host").json_body(json!({
+            "object_type": "host",
+            "object_name": "my_host",
+            "zone": "default_zone"
+        }));
+        then.status(201);
+    });
+
+    let executor = DirectorExecutor::new(DirectorClientConfig {
+        timeout_secs: None,
+        pool_max_idle_per_host: None,
+        pool_idle_timeout_secs: None,
+        username: "".to_owned(),
+        password: "".to_owned(),
+        disable_ssl_verification: true,
+        server_api_url: server.url(""),
+        default_payload: json!({
+            DIRECTOR_ACTION_PAYLOAD_KEY: {
+                "zone": "default_zone"
+            }
+        })
+        .as_object()
+        .unwrap()
+        .clone(),
+        extra_headers: Default::default(),
+    })
+    .unwrap();
+
+    let mut action = Action::new("");
+    action
+        .payload
+        .insert(DIRECTOR_ACTION_NAME_KEY.to_owned(), Value::String("create_host".to_owned()));
+    action.payload.insert(
+        DIRECTOR_ACTION_PAYLOAD_KEY.to_owned(),
+        json!({
+            "object_type": "host",
+            "object_name": "my_host"
+        }),
+    );
+
+    // Act
+    let result = executor.execute(action.into()).await;
+
+    // Assert
+    assert!(result.is_ok());
+    mock.assert();
+}
+
+#[tokio::test]
+async fn should_not_override_the_action_payload_with_the_default_payload() {
+    // Arrange
+    let server = MockServer::start();
+
+    let mock = server.mock(|when, then| {
+        when.method(POST).path("/host").json_body(json!({
+            "object_type": "host",
+            "object_name": "my_host",
+            "zone": "action_zone"
+        }));
+        then.status(201);
+    });
+
+    let executor = DirectorExecutor::new(DirectorClientConfig {
+        timeout_secs: None,
+        pool_max_idle_per_host: None,
+        pool_idle_timeout_secs: None,
+        username: "".to_owned(),
+        password: "".to_owned(),
+        disable_ssl_verification: true,
+        server_api_url: server.url(""),
+        default_payload: json!({
+            DIRECTOR_ACTION_PAYLOAD_KEY: {
+                "zone": "default_zone"
+            }
+        })
+        .as_object()
+        .unwrap()
+        .clone(),
+        extra_headers: Default::default(),
+    })
+    .unwrap();
+
+    let mut action = Action::new("");
+    action
+        .payload
+        .insert(DIRECTOR_ACTION_NAME_KEY.to_owned(), Value::String("create_host".to_owned()));
+    action.payload.insert(
+        DIRECTOR_ACTION_PAYLOAD_KEY.to_owned(),
+        json!({
+            "object_type": "host",
+            "object_name": "my_host",
+            "zone": "action_zone"
+        }),
+    );
+
+    // Act
+    let result = executor.execute(action.into()).await;
+
+    // Assert
+    assert!(result.is_ok());
+    mock.assert();
+}
+
+#[tokio::test]
+async fn should_send_the_extra_headers() {
+    // Arrange
+    let server = MockServer::start();
+
+    let mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/host")
+            .header("X-Forwarded-Tenant", "tenant_director")
+            .header("X-Gateway-Key", "static_key");
+        then.status(201);
+    });
+
+    std::env::set_var("TORNADO_DIRECTOR_TEST_TENANT", "tenant_director");
+
+    let executor = DirectorExecutor::new(DirectorClientConfig {
+        timeout_secs: None,
+        pool_max_idle_per_host: None,
+        pool_idle_timeout_secs: None,
+        username: "".to_owned(),
+        password: "".to_owned(),
+        disable_ssl_verification: true,
+        server_api_url: server.url(""),
+        default_payload: Default::default(),
+        extra_headers: hashmap![
+            "X-Forwarded-Tenant".to_owned() => "${TORNADO_DIRECTOR_TEST_TENANT}".to_owned(),
+            "X-Gateway-Key".to_owned() => "static_key".to_owned(),
+        ],
+    })
+    .unwrap();
+
+    let mut action = Action::new("");
+    action
+        .payload
+        .insert(DIRECTOR_ACTION_NAME_KEY.to_owned(), Value::String("create_host".to_owned()));
+    action.payload.insert(
+        DIRECTOR_ACTION_PAYLOAD_KEY.to_owned(),
+        json!({
+            "object_type": "host",
+            "object_name": "my_host"
+        }),
+    );
+
+    // Act
+    let result = executor.execute(action.into()).await;
+
+    // Assert
+    assert!(result.is_ok());
+    mock.assert();
+}
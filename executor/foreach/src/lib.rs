@@ -12,8 +12,13 @@ const FOREACH_ACTIONS_KEY: &str = "actions";
 const FOREACH_ACTION_ID_KEY: &str = "id";
 const FOREACH_ACTION_PAYLOAD_KEY: &str = "payload";
 
+/// The default maximum nesting level of the arrays and objects of an action payload
+/// in which the placeholders are resolved.
+pub const DEFAULT_MAX_RESOLUTION_DEPTH: usize = 64;
+
 pub struct ForEachExecutor {
     bus: Arc<dyn EventBus>,
+    max_resolution_depth: usize,
 }
 
 pub struct Params<'a> {
@@ -30,7 +35,16 @@ impl std::fmt::Display for ForEachExecutor {
 
 impl ForEachExecutor {
     pub fn new(bus: Arc<dyn EventBus>) -> Self {
-        Self { bus }
+        Self::new_with_max_resolution_depth(bus, DEFAULT_MAX_RESOLUTION_DEPTH)
+    }
+
+    /// Creates a ForEachExecutor that fails to resolve the actions whose payload contains
+    /// arrays and objects nested deeper than `max_resolution_depth`.
+    pub fn new_with_max_resolution_depth(
+        bus: Arc<dyn EventBus>,
+        max_resolution_depth: usize,
+    ) -> Self {
+        Self { bus, max_resolution_depth }
     }
 
     #[instrument(level = "debug", name = "Extract parameters for Executor", skip_all)]
@@ -84,8 +98,12 @@ impl StatelessExecutor for ForEachExecutor {
     async fn execute(&self, action: Arc<Action>) -> Result<(), ExecutorError> {
         trace!("ForEachExecutor - received action: \n[{:?}]", action);
 
-        let Params { values, actions } =
+        let Params { values, mut actions } =
             self.extract_params_from_payload(&action.payload, action.created_ms)?;
+        // The internal Actions are correlated to the same Event of the foreach Action
+        for internal_action in actions.iter_mut() {
+            internal_action.trace_id = action.trace_id.clone();
+        }
 
         let execution_span = tracing::debug_span!(
             "ForEachExecutor",
@@ -101,12 +119,24 @@ impl StatelessExecutor for ForEachExecutor {
                 let mut item = Map::new();
                 item.insert(FOREACH_ITEM_KEY.to_owned(), value.clone());
 
-                let result = resolve_action(&Value::Object(item), action.clone()).map(|action| {
-                    self.bus.publish_action(ActionMessage(TracedAction {
-                        action: Arc::new(action),
-                        span: execution_span.clone(),
-                    }))
-                });
+                let result =
+                    resolve_action(&Value::Object(item), action.clone(), self.max_resolution_depth)
+                        .and_then(|action| {
+                            self.bus
+                                .publish_action(ActionMessage(
+                                    TracedAction {
+                                        action: Arc::new(action),
+                                        span: execution_span.clone(),
+                                    },
+                                    None,
+                                ))
+                                .map_err(|err| ExecutorError::ActionExecutionError {
+                                    message: format!("{}", err),
+                                    can_retry: false,
+                                    code: None,
+                                    data: Default::default(),
+                                })
+                        });
 
                 if let Err(err) = result {
                     warn!(
@@ -140,23 +170,77 @@ fn to_action(value: &Value, action_created_ms: u64) -> Result<Action, ExecutorEr
     Err(ExecutorError::MissingArgumentError { message: message.to_owned() })
 }
 
-fn resolve_action(item: &Value, mut action: Action) -> Result<Action, ExecutorError> {
+fn resolve_action(
+    item: &Value,
+    mut action: Action,
+    max_depth: usize,
+) -> Result<Action, ExecutorError> {
+    action.id = resolve_id(item, &action.id)?;
     for (_key, element) in action.payload.iter_mut() {
-        resolve_payload(item, element)?;
+        resolve_payload(item, element, max_depth)?;
     }
     Ok(action)
 }
 
-fn resolve_payload(item: &Value, mut value: &mut Value) -> Result<(), ExecutorError> {
+/// Resolves the placeholders in the action id.
+/// The resolved id must be a non empty text, otherwise the action could not be routed.
+fn resolve_id(item: &Value, id: &str) -> Result<String, ExecutorError> {
+    let parser = ParserBuilder::default().build_parser(id).map_err(|err| {
+        ExecutorError::InvalidTemplateError {
+            template: id.to_owned(),
+            message: format!("Cannot build parser for action id. Err: {:?}", err),
+        }
+    })?;
+
+    let resolved_id = match parser.parse_value(item, "").as_deref() {
+        Some(Value::String(text)) => text.to_owned(),
+        Some(Value::Number(number)) => number.to_string(),
+        _ => {
+            return Err(ExecutorError::ActionExecutionError {
+                can_retry: false,
+                message: format!("ForEachExecutor - Cannot resolve the action id [{}]", id),
+                code: None,
+                data: Default::default(),
+            })
+        }
+    };
+
+    if resolved_id.trim().is_empty() {
+        return Err(ExecutorError::ActionExecutionError {
+            can_retry: false,
+            message: format!("ForEachExecutor - The action id [{}] resolves to an empty id", id),
+            code: None,
+            data: Default::default(),
+        });
+    }
+
+    Ok(resolved_id)
+}
+
+/// Resolves the placeholders in the value. `remaining_depth` is the number of nested levels
+/// of arrays and objects that can still be traversed.
+fn resolve_payload(
+    item: &Value,
+    mut value: &mut Value,
+    remaining_depth: usize,
+) -> Result<(), ExecutorError> {
+    if remaining_depth == 0 && matches!(value, Value::Array(_) | Value::Object(_)) {
+        return Err(ExecutorError::ActionExecutionError {
+            can_retry: false,
+            message:
+                "ForEachExecutor - The action payload exceeds the max placeholder resolution depth"
+                    .to_owned(),
+            code: None,
+            data: Default::default(),
+        });
+    }
     match &mut value {
         Value::String(text) => {
             if let Some(parse_result) = ParserBuilder::default()
                 .build_parser(text)
-                .map_err(|err| ExecutorError::ActionExecutionError {
-                    can_retry: false,
-                    message: format!("Cannot build parser for [{}]. Err: {:?}", text, err),
-                    code: None,
-                    data: Default::default(),
+                .map_err(|err| ExecutorError::InvalidTemplateError {
+                    template: text.to_owned(),
+                    message: format!("Cannot build parser. Err: {:?}", err),
                 })?
                 .parse_value(item, "")
             {
@@ -165,12 +249,12 @@ fn resolve_payload(item: &Value, mut value: &mut Value) -> Result<(), ExecutorEr
         }
         Value::Array(values) => {
             for element in values.iter_mut() {
-                resolve_payload(item, element)?;
+                resolve_payload(item, element, remaining_depth - 1)?;
             }
         }
         Value::Object(values) => {
             for (_key, element) in values.iter_mut() {
-                resolve_payload(item, element)?;
+                resolve_payload(item, element, remaining_depth - 1)?;
             }
         }
         _ => {}
@@ -187,7 +271,7 @@ mod test {
         collections::{hash_map::Entry, HashMap},
         sync::RwLock,
     };
-    use tornado_common_api::ValueExt;
+    use tornado_common_api::{RetriableError, ValueExt};
     use tornado_network_simple::SimpleEventBus;
 
     #[test]
@@ -758,4 +842,160 @@ mod test {
             vec![Value::String("first".to_owned()), Value::String("second".to_owned())];
         assert_eq!(&expected_array, value);
     }
+
+    #[tokio::test]
+    async fn should_resolve_the_placeholders_in_the_action_id() {
+        // Arrange
+        let execution_results = Arc::new(RwLock::new(vec![]));
+
+        let mut bus = SimpleEventBus::new();
+        for action_id in ["notify_eu", "notify_us"] {
+            let execution_results = execution_results.clone();
+            bus.subscribe_to_action(
+                action_id,
+                Box::new(move |action| {
+                    let mut lock = execution_results.write().unwrap();
+                    lock.push((action_id, action));
+                }),
+            );
+        }
+
+        let executor = ForEachExecutor::new(Arc::new(bus));
+
+        let mut action = Action::new("");
+        action.payload.insert(
+            "target".to_owned(),
+            json!([{ "region": "eu", "host": "host1" }, { "region": "us", "host": "host2" }]),
+        );
+        action.payload.insert(
+            "actions".to_owned(),
+            json!([{ "id": "notify_${item.region}", "payload": { "host": "${item.host}" } }]),
+        );
+
+        // Act
+        let result = executor.execute(action.into()).await;
+
+        // Assert
+        assert!(result.is_ok());
+
+        let lock = execution_results.read().unwrap();
+        assert_eq!(2, lock.len());
+
+        let (subscriber_id, action) = &lock[0];
+        assert_eq!("notify_eu", *subscriber_id);
+        assert_eq!("notify_eu", action.0.action.id);
+        assert_eq!(Some(&json!("host1")), action.0.action.payload.get("host"));
+
+        let (subscriber_id, action) = &lock[1];
+        assert_eq!("notify_us", *subscriber_id);
+        assert_eq!("notify_us", action.0.action.id);
+        assert_eq!(Some(&json!("host2")), action.0.action.payload.get("host"));
+    }
+
+    #[test]
+    fn should_fail_if_the_action_id_resolves_to_an_empty_id() {
+        // Arrange
+        let action = Action::new("${item.region}");
+        let item = json!({ "item": { "region": "" } });
+
+        // Act
+        let result = resolve_action(&item, action, DEFAULT_MAX_RESOLUTION_DEPTH);
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::ActionExecutionError { .. })));
+    }
+
+    #[test]
+    fn should_fail_if_the_action_id_cannot_be_resolved() {
+        // Arrange
+        let action = Action::new("notify_${item.region}");
+        let item = json!({ "item": { "host": "host1" } });
+
+        // Act
+        let result = resolve_action(&item, action, DEFAULT_MAX_RESOLUTION_DEPTH);
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::ActionExecutionError { .. })));
+    }
+
+    fn nested_arrays(depth: usize) -> Value {
+        let mut value = Value::String("${item}".to_owned());
+        for _ in 0..depth {
+            value = Value::Array(vec![value]);
+        }
+        value
+    }
+
+    #[test]
+    fn should_resolve_placeholders_nested_up_to_the_max_depth() {
+        // Arrange
+        let mut action = Action::new("id_one");
+        action.payload.insert("nested".to_owned(), nested_arrays(5));
+        let item = json!({ "item": "value" });
+
+        // Act
+        let result = resolve_action(&item, action, 5).unwrap();
+
+        // Assert
+        let mut expected = Value::String("value".to_owned());
+        for _ in 0..5 {
+            expected = Value::Array(vec![expected]);
+        }
+        assert_eq!(Some(&expected), result.payload.get("nested"));
+    }
+
+    #[test]
+    fn should_fail_to_resolve_placeholders_nested_beyond_the_max_depth() {
+        // Arrange
+        let mut action = Action::new("id_one");
+        action.payload.insert("nested".to_owned(), nested_arrays(DEFAULT_MAX_RESOLUTION_DEPTH + 1));
+        let item = json!({ "item": "value" });
+
+        // Act
+        let result = resolve_action(&item, action, DEFAULT_MAX_RESOLUTION_DEPTH);
+
+        // Assert
+        match result {
+            Err(ExecutorError::ActionExecutionError { can_retry, .. }) => assert!(!can_retry),
+            _ => panic!("Expected an ActionExecutionError"),
+        }
+    }
+
+    #[test]
+    fn should_fail_with_a_not_retryable_error_if_the_action_id_template_is_malformed() {
+        // Arrange
+        let action = Action::new("${}");
+        let item = json!({ "item": "value" });
+
+        // Act
+        let result = resolve_action(&item, action, DEFAULT_MAX_RESOLUTION_DEPTH);
+
+        // Assert
+        let err = result.unwrap_err();
+        assert!(!err.can_retry());
+        match err {
+            ExecutorError::InvalidTemplateError { template, .. } => assert_eq!("${}", template),
+            err => panic!("Expected an InvalidTemplateError. Found: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn should_fail_with_a_not_retryable_error_if_a_payload_template_is_malformed() {
+        // Arrange
+        let mut action = Action::new("id_one");
+        action.payload.insert("host".to_owned(), json!("${item.host}"));
+        action.payload.insert("message".to_owned(), json!(["${}"]));
+        let item = json!({ "item": { "host": "host1" } });
+
+        // Act
+        let result = resolve_action(&item, action, DEFAULT_MAX_RESOLUTION_DEPTH);
+
+        // Assert
+        let err = result.unwrap_err();
+        assert!(!err.can_retry());
+        match err {
+            ExecutorError::InvalidTemplateError { template, .. } => assert_eq!("${}", template),
+            err => panic!("Expected an InvalidTemplateError. Found: {:?}", err),
+        }
+    }
 }
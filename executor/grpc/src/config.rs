@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct GrpcExecutorConfig {
+    /// The paths of the files with the descriptors of the invoked services.
+    /// Each file contains a serialized FileDescriptorSet, as generated by
+    /// `protoc --include_imports --descriptor_set_out=<path>`.
+    pub descriptor_set_paths: Vec<String>,
+
+    /// The request timeout in seconds. Default is 10 seconds
+    pub timeout_secs: Option<u64>,
+}
@@ -0,0 +1,318 @@
+use crate::config::GrpcExecutorConfig;
+use log::*;
+use maplit::*;
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor};
+use std::convert::TryFrom;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::Endpoint;
+use tonic::{Code, Status};
+use tornado_common_api::{Action, Map, Value, ValueExt};
+use tornado_executor_common::{ExecutorError, StatelessExecutor};
+use tracing::instrument;
+
+pub mod config;
+
+pub const GRPC_ENDPOINT_KEY: &str = "endpoint";
+pub const GRPC_METHOD_KEY: &str = "method";
+pub const GRPC_REQUEST_KEY: &str = "request";
+
+pub const GRPC_STATUS_EXECUTOR_ERROR_CODE: &str = "GrpcStatus";
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// An executor that invokes the unary methods of gRPC services
+pub struct GrpcExecutor {
+    descriptor_pool: DescriptorPool,
+    timeout: Duration,
+}
+
+impl std::fmt::Display for GrpcExecutor {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str("GrpcExecutor")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct GrpcCallAction {
+    pub endpoint: String,
+    pub method: MethodDescriptor,
+    pub request: DynamicMessage,
+}
+
+impl GrpcExecutor {
+    pub fn new(config: GrpcExecutorConfig) -> Result<GrpcExecutor, ExecutorError> {
+        let mut descriptor_pool = DescriptorPool::new();
+        for path in &config.descriptor_set_paths {
+            let descriptor_set =
+                std::fs::read(path).map_err(|err| ExecutorError::ConfigurationError {
+                    message: format!(
+                        "GrpcExecutor - Cannot read the descriptor set file [{}]. Err: {:?}",
+                        path, err
+                    ),
+                })?;
+            descriptor_pool.decode_file_descriptor_set(descriptor_set.as_slice()).map_err(
+                |err| ExecutorError::ConfigurationError {
+                    message: format!(
+                        "GrpcExecutor - Invalid descriptor set file [{}]. Err: {}",
+                        path, err
+                    ),
+                },
+            )?;
+        }
+        let timeout = Duration::from_secs(config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        Ok(GrpcExecutor { descriptor_pool, timeout })
+    }
+
+    #[instrument(level = "debug", name = "Extract parameters for Executor", skip_all)]
+    fn parse_action(&self, action: &Action) -> Result<GrpcCallAction, ExecutorError> {
+        let endpoint = get_text(action, GRPC_ENDPOINT_KEY)?.to_owned();
+        let method = self.find_method(get_text(action, GRPC_METHOD_KEY)?)?;
+
+        let request = action
+            .payload
+            .get(GRPC_REQUEST_KEY)
+            .cloned()
+            .unwrap_or_else(|| Value::Object(Map::new()));
+        let request = DynamicMessage::deserialize(method.input(), request).map_err(|err| {
+            ExecutorError::UnknownArgumentError {
+                message: format!(
+                    "GrpcExecutor - The [{}] is not a valid [{}] message. Err: {}",
+                    GRPC_REQUEST_KEY,
+                    method.input().full_name(),
+                    err
+                ),
+            }
+        })?;
+
+        Ok(GrpcCallAction { endpoint, method, request })
+    }
+
+    /// Returns the method with the given name in the 'package.Service/Method' format
+    fn find_method(&self, method_name: &str) -> Result<MethodDescriptor, ExecutorError> {
+        let (service_name, method_name) =
+            method_name.rsplit_once('/').ok_or_else(|| ExecutorError::UnknownArgumentError {
+                message: format!(
+                    "GrpcExecutor - The [{}] should be in the 'package.Service/Method' format. Found: {}",
+                    GRPC_METHOD_KEY, method_name
+                ),
+            })?;
+        let method = self
+            .descriptor_pool
+            .get_service_by_name(service_name)
+            .and_then(|service| service.methods().find(|method| method.name() == method_name))
+            .ok_or_else(|| ExecutorError::UnknownArgumentError {
+                message: format!(
+                    "GrpcExecutor - Method [{}] of service [{}] not found in the descriptors",
+                    method_name, service_name
+                ),
+            })?;
+        if method.is_client_streaming() || method.is_server_streaming() {
+            return Err(ExecutorError::UnknownArgumentError {
+                message: format!(
+                    "GrpcExecutor - Method [{}] is a streaming method. Only unary methods are supported",
+                    method.full_name()
+                ),
+            });
+        }
+        Ok(method)
+    }
+
+    #[instrument(level = "debug", name = "GrpcExecutor", skip_all, fields(otel.name = format!("Call gRPC method [{}]", grpc_action.method.full_name()).as_str()))]
+    pub async fn call(&self, grpc_action: GrpcCallAction) -> Result<(), ExecutorError> {
+        let GrpcCallAction { endpoint, method, request } = grpc_action;
+        let path = format!("/{}/{}", method.parent_service().full_name(), method.name());
+
+        let response = tokio::time::timeout(self.timeout, async {
+            let channel = Endpoint::from_shared(endpoint.clone())
+                .map_err(|err| ExecutorError::UnknownArgumentError {
+                    message: format!(
+                        "GrpcExecutor - Invalid [{}] [{}]. Err: {}",
+                        GRPC_ENDPOINT_KEY, endpoint, err
+                    ),
+                })?
+                .connect()
+                .await
+                .map_err(|err| {
+                    to_retryable_error(
+                        &endpoint,
+                        &path,
+                        format!("Connection failed. Err: {:?}", err),
+                    )
+                })?;
+
+            let mut client = tonic::client::Grpc::new(channel);
+            client.ready().await.map_err(|err| {
+                to_retryable_error(&endpoint, &path, format!("Service not ready. Err: {:?}", err))
+            })?;
+
+            let path_and_query = PathAndQuery::try_from(path.as_str()).map_err(|err| {
+                ExecutorError::UnknownArgumentError {
+                    message: format!("GrpcExecutor - Invalid method path [{}]. Err: {}", path, err),
+                }
+            })?;
+            client
+                .unary(
+                    tonic::Request::new(request),
+                    path_and_query,
+                    DynamicMessageCodec { output: method.output() },
+                )
+                .await
+                .map_err(|status| to_status_error(&endpoint, &path, status))
+        })
+        .await
+        .map_err(|_| {
+            to_retryable_error(
+                &endpoint,
+                &path,
+                format!("No response within {} seconds", self.timeout.as_secs()),
+            )
+        })??;
+
+        debug!(
+            "GrpcExecutor - Method [{}] of endpoint [{}] returned: {:?}",
+            path,
+            endpoint,
+            response.get_ref()
+        );
+        Ok(())
+    }
+}
+
+fn get_text<'a>(action: &'a Action, key: &str) -> Result<&'a str, ExecutorError> {
+    action.payload.get(key).and_then(ValueExt::get_text).ok_or_else(|| {
+        ExecutorError::MissingArgumentError {
+            message: format!("GrpcExecutor - [{}] not specified", key),
+        }
+    })
+}
+
+/// The status codes of the failures that could succeed if the call is repeated later
+fn is_retryable(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Unavailable
+            | Code::DeadlineExceeded
+            | Code::ResourceExhausted
+            | Code::Aborted
+            | Code::Cancelled
+    )
+}
+
+fn to_status_error(endpoint: &str, path: &str, status: Status) -> ExecutorError {
+    ExecutorError::ActionExecutionError {
+        can_retry: is_retryable(status.code()),
+        message: format!(
+            "GrpcExecutor - Method [{}] returned status [{:?}]. Message: {}",
+            path,
+            status.code(),
+            status.message()
+        ),
+        code: Some(GRPC_STATUS_EXECUTOR_ERROR_CODE),
+        data: hashmap![
+            "endpoint" => endpoint.into(),
+            "method" => path.into(),
+            "grpc_status" => (status.code() as i32).into(),
+        ]
+        .into(),
+    }
+}
+
+fn to_retryable_error(endpoint: &str, path: &str, message: String) -> ExecutorError {
+    ExecutorError::ActionExecutionError {
+        can_retry: true,
+        message: format!("GrpcExecutor - {}", message),
+        code: None,
+        data: hashmap!["endpoint" => endpoint.into(), "method" => path.into()].into(),
+    }
+}
+
+/// Encodes and decodes the messages of a method known only at runtime from its descriptor
+struct DynamicMessageCodec {
+    output: MessageDescriptor,
+}
+
+impl Codec for DynamicMessageCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicMessageEncoder;
+    type Decoder = DynamicMessageDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicMessageEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicMessageDecoder { output: self.output.clone() }
+    }
+}
+
+struct DynamicMessageEncoder;
+
+impl Encoder for DynamicMessageEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst).map_err(|err| Status::internal(err.to_string()))
+    }
+}
+
+struct DynamicMessageDecoder {
+    output: MessageDescriptor,
+}
+
+impl Decoder for DynamicMessageDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        DynamicMessage::decode(self.output.clone(), src)
+            .map(Some)
+            .map_err(|err| Status::internal(err.to_string()))
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl StatelessExecutor for GrpcExecutor {
+    #[tracing::instrument(level = "info", skip_all, err, fields(otel.name = format!("Execute Action: {}", &action.id).as_str(), otel.kind = "Consumer"))]
+    async fn execute(&self, action: Arc<Action>) -> Result<(), ExecutorError> {
+        trace!("GrpcExecutor - received action: \n[{:?}]", action);
+        let grpc_action = self.parse_action(&action)?;
+        self.call(grpc_action).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn should_retry_only_the_transient_status_codes() {
+        // Assert
+        assert!(is_retryable(Code::Unavailable));
+        assert!(is_retryable(Code::DeadlineExceeded));
+        assert!(is_retryable(Code::ResourceExhausted));
+        assert!(!is_retryable(Code::InvalidArgument));
+        assert!(!is_retryable(Code::NotFound));
+        assert!(!is_retryable(Code::PermissionDenied));
+        assert!(!is_retryable(Code::Unimplemented));
+    }
+
+    #[test]
+    fn should_fail_if_the_descriptor_set_file_does_not_exist() {
+        // Act
+        let result = GrpcExecutor::new(GrpcExecutorConfig {
+            descriptor_set_paths: vec!["./not_existing.desc".to_owned()],
+            timeout_secs: None,
+        });
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::ConfigurationError { .. })));
+    }
+}
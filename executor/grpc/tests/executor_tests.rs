@@ -0,0 +1,293 @@
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    MethodDescriptorProto, ServiceDescriptorProto,
+};
+use serde_json::json;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::body::BoxBody;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{empty_body, http, Body, BoxFuture, Context, Poll, Service, StdError};
+use tonic::server::{NamedService, UnaryService};
+use tonic::{Request, Response, Status};
+use tornado_common_api::{Action, Event, Map, Payload, Value};
+use tornado_engine_matcher::config::rule::ConfigAction;
+use tornado_engine_matcher::matcher::action::ActionResolverBuilder;
+use tornado_executor_common::{ExecutorError, StatelessExecutor};
+use tornado_executor_grpc::config::GrpcExecutorConfig;
+use tornado_executor_grpc::GrpcExecutor;
+
+#[derive(Clone, PartialEq, Message)]
+struct EchoRequest {
+    #[prost(string, tag = "1")]
+    message: String,
+    #[prost(int32, tag = "2")]
+    count: i32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct EchoResponse {
+    #[prost(string, tag = "1")]
+    message: String,
+}
+
+/// A gRPC service with a single unary 'test.Echo/Echo' method.
+/// It sends the received requests through a channel and replies with an error
+/// status when the message of the request is the name of a status code.
+#[derive(Clone)]
+struct EchoService {
+    sender: UnboundedSender<EchoRequest>,
+}
+
+impl NamedService for EchoService {
+    const NAME: &'static str = "test.Echo";
+}
+
+impl UnaryService<EchoRequest> for EchoService {
+    type Response = EchoResponse;
+    type Future = BoxFuture<Response<EchoResponse>, Status>;
+
+    fn call(&mut self, request: Request<EchoRequest>) -> Self::Future {
+        let sender = self.sender.clone();
+        Box::pin(async move {
+            let request = request.into_inner();
+            match request.message.as_str() {
+                "InvalidArgument" => Err(Status::invalid_argument("wrong message")),
+                "Unavailable" => Err(Status::unavailable("try later")),
+                _ => {
+                    let message = request.message.repeat(request.count.max(1) as usize);
+                    sender.send(request).unwrap();
+                    Ok(Response::new(EchoResponse { message }))
+                }
+            }
+        })
+    }
+}
+
+impl<B> Service<http::Request<B>> for EchoService
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let service = self.clone();
+        Box::pin(async move {
+            if request.uri().path() == "/test.Echo/Echo" {
+                let mut grpc = tonic::server::Grpc::new(ProstCodec::default());
+                Ok(grpc.unary(service, request).await)
+            } else {
+                Ok(http::Response::builder()
+                    .status(200)
+                    .header("grpc-status", (tonic::Code::Unimplemented as i32).to_string())
+                    .header("content-type", "application/grpc")
+                    .body(empty_body())
+                    .unwrap())
+            }
+        })
+    }
+}
+
+async fn start_echo_server() -> (String, UnboundedReceiver<EchoRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(EchoService { sender })
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    (format!("http://127.0.0.1:{}", port), receiver)
+}
+
+fn field(name: &str, number: i32, field_type: Type) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.to_owned()),
+        json_name: Some(name.to_owned()),
+        number: Some(number),
+        label: Some(Label::Optional as i32),
+        r#type: Some(field_type as i32),
+        ..Default::default()
+    }
+}
+
+/// Writes the descriptor set of the 'test.Echo' service, as generated by protoc, to a temporary file
+fn write_descriptor_set() -> NamedTempFile {
+    let file = FileDescriptorProto {
+        name: Some("echo.proto".to_owned()),
+        package: Some("test".to_owned()),
+        syntax: Some("proto3".to_owned()),
+        message_type: vec![
+            DescriptorProto {
+                name: Some("EchoRequest".to_owned()),
+                field: vec![field("message", 1, Type::String), field("count", 2, Type::Int32)],
+                ..Default::default()
+            },
+            DescriptorProto {
+                name: Some("EchoResponse".to_owned()),
+                field: vec![field("message", 1, Type::String)],
+                ..Default::default()
+            },
+        ],
+        service: vec![ServiceDescriptorProto {
+            name: Some("Echo".to_owned()),
+            method: vec![MethodDescriptorProto {
+                name: Some("Echo".to_owned()),
+                input_type: Some(".test.EchoRequest".to_owned()),
+                output_type: Some(".test.EchoResponse".to_owned()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let descriptor_set_file = NamedTempFile::new().unwrap();
+    std::fs::write(
+        descriptor_set_file.path(),
+        FileDescriptorSet { file: vec![file] }.encode_to_vec(),
+    )
+    .unwrap();
+    descriptor_set_file
+}
+
+fn new_executor(descriptor_set_file: &NamedTempFile) -> GrpcExecutor {
+    GrpcExecutor::new(GrpcExecutorConfig {
+        descriptor_set_paths: vec![descriptor_set_file.path().to_str().unwrap().to_owned()],
+        timeout_secs: Some(5),
+    })
+    .unwrap()
+}
+
+fn echo_action(endpoint: &str, message: &str) -> Action {
+    let mut action = Action::new("grpc");
+    action.payload.insert("endpoint".to_owned(), json!(endpoint));
+    action.payload.insert("method".to_owned(), json!("test.Echo/Echo"));
+    action.payload.insert("request".to_owned(), json!({ "message": message }));
+    action
+}
+
+#[actix_rt::test]
+async fn should_call_the_method_with_a_templated_request() {
+    // Arrange
+    let (endpoint, mut received) = start_echo_server().await;
+    let descriptor_set_file = write_descriptor_set();
+    let executor = new_executor(&descriptor_set_file);
+
+    let mut config_action = ConfigAction {
+        id: "grpc".to_owned(),
+        payload: Map::new(),
+        when: None,
+        retry_strategy: None,
+    };
+    config_action.payload.insert("endpoint".to_owned(), json!(endpoint));
+    config_action.payload.insert("method".to_owned(), json!("test.Echo/Echo"));
+    config_action.payload.insert(
+        "request".to_owned(),
+        json!({ "message": "${event.payload.host}", "count": "${event.payload.count}" }),
+    );
+
+    let action_resolver = ActionResolverBuilder::new().build("rule", &config_action).unwrap();
+
+    let mut event_payload = Payload::new();
+    event_payload.insert("host".to_owned(), Value::String("host1".to_owned()));
+    event_payload.insert("count".to_owned(), json!(3));
+    let event = json!(Event::new_with_payload("monitoring", event_payload));
+    let action = action_resolver.resolve(&(&event, &mut Value::Null).into()).unwrap();
+
+    // Act
+    let result = executor.execute(Arc::new(action)).await;
+
+    // Assert
+    assert!(result.is_ok());
+    assert_eq!(
+        EchoRequest { message: "host1".to_owned(), count: 3 },
+        received.recv().await.unwrap()
+    );
+}
+
+#[actix_rt::test]
+async fn should_not_retry_an_invalid_argument_status() {
+    // Arrange
+    let (endpoint, _received) = start_echo_server().await;
+    let descriptor_set_file = write_descriptor_set();
+    let executor = new_executor(&descriptor_set_file);
+
+    // Act
+    let result = executor.execute(Arc::new(echo_action(&endpoint, "InvalidArgument"))).await;
+
+    // Assert
+    match result {
+        Err(ExecutorError::ActionExecutionError { can_retry, code, message, .. }) => {
+            assert!(!can_retry);
+            assert_eq!(Some("GrpcStatus"), code);
+            assert!(message.contains("InvalidArgument"));
+        }
+        _ => panic!("Expected an ActionExecutionError. Found: {:?}", result),
+    }
+}
+
+#[actix_rt::test]
+async fn should_retry_an_unavailable_status() {
+    // Arrange
+    let (endpoint, _received) = start_echo_server().await;
+    let descriptor_set_file = write_descriptor_set();
+    let executor = new_executor(&descriptor_set_file);
+
+    // Act
+    let result = executor.execute(Arc::new(echo_action(&endpoint, "Unavailable"))).await;
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(ExecutorError::ActionExecutionError { can_retry: true, code: Some("GrpcStatus"), .. })
+    ));
+}
+
+#[actix_rt::test]
+async fn should_retry_if_the_endpoint_is_not_reachable() {
+    // Arrange
+    let port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+    let descriptor_set_file = write_descriptor_set();
+    let executor = new_executor(&descriptor_set_file);
+
+    // Act
+    let result = executor
+        .execute(Arc::new(echo_action(&format!("http://127.0.0.1:{}", port), "hello")))
+        .await;
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(ExecutorError::ActionExecutionError { can_retry: true, code: None, .. })
+    ));
+}
+
+#[actix_rt::test]
+async fn should_fail_if_the_method_is_unknown() {
+    // Arrange
+    let descriptor_set_file = write_descriptor_set();
+    let executor = new_executor(&descriptor_set_file);
+    let mut action = echo_action("http://127.0.0.1:1", "hello");
+    action.payload.insert("method".to_owned(), json!("test.Echo/Shout"));
+
+    // Act
+    let result = executor.execute(Arc::new(action)).await;
+
+    // Assert
+    assert!(matches!(result, Err(ExecutorError::UnknownArgumentError { .. })));
+}
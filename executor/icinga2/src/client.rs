@@ -5,7 +5,7 @@ use maplit::*;
 use reqwest::{Client, Response};
 use serde::Serialize;
 use std::time::Duration;
-use tornado_executor_common::ExecutorError;
+use tornado_executor_common::{build_extra_headers, ExecutorError};
 
 #[derive(Clone)]
 pub struct ApiClient {
@@ -27,6 +27,18 @@ impl ApiClient {
             client_builder = client_builder.danger_accept_invalid_certs(true)
         }
 
+        if let Some(pool_max_idle_per_host) = config.pool_max_idle_per_host {
+            client_builder = client_builder.pool_max_idle_per_host(pool_max_idle_per_host)
+        }
+
+        if let Some(pool_idle_timeout_secs) = config.pool_idle_timeout_secs {
+            client_builder =
+                client_builder.pool_idle_timeout(Duration::from_secs(pool_idle_timeout_secs))
+        }
+
+        client_builder =
+            client_builder.default_headers(build_extra_headers(&config.extra_headers)?);
+
         let client = client_builder.build().map_err(|err| ExecutorError::ConfigurationError {
             message: format!("Error while building Icinga2Client. Err: {:?}", err),
         })?;
@@ -103,7 +115,10 @@ mod test {
             disable_ssl_verification: false,
             password: "".to_owned(),
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             server_api_url: "http://localhost".to_owned(),
+            extra_headers: Default::default(),
         };
 
         // Act & Assert
@@ -127,4 +142,25 @@ mod test {
             assert_eq!("http://127.0.0.1:8080", ApiClient::new(&config).unwrap().server_api_url);
         }
     }
+
+    #[test]
+    fn should_build_a_client_with_custom_pool_settings() {
+        // Arrange
+        let config = Icinga2ClientConfig {
+            username: "".to_owned(),
+            disable_ssl_verification: false,
+            password: "".to_owned(),
+            timeout_secs: None,
+            pool_max_idle_per_host: Some(4),
+            pool_idle_timeout_secs: Some(30),
+            server_api_url: "http://localhost".to_owned(),
+            extra_headers: Default::default(),
+        };
+
+        // Act
+        let client = ApiClient::new(&config);
+
+        // Assert
+        assert!(client.is_ok());
+    }
 }
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Deserialize, Serialize, Clone)]
 pub struct Icinga2ClientConfig {
@@ -16,4 +17,17 @@ pub struct Icinga2ClientConfig {
 
     /// The call timeout in seconds. Default is 10 seconds
     pub timeout_secs: Option<u64>,
+
+    /// The maximum number of idle connections kept open for each host.
+    /// If not set, the number of idle connections is not limited
+    pub pool_max_idle_per_host: Option<usize>,
+
+    /// The time in seconds after which an idle connection is closed. Default is 90 seconds
+    pub pool_idle_timeout_secs: Option<u64>,
+
+    /// Additional HTTP headers sent with every request, e.g. the ones required by an API gateway.
+    /// The values can contain `${VARIABLE_NAME}` placeholders, replaced by the values
+    /// of the corresponding environment variables when the client is created.
+    #[serde(default)]
+    pub extra_headers: HashMap<String, String>,
 }
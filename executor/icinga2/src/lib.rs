@@ -339,10 +339,13 @@ mod test {
         // Arrange
         let executor = Icinga2Executor::new(Icinga2ClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: "".to_owned(),
+            extra_headers: Default::default(),
         })
         .unwrap();
 
@@ -366,10 +369,13 @@ mod test {
         // Arrange
         let executor = Icinga2Executor::new(Icinga2ClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: "".to_owned(),
+            extra_headers: Default::default(),
         })
         .unwrap();
 
@@ -390,10 +396,13 @@ mod test {
         // Arrange
         let executor = Icinga2Executor::new(Icinga2ClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: "".to_owned(),
+            extra_headers: Default::default(),
         })
         .unwrap();
 
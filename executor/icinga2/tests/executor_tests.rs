@@ -50,6 +50,9 @@ async fn should_perform_a_post_request() {
                 password: "".to_owned(),
                 username: "".to_owned(),
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
+                extra_headers: Default::default(),
             };
 
             actix_rt::spawn(async move {
@@ -103,10 +106,13 @@ async fn should_return_object_not_existing_error_in_case_of_404_status_code() {
 
     let executor = Icinga2Executor::new(Icinga2ClientConfig {
         timeout_secs: None,
+        pool_max_idle_per_host: None,
+        pool_idle_timeout_secs: None,
         username: "".to_owned(),
         password: "".to_owned(),
         disable_ssl_verification: true,
         server_api_url: server.url(""),
+        extra_headers: Default::default(),
     })
     .unwrap();
 
@@ -153,10 +159,13 @@ async fn should_return_non_retryable_error_in_case_of_outdated_process_check_res
 
     let executor = Icinga2Executor::new(Icinga2ClientConfig {
         timeout_secs: None,
+        pool_max_idle_per_host: None,
+        pool_idle_timeout_secs: None,
         username: "".to_owned(),
         password: "".to_owned(),
         disable_ssl_verification: true,
         server_api_url: server.url(""),
+        extra_headers: Default::default(),
     })
     .unwrap();
 
@@ -189,3 +198,52 @@ async fn should_return_non_retryable_error_in_case_of_outdated_process_check_res
         }.into(),
     }))
 }
+
+#[tokio::test]
+async fn should_send_the_extra_headers() {
+    // Arrange
+    let server = MockServer::start();
+
+    let mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/v1/actions/icinga2-api-action")
+            .header("X-Forwarded-Tenant", "tenant_icinga2")
+            .header("X-Gateway-Key", "static_key");
+        then.body("{}").status(200);
+    });
+
+    std::env::set_var("TORNADO_ICINGA2_TEST_TENANT", "tenant_icinga2");
+
+    let executor = Icinga2Executor::new(Icinga2ClientConfig {
+        timeout_secs: None,
+        pool_max_idle_per_host: None,
+        pool_idle_timeout_secs: None,
+        username: "".to_owned(),
+        password: "".to_owned(),
+        disable_ssl_verification: true,
+        server_api_url: server.url(""),
+        extra_headers: hashmap![
+            "X-Forwarded-Tenant".to_owned() => "${TORNADO_ICINGA2_TEST_TENANT}".to_owned(),
+            "X-Gateway-Key".to_owned() => "static_key".to_owned(),
+        ],
+    })
+    .unwrap();
+
+    let mut action = Action::new("");
+    action
+        .payload
+        .insert(ICINGA2_ACTION_NAME_KEY.to_owned(), Value::String("icinga2-api-action".to_owned()));
+    action.payload.insert(
+        ICINGA2_ACTION_PAYLOAD_KEY.to_owned(),
+        json!(hashmap![
+            "filter".to_owned() => Value::String("my_service".to_owned()),
+        ]),
+    );
+
+    // Act
+    let result = executor.execute(action.into()).await;
+
+    // Assert
+    assert!(result.is_ok());
+    mock.assert();
+}
@@ -1,16 +1,109 @@
 use log::*;
-use std::sync::Arc;
-use tornado_common_api::Action;
+use serde_json::json;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tornado_common_api::{Action, Value};
+use tornado_common_parser::{Parser, ParserBuilder};
 use tornado_executor_common::{ExecutorError, StatelessExecutor};
 
-/// An executor that logs received actions at the 'info' level
+/// An executor that logs received actions at the 'info' level.
+/// Optionally, each action is also appended as a JSON line to a file.
 #[derive(Default, Clone)]
-pub struct LoggerExecutor {}
+pub struct LoggerExecutor {
+    file_sink: Option<Arc<FileSink>>,
+    message_template: Option<Arc<Parser>>,
+}
+
+/// A file to which the actions are appended, one JSON document per line.
+/// The file is opened when the first action is written.
+struct FileSink {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+}
 
 impl LoggerExecutor {
     pub fn new() -> LoggerExecutor {
         Default::default()
     }
+
+    /// Creates a LoggerExecutor that, in addition to logging them,
+    /// appends the received actions to the file at `path`.
+    pub fn new_with_file_sink<P: Into<PathBuf>>(path: P) -> LoggerExecutor {
+        LoggerExecutor {
+            file_sink: Some(Arc::new(FileSink { path: path.into(), file: Mutex::new(None) })),
+            message_template: None,
+        }
+    }
+
+    /// Sets the template of the logged message, e.g. `action=${id} host=${payload.host}`.
+    /// The placeholders are resolved against the received action.
+    pub fn with_message_template(
+        mut self,
+        template: &str,
+    ) -> Result<LoggerExecutor, ExecutorError> {
+        let parser = ParserBuilder::default().build_parser(template).map_err(|err| {
+            ExecutorError::ConfigurationError {
+                message: format!(
+                    "LoggerExecutor - Cannot build parser for the message template [{}]. Err: {:?}",
+                    template, err
+                ),
+            }
+        })?;
+        self.message_template = Some(Arc::new(parser));
+        Ok(self)
+    }
+
+    /// Returns the message to be logged for the action.
+    /// Without a template, or if the template cannot be resolved, it is the whole action.
+    fn log_message(&self, action: &Action) -> String {
+        if let Some(parser) = &self.message_template {
+            let action_value = json!(action);
+            match parser.parse_value(&action_value, "").as_deref() {
+                Some(Value::String(text)) => return text.to_owned(),
+                Some(value) => return value.to_string(),
+                None => debug!(
+                    "LoggerExecutor - Cannot resolve the message template for action [{}]",
+                    action.id
+                ),
+            }
+        }
+        format!("LoggerExecutor - received action: \n[{:?}]", action)
+    }
+}
+
+impl FileSink {
+    fn append(&self, action: &Action) -> Result<(), String> {
+        let mut line = serde_json::to_vec(action)
+            .map_err(|err| format!("Cannot serialize the action. Err: {:?}", err))?;
+        line.push(b'\n');
+
+        let mut file = match self.file.lock() {
+            Ok(file) => file,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if file.is_none() {
+            let opened =
+                OpenOptions::new().create(true).append(true).open(&self.path).map_err(|err| {
+                    format!("Cannot open the file [{}]. Err: {:?}", self.path.display(), err)
+                })?;
+            *file = Some(opened);
+        }
+
+        if let Some(opened) = file.as_mut() {
+            if let Err(err) = opened.write_all(&line) {
+                // The file is reopened by the next write
+                *file = None;
+                return Err(format!(
+                    "Cannot write to the file [{}]. Err: {:?}",
+                    self.path.display(),
+                    err
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl std::fmt::Display for LoggerExecutor {
@@ -24,7 +117,88 @@ impl std::fmt::Display for LoggerExecutor {
 impl StatelessExecutor for LoggerExecutor {
     #[tracing::instrument(level = "info", skip_all, err, fields(otel.name = format!("Execute Action: {}", &action.id).as_str(), otel.kind = "Consumer"))]
     async fn execute(&self, action: Arc<Action>) -> Result<(), ExecutorError> {
-        info!("LoggerExecutor - received action: \n[{:?}]", action);
+        info!("{}", self.log_message(&action));
+        if let Some(file_sink) = &self.file_sink {
+            if let Err(message) = file_sink.append(&action) {
+                warn!(
+                    "LoggerExecutor - Cannot append action [{}] to the file sink. {}",
+                    action.id, message
+                );
+            }
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn should_append_the_actions_to_the_file_sink() {
+        // Arrange
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("actions.log");
+        let executor = LoggerExecutor::new_with_file_sink(&path);
+
+        let mut action = Action::new_with_payload_and_created_ms("logger", Default::default(), 10);
+        action.payload.insert("hostname".to_owned(), Value::String("host1".to_owned()));
+        let action = Arc::new(action);
+
+        // Act
+        executor.execute(action.clone()).await.unwrap();
+        executor.execute(action.clone()).await.unwrap();
+
+        // Assert
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(2, lines.len());
+        for line in lines {
+            assert_eq!(action.as_ref(), &serde_json::from_str::<Action>(line).unwrap());
+        }
+    }
+
+    #[test]
+    fn should_log_the_message_template_resolved_against_the_action() {
+        // Arrange
+        let executor = LoggerExecutor::new()
+            .with_message_template("action=${id} host=${payload.host}")
+            .unwrap();
+
+        let mut action = Action::new("notify");
+        action.payload.insert("host".to_owned(), Value::String("host1".to_owned()));
+
+        // Act
+        let message = executor.log_message(&action);
+
+        // Assert
+        assert_eq!("action=notify host=host1", message);
+    }
+
+    #[test]
+    fn should_log_the_whole_action_if_the_template_cannot_be_resolved() {
+        // Arrange
+        let executor = LoggerExecutor::new().with_message_template("host=${payload.host}").unwrap();
+        let action = Action::new("notify");
+
+        // Act
+        let message = executor.log_message(&action);
+
+        // Assert
+        assert_eq!(format!("LoggerExecutor - received action: \n[{:?}]", action), message);
+    }
+
+    #[actix_rt::test]
+    async fn should_not_fail_the_action_if_the_file_cannot_be_written() {
+        // Arrange
+        let dir = tempfile::TempDir::new().unwrap();
+        let executor =
+            LoggerExecutor::new_with_file_sink(dir.path().join("missing").join("actions.log"));
+
+        // Act
+        let result = executor.execute(Arc::new(Action::new("logger"))).await;
+
+        // Assert
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use tornado_common_api::dedup_key::DedupKey;
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct MergeConfig {
+    /// The expression that extracts the correlation key from a received Action,
+    /// e.g. `${payload.hostname}`, or the fields whose values are joined in the key
+    pub key: DedupKey,
+
+    /// The id of the Action emitted with the merged payloads
+    pub action_id: String,
+
+    /// The key of the emitted Action payload that contains the array of the merged payloads
+    #[serde(default = "default_merged_payloads_key")]
+    pub merged_payloads_key: String,
+
+    /// The time window in milliseconds, starting from the first Action with a correlation key,
+    /// in which the Actions with the same key are merged
+    pub window_ms: u64,
+
+    /// The max number of Actions merged in a single Action.
+    /// When it is reached, the merged Action is emitted without waiting for the end of the window
+    pub max_count: usize,
+}
+
+fn default_merged_payloads_key() -> String {
+    "payloads".to_owned()
+}
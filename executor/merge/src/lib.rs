@@ -0,0 +1,360 @@
+use crate::config::MergeConfig;
+use log::*;
+use serde_json::json;
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+use tornado_common::actors::message::ActionMessage;
+use tornado_common_api::dedup_key::{to_key_text, DedupKeyResolver};
+use tornado_common_api::{Action, Map, TracedAction, Value};
+use tornado_common_parser::{Parser, ParserBuilder};
+use tornado_executor_common::{ExecutorError, StatelessExecutor};
+use tornado_network_common::EventBus;
+use tracing::instrument;
+
+pub mod config;
+
+/// An executor that accumulates the received Actions by correlation key and emits on the bus
+/// a single Action containing all their payloads.
+/// The merged Action is emitted when the time window started by the first Action with a key
+/// expires or when the max number of Actions for that key is reached.
+pub struct MergeExecutor {
+    key_resolver: DedupKeyResolver<Parser>,
+    state: Rc<MergeState>,
+}
+
+struct MergeState {
+    bus: Arc<dyn EventBus>,
+    action_id: String,
+    merged_payloads_key: String,
+    window: Duration,
+    max_count: usize,
+    next_batch_id: Cell<u64>,
+    pending: RefCell<HashMap<String, PendingMerge>>,
+}
+
+struct PendingMerge {
+    batch_id: u64,
+    payloads: Vec<Value>,
+}
+
+impl std::fmt::Display for MergeExecutor {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str("MergeExecutor")?;
+        Ok(())
+    }
+}
+
+impl MergeExecutor {
+    pub fn new(bus: Arc<dyn EventBus>, config: MergeConfig) -> Result<Self, ExecutorError> {
+        let parser_builder = ParserBuilder::default();
+        let key_resolver =
+            config.key.build(|field| parser_builder.build_parser(field)).map_err(|err| {
+                ExecutorError::ConfigurationError {
+                    message: format!(
+                        "MergeExecutor - Cannot build parser for key [{:?}]. Err: {:?}",
+                        config.key, err
+                    ),
+                }
+            })?;
+
+        Ok(Self {
+            key_resolver,
+            state: Rc::new(MergeState {
+                bus,
+                action_id: config.action_id,
+                merged_payloads_key: config.merged_payloads_key,
+                window: Duration::from_millis(config.window_ms),
+                max_count: config.max_count,
+                next_batch_id: Cell::new(0),
+                pending: RefCell::new(HashMap::new()),
+            }),
+        })
+    }
+
+    #[instrument(level = "debug", name = "Extract parameters for Executor", skip_all)]
+    fn extract_key(&self, action: &Action) -> Result<String, ExecutorError> {
+        let action_value = json!(action);
+        let key = self.key_resolver.derive(|parser| {
+            parser.parse_value(&action_value, "").map(|value| to_key_text(value.as_ref()))
+        });
+        match key {
+            Some(key) => Ok(key),
+            None => Err(ExecutorError::MissingArgumentError {
+                message: format!(
+                    "MergeExecutor - Cannot resolve the correlation key of action [{}]",
+                    action.id
+                ),
+            }),
+        }
+    }
+
+    /// Waits for the end of the window and then emits the batch, if it was not already emitted
+    /// because the max count was reached.
+    fn schedule_window_expiration(&self, key: String, batch_id: u64) {
+        let state = self.state.clone();
+        tokio::task::spawn_local(async move {
+            tokio::time::sleep(state.window).await;
+            let payloads = match state.pending.borrow_mut().entry(key) {
+                Entry::Occupied(entry) if entry.get().batch_id == batch_id => {
+                    Some(entry.remove().payloads)
+                }
+                _ => None,
+            };
+            if let Some(payloads) = payloads {
+                state.publish(payloads);
+            }
+        });
+    }
+}
+
+impl MergeState {
+    fn publish(&self, payloads: Vec<Value>) {
+        debug!("MergeExecutor - Emitting an action that merges {} payloads", payloads.len());
+        let mut payload = Map::new();
+        payload.insert(self.merged_payloads_key.clone(), Value::Array(payloads));
+        let result = self.bus.publish_action(ActionMessage(
+            TracedAction {
+                action: Arc::new(Action::new_with_payload_and_created_ms(
+                    self.action_id.clone(),
+                    payload,
+                    current_ts_ms(),
+                )),
+                span: tracing::Span::current(),
+            },
+            None,
+        ));
+        if let Err(err) = result {
+            error!("MergeExecutor - Cannot publish the merged action. Err: {}", err);
+        }
+    }
+}
+
+fn current_ts_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[async_trait::async_trait(?Send)]
+impl StatelessExecutor for MergeExecutor {
+    #[tracing::instrument(level = "info", skip_all, err, fields(otel.name = format!("Execute Action: {}", &action.id).as_str(), otel.kind = "Consumer"))]
+    async fn execute(&self, action: Arc<Action>) -> Result<(), ExecutorError> {
+        trace!("MergeExecutor - received action: \n[{:?}]", action);
+
+        let key = self.extract_key(&action)?;
+        let payload = Value::Object(action.payload.clone());
+
+        let mut new_batch_id = None;
+        let completed = match self.state.pending.borrow_mut().entry(key.clone()) {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().payloads.push(payload);
+                if entry.get().payloads.len() >= self.state.max_count {
+                    Some(entry.remove().payloads)
+                } else {
+                    None
+                }
+            }
+            Entry::Vacant(entry) => {
+                if self.state.max_count <= 1 {
+                    Some(vec![payload])
+                } else {
+                    let batch_id = self.state.next_batch_id.get();
+                    self.state.next_batch_id.set(batch_id + 1);
+                    entry.insert(PendingMerge { batch_id, payloads: vec![payload] });
+                    new_batch_id = Some(batch_id);
+                    None
+                }
+            }
+        };
+
+        if let Some(batch_id) = new_batch_id {
+            self.schedule_window_expiration(key, batch_id);
+        }
+
+        if let Some(payloads) = completed {
+            self.state.publish(payloads);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Mutex;
+    use tornado_common_api::dedup_key::DedupKey;
+    use tornado_network_simple::SimpleEventBus;
+
+    const MERGED_ACTION_ID: &str = "merged";
+
+    fn new_executor(window_ms: u64, max_count: usize) -> (MergeExecutor, Arc<Mutex<Vec<Action>>>) {
+        new_executor_with_key("${payload.hostname}".into(), window_ms, max_count)
+    }
+
+    fn new_executor_with_key(
+        key: DedupKey,
+        window_ms: u64,
+        max_count: usize,
+    ) -> (MergeExecutor, Arc<Mutex<Vec<Action>>>) {
+        let merged_actions = Arc::new(Mutex::new(vec![]));
+
+        let mut bus = SimpleEventBus::new();
+        {
+            let merged_actions = merged_actions.clone();
+            bus.subscribe_to_action(
+                MERGED_ACTION_ID,
+                Box::new(move |message: ActionMessage| {
+                    merged_actions.lock().unwrap().push(message.0.action.as_ref().clone());
+                }),
+            );
+        }
+
+        let executor = MergeExecutor::new(
+            Arc::new(bus),
+            MergeConfig {
+                key,
+                action_id: MERGED_ACTION_ID.to_owned(),
+                merged_payloads_key: "events".to_owned(),
+                window_ms,
+                max_count,
+            },
+        )
+        .unwrap();
+
+        (executor, merged_actions)
+    }
+
+    fn new_action(hostname: &str, state: &str) -> Arc<Action> {
+        let mut action = Action::new("merge");
+        action.payload.insert("hostname".to_owned(), Value::String(hostname.to_owned()));
+        action.payload.insert("state".to_owned(), Value::String(state.to_owned()));
+        Arc::new(action)
+    }
+
+    #[actix_rt::test]
+    async fn should_merge_the_actions_with_the_same_key_received_within_the_window() {
+        // Arrange
+        let (executor, merged_actions) = new_executor(100, 10);
+
+        // Act
+        executor.execute(new_action("host1", "DOWN")).await.unwrap();
+        executor.execute(new_action("host1", "UNREACHABLE")).await.unwrap();
+        executor.execute(new_action("host1", "UP")).await.unwrap();
+
+        let emitted_before_window_end = merged_actions.lock().unwrap().len();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        // Assert
+        assert_eq!(0, emitted_before_window_end);
+
+        let merged_actions = merged_actions.lock().unwrap();
+        assert_eq!(1, merged_actions.len());
+        assert_eq!(
+            &json!([
+                { "hostname": "host1", "state": "DOWN" },
+                { "hostname": "host1", "state": "UNREACHABLE" },
+                { "hostname": "host1", "state": "UP" },
+            ]),
+            merged_actions[0].payload.get("events").unwrap()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn should_merge_the_actions_by_a_key_of_several_fields() {
+        // Arrange
+        let key = DedupKey::Fields {
+            fields: vec!["${payload.hostname}".to_owned(), "${payload.state}".to_owned()],
+            separator: "::".to_owned(),
+        };
+        let (executor, merged_actions) = new_executor_with_key(key, 60_000, 2);
+
+        // Act
+        executor.execute(new_action("host1", "DOWN")).await.unwrap();
+        executor.execute(new_action("host1", "UP")).await.unwrap();
+        executor.execute(new_action("host1", "DOWN")).await.unwrap();
+
+        // Assert
+        let merged_actions = merged_actions.lock().unwrap();
+        assert_eq!(1, merged_actions.len());
+        assert_eq!(
+            &json!([
+                { "hostname": "host1", "state": "DOWN" },
+                { "hostname": "host1", "state": "DOWN" },
+            ]),
+            merged_actions[0].payload.get("events").unwrap()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn should_not_merge_the_actions_with_different_keys() {
+        // Arrange
+        let (executor, merged_actions) = new_executor(100, 10);
+
+        // Act
+        executor.execute(new_action("host1", "DOWN")).await.unwrap();
+        executor.execute(new_action("host2", "DOWN")).await.unwrap();
+        executor.execute(new_action("host1", "UP")).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        // Assert
+        let merged_actions = merged_actions.lock().unwrap();
+        assert_eq!(2, merged_actions.len());
+
+        let payloads_of = |hostname: &str| {
+            merged_actions
+                .iter()
+                .map(|action| action.payload.get("events").unwrap().clone())
+                .find(|events| events[0]["hostname"] == hostname)
+                .unwrap()
+        };
+        assert_eq!(
+            json!([
+                { "hostname": "host1", "state": "DOWN" },
+                { "hostname": "host1", "state": "UP" },
+            ]),
+            payloads_of("host1")
+        );
+        assert_eq!(json!([{ "hostname": "host2", "state": "DOWN" }]), payloads_of("host2"));
+    }
+
+    #[actix_rt::test]
+    async fn should_emit_the_merged_action_when_the_max_count_is_reached() {
+        // Arrange
+        let (executor, merged_actions) = new_executor(60_000, 2);
+
+        // Act
+        executor.execute(new_action("host1", "DOWN")).await.unwrap();
+        executor.execute(new_action("host1", "UP")).await.unwrap();
+        executor.execute(new_action("host1", "DOWN")).await.unwrap();
+
+        // Assert
+        let merged_actions = merged_actions.lock().unwrap();
+        assert_eq!(1, merged_actions.len());
+        assert_eq!(
+            &json!([
+                { "hostname": "host1", "state": "DOWN" },
+                { "hostname": "host1", "state": "UP" },
+            ]),
+            merged_actions[0].payload.get("events").unwrap()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn should_fail_if_the_key_cannot_be_resolved() {
+        // Arrange
+        let (executor, _merged_actions) = new_executor(100, 10);
+
+        // Act
+        let result = executor.execute(Arc::new(Action::new("merge"))).await;
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::MissingArgumentError { .. })));
+    }
+}
@@ -0,0 +1,24 @@
+use rumqttc::MqttOptions;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct MqttPublisherConfig {
+    /// The host of the MQTT broker
+    pub host: String,
+
+    /// The port of the MQTT broker
+    pub port: u16,
+
+    /// The prefix of the client identifier used when connecting to the MQTT broker.
+    /// A progressive suffix is appended to it for each connection.
+    pub client_id: String,
+
+    /// The publish timeout in seconds. Default is 10 seconds
+    pub timeout_secs: Option<u64>,
+}
+
+impl MqttPublisherConfig {
+    pub fn to_mqtt_options(&self, client_id: String) -> MqttOptions {
+        MqttOptions::new(client_id, self.host.clone(), self.port)
+    }
+}
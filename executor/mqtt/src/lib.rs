@@ -0,0 +1,444 @@
+use crate::config::MqttPublisherConfig;
+use log::*;
+use maplit::*;
+use rumqttc::{AsyncClient, Event, EventLoop, Incoming, Outgoing, QoS};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tornado_common_api::{Action, Value, ValueExt};
+use tornado_executor_common::{ExecutorError, StatelessExecutor};
+use tracing::instrument;
+
+pub mod config;
+
+pub const MQTT_TOPIC_KEY: &str = "topic";
+pub const MQTT_PAYLOAD_KEY: &str = "payload";
+pub const MQTT_QOS_KEY: &str = "qos";
+pub const MQTT_RETAIN_KEY: &str = "retain";
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const MQTT_CLIENT_REQUEST_CAPACITY: usize = 10;
+
+/// An executor that publishes messages on the topics of an MQTT broker
+pub struct MqttExecutor {
+    config: MqttPublisherConfig,
+    connection_counter: AtomicUsize,
+    connection: Mutex<Option<Arc<MqttConnection>>>,
+    publish_lock: tokio::sync::Mutex<()>,
+}
+
+impl std::fmt::Display for MqttExecutor {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str("MqttExecutor")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MqttPublishAction {
+    pub topic: String,
+    pub payload: Vec<u8>,
+    pub qos: QoS,
+    pub retain: bool,
+}
+
+type PublishResultSender = oneshot::Sender<Result<(), String>>;
+
+/// A connection to the broker shared by all the Actions.
+/// Its event loop runs in a dedicated task that notifies each publisher
+///   when the acknowledgement required by the QoS of its message is received.
+struct MqttConnection {
+    client: AsyncClient,
+    pending: Mutex<PendingPublishes>,
+}
+
+#[derive(Default)]
+struct PendingPublishes {
+    closed: bool,
+    /// The messages not yet written on the connection, in the same order they were published
+    queued: VecDeque<(QoS, PublishResultSender)>,
+    /// The messages written on the connection and waiting for an acknowledgement, by packet id
+    in_flight: HashMap<u16, PublishResultSender>,
+}
+
+impl PendingPublishes {
+    fn fail_all(&mut self, message: &str) {
+        self.closed = true;
+        for (_, sender) in self.queued.drain(..) {
+            let _ = sender.send(Err(message.to_owned()));
+        }
+        for (_, sender) in self.in_flight.drain() {
+            let _ = sender.send(Err(message.to_owned()));
+        }
+    }
+}
+
+impl MqttConnection {
+    fn is_closed(&self) -> bool {
+        self.pending.lock().map(|pending| pending.closed).unwrap_or(true)
+    }
+
+    async fn run_event_loop(connection: Arc<MqttConnection>, mut event_loop: EventLoop) {
+        loop {
+            let event = event_loop.poll().await;
+            let mut pending = match connection.pending.lock() {
+                Ok(pending) => pending,
+                Err(_) => return,
+            };
+            match event {
+                Ok(Event::Outgoing(Outgoing::Publish(pkid))) => {
+                    if let Some((qos, sender)) = pending.queued.pop_front() {
+                        // With QoS 0 there is no acknowledgement, the message is sent once written
+                        if qos == QoS::AtMostOnce {
+                            let _ = sender.send(Ok(()));
+                        } else {
+                            pending.in_flight.insert(pkid, sender);
+                        }
+                    }
+                }
+                Ok(Event::Incoming(Incoming::PubAck(ack))) => {
+                    if let Some(sender) = pending.in_flight.remove(&ack.pkid) {
+                        let _ = sender.send(Ok(()));
+                    }
+                }
+                Ok(Event::Incoming(Incoming::PubComp(comp))) => {
+                    if let Some(sender) = pending.in_flight.remove(&comp.pkid) {
+                        let _ = sender.send(Ok(()));
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    // The connection is discarded; a new one is opened by the next publish.
+                    warn!("MqttExecutor - Connection to the broker failed. Err: {:?}", err);
+                    pending.fail_all(&format!("Connection failed. Err: {:?}", err));
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl MqttExecutor {
+    pub fn new(config: MqttPublisherConfig) -> MqttExecutor {
+        MqttExecutor {
+            config,
+            connection_counter: AtomicUsize::new(0),
+            connection: Mutex::new(None),
+            publish_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    #[instrument(level = "debug", name = "Extract parameters for Executor", skip_all)]
+    fn parse_action(&self, action: &Action) -> Result<MqttPublishAction, ExecutorError> {
+        let topic = action
+            .payload
+            .get(MQTT_TOPIC_KEY)
+            .and_then(ValueExt::get_text)
+            .ok_or_else(|| ExecutorError::MissingArgumentError {
+                message: format!("MqttExecutor - [{}] not specified", MQTT_TOPIC_KEY),
+            })?
+            .to_owned();
+
+        // Text payloads are published as they are, any other value is published as JSON
+        let payload = match action.payload.get(MQTT_PAYLOAD_KEY) {
+            Some(Value::String(text)) => text.as_bytes().to_vec(),
+            Some(value) => serde_json::to_vec(value)?,
+            None => {
+                return Err(ExecutorError::MissingArgumentError {
+                    message: format!("MqttExecutor - [{}] not specified", MQTT_PAYLOAD_KEY),
+                })
+            }
+        };
+
+        let qos = match action.payload.get(MQTT_QOS_KEY) {
+            Some(value) => parse_qos(value)?,
+            None => QoS::AtMostOnce,
+        };
+
+        let retain = match action.payload.get(MQTT_RETAIN_KEY) {
+            Some(value) => {
+                *value.get_bool().ok_or_else(|| ExecutorError::UnknownArgumentError {
+                    message: format!(
+                        "MqttExecutor - [{}] should be a boolean. Found: {}",
+                        MQTT_RETAIN_KEY, value
+                    ),
+                })?
+            }
+            None => false,
+        };
+
+        Ok(MqttPublishAction { topic, payload, qos, retain })
+    }
+
+    /// Returns the open connection to the broker, or opens a new one if there is none.
+    fn connection(&self) -> Arc<MqttConnection> {
+        let mut connection = self.connection.lock().unwrap_or_else(|err| err.into_inner());
+        match connection.as_ref() {
+            Some(open) if !open.is_closed() => open.clone(),
+            _ => {
+                let client_id = format!(
+                    "{}_{}",
+                    self.config.client_id,
+                    self.connection_counter.fetch_add(1, Ordering::Relaxed)
+                );
+                debug!("MqttExecutor - Opening a new connection with client id [{}]", client_id);
+                let (client, event_loop) = AsyncClient::new(
+                    self.config.to_mqtt_options(client_id),
+                    MQTT_CLIENT_REQUEST_CAPACITY,
+                );
+                let open = Arc::new(MqttConnection { client, pending: Default::default() });
+                tokio::spawn(MqttConnection::run_event_loop(open.clone(), event_loop));
+                *connection = Some(open.clone());
+                open
+            }
+        }
+    }
+
+    #[instrument(level = "debug", name = "MqttExecutor", skip_all, fields(otel.name = format!("Publish message to MQTT topic [{}]", mqtt_action.topic).as_str()))]
+    pub async fn publish(&self, mqtt_action: MqttPublishAction) -> Result<(), ExecutorError> {
+        let topic = mqtt_action.topic.clone();
+        let (sender, receiver) = oneshot::channel();
+
+        {
+            // The event loop assigns the packet ids in the same order the messages are
+            //   enqueued, so enqueuing the message and its result sender must not interleave
+            //   with other publishes.
+            let _publish_guard = self.publish_lock.lock().await;
+            let connection = self.connection();
+            {
+                let mut pending = connection.pending.lock().map_err(|err| {
+                    to_retryable_error(
+                        &topic,
+                        format!("Cannot access the connection. Err: {}", err),
+                    )
+                })?;
+                if pending.closed {
+                    return Err(to_retryable_error(&topic, "Connection closed".to_owned()));
+                }
+                pending.queued.push_back((mqtt_action.qos, sender));
+            }
+
+            if let Err(err) = connection
+                .client
+                .publish(
+                    mqtt_action.topic,
+                    mqtt_action.qos,
+                    mqtt_action.retain,
+                    mqtt_action.payload,
+                )
+                .await
+            {
+                if let Ok(mut pending) = connection.pending.lock() {
+                    pending.queued.pop_back();
+                }
+                return Err(to_retryable_error(
+                    &topic,
+                    format!("Cannot enqueue the message. Err: {:?}", err),
+                ));
+            }
+        }
+
+        let timeout = Duration::from_secs(self.config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(Ok(()))) => {
+                debug!("MqttExecutor - Message published on topic [{}]", topic);
+                Ok(())
+            }
+            Ok(Ok(Err(message))) => Err(to_retryable_error(&topic, message)),
+            Ok(Err(_)) => Err(to_retryable_error(&topic, "Connection closed".to_owned())),
+            Err(_) => Err(to_retryable_error(
+                &topic,
+                format!("Message not published within {} seconds", timeout.as_secs()),
+            )),
+        }
+    }
+}
+
+fn parse_qos(value: &Value) -> Result<QoS, ExecutorError> {
+    let qos_number = match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(text) => text.parse::<u64>().ok(),
+        _ => None,
+    };
+    match qos_number {
+        Some(0) => Ok(QoS::AtMostOnce),
+        Some(1) => Ok(QoS::AtLeastOnce),
+        Some(2) => Ok(QoS::ExactlyOnce),
+        _ => Err(ExecutorError::UnknownArgumentError {
+            message: format!(
+                "MqttExecutor - Invalid [{}] value. Found: {}. Expected one of: 0, 1, 2",
+                MQTT_QOS_KEY, value
+            ),
+        }),
+    }
+}
+
+fn to_retryable_error(topic: &str, message: String) -> ExecutorError {
+    ExecutorError::ActionExecutionError {
+        can_retry: true,
+        message: format!("MqttExecutor - {}", message),
+        code: None,
+        data: hashmap!["topic" => topic.into()].into(),
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl StatelessExecutor for MqttExecutor {
+    #[tracing::instrument(level = "info", skip_all, err, fields(otel.name = format!("Execute Action: {}", &action.id).as_str(), otel.kind = "Consumer"))]
+    async fn execute(&self, action: Arc<Action>) -> Result<(), ExecutorError> {
+        trace!("MqttExecutor - received action: \n[{:?}]", action);
+        let mqtt_action = self.parse_action(&action)?;
+        self.publish(mqtt_action).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+
+    fn new_executor() -> MqttExecutor {
+        MqttExecutor::new(MqttPublisherConfig {
+            host: "127.0.0.1".to_owned(),
+            port: 1883,
+            client_id: "tornado".to_owned(),
+            timeout_secs: None,
+        })
+    }
+
+    #[test]
+    fn should_parse_the_action() {
+        // Arrange
+        let executor = new_executor();
+        let mut action = Action::new("mqtt");
+        action.payload.insert("topic".to_owned(), json!("alerts/host1"));
+        action.payload.insert("payload".to_owned(), json!({"state": "DOWN"}));
+        action.payload.insert("qos".to_owned(), json!(2));
+        action.payload.insert("retain".to_owned(), json!(true));
+
+        // Act
+        let mqtt_action = executor.parse_action(&action).unwrap();
+
+        // Assert
+        assert_eq!(
+            MqttPublishAction {
+                topic: "alerts/host1".to_owned(),
+                payload: br#"{"state":"DOWN"}"#.to_vec(),
+                qos: QoS::ExactlyOnce,
+                retain: true
+            },
+            mqtt_action
+        );
+    }
+
+    #[test]
+    fn should_use_default_qos_and_retain() {
+        // Arrange
+        let executor = new_executor();
+        let mut action = Action::new("mqtt");
+        action.payload.insert("topic".to_owned(), json!("alerts/host1"));
+        action.payload.insert("payload".to_owned(), json!("host1 is DOWN"));
+
+        // Act
+        let mqtt_action = executor.parse_action(&action).unwrap();
+
+        // Assert
+        assert_eq!(b"host1 is DOWN".to_vec(), mqtt_action.payload);
+        assert_eq!(QoS::AtMostOnce, mqtt_action.qos);
+        assert!(!mqtt_action.retain);
+    }
+
+    #[test]
+    fn should_fail_if_topic_is_missing() {
+        // Arrange
+        let executor = new_executor();
+        let mut action = Action::new("mqtt");
+        action.payload.insert("payload".to_owned(), json!("host1 is DOWN"));
+
+        // Act
+        let result = executor.parse_action(&action);
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::MissingArgumentError { .. })));
+    }
+
+    #[test]
+    fn should_fail_if_qos_is_not_valid() {
+        // Arrange
+        let executor = new_executor();
+        let mut action = Action::new("mqtt");
+        action.payload.insert("topic".to_owned(), json!("alerts/host1"));
+        action.payload.insert("payload".to_owned(), json!("host1 is DOWN"));
+        action.payload.insert("qos".to_owned(), json!(3));
+
+        // Act
+        let result = executor.parse_action(&action);
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::UnknownArgumentError { .. })));
+    }
+
+    #[actix_rt::test]
+    async fn should_return_a_retryable_error_if_the_broker_is_not_available() {
+        // Arrange
+        let executor = MqttExecutor::new(MqttPublisherConfig {
+            host: "127.0.0.1".to_owned(),
+            port: 1,
+            client_id: "tornado".to_owned(),
+            timeout_secs: Some(5),
+        });
+        let mut action = Action::new("mqtt");
+        action.payload.insert("topic".to_owned(), json!("alerts/host1"));
+        action.payload.insert("payload".to_owned(), json!("host1 is DOWN"));
+
+        // Act
+        let result = executor.execute(Arc::new(action)).await;
+
+        // Assert
+        match result {
+            Err(ExecutorError::ActionExecutionError { can_retry, .. }) => assert!(can_retry),
+            _ => panic!("Expected a retryable ActionExecutionError"),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn should_share_the_open_connection_among_the_actions() {
+        // Arrange
+        let executor = new_executor();
+
+        // Act
+        let first = executor.connection();
+        let second = executor.connection();
+
+        // Assert
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(1, executor.connection_counter.load(Ordering::Relaxed));
+    }
+
+    #[actix_rt::test]
+    async fn should_open_a_new_connection_after_a_failure() {
+        // Arrange
+        let executor = MqttExecutor::new(MqttPublisherConfig {
+            host: "127.0.0.1".to_owned(),
+            port: 1,
+            client_id: "tornado".to_owned(),
+            timeout_secs: Some(5),
+        });
+        let mut action = Action::new("mqtt");
+        action.payload.insert("topic".to_owned(), json!("alerts/host1"));
+        action.payload.insert("payload".to_owned(), json!("host1 is DOWN"));
+        let action = Arc::new(action);
+
+        // Act
+        let first_result = executor.execute(action.clone()).await;
+        let second_result = executor.execute(action).await;
+
+        // Assert
+        assert!(first_result.is_err());
+        assert!(second_result.is_err());
+        assert_eq!(2, executor.connection_counter.load(Ordering::Relaxed));
+    }
+}
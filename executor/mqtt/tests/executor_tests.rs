@@ -0,0 +1,150 @@
+use bytes::BytesMut;
+use rumqttc::mqttbytes::v4::read;
+use rumqttc::{
+    ConnAck, ConnectReturnCode, Packet, PingResp, PubAck, PubComp, PubRec, Publish, QoS,
+};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tornado_common_api::{Event, Map, Payload, Value};
+use tornado_engine_matcher::config::rule::ConfigAction;
+use tornado_engine_matcher::matcher::action::ActionResolverBuilder;
+use tornado_executor_common::StatelessExecutor;
+use tornado_executor_mqtt::config::MqttPublisherConfig;
+use tornado_executor_mqtt::MqttExecutor;
+
+/// Starts a mock MQTT broker that accepts a single client, acknowledges the received messages
+/// according to their QoS level and sends them through the returned channel.
+async fn start_mock_broker() -> (u16, UnboundedReceiver<Publish>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut read_buffer = BytesMut::new();
+        loop {
+            let packet = match read(&mut read_buffer, 1024 * 1024) {
+                Ok(packet) => packet,
+                Err(rumqttc::mqttbytes::Error::InsufficientBytes(_)) => {
+                    if stream.read_buf(&mut read_buffer).await.unwrap() == 0 {
+                        return;
+                    }
+                    continue;
+                }
+                Err(err) => panic!("Mock broker received an invalid packet. Err: {:?}", err),
+            };
+
+            let mut write_buffer = BytesMut::new();
+            match packet {
+                Packet::Connect(_) => {
+                    ConnAck::new(ConnectReturnCode::Success, false)
+                        .write(&mut write_buffer)
+                        .unwrap();
+                }
+                Packet::Publish(publish) => {
+                    match publish.qos {
+                        QoS::AtMostOnce => {}
+                        QoS::AtLeastOnce => {
+                            PubAck::new(publish.pkid).write(&mut write_buffer).unwrap();
+                        }
+                        QoS::ExactlyOnce => {
+                            PubRec::new(publish.pkid).write(&mut write_buffer).unwrap();
+                        }
+                    }
+                    sender.send(publish).unwrap();
+                }
+                Packet::PubRel(pubrel) => {
+                    PubComp::new(pubrel.pkid).write(&mut write_buffer).unwrap();
+                }
+                Packet::PingReq => {
+                    PingResp.write(&mut write_buffer).unwrap();
+                }
+                Packet::Disconnect => return,
+                _ => {}
+            }
+            stream.write_all(&write_buffer).await.unwrap();
+        }
+    });
+
+    (port, receiver)
+}
+
+fn new_executor(port: u16) -> MqttExecutor {
+    MqttExecutor::new(MqttPublisherConfig {
+        host: "127.0.0.1".to_owned(),
+        port,
+        client_id: "tornado_executor_test".to_owned(),
+        timeout_secs: Some(5),
+    })
+}
+
+#[actix_rt::test]
+async fn should_publish_a_templated_topic_and_payload_with_the_requested_qos() {
+    // Arrange
+    let (port, mut published) = start_mock_broker().await;
+    let executor = new_executor(port);
+
+    let mut config_action = ConfigAction {
+        id: "mqtt".to_owned(),
+        payload: Map::new(),
+        when: None,
+        retry_strategy: None,
+    };
+    config_action.payload.insert("topic".to_owned(), json!("alerts/${event.payload.host}"));
+    config_action.payload.insert(
+        "payload".to_owned(),
+        json!({ "host": "${event.payload.host}", "state": "${event.payload.state}" }),
+    );
+    config_action.payload.insert("qos".to_owned(), json!(1));
+    config_action.payload.insert("retain".to_owned(), json!(true));
+
+    let action_resolver = ActionResolverBuilder::new().build("rule", &config_action).unwrap();
+
+    let mut event_payload = Payload::new();
+    event_payload.insert("host".to_owned(), Value::String("host1".to_owned()));
+    event_payload.insert("state".to_owned(), Value::String("DOWN".to_owned()));
+    let event = json!(Event::new_with_payload("monitoring", event_payload));
+    let action = action_resolver.resolve(&(&event, &mut Value::Null).into()).unwrap();
+
+    // Act
+    let result = executor.execute(Arc::new(action)).await;
+
+    // Assert
+    assert!(result.is_ok());
+
+    let publish = published.recv().await.unwrap();
+    assert_eq!("alerts/host1", publish.topic);
+    assert_eq!(QoS::AtLeastOnce, publish.qos);
+    assert!(publish.retain);
+    assert_eq!(
+        json!({ "host": "host1", "state": "DOWN" }),
+        serde_json::from_slice::<Value>(&publish.payload).unwrap()
+    );
+}
+
+#[actix_rt::test]
+async fn should_publish_a_text_payload_with_exactly_once_qos() {
+    // Arrange
+    let (port, mut published) = start_mock_broker().await;
+    let executor = new_executor(port);
+
+    let mut action = tornado_common_api::Action::new("mqtt");
+    action.payload.insert("topic".to_owned(), json!("alerts/host2"));
+    action.payload.insert("payload".to_owned(), json!("host2 is DOWN"));
+    action.payload.insert("qos".to_owned(), json!(2));
+
+    // Act
+    let result = executor.execute(Arc::new(action)).await;
+
+    // Assert
+    assert!(result.is_ok());
+
+    let publish = published.recv().await.unwrap();
+    assert_eq!("alerts/host2", publish.topic);
+    assert_eq!(QoS::ExactlyOnce, publish.qos);
+    assert!(!publish.retain);
+    assert_eq!(b"host2 is DOWN".to_vec(), publish.payload.to_vec());
+}
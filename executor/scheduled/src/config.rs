@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct ScheduledConfig {
+    /// The path of the file where the pending scheduled Actions are persisted,
+    /// so that they are not lost when Tornado is restarted
+    pub store_path: String,
+}
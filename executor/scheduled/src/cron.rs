@@ -0,0 +1,221 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+
+const FIELDS_SEPARATOR: char = ' ';
+const LIST_SEPARATOR: char = ',';
+const RANGE_SEPARATOR: char = '-';
+const STEP_SEPARATOR: char = '/';
+const ANY_VALUE: &str = "*";
+
+/// The max number of years searched for the next occurrence of a schedule.
+/// It prevents endless searches for schedules that can never fire, e.g. `0 0 31 2 *`.
+const MAX_SEARCHED_YEARS: i32 = 5;
+
+/// A five-field cron expression in the form `<minute> <hour> <day of month> <month> <day of week>`.
+/// Each field accepts `*`, single values, ranges (`1-5`), steps (`*/15`, `0-30/10`)
+/// and comma-separated lists of them. The day of week goes from 0 (Sunday) to 7 (Sunday).
+/// The expressions are always evaluated in UTC.
+#[derive(Debug, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<CronSchedule, String> {
+        let fields: Vec<&str> =
+            expression.split(FIELDS_SEPARATOR).filter(|field| !field.is_empty()).collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "The cron expression [{}] must have 5 fields, found {}",
+                expression,
+                fields.len()
+            ));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        // Both 0 and 7 represent Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(CronSchedule {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            any_day_of_month: fields[2] == ANY_VALUE,
+            any_day_of_week: fields[4] == ANY_VALUE,
+        })
+    }
+
+    /// Returns the epoch milliseconds of the first occurrence of the schedule
+    ///   strictly after `after_ms`, or None if there is none in the next years.
+    pub fn next_after(&self, after_ms: u64) -> Option<u64> {
+        let after = Utc.timestamp_millis_opt(after_ms as i64).single()?;
+        let mut time = at_minute_start(&after)? + Duration::minutes(1);
+        let max_year = after.year() + MAX_SEARCHED_YEARS;
+
+        while time.year() <= max_year {
+            if !contains(self.months, time.month()) {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                time = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.matches_day(&time) {
+                time = Utc.from_utc_datetime(&time.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?);
+            } else if !contains(self.hours, time.hour()) {
+                time = at_minute_start(&time)? - Duration::minutes(time.minute() as i64)
+                    + Duration::hours(1);
+            } else if !contains(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time.timestamp_millis() as u64);
+            }
+        }
+        None
+    }
+
+    /// As in the standard cron, when both the day of month and the day of week are restricted,
+    ///   a day matches if it matches either of them.
+    fn matches_day(&self, time: &DateTime<Utc>) -> bool {
+        let day_of_month = contains(self.days_of_month, time.day());
+        let day_of_week = contains(self.days_of_week, time.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+}
+
+fn at_minute_start(time: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+    time.with_second(0)?.with_nanosecond(0)
+}
+
+fn contains(values: u64, value: u32) -> bool {
+    values & (1 << value) != 0
+}
+
+/// Parses a cron field into a bit set of the accepted values
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut values = 0;
+    for item in field.split(LIST_SEPARATOR) {
+        let (range, step) = match item.split_once(STEP_SEPARATOR) {
+            Some((range, step)) => (range, parse_number(step, 1, max)?),
+            None => (item, 1),
+        };
+
+        let (start, end) = if range == ANY_VALUE {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once(RANGE_SEPARATOR) {
+            (parse_number(start, min, max)?, parse_number(end, min, max)?)
+        } else {
+            let value = parse_number(range, min, max)?;
+            (value, value)
+        };
+
+        if start > end {
+            return Err(format!("The cron range [{}] has the start greater than the end", item));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            values |= 1 << value;
+        }
+    }
+    Ok(values)
+}
+
+fn parse_number(text: &str, min: u32, max: u32) -> Result<u32, String> {
+    match text.parse::<u32>() {
+        Ok(value) if (min..=max).contains(&value) => Ok(value),
+        _ => Err(format!(
+            "The cron value [{}] is not valid. Expected a number between {} and {}",
+            text, min, max
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ms(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> u64 {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap().timestamp_millis() as u64
+    }
+
+    #[test]
+    fn should_return_the_next_minute_for_the_any_expression() {
+        // Arrange
+        let schedule = CronSchedule::parse("* * * * *").unwrap();
+
+        // Act
+        let next = schedule.next_after(ms(2024, 3, 10, 12, 30) + 15_000);
+
+        // Assert
+        assert_eq!(Some(ms(2024, 3, 10, 12, 31)), next);
+    }
+
+    #[test]
+    fn should_return_the_next_occurrence_of_a_maintenance_window() {
+        // Arrange
+        // At 02:30 on Saturday and Sunday
+        let schedule = CronSchedule::parse("30 2 * * 6,7").unwrap();
+
+        // Act
+        // Wednesday
+        let next = schedule.next_after(ms(2024, 3, 13, 10, 0));
+
+        // Assert
+        assert_eq!(Some(ms(2024, 3, 16, 2, 30)), next);
+    }
+
+    #[test]
+    fn should_support_ranges_steps_and_month_changes() {
+        // Arrange
+        let schedule = CronSchedule::parse("0-30/15 22 1 1-3 *").unwrap();
+
+        // Act & Assert
+        assert_eq!(Some(ms(2025, 1, 1, 22, 0)), schedule.next_after(ms(2024, 12, 5, 0, 0)));
+        assert_eq!(Some(ms(2025, 1, 1, 22, 15)), schedule.next_after(ms(2025, 1, 1, 22, 0)));
+        assert_eq!(Some(ms(2025, 2, 1, 22, 0)), schedule.next_after(ms(2025, 1, 1, 22, 30)));
+    }
+
+    #[test]
+    fn should_match_either_the_day_of_month_or_the_day_of_week() {
+        // Arrange
+        // At midnight on the 15th and on Mondays
+        let schedule = CronSchedule::parse("0 0 15 * 1").unwrap();
+
+        // Act & Assert
+        // Thursday 14th -> Friday 15th
+        assert_eq!(Some(ms(2024, 3, 15, 0, 0)), schedule.next_after(ms(2024, 3, 14, 0, 0)));
+        // Friday 15th -> Monday 18th
+        assert_eq!(Some(ms(2024, 3, 18, 0, 0)), schedule.next_after(ms(2024, 3, 15, 0, 0)));
+    }
+
+    #[test]
+    fn should_return_none_if_the_schedule_never_fires() {
+        // Arrange
+        let schedule = CronSchedule::parse("0 0 31 2 *").unwrap();
+
+        // Act & Assert
+        assert_eq!(None, schedule.next_after(ms(2024, 1, 1, 0, 0)));
+    }
+
+    #[test]
+    fn should_reject_invalid_expressions() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("* * 0 * *").is_err());
+        assert!(CronSchedule::parse("10-5 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("a * * * *").is_err());
+    }
+}
@@ -0,0 +1,432 @@
+use crate::config::ScheduledConfig;
+use crate::cron::CronSchedule;
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+use tornado_common::actors::message::ActionMessage;
+use tornado_common_api::{Action, Payload, TracedAction, Value};
+use tornado_executor_common::{ExecutorError, StatelessExecutor};
+use tornado_network_common::EventBus;
+use tracing::instrument;
+
+pub mod config;
+pub mod cron;
+
+const SCHEDULED_AT_KEY: &str = "at";
+const SCHEDULED_CRON_KEY: &str = "cron";
+const SCHEDULED_ACTION_KEY: &str = "action";
+const SCHEDULED_ACTION_ID_KEY: &str = "id";
+const SCHEDULED_ACTION_PAYLOAD_KEY: &str = "payload";
+
+/// An executor that holds an Action until a point in time and then publishes it on the bus.
+/// The point in time is either a fixed timestamp or the next occurrence of a cron expression.
+/// The pending Actions are persisted to a file and they are rescheduled
+/// when the executor is created again after a restart.
+pub struct ScheduledExecutor {
+    state: Rc<ScheduledState>,
+}
+
+struct ScheduledState {
+    bus: Arc<dyn EventBus>,
+    store_path: PathBuf,
+    next_id: Cell<u64>,
+    pending: RefCell<BTreeMap<u64, ScheduledAction>>,
+}
+
+/// An Action waiting for its execution time, as persisted in the store file
+#[derive(Serialize, Deserialize, Clone)]
+struct ScheduledAction {
+    id: u64,
+    execute_at_ms: u64,
+    action: Action,
+}
+
+pub struct Params {
+    execute_at_ms: u64,
+    action: Action,
+}
+
+impl std::fmt::Display for ScheduledExecutor {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str("ScheduledExecutor")?;
+        Ok(())
+    }
+}
+
+impl ScheduledExecutor {
+    /// Creates a ScheduledExecutor and reschedules the Actions found in the store file.
+    /// As the Actions are scheduled on the current thread, it must be called
+    /// from within a Tokio LocalSet, e.g. inside an Actix System.
+    pub fn new(bus: Arc<dyn EventBus>, config: ScheduledConfig) -> Result<Self, ExecutorError> {
+        let store_path = PathBuf::from(&config.store_path);
+        let restored = load_scheduled_actions(&store_path)?;
+        info!(
+            "ScheduledExecutor - Restored {} pending actions from [{}]",
+            restored.len(),
+            config.store_path
+        );
+
+        let executor = Self {
+            state: Rc::new(ScheduledState {
+                bus,
+                store_path,
+                next_id: Cell::new(
+                    restored.iter().map(|scheduled| scheduled.id + 1).max().unwrap_or(0),
+                ),
+                pending: RefCell::new(BTreeMap::new()),
+            }),
+        };
+
+        for scheduled in restored {
+            let (id, execute_at_ms) = (scheduled.id, scheduled.execute_at_ms);
+            executor.state.pending.borrow_mut().insert(id, scheduled);
+            executor.start_timer(id, execute_at_ms);
+        }
+
+        Ok(executor)
+    }
+
+    #[instrument(level = "debug", name = "Extract parameters for Executor", skip_all)]
+    fn extract_params_from_payload(
+        &self,
+        payload: &Payload,
+        now_ms: u64,
+    ) -> Result<Params, ExecutorError> {
+        let execute_at_ms = match (payload.get(SCHEDULED_AT_KEY), payload.get(SCHEDULED_CRON_KEY)) {
+            (Some(at), None) => parse_at(at)?,
+            (None, Some(Value::String(expression))) => {
+                let schedule = CronSchedule::parse(expression).map_err(|message| {
+                    ExecutorError::MissingArgumentError {
+                        message: format!("ScheduledExecutor - {}", message),
+                    }
+                })?;
+                schedule.next_after(now_ms).ok_or_else(|| ExecutorError::MissingArgumentError {
+                    message: format!(
+                        "ScheduledExecutor - The cron expression [{}] has no next occurrence",
+                        expression
+                    ),
+                })?
+            }
+            (None, Some(_)) => {
+                return Err(ExecutorError::MissingArgumentError {
+                    message: format!(
+                        "ScheduledExecutor - Key [{}] is not a string",
+                        SCHEDULED_CRON_KEY
+                    ),
+                })
+            }
+            (Some(_), Some(_)) => {
+                return Err(ExecutorError::UnknownArgumentError {
+                    message: format!(
+                        "ScheduledExecutor - Only one of the [{}] and [{}] keys can be set",
+                        SCHEDULED_AT_KEY, SCHEDULED_CRON_KEY
+                    ),
+                })
+            }
+            (None, None) => {
+                return Err(ExecutorError::MissingArgumentError {
+                    message: format!(
+                        "ScheduledExecutor - No [{}] or [{}] key found in payload",
+                        SCHEDULED_AT_KEY, SCHEDULED_CRON_KEY
+                    ),
+                })
+            }
+        };
+
+        let action = match payload.get(SCHEDULED_ACTION_KEY) {
+            Some(value) => to_action(value, now_ms)?,
+            None => {
+                return Err(ExecutorError::MissingArgumentError {
+                    message: format!(
+                        "ScheduledExecutor - No [{}] key found in payload",
+                        SCHEDULED_ACTION_KEY
+                    ),
+                })
+            }
+        };
+
+        Ok(Params { execute_at_ms, action })
+    }
+
+    /// Waits for the execution time of the pending Action and then publishes it.
+    fn start_timer(&self, id: u64, execute_at_ms: u64) {
+        let delay = Duration::from_millis(execute_at_ms.saturating_sub(current_ts_ms()));
+        let state = self.state.clone();
+        tokio::task::spawn_local(async move {
+            tokio::time::sleep(delay).await;
+            let scheduled = state.pending.borrow_mut().remove(&id);
+            if let Some(scheduled) = scheduled {
+                if let Err(err) = state.persist() {
+                    warn!(
+                        "ScheduledExecutor - Cannot remove the executed action [{}] from the store. Err: {:?}",
+                        scheduled.action.id, err
+                    );
+                }
+                state.publish(scheduled.action);
+            }
+        });
+    }
+}
+
+impl ScheduledState {
+    fn publish(&self, action: Action) {
+        debug!("ScheduledExecutor - Publishing the scheduled action [{}]", action.id);
+        let result = self.bus.publish_action(ActionMessage(
+            TracedAction { action: Arc::new(action), span: tracing::Span::current() },
+            None,
+        ));
+        if let Err(err) = result {
+            error!("ScheduledExecutor - Cannot publish the scheduled action. Err: {}", err);
+        }
+    }
+
+    /// Writes all the pending Actions to a temporary file that then replaces the store file,
+    /// so that a crash cannot leave a partially written store.
+    fn persist(&self) -> Result<(), ExecutorError> {
+        let content = serde_json::to_vec(&self.pending.borrow().values().collect::<Vec<_>>())?;
+        let tmp_path = self.store_path.with_extension("tmp");
+        std::fs::write(&tmp_path, content)
+            .and_then(|()| std::fs::rename(&tmp_path, &self.store_path))
+            .map_err(|err| ExecutorError::ActionExecutionError {
+                can_retry: true,
+                message: format!(
+                    "ScheduledExecutor - Cannot write the store file [{}]. Err: {:?}",
+                    self.store_path.display(),
+                    err
+                ),
+                code: None,
+                data: Default::default(),
+            })
+    }
+}
+
+fn load_scheduled_actions(store_path: &Path) -> Result<Vec<ScheduledAction>, ExecutorError> {
+    if !store_path.exists() {
+        return Ok(vec![]);
+    }
+    let content = std::fs::read(store_path).map_err(|err| ExecutorError::ConfigurationError {
+        message: format!(
+            "ScheduledExecutor - Cannot read the store file [{}]. Err: {:?}",
+            store_path.display(),
+            err
+        ),
+    })?;
+    serde_json::from_slice(&content).map_err(|err| ExecutorError::ConfigurationError {
+        message: format!(
+            "ScheduledExecutor - Cannot parse the store file [{}]. Err: {:?}",
+            store_path.display(),
+            err
+        ),
+    })
+}
+
+/// Parses the execution time, expressed either in epoch milliseconds
+///   or as an RFC 3339 date, e.g. `2024-03-16T02:30:00+01:00`
+fn parse_at(value: &Value) -> Result<u64, ExecutorError> {
+    let execute_at_ms = match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(text) => chrono::DateTime::parse_from_rfc3339(text)
+            .ok()
+            .and_then(|date| u64::try_from(date.timestamp_millis()).ok()),
+        _ => None,
+    };
+    execute_at_ms.ok_or_else(|| ExecutorError::MissingArgumentError {
+        message: format!(
+            "ScheduledExecutor - Key [{}] must be an epoch timestamp in milliseconds or an RFC 3339 date, found [{}]",
+            SCHEDULED_AT_KEY, value
+        ),
+    })
+}
+
+fn to_action(value: &Value, action_created_ms: u64) -> Result<Action, ExecutorError> {
+    match (value.get(SCHEDULED_ACTION_ID_KEY), value.get(SCHEDULED_ACTION_PAYLOAD_KEY)) {
+        (Some(Value::String(id)), Some(Value::Object(payload))) => {
+            Ok(Action::new_with_payload_and_created_ms(id.to_owned(), payload.clone(), action_created_ms))
+        }
+        _ => Err(ExecutorError::MissingArgumentError {
+            message: format!(
+                "ScheduledExecutor - Not valid action format: the [{}] key must contain an object with [{}] and [{}]",
+                SCHEDULED_ACTION_KEY, SCHEDULED_ACTION_ID_KEY, SCHEDULED_ACTION_PAYLOAD_KEY
+            ),
+        }),
+    }
+}
+
+fn current_ts_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[async_trait::async_trait(?Send)]
+impl StatelessExecutor for ScheduledExecutor {
+    #[tracing::instrument(level = "info", skip_all, err, fields(otel.name = format!("Execute Action: {}", &action.id).as_str(), otel.kind = "Consumer"))]
+    async fn execute(&self, action: Arc<Action>) -> Result<(), ExecutorError> {
+        trace!("ScheduledExecutor - received action: \n[{:?}]", action);
+
+        let now_ms = current_ts_ms();
+        let Params { execute_at_ms, action } =
+            self.extract_params_from_payload(&action.payload, now_ms)?;
+
+        if execute_at_ms <= now_ms {
+            debug!(
+                "ScheduledExecutor - The execution time of action [{}] is past due, publishing it immediately",
+                action.id
+            );
+            self.state.publish(action);
+            return Ok(());
+        }
+
+        let id = self.state.next_id.get();
+        self.state.next_id.set(id + 1);
+        self.state.pending.borrow_mut().insert(id, ScheduledAction { id, execute_at_ms, action });
+        if let Err(err) = self.state.persist() {
+            self.state.pending.borrow_mut().remove(&id);
+            return Err(err);
+        }
+
+        self.start_timer(id, execute_at_ms);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+    use tornado_network_simple::SimpleEventBus;
+
+    const SCHEDULED_ACTION_ID: &str = "maintenance";
+
+    fn new_executor(store_path: &str) -> (ScheduledExecutor, Arc<Mutex<Vec<Action>>>) {
+        let published_actions = Arc::new(Mutex::new(vec![]));
+
+        let mut bus = SimpleEventBus::new();
+        {
+            let published_actions = published_actions.clone();
+            bus.subscribe_to_action(
+                SCHEDULED_ACTION_ID,
+                Box::new(move |message: ActionMessage| {
+                    published_actions.lock().unwrap().push(message.0.action.as_ref().clone());
+                }),
+            );
+        }
+
+        let executor = ScheduledExecutor::new(
+            Arc::new(bus),
+            ScheduledConfig { store_path: store_path.to_owned() },
+        )
+        .unwrap();
+
+        (executor, published_actions)
+    }
+
+    fn new_action(schedule_key: &str, schedule: Value) -> Arc<Action> {
+        let mut action = Action::new("scheduled");
+        action.payload.insert(schedule_key.to_owned(), schedule);
+        action.payload.insert(
+            SCHEDULED_ACTION_KEY.to_owned(),
+            json!({ "id": SCHEDULED_ACTION_ID, "payload": { "hostname": "host1" } }),
+        );
+        Arc::new(action)
+    }
+
+    fn store_path(dir: &tempfile::TempDir) -> String {
+        dir.path().join("scheduled.json").to_str().unwrap().to_owned()
+    }
+
+    #[actix_rt::test]
+    async fn should_publish_the_action_at_the_scheduled_time() {
+        // Arrange
+        let dir = tempfile::TempDir::new().unwrap();
+        let (executor, published_actions) = new_executor(&store_path(&dir));
+
+        // Act
+        executor.execute(new_action(SCHEDULED_AT_KEY, json!(current_ts_ms() + 200))).await.unwrap();
+
+        let published_before_schedule = published_actions.lock().unwrap().len();
+        tokio::time::sleep(Duration::from_millis(400)).await;
+
+        // Assert
+        assert_eq!(0, published_before_schedule);
+
+        let published_actions = published_actions.lock().unwrap();
+        assert_eq!(1, published_actions.len());
+        assert_eq!(SCHEDULED_ACTION_ID, published_actions[0].id);
+        assert_eq!(&json!("host1"), published_actions[0].payload.get("hostname").unwrap());
+    }
+
+    #[actix_rt::test]
+    async fn should_publish_a_past_due_action_immediately() {
+        // Arrange
+        let dir = tempfile::TempDir::new().unwrap();
+        let (executor, published_actions) = new_executor(&store_path(&dir));
+
+        // Act
+        executor
+            .execute(new_action(SCHEDULED_AT_KEY, json!("2020-01-01T00:00:00Z")))
+            .await
+            .unwrap();
+
+        // Assert
+        let published_actions = published_actions.lock().unwrap();
+        assert_eq!(1, published_actions.len());
+        assert_eq!(SCHEDULED_ACTION_ID, published_actions[0].id);
+    }
+
+    #[actix_rt::test]
+    async fn should_restore_the_pending_actions_after_a_restart() {
+        // Arrange
+        let dir = tempfile::TempDir::new().unwrap();
+        let store_path = store_path(&dir);
+        {
+            let (executor, _published_actions) = new_executor(&store_path);
+            executor
+                .execute(new_action(SCHEDULED_AT_KEY, json!(current_ts_ms() + 60_000)))
+                .await
+                .unwrap();
+        }
+
+        // Move the persisted action execution time closer
+        let mut persisted: Vec<ScheduledAction> =
+            serde_json::from_slice(&std::fs::read(&store_path).unwrap()).unwrap();
+        assert_eq!(1, persisted.len());
+        persisted[0].execute_at_ms = current_ts_ms() + 100;
+        std::fs::write(&store_path, serde_json::to_vec(&persisted).unwrap()).unwrap();
+
+        // Act
+        let (_executor, published_actions) = new_executor(&store_path);
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        // Assert
+        assert_eq!(1, published_actions.lock().unwrap().len());
+        let persisted: Vec<ScheduledAction> =
+            serde_json::from_slice(&std::fs::read(&store_path).unwrap()).unwrap();
+        assert!(persisted.is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn should_fail_if_the_schedule_is_missing() {
+        // Arrange
+        let dir = tempfile::TempDir::new().unwrap();
+        let (executor, _published_actions) = new_executor(&store_path(&dir));
+        let mut action = Action::new("scheduled");
+        action.payload.insert(
+            SCHEDULED_ACTION_KEY.to_owned(),
+            json!({ "id": SCHEDULED_ACTION_ID, "payload": {} }),
+        );
+
+        // Act
+        let result = executor.execute(Arc::new(action)).await;
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::MissingArgumentError { .. })));
+    }
+}
@@ -1,5 +1,8 @@
 use log::*;
+use std::collections::HashSet;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
 use std::sync::Arc;
 use tokio::process::Command;
 use tornado_common_api::{Action, Payload, Value};
@@ -10,7 +13,10 @@ pub const SCRIPT_TYPE_KEY: &str = "script";
 pub const SCRIPT_ARGS_KEY: &str = "args";
 
 #[derive(Default, Clone)]
-pub struct ScriptExecutor {}
+pub struct ScriptExecutor {
+    retryable_exit_codes: Option<HashSet<i32>>,
+    allowed_paths: Option<Vec<PathBuf>>,
+}
 
 struct Params<'a> {
     script: String,
@@ -18,10 +24,83 @@ struct Params<'a> {
 }
 
 impl ScriptExecutor {
+    /// Creates a ScriptExecutor that considers retryable all the failed script executions.
     pub fn new() -> ScriptExecutor {
         Default::default()
     }
 
+    /// Creates a ScriptExecutor that considers retryable only the script executions that fail
+    /// with one of the given exit codes (e.g. 75 - EX_TEMPFAIL).
+    /// All other failures, including scripts terminated by a signal, are not retryable.
+    pub fn with_retryable_exit_codes<I: IntoIterator<Item = i32>>(
+        retryable_exit_codes: I,
+    ) -> ScriptExecutor {
+        ScriptExecutor {
+            retryable_exit_codes: Some(retryable_exit_codes.into_iter().collect()),
+            allowed_paths: None,
+        }
+    }
+
+    /// Restricts the scripts that can be executed to the given paths.
+    /// Each path is either an executable file or a directory that contains the allowed
+    /// executables, at any depth. The scripts outside these paths are refused with a
+    /// non-retryable error.
+    pub fn with_allowed_paths<I: IntoIterator<Item = P>, P: Into<PathBuf>>(
+        mut self,
+        allowed_paths: I,
+    ) -> ScriptExecutor {
+        self.allowed_paths = Some(allowed_paths.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Verifies that the executable is inside one of the allowed paths.
+    /// The paths are canonicalized, so symbolic links and '..' components cannot be used
+    /// to escape from the allowed paths.
+    fn check_allowed_path(&self, executable: &str) -> Result<(), ExecutorError> {
+        let allowed_paths = match &self.allowed_paths {
+            Some(allowed_paths) => allowed_paths,
+            None => return Ok(()),
+        };
+
+        let is_allowed = Path::new(executable)
+            .canonicalize()
+            .map(|executable| {
+                allowed_paths.iter().filter_map(|path| path.canonicalize().ok()).any(
+                    |allowed_path| {
+                        if allowed_path.is_dir() {
+                            executable.starts_with(&allowed_path)
+                        } else {
+                            executable == allowed_path
+                        }
+                    },
+                )
+            })
+            .unwrap_or(false);
+
+        if is_allowed {
+            Ok(())
+        } else {
+            warn!("ScriptExecutor - Refused to execute [{}] outside the allowed paths", executable);
+            Err(ExecutorError::ActionExecutionError {
+                can_retry: false,
+                message: format!(
+                    "The script [{}] does not exist or it is not in the allowed paths",
+                    executable
+                ),
+                code: None,
+                data: Default::default(),
+            })
+        }
+    }
+
+    fn is_retryable(&self, status: &ExitStatus) -> bool {
+        match (&self.retryable_exit_codes, status.code()) {
+            (None, _) => true,
+            (Some(retryable_exit_codes), Some(code)) => retryable_exit_codes.contains(&code),
+            (Some(_), None) => false,
+        }
+    }
+
     fn append_args(cmd: &mut Command, value: &Value) {
         match value {
             Value::String(args) => {
@@ -68,18 +147,23 @@ impl ScriptExecutor {
     }
 
     #[instrument(level = "debug", name = "ScriptExecutor", skip_all, fields(otel.name = format!("Execute script: [{}]. Args: {:?}", script, args).as_str()))]
-    async fn execute_script(script: String, args: Option<&Value>) -> Result<(), ExecutorError> {
+    async fn execute_script(
+        &self,
+        script: String,
+        args: Option<&Value>,
+    ) -> Result<(), ExecutorError> {
         let output = {
             let script_iter = script.split_whitespace().collect::<Vec<&str>>();
             let mut script_iter = script_iter.iter();
-            let mut cmd = Command::new(script_iter.next().ok_or_else(|| {
-                ExecutorError::ActionExecutionError {
+            let executable =
+                script_iter.next().ok_or_else(|| ExecutorError::ActionExecutionError {
                     can_retry: false,
                     message: "The script in the payload is empty".to_owned(),
                     code: None,
                     data: Default::default(),
-                }
-            })?);
+                })?;
+            self.check_allowed_path(executable)?;
+            let mut cmd = Command::new(executable);
 
             for arg in script_iter {
                 cmd.arg(arg);
@@ -113,7 +197,7 @@ impl ScriptExecutor {
             );
 
             Err(ExecutorError::ActionExecutionError {
-                can_retry: true,
+                can_retry: self.is_retryable(&output.status),
                 message: format!(
                     "Script execution failed with status: [{}] - script: [{:?}] - stderr: [{}]",
                     output.status, script, stderr
@@ -141,7 +225,7 @@ impl StatelessExecutor for ScriptExecutor {
         let script = params.script;
         let args = params.args;
 
-        ScriptExecutor::execute_script(script, args).await
+        self.execute_script(script, args).await
     }
 }
 
@@ -185,6 +269,117 @@ mod test_unix {
         assert!(result.is_err())
     }
 
+    #[tokio::test]
+    async fn should_return_retryable_error_for_any_exit_code_by_default() {
+        // Arrange
+        let mut action = Action::new("script");
+        action.payload.insert(
+            SCRIPT_TYPE_KEY.to_owned(),
+            Value::String("./test_resources/fail.sh".to_owned()),
+        );
+
+        let executor = ScriptExecutor::new();
+
+        // Act
+        let result = executor.execute(action.into()).await;
+
+        // Assert
+        match result {
+            Err(ExecutorError::ActionExecutionError { can_retry, .. }) => assert!(can_retry),
+            _ => panic!("Expected an ActionExecutionError"),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_return_retryable_error_only_for_the_configured_exit_codes() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let filename = format!("{}/output.txt", tempdir.path().to_str().unwrap().to_owned());
+        let executor = ScriptExecutor::with_retryable_exit_codes(vec![75]);
+
+        let action_with_exit_code = |exit_code: &str| {
+            let mut action = Action::new("script");
+            action.payload.insert(
+                SCRIPT_TYPE_KEY.to_owned(),
+                Value::String(format!(
+                    "./test_resources/count_and_exit.sh {} {}",
+                    filename, exit_code
+                )),
+            );
+            Arc::new(action)
+        };
+
+        // Act
+        let temporary_failure = executor.execute(action_with_exit_code("75")).await;
+        let permanent_failure = executor.execute(action_with_exit_code("1")).await;
+
+        // Assert
+        match temporary_failure {
+            Err(ExecutorError::ActionExecutionError { can_retry, .. }) => assert!(can_retry),
+            _ => panic!("Expected an ActionExecutionError"),
+        }
+        match permanent_failure {
+            Err(ExecutorError::ActionExecutionError { can_retry, .. }) => assert!(!can_retry),
+            _ => panic!("Expected an ActionExecutionError"),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_execute_a_script_in_the_allowed_paths() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let filename = format!("{}/output.txt", tempdir.path().to_str().unwrap().to_owned());
+        let content = "HelloRustyWorld!";
+
+        let mut action = Action::new("script");
+        action.payload.insert(
+            SCRIPT_TYPE_KEY.to_owned(),
+            Value::String(format!("./test_resources/write_file.sh {} {}", filename, content)),
+        );
+
+        let executor = ScriptExecutor::new().with_allowed_paths(["/bin", "./test_resources"]);
+
+        // Act
+        executor.execute(action.into()).await.unwrap();
+
+        // Assert
+        let file_content = std::fs::read_to_string(&filename).unwrap();
+        assert_eq!(content, file_content.trim())
+    }
+
+    #[tokio::test]
+    async fn should_refuse_a_script_outside_the_allowed_paths() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let filename = format!("{}/output.txt", tempdir.path().to_str().unwrap().to_owned());
+
+        let executor = ScriptExecutor::new().with_allowed_paths(["./test_resources/echo.sh"]);
+
+        let action_with_script = |script: String| {
+            let mut action = Action::new("script");
+            action.payload.insert(SCRIPT_TYPE_KEY.to_owned(), Value::String(script));
+            Arc::new(action)
+        };
+
+        // Act
+        let not_allowed_script = executor
+            .execute(action_with_script(format!(
+                "./test_resources/write_file.sh {} content",
+                filename
+            )))
+            .await;
+        let allowed_script =
+            executor.execute(action_with_script("./test_resources/echo.sh".to_owned())).await;
+
+        // Assert
+        match not_allowed_script {
+            Err(ExecutorError::ActionExecutionError { can_retry, .. }) => assert!(!can_retry),
+            _ => panic!("Expected an ActionExecutionError"),
+        }
+        assert!(!Path::new(&filename).exists());
+        assert!(allowed_script.is_ok());
+    }
+
     #[tokio::test]
     async fn should_execute_echo_script() {
         // Arrange
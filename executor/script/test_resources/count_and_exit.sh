@@ -0,0 +1,4 @@
+#!/bin/bash
+
+echo "executed" >> $1
+exit $2
@@ -0,0 +1,55 @@
+#![cfg(unix)]
+
+use std::sync::Arc;
+use tornado_common::command::retry::{BackoffPolicy, RetryCommand, RetryPolicy, RetryStrategy};
+use tornado_common::command::{Command, StatelessExecutorCommand};
+use tornado_common::metrics::ActionMeter;
+use tornado_common_api::{Action, Value};
+use tornado_executor_script::{ScriptExecutor, SCRIPT_TYPE_KEY};
+
+const RETRIES: u32 = 3;
+
+async fn execute_with_retries(exit_code: i32) -> usize {
+    let tempdir = tempfile::tempdir().unwrap();
+    let filename = format!("{}/executions.txt", tempdir.path().to_str().unwrap());
+
+    let command = RetryCommand::new(
+        RetryStrategy {
+            retry_policy: RetryPolicy::MaxRetries { retries: RETRIES },
+            backoff_policy: BackoffPolicy::None,
+        },
+        StatelessExecutorCommand::new(
+            Arc::new(ActionMeter::new("test_meter")),
+            ScriptExecutor::with_retryable_exit_codes(vec![75]),
+        ),
+    );
+
+    let mut action = Action::new("script");
+    action.payload.insert(
+        SCRIPT_TYPE_KEY.to_owned(),
+        Value::String(format!("./test_resources/count_and_exit.sh {} {}", filename, exit_code)),
+    );
+
+    let result = command.execute(Arc::new(action)).await;
+    assert!(result.is_err());
+
+    std::fs::read_to_string(&filename).unwrap().lines().count()
+}
+
+#[actix_rt::test]
+async fn should_retry_the_script_if_the_exit_code_is_retryable() {
+    // Act
+    let executions = execute_with_retries(75).await;
+
+    // Assert
+    assert_eq!(RETRIES as usize + 1, executions);
+}
+
+#[actix_rt::test]
+async fn should_not_retry_the_script_if_the_exit_code_is_not_retryable() {
+    // Act
+    let executions = execute_with_retries(1).await;
+
+    // Assert
+    assert_eq!(1, executions);
+}
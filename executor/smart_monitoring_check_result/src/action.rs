@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tornado_common_api::{Action, Payload, Value, ValueExt};
-use tornado_executor_common::ExecutorError;
+use tornado_executor_common::{merge_default_payload, ExecutorError};
 use tornado_executor_director::{DirectorAction, DirectorActionName};
 use tornado_executor_icinga2::Icinga2Action;
 
@@ -37,6 +37,15 @@ impl SimpleCreateAndProcess {
         Ok(scap)
     }
 
+    /// Merges a default payload under the payloads of the host and of the service to be created.
+    /// In case of conflict, the values of the Action win.
+    pub fn merge_default_object_payload(&mut self, default_object_payload: &Payload) {
+        merge_default_payload(&mut self.host, default_object_payload);
+        if let Some(service) = &mut self.service {
+            merge_default_payload(service, default_object_payload);
+        }
+    }
+
     // Transforms the SimpleCreateAndProcess into the actions needed to call the IcingaExecutor and the
     // DirectorExecutor.
     // Returns a triple, with these elements:
@@ -147,6 +156,46 @@ pub mod test {
     use serde_json::json;
     use tornado_common_api::{Action, Map, Value};
 
+    #[test]
+    fn should_merge_the_default_object_payload_under_the_host_and_the_service() {
+        // Arrange
+        let mut action = Action::new("");
+        action.payload.insert("check_result".to_owned(), Value::Object(Map::new()));
+        action.payload.insert(
+            "host".to_owned(),
+            json!({ "object_name": "myhost", "vars": { "location": "Rome" } }),
+        );
+        action.payload.insert(
+            "service".to_owned(),
+            json!({ "object_name": "myservice", "zone": "satellite" }),
+        );
+        let default_object_payload =
+            json!({ "zone": "master", "vars": { "location": "Bolzano", "owner": "ops" } });
+
+        let mut monitoring_action = SimpleCreateAndProcess::new(&action).unwrap();
+
+        // Act
+        monitoring_action.merge_default_object_payload(default_object_payload.get_map().unwrap());
+
+        // Assert
+        assert_eq!(
+            &json!({
+                "object_name": "myhost",
+                "zone": "master",
+                "vars": { "location": "Rome", "owner": "ops" }
+            }),
+            &json!(monitoring_action.host)
+        );
+        assert_eq!(
+            &json!({
+                "object_name": "myservice",
+                "zone": "satellite",
+                "vars": { "location": "Bolzano", "owner": "ops" }
+            }),
+            &json!(monitoring_action.service)
+        );
+    }
+
     #[test]
     fn to_sub_actions_should_throw_error_if_process_check_result_host_not_specified_with_host_field(
     ) {
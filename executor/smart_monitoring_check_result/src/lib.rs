@@ -2,11 +2,12 @@ use action::SimpleCreateAndProcess;
 use log::*;
 use std::{future::Future, pin::Pin, sync::Arc};
 use tornado_common_api::RetriableError;
-use tornado_common_api::{Action, Payload};
+use tornado_common_api::{Action, Payload, ValueExt};
 use tornado_executor_common::{ExecutorError, StatelessExecutor};
 use tornado_executor_director::config::DirectorClientConfig;
 use tornado_executor_director::{
-    DirectorAction, DirectorExecutor, ICINGA2_OBJECT_ALREADY_EXISTING_EXECUTOR_ERROR_CODE,
+    DirectorAction, DirectorExecutor, DIRECTOR_ACTION_PAYLOAD_KEY,
+    ICINGA2_OBJECT_ALREADY_EXISTING_EXECUTOR_ERROR_CODE,
 };
 use tornado_executor_icinga2::config::Icinga2ClientConfig;
 use tornado_executor_icinga2::{
@@ -23,6 +24,7 @@ mod action;
 pub struct SmartMonitoringExecutor {
     icinga_executor: Arc<Icinga2Executor>,
     director_executor: DirectorExecutor,
+    default_object_payload: Payload,
 }
 
 impl std::fmt::Display for SmartMonitoringExecutor {
@@ -37,9 +39,17 @@ impl SmartMonitoringExecutor {
         icinga2_client_config: Icinga2ClientConfig,
         director_client_config: DirectorClientConfig,
     ) -> Result<SmartMonitoringExecutor, ExecutorError> {
+        // The created objects get the defaults of the Director Actions payload
+        let default_object_payload = director_client_config
+            .default_payload
+            .get(DIRECTOR_ACTION_PAYLOAD_KEY)
+            .and_then(|value| value.get_map())
+            .cloned()
+            .unwrap_or_default();
         Ok(SmartMonitoringExecutor {
             icinga_executor: Arc::new(Icinga2Executor::new(icinga2_client_config)?),
             director_executor: DirectorExecutor::new(director_client_config)?,
+            default_object_payload,
         })
     }
 
@@ -170,6 +180,7 @@ impl StatelessExecutor for SmartMonitoringExecutor {
         let extraction_params_guard =
             tracing::debug_span!("Extract parameters for Executor").entered();
         let mut monitoring_action = SimpleCreateAndProcess::new(&action)?;
+        monitoring_action.merge_default_object_payload(&self.default_object_payload);
         let host_name = monitoring_action.get_host_name().map(|val| val.to_owned());
         let service_name = monitoring_action.get_service_name().map(|val| val.to_owned());
 
@@ -214,17 +225,24 @@ mod test {
         let executor = SmartMonitoringExecutor::new(
             Icinga2ClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: "".to_owned(),
+                extra_headers: Default::default(),
             },
             DirectorClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: "".to_owned(),
+                default_payload: Default::default(),
+                extra_headers: Default::default(),
             },
         )
         .unwrap();
@@ -254,17 +272,24 @@ mod test {
         let executor = SmartMonitoringExecutor::new(
             Icinga2ClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: mock_server.url(""),
+                extra_headers: Default::default(),
             },
             DirectorClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: "".to_owned(),
+                default_payload: Default::default(),
+                extra_headers: Default::default(),
             },
         )
         .unwrap();
@@ -299,17 +324,24 @@ mod test {
         let executor = SmartMonitoringExecutor::new(
             Icinga2ClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: "".to_owned(),
+                extra_headers: Default::default(),
             },
             DirectorClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: "".to_owned(),
+                default_payload: Default::default(),
+                extra_headers: Default::default(),
             },
         )
         .unwrap();
@@ -348,17 +380,24 @@ mod test {
         let executor = SmartMonitoringExecutor::new(
             Icinga2ClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: "".to_owned(),
+                extra_headers: Default::default(),
             },
             DirectorClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: "".to_owned(),
+                default_payload: Default::default(),
+                extra_headers: Default::default(),
             },
         )
         .unwrap();
@@ -394,17 +433,24 @@ mod test {
         let executor = SmartMonitoringExecutor::new(
             Icinga2ClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: "".to_owned(),
+                extra_headers: Default::default(),
             },
             DirectorClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: "".to_owned(),
+                default_payload: Default::default(),
+                extra_headers: Default::default(),
             },
         )
         .unwrap();
@@ -447,17 +493,24 @@ mod test {
         let executor = SmartMonitoringExecutor::new(
             Icinga2ClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: mock_server.url(""),
+                extra_headers: Default::default(),
             },
             DirectorClientConfig {
                 timeout_secs: None,
+                pool_max_idle_per_host: None,
+                pool_idle_timeout_secs: None,
                 username: "".to_owned(),
                 password: "".to_owned(),
                 disable_ssl_verification: true,
                 server_api_url: "".to_owned(),
+                default_payload: Default::default(),
+                extra_headers: Default::default(),
             },
         )
         .unwrap();
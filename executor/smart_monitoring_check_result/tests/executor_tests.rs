@@ -22,17 +22,24 @@ async fn should_return_error_if_process_check_result_fails_with_error_different_
     let executor = SmartMonitoringExecutor::new(
         Icinga2ClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: icinga_server.url(""),
+            extra_headers: Default::default(),
         },
         DirectorClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: "".to_owned(),
+            default_payload: Default::default(),
+            extra_headers: Default::default(),
         },
     )
     .unwrap();
@@ -81,17 +88,24 @@ async fn should_return_ok_if_process_check_result_is_successful() {
     let executor = SmartMonitoringExecutor::new(
         Icinga2ClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: icinga_server.url(""),
+            extra_headers: Default::default(),
         },
         DirectorClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: "".to_owned(),
+            default_payload: Default::default(),
+            extra_headers: Default::default(),
         },
     )
     .unwrap();
@@ -139,17 +153,24 @@ async fn should_return_call_process_check_result_twice_on_non_existing_object()
     let executor = SmartMonitoringExecutor::new(
         Icinga2ClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: icinga_server.url(""),
+            extra_headers: Default::default(),
         },
         DirectorClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: director_server.url(""),
+            default_payload: Default::default(),
+            extra_headers: Default::default(),
         },
     )
     .unwrap();
@@ -200,17 +221,24 @@ async fn should_return_return_error_on_object_creation_failure() {
     let executor = SmartMonitoringExecutor::new(
         Icinga2ClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: icinga_server.url(""),
+            extra_headers: Default::default(),
         },
         DirectorClientConfig {
             timeout_secs: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_secs: None,
             username: "".to_owned(),
             password: "".to_owned(),
             disable_ssl_verification: true,
             server_api_url: director_server.url(""),
+            default_payload: Default::default(),
+            extra_headers: Default::default(),
         },
     )
     .unwrap();
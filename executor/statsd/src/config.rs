@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct StatsdExecutorConfig {
+    /// The address of the StatsD server in the `host:port` format, e.g. `127.0.0.1:8125`
+    pub address: String,
+
+    /// An optional prefix added to the name of every metric. E.g. with the `tornado` prefix,
+    /// the `events` metric is sent as `tornado.events`.
+    pub prefix: Option<String>,
+
+    /// How the tags of the metrics are sent to the server. Default is `None`
+    #[serde(default)]
+    pub tag_format: StatsdTagFormat,
+}
+
+/// The format of the metric tags
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum StatsdTagFormat {
+    /// Plain StatsD; it has no support for tags, so the tags of the metrics are discarded
+    #[default]
+    None,
+    /// The DogStatsD extension; the tags are appended to the packet as `|#key:value,key:value`
+    DogStatsd,
+}
@@ -0,0 +1,441 @@
+use crate::config::{StatsdExecutorConfig, StatsdTagFormat};
+use log::*;
+use maplit::*;
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use tokio::sync::OnceCell;
+use tornado_common_api::{Action, Value, ValueExt};
+use tornado_executor_common::{ExecutorError, StatelessExecutor};
+use tracing::instrument;
+
+pub mod config;
+
+pub const STATSD_NAME_KEY: &str = "name";
+pub const STATSD_TYPE_KEY: &str = "type";
+pub const STATSD_VALUE_KEY: &str = "value";
+pub const STATSD_TAGS_KEY: &str = "tags";
+
+const FORBIDDEN_NAME_CHARS: &[char] = &[':', '|', '@', '#', ',', '\n'];
+const FORBIDDEN_TAG_CHARS: &[char] = &['|', '@', '#', ',', '\n'];
+
+/// An executor that sends metrics to a StatsD server
+pub struct StatsdExecutor {
+    config: StatsdExecutorConfig,
+    /// The UDP socket connected to the server, created when the first metric is sent.
+    /// The server address is resolved only once, when the socket is created.
+    socket: OnceCell<UdpSocket>,
+}
+
+impl std::fmt::Display for StatsdExecutor {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str("StatsdExecutor")?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StatsdMetricType {
+    Counter,
+    Gauge,
+    Timer,
+}
+
+impl StatsdMetricType {
+    fn to_statsd_type(self) -> &'static str {
+        match self {
+            StatsdMetricType::Counter => "c",
+            StatsdMetricType::Gauge => "g",
+            StatsdMetricType::Timer => "ms",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StatsdMetric {
+    pub name: String,
+    pub metric_type: StatsdMetricType,
+    pub value: String,
+    pub tags: Vec<(String, String)>,
+}
+
+impl StatsdExecutor {
+    pub fn new(config: StatsdExecutorConfig) -> StatsdExecutor {
+        StatsdExecutor { config, socket: OnceCell::new() }
+    }
+
+    #[instrument(level = "debug", name = "Extract parameters for Executor", skip_all)]
+    fn parse_action(&self, action: &Action) -> Result<StatsdMetric, ExecutorError> {
+        let name =
+            action.payload.get(STATSD_NAME_KEY).and_then(ValueExt::get_text).ok_or_else(|| {
+                ExecutorError::MissingArgumentError {
+                    message: format!("StatsdExecutor - [{}] not specified", STATSD_NAME_KEY),
+                }
+            })?;
+        check_text(STATSD_NAME_KEY, name, FORBIDDEN_NAME_CHARS)?;
+
+        let metric_type = match action.payload.get(STATSD_TYPE_KEY) {
+            Some(value) => parse_metric_type(value)?,
+            None => {
+                return Err(ExecutorError::MissingArgumentError {
+                    message: format!("StatsdExecutor - [{}] not specified", STATSD_TYPE_KEY),
+                })
+            }
+        };
+
+        let value = match action.payload.get(STATSD_VALUE_KEY) {
+            Some(value) => parse_metric_value(value)?,
+            None => {
+                return Err(ExecutorError::MissingArgumentError {
+                    message: format!("StatsdExecutor - [{}] not specified", STATSD_VALUE_KEY),
+                })
+            }
+        };
+
+        let tags = match action.payload.get(STATSD_TAGS_KEY) {
+            Some(value) => parse_tags(value)?,
+            None => vec![],
+        };
+
+        Ok(StatsdMetric { name: name.to_owned(), metric_type, value, tags })
+    }
+
+    /// Builds the StatsD packet of a metric, e.g. `tornado.events:1|c|#host:host1`
+    pub fn to_packet(&self, metric: &StatsdMetric) -> String {
+        let mut packet = match &self.config.prefix {
+            Some(prefix) => format!("{}.{}", prefix, metric.name),
+            None => metric.name.clone(),
+        };
+        packet.push(':');
+        packet.push_str(&metric.value);
+        packet.push('|');
+        packet.push_str(metric.metric_type.to_statsd_type());
+
+        if !metric.tags.is_empty() {
+            match self.config.tag_format {
+                StatsdTagFormat::DogStatsd => {
+                    let tags = metric
+                        .tags
+                        .iter()
+                        .map(|(key, value)| format!("{}:{}", key, value))
+                        .collect::<Vec<_>>();
+                    packet.push_str("|#");
+                    packet.push_str(&tags.join(","));
+                }
+                StatsdTagFormat::None => {
+                    warn!(
+                        "StatsdExecutor - The tags of metric [{}] are discarded because the tag format is not configured",
+                        metric.name
+                    );
+                }
+            }
+        }
+
+        packet
+    }
+
+    #[instrument(level = "debug", name = "StatsdExecutor", skip_all, fields(otel.name = format!("Send metric to StatsD server [{}]", self.config.address).as_str()))]
+    pub async fn send(&self, packet: &str) -> Result<(), ExecutorError> {
+        let socket = self.socket.get_or_try_init(|| self.connect()).await?;
+        socket.send(packet.as_bytes()).await.map_err(|err| {
+            to_retryable_error(
+                &self.config.address,
+                format!("Cannot send the metric. Err: {:?}", err),
+            )
+        })?;
+
+        debug!("StatsdExecutor - Metric sent to [{}]: {}", self.config.address, packet);
+        Ok(())
+    }
+
+    /// Resolves the server address and opens a UDP socket connected to it.
+    async fn connect(&self) -> Result<UdpSocket, ExecutorError> {
+        let address = tokio::net::lookup_host(&self.config.address)
+            .await
+            .map_err(|err| {
+                to_retryable_error(
+                    &self.config.address,
+                    format!("Cannot resolve the server address. Err: {:?}", err),
+                )
+            })?
+            .next()
+            .ok_or_else(|| {
+                to_retryable_error(
+                    &self.config.address,
+                    "The server address does not resolve to any IP".to_owned(),
+                )
+            })?;
+
+        let local_address = if address.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(local_address).await.map_err(|err| {
+            to_retryable_error(
+                &self.config.address,
+                format!("Cannot open the UDP socket. Err: {:?}", err),
+            )
+        })?;
+        socket.connect(address).await.map_err(|err| {
+            to_retryable_error(
+                &self.config.address,
+                format!("Cannot connect the UDP socket to [{}]. Err: {:?}", address, err),
+            )
+        })?;
+        debug!(
+            "StatsdExecutor - Server address [{}] resolved to [{}]",
+            self.config.address, address
+        );
+        Ok(socket)
+    }
+}
+
+fn parse_metric_type(value: &Value) -> Result<StatsdMetricType, ExecutorError> {
+    match value.get_text() {
+        Some("counter") => Ok(StatsdMetricType::Counter),
+        Some("gauge") => Ok(StatsdMetricType::Gauge),
+        Some("timer") => Ok(StatsdMetricType::Timer),
+        _ => Err(ExecutorError::UnknownArgumentError {
+            message: format!(
+                "StatsdExecutor - Invalid [{}] value. Found: {}. Expected one of: counter, gauge, timer",
+                STATSD_TYPE_KEY, value
+            ),
+        }),
+    }
+}
+
+/// Returns the text representation of the metric value.
+/// The value can be a number or a text containing a number, as produced by an interpolation.
+fn parse_metric_value(value: &Value) -> Result<String, ExecutorError> {
+    let text = match value {
+        Value::Number(number) => Some(number.to_string()),
+        Value::String(text) => {
+            let text = text.trim();
+            match text.parse::<f64>() {
+                Ok(number) if number.is_finite() => Some(text.to_owned()),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    text.ok_or_else(|| ExecutorError::UnknownArgumentError {
+        message: format!(
+            "StatsdExecutor - [{}] should be a number. Found: {}",
+            STATSD_VALUE_KEY, value
+        ),
+    })
+}
+
+fn parse_tags(value: &Value) -> Result<Vec<(String, String)>, ExecutorError> {
+    let tags = value.get_map().ok_or_else(|| ExecutorError::UnknownArgumentError {
+        message: format!(
+            "StatsdExecutor - [{}] should be a map. Found: {}",
+            STATSD_TAGS_KEY, value
+        ),
+    })?;
+
+    tags.iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(text) => text.clone(),
+                Value::Number(number) => number.to_string(),
+                Value::Bool(boolean) => boolean.to_string(),
+                _ => {
+                    return Err(ExecutorError::UnknownArgumentError {
+                        message: format!(
+                            "StatsdExecutor - The value of tag [{}] should be a text, a number or a boolean. Found: {}",
+                            key, value
+                        ),
+                    })
+                }
+            };
+            check_text(STATSD_TAGS_KEY, key, FORBIDDEN_NAME_CHARS)?;
+            check_text(STATSD_TAGS_KEY, &value, FORBIDDEN_TAG_CHARS)?;
+            Ok((key.clone(), value))
+        })
+        .collect()
+}
+
+/// Verifies that the text is not empty and does not contain any of the characters
+/// that have a special meaning in the StatsD packets.
+fn check_text(key: &str, text: &str, forbidden_chars: &[char]) -> Result<(), ExecutorError> {
+    if text.is_empty() || text.contains(forbidden_chars) {
+        return Err(ExecutorError::UnknownArgumentError {
+            message: format!(
+                "StatsdExecutor - Invalid [{}] value [{}]. It must not be empty and must not contain any of: {:?}",
+                key, text, forbidden_chars
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn to_retryable_error(address: &str, message: String) -> ExecutorError {
+    ExecutorError::ActionExecutionError {
+        can_retry: true,
+        message: format!("StatsdExecutor - {}", message),
+        code: None,
+        data: hashmap!["address" => address.into()].into(),
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl StatelessExecutor for StatsdExecutor {
+    #[tracing::instrument(level = "info", skip_all, err, fields(otel.name = format!("Execute Action: {}", &action.id).as_str(), otel.kind = "Consumer"))]
+    async fn execute(&self, action: Arc<Action>) -> Result<(), ExecutorError> {
+        trace!("StatsdExecutor - received action: \n[{:?}]", action);
+        let metric = self.parse_action(&action)?;
+        let packet = self.to_packet(&metric);
+        self.send(&packet).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+
+    fn new_executor(prefix: Option<&str>, tag_format: StatsdTagFormat) -> StatsdExecutor {
+        StatsdExecutor::new(StatsdExecutorConfig {
+            address: "127.0.0.1:8125".to_owned(),
+            prefix: prefix.map(|prefix| prefix.to_owned()),
+            tag_format,
+        })
+    }
+
+    fn new_action(name: &str, metric_type: &str, value: Value) -> Action {
+        let mut action = Action::new("statsd");
+        action.payload.insert("name".to_owned(), json!(name));
+        action.payload.insert("type".to_owned(), json!(metric_type));
+        action.payload.insert("value".to_owned(), value);
+        action
+    }
+
+    #[test]
+    fn should_parse_the_action() {
+        // Arrange
+        let executor = new_executor(None, StatsdTagFormat::DogStatsd);
+        let mut action = new_action("cpu.load", "gauge", json!(" 0.75 "));
+        action.payload.insert("tags".to_owned(), json!({"host": "host1", "core": 2}));
+
+        // Act
+        let metric = executor.parse_action(&action).unwrap();
+
+        // Assert
+        assert_eq!(
+            StatsdMetric {
+                name: "cpu.load".to_owned(),
+                metric_type: StatsdMetricType::Gauge,
+                value: "0.75".to_owned(),
+                tags: vec![
+                    ("core".to_owned(), "2".to_owned()),
+                    ("host".to_owned(), "host1".to_owned())
+                ],
+            },
+            metric
+        );
+    }
+
+    #[test]
+    fn should_build_the_packet_with_prefix() {
+        // Arrange
+        let executor = new_executor(Some("tornado"), StatsdTagFormat::None);
+        let action = new_action("response_time", "timer", json!(320));
+
+        // Act
+        let packet = executor.to_packet(&executor.parse_action(&action).unwrap());
+
+        // Assert
+        assert_eq!("tornado.response_time:320|ms", packet);
+    }
+
+    #[test]
+    fn should_discard_the_tags_without_tag_format() {
+        // Arrange
+        let executor = new_executor(None, StatsdTagFormat::None);
+        let mut action = new_action("events", "counter", json!(1));
+        action.payload.insert("tags".to_owned(), json!({"host": "host1"}));
+
+        // Act
+        let packet = executor.to_packet(&executor.parse_action(&action).unwrap());
+
+        // Assert
+        assert_eq!("events:1|c", packet);
+    }
+
+    #[test]
+    fn should_fail_if_type_is_unknown() {
+        // Arrange
+        let executor = new_executor(None, StatsdTagFormat::None);
+        let action = new_action("events", "histogram", json!(1));
+
+        // Act
+        let result = executor.parse_action(&action);
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::UnknownArgumentError { .. })));
+    }
+
+    #[test]
+    fn should_fail_if_value_is_not_a_number() {
+        // Arrange
+        let executor = new_executor(None, StatsdTagFormat::None);
+        let action = new_action("events", "counter", json!("one"));
+
+        // Act
+        let result = executor.parse_action(&action);
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::UnknownArgumentError { .. })));
+    }
+
+    #[test]
+    fn should_fail_if_name_contains_reserved_chars() {
+        // Arrange
+        let executor = new_executor(None, StatsdTagFormat::None);
+        let action = new_action("events|c", "counter", json!(1));
+
+        // Act
+        let result = executor.parse_action(&action);
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::UnknownArgumentError { .. })));
+    }
+
+    #[test]
+    fn should_fail_if_name_is_missing() {
+        // Arrange
+        let executor = new_executor(None, StatsdTagFormat::None);
+        let mut action = new_action("events", "counter", json!(1));
+        action.payload.remove("name");
+
+        // Act
+        let result = executor.parse_action(&action);
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::MissingArgumentError { .. })));
+    }
+
+    #[actix_rt::test]
+    async fn should_send_all_the_metrics_from_the_same_socket() {
+        // Arrange
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let executor = StatsdExecutor::new(StatsdExecutorConfig {
+            address: server.local_addr().unwrap().to_string(),
+            prefix: None,
+            tag_format: StatsdTagFormat::None,
+        });
+        let mut buf = [0; 1024];
+
+        // Act
+        executor.execute(Arc::new(new_action("events", "counter", json!(1)))).await.unwrap();
+        let (first_len, first_sender) = server.recv_from(&mut buf).await.unwrap();
+        let first_packet = String::from_utf8_lossy(&buf[..first_len]).to_string();
+
+        executor.execute(Arc::new(new_action("events", "counter", json!(2)))).await.unwrap();
+        let (second_len, second_sender) = server.recv_from(&mut buf).await.unwrap();
+        let second_packet = String::from_utf8_lossy(&buf[..second_len]).to_string();
+
+        // Assert
+        assert_eq!("events:1|c", first_packet);
+        assert_eq!("events:2|c", second_packet);
+        assert_eq!(first_sender, second_sender);
+    }
+}
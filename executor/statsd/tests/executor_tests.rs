@@ -0,0 +1,66 @@
+use serde_json::json;
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use tornado_common_api::Action;
+use tornado_executor_common::StatelessExecutor;
+use tornado_executor_statsd::config::{StatsdExecutorConfig, StatsdTagFormat};
+use tornado_executor_statsd::StatsdExecutor;
+
+async fn new_statsd_server() -> (UdpSocket, String) {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let address = socket.local_addr().unwrap().to_string();
+    (socket, address)
+}
+
+async fn receive_packet(socket: &UdpSocket) -> String {
+    let mut buffer = [0; 1024];
+    let (size, _) = socket.recv_from(&mut buffer).await.unwrap();
+    String::from_utf8(buffer[..size].to_vec()).unwrap()
+}
+
+#[actix_rt::test]
+async fn should_send_a_counter_increment() {
+    // Arrange
+    let (server, address) = new_statsd_server().await;
+    let executor = StatsdExecutor::new(StatsdExecutorConfig {
+        address,
+        prefix: Some("tornado".to_owned()),
+        tag_format: StatsdTagFormat::None,
+    });
+
+    let mut action = Action::new("statsd");
+    action.payload.insert("name".to_owned(), json!("events.critical"));
+    action.payload.insert("type".to_owned(), json!("counter"));
+    action.payload.insert("value".to_owned(), json!(1));
+
+    // Act
+    let result = executor.execute(Arc::new(action)).await;
+
+    // Assert
+    assert!(result.is_ok());
+    assert_eq!("tornado.events.critical:1|c", receive_packet(&server).await);
+}
+
+#[actix_rt::test]
+async fn should_send_a_tagged_gauge_with_the_dogstatsd_format() {
+    // Arrange
+    let (server, address) = new_statsd_server().await;
+    let executor = StatsdExecutor::new(StatsdExecutorConfig {
+        address,
+        prefix: None,
+        tag_format: StatsdTagFormat::DogStatsd,
+    });
+
+    let mut action = Action::new("statsd");
+    action.payload.insert("name".to_owned(), json!("disk.usage"));
+    action.payload.insert("type".to_owned(), json!("gauge"));
+    action.payload.insert("value".to_owned(), json!("87.5"));
+    action.payload.insert("tags".to_owned(), json!({"host": "host1", "mount": "/var"}));
+
+    // Act
+    let result = executor.execute(Arc::new(action)).await;
+
+    // Assert
+    assert!(result.is_ok());
+    assert_eq!("disk.usage:87.5|g|#host:host1,mount:/var", receive_packet(&server).await);
+}
@@ -1,5 +1,12 @@
+use thiserror::Error;
 use tornado_common::actors::message::ActionMessage;
 
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum EventBusError {
+    #[error("ActionTooLargeError: the action [{action_id}] is {size} bytes long, but the max allowed size is {max_size} bytes")]
+    ActionTooLargeError { action_id: String, size: usize, max_size: usize },
+}
+
 pub trait EventBus {
-    fn publish_action(&self, message: ActionMessage);
+    fn publish_action(&self, message: ActionMessage) -> Result<(), EventBusError>;
 }
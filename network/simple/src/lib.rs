@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use tornado_common::actors::message::ActionMessage;
-use tornado_network_common::EventBus;
+use tornado_network_common::{EventBus, EventBusError};
 
 #[derive(Default)]
 pub struct SimpleEventBus {
@@ -24,10 +24,11 @@ impl SimpleEventBus {
 }
 
 impl EventBus for SimpleEventBus {
-    fn publish_action(&self, message: ActionMessage) {
+    fn publish_action(&self, message: ActionMessage) -> Result<(), EventBusError> {
         if let Some(handler) = self.subscribers.get(&message.0.action.id) {
             handler(message)
         };
+        Ok(())
     }
 }
 
@@ -56,13 +57,13 @@ mod test {
             }),
         );
 
-        let action = ActionMessage(TracedAction {
-            span: Span::current(),
-            action: Arc::new(Action::new(action_id)),
-        });
+        let action = ActionMessage(
+            TracedAction { span: Span::current(), action: Arc::new(Action::new(action_id)) },
+            None,
+        );
 
         // Act
-        bus.publish_action(action);
+        bus.publish_action(action).unwrap();
 
         // Assert
         let value = &*received.lock().unwrap();
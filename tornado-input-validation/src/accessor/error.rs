@@ -7,6 +7,7 @@ pub enum AccessorErrorKind {
     NotANumberError,
     InvalidCharacterError,
     EmptyAccessorError,
+    InvalidFunctionError,
 }
 
 // The AccessorError struct must be "inspectable" because otherwise the
@@ -60,6 +61,13 @@ impl From<tornado_common_parser::ParserError> for AccessorError {
                 key: None,
                 character: None,
             },
+            tornado_common_parser::ParserError::InvalidFunctionError { function, .. } => {
+                AccessorError {
+                    kind: AccessorErrorKind::InvalidFunctionError,
+                    key: Some(function),
+                    character: None,
+                }
+            }
         }
     }
 }
@@ -1,19 +1,23 @@
 use crate::actors::message::ActionMessage;
 use crate::command::Command;
+use crate::dead_letter::DeadLetterStore;
 use crate::metrics::{
     ActionMeter, ACTION_ID_LABEL_KEY, ACTION_RESULT_KEY, RESULT_FAILURE, RESULT_SUCCESS,
 };
+use crate::shutdown::ShutdownCoordinator;
 use actix::{Actor, Addr, Context, Handler};
 use log::*;
 use std::rc::Rc;
 use std::sync::Arc;
-use tornado_common_api::Action;
+use tornado_common_api::{Action, RetriableError};
 use tornado_executor_common::ExecutorError;
 use tracing_futures::Instrument;
 
 pub struct CommandExecutorActor<T: Command<Arc<Action>, Result<(), ExecutorError>> + 'static> {
     pub command: Rc<T>,
     action_meter: Arc<ActionMeter>,
+    shutdown: ShutdownCoordinator,
+    dead_letter_store: Option<Arc<DeadLetterStore>>,
 }
 
 impl<T: Command<Arc<Action>, Result<(), ExecutorError>> + 'static> CommandExecutorActor<T> {
@@ -21,10 +25,44 @@ impl<T: Command<Arc<Action>, Result<(), ExecutorError>> + 'static> CommandExecut
         message_mailbox_capacity: usize,
         command: Rc<T>,
         action_meter: Arc<ActionMeter>,
+    ) -> Addr<CommandExecutorActor<T>> {
+        Self::start_new_with_shutdown(
+            message_mailbox_capacity,
+            command,
+            action_meter,
+            ShutdownCoordinator::new(),
+        )
+    }
+
+    /// Starts a CommandExecutorActor that registers the actions in execution
+    /// in the ShutdownCoordinator, so that they can be drained on shutdown.
+    pub fn start_new_with_shutdown(
+        message_mailbox_capacity: usize,
+        command: Rc<T>,
+        action_meter: Arc<ActionMeter>,
+        shutdown: ShutdownCoordinator,
+    ) -> Addr<CommandExecutorActor<T>> {
+        Self::start_new_with_dead_letter_store(
+            message_mailbox_capacity,
+            command,
+            action_meter,
+            shutdown,
+            None,
+        )
+    }
+
+    /// Starts a CommandExecutorActor that writes to the DeadLetterStore, if present,
+    /// the actions that failed after exhausting their retries.
+    pub fn start_new_with_dead_letter_store(
+        message_mailbox_capacity: usize,
+        command: Rc<T>,
+        action_meter: Arc<ActionMeter>,
+        shutdown: ShutdownCoordinator,
+        dead_letter_store: Option<Arc<DeadLetterStore>>,
     ) -> Addr<CommandExecutorActor<T>> {
         CommandExecutorActor::create(move |ctx| {
             ctx.set_mailbox_capacity(message_mailbox_capacity);
-            Self { command, action_meter }
+            Self { command, action_meter, shutdown, dead_letter_store }
         })
     }
 }
@@ -44,21 +82,36 @@ impl<T: Command<Arc<Action>, Result<(), ExecutorError>> + 'static> Handler<Actio
     type Result = Result<(), ExecutorError>;
 
     fn handle(&mut self, msg: ActionMessage, _: &mut Context<Self>) -> Self::Result {
-        let _parent_guard = msg.0.span.clone().entered();
+        let _parent_guard = msg.0.span.entered();
 
         let command = self.command.clone();
         let action_meter = self.action_meter.clone();
+        // The actions enqueued without an InFlightAction are registered when they are received
+        let in_flight_action = msg.1.unwrap_or_else(|| self.shutdown.action_started());
+        let dead_letter_store = self.dead_letter_store.clone();
 
         let action = msg.0.action;
+        // All the log records of the execution are enclosed in this span,
+        // so they can be correlated to the Event that originated the Action
+        let execution_span = tracing::error_span!(
+            "execute_action",
+            action_id = action.id.as_str(),
+            trace_id = tracing::field::Empty
+        );
+        if let Some(trace_id) = &action.trace_id {
+            execution_span.record("trace_id", trace_id.as_str());
+        }
+
         actix::spawn(
             async move {
+                let _in_flight_action = in_flight_action;
                 let action_id = action.id.to_owned();
                 trace!("CommandExecutorActor - received new action [{:?}]", &action);
                 debug!("CommandExecutorActor - Execute action [{:?}]", &action_id);
 
                 let action_id_label = ACTION_ID_LABEL_KEY.string(action_id.to_owned());
 
-                match command.execute(action).await {
+                match command.execute(action.clone()).await {
                     Ok(_) => {
                         action_meter
                             .actions_processed_counter
@@ -76,10 +129,24 @@ impl<T: Command<Arc<Action>, Result<(), ExecutorError>> + 'static> Handler<Actio
                             "CommandExecutorActor - Failed to execute action [{}]: {:?}",
                             &action_id, e
                         );
+                        // Only the retriable errors are dead-lettered: an action that
+                        // failed with a non retriable error would fail again if replayed
+                        if let Some(store) = dead_letter_store.filter(|_| e.can_retry()) {
+                            match store.add(&action, &e.to_string()).await {
+                                Ok(id) => warn!(
+                                    "CommandExecutorActor - Action [{}] dead-lettered with id [{}]",
+                                    &action_id, id
+                                ),
+                                Err(err) => error!(
+                                    "CommandExecutorActor - Cannot dead-letter action [{}]: {:?}",
+                                    &action_id, err
+                                ),
+                            }
+                        }
                     }
                 }
             }
-            .instrument(msg.0.span),
+            .instrument(execution_span),
         );
         Ok(())
     }
@@ -89,12 +156,197 @@ impl<T: Command<Arc<Action>, Result<(), ExecutorError>> + 'static> Handler<Actio
 mod test {
     use super::*;
     use crate::command::retry::test::{AlwaysFailExecutor, AlwaysOkExecutor};
+    use crate::command::retry::{BackoffPolicy, RetryCommand, RetryPolicy, RetryStrategy};
     use crate::command::StatelessExecutorCommand;
     use crate::root_test::prometheus_exporter;
-    use tokio::sync::mpsc::unbounded_channel;
+    use std::io::Write;
+    use std::sync::Mutex;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
     use tokio::time::Duration;
     use tornado_common_api::{Action, TracedAction};
     use tornado_common_metrics::prometheus::{Encoder, TextEncoder};
+    use tornado_executor_common::StatelessExecutor;
+
+    struct SlowExecutor {
+        delay: Duration,
+        sender: UnboundedSender<Arc<Action>>,
+    }
+
+    impl std::fmt::Display for SlowExecutor {
+        fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+            fmt.write_str("SlowExecutor")
+        }
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl StatelessExecutor for SlowExecutor {
+        async fn execute(&self, action: Arc<Action>) -> Result<(), ExecutorError> {
+            tokio::time::sleep(self.delay).await;
+            self.sender.send(action).unwrap();
+            Ok(())
+        }
+    }
+
+    fn start_slow_executor(
+        delay: Duration,
+        shutdown: ShutdownCoordinator,
+    ) -> (
+        Addr<CommandExecutorActor<StatelessExecutorCommand<SlowExecutor>>>,
+        UnboundedReceiver<Arc<Action>>,
+    ) {
+        let (sender, receiver) = unbounded_channel();
+        let action_meter = Arc::new(ActionMeter::new("test_action_meter"));
+        let executor = CommandExecutorActor::start_new_with_shutdown(
+            100,
+            Rc::new(StatelessExecutorCommand::new(
+                action_meter.clone(),
+                SlowExecutor { delay, sender },
+            )),
+            action_meter,
+            shutdown,
+        );
+        (executor, receiver)
+    }
+
+    fn action_message(action_id: &str) -> ActionMessage {
+        ActionMessage(
+            TracedAction {
+                action: Arc::new(Action::new(action_id)),
+                span: tracing::Span::current(),
+            },
+            None,
+        )
+    }
+
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[actix_rt::test]
+    async fn should_include_the_trace_id_of_the_action_in_the_execution_logs() {
+        // Arrange
+        let _ = tracing_log::LogTracer::init();
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _subscriber_guard = tracing::subscriber::set_default(subscriber);
+
+        let (sender, mut receiver) = unbounded_channel();
+        let action_meter = Arc::new(ActionMeter::new("test_action_meter"));
+        let executor = CommandExecutorActor::start_new(
+            10,
+            Rc::new(StatelessExecutorCommand::new(
+                action_meter.clone(),
+                AlwaysFailExecutor { sender, can_retry: false },
+            )),
+            action_meter,
+        );
+
+        let mut action = Action::new("action_with_trace_id");
+        action.trace_id = Some("0af7651916cd43dd8448eb211c80319c".to_owned());
+
+        // Act
+        executor
+            .send(ActionMessage(
+                TracedAction { action: Arc::new(action), span: tracing::Span::current() },
+                None,
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        receiver.recv().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Assert
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let failure_log = logs
+            .lines()
+            .find(|line| line.contains("Failed to execute action [action_with_trace_id]"))
+            .unwrap();
+        assert!(failure_log.contains("trace_id"));
+        assert!(failure_log.contains("0af7651916cd43dd8448eb211c80319c"));
+    }
+
+    #[actix_rt::test]
+    async fn should_execute_all_the_in_flight_actions_before_the_drain_completes() {
+        // Arrange
+        let shutdown = ShutdownCoordinator::new();
+        let (executor, mut receiver) =
+            start_slow_executor(Duration::from_millis(200), shutdown.clone());
+
+        for index in 0..5 {
+            executor.send(action_message(&format!("action_{}", index))).await.unwrap().unwrap();
+        }
+
+        // Act
+        let abandoned_actions = shutdown.drain(Duration::from_secs(10)).await;
+
+        // Assert
+        assert_eq!(0, abandoned_actions);
+        assert_eq!(0, shutdown.in_flight_actions());
+        for _ in 0..5 {
+            assert!(receiver.try_recv().is_ok());
+        }
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[actix_rt::test]
+    async fn should_execute_the_actions_waiting_in_the_mailbox_before_the_drain_completes() {
+        // Arrange
+        let shutdown = ShutdownCoordinator::new();
+        let (executor, mut receiver) =
+            start_slow_executor(Duration::from_millis(100), shutdown.clone());
+
+        for index in 0..5 {
+            let mut message = action_message(&format!("action_{}", index));
+            message.1 = Some(shutdown.action_started());
+            executor.try_send(message).unwrap();
+        }
+        let in_flight_before_drain = shutdown.in_flight_actions();
+
+        // Act
+        let abandoned_actions = shutdown.drain(Duration::from_secs(10)).await;
+
+        // Assert
+        assert_eq!(5, in_flight_before_drain);
+        assert_eq!(0, abandoned_actions);
+        assert_eq!(0, shutdown.in_flight_actions());
+        for _ in 0..5 {
+            assert!(receiver.try_recv().is_ok());
+        }
+    }
+
+    #[actix_rt::test]
+    async fn should_abandon_the_in_flight_actions_when_the_drain_timeout_expires() {
+        // Arrange
+        let shutdown = ShutdownCoordinator::new();
+        let (executor, mut receiver) =
+            start_slow_executor(Duration::from_secs(60), shutdown.clone());
+
+        for index in 0..3 {
+            executor.send(action_message(&format!("action_{}", index))).await.unwrap().unwrap();
+        }
+
+        // Act
+        let abandoned_actions = shutdown.drain(Duration::from_millis(300)).await;
+
+        // Assert
+        assert_eq!(3, abandoned_actions);
+        assert!(receiver.try_recv().is_err());
+    }
 
     #[actix_rt::test]
     async fn should_increase_processed_and_attempts_counters_if_action_succeeds() {
@@ -105,7 +357,7 @@ mod test {
         let action_id = format!("{}", rand::random::<usize>());
         let action = Arc::new(Action::new(action_id.clone()));
         let span = tracing::Span::current();
-        let message = ActionMessage(TracedAction { action, span });
+        let message = ActionMessage(TracedAction { action, span }, None);
         let action_meter = Arc::new(ActionMeter::new("test_action_meter"));
 
         let stateless_executor_command = StatelessExecutorCommand::new(
@@ -148,7 +400,7 @@ mod test {
         let action_id = format!("{}", rand::random::<usize>());
         let action = Arc::new(Action::new(action_id.clone()));
         let span = tracing::Span::current();
-        let message = ActionMessage(TracedAction { action, span });
+        let message = ActionMessage(TracedAction { action, span }, None);
         let action_meter = Arc::new(ActionMeter::new("test_action_meter"));
         let stateless_executor_command = StatelessExecutorCommand::new(
             action_meter.clone(),
@@ -180,4 +432,73 @@ mod test {
             action_id
         )));
     }
+
+    #[actix_rt::test]
+    async fn should_add_the_action_to_the_dead_letter_store_when_the_retries_are_exhausted() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dead_letter_store =
+            Arc::new(DeadLetterStore::new(tempdir.path().join("dead_letters.jsonl")));
+        let (sender, mut receiver) = unbounded_channel();
+        let action_meter = Arc::new(ActionMeter::new("test_action_meter"));
+        let executor = CommandExecutorActor::start_new_with_dead_letter_store(
+            10,
+            Rc::new(RetryCommand::new(
+                RetryStrategy {
+                    retry_policy: RetryPolicy::MaxRetries { retries: 1 },
+                    backoff_policy: BackoffPolicy::None,
+                },
+                StatelessExecutorCommand::new(
+                    action_meter.clone(),
+                    AlwaysFailExecutor { sender, can_retry: true },
+                ),
+            )),
+            action_meter,
+            ShutdownCoordinator::new(),
+            Some(dead_letter_store.clone()),
+        );
+
+        // Act
+        executor.send(action_message("exhausted_action")).await.unwrap().unwrap();
+
+        // Assert
+        for _ in 0..2 {
+            assert_eq!("exhausted_action", receiver.recv().await.unwrap().id);
+        }
+        let mut dead_letters = dead_letter_store.list().await.unwrap();
+        while dead_letters.is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            dead_letters = dead_letter_store.list().await.unwrap();
+        }
+        assert_eq!(1, dead_letters.len());
+        assert_eq!("exhausted_action", dead_letters[0].action.id);
+    }
+
+    #[actix_rt::test]
+    async fn should_not_add_the_action_to_the_dead_letter_store_if_the_error_is_not_retriable() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let dead_letter_store =
+            Arc::new(DeadLetterStore::new(tempdir.path().join("dead_letters.jsonl")));
+        let (sender, mut receiver) = unbounded_channel();
+        let action_meter = Arc::new(ActionMeter::new("test_action_meter"));
+        let executor = CommandExecutorActor::start_new_with_dead_letter_store(
+            10,
+            Rc::new(StatelessExecutorCommand::new(
+                action_meter.clone(),
+                AlwaysFailExecutor { sender, can_retry: false },
+            )),
+            action_meter,
+            ShutdownCoordinator::new(),
+            Some(dead_letter_store.clone()),
+        );
+
+        // Act
+        executor.send(action_message("failed_action")).await.unwrap().unwrap();
+        receiver.recv().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Assert
+        assert!(dead_letter_store.list().await.unwrap().is_empty());
+    }
 }
@@ -2,14 +2,18 @@ use crate::actors::message::AsyncReadMessage;
 
 use actix::prelude::*;
 use log::*;
+use std::time::{Duration, Instant};
 use tokio::io::AsyncRead;
 use tokio_util::codec::{FramedRead, LinesCodec, LinesCodecError};
 use tornado_collector_common::Collector;
-use tornado_collector_json::JsonEventCollector;
+use tornado_collector_json::{JsonEventCollector, DEFAULT_MAX_NESTING_DEPTH};
 use tornado_common_api::Event;
 
 pub struct JsonEventReaderActor<F: Fn(Event) + 'static + Unpin> {
     json_collector: JsonEventCollector,
+    idle_timeout: Option<Duration>,
+    delimiter: Option<String>,
+    last_activity: Instant,
     callback: F,
 }
 
@@ -18,6 +22,62 @@ impl<F: Fn(Event) + 'static + Unpin> JsonEventReaderActor<F> {
         connect_msg: AsyncReadMessage<R>,
         message_mailbox_capacity: usize,
         callback: F,
+    ) {
+        Self::start_new_with_max_nesting_depth(
+            connect_msg,
+            message_mailbox_capacity,
+            DEFAULT_MAX_NESTING_DEPTH,
+            callback,
+        )
+    }
+
+    /// Starts a JsonEventReaderActor that discards the received events whose arrays and objects
+    /// are nested deeper than `max_nesting_depth`.
+    pub fn start_new_with_max_nesting_depth<R: AsyncRead + 'static>(
+        connect_msg: AsyncReadMessage<R>,
+        message_mailbox_capacity: usize,
+        max_nesting_depth: usize,
+        callback: F,
+    ) {
+        Self::start_new_with_idle_timeout(
+            connect_msg,
+            message_mailbox_capacity,
+            max_nesting_depth,
+            None,
+            callback,
+        )
+    }
+
+    /// Starts a JsonEventReaderActor that closes the connection when no data is received
+    /// for longer than the `idle_timeout`. If `idle_timeout` is None, the connection is never
+    /// closed for inactivity.
+    pub fn start_new_with_idle_timeout<R: AsyncRead + 'static>(
+        connect_msg: AsyncReadMessage<R>,
+        message_mailbox_capacity: usize,
+        max_nesting_depth: usize,
+        idle_timeout: Option<Duration>,
+        callback: F,
+    ) {
+        Self::start_new_with_delimiter(
+            connect_msg,
+            message_mailbox_capacity,
+            max_nesting_depth,
+            idle_timeout,
+            None,
+            callback,
+        )
+    }
+
+    /// Starts a JsonEventReaderActor that splits each received line on the `delimiter`
+    /// and deserializes every segment as a distinct event. Empty segments are skipped.
+    /// If `delimiter` is None, each line is deserialized as a single event.
+    pub fn start_new_with_delimiter<R: AsyncRead + 'static>(
+        connect_msg: AsyncReadMessage<R>,
+        message_mailbox_capacity: usize,
+        max_nesting_depth: usize,
+        idle_timeout: Option<Duration>,
+        delimiter: Option<String>,
+        callback: F,
     ) {
         JsonEventReaderActor::create(move |ctx| {
             ctx.set_mailbox_capacity(message_mailbox_capacity);
@@ -27,16 +87,47 @@ impl<F: Fn(Event) + 'static + Unpin> JsonEventReaderActor<F> {
 
             let framed = FramedRead::new(connect_msg.stream, codec);
             ctx.add_stream(framed);
-            JsonEventReaderActor { json_collector: JsonEventCollector::new(), callback }
+            JsonEventReaderActor {
+                json_collector: JsonEventCollector::new_with_max_nesting_depth(max_nesting_depth),
+                idle_timeout,
+                delimiter,
+                last_activity: Instant::now(),
+                callback,
+            }
         });
     }
+
+    fn process_json(&self, msg: &str) {
+        match self.json_collector.to_event(msg) {
+            Ok(event) => (self.callback)(event),
+            Err(e) => error!("JsonReaderActor - Cannot unmarshal event from json: {:?}", e),
+        };
+    }
+
+    /// Stops the actor, and closes the connection, if no data was received within the
+    /// idle timeout; otherwise, it schedules a new check for when the timeout would expire.
+    fn check_idle_timeout(&mut self, ctx: &mut Context<Self>) {
+        if let Some(idle_timeout) = self.idle_timeout {
+            let idle_time = self.last_activity.elapsed();
+            if idle_time >= idle_timeout {
+                info!(
+                    "JsonEventReaderActor - No data received for {} ms. Closing the connection",
+                    idle_time.as_millis()
+                );
+                ctx.stop();
+            } else {
+                ctx.run_later(idle_timeout - idle_time, |act, ctx| act.check_idle_timeout(ctx));
+            }
+        }
+    }
 }
 
 impl<F: Fn(Event) + 'static + Unpin> Actor for JsonEventReaderActor<F> {
     type Context = Context<Self>;
 
-    fn started(&mut self, _ctx: &mut Self::Context) {
+    fn started(&mut self, ctx: &mut Self::Context) {
         info!("EventJsonReaderActor started.");
+        self.check_idle_timeout(ctx);
     }
 }
 
@@ -45,13 +136,17 @@ impl<F: Fn(Event) + 'static + Unpin> StreamHandler<Result<String, LinesCodecErro
     for JsonEventReaderActor<F>
 {
     fn handle(&mut self, msg: Result<String, LinesCodecError>, _ctx: &mut Self::Context) {
+        self.last_activity = Instant::now();
         match msg {
             Ok(msg) => {
                 debug!("JsonReaderActor - received json message: [{}]", msg);
-                match self.json_collector.to_event(&msg) {
-                    Ok(event) => (self.callback)(event),
-                    Err(e) => error!("JsonReaderActor - Cannot unmarshal event from json: {:?}", e),
-                };
+                match &self.delimiter {
+                    Some(delimiter) => msg
+                        .split(delimiter.as_str())
+                        .filter(|segment| !segment.trim().is_empty())
+                        .for_each(|segment| self.process_json(segment)),
+                    None => self.process_json(&msg),
+                }
             }
             Err(err) => {
                 error!("JsonEventReaderActor stream error. Err: {:?}", err);
@@ -1,12 +1,21 @@
+use crate::shutdown::InFlightAction;
 use actix::prelude::Message;
 use thiserror::Error;
 use tokio::io::AsyncRead;
 use tornado_executor_common::ExecutorError;
 use tracing::Span;
 
+/// An action to be executed.
+/// The InFlightAction, if present, keeps the action registered in the ShutdownCoordinator
+///   from when it is enqueued until its execution completes.
 #[derive(Debug, Message, Clone)]
 #[rtype(result = "Result<(), ExecutorError>")]
-pub struct ActionMessage(pub tornado_common_api::TracedAction);
+pub struct ActionMessage(pub tornado_common_api::TracedAction, pub Option<InFlightAction>);
+
+/// Replies once all the messages received by the actor before it have been handled
+#[derive(Message, Debug)]
+#[rtype(result = "()")]
+pub struct FlushMessage;
 
 #[derive(Error, Debug)]
 pub enum TornadoCommonActorError {
@@ -16,6 +25,8 @@ pub enum TornadoCommonActorError {
     SerdeError { message: String },
     #[error("GenericError: [{message}]")]
     GenericError { message: String },
+    #[error("InvalidSignatureError: [{message}]")]
+    InvalidSignatureError { message: String },
 }
 
 #[derive(Message, Debug)]
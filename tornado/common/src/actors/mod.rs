@@ -22,11 +22,11 @@ pub mod uds_server;
 #[serde(untagged)]
 pub enum TornadoConnectionChannel {
     #[cfg(feature = "nats")]
-    Nats {
-        nats: NatsPublisherConfig,
-    },
+    Nats { nats: NatsPublisherConfig },
     Tcp {
         tcp_socket_ip: String,
         tcp_socket_port: u16,
+        /// If set, the sent events are signed with this shared secret
+        signature_secret: Option<String>,
     },
 }
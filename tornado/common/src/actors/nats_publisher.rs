@@ -1,4 +1,6 @@
 use crate::actors::message::{EventMessage, TornadoCommonActorError};
+use crate::health::ConnectionHealth;
+use crate::signature::EventSigner;
 use crate::TornadoError;
 use actix::prelude::*;
 use async_nats::{Connection, Options};
@@ -9,6 +11,7 @@ use std::io::Error;
 use std::ops::Deref;
 use std::rc::Rc;
 use tokio::time;
+use tornado_common_api::Event;
 use tornado_common_logger::opentelemetry_logger::TelemetryContextInjector;
 use tornado_common_metrics::opentelemetry::sdk::propagation::TraceContextPropagator;
 use tracing_futures::Instrument;
@@ -16,11 +19,18 @@ use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 const WAIT_BETWEEN_RESTARTS_SEC: u64 = 10;
 
+/// The first byte of the NATS payloads serialized with MessagePack.
+/// 0xc1 is never used by the MessagePack format and cannot start a JSON document,
+/// so the payloads without this marker are always read as JSON.
+pub const MESSAGE_PACK_MARKER: u8 = 0xc1;
+
 pub struct NatsPublisherActor {
     config: NatsPublisherConfig,
     nats_connection: Rc<Option<Connection>>,
     restarted: bool,
     trace_context_propagator: TraceContextPropagator,
+    health: Option<ConnectionHealth>,
+    signer: Option<EventSigner>,
 }
 
 impl actix::io::WriteHandler<Error> for NatsPublisherActor {}
@@ -29,6 +39,49 @@ impl actix::io::WriteHandler<Error> for NatsPublisherActor {}
 pub struct NatsPublisherConfig {
     pub client: NatsClientConfig,
     pub subject: String,
+    /// If set, the published events are signed with this shared secret
+    pub signature_secret: Option<String>,
+    #[serde(default)]
+    pub serialization_format: NatsSerializationFormat,
+}
+
+/// The format of the events published to NATS
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub enum NatsSerializationFormat {
+    /// The events are published as plain JSON documents
+    #[default]
+    Json,
+    /// The events are published in MessagePack format, prefixed by the MESSAGE_PACK_MARKER
+    MessagePack,
+}
+
+impl NatsSerializationFormat {
+    pub fn serialize_event(&self, event: &Event) -> Result<Vec<u8>, TornadoCommonActorError> {
+        match self {
+            NatsSerializationFormat::Json => serde_json::to_vec(event).map_err(|err| {
+                TornadoCommonActorError::SerdeError { message: format! {"{}", err} }
+            }),
+            NatsSerializationFormat::MessagePack => {
+                let mut data = vec![MESSAGE_PACK_MARKER];
+                rmp_serde::encode::write_named(&mut data, event).map_err(|err| {
+                    TornadoCommonActorError::SerdeError { message: format! {"{}", err} }
+                })?;
+                Ok(data)
+            }
+        }
+    }
+}
+
+/// Deserializes an Event received from NATS.
+/// The format is detected from the payload itself: the payloads starting with the
+/// MESSAGE_PACK_MARKER are read as MessagePack, all the others as JSON.
+pub fn deserialize_event(data: &[u8]) -> Result<Event, TornadoCommonActorError> {
+    match data.split_first() {
+        Some((&MESSAGE_PACK_MARKER, message_pack_data)) => rmp_serde::from_slice(message_pack_data)
+            .map_err(|err| TornadoCommonActorError::SerdeError { message: format! {"{}", err} }),
+        _ => serde_json::from_slice(data)
+            .map_err(|err| TornadoCommonActorError::SerdeError { message: format! {"{}", err} }),
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -40,6 +93,15 @@ pub enum NatsClientAuth {
         private_key_path: String,
         path_to_root_certificate: Option<String>,
     },
+    /// Authentication with a user JWT and NKey seed, as distributed in NATS `.creds` files.
+    /// Exactly one between `credentials_path` and `credentials` must be provided.
+    Credentials {
+        /// The path to a `.creds` file
+        credentials_path: Option<String>,
+        /// The inline content of a `.creds` file
+        credentials: Option<String>,
+        path_to_root_certificate: Option<String>,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -50,14 +112,53 @@ pub struct NatsClientConfig {
 
 impl NatsClientConfig {
     pub async fn new_client(&self) -> std::io::Result<Connection> {
+        self.new_client_with_health(None).await
+    }
+
+    /// Creates a new NATS client that keeps the given ConnectionHealth updated
+    ///   with the state of its connection.
+    pub async fn new_client_with_health(
+        &self,
+        health: Option<&ConnectionHealth>,
+    ) -> std::io::Result<Connection> {
+        let addresses = self.addresses.join(",");
+        let options = self.new_options(health.cloned())?;
+        let connection = options.connect(&addresses).await?;
+        if let Some(health) = health {
+            health.set_connected();
+        }
+        Ok(connection)
+    }
+
+    fn new_options(&self, health: Option<ConnectionHealth>) -> std::io::Result<Options> {
         let addresses = self.addresses.join(",");
 
         let auth = self.get_auth();
 
-        let mut options = Options::new()
-            .disconnect_callback(|| error!("NatsClientConfig - connection to NATS server was lost"))
-            .reconnect_callback(|| {
-                info!("NatsClientConfig - connection to NATS server was restored")
+        let options = match auth {
+            NatsClientAuth::Credentials { credentials_path, credentials, .. } => {
+                Options::with_static_credentials(&read_credentials(
+                    credentials_path.as_deref(),
+                    credentials.as_deref(),
+                )?)?
+            }
+            _ => Options::new(),
+        };
+
+        let disconnect_health = health.clone();
+        let reconnect_health = health;
+        let mut options = options
+            .disconnect_callback(move || {
+                error!("NatsClientConfig - connection to NATS server was lost");
+                if let Some(health) = &disconnect_health {
+                    health.set_disconnected();
+                }
+            })
+            .reconnect_callback(move || {
+                info!("NatsClientConfig - connection to NATS server was restored");
+                if let Some(health) = &reconnect_health {
+                    health.set_connected();
+                }
             })
             .max_reconnects(None);
         match auth {
@@ -75,11 +176,22 @@ impl NatsClientConfig {
                     options = options.add_root_certificate(path_to_root_certificate)
                 }
             }
+            NatsClientAuth::Credentials { path_to_root_certificate, .. } => {
+                info!(
+                    "NatsClientConfig - Open Nats connection (with credentials) to [{}]",
+                    addresses
+                );
+                if let Some(path_to_root_certificate) = path_to_root_certificate {
+                    debug!("NatsClientConfig - Trusting CA: {}", path_to_root_certificate);
+                    options =
+                        options.tls_required(true).add_root_certificate(path_to_root_certificate)
+                }
+            }
             NatsClientAuth::None => {
                 info!("NatsClientConfig - Open Nats connection (without TLS) to [{}]", addresses);
             }
         };
-        options.connect(&addresses).await
+        Ok(options)
     }
 
     fn get_auth(&self) -> &NatsClientAuth {
@@ -90,12 +202,49 @@ impl NatsClientConfig {
     }
 }
 
+fn read_credentials(
+    credentials_path: Option<&str>,
+    credentials: Option<&str>,
+) -> std::io::Result<String> {
+    match (credentials_path, credentials) {
+        (Some(credentials_path), None) => {
+            debug!("NatsClientConfig - Read credentials from: {}", credentials_path);
+            std::fs::read_to_string(credentials_path).map_err(|err| {
+                std::io::Error::new(
+                    err.kind(),
+                    format!("Cannot read NATS credentials file [{}]: {}", credentials_path, err),
+                )
+            })
+        }
+        (None, Some(credentials)) => Ok(credentials.to_owned()),
+        (Some(_), Some(_)) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "NATS credentials_path and credentials cannot be set at the same time",
+        )),
+        (None, None) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "One between NATS credentials_path and credentials must be set",
+        )),
+    }
+}
+
 impl NatsPublisherActor {
     pub async fn start_new(
         config: NatsPublisherConfig,
         message_mailbox_capacity: usize,
+    ) -> Result<Addr<NatsPublisherActor>, TornadoError> {
+        NatsPublisherActor::start_new_with_health(config, message_mailbox_capacity, None).await
+    }
+
+    /// Starts a new NatsPublisherActor that keeps the given ConnectionHealth updated
+    ///   with the state of its NATS connection.
+    pub async fn start_new_with_health(
+        config: NatsPublisherConfig,
+        message_mailbox_capacity: usize,
+        health: Option<ConnectionHealth>,
     ) -> Result<Addr<NatsPublisherActor>, TornadoError> {
         let trace_context_propagator = TraceContextPropagator::new();
+        let signer = config.signature_secret.as_deref().map(EventSigner::new);
         Ok(actix::Supervisor::start(move |ctx: &mut Context<NatsPublisherActor>| {
             ctx.set_mailbox_capacity(message_mailbox_capacity);
             NatsPublisherActor {
@@ -103,6 +252,8 @@ impl NatsPublisherActor {
                 nats_connection: Rc::new(None),
                 restarted: false,
                 trace_context_propagator,
+                health,
+                signer,
             }
         }))
     }
@@ -120,6 +271,7 @@ impl Actor for NatsPublisherActor {
         let client_config = self.config.client.clone();
         let nats_connection = self.nats_connection.clone();
         let restarted = self.restarted;
+        let health = self.health.clone();
         ctx.wait(
             async move {
                 if restarted {
@@ -140,7 +292,7 @@ impl Actor for NatsPublisherActor {
                         }
                     };
                 }
-                client_config.new_client().await
+                client_config.new_client_with_health(health.as_ref()).await
             }
             .into_actor(self)
                 .map(move |client, act, ctx| match client {
@@ -153,6 +305,9 @@ impl Actor for NatsPublisherActor {
                     }
                     Err(err) => {
                         act.nats_connection = Rc::new(None);
+                        if let Some(health) = &act.health {
+                            health.set_disconnected();
+                        }
                         warn!("NatsPublisherActor connection failed. Err: {}", err);
                         ctx.stop();
                     }
@@ -187,15 +342,16 @@ impl Handler<EventMessage> for NatsPublisherActor {
             &self.trace_context_propagator,
         );
         msg.0.event.set_trace_context(trace_context);
+        if let Some(signer) = &self.signer {
+            signer.sign(&mut msg.0.event)?;
+        }
 
         trace!("NatsPublisherActor - Handling Event to be sent to Nats - {:?}", &msg.0.event);
 
         let address = ctx.address();
 
         if let Some(connection) = self.nats_connection.deref() {
-            let event = serde_json::to_vec(&msg.0.event).map_err(|err| {
-                TornadoCommonActorError::SerdeError { message: format! {"{}", err} }
-            })?;
+            let event = self.config.serialization_format.serialize_event(&msg.0.event)?;
 
             let client = connection.clone();
             let config = self.config.clone();
@@ -226,9 +382,12 @@ impl Handler<EventMessage> for NatsPublisherActor {
     }
 }
 
-pub async fn wait_for_nats_connection(client_config: &NatsClientConfig) -> Connection {
+pub async fn wait_for_nats_connection(
+    client_config: &NatsClientConfig,
+    health: Option<&ConnectionHealth>,
+) -> Connection {
     loop {
-        match client_config.new_client().await {
+        match client_config.new_client_with_health(health).await {
             Err(connection_error) => {
                 error!("Error during connection to NATS. Err: {:?}", connection_error);
                 time::sleep(time::Duration::from_secs(5)).await;
@@ -240,3 +399,139 @@ pub async fn wait_for_nats_connection(client_config: &NatsClientConfig) -> Conne
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    const CREDENTIALS_PATH: &str = "./test_resources/nats-user.creds";
+
+    fn config_with_credentials(
+        credentials_path: Option<String>,
+        credentials: Option<String>,
+    ) -> NatsClientConfig {
+        NatsClientConfig {
+            addresses: vec!["127.0.0.1:4222".to_owned()],
+            auth: Some(NatsClientAuth::Credentials {
+                credentials_path,
+                credentials,
+                path_to_root_certificate: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn should_build_client_options_from_credentials_file() {
+        // Arrange
+        let config = config_with_credentials(Some(CREDENTIALS_PATH.to_owned()), None);
+
+        // Act
+        let options = config.new_options(None);
+
+        // Assert
+        assert!(options.is_ok());
+    }
+
+    #[test]
+    fn should_build_client_options_from_inline_credentials() {
+        // Arrange
+        let credentials = std::fs::read_to_string(CREDENTIALS_PATH).unwrap();
+        let config = config_with_credentials(None, Some(credentials));
+
+        // Act
+        let options = config.new_options(None);
+
+        // Assert
+        assert!(options.is_ok());
+    }
+
+    #[test]
+    fn should_fail_if_both_credentials_file_and_inline_credentials_are_set() {
+        // Arrange
+        let credentials = std::fs::read_to_string(CREDENTIALS_PATH).unwrap();
+        let config = config_with_credentials(Some(CREDENTIALS_PATH.to_owned()), Some(credentials));
+
+        // Act
+        let options = config.new_options(None);
+
+        // Assert
+        assert!(options.is_err());
+    }
+
+    #[test]
+    fn should_fail_if_credentials_file_does_not_exist() {
+        // Arrange
+        let config =
+            config_with_credentials(Some("./test_resources/not_existing.creds".to_owned()), None);
+
+        // Act
+        let options = config.new_options(None);
+
+        // Assert
+        assert!(options.is_err());
+    }
+
+    fn event_with_payload() -> Event {
+        let mut payload = tornado_common_api::Map::new();
+        payload.insert("host".to_owned(), serde_json::json!("localhost"));
+        payload.insert("value".to_owned(), serde_json::json!(12.5));
+        payload.insert("tags".to_owned(), serde_json::json!(["a", "b"]));
+        payload.insert("nested".to_owned(), serde_json::json!({"count": 3, "ok": true}));
+        let mut event = Event::new_with_payload("a_type", payload);
+        event.metadata.insert("tenant_id".to_owned(), serde_json::json!("alpha"));
+        event
+    }
+
+    #[test]
+    fn json_should_be_the_default_serialization_format() {
+        // Act
+        let config: NatsPublisherConfig = serde_json::from_str(
+            r#"{"client": {"addresses": ["127.0.0.1:4222"]}, "subject": "events"}"#,
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(NatsSerializationFormat::Json, config.serialization_format);
+    }
+
+    #[test]
+    fn should_serialize_and_deserialize_an_event_with_message_pack() {
+        // Arrange
+        let event = event_with_payload();
+
+        // Act
+        let data = NatsSerializationFormat::MessagePack.serialize_event(&event).unwrap();
+        let deserialized = deserialize_event(&data).unwrap();
+
+        // Assert
+        assert_eq!(MESSAGE_PACK_MARKER, data[0]);
+        assert_eq!(event, deserialized);
+    }
+
+    #[test]
+    fn json_serialized_events_should_be_readable_by_json_consumers() {
+        // Arrange
+        let event = event_with_payload();
+
+        // Act
+        let data = NatsSerializationFormat::Json.serialize_event(&event).unwrap();
+
+        // Assert
+        assert_eq!(serde_json::to_vec(&event).unwrap(), data);
+        assert_eq!(event, serde_json::from_slice::<Event>(&data).unwrap());
+        assert_eq!(event, deserialize_event(&data).unwrap());
+    }
+
+    #[test]
+    fn should_fail_to_deserialize_an_invalid_message_pack_payload() {
+        // Arrange
+        let data = vec![MESSAGE_PACK_MARKER, 0x01, 0x02];
+
+        // Act
+        let result = deserialize_event(&data);
+
+        // Assert
+        assert!(result.is_err());
+    }
+}
@@ -1,11 +1,21 @@
 use crate::actors::message::TornadoCommonActorError;
 use crate::actors::nats_publisher::{wait_for_nats_connection, NatsClientConfig};
+use crate::health::ConnectionHealth;
 use crate::TornadoError;
 use actix::prelude::*;
 use async_nats::{Connection, Message};
-use futures_util::stream;
+use futures_util::stream::{self, Stream, StreamExt};
 use log::*;
 use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use tokio::sync::Notify;
+use tornado_common_api::retry::{BackoffPolicy, RetryPolicy, RetryStrategy};
+use tornado_common_metrics::opentelemetry::metrics::Counter;
 
 #[derive(Message, Debug)]
 #[rtype(result = "Result<(), TornadoCommonActorError>")]
@@ -17,6 +27,33 @@ pub struct NatsMessage {
 pub struct NatsSubscriberConfig {
     pub client: NatsClientConfig,
     pub subject: String,
+    #[serde(default)]
+    pub backpressure: NatsBackpressure,
+    /// The retry strategy applied when the subscription to the subject fails at startup,
+    /// for example because NATS is not yet available
+    #[serde(default = "default_subscribe_retry_strategy")]
+    pub subscribe_retry_strategy: RetryStrategy,
+}
+
+/// The default retry strategy of the subscription at startup:
+/// up to 5 retries, waiting 500 ms after the first failure and doubling the wait each time.
+pub fn default_subscribe_retry_strategy() -> RetryStrategy {
+    RetryStrategy {
+        retry_policy: RetryPolicy::MaxRetries { retries: 5 },
+        backoff_policy: BackoffPolicy::Exponential { ms: 500, multiplier: 2 },
+    }
+}
+
+/// What happens to the received messages when the subscriber cannot keep up with them
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum NatsBackpressure {
+    /// The oldest buffered message is dropped to make room for the new one
+    DropOldest,
+    /// The new message is dropped
+    DropNewest,
+    /// The subscription is not read until the buffered messages are processed
+    #[default]
+    Block,
 }
 
 pub async fn subscribe_to_nats<
@@ -26,9 +63,26 @@ pub async fn subscribe_to_nats<
     message_mailbox_capacity: usize,
     callback: F,
 ) -> Result<(), TornadoError> {
-    let client = wait_for_nats_connection(&config.client).await;
+    subscribe_to_nats_with_health(config, message_mailbox_capacity, None, callback).await
+}
 
-    let subscription = client.subscribe(&config.subject).await.map_err(|err| {
+/// Subscribes to NATS like `subscribe_to_nats` and keeps the given ConnectionHealth updated
+///   with the state of the NATS connection.
+pub async fn subscribe_to_nats_with_health<
+    F: 'static + FnMut(NatsMessage) -> Result<(), TornadoCommonActorError> + Sized + Unpin,
+>(
+    config: NatsSubscriberConfig,
+    message_mailbox_capacity: usize,
+    health: Option<ConnectionHealth>,
+    callback: F,
+) -> Result<(), TornadoError> {
+    let client = wait_for_nats_connection(&config.client, health.as_ref()).await;
+
+    let subscription = with_retry(&config.subscribe_retry_strategy, || {
+        client.subscribe(&config.subject)
+    })
+    .await
+    .map_err(|err| {
         TornadoError::ConfigurationError { message: format! {"NatsSubscriberActor - Cannot subscribe to subject [{}]. Err: {:?}", config.subject, err} }
     })?;
 
@@ -38,6 +92,20 @@ pub async fn subscribe_to_nats<
         sub.next().await.map(|msg| (NatsMessage { msg }, sub))
     });
 
+    let dropped_messages_counter =
+        tornado_common_metrics::opentelemetry::global::meter("tornado_common")
+            .u64_counter("nats_subscriber_dropped_messages_counter")
+            .with_description(
+                "Counter of the NATS messages dropped because the subscriber was saturated",
+            )
+            .init();
+    let message_stream = with_backpressure(
+        message_stream,
+        message_mailbox_capacity,
+        config.backpressure,
+        dropped_messages_counter,
+    );
+
     NatsSubscriberActor::create(|ctx| {
         ctx.set_mailbox_capacity(message_mailbox_capacity);
         ctx.add_message_stream(message_stream);
@@ -67,6 +135,108 @@ pub async fn subscribe_to_nats<
     Ok(())
 }
 
+/// Executes the operation until it succeeds or the retry strategy does not allow further attempts.
+/// Returns the result of the last attempt.
+async fn with_retry<T, E: Debug, F: FnMut() -> Fut, Fut: Future<Output = Result<T, E>>>(
+    retry_strategy: &RetryStrategy,
+    mut operation: F,
+) -> Result<T, E> {
+    let mut failed_attempts = 0;
+    loop {
+        match operation().await {
+            Ok(result) => return Ok(result),
+            Err(err) => {
+                failed_attempts += 1;
+                let (should_retry, backoff) = retry_strategy.should_retry(failed_attempts);
+                if !should_retry {
+                    return Err(err);
+                }
+                warn!(
+                    "NatsSubscriberActor - Attempt {} to subscribe failed, retrying in {:?}. Err: {:?}",
+                    failed_attempts,
+                    backoff.unwrap_or_default(),
+                    err
+                );
+                if let Some(backoff) = backoff {
+                    tokio::time::sleep(backoff).await;
+                }
+            }
+        }
+    }
+}
+
+struct BoundedQueue<T> {
+    items: RefCell<VecDeque<T>>,
+    closed: Cell<bool>,
+    notify: Notify,
+}
+
+/// Applies the backpressure strategy to the given stream.
+/// With `Block` the stream is returned as it is, so the source is read only when the consumer is ready.
+/// With `DropOldest` and `DropNewest` the source is eagerly read in a separate task and
+/// its items are kept in a queue of the given capacity; every item dropped because the queue
+/// is full increments the dropped_messages_counter.
+pub fn with_backpressure<T: 'static, S: 'static + Stream<Item = T>>(
+    source: S,
+    capacity: usize,
+    backpressure: NatsBackpressure,
+    dropped_messages_counter: Counter<u64>,
+) -> Pin<Box<dyn Stream<Item = T>>> {
+    if backpressure == NatsBackpressure::Block {
+        return Box::pin(source);
+    }
+
+    let capacity = capacity.max(1);
+    let queue = Rc::new(BoundedQueue {
+        items: RefCell::new(VecDeque::with_capacity(capacity)),
+        closed: Cell::new(false),
+        notify: Notify::new(),
+    });
+
+    let producer_queue = queue.clone();
+    actix::spawn(async move {
+        let mut source = Box::pin(source);
+        while let Some(item) = source.next().await {
+            let dropped = {
+                let mut items = producer_queue.items.borrow_mut();
+                if items.len() < capacity {
+                    items.push_back(item);
+                    false
+                } else {
+                    if backpressure == NatsBackpressure::DropOldest {
+                        items.pop_front();
+                        items.push_back(item);
+                    }
+                    true
+                }
+            };
+            if dropped {
+                trace!(
+                    "NatsSubscriberActor - Subscriber saturated. Message dropped with strategy {:?}",
+                    backpressure
+                );
+                dropped_messages_counter.add(1, &[]);
+            }
+            producer_queue.notify.notify_one();
+        }
+        producer_queue.closed.set(true);
+        producer_queue.notify.notify_one();
+    });
+
+    Box::pin(stream::unfold(queue, |queue| async move {
+        loop {
+            let item = queue.items.borrow_mut().pop_front();
+            if let Some(item) = item {
+                return Some((item, queue));
+            }
+            if queue.closed.get() {
+                return None;
+            }
+            queue.notify.notified().await;
+        }
+    }))
+}
+
 struct NatsSubscriberActor<F>
 where
     F: 'static + FnMut(NatsMessage) -> Result<(), TornadoCommonActorError> + Sized + Unpin,
@@ -100,3 +270,104 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    async fn retained_items(backpressure: NatsBackpressure) -> Vec<u32> {
+        let counter = tornado_common_metrics::opentelemetry::global::meter("test")
+            .u64_counter("dropped")
+            .init();
+        // The source stream is always ready, so it is fully read before the first item is consumed
+        with_backpressure(stream::iter(0..10), 3, backpressure, counter).collect().await
+    }
+
+    #[actix_rt::test]
+    async fn drop_oldest_should_retain_the_newest_messages() {
+        // Act
+        let items = retained_items(NatsBackpressure::DropOldest).await;
+
+        // Assert
+        assert_eq!(vec![7, 8, 9], items);
+    }
+
+    #[actix_rt::test]
+    async fn drop_newest_should_retain_the_oldest_messages() {
+        // Act
+        let items = retained_items(NatsBackpressure::DropNewest).await;
+
+        // Assert
+        assert_eq!(vec![0, 1, 2], items);
+    }
+
+    #[actix_rt::test]
+    async fn block_should_retain_all_the_messages() {
+        // Act
+        let items = retained_items(NatsBackpressure::Block).await;
+
+        // Assert
+        assert_eq!((0..10).collect::<Vec<_>>(), items);
+    }
+
+    fn retry_strategy(retries: u32) -> RetryStrategy {
+        RetryStrategy {
+            retry_policy: RetryPolicy::MaxRetries { retries },
+            backoff_policy: BackoffPolicy::Fixed { ms: 1 },
+        }
+    }
+
+    #[actix_rt::test]
+    async fn should_subscribe_if_the_first_attempt_fails() {
+        // Arrange
+        let attempts = Cell::new(0);
+        let subscribe = || {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move {
+                if attempt == 1 {
+                    Err("NATS not available")
+                } else {
+                    Ok("subscription")
+                }
+            }
+        };
+
+        // Act
+        let result = with_retry(&retry_strategy(3), subscribe).await;
+
+        // Assert
+        assert_eq!(Ok("subscription"), result);
+        assert_eq!(2, attempts.get());
+    }
+
+    #[actix_rt::test]
+    async fn should_fail_to_subscribe_after_exhausting_the_retries() {
+        // Arrange
+        let attempts = Cell::new(0);
+        let subscribe = || {
+            attempts.set(attempts.get() + 1);
+            async { Err::<(), _>("NATS not available") }
+        };
+
+        // Act
+        let result = with_retry(&retry_strategy(3), subscribe).await;
+
+        // Assert
+        assert_eq!(Err("NATS not available"), result);
+        assert_eq!(4, attempts.get());
+    }
+
+    #[test]
+    fn block_should_be_the_default_backpressure() {
+        // Act
+        let config: NatsSubscriberConfig = serde_json::from_str(
+            r#"{"client": {"addresses": ["127.0.0.1:4222"]}, "subject": "events"}"#,
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(NatsBackpressure::Block, config.backpressure);
+        assert_eq!(default_subscribe_retry_strategy(), config.subscribe_retry_strategy);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::actors::message::{EventMessage, TornadoCommonActorError};
+use crate::signature::EventSigner;
 use actix::prelude::*;
 use log::*;
 use std::io::Error;
@@ -14,6 +15,7 @@ pub struct TcpClientActor {
     restarted: bool,
     address: String,
     tx: Option<actix::io::FramedWrite<String, WriteHalf<TcpStream>, LinesCodec>>,
+    signer: Option<EventSigner>,
 }
 
 impl actix::io::WriteHandler<Error> for TcpClientActor {}
@@ -22,10 +24,19 @@ impl TcpClientActor {
     pub fn start_new<T: 'static + Into<String>>(
         address: T,
         tcp_socket_mailbox_capacity: usize,
+    ) -> Addr<TcpClientActor> {
+        TcpClientActor::start_new_with_signer(address, tcp_socket_mailbox_capacity, None)
+    }
+
+    /// Starts a new TcpClientActor that signs the sent events with the given EventSigner
+    pub fn start_new_with_signer<T: 'static + Into<String>>(
+        address: T,
+        tcp_socket_mailbox_capacity: usize,
+        signer: Option<EventSigner>,
     ) -> Addr<TcpClientActor> {
         actix::Supervisor::start(move |ctx: &mut Context<TcpClientActor>| {
             ctx.set_mailbox_capacity(tcp_socket_mailbox_capacity);
-            TcpClientActor { restarted: false, address: address.into(), tx: None }
+            TcpClientActor { restarted: false, address: address.into(), tx: None, signer }
         })
     }
 }
@@ -76,7 +87,7 @@ impl actix::io::WriteHandler<LinesCodecError> for TcpClientActor {}
 impl Handler<EventMessage> for TcpClientActor {
     type Result = Result<(), TornadoCommonActorError>;
 
-    fn handle(&mut self, msg: EventMessage, ctx: &mut Context<Self>) -> Self::Result {
+    fn handle(&mut self, mut msg: EventMessage, ctx: &mut Context<Self>) -> Self::Result {
         let parent_span = msg.0.span.clone().entered();
         let trace_id = msg.0.event.get_trace_id_for_logging(&parent_span.context());
         let _span = tracing::error_span!("TcpClientActor", trace_id = trace_id.as_ref()).entered();
@@ -85,6 +96,9 @@ impl Handler<EventMessage> for TcpClientActor {
 
         match &mut self.tx {
             Some(stream) => {
+                if let Some(signer) = &self.signer {
+                    signer.sign(&mut msg.0.event)?;
+                }
                 let event = serde_json::to_string(&msg.0.event).map_err(|err| {
                     TornadoCommonActorError::SerdeError { message: format! {"{}", err} }
                 })?;
@@ -2,17 +2,61 @@ use crate::actors::message::AsyncReadMessage;
 use crate::TornadoError;
 use actix::prelude::*;
 use futures_util::StreamExt;
+use ipnet::IpNet;
 use log::*;
 use std::net;
+use std::net::IpAddr;
+use std::pin::Pin;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
 use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
+/// A connection accepted by the TCP server.
+/// When the server has a max number of concurrent connections, the connection holds one of
+/// the available slots until it is dropped.
+pub struct TcpConnection {
+    stream: TcpStream,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl AsyncRead for TcpConnection {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_read(cx, buf)
+    }
+}
+
+/// The options of a TCP server
+#[derive(Default)]
+pub struct TcpServerOptions {
+    /// The max number of concurrent connections.
+    /// The connections received when the limit is reached are closed immediately and
+    /// `on_connection_refused` is called; the already open connections are not affected.
+    /// If None, the number of concurrent connections is unlimited.
+    pub max_connections: Option<usize>,
+    pub on_connection_refused: Option<Box<dyn FnMut()>>,
+    /// The networks of the peers whose connections are accepted.
+    /// The connections from the other peers are closed immediately and
+    /// `on_connection_not_allowed` is called with the address of the peer.
+    /// If None, the connections from all the peers are accepted.
+    pub allowed_networks: Option<Vec<IpNet>>,
+    pub on_connection_not_allowed: Option<Box<dyn FnMut(net::SocketAddr)>>,
+}
+
+/// Starts a TCP server that calls `callback` for each accepted connection.
 pub async fn listen_to_tcp<
     P: 'static + Into<String>,
-    F: 'static + FnMut(AsyncReadMessage<TcpStream>) + Sized + Unpin,
+    F: 'static + FnMut(AsyncReadMessage<TcpConnection>) + Sized + Unpin,
 >(
     address: P,
     message_mailbox_capacity: usize,
+    options: TcpServerOptions,
     callback: F,
 ) -> Result<(), TornadoError> {
     let address = address.into();
@@ -30,7 +74,14 @@ pub async fn listen_to_tcp<
         ctx.add_message_stream(Box::leak(listener).map(|stream| AsyncReadMessage {
             stream: stream.expect("Cannot read from TCP server stream"),
         }));
-        TcpServerActor { address, callback }
+        TcpServerActor {
+            address,
+            connection_slots: options
+                .max_connections
+                .map(|max_connections| Arc::new(Semaphore::new(max_connections))),
+            options,
+            callback,
+        }
     });
 
     Ok(())
@@ -38,27 +89,127 @@ pub async fn listen_to_tcp<
 
 struct TcpServerActor<F>
 where
-    F: 'static + FnMut(AsyncReadMessage<TcpStream>) + Sized + Unpin,
+    F: 'static + FnMut(AsyncReadMessage<TcpConnection>) + Sized + Unpin,
 {
     address: String,
+    options: TcpServerOptions,
+    connection_slots: Option<Arc<Semaphore>>,
     callback: F,
 }
 
 impl<F> Actor for TcpServerActor<F>
 where
-    F: 'static + FnMut(AsyncReadMessage<TcpStream>) + Sized + Unpin,
+    F: 'static + FnMut(AsyncReadMessage<TcpConnection>) + Sized + Unpin,
 {
     type Context = Context<Self>;
 }
 
 impl<F> Handler<AsyncReadMessage<TcpStream>> for TcpServerActor<F>
 where
-    F: 'static + FnMut(AsyncReadMessage<TcpStream>) + Sized + Unpin,
+    F: 'static + FnMut(AsyncReadMessage<TcpConnection>) + Sized + Unpin,
 {
     type Result = ();
 
     fn handle(&mut self, msg: AsyncReadMessage<TcpStream>, _: &mut Context<Self>) {
+        if let Some(allowed_networks) = &self.options.allowed_networks {
+            match msg.stream.peer_addr() {
+                Ok(peer_address) if is_allowed(allowed_networks, peer_address.ip()) => {}
+                Ok(peer_address) => {
+                    warn!(
+                        "TcpServerActor - The peer [{}] is not in the allowed networks of [{}]. The new connection is refused",
+                        peer_address, &self.address
+                    );
+                    if let Some(on_connection_not_allowed) =
+                        &mut self.options.on_connection_not_allowed
+                    {
+                        on_connection_not_allowed(peer_address);
+                    }
+                    return;
+                }
+                Err(err) => {
+                    warn!(
+                        "TcpServerActor - Cannot read the address of the peer connected to [{}]. The new connection is refused. Err: {:?}",
+                        &self.address, err
+                    );
+                    return;
+                }
+            }
+        }
+
+        let permit = match &self.connection_slots {
+            Some(connection_slots) => match connection_slots.clone().try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    warn!(
+                        "TcpServerActor - The max number of concurrent connections [{}] to [{}] has been reached. The new connection is refused",
+                        self.options.max_connections.unwrap_or_default(),
+                        &self.address
+                    );
+                    if let Some(on_connection_refused) = &mut self.options.on_connection_refused {
+                        on_connection_refused();
+                    }
+                    return;
+                }
+            },
+            None => None,
+        };
+
         debug!("TcpServerActor - new client connected to [{}]", &self.address);
-        (self.callback)(msg);
+        (self.callback)(AsyncReadMessage {
+            stream: TcpConnection { stream: msg.stream, _permit: permit },
+        });
+    }
+}
+
+/// Returns whether the address belongs to one of the networks.
+/// IPv4 addresses mapped to IPv6, as received by a server listening on an IPv6 address,
+/// are checked as IPv4 addresses.
+fn is_allowed(allowed_networks: &[IpNet], address: IpAddr) -> bool {
+    let address = match address {
+        IpAddr::V6(v6_address) => {
+            v6_address.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(v6_address))
+        }
+        IpAddr::V4(_) => address,
+    };
+    allowed_networks.iter().any(|network| network.contains(&address))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn networks(networks: &[&str]) -> Vec<IpNet> {
+        networks.iter().map(|network| network.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn should_allow_only_the_addresses_in_the_networks() {
+        // Arrange
+        let allowed_networks = networks(&["10.0.0.0/8", "192.168.1.10/32", "fd00::/8"]);
+
+        // Act & Assert
+        assert!(is_allowed(&allowed_networks, "10.1.2.3".parse().unwrap()));
+        assert!(is_allowed(&allowed_networks, "192.168.1.10".parse().unwrap()));
+        assert!(is_allowed(&allowed_networks, "fd12::1".parse().unwrap()));
+        assert!(!is_allowed(&allowed_networks, "192.168.1.11".parse().unwrap()));
+        assert!(!is_allowed(&allowed_networks, "11.0.0.1".parse().unwrap()));
+        assert!(!is_allowed(&allowed_networks, "fe80::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn should_check_the_ipv4_mapped_addresses_as_ipv4() {
+        // Arrange
+        let allowed_networks = networks(&["10.0.0.0/8"]);
+
+        // Act & Assert
+        assert!(is_allowed(&allowed_networks, "::ffff:10.1.2.3".parse().unwrap()));
+        assert!(!is_allowed(&allowed_networks, "::ffff:11.1.2.3".parse().unwrap()));
+    }
+
+    #[test]
+    fn should_not_allow_any_address_if_no_networks() {
+        // Act & Assert
+        assert!(!is_allowed(&[], "127.0.0.1".parse().unwrap()));
     }
 }
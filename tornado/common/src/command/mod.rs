@@ -1,3 +1,4 @@
+use crate::command::timeout::{timeout_error, ActionTimeouts};
 use crate::metrics::{
     ActionMeter, ACTION_ID_LABEL_KEY, ATTEMPT_RESULT_KEY, RESULT_FAILURE, RESULT_SUCCESS,
 };
@@ -12,6 +13,7 @@ use tornado_executor_common::{ExecutorError, StatefulExecutor, StatelessExecutor
 pub mod callback;
 pub mod pool;
 pub mod retry;
+pub mod timeout;
 
 /// Basic Trait to implement the Command Design Pattern.
 /// See: https://refactoring.guru/design-patterns/command
@@ -23,11 +25,22 @@ pub trait Command<Message, Output> {
 pub struct StatelessExecutorCommand<T: StatelessExecutor> {
     action_meter: Arc<ActionMeter>,
     executor: T,
+    timeouts: ActionTimeouts,
 }
 
 impl<T: StatelessExecutor> StatelessExecutorCommand<T> {
     pub fn new(action_meter: Arc<ActionMeter>, executor: T) -> Self {
-        Self { action_meter, executor }
+        Self::new_with_timeouts(action_meter, executor, ActionTimeouts::default())
+    }
+
+    /// Creates a StatelessExecutorCommand that cancels the action executions
+    /// that do not complete within the timeout configured for their action id.
+    pub fn new_with_timeouts(
+        action_meter: Arc<ActionMeter>,
+        executor: T,
+        timeouts: ActionTimeouts,
+    ) -> Self {
+        Self { action_meter, executor, timeouts }
     }
 }
 
@@ -38,7 +51,13 @@ impl<T: StatelessExecutor> Command<Arc<Action>, Result<(), ExecutorError>>
 {
     async fn execute(&self, message: Arc<Action>) -> Result<(), ExecutorError> {
         let action_id = message.id.to_owned();
-        let result = self.executor.execute(message).await;
+        let result = match self.timeouts.timeout_for(&action_id) {
+            // The execution future is dropped when the timeout expires
+            Some(timeout) => tokio::time::timeout(timeout, self.executor.execute(message))
+                .await
+                .unwrap_or_else(|_| Err(timeout_error(&action_id, timeout))),
+            None => self.executor.execute(message).await,
+        };
         increment_processing_attempt_counter(&result, action_id, self.action_meter.as_ref());
         result
     }
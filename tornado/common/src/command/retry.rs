@@ -1,134 +1,122 @@
 use crate::command::Command;
+use crate::token_bucket::TokenBucket;
 use core::fmt::Debug;
 use core::marker::PhantomData;
 use log::*;
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tornado_common_api::RetriableError;
+use tornado_common_api::{Action, RetriableError};
+use tornado_common_metrics::opentelemetry::metrics::Counter;
+use tornado_common_metrics::opentelemetry::Key;
 
-/// Defines the strategy to apply in case of a failure.
-/// This is applied, for example, when an action execution fails
+pub use tornado_common_api::retry::{BackoffPolicy, RetryPolicy, RetryStrategy};
+
+const RETRY_BUDGET_OUTCOME_KEY: Key = Key::from_static_str("outcome");
+
+/// Limits the number of retries performed across all the actions, to prevent a widespread
+///   failure from multiplying the load with the retries of every action.
+/// The budget works as a token bucket: each retry consumes a token and the tokens are
+///   refilled at a fixed rate up to the burst size.
 #[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct RetryStrategy {
-    pub retry_policy: RetryPolicy,
-    pub backoff_policy: BackoffPolicy,
+pub struct RetryBudgetConfig {
+    /// The max number of retries per second across all the actions
+    pub retries_per_sec: f64,
+    /// The max number of retries that can be performed at once when the budget is full.
+    /// Defaults to `retries_per_sec`, with a minimum of 1.
+    pub burst: Option<u32>,
+    /// What happens to a retry when the budget is exhausted
+    #[serde(default)]
+    pub when_exhausted: RetryBudgetExhaustedPolicy,
 }
 
-impl Default for RetryStrategy {
-    fn default() -> Self {
-        Self {
-            retry_policy: RetryPolicy::MaxRetries { retries: 20 },
-            backoff_policy: BackoffPolicy::Exponential { ms: 1000, multiplier: 2 },
-        }
-    }
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub enum RetryBudgetExhaustedPolicy {
+    /// The retry waits until the budget has an available token
+    #[default]
+    Defer,
+    /// The retry is not performed and the action fails
+    Abandon,
+}
+
+/// The retry budget shared by all the RetryCommands created with it.
+/// Clones of a RetryBudget share the same tokens.
+#[derive(Clone)]
+pub struct RetryBudget {
+    when_exhausted: RetryBudgetExhaustedPolicy,
+    bucket: Arc<Mutex<TokenBucket>>,
+    exhausted_counter: Counter<u64>,
 }
 
-impl RetryStrategy {
-    /// Returns whether a retry attempt should be performed and an optional backoff time
-    pub fn should_retry(&self, failed_attempts: u32) -> (bool, Option<Duration>) {
-        (
-            self.retry_policy.should_retry(failed_attempts),
-            self.backoff_policy.should_wait(failed_attempts),
+impl RetryBudget {
+    pub fn new(config: &RetryBudgetConfig) -> Self {
+        let burst = config.burst.map(f64::from).unwrap_or(config.retries_per_sec.ceil()).max(1.0);
+        let exhausted_counter = tornado_common_metrics::opentelemetry::global::meter(
+            "tornado_common",
+        )
+        .u64_counter("retry_budget_exhausted_counter")
+        .with_description(
+            "Counter of the retries deferred or abandoned because the retry budget was exhausted",
         )
+        .init();
+        Self {
+            when_exhausted: config.when_exhausted,
+            bucket: Arc::new(Mutex::new(TokenBucket::new(config.retries_per_sec, burst))),
+            exhausted_counter,
+        }
     }
-}
 
-// Defines the retry policy of a RetryStrategy
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(tag = "type")]
-pub enum RetryPolicy {
-    /// No Retry attempts defined
-    None,
-    /// The operation will be retried for a max number of times.
-    MaxRetries { retries: u32 },
-    /// The operation will be retried an infinite number of times.
-    Infinite,
-    // Timeout,
-}
+    /// Consumes a token of the budget.
+    /// If no tokens are available, it returns the time to wait for the next one.
+    fn try_acquire(&self) -> Result<(), Duration> {
+        self.bucket.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).try_acquire()
+    }
 
-impl RetryPolicy {
-    fn should_retry(&self, failed_attempts: u32) -> bool {
-        if failed_attempts == 0 {
-            true
-        } else {
-            match self {
-                RetryPolicy::None => false,
-                RetryPolicy::Infinite => true,
-                RetryPolicy::MaxRetries { retries: attempts } => *attempts + 1 > failed_attempts,
+    /// Waits until a retry is allowed by the budget.
+    /// Returns false if the budget is exhausted and the retry should be abandoned.
+    async fn acquire(&self) -> bool {
+        loop {
+            match self.try_acquire() {
+                Ok(()) => return true,
+                Err(wait) => match self.when_exhausted {
+                    RetryBudgetExhaustedPolicy::Defer => {
+                        self.exhausted_counter
+                            .add(1, &[RETRY_BUDGET_OUTCOME_KEY.string("deferred")]);
+                        debug!(
+                            "The retry budget is exhausted. Wait for {:?} before retrying.",
+                            wait
+                        );
+                        actix::clock::sleep(wait).await;
+                    }
+                    RetryBudgetExhaustedPolicy::Abandon => {
+                        self.exhausted_counter
+                            .add(1, &[RETRY_BUDGET_OUTCOME_KEY.string("abandoned")]);
+                        return false;
+                    }
+                },
             }
         }
     }
 }
 
-// Defines the backoff policy of a RetryStrategy
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(tag = "type")]
-pub enum BackoffPolicy {
-    /// No backoff, the retry will be attempted without waiting
-    None,
-    /// A fixed amount ot time will be waited between each retry attempt
-    Fixed { ms: u32 },
-    /// Permits to specify the amount of time between two consecutive retry attempts.
-    /// The time to wait after 'i' retries is specified in the vector at position 'i'.
-    /// If the number of retries is bigger than the vector length, then the last value in the vector is used.
-    /// For example:
-    /// ms = [111,222,333] -> It waits 111 ms after the first failure, 222 ms after the second failure and then 333 ms for all following failures.
-    Variable { ms: Vec<u32> },
-    /// Implementation of BackoffPolicy that increases the back off period for each retry attempt in a given set using the exponential function.
-    Exponential {
-        /// The period to sleep on the first backoff.
-        ms: u32,
-        // The multiplier to use to generate the next backoff interval from the last.
-        multiplier: u64,
-    },
+/// A message that can override the RetryStrategy of the RetryCommand executing it.
+pub trait WithRetryStrategy {
+    /// Returns the RetryStrategy to use instead of the one of the RetryCommand, if any
+    fn retry_strategy(&self) -> Option<&RetryStrategy>;
 }
 
-impl BackoffPolicy {
-    fn should_wait(&self, failed_attempts: u32) -> Option<Duration> {
-        if failed_attempts == 0 {
-            None
-        } else {
-            match self {
-                BackoffPolicy::None => None,
-                BackoffPolicy::Fixed { ms } => {
-                    if *ms > 0 {
-                        Some(Duration::from_millis(*ms as u64))
-                    } else {
-                        None
-                    }
-                }
-                BackoffPolicy::Variable { ms } => {
-                    let index = (failed_attempts - 1) as usize;
-                    let option_wait_ms = if ms.len() > index { ms.get(index) } else { ms.last() };
-                    match option_wait_ms {
-                        Some(wait_ms) => {
-                            if *wait_ms > 0 {
-                                Some(Duration::from_millis(*wait_ms as u64))
-                            } else {
-                                None
-                            }
-                        }
-                        None => None,
-                    }
-                }
-                BackoffPolicy::Exponential { ms, multiplier } => {
-                    if *ms > 0 {
-                        let multiplier = multiplier.saturating_pow(failed_attempts - 1);
-                        let wait_ms = multiplier.saturating_mul(*ms as u64);
-                        Some(Duration::from_millis(wait_ms))
-                    } else {
-                        None
-                    }
-                }
-            }
-        }
+impl WithRetryStrategy for Arc<Action> {
+    fn retry_strategy(&self) -> Option<&RetryStrategy> {
+        self.retry_strategy.as_ref()
     }
 }
 
-/// A Command that reties a failing operation based on the specified RetryStrategy
+/// A Command that reties a failing operation based on the specified RetryStrategy.
+/// The messages that carry their own RetryStrategy are retried based on it instead.
 pub struct RetryCommand<I: Clone + Debug, O, E: RetriableError, T: Command<I, Result<O, E>>> {
     command: T,
     retry_strategy: RetryStrategy,
+    retry_budget: Option<RetryBudget>,
     phantom_i: PhantomData<I>,
     phantom_o: PhantomData<O>,
     phantom_e: PhantomData<E>,
@@ -136,8 +124,19 @@ pub struct RetryCommand<I: Clone + Debug, O, E: RetriableError, T: Command<I, Re
 
 impl<I: Clone + Debug, O, E: RetriableError, T: Command<I, Result<O, E>>> RetryCommand<I, O, E, T> {
     pub fn new(retry_strategy: RetryStrategy, command: T) -> Self {
+        Self::new_with_retry_budget(retry_strategy, None, command)
+    }
+
+    /// Creates a RetryCommand whose retries are also limited by the given RetryBudget,
+    ///   that can be shared with other RetryCommands.
+    pub fn new_with_retry_budget(
+        retry_strategy: RetryStrategy,
+        retry_budget: Option<RetryBudget>,
+        command: T,
+    ) -> Self {
         Self {
             retry_strategy,
+            retry_budget,
             command,
             phantom_i: PhantomData,
             phantom_o: PhantomData,
@@ -147,14 +146,18 @@ impl<I: Clone + Debug, O, E: RetriableError, T: Command<I, Result<O, E>>> RetryC
 }
 
 #[async_trait::async_trait(?Send)]
-impl<I: Clone + Debug, O, E: RetriableError + Debug, T: Command<I, Result<O, E>>>
-    Command<I, Result<O, E>> for RetryCommand<I, O, E, T>
+impl<
+        I: Clone + Debug + WithRetryStrategy,
+        O,
+        E: RetriableError + Debug,
+        T: Command<I, Result<O, E>>,
+    > Command<I, Result<O, E>> for RetryCommand<I, O, E, T>
 {
     async fn execute(&self, message: I) -> Result<O, E> {
         trace!("RetryCommand - received new message");
 
         let command = &self.command;
-        let retry_strategy = &self.retry_strategy;
+        let retry_strategy = message.retry_strategy().unwrap_or(&self.retry_strategy);
 
         let mut should_retry = true;
         let mut failed_attempts = 0;
@@ -180,6 +183,12 @@ impl<I: Clone + Debug, O, E: RetriableError + Debug, T: Command<I, Result<O, E>>
                                 debug!("Wait for {:?} before retrying.", sleep);
                                 actix::clock::sleep(sleep).await;
                             }
+                            if let Some(retry_budget) = &self.retry_budget {
+                                if !retry_budget.acquire().await {
+                                    warn!("The failed message will not be retried as the retry budget is exhausted. Failed attempts: {}. Message: {:?}", failed_attempts, message);
+                                    return Err(err);
+                                }
+                            }
                         } else {
                             warn!("The failed message will not be retried any more in respect of the current RetryPolicy. Failed attempts: {}. Message: {:?}", failed_attempts, message);
                             return Err(err);
@@ -199,168 +208,11 @@ pub mod test {
     use crate::metrics::ActionMeter;
     use rand::Rng;
     use std::sync::Arc;
+    use std::time::Instant;
     use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
     use tornado_common_api::Action;
     use tornado_executor_common::{ExecutorError, StatelessExecutor};
 
-    #[test]
-    fn retry_policy_none_should_never_retry() {
-        assert!(RetryPolicy::None.should_retry(0));
-        assert!(!RetryPolicy::None.should_retry(1));
-        assert!(!RetryPolicy::None.should_retry(10));
-        assert!(!RetryPolicy::None.should_retry(100));
-    }
-
-    #[test]
-    fn retry_policy_max_should_return_when_to_retry() {
-        assert!(RetryPolicy::MaxRetries { retries: 0 }.should_retry(0));
-        assert!(!RetryPolicy::MaxRetries { retries: 0 }.should_retry(1));
-        assert!(!RetryPolicy::MaxRetries { retries: 0 }.should_retry(10));
-        assert!(!RetryPolicy::MaxRetries { retries: 0 }.should_retry(100));
-
-        assert!(RetryPolicy::MaxRetries { retries: 1 }.should_retry(0));
-        assert!(RetryPolicy::MaxRetries { retries: 1 }.should_retry(1));
-        assert!(!RetryPolicy::MaxRetries { retries: 1 }.should_retry(2));
-        assert!(!RetryPolicy::MaxRetries { retries: 1 }.should_retry(10));
-        assert!(!RetryPolicy::MaxRetries { retries: 1 }.should_retry(100));
-
-        assert!(RetryPolicy::MaxRetries { retries: 10 }.should_retry(0));
-        assert!(RetryPolicy::MaxRetries { retries: 10 }.should_retry(1));
-        assert!(RetryPolicy::MaxRetries { retries: 10 }.should_retry(10));
-        assert!(!RetryPolicy::MaxRetries { retries: 10 }.should_retry(11));
-        assert!(!RetryPolicy::MaxRetries { retries: 10 }.should_retry(100));
-    }
-
-    #[test]
-    fn retry_policy_infinite_should_return_when_to_retry() {
-        assert!(RetryPolicy::Infinite.should_retry(0));
-        assert!(RetryPolicy::Infinite.should_retry(1));
-        assert!(RetryPolicy::Infinite.should_retry(10));
-        assert!(RetryPolicy::Infinite.should_retry(100));
-    }
-
-    #[test]
-    fn backoff_policy_none_should_never_wait() {
-        assert_eq!(None, BackoffPolicy::None.should_wait(0));
-        assert_eq!(None, BackoffPolicy::None.should_wait(1));
-        assert_eq!(None, BackoffPolicy::None.should_wait(10));
-        assert_eq!(None, BackoffPolicy::None.should_wait(100));
-    }
-
-    #[test]
-    fn backoff_policy_fixed_should_return_the_wait_time() {
-        assert_eq!(None, BackoffPolicy::Fixed { ms: 100 }.should_wait(0));
-        assert_eq!(
-            Some(Duration::from_millis(100)),
-            BackoffPolicy::Fixed { ms: 100 }.should_wait(1)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(100)),
-            BackoffPolicy::Fixed { ms: 100 }.should_wait(10)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(1123)),
-            BackoffPolicy::Fixed { ms: 1123 }.should_wait(100)
-        );
-        assert_eq!(None, BackoffPolicy::Fixed { ms: 0 }.should_wait(0));
-        assert_eq!(None, BackoffPolicy::Fixed { ms: 0 }.should_wait(1));
-        assert_eq!(None, BackoffPolicy::Fixed { ms: 0 }.should_wait(10));
-    }
-
-    #[test]
-    fn backoff_policy_variable_should_return_the_wait_time() {
-        assert_eq!(None, BackoffPolicy::Variable { ms: vec!() }.should_wait(0));
-        assert_eq!(None, BackoffPolicy::Variable { ms: vec!() }.should_wait(1));
-        assert_eq!(None, BackoffPolicy::Variable { ms: vec!() }.should_wait(200));
-
-        assert_eq!(None, BackoffPolicy::Variable { ms: vec!(0) }.should_wait(0));
-        assert_eq!(None, BackoffPolicy::Variable { ms: vec!(0) }.should_wait(1));
-        assert_eq!(None, BackoffPolicy::Variable { ms: vec!(0) }.should_wait(100));
-
-        assert_eq!(None, BackoffPolicy::Variable { ms: vec!(100) }.should_wait(0));
-        assert_eq!(
-            Some(Duration::from_millis(100)),
-            BackoffPolicy::Variable { ms: vec!(100) }.should_wait(1)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(100)),
-            BackoffPolicy::Variable { ms: vec!(100) }.should_wait(2)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(100)),
-            BackoffPolicy::Variable { ms: vec!(100) }.should_wait(10)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(100)),
-            BackoffPolicy::Variable { ms: vec!(100) }.should_wait(100)
-        );
-
-        assert_eq!(None, BackoffPolicy::Variable { ms: vec!(111, 222, 0, 444) }.should_wait(0));
-        assert_eq!(
-            Some(Duration::from_millis(111)),
-            BackoffPolicy::Variable { ms: vec!(111, 222, 0, 444) }.should_wait(1)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(222)),
-            BackoffPolicy::Variable { ms: vec!(111, 222, 0, 444) }.should_wait(2)
-        );
-        assert_eq!(None, BackoffPolicy::Variable { ms: vec!(111, 222, 0, 444) }.should_wait(3));
-        assert_eq!(
-            Some(Duration::from_millis(444)),
-            BackoffPolicy::Variable { ms: vec!(111, 222, 0, 444) }.should_wait(4)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(444)),
-            BackoffPolicy::Variable { ms: vec!(111, 222, 0, 444) }.should_wait(5)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(444)),
-            BackoffPolicy::Variable { ms: vec!(111, 222, 0, 444) }.should_wait(100_000)
-        );
-    }
-
-    #[test]
-    fn backoff_policy_exponential_should_return_the_wait_time() {
-        assert_eq!(None, BackoffPolicy::Exponential { ms: 123, multiplier: 2 }.should_wait(0));
-        assert_eq!(
-            Some(Duration::from_millis(123)),
-            BackoffPolicy::Exponential { ms: 123, multiplier: 2 }.should_wait(1)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(246)),
-            BackoffPolicy::Exponential { ms: 123, multiplier: 2 }.should_wait(2)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(492)),
-            BackoffPolicy::Exponential { ms: 123, multiplier: 2 }.should_wait(3)
-        );
-
-        assert_eq!(None, BackoffPolicy::Exponential { ms: 1000, multiplier: 3 }.should_wait(0));
-        assert_eq!(
-            Some(Duration::from_millis(1000)),
-            BackoffPolicy::Exponential { ms: 1000, multiplier: 3 }.should_wait(1)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(3000)),
-            BackoffPolicy::Exponential { ms: 1000, multiplier: 3 }.should_wait(2)
-        );
-        assert_eq!(
-            Some(Duration::from_millis(9000)),
-            BackoffPolicy::Exponential { ms: 1000, multiplier: 3 }.should_wait(3)
-        );
-    }
-
-    #[test]
-    fn retry_policy_should_return_whether_to_retry() {
-        let retry_strategy = RetryStrategy {
-            retry_policy: RetryPolicy::MaxRetries { retries: 1 },
-            backoff_policy: BackoffPolicy::Fixed { ms: 34 },
-        };
-        assert_eq!((true, None), retry_strategy.should_retry(0));
-        assert_eq!((true, Some(Duration::from_millis(34))), retry_strategy.should_retry(1));
-        assert_eq!((false, Some(Duration::from_millis(34))), retry_strategy.should_retry(2));
-    }
-
     #[actix_rt::test]
     async fn should_retry_if_failure() {
         let (sender, mut receiver) = unbounded_channel();
@@ -390,6 +242,47 @@ pub mod test {
         }
     }
 
+    #[actix_rt::test]
+    async fn should_retry_based_on_the_retry_strategy_of_the_action_if_set() {
+        let (sender, mut receiver) = unbounded_channel();
+        let retry_strategy = RetryStrategy {
+            retry_policy: RetryPolicy::MaxRetries { retries: 2 },
+            backoff_policy: BackoffPolicy::None,
+        };
+
+        let mut action_with_override = Action::new("with_override");
+        action_with_override.retry_strategy = Some(RetryStrategy {
+            retry_policy: RetryPolicy::MaxRetries { retries: 5 },
+            backoff_policy: BackoffPolicy::None,
+        });
+        let action_without_override = Action::new("without_override");
+
+        let command = RetryCommand::new(
+            retry_strategy,
+            StatelessExecutorCommand::new(
+                Arc::new(ActionMeter::new("test_meter")),
+                AlwaysFailExecutor { sender: sender.clone(), can_retry: true },
+            ),
+        );
+
+        let result_with_override = command.execute(action_with_override.into()).await;
+        let result_without_override = command.execute(action_without_override.into()).await;
+
+        assert!(result_with_override.is_err());
+        assert!(result_without_override.is_err());
+        let mut executions_with_override = 0;
+        let mut executions_without_override = 0;
+        while let Ok(action) = receiver.try_recv() {
+            match action.id.as_str() {
+                "with_override" => executions_with_override += 1,
+                _ => executions_without_override += 1,
+            }
+        }
+        // The first execution plus the retries
+        assert_eq!(1 + 5, executions_with_override);
+        assert_eq!(1 + 2, executions_without_override);
+    }
+
     #[actix_rt::test]
     async fn should_not_retry_if_ok() {
         let (sender, mut receiver) = unbounded_channel();
@@ -483,6 +376,89 @@ pub mod test {
         }
     }
 
+    #[actix_rt::test]
+    async fn concurrent_retries_should_respect_the_shared_retry_budget() {
+        let (sender, mut receiver) = unbounded_channel();
+        let retry_strategy = RetryStrategy {
+            retry_policy: RetryPolicy::MaxRetries { retries: 10 },
+            backoff_policy: BackoffPolicy::None,
+        };
+        let retry_budget = RetryBudget::new(&RetryBudgetConfig {
+            retries_per_sec: 0.1,
+            burst: Some(2),
+            when_exhausted: RetryBudgetExhaustedPolicy::Abandon,
+        });
+
+        let commands = (0..5)
+            .map(|_| {
+                RetryCommand::new_with_retry_budget(
+                    retry_strategy.clone(),
+                    Some(retry_budget.clone()),
+                    StatelessExecutorCommand::new(
+                        Arc::new(ActionMeter::new("test_meter")),
+                        AlwaysFailExecutor { sender: sender.clone(), can_retry: true },
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let results = futures_util::future::join_all(
+            commands.iter().map(|command| command.execute(Action::new("hello").into())),
+        )
+        .await;
+
+        assert!(results.iter().all(|result| result.is_err()));
+        let mut executions = 0;
+        while receiver.try_recv().is_ok() {
+            executions += 1;
+        }
+        // One execution for each action plus the two retries allowed by the budget
+        assert_eq!(5 + 2, executions);
+    }
+
+    #[actix_rt::test]
+    async fn should_defer_the_retries_when_the_retry_budget_is_exhausted() {
+        let (sender, mut receiver) = unbounded_channel();
+        let retry_strategy = RetryStrategy {
+            retry_policy: RetryPolicy::MaxRetries { retries: 2 },
+            backoff_policy: BackoffPolicy::None,
+        };
+        let retry_budget = RetryBudget::new(&RetryBudgetConfig {
+            retries_per_sec: 20.0,
+            burst: Some(1),
+            when_exhausted: RetryBudgetExhaustedPolicy::Defer,
+        });
+
+        let commands = (0..3)
+            .map(|_| {
+                RetryCommand::new_with_retry_budget(
+                    retry_strategy.clone(),
+                    Some(retry_budget.clone()),
+                    StatelessExecutorCommand::new(
+                        Arc::new(ActionMeter::new("test_meter")),
+                        AlwaysFailExecutor { sender: sender.clone(), can_retry: true },
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let before = Instant::now();
+        futures_util::future::join_all(
+            commands.iter().map(|command| command.execute(Action::new("hello").into())),
+        )
+        .await;
+        let elapsed = before.elapsed();
+
+        let mut executions = 0;
+        while receiver.try_recv().is_ok() {
+            executions += 1;
+        }
+        // All the retries are performed, but only the first one is immediately available:
+        // the other five wait for the budget to be refilled at 20 retries per second
+        assert_eq!(3 * 3, executions);
+        assert!(elapsed >= Duration::from_millis(240));
+    }
+
     pub struct AlwaysFailExecutor {
         pub can_retry: bool,
         pub sender: UnboundedSender<Arc<Action>>,
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tornado_executor_common::ExecutorError;
+
+/// The error code of the ExecutorError returned when an action execution exceeds its timeout
+pub const ACTION_TIMEOUT_ERROR_CODE: &str = "ActionTimeout";
+
+/// Defines the max time allowed to the execution of an action.
+/// When it expires, the execution is cancelled and a retryable error is returned.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct ActionTimeouts {
+    /// The timeout in milliseconds of the actions with no specific timeout.
+    /// If not set, these actions have no timeout.
+    pub default_ms: Option<u64>,
+    /// The timeouts in milliseconds by action id
+    #[serde(default)]
+    pub by_action_id_ms: HashMap<String, u64>,
+}
+
+impl ActionTimeouts {
+    /// Returns the timeout of the actions with the given id, if any
+    pub fn timeout_for(&self, action_id: &str) -> Option<Duration> {
+        self.by_action_id_ms.get(action_id).copied().or(self.default_ms).map(Duration::from_millis)
+    }
+}
+
+pub(crate) fn timeout_error(action_id: &str, timeout: Duration) -> ExecutorError {
+    ExecutorError::ActionExecutionError {
+        message: format!(
+            "The execution of action [{}] did not complete within {} ms",
+            action_id,
+            timeout.as_millis()
+        ),
+        can_retry: true,
+        code: Some(ACTION_TIMEOUT_ERROR_CODE),
+        data: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::{Command, StatelessExecutorCommand};
+    use crate::metrics::ActionMeter;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use tornado_common_api::{Action, RetriableError};
+    use tornado_executor_common::StatelessExecutor;
+
+    struct SleepingExecutor {
+        delay: Duration,
+        completed: Rc<Cell<bool>>,
+    }
+
+    impl std::fmt::Display for SleepingExecutor {
+        fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+            fmt.write_str("SleepingExecutor")
+        }
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl StatelessExecutor for SleepingExecutor {
+        async fn execute(&self, _action: Arc<Action>) -> Result<(), ExecutorError> {
+            tokio::time::sleep(self.delay).await;
+            self.completed.set(true);
+            Ok(())
+        }
+    }
+
+    fn new_command(
+        delay: Duration,
+        timeouts: ActionTimeouts,
+    ) -> (StatelessExecutorCommand<SleepingExecutor>, Rc<Cell<bool>>) {
+        let completed = Rc::new(Cell::new(false));
+        let command = StatelessExecutorCommand::new_with_timeouts(
+            Arc::new(ActionMeter::new("test_action_meter")),
+            SleepingExecutor { delay, completed: completed.clone() },
+            timeouts,
+        );
+        (command, completed)
+    }
+
+    #[test]
+    fn should_return_the_timeout_of_the_action_id_or_the_default_one() {
+        // Arrange
+        let timeouts = ActionTimeouts {
+            default_ms: Some(1000),
+            by_action_id_ms: HashMap::from([("icinga2".to_owned(), 200)]),
+        };
+
+        // Act & Assert
+        assert_eq!(Some(Duration::from_millis(200)), timeouts.timeout_for("icinga2"));
+        assert_eq!(Some(Duration::from_millis(1000)), timeouts.timeout_for("script"));
+        assert_eq!(None, ActionTimeouts::default().timeout_for("script"));
+    }
+
+    #[actix_rt::test]
+    async fn should_cancel_the_execution_when_the_timeout_expires() {
+        // Arrange
+        let timeouts = ActionTimeouts {
+            default_ms: None,
+            by_action_id_ms: HashMap::from([("slow".to_owned(), 100)]),
+        };
+        let (command, completed) = new_command(Duration::from_millis(500), timeouts);
+
+        // Act
+        let result = command.execute(Arc::new(Action::new("slow"))).await;
+        tokio::time::sleep(Duration::from_millis(600)).await;
+
+        // Assert
+        let err = result.unwrap_err();
+        assert!(err.can_retry());
+        assert!(matches!(
+            err,
+            ExecutorError::ActionExecutionError { code: Some(ACTION_TIMEOUT_ERROR_CODE), .. }
+        ));
+        assert!(!completed.get());
+    }
+
+    #[actix_rt::test]
+    async fn should_not_timeout_the_actions_completed_in_time() {
+        // Arrange
+        let timeouts = ActionTimeouts { default_ms: Some(1000), by_action_id_ms: HashMap::new() };
+        let (command, completed) = new_command(Duration::from_millis(10), timeouts);
+
+        // Act
+        let result = command.execute(Arc::new(Action::new("fast"))).await;
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(completed.get());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::TornadoError;
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tornado_common_api::Action;
+
+/// An Action whose execution failed after exhausting its retries.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeadLetter {
+    /// The unique id of the dead letter
+    pub id: String,
+    /// When the Action was added to the store, in milliseconds since the Unix epoch
+    pub failed_ms: u64,
+    /// The error of the last execution attempt
+    pub error: String,
+    pub action: Action,
+}
+
+/// A file-backed store of the Actions whose execution failed after exhausting their retries.
+/// The dead letters are kept in a file with one JSON entry per line,
+///   so that they survive a restart of Tornado and can be replayed later.
+/// The file is accessed in a blocking thread, so that the async tasks are never blocked.
+pub struct DeadLetterStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl DeadLetterStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into(), lock: Mutex::new(()) }
+    }
+
+    /// Adds an Action to the store and returns the id of its dead letter.
+    pub async fn add(
+        self: &Arc<Self>,
+        action: &Action,
+        error: &str,
+    ) -> Result<String, TornadoError> {
+        let store = self.clone();
+        let action = action.clone();
+        let error = error.to_owned();
+        run_blocking(move || store.add_blocking(&action, &error)).await
+    }
+
+    /// Returns all the dead letters in the store, in the order in which they were added.
+    pub async fn list(self: &Arc<Self>) -> Result<Vec<DeadLetter>, TornadoError> {
+        let store = self.clone();
+        run_blocking(move || store.list_blocking()).await
+    }
+
+    /// Removes from the store the dead letters with the given ids.
+    pub async fn remove(self: &Arc<Self>, ids: Vec<String>) -> Result<(), TornadoError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let store = self.clone();
+        run_blocking(move || store.remove_blocking(&ids)).await
+    }
+
+    fn add_blocking(&self, action: &Action, error: &str) -> Result<String, TornadoError> {
+        let dead_letter = DeadLetter {
+            id: uuid::Uuid::new_v4().to_string(),
+            failed_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|now| now.as_millis() as u64)
+                .unwrap_or_default(),
+            error: error.to_owned(),
+            action: action.clone(),
+        };
+        let line = to_json_line(&dead_letter)?;
+
+        let _guard = self.lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|err| self.io_error(err))?;
+        Ok(dead_letter.id)
+    }
+
+    fn list_blocking(&self) -> Result<Vec<DeadLetter>, TornadoError> {
+        let _guard = self.lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.read_all()
+    }
+
+    fn remove_blocking(&self, ids: &[String]) -> Result<(), TornadoError> {
+        let _guard = self.lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let kept: Vec<_> = self
+            .read_all()?
+            .into_iter()
+            .filter(|dead_letter| !ids.contains(&dead_letter.id))
+            .collect();
+
+        let mut content = String::new();
+        for dead_letter in &kept {
+            content.push_str(&to_json_line(dead_letter)?);
+        }
+        // The file is replaced atomically, so a crash cannot leave it truncated
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, content)
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|err| self.io_error(err))?;
+        Ok(())
+    }
+
+    fn read_all(&self) -> Result<Vec<DeadLetter>, TornadoError> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(self.io_error(err)),
+        };
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(dead_letter) => Some(dead_letter),
+                Err(err) => {
+                    warn!(
+                        "DeadLetterStore - Ignore invalid entry in [{}]. Err: {:?}",
+                        self.path.display(),
+                        err
+                    );
+                    None
+                }
+            })
+            .collect())
+    }
+
+    fn io_error(&self, err: std::io::Error) -> TornadoError {
+        TornadoError::ExecutionError {
+            message: format!(
+                "Cannot access the dead letter store [{}]. Err: {:?}",
+                self.path.display(),
+                err
+            ),
+        }
+    }
+}
+
+async fn run_blocking<T: Send + 'static>(
+    operation: impl FnOnce() -> Result<T, TornadoError> + Send + 'static,
+) -> Result<T, TornadoError> {
+    tokio::task::spawn_blocking(operation).await.map_err(|err| TornadoError::ExecutionError {
+        message: format!("The dead letter store operation did not complete. Err: {:?}", err),
+    })?
+}
+
+fn to_json_line(dead_letter: &DeadLetter) -> Result<String, TornadoError> {
+    serde_json::to_string(dead_letter).map(|json| json + "\n").map_err(|err| {
+        TornadoError::ExecutionError {
+            message: format!("Cannot serialize the dead letter. Err: {:?}", err),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn should_return_the_added_dead_letters() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let store = Arc::new(DeadLetterStore::new(tempdir.path().join("dead_letters.jsonl")));
+
+        // Act
+        let first_id = store.add(&Action::new("first"), "first error").await.unwrap();
+        let second_id = store.add(&Action::new("second"), "second error").await.unwrap();
+        let dead_letters = store.list().await.unwrap();
+
+        // Assert
+        assert_eq!(2, dead_letters.len());
+        assert_eq!(first_id, dead_letters[0].id);
+        assert_eq!("first", dead_letters[0].action.id);
+        assert_eq!("first error", dead_letters[0].error);
+        assert_eq!(second_id, dead_letters[1].id);
+        assert_eq!("second", dead_letters[1].action.id);
+    }
+
+    #[actix_rt::test]
+    async fn should_return_no_dead_letters_if_the_file_does_not_exist() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let store = Arc::new(DeadLetterStore::new(tempdir.path().join("dead_letters.jsonl")));
+
+        // Act & Assert
+        assert!(store.list().await.unwrap().is_empty());
+        store.remove(vec!["an_id".to_owned()]).await.unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn should_remove_only_the_selected_dead_letters() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let store = Arc::new(DeadLetterStore::new(tempdir.path().join("dead_letters.jsonl")));
+        let first_id = store.add(&Action::new("first"), "").await.unwrap();
+        let second_id = store.add(&Action::new("second"), "").await.unwrap();
+
+        // Act
+        store.remove(vec![second_id]).await.unwrap();
+
+        // Assert
+        let kept = store.list().await.unwrap();
+        assert_eq!(1, kept.len());
+        assert_eq!(first_id, kept[0].id);
+        store.remove(vec![first_id]).await.unwrap();
+        assert!(store.list().await.unwrap().is_empty());
+    }
+}
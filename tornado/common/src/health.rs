@@ -0,0 +1,123 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The default time a dependency can stay disconnected before being reported as unhealthy
+pub const DEFAULT_HEALTH_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// Tracks the connection state of an external dependency (e.g. NATS).
+/// A dependency that lost its connection is reported as unhealthy only if it is not
+///   able to reconnect within the grace period, so that short reconnections do not affect the
+///   readiness of the application. A dependency that was never connected is unhealthy.
+/// Clones share the same state.
+#[derive(Debug, Clone)]
+pub struct ConnectionHealth {
+    grace_period: Duration,
+    state: Arc<Mutex<ConnectionState>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectionState {
+    NeverConnected,
+    Connected,
+    Disconnected { since: Instant },
+}
+
+impl ConnectionHealth {
+    pub fn new(grace_period: Duration) -> Self {
+        ConnectionHealth {
+            grace_period,
+            state: Arc::new(Mutex::new(ConnectionState::NeverConnected)),
+        }
+    }
+
+    pub fn set_connected(&self) {
+        self.set_state(ConnectionState::Connected);
+    }
+
+    pub fn set_disconnected(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        // Keep the time of the first disconnection if the state is already disconnected
+        if *state == ConnectionState::Connected {
+            *state = ConnectionState::Disconnected { since: Instant::now() };
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.get_state() == ConnectionState::Connected
+    }
+
+    /// Returns whether the dependency is connected or it was disconnected
+    ///   less than a grace period ago.
+    pub fn is_healthy(&self) -> bool {
+        match self.get_state() {
+            ConnectionState::NeverConnected => false,
+            ConnectionState::Connected => true,
+            ConnectionState::Disconnected { since } => since.elapsed() < self.grace_period,
+        }
+    }
+
+    fn get_state(&self) -> ConnectionState {
+        *self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn set_state(&self, new_state: ConnectionState) {
+        *self.state.lock().unwrap_or_else(|err| err.into_inner()) = new_state;
+    }
+}
+
+impl Default for ConnectionHealth {
+    fn default() -> Self {
+        ConnectionHealth::new(DEFAULT_HEALTH_GRACE_PERIOD)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_be_unhealthy_if_never_connected() {
+        // Arrange
+        let health = ConnectionHealth::new(Duration::from_secs(60));
+
+        // Assert
+        assert!(!health.is_connected());
+        assert!(!health.is_healthy());
+    }
+
+    #[test]
+    fn should_become_unhealthy_after_the_grace_period_when_disconnected() {
+        // Arrange
+        let health = ConnectionHealth::new(Duration::from_millis(100));
+        health.set_connected();
+        assert!(health.is_healthy());
+
+        // Act
+        health.clone().set_disconnected();
+
+        // Assert
+        assert!(!health.is_connected());
+        assert!(health.is_healthy());
+
+        std::thread::sleep(Duration::from_millis(150));
+        assert!(!health.is_healthy());
+
+        health.set_connected();
+        assert!(health.is_healthy());
+    }
+
+    #[test]
+    fn should_keep_the_time_of_the_first_disconnection() {
+        // Arrange
+        let health = ConnectionHealth::new(Duration::from_millis(100));
+        health.set_connected();
+        health.set_disconnected();
+
+        // Act
+        std::thread::sleep(Duration::from_millis(150));
+        health.set_disconnected();
+
+        // Assert
+        assert!(!health.is_healthy());
+    }
+}
@@ -2,7 +2,12 @@ use thiserror::Error;
 
 pub mod actors;
 pub mod command;
+pub mod dead_letter;
+pub mod health;
 pub mod metrics;
+pub mod shutdown;
+pub mod signature;
+pub mod token_bucket;
 
 #[derive(Error, Debug)]
 pub enum TornadoError {
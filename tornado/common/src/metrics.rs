@@ -18,20 +18,25 @@ pub struct ActionMeter {
 
 impl ActionMeter {
     pub fn new(meter_name: &'static str) -> Self {
+        Self::new_with_prefix(meter_name, "")
+    }
+
+    /// Creates an ActionMeter whose instrument names start with the given prefix.
+    pub fn new_with_prefix(meter_name: &'static str, metric_name_prefix: &str) -> Self {
         let meter = tornado_common_metrics::opentelemetry::global::meter(meter_name);
 
         let actions_received_counter = meter
-            .u64_counter("actions_received_counter")
+            .u64_counter(format!("{}actions_received_counter", metric_name_prefix))
             .with_description("Actions received count")
             .init();
 
         let actions_processed_counter = meter
-            .u64_counter("actions_processed_counter")
+            .u64_counter(format!("{}actions_processed_counter", metric_name_prefix))
             .with_description("Actions processed count")
             .init();
 
         let actions_processing_attempts_counter = meter
-            .u64_counter("actions_processing_attempts_counter")
+            .u64_counter(format!("{}actions_processing_attempts_counter", metric_name_prefix))
             .with_description("Counter of the actions execution attempts")
             .init();
 
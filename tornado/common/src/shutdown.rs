@@ -0,0 +1,205 @@
+use log::*;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// The default max time to wait for the in-flight actions to complete when shutting down
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Coordinates the graceful shutdown of the application.
+/// It keeps track of the actions enqueued to the executors and not yet completed,
+///   including the ones waiting in a mailbox or for a retry, so that, on shutdown, the
+///   application can stop accepting new events and wait for the in-flight actions to complete
+///   before exiting.
+/// Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownCoordinator {
+    state: Arc<ShutdownState>,
+}
+
+#[derive(Debug, Default)]
+struct ShutdownState {
+    draining: AtomicBool,
+    in_flight_actions: AtomicUsize,
+    /// Notified when the last in-flight action completes
+    idle: Notify,
+}
+
+/// An enqueued or executing action. The action is considered completed when this is dropped.
+/// A clone is registered as a distinct in-flight action.
+#[derive(Debug)]
+pub struct InFlightAction {
+    state: Arc<ShutdownState>,
+}
+
+impl Clone for InFlightAction {
+    fn clone(&self) -> Self {
+        self.state.in_flight_actions.fetch_add(1, Ordering::SeqCst);
+        InFlightAction { state: self.state.clone() }
+    }
+}
+
+impl Drop for InFlightAction {
+    fn drop(&mut self) {
+        if self.state.in_flight_actions.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.state.idle.notify_waiters();
+        }
+    }
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns false once the drain has started
+    pub fn is_accepting_events(&self) -> bool {
+        !self.state.draining.load(Ordering::SeqCst)
+    }
+
+    pub fn in_flight_actions(&self) -> usize {
+        self.state.in_flight_actions.load(Ordering::SeqCst)
+    }
+
+    /// Registers an action as in flight. The action should be registered when it is enqueued
+    /// to its executor, so that the actions waiting in the mailbox are tracked too.
+    /// The action completes when the returned InFlightAction is dropped.
+    pub fn action_started(&self) -> InFlightAction {
+        self.state.in_flight_actions.fetch_add(1, Ordering::SeqCst);
+        InFlightAction { state: self.state.clone() }
+    }
+
+    /// Stops accepting new events and waits, up to the timeout, for the in-flight actions
+    /// to complete. Returns the number of actions abandoned because the timeout expired.
+    pub async fn drain(&self, timeout: Duration) -> usize {
+        self.drain_after(timeout, async {}).await
+    }
+
+    /// Stops accepting new events and waits, up to the timeout, first for `pending_events`,
+    /// that must complete when the events accepted before the drain have enqueued their
+    /// actions, and then for the in-flight actions to complete.
+    /// Returns the number of actions abandoned because the timeout expired.
+    pub async fn drain_after<F: Future<Output = ()>>(
+        &self,
+        timeout: Duration,
+        pending_events: F,
+    ) -> usize {
+        self.state.draining.store(true, Ordering::SeqCst);
+        info!(
+            "ShutdownCoordinator - Stopped accepting new events. Waiting up to {:?} for the pending events and the in-flight actions to complete",
+            timeout
+        );
+
+        let drained = tokio::time::timeout(timeout, async {
+            pending_events.await;
+            loop {
+                // The future is registered before the check, so a completion between the
+                // check and the await is not missed
+                let idle = self.state.idle.notified();
+                if self.in_flight_actions() == 0 {
+                    break;
+                }
+                idle.await;
+            }
+        })
+        .await;
+
+        match drained {
+            Ok(()) => {
+                info!("ShutdownCoordinator - All the in-flight actions completed");
+                0
+            }
+            Err(_) => {
+                let abandoned_actions = self.in_flight_actions();
+                warn!(
+                    "ShutdownCoordinator - The in-flight actions did not complete within {:?}. {} actions are abandoned",
+                    timeout, abandoned_actions
+                );
+                abandoned_actions
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_count_the_in_flight_actions() {
+        // Arrange
+        let shutdown = ShutdownCoordinator::new();
+
+        // Act
+        let first = shutdown.action_started();
+        let second = shutdown.clone().action_started();
+        let in_flight_before_drop = shutdown.in_flight_actions();
+        drop(first);
+
+        // Assert
+        assert_eq!(2, in_flight_before_drop);
+        assert_eq!(1, shutdown.in_flight_actions());
+        drop(second);
+        assert_eq!(0, shutdown.in_flight_actions());
+    }
+
+    #[tokio::test]
+    async fn should_stop_accepting_events_when_draining() {
+        // Arrange
+        let shutdown = ShutdownCoordinator::new();
+        let accepting_before_drain = shutdown.is_accepting_events();
+
+        // Act
+        let abandoned_actions = shutdown.drain(Duration::from_secs(1)).await;
+
+        // Assert
+        assert!(accepting_before_drain);
+        assert!(!shutdown.is_accepting_events());
+        assert_eq!(0, abandoned_actions);
+    }
+
+    #[tokio::test]
+    async fn should_count_the_clone_of_an_in_flight_action_as_another_action() {
+        // Arrange
+        let shutdown = ShutdownCoordinator::new();
+        let action = shutdown.action_started();
+
+        // Act
+        let cloned_action = action.clone();
+        drop(action);
+
+        // Assert
+        assert_eq!(1, shutdown.in_flight_actions());
+        drop(cloned_action);
+        assert_eq!(0, shutdown.in_flight_actions());
+    }
+
+    #[tokio::test]
+    async fn should_wait_for_the_actions_enqueued_by_the_pending_events() {
+        // Arrange
+        let shutdown = ShutdownCoordinator::new();
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let executor = tokio::spawn(async move {
+            while let Some(in_flight_action) = receiver.recv().await {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                drop(in_flight_action);
+            }
+        });
+        let pending_shutdown = shutdown.clone();
+        let pending_events = async move {
+            for _ in 0..3 {
+                sender.send(pending_shutdown.action_started()).unwrap();
+            }
+        };
+
+        // Act
+        let abandoned_actions = shutdown.drain_after(Duration::from_secs(5), pending_events).await;
+
+        // Assert
+        assert_eq!(0, abandoned_actions);
+        assert_eq!(0, shutdown.in_flight_actions());
+        executor.await.unwrap();
+    }
+}
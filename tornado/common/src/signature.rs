@@ -0,0 +1,214 @@
+use crate::actors::message::TornadoCommonActorError;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tornado_common_api::{Event, Value};
+
+/// The metadata field where the signature of an event is stored
+pub const METADATA_SIGNATURE: &str = "signature";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signs the outgoing events and verifies the signatures of the incoming ones with a shared secret.
+/// The signature is the hex encoded HMAC-SHA256 of the canonical form of the event, computed
+/// without the signature itself, and it is stored in the `signature` field of the event metadata.
+/// In the canonical form the object keys are sorted and the numbers are written in their shortest
+/// round-trip representation, so the signature does not depend on how the sender serialized them.
+#[derive(Clone)]
+pub struct EventSigner {
+    secret: Vec<u8>,
+}
+
+impl EventSigner {
+    pub fn new(secret: &str) -> Self {
+        Self { secret: secret.as_bytes().to_vec() }
+    }
+
+    /// Adds the signature to the event metadata, replacing the previous one if present
+    pub fn sign(&self, event: &mut Event) -> Result<(), TornadoCommonActorError> {
+        event.metadata.remove(METADATA_SIGNATURE);
+        let signature = hex::encode(self.hmac(event)?.finalize().into_bytes());
+        event.metadata.insert(METADATA_SIGNATURE.to_owned(), Value::String(signature));
+        Ok(())
+    }
+
+    /// Verifies the signature of the event and removes it from the event metadata.
+    /// Returns an error if the signature is missing or not valid.
+    pub fn verify(&self, event: &mut Event) -> Result<(), TornadoCommonActorError> {
+        let signature = match event.metadata.remove(METADATA_SIGNATURE) {
+            Some(Value::String(signature)) => signature,
+            Some(_) => {
+                return Err(TornadoCommonActorError::InvalidSignatureError {
+                    message: "The event signature is not a string".to_owned(),
+                })
+            }
+            None => {
+                return Err(TornadoCommonActorError::InvalidSignatureError {
+                    message: "The event is not signed".to_owned(),
+                })
+            }
+        };
+        let signature = hex::decode(&signature).map_err(|err| {
+            TornadoCommonActorError::InvalidSignatureError {
+                message: format!("The event signature is not hex encoded. Err: {:?}", err),
+            }
+        })?;
+        self.hmac(event)?.verify_slice(&signature).map_err(|_| {
+            TornadoCommonActorError::InvalidSignatureError {
+                message: "The event signature does not match".to_owned(),
+            }
+        })
+    }
+
+    fn hmac(&self, event: &Event) -> Result<HmacSha256, TornadoCommonActorError> {
+        let canonical_event = serde_json::to_value(event)
+            .and_then(|event| {
+                let mut canonical_event = vec![];
+                write_canonical(&event, &mut canonical_event)?;
+                Ok(canonical_event)
+            })
+            .map_err(|err| TornadoCommonActorError::SerdeError { message: format!("{}", err) })?;
+        let mut hmac = HmacSha256::new_from_slice(&self.secret).map_err(|err| {
+            TornadoCommonActorError::GenericError {
+                message: format!("Cannot create the HMAC from the secret. Err: {:?}", err),
+            }
+        })?;
+        hmac.update(&canonical_event);
+        Ok(hmac)
+    }
+}
+
+/// Writes the value as compact JSON with the object keys sorted
+fn write_canonical(value: &Value, out: &mut Vec<u8>) -> Result<(), serde_json::Error> {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(first, _), (second, _)| first.cmp(second));
+            out.push(b'{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                serde_json::to_writer(&mut *out, key)?;
+                out.push(b':');
+                write_canonical(value, out)?;
+            }
+            out.push(b'}');
+        }
+        Value::Array(values) => {
+            out.push(b'[');
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                write_canonical(value, out)?;
+            }
+            out.push(b']');
+        }
+        value => serde_json::to_writer(&mut *out, value)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+
+    fn signed_and_sent(signer: &EventSigner) -> Event {
+        let mut event = Event::new("email");
+        event.payload.insert("subject".to_owned(), json!("disk full"));
+        event.payload.insert("size".to_owned(), json!(1024));
+        signer.sign(&mut event).unwrap();
+        let sent_event = serde_json::to_vec(&event).unwrap();
+        serde_json::from_slice(&sent_event).unwrap()
+    }
+
+    #[test]
+    fn should_verify_a_correctly_signed_event() {
+        // Arrange
+        let signer = EventSigner::new("shared_secret");
+        let mut event = signed_and_sent(&signer);
+
+        // Act
+        let result = signer.verify(&mut event);
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(event.metadata.get(METADATA_SIGNATURE).is_none());
+    }
+
+    #[test]
+    fn should_verify_a_signed_event_with_float_values() {
+        // Arrange
+        let signer = EventSigner::new("shared_secret");
+        let mut event = Event::new("metric");
+        event.payload.insert("load".to_owned(), json!(0.1 + 0.2));
+        event.payload.insert("ratio".to_owned(), json!(2.0 / 3.0));
+        event.payload.insert("tiny".to_owned(), json!(1.7976931348623157e-300));
+        event.payload.insert("values".to_owned(), json!([1.0, 1e21, -123456.789e-5]));
+        signer.sign(&mut event).unwrap();
+        let sent_event = serde_json::to_string(&event).unwrap();
+
+        // Act
+        let mut received_event: Event = serde_json::from_str(&sent_event).unwrap();
+        let result = signer.verify(&mut received_event);
+
+        // Assert
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn should_write_the_canonical_form_with_sorted_keys() {
+        // Arrange
+        let value = json!({ "b": 1, "a": [2.5, { "d": null, "c": "text" }] });
+        let mut canonical = vec![];
+
+        // Act
+        write_canonical(&value, &mut canonical).unwrap();
+
+        // Assert
+        assert_eq!(
+            r#"{"a":[2.5,{"c":"text","d":null}],"b":1}"#,
+            String::from_utf8(canonical).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_reject_a_tampered_event() {
+        // Arrange
+        let signer = EventSigner::new("shared_secret");
+        let mut event = signed_and_sent(&signer);
+        event.payload.insert("subject".to_owned(), json!("all good"));
+
+        // Act
+        let result = signer.verify(&mut event);
+
+        // Assert
+        assert!(matches!(result, Err(TornadoCommonActorError::InvalidSignatureError { .. })));
+    }
+
+    #[test]
+    fn should_reject_an_event_signed_with_another_secret() {
+        // Arrange
+        let mut event = signed_and_sent(&EventSigner::new("another_secret"));
+
+        // Act
+        let result = EventSigner::new("shared_secret").verify(&mut event);
+
+        // Assert
+        assert!(matches!(result, Err(TornadoCommonActorError::InvalidSignatureError { .. })));
+    }
+
+    #[test]
+    fn should_reject_an_event_without_signature() {
+        // Arrange
+        let mut event = Event::new("email");
+
+        // Act
+        let result = EventSigner::new("shared_secret").verify(&mut event);
+
+        // Assert
+        assert!(matches!(result, Err(TornadoCommonActorError::InvalidSignatureError { .. })));
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+/// A token bucket that is refilled at a fixed rate up to its burst size.
+/// Each operation allowed by the bucket consumes a token, so that at most `burst` operations
+///   can be performed at once and then `rate_per_sec` operations per second.
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate_per_sec: f64,
+    burst: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket
+    pub fn new(rate_per_sec: f64, burst: f64) -> Self {
+        TokenBucket { rate_per_sec, burst, tokens: burst, last_refill: Instant::now() }
+    }
+
+    /// Consumes a token.
+    /// If no tokens are available, it returns the time to wait for the next one.
+    pub fn try_acquire(&mut self) -> Result<(), Duration> {
+        self.refill(Instant::now());
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else if self.rate_per_sec > 0.0 {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate_per_sec))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+
+    /// Returns whether the bucket is full at the given instant, that is, whether it would
+    ///   allow the same operations of a new bucket.
+    pub fn is_full_at(&self, now: Instant) -> bool {
+        self.tokens_at(now) >= self.burst
+    }
+
+    /// Returns the last time a token was requested
+    pub fn last_used(&self) -> Instant {
+        self.last_refill
+    }
+
+    fn refill(&mut self, now: Instant) {
+        self.tokens = self.tokens_at(now);
+        self.last_refill = now;
+    }
+
+    fn tokens_at(&self, now: Instant) -> f64 {
+        let elapsed_secs = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        (self.tokens + elapsed_secs * self.rate_per_sec).min(self.burst)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn should_allow_up_to_burst_operations_at_once() {
+        // Arrange
+        let mut bucket = TokenBucket::new(1.0, 3.0);
+
+        // Act
+        let results: Vec<bool> = (0..4).map(|_| bucket.try_acquire().is_ok()).collect();
+
+        // Assert
+        assert_eq!(vec![true, true, true, false], results);
+    }
+
+    #[test]
+    fn should_return_the_time_to_wait_for_the_next_token() {
+        // Arrange
+        let mut bucket = TokenBucket::new(2.0, 1.0);
+        bucket.try_acquire().unwrap();
+
+        // Act
+        let wait = bucket.try_acquire().unwrap_err();
+
+        // Assert
+        assert!(wait <= Duration::from_millis(500));
+        assert!(wait > Duration::from_millis(400));
+    }
+
+    #[test]
+    fn should_be_full_when_refilled() {
+        // Arrange
+        let mut bucket = TokenBucket::new(10.0, 1.0);
+        bucket.try_acquire().unwrap();
+        let now = bucket.last_used();
+
+        // Assert
+        assert!(!bucket.is_full_at(now));
+        assert!(bucket.is_full_at(now + Duration::from_millis(100)));
+    }
+}
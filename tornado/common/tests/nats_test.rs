@@ -19,7 +19,9 @@ use tornado_common::actors::message::EventMessage;
 use tornado_common::actors::nats_publisher::{
     NatsClientAuth, NatsClientConfig, NatsPublisherActor, NatsPublisherConfig,
 };
-use tornado_common::actors::nats_subscriber::{subscribe_to_nats, NatsSubscriberConfig};
+use tornado_common::actors::nats_subscriber::{
+    default_subscribe_retry_strategy, subscribe_to_nats, NatsSubscriberConfig,
+};
 use tornado_common_api::{Event, TracedEvent};
 use tornado_common_logger::elastic_apm::ApmTracingConfig;
 use tracing::Span;
@@ -143,6 +145,8 @@ async fn nats_subscriber_should_receive_from_nats() {
         NatsSubscriberConfig {
             client: NatsClientConfig { addresses: vec![nats_address.to_owned()], auth: None },
             subject: subject.to_owned(),
+            backpressure: Default::default(),
+            subscribe_retry_strategy: default_subscribe_retry_strategy(),
         },
         10000,
         move |event| {
@@ -197,6 +201,8 @@ async fn nats_publisher_should_publish_to_nats() {
         NatsPublisherConfig {
             client: NatsClientConfig { addresses: vec![nats_address.to_owned()], auth: None },
             subject: subject.to_owned(),
+            signature_secret: None,
+            serialization_format: Default::default(),
         },
         10,
     )
@@ -231,6 +237,8 @@ async fn should_publish_to_nats() {
         NatsSubscriberConfig {
             client: NatsClientConfig { addresses: vec![nats_address.to_owned()], auth: None },
             subject: subject.to_owned(),
+            backpressure: Default::default(),
+            subscribe_retry_strategy: default_subscribe_retry_strategy(),
         },
         10000,
         move |event| {
@@ -245,6 +253,8 @@ async fn should_publish_to_nats() {
         NatsPublisherConfig {
             client: NatsClientConfig { addresses: vec![nats_address.to_owned()], auth: None },
             subject: subject.to_owned(),
+            signature_secret: None,
+            serialization_format: Default::default(),
         },
         10,
     )
@@ -288,6 +298,8 @@ async fn should_publish_to_nats_with_tls() {
                 auth: auth.clone(),
             },
             subject: subject.to_owned(),
+            backpressure: Default::default(),
+            subscribe_retry_strategy: default_subscribe_retry_strategy(),
         },
         10000,
         move |event| {
@@ -302,6 +314,8 @@ async fn should_publish_to_nats_with_tls() {
         NatsPublisherConfig {
             client: NatsClientConfig { addresses: vec![nats_address.to_owned()], auth },
             subject: subject.to_owned(),
+            signature_secret: None,
+            serialization_format: Default::default(),
         },
         10,
     )
@@ -334,6 +348,8 @@ async fn publisher_should_reprocess_the_event_if_nats_is_not_available_at_startu
                 auth: None,
             },
             subject: subject.to_owned(),
+            signature_secret: None,
+            serialization_format: Default::default(),
         },
         10,
     )
@@ -380,6 +396,8 @@ async fn subscriber_should_try_reconnect_if_nats_is_not_available_at_startup() {
                     auth: None,
                 },
                 subject: subject_clone,
+                backpressure: Default::default(),
+                subscribe_retry_strategy: default_subscribe_retry_strategy(),
             },
             10000,
             move |event| {
@@ -415,6 +433,8 @@ async fn subscriber_should_try_reconnect_if_nats_is_not_available_at_startup() {
         NatsPublisherConfig {
             client: NatsClientConfig { addresses: vec![nats_address], auth: None },
             subject: subject.to_owned(),
+            signature_secret: None,
+            serialization_format: Default::default(),
         },
         10,
     )
@@ -457,6 +477,8 @@ async fn publisher_and_subscriber_should_reconnect_and_reprocess_events_if_nats_
         NatsPublisherConfig {
             client: NatsClientConfig { addresses: vec![nats_address.to_owned()], auth: None },
             subject: subject.to_owned(),
+            signature_secret: None,
+            serialization_format: Default::default(),
         },
         10,
     )
@@ -472,6 +494,8 @@ async fn publisher_and_subscriber_should_reconnect_and_reprocess_events_if_nats_
             NatsSubscriberConfig {
                 client: NatsClientConfig { addresses: vec![nats_address.to_owned()], auth: None },
                 subject: subject.to_owned(),
+                backpressure: Default::default(),
+                subscribe_retry_strategy: default_subscribe_retry_strategy(),
             },
             10000,
             move |event| {
@@ -563,6 +587,8 @@ async fn publisher_should_reschedule_all_events_after_a_disconnection() {
         NatsPublisherConfig {
             client: NatsClientConfig { addresses: vec![nats_address], auth: None },
             subject: subject.to_owned(),
+            signature_secret: None,
+            serialization_format: Default::default(),
         },
         10,
     )
@@ -1,7 +1,12 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
 use tornado_common::actors::json_event_reader::JsonEventReaderActor;
 use tornado_common::actors::message::EventMessage;
 use tornado_common::actors::tcp_client::TcpClientActor;
-use tornado_common::actors::tcp_server::listen_to_tcp;
+use tornado_common::actors::tcp_server::{listen_to_tcp, TcpServerOptions};
 use tornado_common_api::{Event, TracedEvent};
 use tracing::Span;
 
@@ -15,14 +20,15 @@ async fn should_perform_a_tcp_request() {
     let address = format!("{}:{}", BASE_ADDRESS, port);
 
     println!("Creating server at: {}", address);
-    let tcp_create = listen_to_tcp(address.clone(), 10000, move |msg| {
-        println!("Received a connection request");
-        let sender = sender.clone();
-        JsonEventReaderActor::start_new(msg, 10000, move |event| {
-            println!("JsonEventReaderActor -  received an event");
-            sender.send(event).unwrap();
+    let tcp_create =
+        listen_to_tcp(address.clone(), 10000, TcpServerOptions::default(), move |msg| {
+            println!("Received a connection request");
+            let sender = sender.clone();
+            JsonEventReaderActor::start_new(msg, 10000, move |event| {
+                println!("JsonEventReaderActor -  received an event");
+                sender.send(event).unwrap();
+            });
         });
-    });
 
     actix::spawn(async move {
         tcp_create.await.unwrap();
@@ -37,3 +43,219 @@ async fn should_perform_a_tcp_request() {
     let event = receiver.recv().await.unwrap();
     assert_eq!("an_event", event.event_type);
 }
+
+#[actix_rt::test]
+async fn should_discard_events_nested_beyond_the_max_depth() {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    let port = port_check::free_local_port().unwrap();
+    let address = format!("{}:{}", BASE_ADDRESS, port);
+
+    listen_to_tcp(address.clone(), 10000, TcpServerOptions::default(), move |msg| {
+        let sender = sender.clone();
+        JsonEventReaderActor::start_new_with_max_nesting_depth(msg, 10000, 10, move |event| {
+            sender.send(event).unwrap();
+        });
+    })
+    .await
+    .unwrap();
+
+    let nested_event = format!(
+        r#"{{"type": "nested_event", "created_ms": 0, "payload": {{"value": {}1{}}}}}"#,
+        "[".repeat(100_000),
+        "]".repeat(100_000)
+    );
+    let valid_event = r#"{"type": "valid_event", "created_ms": 0, "payload": {}}"#;
+
+    let mut stream = TcpStream::connect(&address).await.unwrap();
+    stream.write_all(format!("{}\n{}\n", nested_event, valid_event).as_bytes()).await.unwrap();
+
+    let event = receiver.recv().await.unwrap();
+    assert_eq!("valid_event", event.event_type);
+}
+
+#[actix_rt::test]
+async fn should_close_a_connection_idle_for_longer_than_the_idle_timeout() {
+    let port = port_check::free_local_port().unwrap();
+    let address = format!("{}:{}", BASE_ADDRESS, port);
+
+    listen_to_tcp(address.clone(), 10000, TcpServerOptions::default(), move |msg| {
+        JsonEventReaderActor::start_new_with_idle_timeout(
+            msg,
+            10000,
+            10,
+            Some(Duration::from_millis(100)),
+            move |_event| {},
+        );
+    })
+    .await
+    .unwrap();
+
+    let mut stream = TcpStream::connect(&address).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let mut buf = [0; 16];
+    let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+        .await
+        .expect("The connection should be closed by the server");
+    assert!(matches!(read, Ok(0) | Err(_)));
+}
+
+#[actix_rt::test]
+async fn should_split_the_received_lines_on_the_delimiter() {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    let port = port_check::free_local_port().unwrap();
+    let address = format!("{}:{}", BASE_ADDRESS, port);
+
+    listen_to_tcp(address.clone(), 10000, TcpServerOptions::default(), move |msg| {
+        let sender = sender.clone();
+        JsonEventReaderActor::start_new_with_delimiter(
+            msg,
+            10000,
+            10,
+            None,
+            Some(";".to_owned()),
+            move |event| {
+                sender.send(event).unwrap();
+            },
+        );
+    })
+    .await
+    .unwrap();
+
+    let frame = r#"{"type": "first", "created_ms": 0, "payload": {}};{"type": "second", "created_ms": 0, "payload": {}}; ;{"type": "third", "created_ms": 0, "payload": {}};"#;
+
+    let mut stream = TcpStream::connect(&address).await.unwrap();
+    stream.write_all(format!("{}\n", frame).as_bytes()).await.unwrap();
+
+    let mut event_types = vec![];
+    for _ in 0..3 {
+        event_types.push(receiver.recv().await.unwrap().event_type);
+    }
+    assert_eq!(vec!["first", "second", "third"], event_types);
+    assert!(tokio::time::timeout(Duration::from_millis(200), receiver.recv()).await.is_err());
+}
+
+#[actix_rt::test]
+async fn should_refuse_the_connections_beyond_the_max_connections() {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let refused_connections = Arc::new(AtomicUsize::new(0));
+
+    let port = port_check::free_local_port().unwrap();
+    let address = format!("{}:{}", BASE_ADDRESS, port);
+
+    let refused_connections_clone = refused_connections.clone();
+    listen_to_tcp(
+        address.clone(),
+        10000,
+        TcpServerOptions {
+            max_connections: Some(2),
+            on_connection_refused: Some(Box::new(move || {
+                refused_connections_clone.fetch_add(1, Ordering::SeqCst);
+            })),
+            ..Default::default()
+        },
+        move |msg| {
+            let sender = sender.clone();
+            JsonEventReaderActor::start_new(msg, 10000, move |event| {
+                sender.send(event).unwrap();
+            });
+        },
+    )
+    .await
+    .unwrap();
+
+    let mut first_stream = TcpStream::connect(&address).await.unwrap();
+    first_stream
+        .write_all(b"{\"type\": \"first\", \"created_ms\": 0, \"payload\": {}}\n")
+        .await
+        .unwrap();
+    assert_eq!("first", receiver.recv().await.unwrap().event_type);
+
+    let mut second_stream = TcpStream::connect(&address).await.unwrap();
+    second_stream
+        .write_all(b"{\"type\": \"second\", \"created_ms\": 0, \"payload\": {}}\n")
+        .await
+        .unwrap();
+    assert_eq!("second", receiver.recv().await.unwrap().event_type);
+
+    let mut refused_stream = TcpStream::connect(&address).await.unwrap();
+
+    let mut buf = [0; 16];
+    let read = tokio::time::timeout(Duration::from_secs(5), refused_stream.read(&mut buf))
+        .await
+        .expect("The connection should be closed by the server");
+    assert!(matches!(read, Ok(0) | Err(_)));
+    assert_eq!(1, refused_connections.load(Ordering::SeqCst));
+
+    first_stream
+        .write_all(b"{\"type\": \"still_open\", \"created_ms\": 0, \"payload\": {}}\n")
+        .await
+        .unwrap();
+    assert_eq!("still_open", receiver.recv().await.unwrap().event_type);
+}
+
+#[actix_rt::test]
+async fn should_refuse_the_connections_from_the_peers_not_in_the_allowed_networks() {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let not_allowed_peers = Arc::new(Mutex::new(vec![]));
+
+    let allowed_port = port_check::free_local_port().unwrap();
+    let allowed_address = format!("{}:{}", BASE_ADDRESS, allowed_port);
+    listen_to_tcp(
+        allowed_address.clone(),
+        10000,
+        TcpServerOptions {
+            allowed_networks: Some(vec!["127.0.0.0/8".parse().unwrap()]),
+            ..Default::default()
+        },
+        move |msg| {
+            let sender = sender.clone();
+            JsonEventReaderActor::start_new(msg, 10000, move |event| {
+                sender.send(event).unwrap();
+            });
+        },
+    )
+    .await
+    .unwrap();
+
+    // The local peer is not in the allowed networks of this server
+    let not_allowed_port = port_check::free_local_port().unwrap();
+    let not_allowed_address = format!("{}:{}", BASE_ADDRESS, not_allowed_port);
+    let not_allowed_peers_clone = not_allowed_peers.clone();
+    listen_to_tcp(
+        not_allowed_address.clone(),
+        10000,
+        TcpServerOptions {
+            allowed_networks: Some(vec!["10.0.0.0/8".parse().unwrap(), "::1/128".parse().unwrap()]),
+            on_connection_not_allowed: Some(Box::new(move |peer_address| {
+                not_allowed_peers_clone.lock().unwrap().push(peer_address);
+            })),
+            ..Default::default()
+        },
+        move |_msg| {
+            panic!("The connection should be refused");
+        },
+    )
+    .await
+    .unwrap();
+
+    let mut allowed_stream = TcpStream::connect(&allowed_address).await.unwrap();
+    allowed_stream
+        .write_all(b"{\"type\": \"allowed\", \"created_ms\": 0, \"payload\": {}}\n")
+        .await
+        .unwrap();
+    assert_eq!("allowed", receiver.recv().await.unwrap().event_type);
+
+    let mut not_allowed_stream = TcpStream::connect(&not_allowed_address).await.unwrap();
+    let mut buf = [0; 16];
+    let read = tokio::time::timeout(Duration::from_secs(5), not_allowed_stream.read(&mut buf))
+        .await
+        .expect("The connection should be closed by the server");
+    assert!(matches!(read, Ok(0) | Err(_)));
+
+    let not_allowed_peers = not_allowed_peers.lock().unwrap();
+    assert_eq!(1, not_allowed_peers.len());
+    assert_eq!(BASE_ADDRESS, not_allowed_peers[0].ip().to_string());
+}
@@ -2,6 +2,7 @@ use actix::dev::ToEnvelope;
 use actix::prelude::*;
 use log::*;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncReadExt};
 use tornado_collector_common::Collector;
 use tornado_collector_email::EmailEventCollector;
@@ -14,6 +15,7 @@ where
 {
     client_addr: Addr<A>,
     email_collector: Arc<EmailEventCollector>,
+    idle_timeout: Option<Duration>,
 }
 
 impl<A: Actor + actix::Handler<EventMessage>> EmailReaderActor<A>
@@ -21,9 +23,40 @@ where
     <A as Actor>::Context: ToEnvelope<A, EventMessage>,
 {
     pub fn start_new(client_addr: Addr<A>, message_mailbox_capacity: usize) -> Addr<Self> {
+        Self::start_new_with_idle_timeout(client_addr, message_mailbox_capacity, None)
+    }
+
+    /// Starts an EmailReaderActor that closes the connections that do not deliver any data
+    /// for longer than the `idle_timeout`, discarding the partially received email.
+    /// If `idle_timeout` is None, the connections are never closed for inactivity.
+    pub fn start_new_with_idle_timeout(
+        client_addr: Addr<A>,
+        message_mailbox_capacity: usize,
+        idle_timeout: Option<Duration>,
+    ) -> Addr<Self> {
+        Self::start_new_with_collector(
+            client_addr,
+            message_mailbox_capacity,
+            idle_timeout,
+            EmailEventCollector::new(),
+        )
+    }
+
+    /// Starts an EmailReaderActor like `start_new_with_idle_timeout` that produces the events
+    /// with the given EmailEventCollector.
+    pub fn start_new_with_collector(
+        client_addr: Addr<A>,
+        message_mailbox_capacity: usize,
+        idle_timeout: Option<Duration>,
+        email_collector: EmailEventCollector,
+    ) -> Addr<Self> {
         EmailReaderActor::create(move |ctx| {
             ctx.set_mailbox_capacity(message_mailbox_capacity);
-            EmailReaderActor { email_collector: Arc::new(EmailEventCollector::new()), client_addr }
+            EmailReaderActor {
+                email_collector: Arc::new(email_collector),
+                client_addr,
+                idle_timeout,
+            }
         })
     }
 }
@@ -49,10 +82,23 @@ where
     fn handle(&mut self, mut msg: AsyncReadMessage<R>, _ctx: &mut Context<Self>) -> Self::Result {
         let tcp = self.client_addr.clone();
         let collector = self.email_collector.clone();
+        let idle_timeout = self.idle_timeout;
         let fut = async move {
             let _span = tracing::debug_span!("Collect email Event").entered();
             let mut buf = Vec::new();
-            msg.stream.read_to_end(&mut buf).await.unwrap();
+            let read_result = match idle_timeout {
+                Some(idle_timeout) => {
+                    read_to_end_with_idle_timeout(&mut msg.stream, &mut buf, idle_timeout).await
+                }
+                None => msg.stream.read_to_end(&mut buf).await.map(|_| ()),
+            };
+            if let Err(err) = read_result {
+                error!(
+                    "EmailReaderActor - Cannot read the email. The connection is closed. Err: {:?}",
+                    err
+                );
+                return;
+            }
 
             if log_enabled!(Level::Debug) {
                 let buf_to_string = String::from_utf8_lossy(&buf);
@@ -69,3 +115,24 @@ where
         actix::spawn(fut);
     }
 }
+
+/// Reads all the bytes until EOF, failing if no data is received for longer than the idle timeout.
+async fn read_to_end_with_idle_timeout<R: AsyncRead + Unpin>(
+    stream: &mut R,
+    buf: &mut Vec<u8>,
+    idle_timeout: Duration,
+) -> std::io::Result<()> {
+    loop {
+        match tokio::time::timeout(idle_timeout, stream.read_buf(buf)).await {
+            Ok(Ok(0)) => return Ok(()),
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => return Err(err),
+            Err(_) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!("No data received for {} ms", idle_timeout.as_millis()),
+                ))
+            }
+        }
+    }
+}
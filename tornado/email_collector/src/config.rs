@@ -27,6 +27,13 @@ pub struct CollectorConfig {
 pub struct EmailCollectorConfig {
     pub message_queue_size: usize,
     pub uds_path: String,
+    /// The seconds after which a connection that has not delivered any data is closed
+    pub uds_idle_timeout_secs: Option<u64>,
+    /// The IANA timezone of the email dates without timezone information. Defaults to UTC
+    pub default_timezone: Option<String>,
+    /// The max number of MIME parts of an email that are processed.
+    /// Defaults to `tornado_collector_email::DEFAULT_MAX_MIME_PARTS`
+    pub max_mime_parts: Option<usize>,
 
     pub tornado_connection_channel: Option<TornadoConnectionChannel>,
 
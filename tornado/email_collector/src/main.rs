@@ -7,6 +7,9 @@ use crate::actor::EmailReaderActor;
 use actix::dev::ToEnvelope;
 use actix::{Actor, Addr, System};
 use log::*;
+use std::time::Duration;
+use tornado_collector_common::timestamp::parse_timezone;
+use tornado_collector_email::{EmailEventCollector, DEFAULT_MAX_MIME_PARTS};
 use tornado_common::actors::message::EventMessage;
 use tornado_common::actors::nats_publisher::NatsPublisherActor;
 use tornado_common::actors::tcp_client::TcpClientActor;
@@ -38,6 +41,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     info!("Email collector started");
 
+    let default_timezone =
+        parse_timezone(collector_config.email_collector.default_timezone.as_deref())?;
+    let email_collector = EmailEventCollector::new_with_default_timezone_and_max_mime_parts(
+        default_timezone,
+        collector_config.email_collector.max_mime_parts.unwrap_or(DEFAULT_MAX_MIME_PARTS),
+    );
+
     //
     // WARN:
     // This 'if' block contains some duplicated code to allow temporary compatibility with the config file format of the previous release.
@@ -60,6 +70,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             collector_config.email_collector.uds_path,
             actor_address,
             collector_config.email_collector.message_queue_size,
+            collector_config.email_collector.uds_idle_timeout_secs,
+            email_collector,
         );
     } else if let Some(connection_channel) =
         collector_config.email_collector.tornado_connection_channel
@@ -76,21 +88,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                     collector_config.email_collector.uds_path,
                     actor_address,
                     collector_config.email_collector.message_queue_size,
+                    collector_config.email_collector.uds_idle_timeout_secs,
+                    email_collector,
                 );
             }
-            TornadoConnectionChannel::Tcp { tcp_socket_ip, tcp_socket_port } => {
+            TornadoConnectionChannel::Tcp { tcp_socket_ip, tcp_socket_port, signature_secret } => {
                 info!("Connect to Tornado through TCP socket");
                 // Start TcpWriter
                 let tornado_tcp_address = format!("{}:{}", tcp_socket_ip, tcp_socket_port,);
 
-                let actor_address = TcpClientActor::start_new(
+                let actor_address = TcpClientActor::start_new_with_signer(
                     tornado_tcp_address,
                     collector_config.email_collector.message_queue_size,
+                    signature_secret.as_deref().map(EventSigner::new),
                 );
                 start(
                     collector_config.email_collector.uds_path,
                     actor_address,
                     collector_config.email_collector.message_queue_size,
+                    collector_config.email_collector.uds_idle_timeout_secs,
+                    email_collector,
                 );
             }
         };
@@ -112,11 +129,18 @@ fn start<A: Actor + actix::Handler<EventMessage>>(
     uds_path: String,
     actor_address: Addr<A>,
     message_mailbox_capacity: usize,
+    uds_idle_timeout_secs: Option<u64>,
+    email_collector: EmailEventCollector,
 ) where
     <A as Actor>::Context: ToEnvelope<A, tornado_common::actors::message::EventMessage>,
 {
     // Start Email collector
-    let email_addr = EmailReaderActor::start_new(actor_address, message_mailbox_capacity);
+    let email_addr = EmailReaderActor::start_new_with_collector(
+        actor_address,
+        message_mailbox_capacity,
+        uds_idle_timeout_secs.map(Duration::from_secs),
+        email_collector,
+    );
 
     // Open UDS socket
     listen_to_uds_socket(uds_path.clone(), Some(0o770), message_mailbox_capacity, move |msg| {
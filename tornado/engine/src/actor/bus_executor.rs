@@ -0,0 +1,256 @@
+use actix::prelude::*;
+use log::*;
+use std::rc::Rc;
+use tornado_common::actors::message::ActionMessage;
+use tornado_executor_common::{ExecutorError, StatelessExecutor};
+use tracing::Instrument;
+
+/// The message that builds the executor in the thread of the actor.
+/// It is sent after the creation of the event bus, on which the executor publishes
+///   the actions it produces.
+#[derive(Message)]
+#[rtype(result = "Result<(), ExecutorError>")]
+pub struct BusExecutorActorInitMessage<F>
+where
+    F: Send + Sync,
+{
+    pub init: F,
+}
+
+/// An actor for the executors that publish new actions on the event bus, e.g. the merge
+///   and the scheduled executors.
+/// As they hold a reference to the event bus, these executors are built only when
+///   the actor receives the init message.
+pub struct BusExecutorActor<E: StatelessExecutor + 'static> {
+    executor: Option<Rc<E>>,
+}
+
+impl<E: StatelessExecutor + 'static> BusExecutorActor<E> {
+    pub fn start_new(message_mailbox_capacity: usize) -> Addr<BusExecutorActor<E>> {
+        Self::create(move |ctx| {
+            ctx.set_mailbox_capacity(message_mailbox_capacity);
+            Self { executor: None }
+        })
+    }
+}
+
+impl<E: StatelessExecutor + 'static> Actor for BusExecutorActor<E> {
+    type Context = Context<Self>;
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        debug!("BusExecutorActor started.");
+    }
+}
+
+impl<E: StatelessExecutor + 'static> Handler<ActionMessage> for BusExecutorActor<E> {
+    type Result = Result<(), ExecutorError>;
+
+    fn handle(&mut self, msg: ActionMessage, _: &mut Context<Self>) -> Self::Result {
+        let parent_span = msg.0.span.clone();
+        let _parent_guard = msg.0.span.entered();
+        let action = msg.0.action;
+        let in_flight_action = msg.1;
+        trace!("BusExecutorActor - received new action [{:?}]", &action);
+
+        if let Some(executor) = &self.executor {
+            let executor = executor.clone();
+            actix::spawn(
+                async move {
+                    // The action completes when the executor returns
+                    let _in_flight_action = in_flight_action;
+                    if let Err(e) = executor.execute(action).await {
+                        error!(
+                            "BusExecutorActor - {} - Failed to execute action: {:?}",
+                            &executor, e
+                        );
+                    }
+                }
+                .instrument(parent_span),
+            );
+            Ok(())
+        } else {
+            let message =
+                "BusExecutorActor received a message when it was not yet initialized!".to_owned();
+            error!("{}", message);
+            Err(ExecutorError::ConfigurationError { message })
+        }
+    }
+}
+
+impl<E, F> Handler<BusExecutorActorInitMessage<F>> for BusExecutorActor<E>
+where
+    E: StatelessExecutor + 'static,
+    F: Fn() -> Result<E, ExecutorError> + Send + Sync,
+{
+    type Result = Result<(), ExecutorError>;
+
+    fn handle(
+        &mut self,
+        msg: BusExecutorActorInitMessage<F>,
+        _: &mut Context<Self>,
+    ) -> Self::Result {
+        trace!("BusExecutorActor - received init message");
+        self.executor = Some((msg.init)()?.into());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::actor::dispatcher::{ActixEventBus, ExecutorRegistry};
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+    use tornado_common_api::{Action, TracedAction, Value};
+    use tornado_executor_merge::config::MergeConfig;
+    use tornado_executor_merge::MergeExecutor;
+    use tornado_executor_scheduled::config::ScheduledConfig;
+    use tornado_executor_scheduled::ScheduledExecutor;
+
+    struct FakeExecutor {
+        sender: UnboundedSender<ActionMessage>,
+    }
+
+    impl Actor for FakeExecutor {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<ActionMessage> for FakeExecutor {
+        type Result = Result<(), ExecutorError>;
+        fn handle(&mut self, msg: ActionMessage, _: &mut Context<Self>) -> Self::Result {
+            self.sender.send(msg).unwrap();
+            Ok(())
+        }
+    }
+
+    fn merge_config() -> MergeConfig {
+        MergeConfig {
+            key: "${payload.hostname}".into(),
+            action_id: "logger".to_owned(),
+            merged_payloads_key: "events".to_owned(),
+            window_ms: 60_000,
+            max_count: 2,
+        }
+    }
+
+    fn new_action(hostname: &str, state: &str) -> ActionMessage {
+        let mut action = Action::new("merge");
+        action.payload.insert("hostname".to_owned(), Value::String(hostname.to_owned()));
+        action.payload.insert("state".to_owned(), Value::String(state.to_owned()));
+        ActionMessage(TracedAction::from(action), None)
+    }
+
+    #[actix::test]
+    async fn should_dispatch_the_merged_action_to_its_executor() {
+        // Arrange
+        // The executors are wired as in the daemon: the merged action is emitted on the
+        //   event bus and sent to the executor registered for its id.
+        let (sender, mut receiver) = unbounded_channel();
+        let merge_executor_addr = BusExecutorActor::<MergeExecutor>::start_new(10);
+        let mut executors = ExecutorRegistry::default();
+        executors.register("logger", FakeExecutor { sender }.start().recipient());
+        executors.register("merge", merge_executor_addr.clone().recipient());
+
+        let event_bus = {
+            let executors = executors.clone();
+            Arc::new(ActixEventBus {
+                callback: move |message| executors.try_send(message).unwrap(),
+                max_action_size_bytes: None,
+            })
+        };
+        merge_executor_addr
+            .send(BusExecutorActorInitMessage {
+                init: move || MergeExecutor::new(event_bus.clone(), merge_config()),
+            })
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Act
+        executors.try_send(new_action("host1", "DOWN")).unwrap();
+        executors.try_send(new_action("host1", "UP")).unwrap();
+
+        // Assert
+        let merged =
+            tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap();
+        assert_eq!("logger", merged.0.action.id);
+        assert_eq!(
+            &json!([
+                { "hostname": "host1", "state": "DOWN" },
+                { "hostname": "host1", "state": "UP" },
+            ]),
+            merged.0.action.payload.get("events").unwrap()
+        );
+    }
+
+    #[actix::test]
+    async fn should_fail_to_init_with_an_invalid_configuration() {
+        // Arrange
+        let merge_executor_addr = BusExecutorActor::<MergeExecutor>::start_new(10);
+        let event_bus = Arc::new(ActixEventBus {
+            callback: |_: ActionMessage| {},
+            max_action_size_bytes: None,
+        });
+        let mut config = merge_config();
+        config.key = "${}".to_owned();
+
+        // Act
+        let result = merge_executor_addr
+            .send(BusExecutorActorInitMessage {
+                init: move || MergeExecutor::new(event_bus.clone(), config.clone()),
+            })
+            .await
+            .unwrap();
+
+        // Assert
+        assert!(matches!(result, Err(ExecutorError::ConfigurationError { .. })));
+    }
+
+    #[actix::test]
+    async fn should_dispatch_the_scheduled_action_to_its_executor() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let store_path = tempdir.path().join("scheduled_actions.json").to_str().unwrap().to_owned();
+        let (sender, mut receiver) = unbounded_channel();
+        let scheduled_executor_addr = BusExecutorActor::<ScheduledExecutor>::start_new(10);
+        let mut executors = ExecutorRegistry::default();
+        executors.register("logger", FakeExecutor { sender }.start().recipient());
+        executors.register("scheduled", scheduled_executor_addr.clone().recipient());
+
+        let event_bus = {
+            let executors = executors.clone();
+            Arc::new(ActixEventBus {
+                callback: move |message| executors.try_send(message).unwrap(),
+                max_action_size_bytes: None,
+            })
+        };
+        scheduled_executor_addr
+            .send(BusExecutorActorInitMessage {
+                init: move || {
+                    ScheduledExecutor::new(
+                        event_bus.clone(),
+                        ScheduledConfig { store_path: store_path.clone() },
+                    )
+                },
+            })
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mut action = Action::new("scheduled");
+        action.payload.insert("at".to_owned(), json!(0));
+        action
+            .payload
+            .insert("action".to_owned(), json!({ "id": "logger", "payload": { "state": "DOWN" } }));
+
+        // Act
+        executors.try_send(ActionMessage(TracedAction::from(action), None)).unwrap();
+
+        // Assert
+        let scheduled =
+            tokio::time::timeout(Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap();
+        assert_eq!("logger", scheduled.0.action.id);
+        assert_eq!(&json!("DOWN"), scheduled.0.action.payload.get("state").unwrap());
+    }
+}
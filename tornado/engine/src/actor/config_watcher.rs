@@ -0,0 +1,222 @@
+use crate::actor::matcher::ReconfigureMessage;
+use actix::prelude::*;
+use log::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// The interval at which the watched paths are checked for changes
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct ConfigChangedMessage {}
+
+/// An actor that watches the configuration files on the filesystem and reloads the
+/// configuration when they change.
+/// The changes are debounced: the reload is triggered only when no further change
+/// happens for the debounce interval, so an editor that writes a file several times
+/// (e.g. to a temporary file and then renaming it) causes a single reload.
+/// The new configuration is validated by the matcher before replacing the current one;
+/// if it is not valid, the current configuration stays in use.
+pub struct ConfigWatcherActor {
+    paths: Vec<PathBuf>,
+    poll_interval: Duration,
+    debounce: Duration,
+    reconfigure: Recipient<ReconfigureMessage>,
+    fingerprint: u64,
+    pending_reload: Option<SpawnHandle>,
+}
+
+impl ConfigWatcherActor {
+    pub fn start_new(
+        paths: Vec<PathBuf>,
+        debounce: Duration,
+        reconfigure: Recipient<ReconfigureMessage>,
+    ) -> Addr<Self> {
+        Self::start_new_with_poll_interval(paths, DEFAULT_POLL_INTERVAL, debounce, reconfigure)
+    }
+
+    pub fn start_new_with_poll_interval(
+        paths: Vec<PathBuf>,
+        poll_interval: Duration,
+        debounce: Duration,
+        reconfigure: Recipient<ReconfigureMessage>,
+    ) -> Addr<Self> {
+        let fingerprint = fingerprint(&paths);
+        Self::create(move |_ctx| Self {
+            paths,
+            poll_interval,
+            debounce,
+            reconfigure,
+            fingerprint,
+            pending_reload: None,
+        })
+    }
+
+    /// Triggers a reload after the debounce interval, postponing the one already scheduled
+    fn schedule_reload(&mut self, ctx: &mut Context<Self>) {
+        if let Some(pending_reload) = self.pending_reload.take() {
+            ctx.cancel_future(pending_reload);
+        }
+        self.pending_reload = Some(ctx.run_later(self.debounce, |this, ctx| {
+            this.pending_reload = None;
+            this.reload(ctx);
+        }));
+    }
+
+    fn reload(&self, ctx: &mut Context<Self>) {
+        info!("ConfigWatcherActor - The configuration files changed. Reloading the configuration.");
+        self.reconfigure
+            .send(ReconfigureMessage {})
+            .into_actor(self)
+            .map(|result, _this, _ctx| match result {
+                Ok(Ok(_)) => info!("ConfigWatcherActor - Configuration reloaded."),
+                Ok(Err(err)) => warn!(
+                    "ConfigWatcherActor - The new configuration is not valid and it is ignored. Err: {:?}",
+                    err
+                ),
+                Err(err) => error!(
+                    "ConfigWatcherActor - Cannot send the reconfigure message. Err: {:?}",
+                    err
+                ),
+            })
+            .wait(ctx);
+    }
+}
+
+impl Actor for ConfigWatcherActor {
+    type Context = Context<Self>;
+    fn started(&mut self, ctx: &mut Self::Context) {
+        debug!("ConfigWatcherActor started. Watching paths: {:?}", self.paths);
+        ctx.run_interval(self.poll_interval, |this, ctx| {
+            let fingerprint = fingerprint(&this.paths);
+            if fingerprint != this.fingerprint {
+                this.fingerprint = fingerprint;
+                ctx.notify(ConfigChangedMessage {});
+            }
+        });
+    }
+}
+
+impl Handler<ConfigChangedMessage> for ConfigWatcherActor {
+    type Result = ();
+
+    fn handle(&mut self, _msg: ConfigChangedMessage, ctx: &mut Context<Self>) -> Self::Result {
+        trace!("ConfigWatcherActor - received ConfigChangedMessage");
+        self.schedule_reload(ctx);
+    }
+}
+
+/// Returns a hash of the names, sizes and modification times of all the files in the paths
+fn fingerprint(paths: &[PathBuf]) -> u64 {
+    let mut entries = vec![];
+    for path in paths {
+        collect_entries(path, &mut entries);
+    }
+    entries.sort();
+    let mut hasher = DefaultHasher::new();
+    entries.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn collect_entries(path: &Path, entries: &mut Vec<(PathBuf, u64, Option<SystemTime>)>) {
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            debug!("ConfigWatcherActor - Cannot read the metadata of [{:?}]. Err: {:?}", path, err);
+            return;
+        }
+    };
+    if metadata.is_dir() {
+        if let Ok(dir_entries) = std::fs::read_dir(path) {
+            for dir_entry in dir_entries.flatten() {
+                collect_entries(&dir_entry.path(), entries);
+            }
+        }
+    } else {
+        entries.push((path.to_path_buf(), metadata.len(), metadata.modified().ok()));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+    use tornado_engine_matcher::config::MatcherConfig;
+    use tornado_engine_matcher::error::MatcherError;
+
+    struct FakeMatcher {
+        sender: UnboundedSender<()>,
+    }
+
+    impl Actor for FakeMatcher {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<ReconfigureMessage> for FakeMatcher {
+        type Result = Result<Arc<MatcherConfig>, MatcherError>;
+        fn handle(&mut self, _msg: ReconfigureMessage, _: &mut Context<Self>) -> Self::Result {
+            self.sender.send(()).unwrap();
+            Ok(Arc::new(MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![] }))
+        }
+    }
+
+    fn fake_matcher() -> (Recipient<ReconfigureMessage>, UnboundedReceiver<()>) {
+        let (sender, receiver) = unbounded_channel();
+        (FakeMatcher { sender }.start().recipient(), receiver)
+    }
+
+    #[actix::test]
+    async fn should_reload_once_for_a_burst_of_changes() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (matcher, mut reloads) = fake_matcher();
+        let watcher = ConfigWatcherActor::start_new(
+            vec![tempdir.path().to_path_buf()],
+            Duration::from_millis(200),
+            matcher,
+        );
+
+        // Act
+        for _ in 0..10 {
+            watcher.send(ConfigChangedMessage {}).await.unwrap();
+            actix::clock::sleep(Duration::from_millis(20)).await;
+        }
+        actix::clock::sleep(Duration::from_millis(500)).await;
+
+        // Assert
+        assert!(reloads.try_recv().is_ok());
+        assert!(reloads.try_recv().is_err());
+    }
+
+    #[actix::test]
+    async fn should_reload_when_a_file_changes() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let rules_dir = tempdir.path().join("rules.d");
+        std::fs::create_dir(&rules_dir).unwrap();
+        std::fs::write(rules_dir.join("rule.json"), "{}").unwrap();
+
+        let (matcher, mut reloads) = fake_matcher();
+        let _watcher = ConfigWatcherActor::start_new_with_poll_interval(
+            vec![tempdir.path().to_path_buf()],
+            Duration::from_millis(50),
+            Duration::from_millis(100),
+            matcher,
+        );
+        actix::clock::sleep(Duration::from_millis(200)).await;
+        assert!(reloads.try_recv().is_err());
+
+        // Act
+        std::fs::write(rules_dir.join("rule.json"), r#"{ "name": "rule" }"#).unwrap();
+        std::fs::write(rules_dir.join("another_rule.json"), "{}").unwrap();
+
+        // Assert
+        assert!(actix::clock::timeout(Duration::from_secs(5), reloads.recv()).await.is_ok());
+        actix::clock::sleep(Duration::from_millis(300)).await;
+        assert!(reloads.try_recv().is_err());
+    }
+}
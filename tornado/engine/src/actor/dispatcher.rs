@@ -1,17 +1,84 @@
 use actix::prelude::*;
 use log::*;
-use tornado_common::actors::message::ActionMessage;
+use std::collections::HashMap;
+use tornado_common::actors::message::{ActionMessage, FlushMessage};
 use tornado_engine_matcher::{dispatcher, error, model};
-use tornado_network_common::EventBus;
+use tornado_network_common::{EventBus, EventBusError};
 use tracing::Span;
 
 pub struct ActixEventBus<F: Fn(ActionMessage)> {
     pub callback: F,
+    /// The max size in bytes of the serialized actions. Bigger actions are rejected.
+    /// If None, the size is unlimited.
+    pub max_action_size_bytes: Option<usize>,
 }
 
 impl<F: Fn(ActionMessage)> EventBus for ActixEventBus<F> {
-    fn publish_action(&self, message: ActionMessage) {
-        (self.callback)(message)
+    fn publish_action(&self, message: ActionMessage) -> Result<(), EventBusError> {
+        if let Some(max_size) = self.max_action_size_bytes {
+            let size = serde_json::to_vec(message.0.action.as_ref())
+                .map(|serialized| serialized.len())
+                .unwrap_or_default();
+            if size > max_size {
+                return Err(EventBusError::ActionTooLargeError {
+                    action_id: message.0.action.id.clone(),
+                    size,
+                    max_size,
+                });
+            }
+        }
+        (self.callback)(message);
+        Ok(())
+    }
+}
+
+/// The executors of the actions, registered by the id of the actions they execute.
+#[derive(Clone, Default)]
+pub struct ExecutorRegistry {
+    executors: HashMap<String, Recipient<ActionMessage>>,
+    fallback_executor: Option<Recipient<ActionMessage>>,
+}
+
+impl ExecutorRegistry {
+    pub fn register<S: Into<String>>(&mut self, action_id: S, executor: Recipient<ActionMessage>) {
+        self.executors.insert(action_id.into(), executor);
+    }
+
+    /// Sets the registered executor of the given action id as the one that receives the actions
+    ///   with no registered executor.
+    pub fn set_fallback_executor(&mut self, action_id: &str) -> Result<(), String> {
+        match self.executors.get(action_id) {
+            Some(executor) => {
+                self.fallback_executor = Some(executor.clone());
+                Ok(())
+            }
+            None => Err(format!("Unknown fallback executor [{}]", action_id)),
+        }
+    }
+
+    /// Returns the executor of the action with the given id.
+    /// If there is no registered executor, the fallback executor, if any, is returned.
+    pub fn executor(&self, action_id: &str) -> Option<&Recipient<ActionMessage>> {
+        self.executors.get(action_id).or(self.fallback_executor.as_ref())
+    }
+
+    /// Sends the message to the executor of its action, without waiting for a free slot
+    ///   in the executor mailbox.
+    pub fn try_send(&self, message: ActionMessage) -> Result<(), String> {
+        match self.executor(&message.0.action.id) {
+            Some(executor) => {
+                let action_id = message.0.action.id.clone();
+                executor.try_send(message).map_err(|err| {
+                    format!(
+                        "Error sending message with action id [{}] to its executor. Err: {:?}",
+                        action_id, err
+                    )
+                })
+            }
+            None => {
+                Err(format!("There are not executors for action id [{}]", &message.0.action.id))
+            }
+        }
     }
 }
 
@@ -58,3 +125,150 @@ impl Handler<ProcessedEventMessage> for DispatcherActor {
         self.dispatcher.dispatch_actions(msg.event.result)
     }
 }
+
+impl Handler<FlushMessage> for DispatcherActor {
+    type Result = ();
+
+    fn handle(&mut self, _msg: FlushMessage, _: &mut Context<Self>) -> Self::Result {
+        trace!("DispatcherActor - received new FlushMessage");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+    use tornado_common_api::{Action, TracedAction, Value};
+    use tornado_executor_common::ExecutorError;
+
+    struct FakeExecutor {
+        sender: UnboundedSender<ActionMessage>,
+    }
+
+    impl Actor for FakeExecutor {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<ActionMessage> for FakeExecutor {
+        type Result = Result<(), ExecutorError>;
+        fn handle(&mut self, msg: ActionMessage, _: &mut Context<Self>) -> Self::Result {
+            self.sender.send(msg).unwrap();
+            Ok(())
+        }
+    }
+
+    #[actix::test]
+    async fn should_send_the_action_to_its_registered_executor() {
+        // Arrange
+        let (sender, mut receiver) = unbounded_channel();
+        let (fallback_sender, mut fallback_receiver) = unbounded_channel();
+        let mut registry = ExecutorRegistry::default();
+        registry.register("logger", FakeExecutor { sender }.start().recipient());
+        registry.register("archive", FakeExecutor { sender: fallback_sender }.start().recipient());
+        registry.set_fallback_executor("archive").unwrap();
+
+        // Act
+        let result =
+            registry.try_send(ActionMessage(TracedAction::from(Action::new("logger")), None));
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!("logger", receiver.recv().await.unwrap().0.action.id);
+        assert!(fallback_receiver.try_recv().is_err());
+    }
+
+    #[actix::test]
+    async fn should_send_unrouted_action_to_the_fallback_executor() {
+        // Arrange
+        let (sender, mut receiver) = unbounded_channel();
+        let mut registry = ExecutorRegistry::default();
+        registry.register("archive", FakeExecutor { sender }.start().recipient());
+        registry.set_fallback_executor("archive").unwrap();
+        let message = ActionMessage(TracedAction::from(Action::new("unknown_action_id")), None);
+
+        // Act
+        let result = registry.try_send(message);
+
+        // Assert
+        assert!(result.is_ok());
+        let received = receiver.recv().await.unwrap();
+        assert_eq!("unknown_action_id", received.0.action.id);
+    }
+
+    #[test]
+    fn should_not_set_an_unknown_fallback_executor() {
+        // Arrange
+        let mut registry = ExecutorRegistry::default();
+
+        // Act
+        let result = registry.set_fallback_executor("unknown");
+
+        // Assert
+        assert_eq!(Err("Unknown fallback executor [unknown]".to_owned()), result);
+    }
+
+    #[test]
+    fn should_return_error_if_no_fallback_executor_is_configured() {
+        // Arrange
+        let message = ActionMessage(TracedAction::from(Action::new("unknown_action_id")), None);
+
+        // Act
+        let result = ExecutorRegistry::default().try_send(message);
+
+        // Assert
+        assert_eq!(
+            Err("There are not executors for action id [unknown_action_id]".to_owned()),
+            result
+        );
+    }
+
+    #[test]
+    fn should_reject_the_actions_bigger_than_the_max_size() {
+        // Arrange
+        let published = RefCell::new(vec![]);
+        let bus = ActixEventBus {
+            callback: |message: ActionMessage| {
+                published.borrow_mut().push(message.0.action.id.clone())
+            },
+            max_action_size_bytes: Some(200),
+        };
+
+        let small_action = Action::new("small");
+        let mut big_action = Action::new("big");
+        big_action.payload.insert("data".to_owned(), Value::String("x".repeat(200)));
+
+        // Act
+        let small_result =
+            bus.publish_action(ActionMessage(TracedAction::from(small_action), None));
+        let big_result = bus.publish_action(ActionMessage(TracedAction::from(big_action), None));
+
+        // Assert
+        assert!(small_result.is_ok());
+        assert!(matches!(
+            big_result,
+            Err(EventBusError::ActionTooLargeError { action_id, max_size: 200, .. }) if action_id == "big"
+        ));
+        assert_eq!(vec!["small".to_owned()], *published.borrow());
+    }
+
+    #[test]
+    fn should_not_limit_the_action_size_by_default() {
+        // Arrange
+        let published = RefCell::new(vec![]);
+        let bus = ActixEventBus {
+            callback: |message: ActionMessage| {
+                published.borrow_mut().push(message.0.action.id.clone())
+            },
+            max_action_size_bytes: None,
+        };
+        let mut big_action = Action::new("big");
+        big_action.payload.insert("data".to_owned(), Value::String("x".repeat(100_000)));
+
+        // Act
+        let result = bus.publish_action(ActionMessage(TracedAction::from(big_action), None));
+
+        // Assert
+        assert!(result.is_ok());
+        assert_eq!(vec!["big".to_owned()], *published.borrow());
+    }
+}
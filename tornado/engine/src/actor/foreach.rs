@@ -42,12 +42,15 @@ impl Handler<ActionMessage> for ForEachExecutorActor {
         let parent_span = msg.0.span.clone();
         let _parent_guard = msg.0.span.entered();
         let action = msg.0.action;
+        let in_flight_action = msg.1;
         trace!("ForEachExecutorActor - received new action [{:?}]", &action);
 
         if let Some(executor) = &self.executor {
             let executor = executor.clone();
             actix::spawn(
                 async move {
+                    // The action completes when the actions it produces have been enqueued
+                    let _in_flight_action = in_flight_action;
                     match executor.execute(action).await {
                         Ok(_) => {
                             debug!(
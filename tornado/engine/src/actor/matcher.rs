@@ -1,18 +1,30 @@
 use crate::actor::dispatcher::ProcessedEventMessage;
-use crate::monitoring::metrics::{TornadoMeter, EVENT_TYPE_LABEL_KEY};
+use crate::enrich::lookup::LookupEnricher;
+use crate::enrich::merge_patch::{EventMergePatchConfig, EventMerger};
+use crate::monitoring::metrics::{
+    TornadoMeter, EVENT_TYPE_LABEL_KEY, RULESET_LABEL_KEY, RULE_NAME_LABEL_KEY,
+};
+use crate::monitoring::rule_stats::matched_rules;
 use actix::prelude::*;
 use log::*;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tornado_common::actors::message::FlushMessage;
 use tornado_common_api::{Value, WithEventData};
 use tornado_engine_api::event::api::ProcessType;
 use tornado_engine_matcher::config::operation::{matcher_config_filter, NodeFilter};
 use tornado_engine_matcher::config::{MatcherConfig, MatcherConfigEditor};
 use tornado_engine_matcher::error::MatcherError;
-use tornado_engine_matcher::matcher::Matcher;
+use tornado_engine_matcher::matcher::deduplication::{
+    DeduplicationStore, InMemoryDeduplicationStore,
+};
+use tornado_engine_matcher::matcher::previous_value::{
+    InMemoryPreviousValueStore, PreviousValueStore,
+};
+use tornado_engine_matcher::matcher::{Matcher, MatcherSettings};
 use tornado_engine_matcher::model::ProcessedEvent;
-use tornado_engine_matcher::{error, matcher};
+use tornado_engine_matcher::{error, matcher, normalizer};
 use tracing::{instrument, Span};
 
 #[derive(Message)]
@@ -49,30 +61,160 @@ pub struct ReconfigureMessage {}
 #[rtype(result = "Arc<MatcherConfig>")]
 pub struct GetCurrentConfigMessage {}
 
+/// The metadata key added with value `true` to the events processed over the processing budget
+pub const PROCESSING_OVER_BUDGET_METADATA_KEY: &str = "processing_over_budget";
+
+/// The maximum time that the processing of a single event is expected to take.
+/// The events that exceed it are counted by the `events_over_budget_counter`.
+#[derive(Debug, Clone)]
+pub struct ProcessingBudget {
+    pub max_duration: Duration,
+    /// Whether the processed events that exceed the budget are tagged in their metadata
+    pub tag_events: bool,
+}
+
+/// The optional behaviours of the MatcherActor, all disabled by default.
+#[derive(Debug, Clone, Default)]
+pub struct MatcherActorOptions {
+    /// The budget against which the processing time of each event is checked
+    pub processing_budget: Option<ProcessingBudget>,
+    /// The max age of the events; the older events are dropped without matching them
+    ///   and counted by the `events_expired_counter`
+    pub event_ttl: Option<Duration>,
+    /// Whether the events are normalized with `tornado_engine_matcher::normalizer`
+    ///   before matching them
+    pub normalize_events: bool,
+    /// If set, the events are merged onto the last event of the same entity before matching them
+    pub event_merge_patch: Option<EventMergePatchConfig>,
+    /// The enrichers applied in order to the events before matching them,
+    ///   whatever the source of the events
+    pub lookup_enrichers: Vec<LookupEnricher>,
+    /// The settings applied when the matchers are built, e.g. the regex limits
+    pub matcher_settings: MatcherSettings,
+}
+
 pub struct MatcherActor {
     dispatcher_addr: Recipient<ProcessedEventMessage>,
     matcher_config_manager: Arc<dyn MatcherConfigEditor>,
     matcher_config: Arc<MatcherConfig>,
     matcher: Arc<matcher::Matcher>,
+    /// The stores shared by the matchers that process the real events, so that the events sent
+    ///   through the API with the Full process type see the same deduplication keys and
+    ///   previous values of the others
+    deduplication_store: Arc<dyn DeduplicationStore>,
+    previous_value_store: Arc<dyn PreviousValueStore>,
+    matcher_settings: MatcherSettings,
     meter: Arc<TornadoMeter>,
+    processing_budget: Option<ProcessingBudget>,
+    event_ttl: Option<Duration>,
+    normalize_events: bool,
+    event_merger: Option<EventMerger>,
+    lookup_enrichers: Vec<LookupEnricher>,
 }
 
 impl MatcherActor {
-    pub async fn start(
+    /// Starts a MatcherActor with the given options.
+    pub async fn start_with_options(
         dispatcher_addr: Recipient<ProcessedEventMessage>,
         matcher_config_manager: Arc<dyn MatcherConfigEditor>,
         message_mailbox_capacity: usize,
         meter: Arc<TornadoMeter>,
+        options: MatcherActorOptions,
     ) -> Result<Addr<MatcherActor>, MatcherError> {
+        let MatcherActorOptions {
+            processing_budget,
+            event_ttl,
+            normalize_events,
+            event_merge_patch,
+            lookup_enrichers,
+            matcher_settings,
+        } = options;
+        let event_merger =
+            event_merge_patch.as_ref().map(EventMergePatchConfig::build).transpose()?;
         let matcher_config = Arc::new(matcher_config_manager.get_config().await?);
-        let matcher = Arc::new(Matcher::build(&matcher_config)?);
+        let deduplication_store: Arc<dyn DeduplicationStore> =
+            Arc::new(InMemoryDeduplicationStore::default());
+        let previous_value_store: Arc<dyn PreviousValueStore> =
+            Arc::new(InMemoryPreviousValueStore::default());
+        let matcher = Arc::new(Matcher::build_with_stores(
+            &matcher_config,
+            deduplication_store.clone(),
+            previous_value_store.clone(),
+            &matcher_settings,
+        )?);
 
         Ok(actix::Supervisor::start(move |ctx: &mut Context<MatcherActor>| {
             ctx.set_mailbox_capacity(message_mailbox_capacity);
-            MatcherActor { dispatcher_addr, matcher_config_manager, matcher_config, matcher, meter }
+            MatcherActor {
+                dispatcher_addr,
+                matcher_config_manager,
+                matcher_config,
+                matcher,
+                deduplication_store,
+                previous_value_store,
+                matcher_settings,
+                meter,
+                processing_budget,
+                event_ttl,
+                normalize_events,
+                event_merger,
+                lookup_enrichers,
+            }
         }))
     }
 
+    /// Builds a matcher for the events sent through the API.
+    /// If `update_state` is true, the matcher shares the stores of the actor matcher; otherwise,
+    ///   e.g. for the dry runs, it uses new stores, so that the keys and the values it records
+    ///   do not alter the processing of the next real events.
+    fn build_matcher(
+        &self,
+        config: &MatcherConfig,
+        update_state: bool,
+    ) -> Result<Matcher, MatcherError> {
+        if update_state {
+            Matcher::build_with_stores(
+                config,
+                self.deduplication_store.clone(),
+                self.previous_value_store.clone(),
+                &self.matcher_settings,
+            )
+        } else {
+            Matcher::build_with_stores(
+                config,
+                Arc::new(InMemoryDeduplicationStore::default()),
+                Arc::new(InMemoryPreviousValueStore::default()),
+                &self.matcher_settings,
+            )
+        }
+    }
+
+    /// Returns whether the event was created more than `event_ttl` ago.
+    /// The events without a valid creation time never expire.
+    fn is_expired(&self, event: &Value) -> bool {
+        match (self.event_ttl, event.created_ms()) {
+            (Some(event_ttl), Some(created_ms)) => {
+                let now_ms =
+                    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+                now_ms.saturating_sub(u128::from(created_ms)) > event_ttl.as_millis()
+            }
+            _ => false,
+        }
+    }
+
+    /// Applies the lookup enrichments to the event and merges it onto the last event
+    ///   of its entity, if the merge is enabled.
+    /// If `update_state` is false, the merged event does not become the last one of the entity.
+    fn enrich_event(&mut self, mut event: Value, update_state: bool) -> Value {
+        for enricher in &self.lookup_enrichers {
+            event = enricher.process(event);
+        }
+        match &mut self.event_merger {
+            Some(event_merger) => event_merger.merge(event, update_state),
+            None => event,
+        }
+    }
+
     fn process_event_with_reply(
         &self,
         matcher: &Matcher,
@@ -83,15 +225,32 @@ impl MatcherActor {
         let processed_event = self.process(matcher, event, include_metadata);
 
         match process_type {
-            ProcessType::Full => self
-                .dispatcher_addr
-                .try_send(ProcessedEventMessage { span:  tracing::Span::current(), event: processed_event.clone() }).unwrap_or_else(|err| error!("MatcherActor -  Error while sending ProcessedEventMessage to DispatcherActor. Error: {}", err)),
+            ProcessType::Full => {
+                self.record_matched_rules(&processed_event);
+                self.dispatcher_addr.try_send(ProcessedEventMessage { span:  tracing::Span::current(), event: processed_event.clone() }).unwrap_or_else(|err| error!("MatcherActor -  Error while sending ProcessedEventMessage to DispatcherActor. Error: {}", err))
+            }
             ProcessType::SkipActions => {}
         }
 
         processed_event
     }
 
+    /// Counts the matches of the rules matched by the processed event and keeps
+    ///   the time of their last match.
+    fn record_matched_rules(&self, processed_event: &ProcessedEvent) {
+        let matched_at = SystemTime::now();
+        for (ruleset_path, rule_name) in matched_rules(processed_event) {
+            self.meter.rules_matched_counter.add(
+                1,
+                &[
+                    RULESET_LABEL_KEY.string(ruleset_path.clone()),
+                    RULE_NAME_LABEL_KEY.string(rule_name.clone()),
+                ],
+            );
+            self.meter.rule_match_stats.record(ruleset_path, rule_name, matched_at);
+        }
+    }
+
     #[inline]
     #[instrument(level = "info", name = "Match against Processing Tree", skip_all)]
     fn process(&self, matcher: &Matcher, event: Value, include_metadata: bool) -> ProcessedEvent {
@@ -103,12 +262,33 @@ impl MatcherActor {
                 .unwrap_or_else(|| "".to_owned()),
         )];
 
-        let process = matcher.process(event, include_metadata);
+        let event = if self.normalize_events { normalizer::normalize(event) } else { event };
+        let mut process = matcher.process(event, include_metadata);
+        let elapsed = timer.elapsed().unwrap_or_default();
 
         self.meter.events_processed_counter.add(1, &labels);
-        self.meter
-            .events_processed_duration_seconds
-            .record(timer.elapsed().map(|t| t.as_secs_f64()).unwrap_or_default(), &labels);
+        self.meter.events_processed_duration_seconds.record(elapsed.as_secs_f64(), &labels);
+
+        if let Some(budget) = &self.processing_budget {
+            if elapsed > budget.max_duration {
+                debug!(
+                    "MatcherActor - The event processing took {:?}, over the budget of {:?}",
+                    elapsed, budget.max_duration
+                );
+                self.meter.events_over_budget_counter.add(1, &labels);
+                if budget.tag_events {
+                    if let Err(err) = process.event.add_to_metadata(
+                        PROCESSING_OVER_BUDGET_METADATA_KEY.to_owned(),
+                        Value::Bool(true),
+                    ) {
+                        warn!(
+                            "MatcherActor - Cannot tag the event processed over budget. Err: {:?}",
+                            err
+                        );
+                    }
+                }
+            }
+        }
 
         process
     }
@@ -134,7 +314,20 @@ impl Handler<EventMessage> for MatcherActor {
         let _g = msg.span.clone().entered();
         trace!("MatcherActor - received new EventMessage [{:?}]", &msg.event);
 
-        let processed_event = self.process(&self.matcher, msg.event, false);
+        if self.is_expired(&msg.event) {
+            let event_type = msg.event.event_type().unwrap_or_default().to_owned();
+            debug!(
+                "MatcherActor - The event of type [{}] is older than the TTL of {:?}. It is dropped",
+                event_type,
+                self.event_ttl.unwrap_or_default()
+            );
+            self.meter.events_expired_counter.add(1, &[EVENT_TYPE_LABEL_KEY.string(event_type)]);
+            return Ok(());
+        }
+
+        let event = self.enrich_event(msg.event, true);
+        let processed_event = self.process(&self.matcher, event, false);
+        self.record_matched_rules(&processed_event);
         self.dispatcher_addr.try_send(ProcessedEventMessage { span: msg.span, event: processed_event }).unwrap_or_else(|err| error!("MatcherActor -  Error while sending ProcessedEventMessage to DispatcherActor. Error: {}", err));
         Ok(())
     }
@@ -151,14 +344,11 @@ impl Handler<EventMessageWithReply> for MatcherActor {
             .ok_or_else(|| MatcherError::ConfigurationError {
                 message: "The config filter does not match any existing node".to_owned(),
             })?;
-        let matcher = Matcher::build(&filtered_config)?;
+        let update_state = matches!(msg.process_type, ProcessType::Full);
+        let matcher = self.build_matcher(&filtered_config, update_state)?;
+        let event = self.enrich_event(msg.event, update_state);
 
-        Ok(self.process_event_with_reply(
-            &matcher,
-            msg.event,
-            msg.process_type,
-            msg.include_metadata,
-        ))
+        Ok(self.process_event_with_reply(&matcher, event, msg.process_type, msg.include_metadata))
     }
 }
 
@@ -172,13 +362,10 @@ impl Handler<EventMessageAndConfigWithReply> for MatcherActor {
     ) -> Self::Result {
         trace!("MatcherActor - received new EventMessageAndConfigWithReply [{:?}]", msg);
 
-        let matcher = Matcher::build(&msg.matcher_config)?;
-        Ok(self.process_event_with_reply(
-            &matcher,
-            msg.event,
-            msg.process_type,
-            msg.include_metadata,
-        ))
+        // The config is not the deployed one, e.g. a draft, so the state is never updated
+        let matcher = self.build_matcher(&msg.matcher_config, false)?;
+        let event = self.enrich_event(msg.event, false);
+        Ok(self.process_event_with_reply(&matcher, event, msg.process_type, msg.include_metadata))
     }
 }
 
@@ -191,17 +378,29 @@ impl Handler<GetCurrentConfigMessage> for MatcherActor {
     }
 }
 
+impl Handler<FlushMessage> for MatcherActor {
+    type Result = ();
+
+    fn handle(&mut self, _msg: FlushMessage, _: &mut Context<Self>) -> Self::Result {
+        trace!("MatcherActor - received new FlushMessage");
+    }
+}
+
 impl Handler<ReconfigureMessage> for MatcherActor {
     type Result = ResponseActFuture<Self, Result<Arc<MatcherConfig>, error::MatcherError>>;
 
     fn handle(&mut self, _msg: ReconfigureMessage, _ctx: &mut Context<Self>) -> Self::Result {
         let matcher_config_manager = self.matcher_config_manager.clone();
+        let current_matcher_config = self.matcher_config.clone();
+        let current_matcher = self.matcher.clone();
         info!("MatcherActor - received ReconfigureMessage.");
 
         Box::pin(
             async move {
                 let matcher_config = Arc::new(matcher_config_manager.get_config().await?);
-                let matcher = Arc::new(Matcher::build(&matcher_config)?);
+                // Only the nodes changed since the current configuration are rebuilt
+                let matcher =
+                    Arc::new(current_matcher.rebuild(&current_matcher_config, &matcher_config)?);
                 Ok((matcher, matcher_config))
             }
             .into_actor(self) // converts future to ActorFuture
@@ -227,12 +426,20 @@ mod test {
     use crate::actor::dispatcher::ProcessedEventMessage;
     use crate::command::upgrade_rules::test::prepare_temp_dirs;
     use crate::config::parse_config_files;
+    use crate::enrich::lookup::LookupEnrichment;
+    use crate::monitoring::metrics::TORNADO_APP;
     use maplit::hashmap;
     use serde_json::json;
-    use tornado_common_api::{Event, Value};
+    use tornado_common_api::{Event, Payload, Value};
+    use tornado_common_metrics::opentelemetry::metrics::MeterProvider;
+    use tornado_common_metrics::prometheus::{Encoder, TextEncoder};
+    use tornado_common_metrics::Metrics;
+    use tornado_engine_matcher::config::rule::{
+        ConfigAction, Constraint, Deduplication, Operator, Rule,
+    };
     use tornado_engine_matcher::config::v1::fs::ROOT_NODE_NAME;
     use tornado_engine_matcher::model::ProcessedFilterStatus;
-    use tornado_engine_matcher::model::ProcessedNode;
+    use tornado_engine_matcher::model::{ProcessedNode, ProcessedRuleStatus, ProcessedRules};
 
     #[actix::test]
     async fn should_reconfigure_the_matcher_and_return_the_new_config() {
@@ -245,10 +452,15 @@ mod test {
         let config_manager = configs.matcher_config.clone();
         let dispatcher_addr = FakeDispatcher {}.start().recipient();
 
-        let matcher_actor =
-            MatcherActor::start(dispatcher_addr, config_manager.clone(), 10, Default::default())
-                .await
-                .unwrap();
+        let matcher_actor = MatcherActor::start_with_options(
+            dispatcher_addr,
+            config_manager.clone(),
+            10,
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
 
         let draft_id = config_manager.create_draft("user_1".to_owned()).await.unwrap();
         let draft = config_manager.get_draft(&draft_id).await.unwrap();
@@ -273,10 +485,15 @@ mod test {
 
         let config_manager = configs.matcher_config.clone();
         let dispatcher_addr = FakeDispatcher {}.start().recipient();
-        let matcher_actor =
-            MatcherActor::start(dispatcher_addr, config_manager.clone(), 10, Default::default())
-                .await
-                .unwrap();
+        let matcher_actor = MatcherActor::start_with_options(
+            dispatcher_addr,
+            config_manager.clone(),
+            10,
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
 
         // Act
         let returned_config = matcher_actor.send(GetCurrentConfigMessage {}).await.unwrap();
@@ -296,10 +513,15 @@ mod test {
 
         let config_manager = configs.matcher_config.clone();
         let dispatcher_addr = FakeDispatcher {}.start().recipient();
-        let matcher_actor =
-            MatcherActor::start(dispatcher_addr, config_manager.clone(), 10, Default::default())
-                .await
-                .unwrap();
+        let matcher_actor = MatcherActor::start_with_options(
+            dispatcher_addr,
+            config_manager.clone(),
+            10,
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
 
         let mut event: Value = json!(Event::new("test"));
         event.add_to_metadata("tenant_id".to_owned(), Value::String("alpha".to_owned())).unwrap();
@@ -346,10 +568,15 @@ mod test {
 
         let config_manager = configs.matcher_config.clone();
         let dispatcher_addr = FakeDispatcher {}.start().recipient();
-        let matcher_actor =
-            MatcherActor::start(dispatcher_addr, config_manager.clone(), 10, Default::default())
-                .await
-                .unwrap();
+        let matcher_actor = MatcherActor::start_with_options(
+            dispatcher_addr,
+            config_manager.clone(),
+            10,
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
 
         let mut event_tenant_alpha: Value = json!(Event::new("test"));
         event_tenant_alpha
@@ -433,10 +660,15 @@ mod test {
 
         let config_manager = configs.matcher_config.clone();
         let dispatcher_addr = FakeDispatcher {}.start().recipient();
-        let matcher_actor =
-            MatcherActor::start(dispatcher_addr, config_manager.clone(), 10, Default::default())
-                .await
-                .unwrap();
+        let matcher_actor = MatcherActor::start_with_options(
+            dispatcher_addr,
+            config_manager.clone(),
+            10,
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
 
         let mut event: Value = json!(Event::new("test"));
         event.add_to_metadata("tenant_id".to_owned(), Value::String("alpha".to_owned())).unwrap();
@@ -461,6 +693,589 @@ mod test {
         assert!(processed_event.is_err());
     }
 
+    #[actix::test]
+    async fn should_count_and_tag_the_events_processed_over_budget() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        // The regex has to scan the whole huge payload, which makes the rule artificially slow
+        let slow_config = MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![Rule {
+                name: "slow_rule".to_owned(),
+                description: "".to_owned(),
+                do_continue: true,
+                active: true,
+                constraint: Constraint {
+                    where_operator: Some(Operator::Regex {
+                        regex: "b$".to_owned(),
+                        target: "${event.payload.text}".to_owned(),
+                    }),
+                    with: HashMap::new(),
+                },
+                actions: vec![],
+                deduplication: None,
+            }],
+        };
+        let mut payload = Payload::new();
+        payload.insert("text".to_owned(), Value::String("a".repeat(1_000_000)));
+        let event = json!(Event::new_with_payload("slow_event", payload));
+
+        let metrics = Metrics::new(TORNADO_APP);
+        let provider = metrics.prometheus_exporter.provider().unwrap();
+        let meter = Arc::new(TornadoMeter::new_with_meter(
+            &provider.meter(TORNADO_APP, None),
+            "budget_test_",
+        ));
+        let matcher_actor = MatcherActor::start_with_options(
+            FakeDispatcher {}.start().recipient(),
+            configs.matcher_config.clone(),
+            10,
+            meter,
+            MatcherActorOptions {
+                processing_budget: Some(ProcessingBudget {
+                    max_duration: Duration::from_micros(1),
+                    tag_events: true,
+                }),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        // Act
+        let processed_event = matcher_actor
+            .send(EventMessageAndConfigWithReply {
+                event,
+                matcher_config: slow_config,
+                process_type: ProcessType::SkipActions,
+                include_metadata: false,
+            })
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&metrics.prometheus_exporter.registry().gather(), &mut buf)
+            .unwrap();
+        let content = String::from_utf8(buf).unwrap();
+
+        // Assert
+        let over_budget_line = content
+            .lines()
+            .find(|line| line.starts_with("budget_test_events_over_budget_counter{"))
+            .unwrap();
+        assert!(over_budget_line.contains("event_type=\"slow_event\""));
+        assert!(over_budget_line.ends_with(" 1"));
+        assert_eq!(
+            Some(&Value::Bool(true)),
+            processed_event
+                .event
+                .metadata()
+                .and_then(|metadata| metadata.get(PROCESSING_OVER_BUDGET_METADATA_KEY))
+        );
+    }
+
+    #[actix::test]
+    async fn should_not_tag_the_events_processed_within_budget() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        let matcher_actor = MatcherActor::start_with_options(
+            FakeDispatcher {}.start().recipient(),
+            configs.matcher_config.clone(),
+            10,
+            Default::default(),
+            MatcherActorOptions {
+                processing_budget: Some(ProcessingBudget {
+                    max_duration: Duration::from_secs(3600),
+                    tag_events: true,
+                }),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        // Act
+        let processed_event = matcher_actor
+            .send(EventMessageAndConfigWithReply {
+                event: json!(Event::new("fast_event")),
+                matcher_config: MatcherConfig::Ruleset {
+                    name: "ruleset".to_owned(),
+                    rules: vec![],
+                },
+                process_type: ProcessType::SkipActions,
+                include_metadata: false,
+            })
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Assert
+        assert!(processed_event
+            .event
+            .metadata()
+            .and_then(|metadata| metadata.get(PROCESSING_OVER_BUDGET_METADATA_KEY))
+            .is_none());
+    }
+
+    #[actix::test]
+    async fn should_drop_the_events_older_than_the_ttl() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let matcher_actor = MatcherActor::start_with_options(
+            RecordingDispatcher { sender }.start().recipient(),
+            configs.matcher_config.clone(),
+            10,
+            Default::default(),
+            MatcherActorOptions { event_ttl: Some(Duration::from_secs(60)), ..Default::default() },
+        )
+        .await
+        .unwrap();
+
+        let mut old_event = Event::new("old_event");
+        old_event.created_ms -= 3_600_000;
+        let fresh_event = Event::new("fresh_event");
+
+        // Act
+        matcher_actor
+            .send(EventMessage { event: json!(old_event), span: Span::current() })
+            .await
+            .unwrap()
+            .unwrap();
+        matcher_actor
+            .send(EventMessage { event: json!(fresh_event), span: Span::current() })
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Assert
+        let processed_event = receiver.recv().await.unwrap();
+        assert_eq!(Some("fresh_event"), processed_event.event.event.event_type());
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[actix::test]
+    async fn should_process_the_old_events_if_no_ttl() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let matcher_actor = MatcherActor::start_with_options(
+            RecordingDispatcher { sender }.start().recipient(),
+            configs.matcher_config.clone(),
+            10,
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        let mut old_event = Event::new("old_event");
+        old_event.created_ms = 0;
+
+        // Act
+        matcher_actor
+            .send(EventMessage { event: json!(old_event), span: Span::current() })
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Assert
+        let processed_event = receiver.recv().await.unwrap();
+        assert_eq!(Some("old_event"), processed_event.event.event.event_type());
+    }
+
+    #[actix::test]
+    async fn should_normalize_the_events_only_if_enabled() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        let mut event = Event::new("event");
+        event.payload.insert("value ".to_owned(), json!(1.0));
+
+        for (normalize_events, expected_payload) in
+            [(true, json!({ "value": 1 })), (false, json!({ "value ": 1.0 }))]
+        {
+            let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+            let matcher_actor = MatcherActor::start_with_options(
+                RecordingDispatcher { sender }.start().recipient(),
+                configs.matcher_config.clone(),
+                10,
+                Default::default(),
+                MatcherActorOptions { normalize_events, ..Default::default() },
+            )
+            .await
+            .unwrap();
+
+            // Act
+            matcher_actor
+                .send(EventMessage { event: json!(event), span: Span::current() })
+                .await
+                .unwrap()
+                .unwrap();
+
+            // Assert
+            let processed_event = receiver.recv().await.unwrap();
+            assert_eq!(Some(&expected_payload), processed_event.event.event.get("payload"));
+        }
+    }
+
+    #[actix::test]
+    async fn should_enrich_the_events_sent_through_the_api() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        let lookup_enrichment: LookupEnrichment = serde_json::from_value(json!({
+            "source": "severity",
+            "target": "severity_label",
+            "table": { "1": "critical" }
+        }))
+        .unwrap();
+        let matcher_actor = MatcherActor::start_with_options(
+            FakeDispatcher {}.start().recipient(),
+            configs.matcher_config.clone(),
+            10,
+            Default::default(),
+            MatcherActorOptions {
+                lookup_enrichers: vec![lookup_enrichment.build().unwrap()],
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut event = Event::new("event");
+        event.payload.insert("severity".to_owned(), json!(1));
+        let mut config_filter = HashMap::new();
+        config_filter.insert(ROOT_NODE_NAME.to_owned(), NodeFilter::AllChildren);
+
+        // Act
+        let processed_event = matcher_actor
+            .send(EventMessageWithReply {
+                event: json!(event),
+                config_filter,
+                include_metadata: false,
+                process_type: ProcessType::SkipActions,
+                span: Span::current(),
+            })
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Assert
+        assert_eq!(
+            Some(&json!({ "severity": 1, "severity_label": "critical" })),
+            processed_event.event.get("payload")
+        );
+    }
+
+    #[actix::test]
+    async fn should_match_the_merged_state_of_the_partial_events() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let matcher_actor = MatcherActor::start_with_options(
+            RecordingDispatcher { sender }.start().recipient(),
+            configs.matcher_config.clone(),
+            10,
+            Default::default(),
+            MatcherActorOptions {
+                event_merge_patch: Some(EventMergePatchConfig {
+                    entity_key: "${event.payload.host}".into(),
+                    ttl_secs: 60,
+                    max_entities: 100,
+                }),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let partial_payloads = [
+            json!({ "host": "server-1", "state": "UP", "output": "ok" }),
+            json!({ "host": "server-1", "state": "DOWN", "output": null, "latency_ms": 500 }),
+        ];
+
+        // Act
+        for payload in partial_payloads {
+            let mut event = Event::new("host_state");
+            event.payload = serde_json::from_value(payload).unwrap();
+            matcher_actor
+                .send(EventMessage { event: json!(event), span: Span::current() })
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        // Assert
+        receiver.recv().await.unwrap();
+        let processed_event = receiver.recv().await.unwrap();
+        assert_eq!(
+            Some(&json!({ "host": "server-1", "state": "DOWN", "latency_ms": 500 })),
+            processed_event.event.event.get("payload")
+        );
+    }
+
+    #[actix::test]
+    async fn should_not_record_the_deduplication_keys_of_the_events_sent_with_a_config() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        let matcher_actor = MatcherActor::start_with_options(
+            FakeDispatcher {}.start().recipient(),
+            configs.matcher_config.clone(),
+            10,
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        let matcher_config = MatcherConfig::Ruleset {
+            name: "ruleset".to_owned(),
+            rules: vec![Rule {
+                name: "notify_once".to_owned(),
+                description: "".to_owned(),
+                do_continue: true,
+                active: true,
+                constraint: Constraint { where_operator: None, with: HashMap::new() },
+                actions: vec![ConfigAction {
+                    id: "notify".to_owned(),
+                    payload: Default::default(),
+                    when: None,
+                    retry_strategy: None,
+                }],
+                deduplication: Some(Deduplication { key: "${event.type}".into(), ttl_secs: 60 }),
+            }],
+        };
+
+        // Act
+        let mut actions_count = vec![];
+        for _ in 0..2 {
+            let processed_event = matcher_actor
+                .send(EventMessageAndConfigWithReply {
+                    event: json!(Event::new("host_down")),
+                    matcher_config: matcher_config.clone(),
+                    process_type: ProcessType::SkipActions,
+                    include_metadata: false,
+                })
+                .await
+                .unwrap()
+                .unwrap();
+            match processed_event.result {
+                ProcessedNode::Ruleset { rules, .. } => {
+                    actions_count.push(rules.rules[0].actions.len())
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        // Assert
+        assert_eq!(vec![1, 1], actions_count);
+    }
+
+    #[actix::test]
+    async fn should_not_record_the_deduplication_keys_of_the_events_processed_without_actions() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        write_ruleset(
+            &config_dir,
+            &rules_dir,
+            "deduplicated",
+            &Rule {
+                name: "notify_once".to_owned(),
+                description: "".to_owned(),
+                do_continue: true,
+                active: true,
+                constraint: Constraint { where_operator: None, with: HashMap::new() },
+                actions: vec![ConfigAction {
+                    id: "notify".to_owned(),
+                    payload: Default::default(),
+                    when: None,
+                    retry_strategy: None,
+                }],
+                deduplication: Some(Deduplication { key: "${event.type}".into(), ttl_secs: 60 }),
+            },
+        );
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        let matcher_actor = MatcherActor::start_with_options(
+            FakeDispatcher {}.start().recipient(),
+            configs.matcher_config.clone(),
+            10,
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        // Act
+        let mut actions_count = vec![];
+        for process_type in [ProcessType::SkipActions, ProcessType::Full] {
+            let mut config_filter = HashMap::new();
+            config_filter.insert(ROOT_NODE_NAME.to_owned(), NodeFilter::AllChildren);
+            let processed_event = matcher_actor
+                .send(EventMessageWithReply {
+                    event: json!(Event::new("host_down")),
+                    config_filter,
+                    include_metadata: false,
+                    process_type,
+                    span: Span::current(),
+                })
+                .await
+                .unwrap()
+                .unwrap();
+            let rules = ruleset_rules(processed_event.result, "deduplicated");
+            actions_count.push(rules.rules[0].actions.len())
+        }
+
+        // Assert
+        assert_eq!(vec![1, 1], actions_count);
+    }
+
+    #[actix::test]
+    async fn should_not_record_the_previous_values_of_the_events_processed_without_actions() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let (config_dir, rules_dir, drafts_dir) = prepare_temp_dirs(&tempdir);
+        write_ruleset(
+            &config_dir,
+            &rules_dir,
+            "state_changes",
+            &Rule {
+                name: "state_changed".to_owned(),
+                description: "".to_owned(),
+                do_continue: true,
+                active: true,
+                constraint: Constraint {
+                    where_operator: Some(Operator::Changed {
+                        entity: Value::String("${event.payload.hostname}".to_owned()),
+                        target: Value::String("${event.payload.state}".to_owned()),
+                    }),
+                    with: HashMap::new(),
+                },
+                actions: vec![],
+                deduplication: None,
+            },
+        );
+        let configs = parse_config_files(&config_dir, &rules_dir, &drafts_dir).unwrap();
+
+        let matcher_actor = MatcherActor::start_with_options(
+            FakeDispatcher {}.start().recipient(),
+            configs.matcher_config.clone(),
+            10,
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        // Act
+        let mut statuses = vec![];
+        for (process_type, state) in [
+            (ProcessType::Full, "UP"),
+            (ProcessType::SkipActions, "DOWN"),
+            (ProcessType::Full, "DOWN"),
+        ] {
+            let mut payload = Payload::new();
+            payload.insert("hostname".to_owned(), Value::String("host1".to_owned()));
+            payload.insert("state".to_owned(), Value::String(state.to_owned()));
+            let mut config_filter = HashMap::new();
+            config_filter.insert(ROOT_NODE_NAME.to_owned(), NodeFilter::AllChildren);
+            let processed_event = matcher_actor
+                .send(EventMessageWithReply {
+                    event: json!(Event::new_with_payload("host_state", payload)),
+                    config_filter,
+                    include_metadata: false,
+                    process_type,
+                    span: Span::current(),
+                })
+                .await
+                .unwrap()
+                .unwrap();
+            statuses.push(
+                ruleset_rules(processed_event.result, "state_changes").rules[0].status.clone(),
+            );
+        }
+
+        // Assert
+        assert_eq!(
+            vec![
+                ProcessedRuleStatus::NotMatched,
+                ProcessedRuleStatus::NotMatched,
+                ProcessedRuleStatus::Matched
+            ],
+            statuses
+        );
+    }
+
+    fn ruleset_rules(result: ProcessedNode, ruleset_name: &str) -> ProcessedRules {
+        match result {
+            ProcessedNode::Filter { nodes, .. } => nodes
+                .into_iter()
+                .find_map(|node| match node {
+                    ProcessedNode::Ruleset { name, rules } if name == ruleset_name => Some(rules),
+                    _ => None,
+                })
+                .unwrap(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_ruleset(config_dir: &str, rules_dir: &str, ruleset_name: &str, rule: &Rule) {
+        let ruleset_dir = format!("{}/{}/{}", config_dir, rules_dir, ruleset_name);
+        std::fs::create_dir_all(format!("{}/rules", ruleset_dir)).unwrap();
+        std::fs::write(
+            format!("{}/ruleset.json", ruleset_dir),
+            json!({ "type": "ruleset", "name": ruleset_name }).to_string(),
+        )
+        .unwrap();
+        std::fs::write(
+            format!("{}/rules/001_{}.json", ruleset_dir, rule.name),
+            serde_json::to_string(rule).unwrap(),
+        )
+        .unwrap();
+    }
+
+    struct RecordingDispatcher {
+        sender: tokio::sync::mpsc::UnboundedSender<ProcessedEventMessage>,
+    }
+
+    impl Actor for RecordingDispatcher {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<ProcessedEventMessage> for RecordingDispatcher {
+        type Result = Result<(), MatcherError>;
+        fn handle(&mut self, msg: ProcessedEventMessage, _: &mut Context<Self>) -> Self::Result {
+            self.sender.send(msg).unwrap();
+            Ok(())
+        }
+    }
+
     struct FakeDispatcher {}
 
     impl Actor for FakeDispatcher {
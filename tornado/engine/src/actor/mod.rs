@@ -1,3 +1,6 @@
+pub mod bus_executor;
+pub mod config_watcher;
 pub mod dispatcher;
 pub mod foreach;
 pub mod matcher;
+pub mod round_robin;
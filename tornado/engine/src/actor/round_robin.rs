@@ -0,0 +1,221 @@
+use actix::prelude::*;
+use log::*;
+use std::sync::Arc;
+use tornado_common::actors::message::ActionMessage;
+use tornado_common::metrics::{
+    ActionMeter, ACTION_ID_LABEL_KEY, ACTION_RESULT_KEY, RESULT_FAILURE,
+};
+use tornado_executor_common::ExecutorError;
+
+struct WeightedRecipient {
+    recipient: Recipient<ActionMessage>,
+    weight: i64,
+    current_weight: i64,
+}
+
+/// An actor that forwards each received action to one of the registered recipients,
+/// selected with a smooth weighted round-robin.
+/// A recipient with weight 3 receives three actions every one received by a recipient
+/// with weight 1; the deliveries are interleaved instead of sent in bursts.
+/// The recipients with weight 0 never receive actions.
+/// The actions that cannot be forwarded are logged and counted as failed.
+pub struct WeightedRoundRobinActor {
+    recipients: Vec<WeightedRecipient>,
+    action_meter: Arc<ActionMeter>,
+}
+
+impl WeightedRoundRobinActor {
+    pub fn start_new(
+        message_mailbox_capacity: usize,
+        recipients: Vec<(Recipient<ActionMessage>, u32)>,
+        action_meter: Arc<ActionMeter>,
+    ) -> Addr<Self> {
+        Self::create(move |ctx| {
+            ctx.set_mailbox_capacity(message_mailbox_capacity);
+            Self::new(recipients, action_meter)
+        })
+    }
+
+    fn new(
+        recipients: Vec<(Recipient<ActionMessage>, u32)>,
+        action_meter: Arc<ActionMeter>,
+    ) -> Self {
+        Self {
+            action_meter,
+            recipients: recipients
+                .into_iter()
+                .map(|(recipient, weight)| WeightedRecipient {
+                    recipient,
+                    weight: i64::from(weight),
+                    current_weight: 0,
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the index of the next recipient. At each call, the current weight of every
+    /// recipient is increased by its weight; the recipient with the highest current weight
+    /// is selected and its current weight is decreased by the sum of all the weights.
+    fn next(&mut self) -> Option<usize> {
+        let total_weight: i64 = self.recipients.iter().map(|recipient| recipient.weight).sum();
+        if total_weight == 0 {
+            return None;
+        }
+
+        for recipient in self.recipients.iter_mut() {
+            recipient.current_weight += recipient.weight;
+        }
+        let mut selected = 0;
+        for (index, recipient) in self.recipients.iter().enumerate() {
+            if recipient.current_weight > self.recipients[selected].current_weight {
+                selected = index;
+            }
+        }
+        self.recipients[selected].current_weight -= total_weight;
+        Some(selected)
+    }
+}
+
+impl Actor for WeightedRoundRobinActor {
+    type Context = Context<Self>;
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        debug!("WeightedRoundRobinActor started.");
+    }
+}
+
+impl Handler<ActionMessage> for WeightedRoundRobinActor {
+    type Result = Result<(), ExecutorError>;
+
+    fn handle(&mut self, msg: ActionMessage, _: &mut Context<Self>) -> Self::Result {
+        let action_id = msg.0.action.id.clone();
+        let result = match self.next() {
+            Some(index) => {
+                trace!(
+                    "WeightedRoundRobinActor - Send action [{}] to recipient {}",
+                    action_id,
+                    index
+                );
+                self.recipients[index].recipient.try_send(msg).map_err(|err| {
+                    ExecutorError::SenderError {
+                        message: format!(
+                            "Error sending action [{}] to recipient {}. Err: {:?}",
+                            action_id, index, err
+                        ),
+                    }
+                })
+            }
+            None => Err(ExecutorError::SenderError {
+                message: format!(
+                    "There are no recipients with weight > 0 for action id [{}]",
+                    action_id
+                ),
+            }),
+        };
+
+        // The event bus does not wait for the result, so the failure is reported here
+        if let Err(err) = &result {
+            error!("WeightedRoundRobinActor - Cannot forward action [{}]: {}", action_id, err);
+            self.action_meter.actions_processed_counter.add(
+                1,
+                &[ACTION_ID_LABEL_KEY.string(action_id), ACTION_RESULT_KEY.string(RESULT_FAILURE)],
+            );
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+    use tornado_common_api::{Action, TracedAction};
+
+    struct FakeExecutor {
+        name: &'static str,
+        sender: UnboundedSender<&'static str>,
+    }
+
+    impl Actor for FakeExecutor {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<ActionMessage> for FakeExecutor {
+        type Result = Result<(), ExecutorError>;
+        fn handle(&mut self, _msg: ActionMessage, _: &mut Context<Self>) -> Self::Result {
+            self.sender.send(self.name).unwrap();
+            Ok(())
+        }
+    }
+
+    #[actix::test]
+    async fn should_distribute_the_actions_according_to_the_weights() {
+        // Arrange
+        let (sender, mut receiver) = unbounded_channel();
+        let first = FakeExecutor { name: "first", sender: sender.clone() }.start().recipient();
+        let second = FakeExecutor { name: "second", sender }.start().recipient();
+        let round_robin = WeightedRoundRobinActor::start_new(
+            1000,
+            vec![(first, 3), (second, 1)],
+            Arc::new(ActionMeter::new("test")),
+        );
+
+        // Act
+        for _ in 0..400 {
+            round_robin
+                .send(ActionMessage(TracedAction::from(Action::new("icinga2")), None))
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        // Assert
+        let mut first_count = 0;
+        let mut second_count = 0;
+        for _ in 0..400 {
+            match receiver.recv().await.unwrap() {
+                "first" => first_count += 1,
+                _ => second_count += 1,
+            }
+        }
+        assert_eq!(300, first_count);
+        assert_eq!(100, second_count);
+    }
+
+    #[actix::test]
+    async fn should_interleave_the_selected_recipients() {
+        // Arrange
+        let (sender, _receiver) = unbounded_channel();
+        let recipients = vec![
+            (FakeExecutor { name: "first", sender: sender.clone() }.start().recipient(), 3),
+            (FakeExecutor { name: "second", sender: sender.clone() }.start().recipient(), 1),
+            (FakeExecutor { name: "third", sender }.start().recipient(), 0),
+        ];
+        let mut round_robin =
+            WeightedRoundRobinActor::new(recipients, Arc::new(ActionMeter::new("test")));
+
+        // Act
+        let selected: Vec<usize> = (0..8).filter_map(|_| round_robin.next()).collect();
+
+        // Assert
+        assert_eq!(vec![0, 0, 1, 0, 0, 0, 1, 0], selected);
+    }
+
+    #[actix::test]
+    async fn should_fail_if_all_the_weights_are_zero() {
+        // Arrange
+        let (sender, _receiver) = unbounded_channel();
+        let recipient = FakeExecutor { name: "first", sender }.start().recipient();
+        let round_robin = WeightedRoundRobinActor::start_new(
+            10,
+            vec![(recipient, 0)],
+            Arc::new(ActionMeter::new("test")),
+        );
+
+        // Act
+        let result =
+            round_robin.send(ActionMessage(TracedAction::from(Action::new("icinga2")), None)).await;
+
+        // Assert
+        assert!(matches!(result, Ok(Err(ExecutorError::SenderError { .. }))));
+    }
+}
@@ -0,0 +1,322 @@
+use crate::actor::dispatcher::ExecutorRegistry;
+use actix_web::web::{Data, Json};
+use actix_web::{web, HttpRequest, Scope};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tornado_common::actors::message::ActionMessage;
+use tornado_common::dead_letter::{DeadLetter, DeadLetterStore};
+use tornado_common::TornadoError;
+use tornado_common_api::TracedAction;
+use tornado_engine_api::auth::Permission;
+use tornado_engine_api::error::ApiError;
+use tornado_engine_api::model::ApiData;
+
+/// The max number of dead letters that are re-submitted before removing them from the store.
+const REPLAY_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayRequest {
+    /// The ids of the dead letters to replay. If None, all the dead letters are replayed.
+    #[serde(default)]
+    pub ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayResponse {
+    /// The ids of the dead letters whose actions were re-submitted to the executors
+    pub replayed: Vec<String>,
+}
+
+/// Re-submits the dead-lettered actions to their executors.
+/// The actions that fail again are added back to the store by their executors.
+#[derive(Clone)]
+pub struct DeadLetterApi {
+    store: Arc<DeadLetterStore>,
+    executors: ExecutorRegistry,
+}
+
+impl DeadLetterApi {
+    pub fn new(store: Arc<DeadLetterStore>, executors: ExecutorRegistry) -> Self {
+        Self { store, executors }
+    }
+
+    pub async fn list(&self) -> Result<Vec<DeadLetter>, TornadoError> {
+        self.store.list().await
+    }
+
+    /// Replays the dead letters with the given ids, or all of them if `ids` is None.
+    /// The dead letters are replayed in batches, and each dead letter is removed from the store
+    ///   only after its action is accepted by the executor; the others are kept in the store.
+    /// Returns the ids of the replayed dead letters.
+    pub async fn replay(&self, ids: Option<&[String]>) -> Result<Vec<String>, TornadoError> {
+        let dead_letters: Vec<DeadLetter> = self
+            .store
+            .list()
+            .await?
+            .into_iter()
+            .filter(|dead_letter| ids.map_or(true, |ids| ids.contains(&dead_letter.id)))
+            .collect();
+
+        let mut replayed = vec![];
+        for batch in dead_letters.chunks(REPLAY_BATCH_SIZE) {
+            let mut enqueued = vec![];
+            for dead_letter in batch {
+                match self.enqueue(dead_letter).await {
+                    Ok(()) => {
+                        info!("DeadLetterApi - Replayed dead letter [{}]", dead_letter.id);
+                        enqueued.push(dead_letter.id.clone());
+                    }
+                    Err(err) => warn!(
+                        "DeadLetterApi - Cannot replay dead letter [{}]. It is kept in the store. Err: {}",
+                        dead_letter.id, err
+                    ),
+                }
+            }
+            replayed.extend_from_slice(&enqueued);
+            self.store.remove(enqueued).await?;
+        }
+        Ok(replayed)
+    }
+
+    /// Sends the action of the dead letter to its executor and waits until it is accepted.
+    async fn enqueue(&self, dead_letter: &DeadLetter) -> Result<(), String> {
+        let executor = self.executors.executor(&dead_letter.action.id).ok_or_else(|| {
+            format!("There are not executors for action id [{}]", dead_letter.action.id)
+        })?;
+        let message = ActionMessage(
+            TracedAction {
+                action: Arc::new(dead_letter.action.clone()),
+                span: tracing::Span::current(),
+            },
+            None,
+        );
+        match executor.send(message).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(err)) => Err(format!("{:?}", err)),
+            Err(err) => Err(format!("{:?}", err)),
+        }
+    }
+}
+
+pub fn dead_letter_endpoints(scope: Scope, data: ApiData<DeadLetterApi>) -> Scope {
+    scope
+        .app_data(Data::new(data))
+        .service(web::resource("/dead_letters").route(web::get().to(get_dead_letters)))
+        .service(web::resource("/replay").route(web::post().to(replay)))
+}
+
+async fn get_dead_letters(
+    req: HttpRequest,
+    data: Data<ApiData<DeadLetterApi>>,
+) -> actix_web::Result<Json<Vec<DeadLetter>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req)?;
+    auth_ctx.has_permission(&Permission::ActionsReplay)?;
+    let dead_letters = data.api.list().await.map_err(to_api_error)?;
+    Ok(Json(dead_letters))
+}
+
+async fn replay(
+    req: HttpRequest,
+    data: Data<ApiData<DeadLetterApi>>,
+    body: Json<ReplayRequest>,
+) -> actix_web::Result<Json<ReplayResponse>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req)?;
+    auth_ctx.has_permission(&Permission::ActionsReplay)?;
+    let replayed = data.api.replay(body.ids.as_deref()).await.map_err(to_api_error)?;
+    Ok(Json(ReplayResponse { replayed }))
+}
+
+fn to_api_error(err: TornadoError) -> ApiError {
+    ApiError::InternalServerError { cause: format!("{:?}", err) }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use actix_web::{http::header, http::StatusCode, test, App};
+    use std::collections::BTreeMap;
+    use std::rc::Rc;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+    use tornado_common::actors::command::CommandExecutorActor;
+    use tornado_common::command::retry::{BackoffPolicy, RetryCommand, RetryPolicy, RetryStrategy};
+    use tornado_common::command::StatelessExecutorCommand;
+    use tornado_common::metrics::ActionMeter;
+    use tornado_common::shutdown::ShutdownCoordinator;
+    use tornado_common_api::Action;
+    use tornado_engine_api::auth::AuthService;
+    use tornado_engine_api_dto::auth::Auth;
+    use tornado_executor_common::{ExecutorError, StatelessExecutor};
+
+    struct AlwaysFailExecutor {
+        sender: UnboundedSender<Arc<Action>>,
+    }
+
+    impl std::fmt::Display for AlwaysFailExecutor {
+        fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+            fmt.write_str("AlwaysFailExecutor")
+        }
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl StatelessExecutor for AlwaysFailExecutor {
+        async fn execute(&self, action: Arc<Action>) -> Result<(), ExecutorError> {
+            self.sender.send(action).unwrap();
+            Err(ExecutorError::ActionExecutionError {
+                message: "always fail".to_owned(),
+                can_retry: true,
+                code: None,
+                data: Default::default(),
+            })
+        }
+    }
+
+    fn test_auth_service() -> AuthService {
+        let mut permission_roles_map = BTreeMap::new();
+        permission_roles_map.insert(Permission::ActionsReplay, vec!["replay".to_owned()]);
+        AuthService::new(Arc::new(permission_roles_map))
+    }
+
+    fn auth_header(role: &str) -> (header::HeaderName, String) {
+        (
+            header::AUTHORIZATION,
+            AuthService::auth_to_token_header(&Auth::new("user", vec![role])).unwrap(),
+        )
+    }
+
+    #[actix_rt::test]
+    async fn should_replay_the_dead_letters_through_the_executor() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let store = Arc::new(DeadLetterStore::new(tempdir.path().join("dead_letters.jsonl")));
+        let dead_letter_id = store.add(&Action::new("exhausted"), "previous error").await.unwrap();
+
+        let (sender, mut receiver) = unbounded_channel();
+        let action_meter = Arc::new(ActionMeter::new("test_action_meter"));
+        let executor = CommandExecutorActor::start_new_with_dead_letter_store(
+            10,
+            Rc::new(RetryCommand::new(
+                RetryStrategy {
+                    retry_policy: RetryPolicy::MaxRetries { retries: 0 },
+                    backoff_policy: BackoffPolicy::None,
+                },
+                StatelessExecutorCommand::new(action_meter.clone(), AlwaysFailExecutor { sender }),
+            )),
+            action_meter,
+            ShutdownCoordinator::new(),
+            Some(store.clone()),
+        );
+        let mut executors = ExecutorRegistry::default();
+        executors.register("exhausted", executor.recipient());
+
+        let srv = test::init_service(App::new().service(dead_letter_endpoints(
+            web::scope("/v1/actions"),
+            ApiData {
+                auth: test_auth_service(),
+                api: DeadLetterApi::new(store.clone(), executors),
+            },
+        )))
+        .await;
+
+        // Act
+        let request = test::TestRequest::post()
+            .insert_header(auth_header("replay"))
+            .uri("/v1/actions/replay")
+            .set_json(&ReplayRequest { ids: Some(vec![dead_letter_id.clone()]) })
+            .to_request();
+        let response: ReplayResponse = test::call_and_read_body_json(&srv, request).await;
+
+        // Assert
+        assert_eq!(vec![dead_letter_id.clone()], response.replayed);
+        assert_eq!("exhausted", receiver.recv().await.unwrap().id);
+
+        // The replayed action failed again, so it is back in the store with a new id
+        let mut dead_letters = store.list().await.unwrap();
+        while dead_letters.is_empty() {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            dead_letters = store.list().await.unwrap();
+        }
+        assert_eq!(1, dead_letters.len());
+        assert_eq!("exhausted", dead_letters[0].action.id);
+        assert_ne!(dead_letter_id, dead_letters[0].id);
+    }
+
+    #[actix_rt::test]
+    async fn should_require_the_actions_replay_permission() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let store = Arc::new(DeadLetterStore::new(tempdir.path().join("dead_letters.jsonl")));
+        store.add(&Action::new("exhausted"), "").await.unwrap();
+
+        let srv = test::init_service(App::new().service(dead_letter_endpoints(
+            web::scope("/v1/actions"),
+            ApiData {
+                auth: test_auth_service(),
+                api: DeadLetterApi::new(store.clone(), ExecutorRegistry::default()),
+            },
+        )))
+        .await;
+
+        // Act
+        let request = test::TestRequest::post()
+            .insert_header(auth_header("view"))
+            .uri("/v1/actions/replay")
+            .set_json(&ReplayRequest::default())
+            .to_request();
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!(1, store.list().await.unwrap().len());
+    }
+
+    #[actix_rt::test]
+    async fn should_keep_the_dead_letters_that_cannot_be_enqueued() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let store = Arc::new(DeadLetterStore::new(tempdir.path().join("dead_letters.jsonl")));
+        let dead_letter_id = store.add(&Action::new("no_executor"), "").await.unwrap();
+        let api = DeadLetterApi::new(store.clone(), ExecutorRegistry::default());
+
+        // Act
+        let replayed = api.replay(None).await.unwrap();
+
+        // Assert
+        assert!(replayed.is_empty());
+        let dead_letters = store.list().await.unwrap();
+        assert_eq!(1, dead_letters.len());
+        assert_eq!(dead_letter_id, dead_letters[0].id);
+    }
+
+    #[actix_rt::test]
+    async fn should_return_the_dead_letters() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let store = Arc::new(DeadLetterStore::new(tempdir.path().join("dead_letters.jsonl")));
+        let dead_letter_id = store.add(&Action::new("exhausted"), "an error").await.unwrap();
+
+        let srv = test::init_service(App::new().service(dead_letter_endpoints(
+            web::scope("/v1/actions"),
+            ApiData {
+                auth: test_auth_service(),
+                api: DeadLetterApi::new(store, ExecutorRegistry::default()),
+            },
+        )))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get()
+            .insert_header(auth_header("replay"))
+            .uri("/v1/actions/dead_letters")
+            .to_request();
+        let dead_letters: Vec<DeadLetter> = test::call_and_read_body_json(&srv, request).await;
+
+        // Assert
+        assert_eq!(1, dead_letters.len());
+        assert_eq!(dead_letter_id, dead_letters[0].id);
+        assert_eq!("an error", dead_letters[0].error);
+    }
+}
@@ -2,11 +2,13 @@ use crate::actor::matcher::{
     EventMessageAndConfigWithReply, EventMessageWithReply, MatcherActor, ReconfigureMessage,
 };
 use crate::monitoring::metrics::{TornadoMeter, EVENT_SOURCE_LABEL_KEY, EVENT_TYPE_LABEL_KEY};
+use crate::rate_limit::TenantRateLimiter;
 use actix::Addr;
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::SystemTime;
+use tornado_common_api::Value;
 use tornado_engine_api::config::api::ConfigApiHandler;
 use tornado_engine_api::error::ApiError;
 use tornado_engine_api::event::api::{EventApiHandler, SendEventRequest};
@@ -14,12 +16,14 @@ use tornado_engine_matcher::config::operation::NodeFilter;
 use tornado_engine_matcher::config::MatcherConfig;
 use tornado_engine_matcher::model::ProcessedEvent;
 
+pub mod dead_letter;
 pub mod runtime_config;
 
 #[derive(Clone)]
 pub struct MatcherApiHandler {
     matcher: Addr<MatcherActor>,
     meter: Arc<TornadoMeter>,
+    tenant_rate_limiter: Option<Arc<TenantRateLimiter>>,
 }
 
 #[async_trait(?Send)]
@@ -37,10 +41,11 @@ impl EventApiHandler for MatcherApiHandler {
             EVENT_TYPE_LABEL_KEY.string(event.event.event_type.to_owned()),
         ];
 
+        let event_value = self.accept_event(&event)?;
         let request = self
             .matcher
             .send(EventMessageWithReply {
-                event: event.to_event_with_metadata(),
+                event: event_value,
                 config_filter,
                 process_type: event.process_type,
                 include_metadata: true,
@@ -67,10 +72,11 @@ impl EventApiHandler for MatcherApiHandler {
             EVENT_TYPE_LABEL_KEY.string(event.event.event_type.to_owned()),
         ];
 
+        let event_value = self.accept_event(&event)?;
         let request = self
             .matcher
             .send(EventMessageAndConfigWithReply {
-                event: event.to_event_with_metadata(),
+                event: event_value,
                 process_type: event.process_type,
                 matcher_config,
                 include_metadata: true,
@@ -100,7 +106,29 @@ impl ConfigApiHandler for MatcherApiHandler {
 
 impl MatcherApiHandler {
     pub fn new(matcher: Addr<MatcherActor>, meter: Arc<TornadoMeter>) -> MatcherApiHandler {
-        MatcherApiHandler { matcher, meter }
+        MatcherApiHandler { matcher, meter, tenant_rate_limiter: None }
+    }
+
+    /// Rejects the events exceeding the rate limit of their tenant
+    pub fn with_tenant_rate_limiter(
+        mut self,
+        tenant_rate_limiter: Option<Arc<TenantRateLimiter>>,
+    ) -> Self {
+        self.tenant_rate_limiter = tenant_rate_limiter;
+        self
+    }
+
+    /// Returns the event to process, or an error if it exceeds the rate limit of its tenant.
+    fn accept_event(&self, event: &SendEventRequest) -> Result<Value, ApiError> {
+        let event = event.to_event_with_metadata();
+        match &self.tenant_rate_limiter {
+            Some(rate_limiter) if !rate_limiter.accept(&event) => {
+                Err(ApiError::TooManyRequestsError {
+                    cause: "The event exceeded the rate limit of its tenant".to_owned(),
+                })
+            }
+            _ => Ok(event),
+        }
     }
 }
 
@@ -108,6 +136,7 @@ impl MatcherApiHandler {
 mod test {
     use super::*;
     use crate::actor::dispatcher::{ActixEventBus, DispatcherActor};
+    use crate::rate_limit::TenantRateLimitsConfig;
     use fs_extra::dir::{copy, CopyOptions};
     use serde_json::Map;
     use std::collections::HashMap;
@@ -129,21 +158,22 @@ mod test {
         let path = "./config/rules.d";
         let config_manager = Arc::new(FsMatcherConfigManagerV2::new(path, ""));
 
-        let event_bus = Arc::new(ActixEventBus { callback: |_| {} });
+        let event_bus = Arc::new(ActixEventBus { callback: |_| {}, max_action_size_bytes: None });
 
         let dispatcher_addr =
             DispatcherActor::start_new(1, Dispatcher::build(event_bus.clone()).unwrap());
 
-        let matcher_addr = MatcherActor::start(
+        let matcher_addr = MatcherActor::start_with_options(
             dispatcher_addr.clone().recipient(),
             config_manager,
             47,
             Default::default(),
+            Default::default(),
         )
         .await
         .unwrap();
 
-        let api = MatcherApiHandler { matcher: matcher_addr, meter: Default::default() };
+        let api = MatcherApiHandler::new(matcher_addr, Default::default());
 
         let send_event_request = SendEventRequest {
             process_type: ProcessType::SkipActions,
@@ -161,6 +191,63 @@ mod test {
         assert_eq!(Some("test-type"), res.unwrap().event.event_type());
     }
 
+    #[actix_rt::test]
+    async fn should_reject_the_events_exceeding_the_rate_limit_of_their_tenant() {
+        // Arrange
+        let path = "./config/rules.d";
+        let config_manager = Arc::new(FsMatcherConfigManagerV2::new(path, ""));
+
+        let event_bus = Arc::new(ActixEventBus { callback: |_| {}, max_action_size_bytes: None });
+
+        let dispatcher_addr =
+            DispatcherActor::start_new(1, Dispatcher::build(event_bus.clone()).unwrap());
+
+        let matcher_addr = MatcherActor::start_with_options(
+            dispatcher_addr.clone().recipient(),
+            config_manager,
+            47,
+            Default::default(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        let rate_limiter = TenantRateLimiter::new(
+            TenantRateLimitsConfig {
+                events_per_sec_by_tenant: HashMap::new(),
+                default_events_per_sec: Some(1.0),
+                events_per_sec_without_tenant: None,
+            },
+            Default::default(),
+        );
+        let api = MatcherApiHandler::new(matcher_addr, Default::default())
+            .with_tenant_rate_limiter(Some(Arc::new(rate_limiter)));
+
+        let mut event = Event::new("test-type");
+        event.metadata.insert("tenant_id".to_owned(), Value::String("alpha".to_owned()));
+
+        let mut config_filter = HashMap::new();
+        config_filter.insert(ROOT_NODE_NAME.to_owned(), NodeFilter::AllChildren);
+
+        // Act
+        let first_res = api
+            .send_event_to_current_config(
+                config_filter.clone(),
+                SendEventRequest { process_type: ProcessType::SkipActions, event: event.clone() },
+            )
+            .await;
+        let second_res = api
+            .send_event_to_current_config(
+                config_filter,
+                SendEventRequest { process_type: ProcessType::SkipActions, event },
+            )
+            .await;
+
+        // Assert
+        assert!(first_res.is_ok());
+        assert!(matches!(second_res, Err(ApiError::TooManyRequestsError { .. })));
+    }
+
     #[actix_rt::test]
     async fn should_reconfigure_the_matcher_and_send_new_config() {
         // Arrange
@@ -178,21 +265,22 @@ mod test {
             .await
             .unwrap();
 
-        let event_bus = Arc::new(ActixEventBus { callback: |_| {} });
+        let event_bus = Arc::new(ActixEventBus { callback: |_| {}, max_action_size_bytes: None });
 
         let dispatcher_addr =
             DispatcherActor::start_new(1, Dispatcher::build(event_bus.clone()).unwrap());
 
-        let matcher_addr = MatcherActor::start(
+        let matcher_addr = MatcherActor::start_with_options(
             dispatcher_addr.clone().recipient(),
             config_manager.clone(),
             47,
             Default::default(),
+            Default::default(),
         )
         .await
         .unwrap();
 
-        let api = MatcherApiHandler { matcher: matcher_addr, meter: Default::default() };
+        let api = MatcherApiHandler::new(matcher_addr, Default::default());
 
         // Act
         let res = config_manager.get_config().await;
@@ -226,21 +314,22 @@ mod test {
         let path = "./config/rules.d";
         let config_manager = Arc::new(FsMatcherConfigManagerV2::new(path, ""));
 
-        let event_bus = Arc::new(ActixEventBus { callback: |_| {} });
+        let event_bus = Arc::new(ActixEventBus { callback: |_| {}, max_action_size_bytes: None });
 
         let dispatcher_addr =
             DispatcherActor::start_new(1, Dispatcher::build(event_bus.clone()).unwrap());
 
-        let matcher_addr = MatcherActor::start(
+        let matcher_addr = MatcherActor::start_with_options(
             dispatcher_addr.clone().recipient(),
             config_manager,
             47,
             Default::default(),
+            Default::default(),
         )
         .await
         .unwrap();
 
-        let api = MatcherApiHandler { matcher: matcher_addr, meter: Default::default() };
+        let api = MatcherApiHandler::new(matcher_addr, Default::default());
 
         let send_event_request = SendEventRequest {
             process_type: ProcessType::SkipActions,
@@ -262,6 +351,7 @@ mod test {
                     }),
                     with: HashMap::new(),
                 },
+                deduplication: None,
             }],
         };
 
@@ -287,21 +377,22 @@ mod test {
         let path = "./config/rules.d";
         let config_manager = Arc::new(FsMatcherConfigManagerV2::new(path, ""));
 
-        let event_bus = Arc::new(ActixEventBus { callback: |_| {} });
+        let event_bus = Arc::new(ActixEventBus { callback: |_| {}, max_action_size_bytes: None });
 
         let dispatcher_addr =
             DispatcherActor::start_new(1, Dispatcher::build(event_bus.clone()).unwrap());
 
-        let matcher_addr = MatcherActor::start(
+        let matcher_addr = MatcherActor::start_with_options(
             dispatcher_addr.clone().recipient(),
             config_manager,
             47,
             Default::default(),
+            Default::default(),
         )
         .await
         .unwrap();
 
-        let api = MatcherApiHandler { matcher: matcher_addr, meter: Default::default() };
+        let api = MatcherApiHandler::new(matcher_addr, Default::default());
 
         let mut event = Event::new("test-type");
 
@@ -1,4 +1,4 @@
-use crate::config::parse_config_files;
+use crate::config::{build_config, parse_config_files};
 use tornado_engine_matcher::matcher::Matcher;
 
 pub async fn check(
@@ -7,9 +7,15 @@ pub async fn check(
     drafts_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
     println!("Check Tornado configuration");
+    let global_config = build_config(config_dir)?;
+    // The rules are validated with the same settings of the daemon
+    let matcher_settings = global_config.tornado.daemon.matcher_settings();
     let configs = parse_config_files(config_dir, rules_dir, drafts_dir)?;
-    let _matcher =
-        configs.matcher_config.get_config().await.and_then(|config| Matcher::build(&config))?;
+    let _matcher = configs
+        .matcher_config
+        .get_config()
+        .await
+        .and_then(|config| Matcher::build_with_settings(&config, &matcher_settings))?;
     println!("The configuration is correct.");
     Ok(())
 }
@@ -97,6 +97,7 @@ pub mod test {
     use super::*;
     use crate::command::upgrade_rules::test::prepare_temp_dirs;
     use tornado_common_api::Value;
+    use tornado_engine_matcher::config::nodes::FilterMode;
     use tornado_engine_matcher::config::rule::{Constraint, Operator, Rule};
     use tornado_engine_matcher::config::Defaultable;
 
@@ -114,6 +115,8 @@ pub mod test {
             description: "my new filter".to_string(),
             active: true,
             filter: Defaultable::Default {},
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
         };
 
         // Act
@@ -155,6 +158,8 @@ pub mod test {
                 first: Value::String("1".to_owned()),
                 second: Value::String("1".to_owned()),
             }),
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
         };
 
         // Act
@@ -201,6 +206,8 @@ pub mod test {
                 first: Value::String("1".to_owned()),
                 second: Value::String("1".to_owned()),
             }),
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
         };
 
         // Act
@@ -247,6 +254,8 @@ pub mod test {
                 first: Value::String("${event.metadata.tenant_id}".to_owned()),
                 second: Value::String("alpha".to_owned()),
             }),
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
         };
 
         // Act
@@ -268,6 +277,7 @@ pub mod test {
                 active: false,
                 constraint: Constraint { where_operator: None, with: Default::default() },
                 actions: vec![],
+                deduplication: None,
             }],
         };
 
@@ -276,6 +286,8 @@ pub mod test {
             description: "my new filter".to_string(),
             active: true,
             filter: Defaultable::Default {},
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
         };
 
         // Act
@@ -293,6 +305,8 @@ pub mod test {
                 description: "".to_string(),
                 active: true,
                 filter: Defaultable::Default {},
+                mode: FilterMode::AllMatches,
+                continue_on_match: true,
             },
             nodes: vec![],
         };
@@ -303,6 +317,8 @@ pub mod test {
             description: "my new filter".to_string(),
             active: true,
             filter: Defaultable::Default {},
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
         };
 
         // Act
@@ -318,7 +334,9 @@ pub mod test {
                     Filter {
                         description: "".to_string(),
                         active: true,
-                        filter: Defaultable::Default {}
+                        filter: Defaultable::Default {},
+                        mode: FilterMode::AllMatches,
+                        continue_on_match: true,
                     }
                 );
                 assert_eq!(nodes.len(), 1);
@@ -355,6 +373,8 @@ pub mod test {
                 first: Value::String("1".to_owned()),
                 second: Value::String("1".to_owned()),
             }),
+            mode: FilterMode::AllMatches,
+            continue_on_match: true,
         };
 
         // Act
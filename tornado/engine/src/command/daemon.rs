@@ -1,31 +1,50 @@
-use crate::actor::dispatcher::{ActixEventBus, DispatcherActor};
+use crate::actor::bus_executor::{BusExecutorActor, BusExecutorActorInitMessage};
+use crate::actor::config_watcher::ConfigWatcherActor;
+use crate::actor::dispatcher::{ActixEventBus, DispatcherActor, ExecutorRegistry};
 use crate::actor::foreach::{ForEachExecutorActor, ForEachExecutorActorInitMessage};
-use crate::actor::matcher::{EventMessage, MatcherActor};
+use crate::actor::matcher::{EventMessage, MatcherActor, MatcherActorOptions, ProcessingBudget};
+use crate::actor::round_robin::WeightedRoundRobinActor;
+use crate::api::dead_letter::{dead_letter_endpoints, DeadLetterApi};
 use crate::api::runtime_config::RuntimeConfigApiHandlerImpl;
 use crate::api::MatcherApiHandler;
 use crate::config;
-use crate::config::build_config;
-use crate::monitoring::endpoint::monitoring_endpoints;
+use crate::config::{build_config, ExecutorInstance};
+use crate::exit::{exit_with_reason, ShutdownError, ShutdownReason};
+use crate::monitoring::endpoint::{monitoring_endpoints, start_metrics_server, DependenciesHealth};
 use crate::monitoring::metrics::{
     TornadoMeter, EVENT_SOURCE_LABEL_KEY, EVENT_TYPE_LABEL_KEY, TORNADO_APP,
 };
+use crate::monitoring::rule_stats::rule_stats_endpoints;
+use crate::rate_limit::TenantRateLimiter;
+use actix::Recipient;
 use actix_web::middleware::Logger;
 use actix_web::{web, App, HttpServer};
+use config_rs::ConfigError;
 use log::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::json;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 use tornado_common::actors::command::CommandExecutorActor;
 use tornado_common::actors::json_event_reader::JsonEventReaderActor;
-use tornado_common::actors::message::TornadoCommonActorError;
-use tornado_common::actors::nats_subscriber::subscribe_to_nats;
-use tornado_common::actors::tcp_server::listen_to_tcp;
+use tornado_common::actors::message::{ActionMessage, FlushMessage};
+use tornado_common::actors::nats_publisher::deserialize_event;
+use tornado_common::actors::nats_subscriber::subscribe_to_nats_with_health;
+use tornado_common::actors::tcp_server::{listen_to_tcp, TcpServerOptions};
 use tornado_common::command::pool::{CommandMutPool, CommandPool};
-use tornado_common::command::retry::RetryCommand;
+use tornado_common::command::retry::{RetryBudget, RetryCommand};
 use tornado_common::command::{StatefulExecutorCommand, StatelessExecutorCommand};
+use tornado_common::dead_letter::DeadLetterStore;
+use tornado_common::health::ConnectionHealth;
 use tornado_common::metrics::{ActionMeter, ACTION_ID_LABEL_KEY};
+use tornado_common::shutdown::{ShutdownCoordinator, DEFAULT_DRAIN_TIMEOUT};
+use tornado_common::signature::EventSigner;
 use tornado_common::TornadoError;
-use tornado_common_api::Event;
 use tornado_common_logger::elastic_apm::DEFAULT_APM_SERVER_CREDENTIALS_FILENAME;
 use tornado_common_logger::opentelemetry_logger::TelemetryContextExtractor;
 use tornado_common_logger::setup_logger;
@@ -33,19 +52,29 @@ use tornado_common_metrics::opentelemetry::sdk::propagation::TraceContextPropaga
 use tornado_common_metrics::Metrics;
 use tornado_engine_api::auth::auth_v2::AuthServiceV2;
 use tornado_engine_api::auth::{roles_map_to_permissions_map, AuthService};
+use tornado_engine_api::concurrency::ConcurrencyLimit;
 use tornado_engine_api::config::api::ConfigApi;
 use tornado_engine_api::event::api_v2::EventApiV2;
 use tornado_engine_api::model::{ApiData, ApiDataV2};
 use tornado_engine_api::runtime_config::api::RuntimeConfigApi;
 use tornado_engine_matcher::dispatcher::Dispatcher;
+use tornado_executor_director::config::DirectorClientConfig;
+use tornado_executor_icinga2::config::Icinga2ClientConfig;
 use tracing_actix_web::TracingLogger;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 pub const ACTION_ID_SMART_MONITORING_CHECK_RESULT: &str = "smart_monitoring_check_result";
 pub const ACTION_ID_FOREACH: &str = "foreach";
 pub const ACTION_ID_LOGGER: &str = "logger";
+pub const ACTION_ID_MERGE: &str = "merge";
+pub const ACTION_ID_SCHEDULED: &str = "scheduled";
+
+const DEFAULT_RULES_WATCHER_DEBOUNCE_MS: u64 = 500;
 
 // 64*1024*1024 byte = 64MB limit
+/// The ids of the executors that can be started as multiple instances
+const EXECUTOR_IDS_WITH_INSTANCES: [&str; 2] = ["icinga2", "director"];
+
 const MAX_JSON_PAYLOAD_SIZE: usize = 67_108_860;
 
 pub async fn daemon(
@@ -53,7 +82,8 @@ pub async fn daemon(
     rules_dir: &str,
     drafts_dir: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-    let mut global_config = build_config(config_dir)?;
+    let mut global_config =
+        build_config(config_dir).map_err(ShutdownError::with_reason(ShutdownReason::ConfigLoad))?;
     let apm_server_api_credentials_filepath =
         format!("{}/{}", config_dir, DEFAULT_APM_SERVER_CREDENTIALS_FILENAME);
     // Get the result and log the error later because the logger is not available yet
@@ -67,14 +97,17 @@ pub async fn daemon(
         warn!("{:?}", apm_credentials_read_error);
     }
 
-    let configs = config::parse_config_files(config_dir, rules_dir, drafts_dir)?;
+    let configs = config::parse_config_files(config_dir, rules_dir, drafts_dir)
+        .map_err(ShutdownError::with_reason(ShutdownReason::ConfigLoad))?;
 
     // start system
+    let daemon_config = global_config.tornado.daemon;
+
     let metrics = Arc::new(Metrics::new(TORNADO_APP));
-    let tornado_meter = Arc::new(TornadoMeter::default());
-    let action_meter = Arc::new(ActionMeter::new(TORNADO_APP));
+    let metrics_prefix = daemon_config.metrics_prefix.clone().unwrap_or_default();
+    let tornado_meter = Arc::new(TornadoMeter::new_with_prefix(&metrics_prefix));
+    let action_meter = Arc::new(ActionMeter::new_with_prefix(TORNADO_APP, &metrics_prefix));
 
-    let daemon_config = global_config.tornado.daemon;
     let thread_pool_config = daemon_config.thread_pool_config.clone().unwrap_or_default();
     let threads_per_queue = thread_pool_config.get_threads_count();
     info!(
@@ -85,57 +118,123 @@ pub async fn daemon(
     let retry_strategy = daemon_config.retry_strategy.clone();
     info!("Tornado global retry strategy: {:?}", retry_strategy);
 
+    let retry_budget = daemon_config.retry_budget.as_ref().map(|retry_budget_config| {
+        info!("Tornado global retry budget: {:?}", retry_budget_config);
+        RetryBudget::new(retry_budget_config)
+    });
+
+    let dead_letter_store = daemon_config.dead_letter_store_path.as_ref().map(|path| {
+        info!("The actions that exhaust their retries are written to [{}]", path);
+        Arc::new(DeadLetterStore::new(path))
+    });
+
+    let action_timeouts = daemon_config.action_timeouts.clone();
+    info!("Tornado action timeouts: {:?}", action_timeouts);
+
     let message_queue_size = daemon_config.message_queue_size;
 
+    let shutdown = ShutdownCoordinator::new();
+
     // Start ForEach executor actor
     let foreach_executor_addr = ForEachExecutorActor::start_new(message_queue_size);
 
+    // Start merge executor actor, if configured
+    let merge_executor_addr = configs.merge_executor_config.as_ref().map(|_| {
+        BusExecutorActor::<tornado_executor_merge::MergeExecutor>::start_new(message_queue_size)
+    });
+
+    // Start scheduled executor actor, if configured
+    let scheduled_executor_addr = configs.scheduled_executor_config.as_ref().map(|_| {
+        BusExecutorActor::<tornado_executor_scheduled::ScheduledExecutor>::start_new(
+            message_queue_size,
+        )
+    });
+
     let archive_action_meter = action_meter.clone();
+    // The files of the archive executors, closed when Tornado stops
+    let archive_files = Rc::new(RefCell::new(vec![]));
     // Start archive executor actor
     let archive_executor_addr = {
         let archive_config = configs.archive_executor_config.clone();
-        CommandExecutorActor::start_new(
+        let archive_files = archive_files.clone();
+        CommandExecutorActor::start_new_with_dead_letter_store(
             message_queue_size,
-            Rc::new(RetryCommand::new(
+            Rc::new(RetryCommand::new_with_retry_budget(
                 retry_strategy.clone(),
+                retry_budget.clone(),
                 CommandMutPool::new(1, move || {
-                    StatefulExecutorCommand::new(
-                        archive_action_meter.clone(),
-                        tornado_executor_archive::ArchiveExecutor::new(&archive_config),
-                    )
+                    let archive_executor =
+                        tornado_executor_archive::ArchiveExecutor::new(&archive_config)
+                            .expect("Cannot start the ArchiveExecutor Executor");
+                    archive_files.borrow_mut().push(archive_executor.files());
+                    StatefulExecutorCommand::new(archive_action_meter.clone(), archive_executor)
                 }),
             )),
             action_meter.clone(),
+            shutdown.clone(),
+            dead_letter_store.clone(),
         )
     };
 
     // Start script executor actor
     let script_executor_addr = {
-        let executor = tornado_executor_script::ScriptExecutor::new();
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
+        let executor = match &daemon_config.script_retryable_exit_codes {
+            Some(retryable_exit_codes) => {
+                tornado_executor_script::ScriptExecutor::with_retryable_exit_codes(
+                    retryable_exit_codes.iter().copied(),
+                )
+            }
+            None => tornado_executor_script::ScriptExecutor::new(),
+        };
+        let executor = match &daemon_config.script_allowed_paths {
+            Some(allowed_paths) => executor.with_allowed_paths(allowed_paths),
+            None => executor,
+        };
+        let stateless_executor_command = StatelessExecutorCommand::new_with_timeouts(
+            action_meter.clone(),
+            executor,
+            action_timeouts.clone(),
+        );
+        CommandExecutorActor::start_new_with_dead_letter_store(
             message_queue_size,
-            Rc::new(RetryCommand::new(
+            Rc::new(RetryCommand::new_with_retry_budget(
                 retry_strategy.clone(),
+                retry_budget.clone(),
                 CommandPool::new(threads_per_queue, stateless_executor_command),
             )),
             action_meter.clone(),
+            shutdown.clone(),
+            dead_letter_store.clone(),
         )
     };
 
     // Start logger executor actor
     let logger_executor_addr = {
-        let executor = tornado_executor_logger::LoggerExecutor::new();
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
+        let executor = match &daemon_config.logger_executor_file_path {
+            Some(file_path) => {
+                tornado_executor_logger::LoggerExecutor::new_with_file_sink(file_path)
+            }
+            None => tornado_executor_logger::LoggerExecutor::new(),
+        };
+        let executor = match &daemon_config.logger_executor_message_template {
+            Some(template) => executor.with_message_template(template)?,
+            None => executor,
+        };
+        let stateless_executor_command = StatelessExecutorCommand::new_with_timeouts(
+            action_meter.clone(),
+            executor,
+            action_timeouts.clone(),
+        );
+        CommandExecutorActor::start_new_with_dead_letter_store(
             message_queue_size,
-            Rc::new(RetryCommand::new(
+            Rc::new(RetryCommand::new_with_retry_budget(
                 retry_strategy.clone(),
+                retry_budget.clone(),
                 CommandPool::new(threads_per_queue, stateless_executor_command),
             )),
             action_meter.clone(),
+            shutdown.clone(),
+            dead_letter_store.clone(),
         )
     };
 
@@ -146,145 +245,305 @@ pub async fn daemon(
             tornado_executor_elasticsearch::ElasticsearchExecutor::new(es_authentication)
                 .await
                 .expect("Cannot start the Elasticsearch Executor");
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
+        let stateless_executor_command = StatelessExecutorCommand::new_with_timeouts(
+            action_meter.clone(),
+            executor,
+            action_timeouts.clone(),
+        );
+        CommandExecutorActor::start_new_with_dead_letter_store(
             message_queue_size,
-            Rc::new(RetryCommand::new(
+            Rc::new(RetryCommand::new_with_retry_budget(
                 retry_strategy.clone(),
+                retry_budget.clone(),
                 CommandPool::new(threads_per_queue, stateless_executor_command),
             )),
             action_meter.clone(),
+            shutdown.clone(),
+            dead_letter_store.clone(),
         )
     };
 
+    // Only these executors can be started as multiple instances
+    if let Some(executor_id) = daemon_config
+        .executor_instances
+        .keys()
+        .find(|executor_id| !EXECUTOR_IDS_WITH_INSTANCES.contains(&executor_id.as_str()))
+    {
+        return Err(ShutdownError::with_reason(ShutdownReason::ConfigLoad)(ConfigError::Message(
+            format!(
+                "The [{}] executor cannot be started as multiple instances. Allowed executors: {:?}",
+                executor_id, EXECUTOR_IDS_WITH_INSTANCES
+            ),
+        )));
+    }
+
     // Start icinga2 executor actor
-    let icinga2_executor_addr = {
-        let executor =
-            tornado_executor_icinga2::Icinga2Executor::new(configs.icinga2_executor_config.clone())
+    let icinga2_executor_addr = start_executor_instances(
+        "icinga2",
+        &configs.icinga2_executor_config,
+        &daemon_config.executor_instances,
+        message_queue_size,
+        &action_meter,
+        |icinga2_config: Icinga2ClientConfig| {
+            let executor = tornado_executor_icinga2::Icinga2Executor::new(icinga2_config)
                 .expect("Cannot start the Icinga2Executor Executor");
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
+            let stateless_executor_command = StatelessExecutorCommand::new_with_timeouts(
+                action_meter.clone(),
+                executor,
+                action_timeouts.clone(),
+            );
+            CommandExecutorActor::start_new_with_dead_letter_store(
+                message_queue_size,
+                Rc::new(RetryCommand::new_with_retry_budget(
+                    retry_strategy.clone(),
+                    retry_budget.clone(),
+                    CommandPool::new(threads_per_queue, stateless_executor_command),
+                )),
+                action_meter.clone(),
+                shutdown.clone(),
+                dead_letter_store.clone(),
+            )
+            .recipient()
+        },
+    )
+    .map_err(ShutdownError::with_reason(ShutdownReason::ConfigLoad))?;
+
+    // Start director executor actor
+    let director_executor_addr = start_executor_instances(
+        "director",
+        &configs.director_executor_config,
+        &daemon_config.executor_instances,
+        message_queue_size,
+        &action_meter,
+        |director_client_config: DirectorClientConfig| {
+            let executor = tornado_executor_director::DirectorExecutor::new(director_client_config)
+                .expect("Cannot start the DirectorExecutor Executor");
+            let stateless_executor_command = StatelessExecutorCommand::new_with_timeouts(
+                action_meter.clone(),
+                executor,
+                action_timeouts.clone(),
+            );
+            CommandExecutorActor::start_new_with_dead_letter_store(
+                message_queue_size,
+                Rc::new(RetryCommand::new_with_retry_budget(
+                    retry_strategy.clone(),
+                    retry_budget.clone(),
+                    CommandPool::new(threads_per_queue, stateless_executor_command),
+                )),
+                action_meter.clone(),
+                shutdown.clone(),
+                dead_letter_store.clone(),
+            )
+            .recipient()
+        },
+    )
+    .map_err(ShutdownError::with_reason(ShutdownReason::ConfigLoad))?;
+
+    // Start smart_monitoring_check_result executor actor
+    let smart_monitoring_check_result_executor_addr = {
+        let executor =
+            tornado_executor_smart_monitoring_check_result::SmartMonitoringExecutor::new(
+                configs.icinga2_executor_config.clone(),
+                configs.director_executor_config.clone(),
+            )
+            .expect("Cannot start the SmartMonitoringExecutor Executor");
+        let stateless_executor_command = StatelessExecutorCommand::new_with_timeouts(
+            action_meter.clone(),
+            executor,
+            action_timeouts.clone(),
+        );
+        CommandExecutorActor::start_new_with_dead_letter_store(
             message_queue_size,
-            Rc::new(RetryCommand::new(
+            Rc::new(RetryCommand::new_with_retry_budget(
                 retry_strategy.clone(),
+                retry_budget.clone(),
                 CommandPool::new(threads_per_queue, stateless_executor_command),
             )),
             action_meter.clone(),
+            shutdown.clone(),
+            dead_letter_store.clone(),
         )
     };
 
-    // Start director executor actor
-    let director_client_config = configs.director_executor_config.clone();
-    let director_executor_addr = {
-        let executor =
-            tornado_executor_director::DirectorExecutor::new(director_client_config.clone())
-                .expect("Cannot start the DirectorExecutor Executor");
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
+    // Start mqtt executor actor, if configured
+    let mqtt_executor_addr = configs.mqtt_executor_config.clone().map(|mqtt_config| {
+        info!("Start MQTT executor for broker [{}:{}]", mqtt_config.host, mqtt_config.port);
+        // A single executor instance keeps one connection to the broker shared by all the actions
+        let executor = tornado_executor_mqtt::MqttExecutor::new(mqtt_config);
+        let stateless_executor_command = StatelessExecutorCommand::new_with_timeouts(
+            action_meter.clone(),
+            executor,
+            action_timeouts.clone(),
+        );
+        CommandExecutorActor::start_new_with_dead_letter_store(
             message_queue_size,
-            Rc::new(RetryCommand::new(
+            Rc::new(RetryCommand::new_with_retry_budget(
                 retry_strategy.clone(),
+                retry_budget.clone(),
                 CommandPool::new(threads_per_queue, stateless_executor_command),
             )),
             action_meter.clone(),
+            shutdown.clone(),
+            dead_letter_store.clone(),
         )
-    };
+    });
 
-    // Start smart_monitoring_check_result executor actor
-    let smart_monitoring_check_result_executor_addr = {
-        let executor =
-            tornado_executor_smart_monitoring_check_result::SmartMonitoringExecutor::new(
-                configs.icinga2_executor_config.clone(),
-                configs.director_executor_config.clone(),
-            )
-            .expect("Cannot start the SmartMonitoringExecutor Executor");
-        let stateless_executor_command =
-            StatelessExecutorCommand::new(action_meter.clone(), executor);
-        CommandExecutorActor::start_new(
+    // Start grpc executor actor, if configured
+    let grpc_executor_addr = configs.grpc_executor_config.clone().map(|grpc_config| {
+        let executor = tornado_executor_grpc::GrpcExecutor::new(grpc_config)
+            .expect("Cannot start the GrpcExecutor Executor");
+        let stateless_executor_command = StatelessExecutorCommand::new_with_timeouts(
+            action_meter.clone(),
+            executor,
+            action_timeouts.clone(),
+        );
+        CommandExecutorActor::start_new_with_dead_letter_store(
             message_queue_size,
-            Rc::new(RetryCommand::new(
+            Rc::new(RetryCommand::new_with_retry_budget(
                 retry_strategy.clone(),
+                retry_budget.clone(),
                 CommandPool::new(threads_per_queue, stateless_executor_command),
             )),
             action_meter.clone(),
+            shutdown.clone(),
+            dead_letter_store.clone(),
         )
-    };
+    });
+
+    // Start statsd executor actor, if configured
+    let statsd_executor_addr = configs.statsd_executor_config.clone().map(|statsd_config| {
+        info!("Start StatsD executor for server [{}]", statsd_config.address);
+        // A single executor instance sends all the metrics from the same UDP socket
+        let executor = tornado_executor_statsd::StatsdExecutor::new(statsd_config);
+        let stateless_executor_command = StatelessExecutorCommand::new_with_timeouts(
+            action_meter.clone(),
+            executor,
+            action_timeouts.clone(),
+        );
+        CommandExecutorActor::start_new_with_dead_letter_store(
+            message_queue_size,
+            Rc::new(RetryCommand::new_with_retry_budget(
+                retry_strategy.clone(),
+                retry_budget.clone(),
+                CommandPool::new(threads_per_queue, stateless_executor_command),
+            )),
+            action_meter.clone(),
+            shutdown.clone(),
+            dead_letter_store.clone(),
+        )
+    });
+
+    // Register the executors by the id of the actions they execute
+    let mut executors = ExecutorRegistry::default();
+    executors.register("archive", archive_executor_addr.recipient());
+    executors.register("icinga2", icinga2_executor_addr);
+    executors.register("director", director_executor_addr);
+    executors.register(
+        ACTION_ID_SMART_MONITORING_CHECK_RESULT,
+        smart_monitoring_check_result_executor_addr.recipient(),
+    );
+    executors.register("script", script_executor_addr.recipient());
+    executors.register(ACTION_ID_LOGGER, logger_executor_addr.recipient());
+    executors.register("elasticsearch", elasticsearch_executor_addr.recipient());
+    if let Some(mqtt_executor_addr) = mqtt_executor_addr {
+        executors.register("mqtt", mqtt_executor_addr.recipient());
+    }
+    if let Some(grpc_executor_addr) = grpc_executor_addr {
+        executors.register("grpc", grpc_executor_addr.recipient());
+    }
+    if let Some(statsd_executor_addr) = statsd_executor_addr {
+        executors.register("statsd", statsd_executor_addr.recipient());
+    }
+
+    // Select the executor that receives the actions with no registered executor
+    if let Some(executor_id) = &daemon_config.fallback_executor {
+        executors.set_fallback_executor(executor_id)?;
+        info!("Actions with no registered executor will be sent to the [{}] executor", executor_id);
+    }
+
+    // The foreach, merge and scheduled executors are registered after the selection of the
+    // fallback executor, because they can execute only their own actions
+    executors.register(ACTION_ID_FOREACH, foreach_executor_addr.clone().recipient());
+    if let Some(merge_executor_addr) = &merge_executor_addr {
+        executors.register(ACTION_ID_MERGE, merge_executor_addr.clone().recipient());
+    }
+    if let Some(scheduled_executor_addr) = &scheduled_executor_addr {
+        executors.register(ACTION_ID_SCHEDULED, scheduled_executor_addr.clone().recipient());
+    }
 
     // Configure action dispatcher
-    let foreach_executor_addr_clone = foreach_executor_addr.clone();
     let event_bus = {
+        let executors = executors.clone();
+        let shutdown = shutdown.clone();
         let event_bus = ActixEventBus {
-            callback: move |message| {
+            callback: move |mut message: ActionMessage| {
                 action_meter
                     .actions_received_counter
                     .add(1, &[ACTION_ID_LABEL_KEY.string(message.0.action.id.to_owned())]);
 
-                let send_result = match message.0.action.id.as_ref() {
-                    "archive" => {
-                        archive_executor_addr.try_send(message).map_err(|err| {
-                            format!("Error sending message to 'archive' executor. Err: {:?}", err)
-                        })
-                    }
-                    "icinga2" => {
-                        icinga2_executor_addr.try_send(message).map_err(|err| {
-                            format!("Error sending message to 'icinga2' executor. Err: {:?}", err)
-                        })
-                    }
-                    "director" => {
-                        director_executor_addr.try_send(message).map_err(|err| {
-                            format!("Error sending message to 'director' executor. Err: {:?}", err)
-                        })
-                    }
-                    ACTION_ID_SMART_MONITORING_CHECK_RESULT => {
-                        smart_monitoring_check_result_executor_addr.try_send(message).map_err(|err| {
-                            format!(
-                                "Error sending message to 'smart_monitoring_check_result' executor. Err: {:?}",
-                                err
-                            )
-                        })
-                    }
-                    "script" => {
-                        script_executor_addr.try_send(message).map_err(|err| {
-                            format!("Error sending message to 'script' executor. Err: {:?}", err)
-                        })
-                    }
-                    ACTION_ID_FOREACH => foreach_executor_addr_clone
-                        .try_send(message)
-                        .map_err(|err| {
-                            format!("Error sending message to 'foreach' executor. Err: {:?}", err)
-                        }),
-                    ACTION_ID_LOGGER => {
-                        logger_executor_addr.try_send(message).map_err(|err| {
-                            format!("Error sending message to 'logger' executor. Err: {:?}", err)
-                        })
-                    }
-                    "elasticsearch" => elasticsearch_executor_addr
-                        .try_send(message)
-                        .map_err(|err| {
-                            format!(
-                                "Error sending message to 'elasticsearch' executor. Err: {:?}",
-                                err
-                            )
-                        }),
+                // The action is in flight from when it is enqueued until its execution completes
+                message.1.get_or_insert_with(|| shutdown.action_started());
 
-                    _ => Err(format!("There are not executors for action id [{}]", &message.0.action.id)),
-                };
-                if let Err(error_message) = send_result {
+                if let Err(error_message) = executors.try_send(message) {
                     error!("{}", error_message)
                 }
             },
+            max_action_size_bytes: daemon_config.max_action_size_bytes,
         };
         Arc::new(event_bus)
     };
 
     let event_bus_clone = event_bus.clone();
+    let foreach_max_resolution_depth = daemon_config
+        .foreach_max_resolution_depth
+        .unwrap_or(tornado_executor_foreach::DEFAULT_MAX_RESOLUTION_DEPTH);
     foreach_executor_addr.try_send(ForEachExecutorActorInitMessage {
-        init: move || tornado_executor_foreach::ForEachExecutor::new(event_bus_clone.clone()),
+        init: move || {
+            tornado_executor_foreach::ForEachExecutor::new_with_max_resolution_depth(
+                event_bus_clone.clone(),
+                foreach_max_resolution_depth,
+            )
+        },
     })?;
 
+    if let (Some(merge_executor_addr), Some(merge_config)) =
+        (&merge_executor_addr, &configs.merge_executor_config)
+    {
+        let event_bus = event_bus.clone();
+        let merge_config = merge_config.clone();
+        merge_executor_addr
+            .send(BusExecutorActorInitMessage {
+                init: move || {
+                    tornado_executor_merge::MergeExecutor::new(
+                        event_bus.clone(),
+                        merge_config.clone(),
+                    )
+                },
+            })
+            .await??;
+    }
+
+    if let (Some(scheduled_executor_addr), Some(scheduled_config)) =
+        (&scheduled_executor_addr, &configs.scheduled_executor_config)
+    {
+        // The pending scheduled actions are restored when the executor is built
+        let event_bus = event_bus.clone();
+        let scheduled_config = scheduled_config.clone();
+        scheduled_executor_addr
+            .send(BusExecutorActorInitMessage {
+                init: move || {
+                    tornado_executor_scheduled::ScheduledExecutor::new(
+                        event_bus.clone(),
+                        scheduled_config.clone(),
+                    )
+                },
+            })
+            .await??;
+    }
+
+    let dead_letter_api =
+        dead_letter_store.map(|dead_letter_store| DeadLetterApi::new(dead_letter_store, executors));
+
     // Start dispatcher actor
     let dispatcher_addr = DispatcherActor::start_new(
         message_queue_size,
@@ -292,17 +551,63 @@ pub async fn daemon(
     );
 
     // Start matcher actor
-    let matcher_addr = MatcherActor::start(
+    let matcher_settings = daemon_config.matcher_settings();
+    let processing_budget =
+        daemon_config.event_processing_budget_ms.map(|budget_ms| ProcessingBudget {
+            max_duration: Duration::from_millis(budget_ms),
+            tag_events: daemon_config.tag_events_over_budget.unwrap_or(false),
+        });
+    // The lookup enrichments are applied by the matcher, so that they apply to the events of all the sources
+    let lookup_enrichers = daemon_config
+        .lookup_enrichments
+        .iter()
+        .map(|enrichment| enrichment.build())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ShutdownError::with_reason(ShutdownReason::ConfigLoad))?;
+    let matcher_addr = MatcherActor::start_with_options(
         dispatcher_addr.clone().recipient(),
         configs.matcher_config.clone(),
         message_queue_size,
         tornado_meter.clone(),
+        MatcherActorOptions {
+            processing_budget,
+            event_ttl: daemon_config.event_ttl_ms.map(Duration::from_millis),
+            normalize_events: daemon_config.normalize_events.unwrap_or(false),
+            event_merge_patch: daemon_config.event_merge_patch.clone(),
+            lookup_enrichers,
+            matcher_settings,
+        },
     )
-    .await?;
+    .await
+    .map_err(ShutdownError::with_reason(ShutdownReason::MatcherBuild))?;
+
+    if daemon_config.is_rules_watcher_enabled() {
+        let rules_path = PathBuf::from(format!("{}/{}", config_dir, rules_dir));
+        let debounce = Duration::from_millis(
+            daemon_config.rules_watcher_debounce_ms.unwrap_or(DEFAULT_RULES_WATCHER_DEBOUNCE_MS),
+        );
+        info!(
+            "Watching the rules in [{:?}]. The configuration is reloaded {:?} after the last change",
+            rules_path, debounce
+        );
+        ConfigWatcherActor::start_new(vec![rules_path], debounce, matcher_addr.clone().recipient());
+    }
+
+    let event_signer = daemon_config.event_signature_secret.as_deref().map(EventSigner::new);
+
+    // The rate limits apply to the events of all the sources: NATS, TCP and the REST API
+    let tenant_rate_limiter = daemon_config.tenant_rate_limits.clone().map(|tenant_rate_limits| {
+        Arc::new(TenantRateLimiter::new(tenant_rate_limits, tornado_meter.clone()))
+    });
+
+    let mut dependencies_health = DependenciesHealth::default();
 
     if daemon_config.is_nats_enabled() {
         info!("NATS connection is enabled. Starting it...");
 
+        let nats_health = ConnectionHealth::default();
+        dependencies_health.nats = Some(nats_health.clone());
+
         let nats_config = daemon_config
             .nats
             .clone()
@@ -312,24 +617,39 @@ pub async fn daemon(
         let subject = nats_config.subject.clone();
         let matcher_addr_clone = matcher_addr.clone();
         let nats_extractors = daemon_config.nats_extractors.clone();
+        let nats_signer = event_signer.clone();
+        let nats_rate_limiter = tenant_rate_limiter.clone();
 
         let tornado_meter_nats = tornado_meter.clone();
         let trace_context_propagator = TraceContextPropagator::new();
+        let nats_shutdown = shutdown.clone();
         actix::spawn(async move {
-            subscribe_to_nats(nats_config, message_queue_size, move |msg| {
+            subscribe_to_nats_with_health(nats_config, message_queue_size, Some(nats_health), move |msg| {
+                if !nats_shutdown.is_accepting_events() {
+                    warn!("NatsSubscriberActor - Tornado is shutting down. The received event is discarded");
+                    return Ok(());
+                }
                 let master_span = tracing::info_span!("Process event", trace_id = tracing::field::Empty, otel.kind = "Server");
                 let event = master_span.in_scope(|| {
                     let subscriber_span = tracing::debug_span!("Receive NATS event").entered();
 
                     let meter_event_souce_label = EVENT_SOURCE_LABEL_KEY.string("nats");
 
-                    let mut event: Event = serde_json::from_slice(&msg.msg.data)
+                    let mut event = deserialize_event(&msg.msg.data)
                         .map_err(|err| {
                             tornado_meter_nats.invalid_events_received_counter.add(1, &[
                                 meter_event_souce_label.clone(),
                             ]);
-                            TornadoCommonActorError::SerdeError { message: format! {"{}", err} }
+                            err
                         })?;
+                    if let Some(signer) = &nats_signer {
+                        signer.verify(&mut event).map_err(|err| {
+                            tornado_meter_nats.invalid_events_received_counter.add(1, &[
+                                meter_event_souce_label.clone(),
+                            ]);
+                            err
+                        })?;
+                    }
                     event.remove_undesired_metadata();
 
                     trace!("NatsSubscriberActor - event from message received: {:#?}", event);
@@ -355,6 +675,12 @@ pub async fn daemon(
 
                     Ok(event)
                 })?;
+                if let Some(rate_limiter) = &nats_rate_limiter {
+                    if !rate_limiter.accept(&event) {
+                        debug!("NatsSubscriberActor - The tenant of the received event exceeded its rate limit. The event is discarded");
+                        return Ok(());
+                    }
+                }
                 matcher_addr_clone.try_send(EventMessage { event, span: master_span }).unwrap_or_else(|err| error!("NatsSubscriberActor - Error while sending EventMessage to MatcherActor. Error: {:?}", err));
                 Ok(())
             })
@@ -370,7 +696,7 @@ pub async fn daemon(
                         "NATS connection failed started at [{:#?}], subject [{}]. Err: {:?}",
                         addresses, subject, err
                     );
-                    std::process::exit(1);
+                    exit_with_reason(ShutdownReason::Failure, &err);
                 });
         });
     } else {
@@ -391,13 +717,52 @@ pub async fn daemon(
                 .expect("'event_socket_port' must be provided to start the tornado TCP server")
         );
         let json_matcher_addr_clone = matcher_addr.clone();
+        let event_json_max_nesting_depth = daemon_config
+            .event_json_max_nesting_depth
+            .unwrap_or(tornado_collector_json::DEFAULT_MAX_NESTING_DEPTH);
+        let event_socket_idle_timeout =
+            daemon_config.event_socket_idle_timeout_secs.map(Duration::from_secs);
+        let event_json_delimiter = daemon_config.event_json_delimiter.clone();
+        let event_socket_max_connections = daemon_config.event_socket_max_connections;
+        let event_socket_allowed_networks = daemon_config.event_socket_allowed_networks.clone();
 
         let tornado_meter_tcp = tornado_meter.clone();
+        let tornado_meter_tcp_refused = tornado_meter.clone();
+        let tornado_meter_tcp_not_allowed = tornado_meter.clone();
+        let tcp_shutdown = shutdown.clone();
+        let tcp_signer = event_signer;
+        let tcp_rate_limiter = tenant_rate_limiter.clone();
         actix::spawn(async move {
-            listen_to_tcp(tcp_address.clone(), message_queue_size, move |msg| {
+            let tcp_server_options = TcpServerOptions {
+                max_connections: event_socket_max_connections,
+                on_connection_refused: Some(Box::new(move || {
+                    tornado_meter_tcp_refused.tcp_connections_refused_counter.add(1, &[]);
+                })),
+                allowed_networks: event_socket_allowed_networks,
+                on_connection_not_allowed: Some(Box::new(move |_peer_address| {
+                    tornado_meter_tcp_not_allowed.tcp_connections_not_allowed_counter.add(1, &[]);
+                })),
+            };
+            listen_to_tcp(tcp_address.clone(), message_queue_size, tcp_server_options, move |msg| {
                 let tornado_meter = tornado_meter_tcp.clone();
                 let json_matcher_addr_clone = json_matcher_addr_clone.clone();
-                JsonEventReaderActor::start_new(msg, message_queue_size, move |mut event| {
+                let tcp_shutdown = tcp_shutdown.clone();
+                let tcp_signer = tcp_signer.clone();
+                let tcp_rate_limiter = tcp_rate_limiter.clone();
+                JsonEventReaderActor::start_new_with_delimiter(msg, message_queue_size, event_json_max_nesting_depth, event_socket_idle_timeout, event_json_delimiter.clone(), move |mut event| {
+                    if !tcp_shutdown.is_accepting_events() {
+                        warn!("JsonEventReaderActor - Tornado is shutting down. The received event is discarded");
+                        return;
+                    }
+                    if let Some(signer) = &tcp_signer {
+                        if let Err(err) = signer.verify(&mut event) {
+                            tornado_meter.invalid_events_received_counter.add(1, &[
+                                EVENT_SOURCE_LABEL_KEY.string("tcp"),
+                            ]);
+                            warn!("JsonEventReaderActor - The received event is discarded. Err: {:?}", err);
+                            return;
+                        }
+                    }
                     tornado_meter.events_received_counter.add(1, &[
                         EVENT_SOURCE_LABEL_KEY.string("tcp"),
                         EVENT_TYPE_LABEL_KEY.string(event.event_type.to_owned()),
@@ -406,7 +771,15 @@ pub async fn daemon(
 
                     let span= tracing::info_span!("From tcp");
 
-                    json_matcher_addr_clone.try_send(EventMessage { event: json!(event), span }).unwrap_or_else(|err| error!("JsonEventReaderActor - Error while sending EventMessage to MatcherActor. Error: {:?}", err));
+                    let event = json!(event);
+                    if let Some(rate_limiter) = &tcp_rate_limiter {
+                        if !rate_limiter.accept(&event) {
+                            debug!("JsonEventReaderActor - The received event exceeded its rate limit. The event is discarded");
+                            return;
+                        }
+                    }
+
+                    json_matcher_addr_clone.try_send(EventMessage { event, span }).unwrap_or_else(|err| error!("JsonEventReaderActor - Error while sending EventMessage to MatcherActor. Error: {:?}", err));
                 });
             })
                 .await
@@ -416,7 +789,7 @@ pub async fn daemon(
                 // here we are forced to unwrap by the Actix API. See: https://github.com/actix/actix/issues/203
                 .unwrap_or_else(|err| {
                     error!("Cannot start TCP server at [{}]. Err: {:?}", tcp_address, err);
-                    std::process::exit(1);
+                    exit_with_reason(ShutdownReason::Bind, &err);
                 });
         });
     } else {
@@ -431,19 +804,79 @@ pub async fn daemon(
     )));
     let auth_service_v2 = AuthServiceV2::new(Arc::new(roles_map_to_permissions_map(
         daemon_config.auth.role_permissions.clone(),
-    )));
-    let api_handler = MatcherApiHandler::new(matcher_addr, tornado_meter.clone());
+    )))
+    .with_role_paths(Arc::new(daemon_config.auth.role_paths.clone()));
+    let flush_pipeline = {
+        let matcher_addr = matcher_addr.clone();
+        let dispatcher_addr = dispatcher_addr.clone();
+        // The actors handle the messages in order, so, when both reply, the events accepted
+        // before the drain have enqueued all their actions
+        async move {
+            if let Err(err) = matcher_addr.send(FlushMessage).await {
+                warn!("Cannot flush the MatcherActor mailbox. Err: {:?}", err);
+            }
+            if let Err(err) = dispatcher_addr.send(FlushMessage).await {
+                warn!("Cannot flush the DispatcherActor mailbox. Err: {:?}", err);
+            }
+        }
+    };
+    // The archive files are closed after the drain, so that the events archived by the
+    // in-flight actions are written to disk too
+    let close_archive_files = async move {
+        let archive_files = archive_files.borrow().clone();
+        for files in archive_files {
+            files.close_all().await;
+        }
+    };
+    let api_handler = MatcherApiHandler::new(matcher_addr, tornado_meter.clone())
+        .with_tenant_rate_limiter(tenant_rate_limiter);
+    let drain_timeout = daemon_config
+        .shutdown_drain_timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_DRAIN_TIMEOUT);
+    // Shared by all the workers, so that the limit applies to the whole server
+    let api_concurrency_limit =
+        ConcurrencyLimit::new(daemon_config.web_max_concurrent_requests.unwrap_or(usize::MAX));
+    let web_server_tls_config = daemon_config
+        .web_server_tls
+        .as_ref()
+        .map(|web_server_tls| web_server_tls.build_server_config())
+        .transpose()?;
+
+    // Start the dedicated metrics endpoint, if configured
+    if let Some(metrics_server_address) = daemon_config.metrics_server_address() {
+        let metrics_server = std::net::TcpListener::bind(&metrics_server_address)
+            .and_then(|listener| start_metrics_server(listener, metrics.clone()))
+            .map_err(|err| {
+                let error = format!(
+                    "Metrics server cannot start on address {}. Err: {:?}",
+                    metrics_server_address, err
+                );
+                error!("{}", error);
+                ShutdownError::with_reason(ShutdownReason::Bind)(TornadoError::ExecutionError {
+                    message: error,
+                })
+            })?;
+        info!("Started metrics server at [{}]", metrics_server_address);
+        actix::spawn(async move {
+            if let Err(err) = metrics_server.await {
+                error!("Metrics server stopped with error. Err: {:?}", err);
+            }
+        });
+    }
+
     let daemon_config = daemon_config.clone();
     let matcher_config = configs.matcher_config.clone();
 
     // Start API and monitoring endpoint
     let service_logger_guard = logger_guard.clone();
-    let server_binding_result = HttpServer::new(move || {
+    let server = HttpServer::new(move || {
         let daemon_config = daemon_config.clone();
 
         let v2_config_api = ApiDataV2 {
             auth: auth_service_v2.clone(),
-            api: ConfigApi::new(api_handler.clone(), matcher_config.clone()),
+            api: ConfigApi::new(api_handler.clone(), matcher_config.clone())
+                .with_matcher_settings(matcher_settings),
         };
         let event_api_v2 = ApiDataV2 {
             auth: auth_service_v2.clone(),
@@ -456,11 +889,15 @@ pub async fn daemon(
             )),
         };
         let metrics = metrics.clone();
+        let dependencies_health = dependencies_health.clone();
+        let tornado_meter = tornado_meter.clone();
+        let dead_letter_api = dead_letter_api.clone();
         App::new()
             .wrap(Logger::default())
             .wrap(TracingLogger::default())
             .service(
                 web::scope("/api")
+                    .wrap(api_concurrency_limit.clone())
                     .app_data(
                         // Json extractor configuration for this resource.
                         web::JsonConfig::default().limit(
@@ -476,21 +913,55 @@ pub async fn daemon(
                     )
                     .service(
                         web::scope("/v2_beta")
-                            .service(tornado_engine_api::config::web::build_config_v2_endpoints(
-                                v2_config_api,
-                            ))
-                            .service(tornado_engine_api::event::web::build_event_v2_endpoints(
-                                event_api_v2,
-                            )),
+                            .service(
+                                tornado_engine_api::config::web::build_config_v2_endpoints_with_max_payload_size(
+                                    v2_config_api,
+                                    daemon_config.web_max_config_payload_size,
+                                ),
+                            )
+                            .service(
+                                tornado_engine_api::event::web::build_event_v2_endpoints_with_max_payload_size(
+                                    event_api_v2,
+                                    daemon_config.web_max_event_payload_size,
+                                ),
+                            ),
                     ),
             )
-            .service(monitoring_endpoints(web::scope("/monitoring"), daemon_config, metrics))
-    })
-    .bind(format!("{}:{}", web_server_ip, web_server_port));
+            .service(rule_stats_endpoints(
+                web::scope("/v1/stats"),
+                ApiData { auth: auth_service.clone(), api: tornado_meter },
+            ))
+            .configure(|cfg| {
+                if let Some(dead_letter_api) = dead_letter_api {
+                    cfg.service(dead_letter_endpoints(
+                        web::scope("/v1/actions"),
+                        ApiData { auth: auth_service.clone(), api: dead_letter_api },
+                    ));
+                }
+            })
+            .service(monitoring_endpoints(
+                web::scope("/monitoring"),
+                daemon_config,
+                metrics,
+                dependencies_health,
+            ))
+    });
+    let web_server_address = format!("{}:{}", web_server_ip, web_server_port);
+    let server_binding_result = match web_server_tls_config {
+        Some(tls_config) => {
+            info!("The Web Server accepts only HTTPS connections on {}", web_server_address);
+            server.bind_rustls_021(&web_server_address, tls_config)
+        }
+        None => server.bind(&web_server_address),
+    };
 
     match server_binding_result {
         Ok(server) => {
+            // The server stops when a termination signal is received
             server.run().await?;
+            info!("Tornado is shutting down");
+            shutdown.drain_after(drain_timeout, flush_pipeline).await;
+            close_archive_files.await;
             Ok(())
         }
         Err(err) => {
@@ -499,7 +970,38 @@ pub async fn daemon(
                 web_server_ip, web_server_port, err
             );
             error!("{}", error);
-            Err(TornadoError::ExecutionError { message: error }.into())
+            Err(ShutdownError::with_reason(ShutdownReason::Bind)(TornadoError::ExecutionError {
+                message: error,
+            }))
         }
     }
 }
+
+/// Starts one executor for each configured instance of the executor id and spreads the actions
+/// among them by weighted round-robin. The configuration of each instance is built from the
+/// executor configuration with the entries of the instance.
+/// If the executor id has no instances, a single executor is started with the executor configuration.
+fn start_executor_instances<C: Serialize + DeserializeOwned + Clone>(
+    executor_id: &str,
+    executor_config: &C,
+    executor_instances: &BTreeMap<String, Vec<ExecutorInstance>>,
+    message_queue_size: usize,
+    action_meter: &Arc<ActionMeter>,
+    start_executor: impl Fn(C) -> Recipient<ActionMessage>,
+) -> Result<Recipient<ActionMessage>, ConfigError> {
+    let instances = match executor_instances.get(executor_id) {
+        Some(instances) if !instances.is_empty() => instances,
+        _ => return Ok(start_executor(executor_config.clone())),
+    };
+
+    let mut recipients = Vec::with_capacity(instances.len());
+    for (index, instance) in instances.iter().enumerate() {
+        info!(
+            "Start instance {} of the [{}] executor with weight {}",
+            index, executor_id, instance.weight
+        );
+        recipients.push((start_executor(instance.build_config(executor_config)?), instance.weight));
+    }
+    Ok(WeightedRoundRobinActor::start_new(message_queue_size, recipients, action_meter.clone())
+        .recipient())
+}
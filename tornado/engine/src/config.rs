@@ -1,20 +1,35 @@
+use crate::enrich::lookup::LookupEnrichment;
+use crate::enrich::merge_patch::EventMergePatchConfig;
 use crate::enrich::nats::NatsExtractor;
+use crate::rate_limit::TenantRateLimitsConfig;
+use crate::tls::WebServerTlsConfig;
 use clap::Parser;
 use config_rs::{Config, ConfigError, File};
+use ipnet::IpNet;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::sync::Arc;
 use tornado_common::{
-    actors::nats_subscriber::NatsSubscriberConfig, command::retry::RetryStrategy,
+    actors::nats_subscriber::NatsSubscriberConfig,
+    command::retry::{RetryBudgetConfig, RetryStrategy},
+    command::timeout::ActionTimeouts,
 };
 use tornado_common_logger::LoggerConfig;
 use tornado_engine_api::auth::Permission;
 use tornado_engine_matcher::config::v2::FsMatcherConfigManagerV2;
 use tornado_engine_matcher::config::MatcherConfigEditor;
+use tornado_engine_matcher::matcher::MatcherSettings;
+use tornado_engine_matcher::regex::RegexLimits;
 use tornado_executor_archive::config::ArchiveConfig;
 use tornado_executor_director::config::DirectorClientConfig;
 use tornado_executor_elasticsearch::config::ElasticsearchConfig;
+use tornado_executor_grpc::config::GrpcExecutorConfig;
 use tornado_executor_icinga2::config::Icinga2ClientConfig;
+use tornado_executor_merge::config::MergeConfig;
+use tornado_executor_mqtt::config::MqttPublisherConfig;
+use tornado_executor_scheduled::config::ScheduledConfig;
+use tornado_executor_statsd::config::StatsdExecutorConfig;
 
 pub const CONFIG_DIR_DEFAULT: Option<&'static str> = option_env!("TORNADO_CONFIG_DIR_DEFAULT");
 
@@ -144,31 +159,177 @@ impl ThreadPoolConfig {
     }
 }
 
+/// An instance of an executor that receives a share of the actions of its executor id
+/// proportional to its weight
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ExecutorInstance {
+    #[serde(default = "default_instance_weight")]
+    pub weight: u32,
+    /// The entries that replace the ones of the executor configuration for this instance
+    #[serde(default)]
+    pub config: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ExecutorInstance {
+    /// Returns the executor configuration of this instance, built by replacing the entries
+    /// of the given configuration with the ones of the instance.
+    pub fn build_config<C: Serialize + DeserializeOwned>(
+        &self,
+        executor_config: &C,
+    ) -> Result<C, ConfigError> {
+        let mut config = match serde_json::to_value(executor_config) {
+            Ok(serde_json::Value::Object(config)) => config,
+            Ok(_) => {
+                return Err(ConfigError::Message(
+                    "The executor configuration is not an object".to_owned(),
+                ))
+            }
+            Err(err) => return Err(ConfigError::Message(err.to_string())),
+        };
+        config.extend(self.config.clone());
+        serde_json::from_value(serde_json::Value::Object(config)).map_err(|err| {
+            ConfigError::Message(format!("Invalid executor instance configuration: {}", err))
+        })
+    }
+}
+
+fn default_instance_weight() -> u32 {
+    1
+}
+
+/// Where the Prometheus metrics endpoint is exposed
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct MetricsEndpointConfig {
+    /// Whether the metrics endpoint is exposed at all
+    #[serde(default = "default_metrics_endpoint_enabled")]
+    pub enabled: bool,
+    /// The IP address where the dedicated metrics server listens.
+    /// It is used only if the port is set.
+    #[serde(default = "default_metrics_endpoint_ip")]
+    pub ip: String,
+    /// If set, the metrics endpoint is served by a dedicated server listening on this port
+    ///   instead of by the Web Server of the API
+    pub port: Option<u16>,
+}
+
+fn default_metrics_endpoint_enabled() -> bool {
+    true
+}
+
+fn default_metrics_endpoint_ip() -> String {
+    "127.0.0.1".to_owned()
+}
+
 #[derive(Deserialize, Serialize, Clone)]
+#[cfg_attr(test, derive(Default))]
 pub struct DaemonCommandConfig {
     pub event_tcp_socket_enabled: Option<bool>,
     pub event_socket_ip: Option<String>,
     pub event_socket_port: Option<u16>,
+    pub event_json_max_nesting_depth: Option<usize>,
+    pub event_socket_idle_timeout_secs: Option<u64>,
+    pub event_socket_max_connections: Option<usize>,
+    pub event_socket_allowed_networks: Option<Vec<IpNet>>,
+    pub event_json_delimiter: Option<String>,
+    pub event_signature_secret: Option<String>,
 
     pub nats_enabled: Option<bool>,
     pub nats: Option<NatsSubscriberConfig>,
     #[serde(default)]
     pub nats_extractors: Vec<NatsExtractor>,
 
+    #[serde(default)]
+    pub lookup_enrichments: Vec<LookupEnrichment>,
+
+    pub tenant_rate_limits: Option<TenantRateLimitsConfig>,
+
     pub web_server_ip: String,
     pub web_server_port: u16,
     pub web_max_json_payload_size: Option<usize>,
+    pub web_max_config_payload_size: Option<usize>,
+    pub web_max_event_payload_size: Option<usize>,
+    pub web_max_concurrent_requests: Option<usize>,
+    pub web_server_tls: Option<WebServerTlsConfig>,
 
     pub message_queue_size: usize,
 
+    pub foreach_max_resolution_depth: Option<usize>,
+
+    pub fallback_executor: Option<String>,
+
+    pub script_retryable_exit_codes: Option<Vec<i32>>,
+
+    pub script_allowed_paths: Option<Vec<String>>,
+
+    pub logger_executor_file_path: Option<String>,
+
+    pub logger_executor_message_template: Option<String>,
+
+    pub max_action_size_bytes: Option<usize>,
+
+    #[serde(default)]
+    pub executor_instances: BTreeMap<String, Vec<ExecutorInstance>>,
+
+    pub shutdown_drain_timeout_secs: Option<u64>,
+
+    pub rules_watcher_enabled: Option<bool>,
+    pub rules_watcher_debounce_ms: Option<u64>,
+
+    pub event_processing_budget_ms: Option<u64>,
+    pub tag_events_over_budget: Option<bool>,
+    pub event_ttl_ms: Option<u64>,
+    pub normalize_events: Option<bool>,
+    pub event_merge_patch: Option<EventMergePatchConfig>,
+    pub regex_limits: Option<RegexLimits>,
+    pub globally_unique_rule_names: Option<bool>,
+
+    pub metrics_prefix: Option<String>,
+    pub metrics_endpoint: Option<MetricsEndpointConfig>,
+
     pub thread_pool_config: Option<ThreadPoolConfig>,
     #[serde(default)]
     pub retry_strategy: RetryStrategy,
+    pub retry_budget: Option<RetryBudgetConfig>,
+    pub dead_letter_store_path: Option<String>,
+    #[serde(default)]
+    pub action_timeouts: ActionTimeouts,
 
     pub auth: AuthConfig,
 }
 
 impl DaemonCommandConfig {
+    /// Returns an error if a value of the configuration is not valid
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.event_json_delimiter.as_ref().map_or(false, String::is_empty) {
+            return Err(ConfigError::Message(
+                "The event_json_delimiter must not be empty".to_owned(),
+            ));
+        }
+        if self.web_max_concurrent_requests == Some(0) {
+            return Err(ConfigError::Message(
+                "The web_max_concurrent_requests must be greater than 0".to_owned(),
+            ));
+        }
+        if let Some(retry_budget) = &self.retry_budget {
+            if retry_budget.retries_per_sec.is_nan() || retry_budget.retries_per_sec <= 0.0 {
+                return Err(ConfigError::Message(format!(
+                    "The retry_budget retries_per_sec must be greater than 0. Found: {}",
+                    retry_budget.retries_per_sec
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the settings applied when the Matchers are built,
+    ///   e.g. the regex limits and the validation of the rule names.
+    pub fn matcher_settings(&self) -> MatcherSettings {
+        MatcherSettings {
+            regex_limits: self.regex_limits.unwrap_or_default(),
+            globally_unique_rule_names: self.globally_unique_rule_names.unwrap_or(false),
+        }
+    }
+
     pub fn is_event_tcp_socket_enabled(&self) -> bool {
         self.event_tcp_socket_enabled.unwrap_or(true)
     }
@@ -176,11 +337,36 @@ impl DaemonCommandConfig {
     pub fn is_nats_enabled(&self) -> bool {
         self.nats_enabled.unwrap_or(false)
     }
+
+    pub fn is_rules_watcher_enabled(&self) -> bool {
+        self.rules_watcher_enabled.unwrap_or(false)
+    }
+
+    /// Returns whether the metrics endpoint is served by the Web Server of the API
+    pub fn is_metrics_endpoint_on_web_server(&self) -> bool {
+        match &self.metrics_endpoint {
+            Some(metrics_endpoint) => metrics_endpoint.enabled && metrics_endpoint.port.is_none(),
+            None => true,
+        }
+    }
+
+    /// Returns the address of the dedicated server of the metrics endpoint, if any
+    pub fn metrics_server_address(&self) -> Option<String> {
+        match &self.metrics_endpoint {
+            Some(MetricsEndpointConfig { enabled: true, ip, port: Some(port) }) => {
+                Some(format!("{}:{}", ip, port))
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Default)]
 pub struct AuthConfig {
     pub role_permissions: BTreeMap<String, Vec<Permission>>,
+    /// The default node path that each role is authorized to access
+    #[serde(default)]
+    pub role_paths: BTreeMap<String, Vec<String>>,
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -199,7 +385,9 @@ pub fn build_config(config_dir: &str) -> Result<GlobalConfig, ConfigError> {
     let config_file_path = format!("{}/tornado.toml", config_dir);
     let mut s = Config::new();
     s.merge(File::with_name(&config_file_path))?;
-    s.try_into()
+    let config: GlobalConfig = s.try_into()?;
+    config.tornado.daemon.validate()?;
+    Ok(config)
 }
 
 fn build_archive_config(config_dir: &str) -> Result<ArchiveConfig, ConfigError> {
@@ -230,12 +418,32 @@ fn build_elasticsearch_config(config_dir: &str) -> Result<ElasticsearchConfig, C
     s.try_into()
 }
 
+/// Reads the configuration of an executor that is started only if configured.
+/// Returns None if the configuration file is not in the config folder.
+fn build_optional_executor_config<T: DeserializeOwned>(
+    config_dir: &str,
+    file_name: &str,
+) -> Result<Option<T>, ConfigError> {
+    let config_file_path = format!("{}/{}", config_dir, file_name);
+    if !std::path::Path::new(&config_file_path).exists() {
+        return Ok(None);
+    }
+    let mut s = Config::new();
+    s.merge(File::with_name(&config_file_path))?;
+    s.try_into().map(Some)
+}
+
 pub struct ComponentsConfig {
     pub matcher_config: Arc<dyn MatcherConfigEditor>,
     pub archive_executor_config: ArchiveConfig,
     pub icinga2_executor_config: Icinga2ClientConfig,
     pub director_executor_config: DirectorClientConfig,
     pub elasticsearch_executor_config: ElasticsearchConfig,
+    pub mqtt_executor_config: Option<MqttPublisherConfig>,
+    pub merge_executor_config: Option<MergeConfig>,
+    pub scheduled_executor_config: Option<ScheduledConfig>,
+    pub grpc_executor_config: Option<GrpcExecutorConfig>,
+    pub statsd_executor_config: Option<StatsdExecutorConfig>,
 }
 
 pub fn parse_config_files(
@@ -248,12 +456,24 @@ pub fn parse_config_files(
     let icinga2_executor_config = build_icinga2_client_config(config_dir)?;
     let director_executor_config = build_director_client_config(config_dir)?;
     let elasticsearch_executor_config = build_elasticsearch_config(config_dir)?;
+    let mqtt_executor_config = build_optional_executor_config(config_dir, "mqtt_executor.toml")?;
+    let merge_executor_config = build_optional_executor_config(config_dir, "merge_executor.toml")?;
+    let scheduled_executor_config =
+        build_optional_executor_config(config_dir, "scheduled_executor.toml")?;
+    let grpc_executor_config = build_optional_executor_config(config_dir, "grpc_executor.toml")?;
+    let statsd_executor_config =
+        build_optional_executor_config(config_dir, "statsd_executor.toml")?;
     Ok(ComponentsConfig {
         matcher_config,
         archive_executor_config,
         icinga2_executor_config,
         director_executor_config,
         elasticsearch_executor_config,
+        mqtt_executor_config,
+        merge_executor_config,
+        scheduled_executor_config,
+        grpc_executor_config,
+        statsd_executor_config,
     })
 }
 
@@ -290,11 +510,71 @@ mod test {
                 Permission::RuntimeConfigEdit,
                 Permission::RuntimeConfigView,
                 Permission::TestEventExecuteActions,
+                Permission::ActionsReplay,
             ],
             config.tornado.daemon.auth.role_permissions["admin"]
         );
     }
 
+    #[test]
+    fn should_reject_a_retry_budget_without_retries() {
+        // Arrange
+        let mut config = build_config("./config/").unwrap().tornado.daemon;
+
+        for retries_per_sec in [0.0, -1.0, f64::NAN] {
+            config.retry_budget = Some(RetryBudgetConfig {
+                retries_per_sec,
+                burst: None,
+                when_exhausted: Default::default(),
+            });
+
+            // Act
+            let result = config.validate();
+
+            // Assert
+            assert!(result.is_err(), "retries_per_sec: {}", retries_per_sec);
+        }
+
+        config.retry_budget = Some(RetryBudgetConfig {
+            retries_per_sec: 0.5,
+            burst: None,
+            when_exhausted: Default::default(),
+        });
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn should_reject_an_empty_event_json_delimiter() {
+        // Arrange
+        let mut config = build_config("./config/").unwrap().tornado.daemon;
+
+        // Act
+        config.event_json_delimiter = Some("".to_owned());
+        let empty = config.validate();
+        config.event_json_delimiter = Some(";".to_owned());
+        let not_empty = config.validate();
+
+        // Assert
+        assert!(empty.is_err());
+        assert!(not_empty.is_ok());
+    }
+
+    #[test]
+    fn should_reject_a_concurrency_limit_of_zero() {
+        // Arrange
+        let mut config = build_config("./config/").unwrap().tornado.daemon;
+
+        // Act
+        config.web_max_concurrent_requests = Some(0);
+        let zero = config.validate();
+        config.web_max_concurrent_requests = Some(1);
+        let one = config.validate();
+
+        // Assert
+        assert!(zero.is_err());
+        assert!(one.is_ok());
+    }
+
     #[tokio::test]
     async fn should_read_all_rule_configurations_from_file() {
         // Arrange
@@ -331,6 +611,39 @@ mod test {
         )
     }
 
+    #[test]
+    fn executor_instance_should_replace_the_entries_of_the_executor_configuration() {
+        // Arrange
+        let icinga2_config = build_icinga2_client_config("./config").unwrap();
+        let instance: ExecutorInstance = serde_json::from_str(
+            r#"{"weight": 3, "config": {"server_api_url": "https://icinga2-master-2:5665/v1/actions"}}"#,
+        )
+        .unwrap();
+
+        // Act
+        let instance_config = instance.build_config(&icinga2_config).unwrap();
+
+        // Assert
+        assert_eq!(3, instance.weight);
+        assert_eq!("https://icinga2-master-2:5665/v1/actions", instance_config.server_api_url);
+        assert_eq!(icinga2_config.username, instance_config.username);
+    }
+
+    #[test]
+    fn executor_instance_should_fail_if_the_configuration_is_not_valid() {
+        // Arrange
+        let icinga2_config = build_icinga2_client_config("./config").unwrap();
+        let instance: ExecutorInstance =
+            serde_json::from_str(r#"{"config": {"server_api_url": 5665}}"#).unwrap();
+
+        // Act
+        let result = instance.build_config(&icinga2_config);
+
+        // Assert
+        assert_eq!(1, instance.weight);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn should_read_archiver_configurations_from_file() {
         // Arrange
@@ -367,23 +680,37 @@ mod test {
         assert_eq!("https://localhost/neteye/director", config.server_api_url)
     }
 
+    #[test]
+    fn should_read_the_optional_executor_configurations_only_if_present() {
+        // Arrange
+        let config_dir = tempfile::TempDir::new().unwrap();
+        let config_dir_path = config_dir.path().to_str().unwrap();
+        std::fs::write(
+            config_dir.path().join("mqtt_executor.toml"),
+            "host = \"localhost\"\nport = 1883\nclient_id = \"tornado\"\n",
+        )
+        .unwrap();
+
+        // Act
+        let missing: Option<MqttPublisherConfig> =
+            build_optional_executor_config(config_dir_path, "missing_executor.toml").unwrap();
+        let mqtt_config: Option<MqttPublisherConfig> =
+            build_optional_executor_config(config_dir_path, "mqtt_executor.toml").unwrap();
+
+        // Assert
+        assert!(missing.is_none());
+        let mqtt_config = mqtt_config.unwrap();
+        assert_eq!("localhost", mqtt_config.host);
+        assert_eq!(1883, mqtt_config.port);
+    }
+
     #[test]
     fn channel_config_getters_should_correctly_extract_value() {
         // Arrange
         let daemon_configs = DaemonCommandConfig {
             event_tcp_socket_enabled: Some(false),
-            event_socket_ip: None,
-            event_socket_port: None,
             nats_enabled: Some(true),
-            nats: None,
-            nats_extractors: vec![],
-            web_server_ip: "".to_string(),
-            web_server_port: 0,
-            web_max_json_payload_size: None,
-            message_queue_size: 0,
-            thread_pool_config: None,
-            retry_strategy: Default::default(),
-            auth: AuthConfig::default(),
+            ..Default::default()
         };
 
         // Act
@@ -398,21 +725,7 @@ mod test {
     #[test]
     fn channel_config_getters_should_correctly_handle_none() {
         // Arrange
-        let daemon_configs = DaemonCommandConfig {
-            event_tcp_socket_enabled: None,
-            event_socket_ip: None,
-            event_socket_port: None,
-            nats_enabled: None,
-            nats: None,
-            nats_extractors: vec![],
-            web_server_ip: "".to_string(),
-            web_server_port: 0,
-            web_max_json_payload_size: None,
-            message_queue_size: 0,
-            thread_pool_config: None,
-            retry_strategy: Default::default(),
-            auth: AuthConfig::default(),
-        };
+        let daemon_configs = DaemonCommandConfig::default();
 
         // Act
         let event_tcp_socket_enabled = daemon_configs.is_event_tcp_socket_enabled();
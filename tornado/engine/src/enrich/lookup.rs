@@ -0,0 +1,219 @@
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tornado_common::TornadoError;
+use tornado_common_api::Value;
+
+/// Injects in the event payload the label resolved from a lookup table,
+/// using the value of a payload field as lookup key.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LookupEnrichment {
+    /// The payload field whose value is used as lookup key
+    pub source: String,
+    /// The payload field where the resolved label is written
+    pub target: String,
+    /// The static key -> label table
+    #[serde(default)]
+    pub table: HashMap<String, Value>,
+    /// The path of a CSV file with 'key,label' rows.
+    /// The entries of the static table take precedence over the ones of the file.
+    pub csv_path: Option<String>,
+    /// The label used when the key is not in the table.
+    /// If not set, the target field is left unset.
+    pub default: Option<Value>,
+}
+
+impl LookupEnrichment {
+    /// Builds the LookupEnricher, reading the CSV file if configured
+    pub fn build(&self) -> Result<LookupEnricher, TornadoError> {
+        let mut table = HashMap::new();
+        if let Some(csv_path) = &self.csv_path {
+            let content = std::fs::read_to_string(csv_path).map_err(|err| {
+                TornadoError::ConfigurationError {
+                    message: format!(
+                        "Cannot read the lookup table file [{}]. Err: {:?}",
+                        csv_path, err
+                    ),
+                }
+            })?;
+            table = parse_csv(csv_path, &content)?;
+        }
+        table.extend(self.table.clone());
+
+        Ok(LookupEnricher {
+            source: self.source.clone(),
+            target: self.target.clone(),
+            table,
+            default: self.default.clone(),
+        })
+    }
+}
+
+fn parse_csv(csv_path: &str, content: &str) -> Result<HashMap<String, Value>, TornadoError> {
+    let mut table = HashMap::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once(',') {
+            Some((key, label)) => {
+                table.insert(key.trim().to_owned(), Value::String(label.trim().to_owned()));
+            }
+            None => {
+                return Err(TornadoError::ConfigurationError {
+                    message: format!(
+                        "Invalid row [{}] at line {} of the lookup table file [{}]. Expected 'key,label'",
+                        line,
+                        index + 1,
+                        csv_path
+                    ),
+                })
+            }
+        }
+    }
+    Ok(table)
+}
+
+#[derive(Debug, Clone)]
+pub struct LookupEnricher {
+    source: String,
+    target: String,
+    table: HashMap<String, Value>,
+    default: Option<Value>,
+}
+
+impl LookupEnricher {
+    pub fn process(&self, mut event: Value) -> Value {
+        if let Some(payload) = event.get_mut("payload").and_then(|payload| payload.as_object_mut())
+        {
+            let key = match payload.get(&self.source) {
+                Some(Value::String(key)) => Some(key.to_owned()),
+                Some(Value::Number(key)) => Some(key.to_string()),
+                Some(Value::Bool(key)) => Some(key.to_string()),
+                _ => None,
+            };
+
+            match key.as_ref().and_then(|key| self.table.get(key)).or(self.default.as_ref()) {
+                Some(label) => {
+                    trace!(
+                        "LookupEnricher - key [{:?}] of field [{}] resolved to [{}]",
+                        key,
+                        self.source,
+                        label
+                    );
+                    payload.insert(self.target.clone(), label.clone());
+                }
+                None => {
+                    debug!(
+                        "LookupEnricher - key [{:?}] of field [{}] not found in the lookup table",
+                        key, self.source
+                    );
+                }
+            }
+        }
+        event
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+    use tornado_common_api::{Event, WithEventData};
+
+    fn severity_enrichment(default: Option<Value>) -> LookupEnrichment {
+        LookupEnrichment {
+            source: "severity".to_owned(),
+            target: "severity_label".to_owned(),
+            table: HashMap::from([
+                ("1".to_owned(), json!("critical")),
+                ("2".to_owned(), json!("warning")),
+            ]),
+            csv_path: None,
+            default,
+        }
+    }
+
+    fn event_with_severity(severity: Value) -> Value {
+        let mut event = Event::new("ev");
+        event.payload.insert("severity".to_owned(), severity);
+        json!(event)
+    }
+
+    fn severity_label(event: &Value) -> Option<&Value> {
+        event.payload().and_then(|payload| payload.get("severity_label"))
+    }
+
+    #[test]
+    fn should_enrich_the_event_with_the_mapped_label() {
+        // Arrange
+        let enricher = severity_enrichment(None).build().unwrap();
+
+        // Act
+        let event = enricher.process(event_with_severity(json!(1)));
+
+        // Assert
+        assert_eq!(Some(&json!("critical")), severity_label(&event));
+    }
+
+    #[test]
+    fn should_use_the_default_for_an_unmapped_key() {
+        // Arrange
+        let enricher = severity_enrichment(Some(json!("unknown"))).build().unwrap();
+
+        // Act
+        let event = enricher.process(event_with_severity(json!(7)));
+
+        // Assert
+        assert_eq!(Some(&json!("unknown")), severity_label(&event));
+    }
+
+    #[test]
+    fn should_leave_the_target_unset_for_an_unmapped_key_without_default() {
+        // Arrange
+        let enricher = severity_enrichment(None).build().unwrap();
+        let original_event = event_with_severity(json!("7"));
+
+        // Act
+        let event = enricher.process(original_event.clone());
+
+        // Assert
+        assert_eq!(original_event, event);
+    }
+
+    #[test]
+    fn should_read_the_table_from_a_csv_file() {
+        // Arrange
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "# severity,label\n1, disaster\n3,info").unwrap();
+        let mut enrichment = severity_enrichment(None);
+        enrichment.csv_path = Some(file.path().to_str().unwrap().to_owned());
+        let enricher = enrichment.build().unwrap();
+
+        // Act
+        let critical_event = enricher.process(event_with_severity(json!(1)));
+        let info_event = enricher.process(event_with_severity(json!(3)));
+
+        // Assert
+        assert_eq!(Some(&json!("critical")), severity_label(&critical_event));
+        assert_eq!(Some(&json!("info")), severity_label(&info_event));
+    }
+
+    #[test]
+    fn should_fail_if_the_csv_file_is_not_valid() {
+        // Arrange
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "1;critical").unwrap();
+        let mut enrichment = severity_enrichment(None);
+        enrichment.csv_path = Some(file.path().to_str().unwrap().to_owned());
+
+        // Act
+        let result = enrichment.build();
+
+        // Assert
+        assert!(matches!(result, Err(TornadoError::ConfigurationError { .. })));
+    }
+}
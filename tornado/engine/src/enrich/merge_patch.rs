@@ -0,0 +1,394 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Map;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+use tornado_common_api::dedup_key::DedupKey;
+use tornado_common_api::{Value, ValueExt, WithEventData};
+use tornado_engine_matcher::error::MatcherError;
+use tornado_engine_matcher::matcher::deduplication::key::MatcherDedupKey;
+use tornado_engine_matcher::model::InternalEvent;
+
+/// The metadata field with the tenant of an event
+const TENANT_ID_METADATA: &str = "tenant_id";
+
+/// Merges the incoming events onto the last event of the same entity, so that the producers
+///   can send incremental updates and the rules see the full current state of the entity.
+/// An entity is identified by the tenant and the type of the event together with the value
+///   of the `entity_key`, so the events of different tenants or types are never merged.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EventMergePatchConfig {
+    /// The key that identifies the entity of the event, e.g. `${event.payload.host}`.
+    /// The events whose key cannot be resolved are not merged.
+    pub entity_key: DedupKey,
+    /// The number of seconds after which the last state of an entity is forgotten
+    pub ttl_secs: u64,
+    /// The maximum number of entities whose last state is kept.
+    /// When it is reached, the least recently updated entity is forgotten.
+    pub max_entities: usize,
+}
+
+impl EventMergePatchConfig {
+    pub fn build(&self) -> Result<EventMerger, MatcherError> {
+        Ok(EventMerger {
+            entity_key: MatcherDedupKey::build("event_merge_patch", &self.entity_key)?,
+            ttl: Duration::from_secs(self.ttl_secs),
+            max_entities: self.max_entities,
+            states: HashMap::new(),
+            recency: BTreeMap::new(),
+            next_update_id: 0,
+        })
+    }
+}
+
+/// The identity of an entity: its tenant, the type of its events and its id
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct EntityKey {
+    tenant_id: Option<String>,
+    event_type: String,
+    entity_id: String,
+}
+
+struct EntityState {
+    event: Value,
+    updated_at: Instant,
+    /// The position of the entity in the `recency` index
+    update_id: u64,
+}
+
+pub struct EventMerger {
+    entity_key: MatcherDedupKey,
+    ttl: Duration,
+    max_entities: usize,
+    states: HashMap<EntityKey, EntityState>,
+    /// The entities ordered from the least to the most recently updated
+    recency: BTreeMap<u64, EntityKey>,
+    next_update_id: u64,
+}
+
+impl EventMerger {
+    /// Returns the event merged onto the last state of its entity, if any.
+    /// If `update_state` is true, the merged event becomes the last state of the entity.
+    pub fn merge(&mut self, event: Value, update_state: bool) -> Value {
+        self.merge_at(event, update_state, Instant::now())
+    }
+
+    fn merge_at(&mut self, event: Value, update_state: bool, now: Instant) -> Value {
+        let key = match self.entity_key(&event) {
+            Some(key) => key,
+            None => return event,
+        };
+
+        let mut merged = match self.states.get(&key) {
+            Some(state) if now.duration_since(state.updated_at) <= self.ttl => state.event.clone(),
+            _ => Value::Object(Map::new()),
+        };
+        merge_patch(&mut merged, event);
+
+        if update_state {
+            self.update_state(key, merged.clone(), now);
+        }
+        merged
+    }
+
+    fn entity_key(&self, event: &Value) -> Option<EntityKey> {
+        let mut extracted_variables = Value::Object(Map::new());
+        let entity_id =
+            self.entity_key.derive(&InternalEvent::new(event, &mut extracted_variables))?;
+        let tenant_id = event
+            .metadata()
+            .and_then(|metadata| metadata.get(TENANT_ID_METADATA))
+            .and_then(|tenant_id| tenant_id.get_text())
+            .map(|tenant_id| tenant_id.to_owned());
+        let event_type = event.event_type().unwrap_or_default().to_owned();
+        Some(EntityKey { tenant_id, event_type, entity_id })
+    }
+
+    /// Sets the state of the entity, which becomes the most recently updated one.
+    /// The expired states are removed and, if the store is still full,
+    ///   the least recently updated one is removed too.
+    fn update_state(&mut self, key: EntityKey, event: Value, now: Instant) {
+        if let Some(state) = self.states.remove(&key) {
+            self.recency.remove(&state.update_id);
+        }
+
+        // The states are updated in time order, so the expired ones are the least recent
+        while let Some((&update_id, oldest)) = self.recency.iter().next() {
+            let expired = self
+                .states
+                .get(oldest)
+                .map(|state| now.duration_since(state.updated_at) > self.ttl)
+                .unwrap_or(true);
+            if !expired && self.states.len() < self.max_entities {
+                break;
+            }
+            if let Some(oldest) = self.recency.remove(&update_id) {
+                self.states.remove(&oldest);
+            }
+        }
+
+        if self.states.len() < self.max_entities {
+            let update_id = self.next_update_id;
+            self.next_update_id += 1;
+            self.recency.insert(update_id, key.clone());
+            self.states.insert(key, EntityState { event, updated_at: now, update_id });
+        }
+    }
+}
+
+/// Applies the patch to the target with the JSON merge-patch semantics of RFC 7386:
+/// the objects are merged key by key, a null value removes the key from the target
+///   and any other value replaces the target one.
+pub fn merge_patch(target: &mut Value, patch: Value) {
+    match patch {
+        Value::Object(patch) => {
+            if !target.is_object() {
+                *target = Value::Object(Map::new());
+            }
+            if let Value::Object(target) = target {
+                for (key, value) in patch {
+                    if value.is_null() {
+                        target.remove(&key);
+                    } else {
+                        merge_patch(target.entry(key).or_insert(Value::Null), value);
+                    }
+                }
+            }
+        }
+        patch => *target = patch,
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+
+    fn merger(ttl_secs: u64, max_entities: usize) -> EventMerger {
+        EventMergePatchConfig { entity_key: "${event.payload.host}".into(), ttl_secs, max_entities }
+            .build()
+            .unwrap()
+    }
+
+    fn host_event(payload: Value) -> Value {
+        json!({ "type": "host_state", "created_ms": 0, "payload": payload })
+    }
+
+    fn contains_entity(merger: &EventMerger, entity_id: &str) -> bool {
+        merger.states.keys().any(|key| key.entity_id == entity_id)
+    }
+
+    #[test]
+    fn should_apply_the_merge_patch_semantics() {
+        // Arrange
+        let mut target = json!({ "a": "b", "c": { "d": "e", "f": "g" }, "list": [1, 2] });
+
+        // Act
+        merge_patch(&mut target, json!({ "a": "z", "c": { "f": null }, "list": [3] }));
+
+        // Assert
+        assert_eq!(json!({ "a": "z", "c": { "d": "e" }, "list": [3] }), target);
+    }
+
+    #[test]
+    fn should_merge_two_partial_events_of_the_same_entity() {
+        // Arrange
+        let mut merger = merger(60, 10);
+
+        // Act
+        merger.merge(host_event(json!({ "host": "server-1", "cpu": 10, "disk": "ok" })), true);
+        let merged =
+            merger.merge(host_event(json!({ "host": "server-1", "cpu": 95, "disk": null })), true);
+
+        // Assert
+        assert_eq!(host_event(json!({ "host": "server-1", "cpu": 95 })), merged);
+    }
+
+    #[test]
+    fn should_not_merge_the_events_of_different_entities() {
+        // Arrange
+        let mut merger = merger(60, 10);
+
+        // Act
+        merger.merge(host_event(json!({ "host": "server-1", "cpu": 10 })), true);
+        let merged = merger.merge(host_event(json!({ "host": "server-2", "disk": "ok" })), true);
+
+        // Assert
+        assert_eq!(host_event(json!({ "host": "server-2", "disk": "ok" })), merged);
+    }
+
+    #[test]
+    fn should_not_merge_the_events_without_the_entity_id() {
+        // Arrange
+        let mut merger = merger(60, 10);
+        let event = host_event(json!({ "cpu": 10, "disk": null }));
+
+        // Act
+        let merged = merger.merge(event.clone(), true);
+
+        // Assert
+        assert_eq!(event, merged);
+        assert!(merger.states.is_empty());
+    }
+
+    #[test]
+    fn should_not_update_the_state_if_not_requested() {
+        // Arrange
+        let mut merger = merger(60, 10);
+        merger.merge(host_event(json!({ "host": "server-1", "cpu": 10 })), true);
+
+        // Act
+        let preview = merger.merge(host_event(json!({ "host": "server-1", "disk": "ok" })), false);
+        let merged = merger.merge(host_event(json!({ "host": "server-1", "mem": 50 })), true);
+
+        // Assert
+        assert_eq!(host_event(json!({ "host": "server-1", "cpu": 10, "disk": "ok" })), preview);
+        assert_eq!(host_event(json!({ "host": "server-1", "cpu": 10, "mem": 50 })), merged);
+    }
+
+    #[test]
+    fn should_forget_the_state_after_the_ttl() {
+        // Arrange
+        let mut merger = merger(60, 10);
+        let now = Instant::now();
+        merger.merge_at(host_event(json!({ "host": "server-1", "cpu": 10 })), true, now);
+
+        // Act
+        let merged = merger.merge_at(
+            host_event(json!({ "host": "server-1", "disk": "ok" })),
+            true,
+            now + Duration::from_secs(61),
+        );
+
+        // Assert
+        assert_eq!(host_event(json!({ "host": "server-1", "disk": "ok" })), merged);
+    }
+
+    #[test]
+    fn should_forget_the_least_recently_updated_entity_if_full() {
+        // Arrange
+        let mut merger = merger(60, 2);
+        let now = Instant::now();
+        merger.merge_at(host_event(json!({ "host": "server-1", "cpu": 1 })), true, now);
+        merger.merge_at(
+            host_event(json!({ "host": "server-2", "cpu": 2 })),
+            true,
+            now + Duration::from_secs(1),
+        );
+        merger.merge_at(
+            host_event(json!({ "host": "server-1", "disk": "ok" })),
+            true,
+            now + Duration::from_secs(2),
+        );
+
+        // Act
+        merger.merge_at(
+            host_event(json!({ "host": "server-3", "cpu": 3 })),
+            true,
+            now + Duration::from_secs(3),
+        );
+
+        // Assert
+        assert_eq!(2, merger.states.len());
+        assert_eq!(2, merger.recency.len());
+        assert!(contains_entity(&merger, "server-1"));
+        assert!(contains_entity(&merger, "server-3"));
+    }
+
+    #[test]
+    fn should_remove_the_expired_states_when_a_new_entity_is_added() {
+        // Arrange
+        let mut merger = merger(60, 10);
+        let now = Instant::now();
+        merger.merge_at(host_event(json!({ "host": "server-1", "cpu": 1 })), true, now);
+        merger.merge_at(
+            host_event(json!({ "host": "server-2", "cpu": 2 })),
+            true,
+            now + Duration::from_secs(30),
+        );
+
+        // Act
+        merger.merge_at(
+            host_event(json!({ "host": "server-3", "cpu": 3 })),
+            true,
+            now + Duration::from_secs(61),
+        );
+
+        // Assert
+        assert_eq!(2, merger.states.len());
+        assert!(!contains_entity(&merger, "server-1"));
+        assert!(contains_entity(&merger, "server-2"));
+        assert!(contains_entity(&merger, "server-3"));
+    }
+
+    #[test]
+    fn should_identify_the_entity_by_a_key_of_several_fields() {
+        // Arrange
+        let mut merger = EventMergePatchConfig {
+            entity_key: DedupKey::Fields {
+                fields: vec![
+                    "${event.payload.host}".to_owned(),
+                    "${event.payload.service}".to_owned(),
+                ],
+                separator: "::".to_owned(),
+            },
+            ttl_secs: 60,
+            max_entities: 10,
+        }
+        .build()
+        .unwrap();
+        merger.merge(
+            host_event(json!({ "host": "server-1", "service": "ping", "state": "OK" })),
+            true,
+        );
+
+        // Act
+        let other_service =
+            merger.merge(host_event(json!({ "host": "server-1", "service": "disk" })), true);
+        let same_service =
+            merger.merge(host_event(json!({ "host": "server-1", "service": "ping" })), true);
+
+        // Assert
+        assert_eq!(host_event(json!({ "host": "server-1", "service": "disk" })), other_service);
+        assert_eq!(
+            host_event(json!({ "host": "server-1", "service": "ping", "state": "OK" })),
+            same_service
+        );
+    }
+
+    #[test]
+    fn should_not_merge_the_events_of_different_tenants_or_types() {
+        // Arrange
+        let mut merger = merger(60, 10);
+        let tenant_event = |tenant_id: &str, payload: Value| {
+            json!({
+                "type": "host_state",
+                "created_ms": 0,
+                "payload": payload,
+                "metadata": { "tenant_id": tenant_id }
+            })
+        };
+        merger.merge(tenant_event("alpha", json!({ "host": "server-1", "cpu": 10 })), true);
+
+        // Act
+        let other_tenant =
+            merger.merge(tenant_event("beta", json!({ "host": "server-1", "disk": "ok" })), true);
+        let other_type = merger.merge(
+            json!({ "type": "host_check", "created_ms": 0, "payload": { "host": "server-1" } }),
+            true,
+        );
+        let same_tenant =
+            merger.merge(tenant_event("alpha", json!({ "host": "server-1", "mem": 50 })), true);
+
+        // Assert
+        assert_eq!(tenant_event("beta", json!({ "host": "server-1", "disk": "ok" })), other_tenant);
+        assert_eq!(
+            json!({ "type": "host_check", "created_ms": 0, "payload": { "host": "server-1" } }),
+            other_type
+        );
+        assert_eq!(
+            tenant_event("alpha", json!({ "host": "server-1", "cpu": 10, "mem": 50 })),
+            same_tenant
+        );
+    }
+}
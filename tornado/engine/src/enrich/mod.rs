@@ -1 +1,3 @@
+pub mod lookup;
+pub mod merge_patch;
 pub mod nats;
@@ -0,0 +1,177 @@
+use log::*;
+use std::error::Error;
+use std::fmt;
+
+pub type BoxedError = Box<dyn Error + Send + Sync + 'static>;
+
+/// The reason why Tornado stops because of a failure.
+/// Each reason has a distinct process exit code, so that a supervisor can react appropriately,
+///   e.g. by not restarting Tornado until an invalid configuration is fixed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownReason {
+    /// Any failure not covered by the other reasons
+    Failure,
+    /// The configuration files cannot be read or are not valid
+    ConfigLoad,
+    /// A server cannot listen on its address, e.g. because the port is already in use
+    Bind,
+    /// The Matcher cannot be built from the processing tree
+    MatcherBuild,
+}
+
+impl ShutdownReason {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ShutdownReason::Failure => 1,
+            ShutdownReason::ConfigLoad => 2,
+            ShutdownReason::Bind => 3,
+            ShutdownReason::MatcherBuild => 4,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShutdownReason::Failure => "failure",
+            ShutdownReason::ConfigLoad => "config_load",
+            ShutdownReason::Bind => "bind",
+            ShutdownReason::MatcherBuild => "matcher_build",
+        }
+    }
+
+    /// Returns the reason of the error, if it is a ShutdownError; otherwise, a generic Failure.
+    pub fn of(error: &(dyn Error + 'static)) -> ShutdownReason {
+        error
+            .downcast_ref::<ShutdownError>()
+            .map(|shutdown_error| shutdown_error.reason)
+            .unwrap_or(ShutdownReason::Failure)
+    }
+}
+
+/// An error that stops Tornado, along with the reason of the failure.
+#[derive(Debug)]
+pub struct ShutdownError {
+    pub reason: ShutdownReason,
+    pub cause: BoxedError,
+}
+
+impl ShutdownError {
+    /// Returns a function that wraps an error with the given reason, to be used with `map_err`.
+    pub fn with_reason<E: Into<BoxedError>>(
+        reason: ShutdownReason,
+    ) -> impl FnOnce(E) -> BoxedError {
+        move |cause| Box::new(ShutdownError { reason, cause: cause.into() })
+    }
+}
+
+impl fmt::Display for ShutdownError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.reason.name(), self.cause)
+    }
+}
+
+impl Error for ShutdownError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.cause.as_ref())
+    }
+}
+
+/// Writes the final log record with the reason of the failure and terminates the process
+///   with the exit code of the reason.
+pub fn exit_with_reason(reason: ShutdownReason, cause: &dyn fmt::Display) -> ! {
+    let record = shutdown_record(reason, cause);
+    // The logger is not available if the failure happened before its configuration was loaded
+    if log::max_level() == LevelFilter::Off {
+        eprintln!("{}", record);
+    } else {
+        error!("{}", record);
+        log::logger().flush();
+    }
+    std::process::exit(reason.exit_code())
+}
+
+fn shutdown_record(reason: ShutdownReason, cause: &dyn fmt::Display) -> String {
+    format!(
+        "Tornado is shutting down because of a failure. reason=[{}] exit_code=[{}] cause=[{}]",
+        reason.name(),
+        reason.exit_code(),
+        cause
+    )
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::config::build_config;
+    use std::collections::HashSet;
+    use tornado_common::TornadoError;
+
+    #[test]
+    fn should_map_each_reason_to_a_distinct_exit_code() {
+        // Arrange
+        let reasons = [
+            ShutdownReason::Failure,
+            ShutdownReason::ConfigLoad,
+            ShutdownReason::Bind,
+            ShutdownReason::MatcherBuild,
+        ];
+
+        // Act
+        let exit_codes = reasons.iter().map(ShutdownReason::exit_code).collect::<HashSet<_>>();
+
+        // Assert
+        assert_eq!(reasons.len(), exit_codes.len());
+        assert_eq!(1, ShutdownReason::Failure.exit_code());
+        assert_eq!(2, ShutdownReason::ConfigLoad.exit_code());
+        assert_eq!(3, ShutdownReason::Bind.exit_code());
+        assert_eq!(4, ShutdownReason::MatcherBuild.exit_code());
+    }
+
+    #[test]
+    fn should_return_the_reason_of_a_shutdown_error() {
+        for reason in
+            [ShutdownReason::ConfigLoad, ShutdownReason::Bind, ShutdownReason::MatcherBuild]
+        {
+            // Arrange
+            let error = ShutdownError::with_reason(reason)(TornadoError::ExecutionError {
+                message: "error".to_owned(),
+            });
+
+            // Act & Assert
+            assert_eq!(reason, ShutdownReason::of(error.as_ref()));
+        }
+    }
+
+    #[test]
+    fn should_return_a_generic_failure_for_other_errors() {
+        // Arrange
+        let error: BoxedError =
+            Box::new(TornadoError::ExecutionError { message: "error".to_owned() });
+
+        // Act & Assert
+        assert_eq!(ShutdownReason::Failure, ShutdownReason::of(error.as_ref()));
+        assert_eq!(1, ShutdownReason::of(error.as_ref()).exit_code());
+    }
+
+    #[test]
+    fn should_map_a_missing_config_file_to_the_config_load_exit_code() {
+        // Act
+        let error = build_config("./not_existing_dir")
+            .map_err(ShutdownError::with_reason(ShutdownReason::ConfigLoad))
+            .unwrap_err();
+
+        // Assert
+        assert_eq!(2, ShutdownReason::of(error.as_ref()).exit_code());
+    }
+
+    #[test]
+    fn should_name_the_reason_in_the_shutdown_record() {
+        // Act
+        let record = shutdown_record(ShutdownReason::Bind, &"address in use");
+
+        // Assert
+        assert!(record.contains("reason=[bind]"));
+        assert!(record.contains("exit_code=[3]"));
+        assert!(record.contains("cause=[address in use]"));
+    }
+}
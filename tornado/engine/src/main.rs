@@ -1,5 +1,6 @@
 use crate::command::apm_tracing::apm_tracing;
 use crate::config::{Opt, SubCommand};
+use crate::exit::{exit_with_reason, BoxedError, ShutdownReason};
 use clap::Parser;
 
 pub mod actor;
@@ -7,12 +8,21 @@ mod api;
 mod command;
 pub mod config;
 mod enrich;
+mod exit;
 mod monitoring;
+mod rate_limit;
+mod tls;
 
 #[actix_web::main]
-async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+async fn main() {
     let opt: Opt = Opt::parse();
 
+    if let Err(err) = run(&opt).await {
+        exit_with_reason(ShutdownReason::of(err.as_ref()), &err);
+    }
+}
+
+async fn run(opt: &Opt) -> Result<(), BoxedError> {
     let config_dir = opt.config_dir();
     let rules_dir = opt.rules_dir();
     let drafts_dir = opt.drafts_dir();
@@ -1,10 +1,14 @@
 use crate::config::DaemonCommandConfig;
+use actix_web::dev::Server;
 use actix_web::web::Data;
 use actix_web::web::Json;
-use actix_web::{web, HttpRequest, HttpResponse, Result, Scope};
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer, Result, Scope};
 use chrono::prelude::Local;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::TcpListener;
 use std::sync::Arc;
+use tornado_common::health::ConnectionHealth;
 use tornado_common_metrics::endpoint::actix_web::metrics_endpoints;
 use tornado_common_metrics::Metrics;
 
@@ -12,16 +16,40 @@ pub fn monitoring_endpoints(
     scope: Scope,
     daemon_command_config: DaemonCommandConfig,
     metrics: Arc<Metrics>,
+    dependencies_health: DependenciesHealth,
 ) -> Scope {
-    scope
+    let metrics_endpoint_on_web_server = daemon_command_config.is_metrics_endpoint_on_web_server();
+    let scope = scope
         .app_data(Data::new(daemon_command_config))
+        .app_data(Data::new(dependencies_health))
         .service(web::resource("").route(web::get().to(index)))
         .service(web::resource("/ping").route(web::get().to(pong)))
+        .service(web::resource("/health/ready").route(web::get().to(readiness)))
         .service(
             web::resource("/communication_channel_config")
                 .route(web::get().to(communication_channel_config)),
-        )
-        .service(metrics_endpoints(metrics))
+        );
+    if metrics_endpoint_on_web_server {
+        scope.service(metrics_endpoints(metrics))
+    } else {
+        scope
+    }
+}
+
+/// Starts the dedicated server of the metrics endpoint on the given listener.
+/// It exposes only the metrics, at the same path used by the Web Server of the API,
+///   i.e. `/monitoring/v1/metrics/prometheus`.
+pub fn start_metrics_server(
+    listener: TcpListener,
+    metrics: Arc<Metrics>,
+) -> std::io::Result<Server> {
+    let server = HttpServer::new(move || {
+        App::new().service(web::scope("/monitoring").service(metrics_endpoints(metrics.clone())))
+    })
+    .workers(1)
+    .listen(listener)?
+    .run();
+    Ok(server)
 }
 
 async fn index(_req: HttpRequest) -> HttpResponse {
@@ -31,6 +59,7 @@ async fn index(_req: HttpRequest) -> HttpResponse {
             <h1>Available endpoints:</h1>
             <ul>
                 <li><a href="/monitoring/ping">Ping</a></li>
+                <li><a href="/monitoring/health/ready">Readiness</a></li>
                 <li><a href="/monitoring/communication_channel_config">Communication Channel Config</a></li>
             </ul>
         </div>
@@ -49,6 +78,43 @@ async fn pong(_req: HttpRequest) -> Result<Json<PongResponse>> {
     Ok(Json(PongResponse { message: format!("pong - {}", created_ms) }))
 }
 
+/// The connection state of the external dependencies of Tornado.
+/// A dependency that is not used (e.g. NATS when it is not enabled) is not tracked.
+#[derive(Clone, Default)]
+pub struct DependenciesHealth {
+    pub nats: Option<ConnectionHealth>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReadinessResponse {
+    pub ready: bool,
+    pub dependencies: BTreeMap<String, DependencyStatus>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DependencyStatus {
+    pub connected: bool,
+    pub healthy: bool,
+}
+
+async fn readiness(dependencies_health: Data<DependenciesHealth>) -> HttpResponse {
+    let mut dependencies = BTreeMap::new();
+    if let Some(nats) = &dependencies_health.nats {
+        dependencies.insert(
+            "nats".to_owned(),
+            DependencyStatus { connected: nats.is_connected(), healthy: nats.is_healthy() },
+        );
+    }
+
+    let ready = dependencies.values().all(|dependency| dependency.healthy);
+    let response = ReadinessResponse { ready, dependencies };
+    if ready {
+        HttpResponse::Ok().json(response)
+    } else {
+        HttpResponse::ServiceUnavailable().json(response)
+    }
+}
+
 async fn communication_channel_config(
     daemon_command_config: Data<DaemonCommandConfig>,
 ) -> Result<Json<CommunicationChannelConfig>> {
@@ -67,8 +133,9 @@ pub struct CommunicationChannelConfig {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::config::AuthConfig;
-    use actix_web::{test, App};
+    use crate::config::MetricsEndpointConfig;
+    use actix_web::http::StatusCode;
+    use actix_web::test;
     use chrono::DateTime;
     use std::time;
     use tornado_common_metrics::opentelemetry::Key;
@@ -76,25 +143,12 @@ mod test {
     #[actix_rt::test]
     async fn index_should_have_links_to_the_endpoints() {
         // Arrange
-        let daemon_config = DaemonCommandConfig {
-            event_tcp_socket_enabled: None,
-            event_socket_ip: None,
-            event_socket_port: None,
-            nats_enabled: None,
-            nats: None,
-            nats_extractors: vec![],
-            web_server_ip: "".to_string(),
-            web_server_port: 0,
-            web_max_json_payload_size: None,
-            message_queue_size: 0,
-            thread_pool_config: None,
-            retry_strategy: Default::default(),
-            auth: AuthConfig::default(),
-        };
+        let daemon_config = DaemonCommandConfig::default();
         let srv = test::init_service(App::new().service(monitoring_endpoints(
             web::scope("/monitoring"),
             daemon_config,
             Arc::new(Metrics::new("a")),
+            DependenciesHealth::default(),
         )))
         .await;
 
@@ -105,6 +159,7 @@ mod test {
         // Assert
         let body = std::str::from_utf8(&response).unwrap();
         assert!(body.contains(r#"<a href="/monitoring/ping">"#));
+        assert!(body.contains(r#"<a href="/monitoring/health/ready">"#));
         assert!(body.contains(
             r#"<a href="/monitoring/communication_channel_config">Communication Channel Config</a>"#
         ));
@@ -115,23 +170,14 @@ mod test {
         // Arrange
         let daemon_config = DaemonCommandConfig {
             event_tcp_socket_enabled: Some(true),
-            event_socket_ip: None,
-            event_socket_port: None,
             nats_enabled: Some(false),
-            nats: None,
-            nats_extractors: vec![],
-            web_server_ip: "".to_string(),
-            web_server_port: 0,
-            web_max_json_payload_size: None,
-            message_queue_size: 0,
-            thread_pool_config: None,
-            retry_strategy: Default::default(),
-            auth: AuthConfig::default(),
+            ..Default::default()
         };
         let srv = test::init_service(App::new().service(monitoring_endpoints(
             web::scope("/monitoring"),
             daemon_config,
             Arc::new(Metrics::new("a")),
+            DependenciesHealth::default(),
         )))
         .await;
 
@@ -147,28 +193,80 @@ mod test {
         assert!(date.is_ok());
     }
 
+    #[actix_rt::test]
+    async fn readiness_should_report_nats_unhealthy_after_the_grace_period() {
+        // Arrange
+        let nats_health = ConnectionHealth::new(time::Duration::from_millis(100));
+        nats_health.set_connected();
+        let srv = test::init_service(App::new().service(monitoring_endpoints(
+            web::scope("/monitoring"),
+            new_daemon_config(),
+            Arc::new(Metrics::new("a")),
+            DependenciesHealth { nats: Some(nats_health.clone()) },
+        )))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get().uri("/monitoring/health/ready").to_request();
+        let connected_response = test::call_service(&srv, request).await;
+
+        nats_health.set_disconnected();
+        let request = test::TestRequest::get().uri("/monitoring/health/ready").to_request();
+        let disconnected_response = test::call_service(&srv, request).await;
+
+        tokio::time::sleep(time::Duration::from_millis(150)).await;
+        let request = test::TestRequest::get().uri("/monitoring/health/ready").to_request();
+        let unhealthy_response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, connected_response.status());
+
+        assert_eq!(StatusCode::OK, disconnected_response.status());
+        let readiness: ReadinessResponse = test::read_body_json(disconnected_response).await;
+        assert!(readiness.ready);
+        assert!(!readiness.dependencies["nats"].connected);
+        assert!(readiness.dependencies["nats"].healthy);
+
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, unhealthy_response.status());
+        let readiness: ReadinessResponse = test::read_body_json(unhealthy_response).await;
+        assert!(!readiness.ready);
+        assert!(!readiness.dependencies["nats"].healthy);
+    }
+
+    #[actix_rt::test]
+    async fn readiness_should_be_ready_without_dependencies() {
+        // Arrange
+        let srv = test::init_service(App::new().service(monitoring_endpoints(
+            web::scope("/monitoring"),
+            new_daemon_config(),
+            Arc::new(Metrics::new("a")),
+            DependenciesHealth::default(),
+        )))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get().uri("/monitoring/health/ready").to_request();
+        let readiness: ReadinessResponse = test::call_and_read_body_json(&srv, request).await;
+
+        // Assert
+        assert!(readiness.ready);
+        assert!(readiness.dependencies.is_empty());
+    }
+
+    fn new_daemon_config() -> DaemonCommandConfig {
+        DaemonCommandConfig { nats_enabled: Some(true), ..Default::default() }
+    }
+
     #[actix_rt::test]
     async fn communication_ch_should_return_correct_configs() {
         // Arrange
-        let daemon_config = DaemonCommandConfig {
-            event_tcp_socket_enabled: Some(true),
-            event_socket_ip: None,
-            event_socket_port: None,
-            nats_enabled: None,
-            nats: None,
-            nats_extractors: vec![],
-            web_server_ip: "".to_string(),
-            web_server_port: 0,
-            web_max_json_payload_size: None,
-            message_queue_size: 0,
-            thread_pool_config: None,
-            retry_strategy: Default::default(),
-            auth: AuthConfig::default(),
-        };
+        let daemon_config =
+            DaemonCommandConfig { event_tcp_socket_enabled: Some(true), ..Default::default() };
         let srv = test::init_service(App::new().service(monitoring_endpoints(
             web::scope("/monitoring"),
             daemon_config,
             Arc::new(Metrics::new("a")),
+            DependenciesHealth::default(),
         )))
         .await;
 
@@ -192,26 +290,14 @@ mod test {
         // Fixme: The retry loop with a sleep of 1 sec is needed because in the CI this test could fail.
         //        The problem is that the test API fails randomly to start (probably actix bug).
         for _ in 1..30 {
-            let daemon_config = DaemonCommandConfig {
-                event_tcp_socket_enabled: Some(true),
-                event_socket_ip: None,
-                event_socket_port: None,
-                nats_enabled: None,
-                nats: None,
-                nats_extractors: vec![],
-                web_server_ip: "".to_string(),
-                web_server_port: 0,
-                web_max_json_payload_size: None,
-                message_queue_size: 0,
-                thread_pool_config: None,
-                retry_strategy: Default::default(),
-                auth: AuthConfig::default(),
-            };
+            let daemon_config =
+                DaemonCommandConfig { event_tcp_socket_enabled: Some(true), ..Default::default() };
             let metrics = Arc::new(Metrics::new("aa"));
             let srv = test::init_service(App::new().service(monitoring_endpoints(
                 web::scope("/monitoring-test"),
                 daemon_config,
                 metrics.clone(),
+                DependenciesHealth::default(),
             )))
             .await;
 
@@ -239,4 +325,98 @@ mod test {
         }
         assert!(found)
     }
+
+    fn daemon_config_with_metrics_endpoint(
+        metrics_endpoint: Option<MetricsEndpointConfig>,
+    ) -> DaemonCommandConfig {
+        DaemonCommandConfig { metrics_endpoint, ..Default::default() }
+    }
+
+    #[actix_rt::test]
+    async fn should_expose_the_metrics_only_on_the_dedicated_server_if_configured() {
+        // Arrange
+        let metrics = Arc::new(Metrics::new("a"));
+        let daemon_config = daemon_config_with_metrics_endpoint(Some(MetricsEndpointConfig {
+            enabled: true,
+            ip: "127.0.0.1".to_owned(),
+            port: Some(0),
+        }));
+
+        let api_metrics = metrics.clone();
+        let api_server = HttpServer::new(move || {
+            App::new().service(monitoring_endpoints(
+                web::scope("/monitoring"),
+                daemon_config.clone(),
+                api_metrics.clone(),
+                DependenciesHealth::default(),
+            ))
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let api_port = api_server.addrs()[0].port();
+        let api_server = api_server.run();
+        let api_server_handle = api_server.handle();
+        actix_rt::spawn(api_server);
+
+        let metrics_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let metrics_port = metrics_listener.local_addr().unwrap().port();
+        let metrics_server = start_metrics_server(metrics_listener, metrics).unwrap();
+        let metrics_server_handle = metrics_server.handle();
+        actix_rt::spawn(metrics_server);
+
+        let client = reqwest::Client::new();
+
+        // Act
+        let metrics_server_response = client
+            .get(format!("http://127.0.0.1:{}/monitoring/v1/metrics/prometheus", metrics_port))
+            .send()
+            .await
+            .unwrap();
+        let api_server_response = client
+            .get(format!("http://127.0.0.1:{}/monitoring/v1/metrics/prometheus", api_port))
+            .send()
+            .await
+            .unwrap();
+        let api_server_ping_response = client
+            .get(format!("http://127.0.0.1:{}/monitoring/ping", api_port))
+            .send()
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(200, metrics_server_response.status().as_u16());
+        assert_eq!(404, api_server_response.status().as_u16());
+        assert_eq!(200, api_server_ping_response.status().as_u16());
+
+        api_server_handle.stop(true).await;
+        metrics_server_handle.stop(true).await;
+    }
+
+    #[actix_rt::test]
+    async fn should_not_expose_the_metrics_if_disabled() {
+        // Arrange
+        let daemon_config = daemon_config_with_metrics_endpoint(Some(MetricsEndpointConfig {
+            enabled: false,
+            ip: "127.0.0.1".to_owned(),
+            port: Some(9090),
+        }));
+        assert_eq!(None, daemon_config.metrics_server_address());
+
+        let srv = test::init_service(App::new().service(monitoring_endpoints(
+            web::scope("/monitoring"),
+            daemon_config,
+            Arc::new(Metrics::new("a")),
+            DependenciesHealth::default(),
+        )))
+        .await;
+
+        // Act
+        let request =
+            test::TestRequest::get().uri("/monitoring/v1/metrics/prometheus").to_request();
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
 }
@@ -1,9 +1,13 @@
-use tornado_common_metrics::opentelemetry::metrics::{Counter, Unit, ValueRecorder};
+use crate::monitoring::rule_stats::RuleMatchStats;
+use tornado_common_metrics::opentelemetry::metrics::{Counter, Meter, Unit, ValueRecorder};
 use tornado_common_metrics::opentelemetry::Key;
 
 pub const TORNADO_APP: &str = "tornado";
 pub const EVENT_TYPE_LABEL_KEY: Key = Key::from_static_str("event_type");
 pub const EVENT_SOURCE_LABEL_KEY: Key = Key::from_static_str("source");
+pub const TENANT_ID_LABEL_KEY: Key = Key::from_static_str("tenant_id");
+pub const RULESET_LABEL_KEY: Key = Key::from_static_str("ruleset");
+pub const RULE_NAME_LABEL_KEY: Key = Key::from_static_str("rule_name");
 
 pub struct TornadoMeter {
     /// Counts the total invalid events received
@@ -14,6 +18,20 @@ pub struct TornadoMeter {
     pub events_processed_counter: Counter<u64>,
     /// Counts the total events processing seconds
     pub events_processed_duration_seconds: ValueRecorder<f64>,
+    /// Counts the total events whose processing exceeded the processing budget
+    pub events_over_budget_counter: Counter<u64>,
+    /// Counts the total events rejected because their tenant exceeded its rate limit
+    pub events_rate_limited_counter: Counter<u64>,
+    /// Counts the total events dropped because they were older than the event TTL
+    pub events_expired_counter: Counter<u64>,
+    /// Counts the total matches of each rule
+    pub rules_matched_counter: Counter<u64>,
+    /// Keeps the match count and the last match time of each rule
+    pub rule_match_stats: RuleMatchStats,
+    /// Counts the total TCP connections refused because the max concurrent connections were reached
+    pub tcp_connections_refused_counter: Counter<u64>,
+    /// Counts the total TCP connections refused because the peer is not in the allowed networks
+    pub tcp_connections_not_allowed_counter: Counter<u64>,
     /// Counts the total http requests received
     pub http_requests_counter: Counter<u64>,
     /// Counts the total http requests processing seconds
@@ -22,36 +40,82 @@ pub struct TornadoMeter {
 
 impl Default for TornadoMeter {
     fn default() -> Self {
-        let meter = tornado_common_metrics::opentelemetry::global::meter("tornado");
+        Self::new_with_prefix("")
+    }
+}
+
+impl TornadoMeter {
+    /// Creates a TornadoMeter whose instrument names start with the given prefix,
+    /// e.g. with the prefix `tornado_prod_` the events received are counted by
+    /// `tornado_prod_events_received_counter`.
+    pub fn new_with_prefix(metric_name_prefix: &str) -> Self {
+        Self::new_with_meter(
+            &tornado_common_metrics::opentelemetry::global::meter("tornado"),
+            metric_name_prefix,
+        )
+    }
 
+    /// Creates a TornadoMeter whose instruments are created by the given meter instead of
+    /// the global one.
+    pub fn new_with_meter(meter: &Meter, metric_name_prefix: &str) -> Self {
         let invalid_events_received_counter = meter
-            .u64_counter("invalid_events_received_counter")
+            .u64_counter(format!("{}invalid_events_received_counter", metric_name_prefix))
             .with_description("Invalid events received count")
             .init();
 
         let events_received_counter = meter
-            .u64_counter("events_received_counter")
+            .u64_counter(format!("{}events_received_counter", metric_name_prefix))
             .with_description("Events received count")
             .init();
 
         let events_processed_counter = meter
-            .u64_counter("events_processed_counter")
+            .u64_counter(format!("{}events_processed_counter", metric_name_prefix))
             .with_description("Events processed count")
             .init();
 
         let events_processed_duration_seconds = meter
-            .f64_value_recorder("events_processed_duration_seconds")
+            .f64_value_recorder(format!("{}events_processed_duration_seconds", metric_name_prefix))
             .with_description("Events processed duration")
             .with_unit(Unit::new("seconds"))
             .init();
 
+        let events_over_budget_counter = meter
+            .u64_counter(format!("{}events_over_budget_counter", metric_name_prefix))
+            .with_description("Events processed over the processing budget count")
+            .init();
+
+        let events_rate_limited_counter = meter
+            .u64_counter(format!("{}events_rate_limited_counter", metric_name_prefix))
+            .with_description("Events rejected by the tenant rate limits count")
+            .init();
+
+        let events_expired_counter = meter
+            .u64_counter(format!("{}events_expired_counter", metric_name_prefix))
+            .with_description("Events dropped because older than the TTL count")
+            .init();
+
+        let rules_matched_counter = meter
+            .u64_counter(format!("{}rules_matched_counter", metric_name_prefix))
+            .with_description("Rules matched count")
+            .init();
+
+        let tcp_connections_refused_counter = meter
+            .u64_counter(format!("{}tcp_connections_refused_counter", metric_name_prefix))
+            .with_description("TCP connections refused count")
+            .init();
+
+        let tcp_connections_not_allowed_counter = meter
+            .u64_counter(format!("{}tcp_connections_not_allowed_counter", metric_name_prefix))
+            .with_description("TCP connections from not allowed peers count")
+            .init();
+
         let http_requests_counter = meter
-            .u64_counter("http_requests_counter")
+            .u64_counter(format!("{}http_requests_counter", metric_name_prefix))
             .with_description("HTTP requests count")
             .init();
 
         let http_requests_duration_seconds = meter
-            .f64_value_recorder("http_requests_duration_secs")
+            .f64_value_recorder(format!("{}http_requests_duration_secs", metric_name_prefix))
             .with_description("HTTP requests duration")
             .with_unit(Unit::new("seconds"))
             .init();
@@ -61,8 +125,45 @@ impl Default for TornadoMeter {
             events_received_counter,
             events_processed_counter,
             events_processed_duration_seconds,
+            events_over_budget_counter,
+            events_rate_limited_counter,
+            events_expired_counter,
+            rules_matched_counter,
+            rule_match_stats: RuleMatchStats::default(),
+            tcp_connections_refused_counter,
+            tcp_connections_not_allowed_counter,
             http_requests_counter,
             http_requests_duration_seconds,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use tornado_common_metrics::opentelemetry::metrics::MeterProvider;
+    use tornado_common_metrics::prometheus::{Encoder, TextEncoder};
+    use tornado_common_metrics::Metrics;
+
+    #[actix_rt::test]
+    async fn should_prepend_the_prefix_to_the_instrument_names() {
+        // Arrange
+        let metrics = Metrics::new(TORNADO_APP);
+        let provider = metrics.prometheus_exporter.provider().unwrap();
+
+        // Act
+        let meter =
+            TornadoMeter::new_with_meter(&provider.meter(TORNADO_APP, None), "tornado_prod_");
+        meter.events_received_counter.add(1, &[]);
+
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&metrics.prometheus_exporter.registry().gather(), &mut buf)
+            .unwrap();
+        let content = String::from_utf8(buf).unwrap();
+
+        // Assert
+        assert!(content.contains("tornado_prod_events_received_counter"));
+    }
+}
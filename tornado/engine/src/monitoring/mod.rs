@@ -1,2 +1,3 @@
 pub mod endpoint;
 pub mod metrics;
+pub mod rule_stats;
@@ -0,0 +1,315 @@
+use crate::monitoring::metrics::TornadoMeter;
+use actix_web::web::{Bytes, Data};
+use actix_web::{web, HttpRequest, HttpResponse, Scope};
+use chrono::{DateTime, Utc};
+use log::*;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tornado_engine_api::auth::Permission;
+use tornado_engine_api::model::ApiData;
+use tornado_engine_matcher::model::{ProcessedEvent, ProcessedNode, ProcessedRuleStatus};
+
+/// The separator of the node names in the ruleset path of the matched rules
+const RULESET_PATH_SEPARATOR: &str = "/";
+
+const RULE_MATCHES_CSV_HEADER: &str = "ruleset,rule_name,match_count,last_match\n";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleMatches {
+    pub match_count: u64,
+    pub last_match: SystemTime,
+}
+
+/// Keeps, for each rule, how many times it was matched and when it was matched the last time.
+/// The rules are identified by the path of their ruleset and by their name.
+#[derive(Default)]
+pub struct RuleMatchStats {
+    matches: Mutex<BTreeMap<(String, String), RuleMatches>>,
+}
+
+impl RuleMatchStats {
+    /// Records a match, at the given time, of the rule with the given ruleset path and name.
+    pub fn record(&self, ruleset_path: String, rule_name: String, matched_at: SystemTime) {
+        let mut matches = self.matches.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let rule_matches = matches
+            .entry((ruleset_path, rule_name))
+            .or_insert(RuleMatches { match_count: 0, last_match: matched_at });
+        rule_matches.match_count += 1;
+        rule_matches.last_match = matched_at;
+    }
+
+    /// Returns the statistics of the rule that follows the given one, ordered by ruleset path
+    ///   and rule name, or of the first rule if None is given.
+    /// The lock is held only to read a single rule, so the statistics can be read one rule at a
+    ///   time while the matches are recorded.
+    pub fn next_after(
+        &self,
+        previous: Option<&(String, String)>,
+    ) -> Option<((String, String), RuleMatches)> {
+        let matches = self.matches.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let lower_bound = match previous {
+            Some(previous) => Bound::Excluded(previous),
+            None => Bound::Unbounded,
+        };
+        matches
+            .range((lower_bound, Bound::Unbounded))
+            .next()
+            .map(|(key, rule_matches)| (key.clone(), rule_matches.clone()))
+    }
+}
+
+/// Returns the ruleset path and the name of the rules matched by the processed event.
+/// A rule matched by more iterations of an Iterator is returned once for each iteration.
+pub fn matched_rules(processed_event: &ProcessedEvent) -> Vec<(String, String)> {
+    let mut matched_rules = vec![];
+    collect_matched_rules(&processed_event.result, &mut vec![], &mut matched_rules);
+    matched_rules
+}
+
+fn collect_matched_rules<'a>(
+    node: &'a ProcessedNode,
+    path: &mut Vec<&'a str>,
+    matched_rules: &mut Vec<(String, String)>,
+) {
+    match node {
+        ProcessedNode::Filter { name, nodes, .. } => {
+            path.push(name);
+            for node in nodes {
+                collect_matched_rules(node, path, matched_rules);
+            }
+            path.pop();
+        }
+        ProcessedNode::Iterator { name, events, .. } => {
+            path.push(name);
+            for node in events.iter().flat_map(|iteration| iteration.result.iter()) {
+                collect_matched_rules(node, path, matched_rules);
+            }
+            path.pop();
+        }
+        ProcessedNode::Ruleset { name, rules } => {
+            path.push(name);
+            let ruleset_path = path.join(RULESET_PATH_SEPARATOR);
+            for rule in
+                rules.rules.iter().filter(|rule| rule.status == ProcessedRuleStatus::Matched)
+            {
+                matched_rules.push((ruleset_path.clone(), rule.name.clone()));
+            }
+            path.pop();
+        }
+    }
+}
+
+pub fn rule_stats_endpoints(scope: Scope, data: ApiData<Arc<TornadoMeter>>) -> Scope {
+    scope
+        .app_data(Data::new(data))
+        .service(web::resource("/rule-matches.csv").route(web::get().to(rule_matches_csv)))
+}
+
+/// Streams the rule match statistics as CSV, one chunk for each row.
+/// Each row is read when it is sent, so the whole table is never copied.
+async fn rule_matches_csv(
+    req: HttpRequest,
+    data: Data<ApiData<Arc<TornadoMeter>>>,
+) -> actix_web::Result<HttpResponse> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req)?;
+    auth_ctx.has_permission(&Permission::ConfigView)?;
+
+    let tornado_meter = data.api.clone();
+    let rows = futures_util::stream::unfold(None, move |previous: Option<(String, String)>| {
+        let next = tornado_meter.rule_match_stats.next_after(previous.as_ref());
+        async move {
+            next.map(|(key, rule_matches)| {
+                let row = Bytes::from(format!(
+                    "{},{},{},{}\n",
+                    escape_csv_field(&key.0),
+                    escape_csv_field(&key.1),
+                    rule_matches.match_count,
+                    DateTime::<Utc>::from(rule_matches.last_match).to_rfc3339()
+                ));
+                (Ok::<_, actix_web::Error>(row), Some(key))
+            })
+        }
+    });
+    let header = futures_util::stream::once(async {
+        Ok(Bytes::from_static(RULE_MATCHES_CSV_HEADER.as_bytes()))
+    });
+
+    Ok(HttpResponse::Ok().content_type("text/csv").streaming(header.chain(rows)))
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use actix_web::{http::header, http::StatusCode, test, App};
+    use serde_json::json;
+    use tornado_common_api::{Event, Value};
+    use tornado_engine_api::auth::AuthService;
+    use tornado_engine_api_dto::auth::Auth;
+    use tornado_engine_matcher::model::{
+        ProcessedIteration, ProcessedIterator, ProcessedRule, ProcessedRules,
+    };
+
+    fn ruleset(name: &str, rules: Vec<ProcessedRule>) -> ProcessedNode {
+        ProcessedNode::Ruleset {
+            name: name.to_owned(),
+            rules: ProcessedRules { rules, extracted_vars: Value::Null },
+        }
+    }
+
+    fn rule(name: &str, status: ProcessedRuleStatus) -> ProcessedRule {
+        let mut rule = ProcessedRule::new(name.to_owned());
+        rule.status = status;
+        rule
+    }
+
+    #[test]
+    fn should_return_the_matched_rules_of_the_processed_event() {
+        // Arrange
+        let processed_event = ProcessedEvent {
+            event: json!(Event::new("test")),
+            result: ProcessedNode::Iterator {
+                name: "root".to_owned(),
+                iterator: ProcessedIterator::Matched,
+                events: vec![
+                    ProcessedIteration {
+                        event: Value::Null,
+                        result: vec![ruleset(
+                            "ruleset",
+                            vec![
+                                rule("matched", ProcessedRuleStatus::Matched),
+                                rule("not_matched", ProcessedRuleStatus::NotMatched),
+                            ],
+                        )],
+                    },
+                    ProcessedIteration {
+                        event: Value::Null,
+                        result: vec![ruleset(
+                            "ruleset",
+                            vec![rule("matched", ProcessedRuleStatus::Matched)],
+                        )],
+                    },
+                ],
+            },
+        };
+
+        // Act
+        let matched_rules = matched_rules(&processed_event);
+
+        // Assert
+        assert_eq!(
+            vec![
+                ("root/ruleset".to_owned(), "matched".to_owned()),
+                ("root/ruleset".to_owned(), "matched".to_owned())
+            ],
+            matched_rules
+        );
+    }
+
+    fn test_api_data(tornado_meter: Arc<TornadoMeter>) -> ApiData<Arc<TornadoMeter>> {
+        let mut permission_roles_map = BTreeMap::new();
+        permission_roles_map.insert(Permission::ConfigView, vec!["view".to_owned()]);
+        ApiData { auth: AuthService::new(Arc::new(permission_roles_map)), api: tornado_meter }
+    }
+
+    fn auth_header(role: &str) -> (header::HeaderName, String) {
+        (
+            header::AUTHORIZATION,
+            AuthService::auth_to_token_header(&Auth::new("user", vec![role])).unwrap(),
+        )
+    }
+
+    #[actix_rt::test]
+    async fn should_stream_the_rule_matches_as_csv() {
+        // Arrange
+        let tornado_meter = Arc::new(TornadoMeter::default());
+        let matched_at = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+        tornado_meter.rule_match_stats.record(
+            "root/ruleset".to_owned(),
+            "a_matched_rule".to_owned(),
+            matched_at,
+        );
+        tornado_meter.rule_match_stats.record(
+            "root/other_ruleset".to_owned(),
+            "another_rule".to_owned(),
+            matched_at,
+        );
+
+        let srv =
+            test::init_service(App::new().service(rule_stats_endpoints(
+                web::scope("/v1/stats"),
+                test_api_data(tornado_meter),
+            )))
+            .await;
+
+        // Act
+        let request = test::TestRequest::get()
+            .insert_header(auth_header("view"))
+            .uri("/v1/stats/rule-matches.csv")
+            .to_request();
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert!(response.status().is_success());
+        assert_eq!(
+            "text/csv",
+            response.headers().get(actix_web::http::header::CONTENT_TYPE).unwrap()
+        );
+        let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+        let mut lines = body.lines();
+        assert_eq!(Some("ruleset,rule_name,match_count,last_match"), lines.next());
+        assert_eq!(
+            Some("root/other_ruleset,another_rule,1,2020-09-13T12:26:40+00:00"),
+            lines.next()
+        );
+        assert_eq!(Some("root/ruleset,a_matched_rule,2,2020-09-13T12:26:40+00:00"), lines.next());
+        assert_eq!(None, lines.next());
+    }
+
+    #[actix_rt::test]
+    async fn should_require_the_config_view_permission() {
+        // Arrange
+        let srv = test::init_service(App::new().service(rule_stats_endpoints(
+            web::scope("/v1/stats"),
+            test_api_data(Arc::new(TornadoMeter::default())),
+        )))
+        .await;
+
+        // Act
+        let unauthenticated = test::call_service(
+            &srv,
+            test::TestRequest::get().uri("/v1/stats/rule-matches.csv").to_request(),
+        )
+        .await;
+        let forbidden = test::call_service(
+            &srv,
+            test::TestRequest::get()
+                .insert_header(auth_header("edit"))
+                .uri("/v1/stats/rule-matches.csv")
+                .to_request(),
+        )
+        .await;
+
+        // Assert
+        assert_eq!(StatusCode::UNAUTHORIZED, unauthenticated.status());
+        assert_eq!(StatusCode::FORBIDDEN, forbidden.status());
+    }
+
+    #[test]
+    fn should_escape_the_csv_fields() {
+        assert_eq!("rule", escape_csv_field("rule"));
+        assert_eq!("\"a,rule\"", escape_csv_field("a,rule"));
+        assert_eq!("\"a \"\"rule\"\"\"", escape_csv_field("a \"rule\""));
+    }
+}
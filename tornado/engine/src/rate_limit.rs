@@ -0,0 +1,293 @@
+use crate::monitoring::metrics::{TornadoMeter, TENANT_ID_LABEL_KEY};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tornado_common::token_bucket::TokenBucket;
+use tornado_common_api::{Value, ValueExt, WithEventData};
+
+/// The metadata field with the tenant of an event
+const TENANT_ID_METADATA: &str = "tenant_id";
+
+/// The max number of tenants not in the configuration whose budget is tracked at the same time
+const MAX_TRACKED_TENANTS: usize = 10_000;
+
+/// The value of the tenant id label of the metrics of the tenants not in the configuration,
+///   so that the number of the label values does not depend on the incoming events
+const OTHER_TENANTS_LABEL: &str = "other";
+
+/// The max rates of the events accepted for each tenant.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct TenantRateLimitsConfig {
+    /// The max events per second accepted for each tenant, by tenant id
+    #[serde(default)]
+    pub events_per_sec_by_tenant: HashMap<String, f64>,
+    /// The max events per second accepted for each tenant not in `events_per_sec_by_tenant`.
+    /// If not set, the events of those tenants are not limited.
+    pub default_events_per_sec: Option<f64>,
+    /// The max events per second accepted, as a whole, among the events without a tenant,
+    ///   for example the ones received through the TCP socket.
+    /// If not set, the events without a tenant are not limited.
+    pub events_per_sec_without_tenant: Option<f64>,
+}
+
+/// Limits the rate of the incoming events of each tenant, identified by the `tenant_id`
+///   metadata field of the events.
+/// Each tenant has its own budget, so a tenant exceeding its rate does not affect the others.
+/// The events without a tenant share a single budget.
+/// The budgets of the tenants not in the configuration are tracked up to `MAX_TRACKED_TENANTS`;
+///   when they are all in use, the events of the other tenants not in the configuration are
+///   rejected, so that a tenant cannot get a new budget by changing its id.
+pub struct TenantRateLimiter {
+    config: TenantRateLimitsConfig,
+    /// The budgets of the tenants in the configuration, by tenant;
+    ///   the key of the budget of the events without a tenant is None
+    buckets: Mutex<HashMap<Option<String>, TokenBucket>>,
+    /// The budgets of the tenants not in the configuration, by tenant
+    other_tenants_buckets: Mutex<HashMap<String, TokenBucket>>,
+    max_tracked_tenants: usize,
+    tornado_meter: Arc<TornadoMeter>,
+}
+
+impl TenantRateLimiter {
+    pub fn new(config: TenantRateLimitsConfig, tornado_meter: Arc<TornadoMeter>) -> Self {
+        TenantRateLimiter {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+            other_tenants_buckets: Mutex::new(HashMap::new()),
+            max_tracked_tenants: MAX_TRACKED_TENANTS,
+            tornado_meter,
+        }
+    }
+
+    /// Returns whether the event should be processed.
+    /// The events exceeding the rate of their tenant are rejected and counted by the
+    ///   `events_rate_limited_counter` metric, labelled with the tenant id if it is in the
+    ///   configuration, or with `OTHER_TENANTS_LABEL` otherwise.
+    pub fn accept(&self, event: &Value) -> bool {
+        let tenant_id = event
+            .metadata()
+            .and_then(|metadata| metadata.get(TENANT_ID_METADATA))
+            .and_then(|tenant_id| tenant_id.get_text());
+        let (accepted, label) = match tenant_id {
+            Some(tenant_id) => match self.config.events_per_sec_by_tenant.get(tenant_id) {
+                Some(events_per_sec) => {
+                    (self.accept_tracked(Some(tenant_id.to_owned()), *events_per_sec), tenant_id)
+                }
+                None => match self.config.default_events_per_sec {
+                    Some(events_per_sec) => {
+                        (self.accept_other_tenant(tenant_id, events_per_sec), OTHER_TENANTS_LABEL)
+                    }
+                    None => return true,
+                },
+            },
+            None => match self.config.events_per_sec_without_tenant {
+                Some(events_per_sec) => (self.accept_tracked(None, events_per_sec), ""),
+                None => return true,
+            },
+        };
+        if !accepted {
+            self.tornado_meter
+                .events_rate_limited_counter
+                .add(1, &[TENANT_ID_LABEL_KEY.string(label.to_owned())]);
+        }
+        accepted
+    }
+
+    /// Consumes the budget of a tenant in the configuration, or of the events without a tenant.
+    /// Their number is bounded by the configuration, so their budgets are always tracked.
+    fn accept_tracked(&self, key: Option<String>, events_per_sec: f64) -> bool {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        buckets.entry(key).or_insert_with(|| new_bucket(events_per_sec)).try_acquire().is_ok()
+    }
+
+    /// Consumes the budget of a tenant not in the configuration.
+    /// If the max number of these budgets is reached, the full ones are removed, since they are
+    ///   the same as new ones; if none is full, the event is rejected, because removing a budget
+    ///   in use would reset the rate of its tenant.
+    fn accept_other_tenant(&self, tenant_id: &str, events_per_sec: f64) -> bool {
+        let mut buckets =
+            self.other_tenants_buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(bucket) = buckets.get_mut(tenant_id) {
+            return bucket.try_acquire().is_ok();
+        }
+        if buckets.len() >= self.max_tracked_tenants {
+            let now = Instant::now();
+            buckets.retain(|_, bucket| !bucket.is_full_at(now));
+            if buckets.len() >= self.max_tracked_tenants {
+                return false;
+            }
+        }
+        buckets
+            .entry(tenant_id.to_owned())
+            .or_insert_with(|| new_bucket(events_per_sec))
+            .try_acquire()
+            .is_ok()
+    }
+}
+
+fn new_bucket(events_per_sec: f64) -> TokenBucket {
+    // The burst allows the events of one second to arrive at the same time
+    TokenBucket::new(events_per_sec, events_per_sec.ceil().max(1.0))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+    use tornado_common_api::Event;
+
+    fn event_of_tenant(tenant_id: &str) -> Value {
+        let mut event = json!(Event::new("test"));
+        event.add_to_metadata(TENANT_ID_METADATA.to_owned(), json!(tenant_id)).unwrap();
+        event
+    }
+
+    #[test]
+    fn should_reject_only_the_events_of_the_tenant_exceeding_its_rate() {
+        // Arrange
+        let mut events_per_sec_by_tenant = HashMap::new();
+        events_per_sec_by_tenant.insert("tenant_a".to_owned(), 5.0);
+        events_per_sec_by_tenant.insert("tenant_b".to_owned(), 1000.0);
+        let rate_limiter = TenantRateLimiter::new(
+            TenantRateLimitsConfig {
+                events_per_sec_by_tenant,
+                default_events_per_sec: None,
+                events_per_sec_without_tenant: None,
+            },
+            Arc::new(TornadoMeter::default()),
+        );
+
+        // Act
+        let mut accepted_a = 0;
+        let mut accepted_b = 0;
+        for _ in 0..20 {
+            if rate_limiter.accept(&event_of_tenant("tenant_a")) {
+                accepted_a += 1;
+            }
+            if rate_limiter.accept(&event_of_tenant("tenant_b")) {
+                accepted_b += 1;
+            }
+        }
+
+        // Assert
+        assert!(accepted_a >= 5);
+        assert!(accepted_a < 20);
+        assert_eq!(20, accepted_b);
+    }
+
+    #[test]
+    fn should_apply_the_default_rate_to_the_tenants_not_configured() {
+        // Arrange
+        let rate_limiter = TenantRateLimiter::new(
+            TenantRateLimitsConfig {
+                events_per_sec_by_tenant: HashMap::new(),
+                default_events_per_sec: Some(1.0),
+                events_per_sec_without_tenant: None,
+            },
+            Arc::new(TornadoMeter::default()),
+        );
+
+        // Act & Assert
+        assert!(rate_limiter.accept(&event_of_tenant("tenant_a")));
+        assert!(!rate_limiter.accept(&event_of_tenant("tenant_a")));
+        assert!(rate_limiter.accept(&event_of_tenant("tenant_b")));
+    }
+
+    #[test]
+    fn should_never_reject_the_events_without_a_limited_tenant() {
+        // Arrange
+        let rate_limiter = TenantRateLimiter::new(
+            TenantRateLimitsConfig {
+                events_per_sec_by_tenant: HashMap::new(),
+                default_events_per_sec: None,
+                events_per_sec_without_tenant: None,
+            },
+            Arc::new(TornadoMeter::default()),
+        );
+
+        // Act & Assert
+        for _ in 0..100 {
+            assert!(rate_limiter.accept(&json!(Event::new("test"))));
+            assert!(rate_limiter.accept(&event_of_tenant("tenant_a")));
+        }
+    }
+
+    #[test]
+    fn should_limit_the_events_without_a_tenant_with_a_shared_budget() {
+        // Arrange
+        let rate_limiter = TenantRateLimiter::new(
+            TenantRateLimitsConfig {
+                events_per_sec_by_tenant: HashMap::new(),
+                default_events_per_sec: None,
+                events_per_sec_without_tenant: Some(1.0),
+            },
+            Arc::new(TornadoMeter::default()),
+        );
+
+        // Act & Assert
+        assert!(rate_limiter.accept(&json!(Event::new("test"))));
+        assert!(!rate_limiter.accept(&json!(Event::new("test"))));
+        assert!(rate_limiter.accept(&event_of_tenant("tenant_a")));
+    }
+
+    #[test]
+    fn should_reject_the_new_tenants_when_the_max_tenants_are_tracked() {
+        // Arrange
+        let mut events_per_sec_by_tenant = HashMap::new();
+        events_per_sec_by_tenant.insert("tenant_a".to_owned(), 0.001);
+        let mut rate_limiter = TenantRateLimiter::new(
+            TenantRateLimitsConfig {
+                events_per_sec_by_tenant,
+                default_events_per_sec: Some(0.001),
+                events_per_sec_without_tenant: None,
+            },
+            Arc::new(TornadoMeter::default()),
+        );
+        rate_limiter.max_tracked_tenants = 3;
+
+        // Act
+        let accepted = (0..10)
+            .map(|index| rate_limiter.accept(&event_of_tenant(&format!("tenant_{}", index))))
+            .collect::<Vec<_>>();
+
+        // Assert
+        assert_eq!(
+            vec![true, true, true, false, false, false, false, false, false, false],
+            accepted
+        );
+        assert!(!rate_limiter.accept(&event_of_tenant("tenant_0")));
+        assert!(rate_limiter.accept(&event_of_tenant("tenant_a")));
+        let buckets = rate_limiter.other_tenants_buckets.lock().unwrap();
+        assert_eq!(3, buckets.len());
+        assert!(!buckets.contains_key("tenant_9"));
+    }
+
+    #[test]
+    fn should_replace_the_full_budgets_when_the_max_tenants_are_tracked() {
+        // Arrange
+        let mut rate_limiter = TenantRateLimiter::new(
+            TenantRateLimitsConfig {
+                events_per_sec_by_tenant: HashMap::new(),
+                default_events_per_sec: Some(1000.0),
+                events_per_sec_without_tenant: None,
+            },
+            Arc::new(TornadoMeter::default()),
+        );
+        rate_limiter.max_tracked_tenants = 3;
+        for index in 0..3 {
+            assert!(rate_limiter.accept(&event_of_tenant(&format!("tenant_{}", index))));
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+
+        // Act
+        let accepted = rate_limiter.accept(&event_of_tenant("tenant_3"));
+
+        // Assert
+        assert!(accepted);
+        let buckets = rate_limiter.other_tenants_buckets.lock().unwrap();
+        assert_eq!(1, buckets.len());
+        assert!(buckets.contains_key("tenant_3"));
+    }
+}
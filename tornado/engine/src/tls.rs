@@ -0,0 +1,139 @@
+use rustls::{Certificate, PrivateKey, ServerConfig};
+use rustls_pemfile::Item;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use tornado_common::TornadoError;
+
+/// The certificate and the private key used by the Web Server to accept HTTPS connections.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct WebServerTlsConfig {
+    /// The path to the certificate chain, in PEM format
+    pub cert_path: String,
+    /// The path to the private key of the certificate, in PEM format
+    pub key_path: String,
+}
+
+impl WebServerTlsConfig {
+    /// Builds the rustls configuration of the Web Server from the certificate and key files.
+    pub fn build_server_config(&self) -> Result<ServerConfig, TornadoError> {
+        let certs = rustls_pemfile::certs(&mut BufReader::new(open(&self.cert_path)?))
+            .map_err(|err| TornadoError::ConfigurationError {
+                message: format!(
+                    "Cannot read the certificate [{}]. Err: {:?}",
+                    self.cert_path, err
+                ),
+            })?
+            .into_iter()
+            .map(Certificate)
+            .collect::<Vec<_>>();
+        if certs.is_empty() {
+            return Err(TornadoError::ConfigurationError {
+                message: format!("No certificate found in [{}]", self.cert_path),
+            });
+        }
+
+        let key = rustls_pemfile::read_all(&mut BufReader::new(open(&self.key_path)?))
+            .map_err(|err| TornadoError::ConfigurationError {
+                message: format!("Cannot read the private key [{}]. Err: {:?}", self.key_path, err),
+            })?
+            .into_iter()
+            .find_map(|item| match item {
+                Item::RSAKey(key) | Item::PKCS8Key(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+                _ => None,
+            })
+            .ok_or_else(|| TornadoError::ConfigurationError {
+                message: format!("No private key found in [{}]", self.key_path),
+            })?;
+
+        ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|err| TornadoError::ConfigurationError {
+                message: format!("Invalid Web Server TLS configuration. Err: {:?}", err),
+            })
+    }
+}
+
+fn open(path: &str) -> Result<File, TornadoError> {
+    File::open(path).map_err(|err| TornadoError::ConfigurationError {
+        message: format!("Cannot open the file [{}]. Err: {:?}", path, err),
+    })
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+
+    fn test_tls_config() -> WebServerTlsConfig {
+        WebServerTlsConfig {
+            cert_path: "./test_resources/tls/web-server.crt.pem".to_owned(),
+            key_path: "./test_resources/tls/web-server.key.pem".to_owned(),
+        }
+    }
+
+    #[test]
+    fn should_fail_if_the_certificate_does_not_exist() {
+        // Arrange
+        let tls_config = WebServerTlsConfig {
+            cert_path: "./test_resources/tls/not_existing.crt.pem".to_owned(),
+            ..test_tls_config()
+        };
+
+        // Act
+        let result = tls_config.build_server_config();
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn should_fail_if_the_key_file_contains_no_key() {
+        // Arrange
+        let tls_config = WebServerTlsConfig {
+            key_path: "./test_resources/tls/web-server.crt.pem".to_owned(),
+            ..test_tls_config()
+        };
+
+        // Act
+        let result = tls_config.build_server_config();
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn should_accept_only_https_requests_if_tls_is_configured() {
+        // Arrange
+        let server_config = test_tls_config().build_server_config().unwrap();
+        let server = HttpServer::new(|| {
+            App::new().service(
+                web::resource("/v1/config/current")
+                    .route(web::get().to(|| async { HttpResponse::Ok().finish() })),
+            )
+        })
+        .bind_rustls_021("127.0.0.1:0", server_config)
+        .unwrap();
+        let port = server.addrs()[0].port();
+        let server = server.run();
+        let server_handle = server.handle();
+        actix_rt::spawn(server);
+
+        let client = reqwest::Client::builder().danger_accept_invalid_certs(true).build().unwrap();
+
+        // Act
+        let https_response =
+            client.get(format!("https://127.0.0.1:{}/v1/config/current", port)).send().await;
+        let http_response =
+            client.get(format!("http://127.0.0.1:{}/v1/config/current", port)).send().await;
+
+        // Assert
+        assert_eq!(200, https_response.unwrap().status().as_u16());
+        assert!(http_response.is_err());
+
+        server_handle.stop(true).await;
+    }
+}
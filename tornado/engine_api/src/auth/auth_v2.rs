@@ -12,11 +12,14 @@ use tornado_engine_api_dto::auth_v2::{AuthHeaderV2, AuthV2};
 
 pub const FORBIDDEN_NOT_OWNER: &str = "NOT_OWNER";
 
+static NO_ROLE_PATHS: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct AuthContextV2<'a> {
     pub auth: AuthV2,
     pub valid: bool,
     permission_roles_map: &'a BTreeMap<Permission, Vec<String>>,
+    role_paths: &'a BTreeMap<String, Vec<String>>,
 }
 
 impl AuthContextTrait for AuthContextV2<'_> {
@@ -44,7 +47,20 @@ impl AuthContextTrait for AuthContextV2<'_> {
 
 impl<'a> AuthContextV2<'a> {
     pub fn new(auth: AuthV2, permission_roles_map: &'a BTreeMap<Permission, Vec<String>>) -> Self {
-        AuthContextV2 { valid: !auth.user.is_empty(), auth, permission_roles_map }
+        AuthContextV2 {
+            valid: !auth.user.is_empty(),
+            auth,
+            permission_roles_map,
+            role_paths: &NO_ROLE_PATHS,
+        }
+    }
+
+    /// Sets the default node paths associated with the roles.
+    /// The user is authorized to access the node paths of all its roles,
+    /// in addition to the path of its authorization.
+    pub fn with_role_paths(mut self, role_paths: &'a BTreeMap<String, Vec<String>>) -> Self {
+        self.role_paths = role_paths;
+        self
     }
 
     pub fn from_header(
@@ -58,7 +74,36 @@ impl<'a> AuthContextV2<'a> {
             })?;
         let auth =
             AuthV2 { user: auth_header.user, authorization, preferences: auth_header.preferences };
-        Ok(AuthContextV2 { valid: !auth.user.is_empty(), auth, permission_roles_map })
+        Ok(AuthContextV2::new(auth, permission_roles_map))
+    }
+
+    /// Returns the node paths the user is authorized to access: the path of its authorization
+    /// followed by the default paths associated with its roles, without duplicates.
+    pub fn authorized_paths(&self) -> Vec<Vec<String>> {
+        self.authorized_path_refs().into_iter().map(|path| path.to_vec()).collect()
+    }
+
+    /// Returns the same node paths of `authorized_paths` without copying them.
+    pub fn authorized_path_refs(&self) -> Vec<&[String]> {
+        let mut paths: Vec<&[String]> = vec![];
+        let role_paths =
+            self.auth.authorization.roles.iter().filter_map(|role| self.role_paths.get(role));
+        for path in std::iter::once(&self.auth.authorization.path).chain(role_paths) {
+            if !path.is_empty() && !paths.contains(&path.as_slice()) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// Returns whether the node at the given absolute path is inside
+    /// one of the authorized node paths of the user.
+    pub fn can_access_node(&self, node_path: &[&str]) -> bool {
+        self.valid
+            && self.authorized_path_refs().iter().any(|authorized_path| {
+                authorized_path.len() <= node_path.len()
+                    && authorized_path.iter().zip(node_path).all(|(left, right)| left == *right)
+            })
     }
 
     // Returns an error if user is not authenticated
@@ -97,14 +142,86 @@ impl<'a> AuthContextV2<'a> {
     }
 }
 
+/// The auth contexts of the tenants of a single request, keyed by auth key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthContextsV2<'a> {
+    pub contexts: BTreeMap<String, AuthContextV2<'a>>,
+    /// The requested auth keys not present in the authentication header
+    pub skipped_auth_keys: Vec<String>,
+}
+
+impl<'a> AuthContextsV2<'a> {
+    /// Builds an AuthContextV2 for each one of the auth keys found in the header.
+    /// The auth keys not present in the header are skipped, logged and recorded
+    /// in `skipped_auth_keys`, instead of failing the whole request.
+    pub fn from_header(
+        mut auth_header: AuthHeaderV2,
+        auth_keys: &[&str],
+        permission_roles_map: &'a BTreeMap<Permission, Vec<String>>,
+    ) -> Self {
+        let mut contexts = BTreeMap::new();
+        let mut skipped_auth_keys = vec![];
+
+        for auth_key in auth_keys {
+            match auth_header.auths.remove(*auth_key) {
+                Some(authorization) => {
+                    let auth = AuthV2 {
+                        user: auth_header.user.clone(),
+                        authorization,
+                        preferences: auth_header.preferences.clone(),
+                    };
+                    contexts.insert(
+                        auth_key.to_string(),
+                        AuthContextV2::new(auth, permission_roles_map),
+                    );
+                }
+                None if contexts.contains_key(*auth_key) => {}
+                None => {
+                    warn!(
+                        "Authentication header of user [{}] does not contain auth key: {}. The key is skipped.",
+                        auth_header.user, auth_key
+                    );
+                    skipped_auth_keys.push(auth_key.to_string());
+                }
+            }
+        }
+
+        AuthContextsV2 { contexts, skipped_auth_keys }
+    }
+
+    /// Sets the default node paths associated with the roles in all the contexts
+    pub fn with_role_paths(mut self, role_paths: &'a BTreeMap<String, Vec<String>>) -> Self {
+        self.contexts = self
+            .contexts
+            .into_iter()
+            .map(|(auth_key, context)| (auth_key, context.with_role_paths(role_paths)))
+            .collect();
+        self
+    }
+
+    /// Returns the context of the auth key or an error if the key was not found in the header
+    pub fn get(&self, auth_key: &str) -> Result<&AuthContextV2<'a>, ApiError> {
+        self.contexts.get(auth_key).ok_or(ApiError::InvalidAuthKeyError {
+            message: format!("Authentication header does not contain auth key: {}", auth_key),
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct AuthServiceV2 {
     pub permission_roles_map: Arc<BTreeMap<Permission, Vec<String>>>,
+    pub role_paths: Arc<BTreeMap<String, Vec<String>>>,
 }
 
 impl AuthServiceV2 {
     pub fn new(permission_roles_map: Arc<BTreeMap<Permission, Vec<String>>>) -> Self {
-        Self { permission_roles_map }
+        Self { permission_roles_map, role_paths: Default::default() }
+    }
+
+    /// Sets the default node paths associated with the roles
+    pub fn with_role_paths(mut self, role_paths: Arc<BTreeMap<String, Vec<String>>>) -> Self {
+        self.role_paths = role_paths;
+        self
     }
 
     pub fn auth_from_request(
@@ -115,10 +232,22 @@ impl AuthServiceV2 {
         let auth_header = AuthService::token_string_from_request(req)
             .and_then(Self::auth_header_from_token_string)?;
         let auth_ctx =
-            AuthContextV2::from_header(auth_header, auth_key, &self.permission_roles_map)?;
+            AuthContextV2::from_header(auth_header, auth_key, &self.permission_roles_map)?
+                .with_role_paths(&self.role_paths);
         Ok(auth_ctx)
     }
 
+    pub fn auths_from_request(
+        &self,
+        req: &HttpRequest,
+        auth_keys: &[&str],
+    ) -> Result<AuthContextsV2, ApiError> {
+        let auth_header = AuthService::token_string_from_request(req)
+            .and_then(Self::auth_header_from_token_string)?;
+        Ok(AuthContextsV2::from_header(auth_header, auth_keys, &self.permission_roles_map)
+            .with_role_paths(&self.role_paths))
+    }
+
     pub fn auth_header_from_token_string(token: &str) -> Result<AuthHeaderV2, ApiError> {
         let auth_str = AuthService::decode_token_from_base64(token)?;
         let auth_header =
@@ -201,6 +330,7 @@ pub mod test {
             },
             valid: true,
             permission_roles_map: &permission_roles_map,
+            role_paths: &Default::default(),
         };
         assert_eq!(result, expected);
     }
@@ -237,6 +367,7 @@ pub mod test {
             },
             valid: false,
             permission_roles_map: &permission_roles_map,
+            role_paths: &Default::default(),
         };
         assert_eq!(result, expected);
     }
@@ -252,6 +383,7 @@ pub mod test {
             },
             valid: false,
             permission_roles_map: &Default::default(),
+            role_paths: &Default::default(),
         };
 
         // Act
@@ -272,6 +404,7 @@ pub mod test {
             },
             valid: true,
             permission_roles_map: &Default::default(),
+            role_paths: &Default::default(),
         };
 
         // Act
@@ -292,6 +425,7 @@ pub mod test {
             },
             valid: true,
             permission_roles_map: &permission_map(),
+            role_paths: &Default::default(),
         };
 
         // Act & Assert
@@ -310,6 +444,7 @@ pub mod test {
             },
             valid: false,
             permission_roles_map: &permission_map(),
+            role_paths: &Default::default(),
         };
 
         // Act & Assert
@@ -328,6 +463,7 @@ pub mod test {
             },
             valid: true,
             permission_roles_map: &permission_map(),
+            role_paths: &Default::default(),
         };
 
         // Act & Assert
@@ -340,6 +476,219 @@ pub mod test {
             .is_err());
     }
 
+    fn role_paths() -> BTreeMap<String, Vec<String>> {
+        let mut role_paths = BTreeMap::new();
+        role_paths.insert("network".to_owned(), vec!["root".to_owned(), "network".to_owned()]);
+        role_paths.insert("tenant_a".to_owned(), vec!["root".to_owned(), "tenant_a".to_owned()]);
+        role_paths
+    }
+
+    #[test]
+    fn can_access_node_should_honor_the_explicit_and_the_role_derived_paths() {
+        // Arrange
+        let permission_roles_map = permission_map();
+        let role_paths = role_paths();
+        let auth_context = AuthContextV2::new(
+            AuthV2 {
+                user: "my_user".to_string(),
+                authorization: Authorization {
+                    path: vec!["root".to_owned(), "tenant_a".to_owned()],
+                    roles: vec!["view".to_owned(), "network".to_owned()],
+                },
+                preferences: None,
+            },
+            &permission_roles_map,
+        )
+        .with_role_paths(&role_paths);
+
+        // Act & Assert
+        assert!(auth_context.can_access_node(&["root", "tenant_a"]));
+        assert!(auth_context.can_access_node(&["root", "tenant_a", "rule_1"]));
+        assert!(auth_context.can_access_node(&["root", "network", "filter", "rule_2"]));
+        assert!(!auth_context.can_access_node(&["root"]));
+        assert!(!auth_context.can_access_node(&["root", "tenant_b"]));
+    }
+
+    #[test]
+    fn can_access_node_should_ignore_the_paths_of_the_roles_not_assigned_to_the_user() {
+        // Arrange
+        let permission_roles_map = permission_map();
+        let role_paths = role_paths();
+        let auth_context = AuthContextV2::new(
+            AuthV2 {
+                user: "my_user".to_string(),
+                authorization: Authorization {
+                    path: vec!["root".to_owned(), "tenant_b".to_owned()],
+                    roles: vec!["view".to_owned()],
+                },
+                preferences: None,
+            },
+            &permission_roles_map,
+        )
+        .with_role_paths(&role_paths);
+
+        // Act & Assert
+        assert!(auth_context.can_access_node(&["root", "tenant_b", "rule_1"]));
+        assert!(!auth_context.can_access_node(&["root", "network"]));
+        assert!(!auth_context.can_access_node(&["root", "tenant_a"]));
+    }
+
+    #[test]
+    fn authorized_paths_should_return_the_union_of_the_explicit_and_the_role_derived_paths() {
+        // Arrange
+        let permission_roles_map = permission_map();
+        let role_paths = role_paths();
+        let auth_context = AuthContextV2::new(
+            AuthV2 {
+                user: "my_user".to_string(),
+                authorization: Authorization {
+                    path: vec!["root".to_owned(), "tenant_a".to_owned()],
+                    roles: vec!["tenant_a".to_owned(), "network".to_owned(), "view".to_owned()],
+                },
+                preferences: None,
+            },
+            &permission_roles_map,
+        )
+        .with_role_paths(&role_paths);
+
+        // Act
+        let result = auth_context.authorized_paths();
+
+        // Assert
+        assert_eq!(
+            vec![
+                vec!["root".to_owned(), "tenant_a".to_owned()],
+                vec!["root".to_owned(), "network".to_owned()],
+            ],
+            result
+        );
+    }
+
+    #[test]
+    fn auth_from_request_should_apply_the_role_paths() {
+        // Arrange
+        let mut auths = HashMap::new();
+        auths.insert(
+            "auth_key".to_owned(),
+            Authorization {
+                path: vec!["root".to_owned(), "tenant_b".to_owned()],
+                roles: vec!["network".to_owned()],
+            },
+        );
+        let auth_header = AuthHeaderV2 { user: "user".to_string(), auths, preferences: None };
+        let request = TestRequest::get()
+            .insert_header((
+                header::AUTHORIZATION,
+                AuthServiceV2::auth_to_token_header(&auth_header).unwrap(),
+            ))
+            .to_http_request();
+        let auth_service = test_auth_service_v2().with_role_paths(Arc::new(role_paths()));
+
+        // Act
+        let auth_context = auth_service.auth_from_request(&request, "auth_key").unwrap();
+
+        // Assert
+        assert!(auth_context.can_access_node(&["root", "network", "rule_1"]));
+    }
+
+    #[test]
+    fn auth_contexts_from_header_should_build_a_context_for_each_auth_key() {
+        // Arrange
+        let mut auths = HashMap::new();
+        auths.insert(
+            "tenant_a".to_owned(),
+            Authorization { path: vec!["root".to_owned()], roles: vec!["edit".to_owned()] },
+        );
+        auths.insert(
+            "tenant_b".to_owned(),
+            Authorization {
+                path: vec!["root".to_owned(), "tenant_b".to_owned()],
+                roles: vec!["view".to_owned()],
+            },
+        );
+        let auth_header = AuthHeaderV2 { user: "user".to_string(), auths, preferences: None };
+        let permission_roles_map = permission_map();
+
+        // Act
+        let result = AuthContextsV2::from_header(
+            auth_header,
+            &["tenant_a", "tenant_b"],
+            &permission_roles_map,
+        );
+
+        // Assert
+        assert_eq!(2, result.contexts.len());
+        assert!(result.skipped_auth_keys.is_empty());
+
+        let tenant_a = result.get("tenant_a").unwrap();
+        assert_eq!("user", tenant_a.auth.user);
+        assert!(tenant_a.has_permission(&Permission::ConfigEdit).is_ok());
+        assert!(tenant_a.has_permission(&Permission::ConfigView).is_ok());
+
+        let tenant_b = result.get("tenant_b").unwrap();
+        assert_eq!("user", tenant_b.auth.user);
+        assert!(tenant_b.has_permission(&Permission::ConfigEdit).is_err());
+        assert!(tenant_b.has_permission(&Permission::ConfigView).is_ok());
+        assert_eq!(
+            vec!["root".to_owned(), "tenant_b".to_owned()],
+            tenant_b.auth.authorization.path
+        );
+    }
+
+    #[test]
+    fn auth_contexts_from_header_should_skip_the_missing_auth_keys() {
+        // Arrange
+        let mut auths = HashMap::new();
+        auths.insert(
+            "tenant_a".to_owned(),
+            Authorization { path: vec!["root".to_owned()], roles: vec!["view".to_owned()] },
+        );
+        let auth_header = AuthHeaderV2 { user: "user".to_string(), auths, preferences: None };
+        let permission_roles_map = permission_map();
+
+        // Act
+        let result = AuthContextsV2::from_header(
+            auth_header,
+            &["tenant_a", "tenant_c"],
+            &permission_roles_map,
+        );
+
+        // Assert
+        assert_eq!(1, result.contexts.len());
+        assert!(result.get("tenant_a").unwrap().has_permission(&Permission::ConfigView).is_ok());
+        assert_eq!(vec!["tenant_c".to_owned()], result.skipped_auth_keys);
+        assert!(matches!(result.get("tenant_c"), Err(ApiError::InvalidAuthKeyError { .. })));
+    }
+
+    #[test]
+    fn auths_from_request_should_build_the_contexts_from_http_request() {
+        // Arrange
+        let mut auths = HashMap::new();
+        auths.insert(
+            "tenant_a".to_owned(),
+            Authorization { path: vec!["root".to_owned()], roles: vec!["edit".to_owned()] },
+        );
+        auths.insert(
+            "tenant_b".to_owned(),
+            Authorization { path: vec!["root".to_owned()], roles: vec!["view".to_owned()] },
+        );
+        let auth_header = AuthHeaderV2 { user: "admin".to_string(), auths, preferences: None };
+        let request = TestRequest::get()
+            .insert_header((
+                header::AUTHORIZATION,
+                AuthServiceV2::auth_to_token_header(&auth_header).unwrap(),
+            ))
+            .to_http_request();
+        let auth_service = test_auth_service_v2();
+
+        // Act
+        let result = auth_service.auths_from_request(&request, &["tenant_a", "tenant_b"]).unwrap();
+
+        // Assert
+        assert!(result.get("tenant_a").unwrap().has_permission(&Permission::ConfigEdit).is_ok());
+        assert!(result.get("tenant_b").unwrap().has_permission(&Permission::ConfigEdit).is_err());
+    }
+
     #[test]
     fn auth_header_from_token_string_should_return_parse_token() {
         // Arrange
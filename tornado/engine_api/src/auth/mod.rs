@@ -30,6 +30,7 @@ pub enum Permission {
     RuntimeConfigEdit,
     RuntimeConfigView,
     TestEventExecuteActions,
+    ActionsReplay,
 }
 
 /// A MatcherConfigEditor permits to edit Tornado Configuration drafts
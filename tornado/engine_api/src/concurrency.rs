@@ -0,0 +1,165 @@
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header;
+use actix_web::{Error, HttpResponse};
+use futures_util::future::{ready, LocalBoxFuture, Ready};
+use log::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// The seconds that the clients are asked to wait before retrying a rejected request
+pub const RETRY_AFTER_SECS: u64 = 1;
+
+/// An Actix middleware that limits the number of requests processed concurrently.
+/// When the limit is reached, the surplus requests are rejected with a
+/// `503 Service Unavailable` response with a `Retry-After` header.
+///
+/// The same instance should be shared by all the Actix workers, so that the limit
+/// applies to the whole server and not to each worker.
+#[derive(Clone)]
+pub struct ConcurrencyLimit {
+    max_concurrent_requests: usize,
+    in_flight_requests: Arc<AtomicUsize>,
+}
+
+impl ConcurrencyLimit {
+    pub fn new(max_concurrent_requests: usize) -> Self {
+        Self { max_concurrent_requests, in_flight_requests: Arc::new(AtomicUsize::new(0)) }
+    }
+
+    fn try_acquire(&self) -> Option<ConcurrencyPermit> {
+        self.in_flight_requests
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |in_flight| {
+                (in_flight < self.max_concurrent_requests).then_some(in_flight + 1)
+            })
+            .ok()
+            .map(|_| ConcurrencyPermit { in_flight_requests: self.in_flight_requests.clone() })
+    }
+}
+
+/// Releases the acquired slot when dropped
+struct ConcurrencyPermit {
+    in_flight_requests: Arc<AtomicUsize>,
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        self.in_flight_requests.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ConcurrencyLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = ConcurrencyLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ConcurrencyLimitMiddleware { service, limit: self.clone() }))
+    }
+}
+
+pub struct ConcurrencyLimitMiddleware<S> {
+    service: S,
+    limit: ConcurrencyLimit,
+}
+
+impl<S, B> Service<ServiceRequest> for ConcurrencyLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        match self.limit.try_acquire() {
+            Some(permit) => {
+                let response = self.service.call(req);
+                Box::pin(async move {
+                    let response = response.await;
+                    drop(permit);
+                    response.map(ServiceResponse::map_into_left_body)
+                })
+            }
+            None => {
+                warn!(
+                    "ConcurrencyLimit - Max number of concurrent requests [{}] reached. Rejecting request method [{}] path [{}]",
+                    self.limit.max_concurrent_requests,
+                    req.method(),
+                    req.path()
+                );
+                let response = HttpResponse::ServiceUnavailable()
+                    .insert_header((header::RETRY_AFTER, RETRY_AFTER_SECS.to_string()))
+                    .finish();
+                Box::pin(ready(Ok(req.into_response(response).map_into_right_body())))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, web, App};
+    use futures_util::future::join_all;
+    use std::time::Duration;
+
+    async fn slow_handler() -> HttpResponse {
+        actix_rt::time::sleep(Duration::from_millis(200)).await;
+        HttpResponse::Ok().finish()
+    }
+
+    #[actix_rt::test]
+    async fn should_reject_the_requests_exceeding_the_max_concurrency() {
+        // Arrange
+        let srv = test::init_service(
+            App::new().wrap(ConcurrencyLimit::new(2)).route("/", web::get().to(slow_handler)),
+        )
+        .await;
+
+        // Act
+        let responses = join_all(
+            (0..3)
+                .map(|_| test::call_service(&srv, test::TestRequest::get().uri("/").to_request())),
+        )
+        .await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, responses[0].status());
+        assert_eq!(StatusCode::OK, responses[1].status());
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, responses[2].status());
+        assert_eq!(
+            RETRY_AFTER_SECS.to_string(),
+            responses[2].headers().get(header::RETRY_AFTER).unwrap().to_str().unwrap()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn should_accept_new_requests_when_the_in_flight_ones_are_completed() {
+        // Arrange
+        let srv = test::init_service(
+            App::new().wrap(ConcurrencyLimit::new(1)).route("/", web::get().to(slow_handler)),
+        )
+        .await;
+
+        // Act
+        let first = test::call_service(&srv, test::TestRequest::get().uri("/").to_request()).await;
+        let second = test::call_service(&srv, test::TestRequest::get().uri("/").to_request()).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, first.status());
+        assert_eq!(StatusCode::OK, second.status());
+    }
+}
@@ -1,19 +1,40 @@
 use crate::auth::auth_v2::AuthContextV2;
 use crate::auth::{AuthContext, AuthContextTrait, Permission};
-use crate::config::convert::{dto_into_rule, rule_into_dto};
+use crate::config::convert::{
+    deploy_record_into_dto, dto_into_rule,
+    processing_tree_node_details_dto_into_matcher_config_with_current, rule_into_dto,
+};
 use crate::error::ApiError;
+use json_patch::Patch;
+use lazy_static::lazy_static;
 use log::*;
+use schemars::schema_for;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use tornado_engine_api_dto::common::Id;
+use tornado_engine_api_dto::common::{Id, Page};
 use tornado_engine_api_dto::config::{
-    ProcessingTreeNodeConfigDto, ProcessingTreeNodeDetailsDto, RuleDto, TreeInfoDto,
+    FilterPreviewEventDto, FilterPreviewRequestDto, FilterPreviewResultDto,
+    MatcherConfigDeployRecordDto, ProcessingTreeNodeConfigDto, ProcessingTreeNodeDetailsDto,
+    ProcessingTreeNodeEditDto, RuleDto, TestRuleRequestDto, TestRuleResultDto, TreeInfoDto,
 };
 use tornado_engine_matcher::config::operation::{matcher_config_filter, NodeFilter};
 use tornado_engine_matcher::config::{
-    MatcherConfig, MatcherConfigDraft, MatcherConfigEditor, MatcherConfigReader,
+    MatcherConfig, MatcherConfigDeployRecord, MatcherConfigDraft, MatcherConfigEditor,
+    MatcherConfigReader,
 };
+use tornado_engine_matcher::matcher::{Matcher, MatcherSettings};
+use tornado_engine_matcher::model::{ProcessedFilterStatus, ProcessedNode, ProcessedRuleStatus};
 
 const NODE_PATH_SEPARATOR: &str = ",";
+const TEST_RULE_RULESET_NAME: &str = "test_rule";
+
+lazy_static! {
+    /// The JSON Schema of the processing tree configuration.
+    /// It depends only on the types, so it is generated once.
+    static ref MATCHER_CONFIG_SCHEMA: String = serde_json::to_string(&schema_for!(MatcherConfig))
+        .expect("The JSON Schema of the MatcherConfig should be serializable");
+}
 
 /// The ApiHandler trait defines the contract that a struct has to respect to
 /// be used by the backend.
@@ -26,11 +47,19 @@ pub trait ConfigApiHandler: Send + Sync {
 pub struct ConfigApi<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized> {
     handler: A,
     config_manager: Arc<CM>,
+    matcher_settings: MatcherSettings,
 }
 
 impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized> ConfigApi<A, CM> {
     pub fn new(handler: A, config_manager: Arc<CM>) -> Self {
-        Self { handler, config_manager }
+        Self { handler, config_manager, matcher_settings: MatcherSettings::default() }
+    }
+
+    /// Sets the settings used to build the Matchers that validate and test the configurations.
+    /// They should be the same used by the matcher of the engine.
+    pub fn with_matcher_settings(mut self, matcher_settings: MatcherSettings) -> Self {
+        self.matcher_settings = matcher_settings;
+        self
     }
 
     /// Returns the current configuration of tornado
@@ -65,16 +94,8 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         relative_node_path: Vec<&str>,
         filtered_matcher: MatcherConfig,
     ) -> Result<Vec<ProcessingTreeNodeConfigDto>, ApiError> {
-        let authorized_path =
-            auth.auth.authorization.path.iter().map(|s| s as &str).collect::<Vec<_>>();
-
-        // We must remove the last element of the authorized path because node_path starts from
-        // the entry point (included) of the authorized tree.
-        // It is safe to pop from the authorized path because the MatcherConfig is already filtered.
-        let absolute_node_path = pop_authorized_path_and_append_relative_path(
-            authorized_path,
-            relative_node_path.clone(),
-        )?;
+        let absolute_node_path =
+            self.resolve_relative_node_path(auth, relative_node_path.clone())?;
 
         let child_nodes = filtered_matcher
             .get_child_nodes_by_path(absolute_node_path.as_slice())
@@ -89,6 +110,12 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
             .collect())
     }
 
+    /// Returns the JSON Schema of the processing tree configuration
+    pub fn get_config_schema(&self, auth: &AuthContextV2<'_>) -> Result<&'static str, ApiError> {
+        auth.has_any_permission(&[&Permission::ConfigView, &Permission::ConfigEdit])?;
+        Ok(MATCHER_CONFIG_SCHEMA.as_str())
+    }
+
     pub async fn get_authorized_tree_info(
         &self,
         auth: &AuthContextV2<'_>,
@@ -98,25 +125,32 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         let filtered_matcher =
             get_filtered_matcher(&self.config_manager.get_config().await?, auth).await?;
 
-        let mut absolute_path: Vec<_> =
-            auth.auth.authorization.path.iter().map(|s| s as &str).collect();
-
-        // We must remove the last element of the authorized path because the endpoint is expected
-        // to return the entry point of the authorized tree, when node_path is empty.
-        // It is safe to pop from the authorized path because the MatcherConfig is already filtered.
-        if absolute_path.pop().is_none() {
+        let authorized_paths = auth.authorized_path_refs();
+        if authorized_paths.is_empty() {
             let message = "The authorized node path cannot be empty.";
             warn!("{}", message);
             return Err(ApiError::InvalidAuthorizedPath { message: message.to_owned() });
         }
 
-        let child_nodes = filtered_matcher
-            .get_child_nodes_by_path(absolute_path.as_slice())
-            .ok_or(ApiError::NodeNotFoundError {
-                message: format!("Node for path {:?} not found", absolute_path),
-            })?;
-
-        Ok(Self::fetch_tree_info(child_nodes.as_slice()))
+        // The entry points of the authorized trees are counted, but the nodes inside another
+        // authorized tree are not counted twice
+        let mut tree_info = TreeInfoDto::default();
+        for authorized_path in &authorized_paths {
+            let nested = authorized_paths.iter().any(|other_path| {
+                other_path.len() < authorized_path.len() && authorized_path.starts_with(other_path)
+            });
+            if nested {
+                continue;
+            }
+            let absolute_path: Vec<&str> = authorized_path.iter().map(|s| s as &str).collect();
+            let node = filtered_matcher.get_node_by_path(absolute_path.as_slice()).ok_or(
+                ApiError::NodeNotFoundError {
+                    message: format!("Node for path {:?} not found", absolute_path),
+                },
+            )?;
+            tree_info = tree_info + Self::fetch_tree_info(std::slice::from_ref(node));
+        }
+        Ok(tree_info)
     }
 
     fn fetch_tree_info(children: &[MatcherConfig]) -> TreeInfoDto {
@@ -202,19 +236,37 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         auth: &'a AuthContextV2,
         relative_node_path: &'a str,
     ) -> Result<Vec<&'a str>, ApiError> {
-        let authorized_path =
-            auth.auth.authorization.path.iter().map(|s| s as &str).collect::<Vec<_>>();
-
         let relative_node_path = relative_node_path.split(NODE_PATH_SEPARATOR).collect::<Vec<_>>();
+        self.resolve_relative_node_path(auth, relative_node_path)
+    }
+
+    /// Returns the absolute path of a node from its path relative to the authorized trees.
+    /// The relative path starts from the entry point (included) of one of the authorized trees;
+    ///   an empty relative path refers to the parent of the entry point of the main one.
+    /// Returns an error if the user cannot access the node.
+    fn resolve_relative_node_path<'a>(
+        &self,
+        auth: &'a AuthContextV2,
+        relative_node_path: Vec<&'a str>,
+    ) -> Result<Vec<&'a str>, ApiError> {
+        let authorized_paths = auth.authorized_path_refs();
+        let authorized_path = match relative_node_path.first() {
+            Some(entry_point) => authorized_paths
+                .into_iter()
+                .find(|path| path.last().map(|name| name.as_str()) == Some(*entry_point))
+                .ok_or_else(|| self.get_unauthorized_path_error())?,
+            None => authorized_paths.into_iter().next().unwrap_or_default(),
+        };
+        let authorized_path = authorized_path.iter().map(|s| s as &str).collect::<Vec<_>>();
 
-        if authorized_path.last() != relative_node_path.first() {
-            return Err(self.get_unauthorized_path_error());
-        }
         // We must remove the last element of the authorized path because node_path starts from
         // the entry point (included) of the authorized tree.
-        // It is safe to pop from the authorized path because the MatcherConfig is already filtered.
+        let relative_node_path_is_empty = relative_node_path.is_empty();
         let absolute_node_path =
             pop_authorized_path_and_append_relative_path(authorized_path, relative_node_path)?;
+        if !relative_node_path_is_empty && !auth.can_access_node(&absolute_node_path) {
+            return Err(self.get_unauthorized_path_error());
+        }
         Ok(absolute_node_path)
     }
 
@@ -320,6 +372,123 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
             .await?)
     }
 
+    /// Evaluates a single rule against a sample event.
+    /// Neither the current configuration nor the drafts are read or modified.
+    pub async fn test_rule(
+        &self,
+        auth: AuthContextV2<'_>,
+        request: TestRuleRequestDto,
+    ) -> Result<TestRuleResultDto, ApiError> {
+        auth.has_any_permission(&[&Permission::ConfigView, &Permission::ConfigEdit])?;
+        let rule_name = request.rule.name.clone();
+        let rule = dto_into_rule(request.rule)
+            .map_err(|err| ApiError::InvalidRuleError { message: format!("{}", err) })?;
+        let matcher = Matcher::build_with_settings(
+            &MatcherConfig::Ruleset { name: TEST_RULE_RULESET_NAME.to_owned(), rules: vec![rule] },
+            &self.matcher_settings,
+        )
+        .map_err(|err| ApiError::InvalidRuleError { message: format!("{}", err) })?;
+
+        let event = serde_json::to_value(request.event)?;
+        match matcher.process(event, false).result {
+            ProcessedNode::Ruleset { rules, .. } => {
+                let processed_rule = rules.rules.into_iter().next();
+                Ok(TestRuleResultDto {
+                    matched: processed_rule
+                        .as_ref()
+                        .map(|rule| rule.status == ProcessedRuleStatus::Matched)
+                        .unwrap_or(false),
+                    extracted_vars: rules
+                        .extracted_vars
+                        .get(rule_name.as_str())
+                        .cloned()
+                        .unwrap_or_else(|| Value::Object(Map::new())),
+                    message: processed_rule.and_then(|rule| rule.message),
+                })
+            }
+            _ => Err(ApiError::InternalServerError {
+                cause: "The test rule matcher should contain a single ruleset".to_owned(),
+            }),
+        }
+    }
+
+    /// Previews how a change of a filter of the current configuration reroutes a batch of
+    /// sample events. For each event, it returns the paths of the rulesets reached with the
+    /// current filter and with the proposed one.
+    /// The filter keeps its current name, so that the paths are comparable, and its current
+    /// mode and continue_on_match, unless the proposed filter sets them.
+    /// Neither the current configuration nor the drafts are modified.
+    pub async fn preview_filter(
+        &self,
+        auth: AuthContextV2<'_>,
+        request: FilterPreviewRequestDto,
+    ) -> Result<FilterPreviewResultDto, ApiError> {
+        auth.has_any_permission(&[&Permission::ConfigView, &Permission::ConfigEdit])?;
+        let current_config =
+            get_filtered_matcher(&self.config_manager.get_config().await?, &auth).await?;
+        let absolute_filter_path =
+            self.get_absolute_path_from_relative(&auth, &request.filter_path)?;
+
+        let current_node = current_config.get_node_by_path(&absolute_filter_path);
+        let nodes = match current_node {
+            Some(MatcherConfig::Filter { nodes, .. }) => nodes.clone(),
+            Some(_) => {
+                return Err(ApiError::BadRequestError {
+                    cause: format!("Node [{}] is not a filter", request.filter_path),
+                })
+            }
+            None => {
+                return Err(ApiError::NodeNotFoundError {
+                    message: format!("Node for relative path {:?} not found", request.filter_path),
+                })
+            }
+        };
+        let filter = match processing_tree_node_details_dto_into_matcher_config_with_current(
+            request.filter,
+            current_node,
+        )? {
+            MatcherConfig::Filter { filter, .. } => filter,
+            _ => {
+                return Err(ApiError::BadRequestError {
+                    cause: "The proposed node is not a filter".to_owned(),
+                })
+            }
+        };
+        let mut proposed_config = current_config.clone();
+        proposed_config.replace_node(
+            &absolute_filter_path,
+            MatcherConfig::Filter {
+                name: absolute_filter_path[absolute_filter_path.len() - 1].to_owned(),
+                filter,
+                nodes,
+            },
+        )?;
+
+        let current_matcher =
+            Matcher::build_with_settings(&current_config, &self.matcher_settings)?;
+        let proposed_matcher =
+            Matcher::build_with_settings(&proposed_config, &self.matcher_settings)
+                .map_err(|err| ApiError::InvalidConfigError { message: format!("{}", err) })?;
+
+        // The paths of the nodes are returned starting from the authorized node of the filter
+        let relative_filter_path_len = request.filter_path.split(NODE_PATH_SEPARATOR).count();
+        let authorized_path_len = absolute_filter_path.len() + 1 - relative_filter_path_len;
+        let mut events = vec![];
+        for event in request.events {
+            let event = serde_json::to_value(event)?;
+            let current = reached_ruleset_paths(
+                &current_matcher.process(event.clone(), false).result,
+                authorized_path_len,
+            );
+            let proposed = reached_ruleset_paths(
+                &proposed_matcher.process(event, false).result,
+                authorized_path_len,
+            );
+            events.push(FilterPreviewEventDto { changed: current != proposed, current, proposed });
+        }
+        Ok(FilterPreviewResultDto { events })
+    }
+
     async fn get_rule_details(
         &self,
         auth: &AuthContextV2<'_>,
@@ -404,6 +573,22 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         Ok(self.config_manager.get_drafts().await?)
     }
 
+    /// Returns a page of the available drafts for a specific tenant.
+    /// The drafts are sorted by id, so that the pages are stable; if the `limit` is
+    ///   not specified, the page contains all the drafts starting from the `offset`.
+    pub async fn get_drafts_page_by_tenant(
+        &self,
+        auth: &AuthContextV2<'_>,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Result<Page<String>, ApiError> {
+        let mut drafts = self.get_drafts_by_tenant(auth).await?;
+        drafts.sort();
+        let total = drafts.len();
+        let items = drafts.into_iter().skip(offset).take(limit.unwrap_or(usize::MAX)).collect();
+        Ok(Page { items, total })
+    }
+
     /// Returns a draft by id
     pub async fn get_draft(
         &self,
@@ -450,7 +635,7 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
     ) -> Result<MatcherConfig, ApiError> {
         auth.has_permission(&Permission::ConfigEdit)?;
         self.get_draft_and_check_owner(&auth, draft_id).await?;
-        self.config_manager.deploy_draft(draft_id).await?;
+        self.config_manager.deploy_draft(draft_id, auth.auth.user).await?;
         self.handler.reload_configuration().await
     }
 
@@ -464,10 +649,136 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         auth.has_permission(&Permission::ConfigEdit)?;
         let draft = self.config_manager.get_draft(draft_id).await?;
         auth.is_owner(&draft)?;
-        self.config_manager.deploy_draft(draft_id).await?;
+        self.config_manager.deploy_draft(draft_id, auth.clone().auth.user).await?;
+        self.handler.reload_configuration().await
+    }
+
+    /// Deploys a batch of drafts with a single configuration swap and reloads the tornado configuration.
+    /// The top level nodes of each draft replace the nodes with the same name in the current
+    ///   configuration, or are added to it if they do not exist.
+    /// All the drafts are validated before the deployment: if any draft is not valid, or if a top
+    ///   level node is contained in more than one draft, nothing is deployed and the error
+    ///   reports the failure of each draft.
+    /// TODO: implement the multitenancy https://siwuerthphoenix.atlassian.net/browse/NEPROD-1232
+    pub async fn deploy_drafts_batch_for_tenant(
+        &self,
+        auth: &AuthContextV2<'_>,
+        draft_ids: &[String],
+    ) -> Result<MatcherConfig, ApiError> {
+        auth.has_permission(&Permission::ConfigEdit)?;
+
+        let mut drafts = Vec::with_capacity(draft_ids.len());
+        for draft_id in draft_ids {
+            drafts.push(self.get_draft_and_check_owner(auth, draft_id).await?);
+        }
+
+        let mut failures = BTreeMap::new();
+        let mut node_draft_ids = HashMap::new();
+        for draft in &drafts {
+            let draft_id = draft.data.draft_id.as_str();
+            if let Err(err) = Matcher::build_with_settings(&draft.config, &self.matcher_settings) {
+                failures.insert(draft_id.to_owned(), format!("{}", err));
+                continue;
+            }
+            for node in top_level_nodes(&draft.config) {
+                if let Some(other_draft_id) = node_draft_ids.insert(node.get_name(), draft_id) {
+                    failures.insert(
+                        draft_id.to_owned(),
+                        format!(
+                            "The node [{}] is contained also in the draft [{}]",
+                            node.get_name(),
+                            other_draft_id
+                        ),
+                    );
+                }
+            }
+        }
+        if !failures.is_empty() {
+            return Err(ApiError::InvalidDraftsError { failures });
+        }
+
+        let mut config = self.config_manager.get_config().await?;
+        let root_name = config.get_name().to_owned();
+        for draft in &drafts {
+            for node in top_level_nodes(&draft.config) {
+                let node_path = [root_name.as_str(), node.get_name()];
+                if config.get_node_by_path(&node_path).is_some() {
+                    config.replace_node(&node_path, node.clone())?;
+                } else {
+                    config.create_node_in_path(&[root_name.as_str()], node.clone())?;
+                }
+            }
+        }
+
+        info!(
+            "User {} deploys the drafts {:?} with a single configuration swap",
+            auth.auth.user, draft_ids
+        );
+        self.config_manager.deploy_config(&config).await?;
+
+        // The configuration is already deployed: a failure in writing a record must not be
+        //   reported as a failed deploy
+        let config_hash = config.config_hash()?;
+        let deployed_ts_ms = chrono::Local::now().timestamp_millis();
+        for draft_id in draft_ids {
+            let record = MatcherConfigDeployRecord {
+                user: auth.auth.user.clone(),
+                draft_id: draft_id.clone(),
+                config_hash: config_hash.clone(),
+                deployed_ts_ms,
+            };
+            if let Err(err) = self.config_manager.append_deploy_record(&record).await {
+                error!(
+                    "Cannot write the deploy history record of draft {} with config hash {}: {:?}",
+                    record.draft_id, record.config_hash, err
+                );
+            }
+        }
         self.handler.reload_configuration().await
     }
 
+    /// Applies a JSON Patch (RFC 6902) to the configuration of a draft and returns the
+    ///   resulting configuration.
+    /// The draft is updated only if the patch can be applied and the resulting
+    ///   configuration is valid.
+    /// TODO: implement the multitenancy https://siwuerthphoenix.atlassian.net/browse/NEPROD-1232
+    pub async fn patch_draft_for_tenant(
+        &self,
+        auth: &AuthContextV2<'_>,
+        draft_id: &str,
+        patch: &Patch,
+    ) -> Result<MatcherConfig, ApiError> {
+        auth.has_permission(&Permission::ConfigEdit)?;
+        let draft = self.get_draft_and_check_owner(auth, draft_id).await?;
+
+        let mut config = serde_json::to_value(&draft.config)?;
+        json_patch::patch(&mut config, patch).map_err(|err| ApiError::InvalidConfigError {
+            message: format!("Cannot apply the patch to the draft [{}]: {}", draft_id, err),
+        })?;
+        let config: MatcherConfig =
+            serde_json::from_value(config).map_err(|err| ApiError::InvalidConfigError {
+                message: format!("The patched draft [{}] is not a valid config: {}", draft_id, err),
+            })?;
+        Matcher::build_with_settings(&config, &self.matcher_settings).map_err(|err| {
+            ApiError::InvalidConfigError {
+                message: format!("The patched draft [{}] is not a valid config: {}", draft_id, err),
+            }
+        })?;
+
+        self.config_manager.update_draft(draft_id, auth.auth.user.clone(), &config).await?;
+        Ok(config)
+    }
+
+    /// Returns the audit records of the deployed drafts
+    pub async fn get_deploy_history(
+        &self,
+        auth: &AuthContextV2<'_>,
+    ) -> Result<Vec<MatcherConfigDeployRecordDto>, ApiError> {
+        auth.has_permission(&Permission::ConfigView)?;
+        let history = self.config_manager.get_deploy_history().await?;
+        Ok(history.into_iter().map(deploy_record_into_dto).collect())
+    }
+
     /// Deletes a draft by id
     pub async fn delete_draft(
         &self,
@@ -541,12 +852,17 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
         auth: AuthContextV2<'_>,
         draft_id: &str,
         node_path: &str,
-        config: MatcherConfig,
+        config: ProcessingTreeNodeEditDto,
     ) -> Result<(), ApiError> {
         auth.has_permission(&Permission::ConfigEdit)?;
         let mut draft = self.get_draft_and_check_owner(&auth, draft_id).await?;
         let absolute_node_path = self.get_absolute_path_from_relative(&auth, node_path)?;
 
+        // The properties that are not set in the DTO keep the values of the edited node
+        let config = processing_tree_node_details_dto_into_matcher_config_with_current(
+            config,
+            draft.config.get_node_by_path(&absolute_node_path),
+        )?;
         draft.config.edit_node_in_path(&absolute_node_path, config)?;
         Ok(self.config_manager.update_draft(draft_id, auth.auth.user, &draft.config).await?)
     }
@@ -580,18 +896,71 @@ impl<A: ConfigApiHandler, CM: MatcherConfigReader + MatcherConfigEditor + ?Sized
     }
 }
 
+/// Returns the nodes that a draft deploys below the root of the configuration
+fn top_level_nodes(config: &MatcherConfig) -> &[MatcherConfig] {
+    match config {
+        MatcherConfig::Filter { name, nodes, .. } if name == "root" => nodes,
+        config => std::slice::from_ref(config),
+    }
+}
+
 pub async fn get_filtered_matcher(
     config: &MatcherConfig,
     auth: &AuthContextV2<'_>,
 ) -> Result<MatcherConfig, ApiError> {
-    let node_filter = NodeFilter::map_from(&[auth.auth.authorization.path.clone()]);
+    let authorized_paths = auth.authorized_paths();
+    let node_filter = NodeFilter::map_from(&authorized_paths);
     matcher_config_filter(config, &node_filter).ok_or({
         let message = "The authorized node path does not exist.";
-        warn!("{} Path: {:?}", message, &auth.auth.authorization.path);
+        warn!("{} Paths: {:?}", message, authorized_paths);
         ApiError::InvalidAuthorizedPath { message: message.to_owned() }
     })
 }
 
+/// Returns the paths, with the node names separated by commas, of the rulesets reached
+///   by the processed event, skipping the first `authorized_path_len - 1` nodes of each path.
+fn reached_ruleset_paths(node: &ProcessedNode, authorized_path_len: usize) -> Vec<String> {
+    fn collect<'a>(
+        node: &'a ProcessedNode,
+        path: &mut Vec<&'a str>,
+        paths: &mut Vec<Vec<&'a str>>,
+    ) {
+        match node {
+            ProcessedNode::Filter { name, filter, nodes } => {
+                if filter.status == ProcessedFilterStatus::Matched {
+                    path.push(name);
+                    nodes.iter().for_each(|node| collect(node, path, paths));
+                    path.pop();
+                }
+            }
+            ProcessedNode::Iterator { name, events, .. } => {
+                path.push(name);
+                events
+                    .iter()
+                    .flat_map(|iteration| iteration.result.iter())
+                    .for_each(|node| collect(node, path, paths));
+                path.pop();
+            }
+            ProcessedNode::Ruleset { name, .. } => {
+                path.push(name);
+                if !paths.contains(path) {
+                    paths.push(path.clone());
+                }
+                path.pop();
+            }
+        }
+    }
+
+    let mut paths = vec![];
+    collect(node, &mut vec![], &mut paths);
+    paths
+        .into_iter()
+        .map(|path| {
+            path[authorized_path_len.saturating_sub(1).min(path.len())..].join(NODE_PATH_SEPARATOR)
+        })
+        .collect()
+}
+
 fn pop_authorized_path_and_append_relative_path<'a>(
     mut base_path: Vec<&'a str>,
     mut relative_path: Vec<&'a str>,
@@ -622,21 +991,28 @@ mod test {
     use crate::auth::Permission;
     use crate::error::ApiError;
     use async_trait::async_trait;
+    use serde_json::json;
     use std::collections::BTreeMap;
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
     use tornado_engine_api_dto::auth::Auth;
     use tornado_engine_api_dto::auth_v2::{AuthV2, Authorization};
     use tornado_engine_api_dto::config::{ConstraintDto, RuleDetailsDto};
-    use tornado_engine_matcher::config::nodes::Filter;
+    use tornado_engine_matcher::config::nodes::{Filter, FilterMode};
     use tornado_engine_matcher::config::rule::{Constraint, Rule};
     use tornado_engine_matcher::config::{
-        Defaultable, MatcherConfig, MatcherConfigDraft, MatcherConfigDraftData,
+        Defaultable, MatcherConfig, MatcherConfigDeployRecord, MatcherConfigDraft,
+        MatcherConfigDraftData,
     };
     use tornado_engine_matcher::error::MatcherError;
 
     const DRAFT_OWNER_ID: &str = "OWNER";
 
-    struct TestConfigManager {}
+    #[derive(Default)]
+    struct TestConfigManager {
+        deploy_history: Mutex<Vec<MatcherConfigDeployRecord>>,
+        deployed_configs: Mutex<Vec<MatcherConfig>>,
+        updated_drafts: Mutex<Vec<MatcherConfig>>,
+    }
 
     #[async_trait::async_trait(? Send)]
     impl MatcherConfigReader for TestConfigManager {
@@ -647,6 +1023,8 @@ mod test {
                     description: "".to_string(),
                     active: false,
                     filter: Defaultable::Default {},
+                    mode: FilterMode::AllMatches,
+                    continue_on_match: true,
                 },
                 nodes: vec![
                     MatcherConfig::Filter {
@@ -655,6 +1033,8 @@ mod test {
                             description: "".to_string(),
                             active: false,
                             filter: Defaultable::Default {},
+                            mode: FilterMode::AllMatches,
+                            continue_on_match: true,
                         },
                         nodes: vec![
                             MatcherConfig::Filter {
@@ -663,6 +1043,8 @@ mod test {
                                     description: "".to_string(),
                                     active: false,
                                     filter: Defaultable::Default {},
+                                    mode: FilterMode::AllMatches,
+                                    continue_on_match: true,
                                 },
                                 nodes: vec![],
                             },
@@ -678,6 +1060,7 @@ mod test {
                                         with: Default::default(),
                                     },
                                     actions: vec![],
+                                    deduplication: None,
                                 }],
                             },
                         ],
@@ -688,6 +1071,8 @@ mod test {
                             description: "".to_string(),
                             active: false,
                             filter: Defaultable::Default {},
+                            mode: FilterMode::AllMatches,
+                            continue_on_match: true,
                         },
                         nodes: vec![
                             MatcherConfig::Ruleset {
@@ -702,6 +1087,7 @@ mod test {
                                         with: Default::default(),
                                     },
                                     actions: vec![],
+                                    deduplication: None,
                                 }],
                             },
                             MatcherConfig::Ruleset { name: "root_2_2".to_string(), rules: vec![] },
@@ -726,7 +1112,30 @@ mod test {
                     created_ts_ms: 0,
                     updated_ts_ms: 0,
                 },
-                config: MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![] },
+                config: match draft_id {
+                    "invalid_draft" => {
+                        MatcherConfig::Ruleset { name: "not valid name".to_owned(), rules: vec![] }
+                    }
+                    tenant_draft_id if tenant_draft_id.starts_with("tenant_") => {
+                        MatcherConfig::Ruleset { name: tenant_draft_id.to_owned(), rules: vec![] }
+                    }
+                    "filter_draft" => MatcherConfig::Filter {
+                        name: "root".to_owned(),
+                        filter: Filter::default(),
+                        nodes: vec![MatcherConfig::Filter {
+                            name: "child".to_owned(),
+                            filter: Filter {
+                                description: "".to_string(),
+                                active: true,
+                                filter: Defaultable::Default {},
+                                mode: FilterMode::FirstMatch,
+                                continue_on_match: false,
+                            },
+                            nodes: vec![],
+                        }],
+                    },
+                    _ => MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![] },
+                },
             })
         }
 
@@ -738,15 +1147,38 @@ mod test {
             &self,
             _draft_id: &str,
             _user: String,
-            _config: &MatcherConfig,
+            config: &MatcherConfig,
         ) -> Result<(), MatcherError> {
+            self.updated_drafts.lock().unwrap().push(config.clone());
             Ok(())
         }
 
-        async fn deploy_draft(&self, _draft_id: &str) -> Result<MatcherConfig, MatcherError> {
+        async fn deploy_draft(
+            &self,
+            draft_id: &str,
+            user: String,
+        ) -> Result<MatcherConfig, MatcherError> {
+            self.deploy_history.lock().unwrap().push(MatcherConfigDeployRecord {
+                user,
+                draft_id: draft_id.to_owned(),
+                config_hash: "hash".to_owned(),
+                deployed_ts_ms: 0,
+            });
             Ok(MatcherConfig::Ruleset { name: "ruleset_new".to_owned(), rules: vec![] })
         }
 
+        async fn append_deploy_record(
+            &self,
+            record: &MatcherConfigDeployRecord,
+        ) -> Result<(), MatcherError> {
+            self.deploy_history.lock().unwrap().push(record.clone());
+            Ok(())
+        }
+
+        async fn get_deploy_history(&self) -> Result<Vec<MatcherConfigDeployRecord>, MatcherError> {
+            Ok(self.deploy_history.lock().unwrap().clone())
+        }
+
         async fn delete_draft(&self, _draft_id: &str) -> Result<(), MatcherError> {
             Ok(())
         }
@@ -761,9 +1193,10 @@ mod test {
 
         async fn deploy_config(
             &self,
-            _config: &MatcherConfig,
+            config: &MatcherConfig,
         ) -> Result<MatcherConfig, MatcherError> {
-            unimplemented!()
+            self.deployed_configs.lock().unwrap().push(config.clone());
+            Ok(config.clone())
         }
     }
 
@@ -882,7 +1315,7 @@ mod test {
     #[actix_rt::test]
     async fn get_current_configuration_should_require_view_permission() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
             create_users(&permissions_map);
@@ -897,7 +1330,7 @@ mod test {
     #[actix_rt::test]
     async fn get_drafts_should_require_view_permission() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
             create_users(&permissions_map);
@@ -912,7 +1345,7 @@ mod test {
     #[actix_rt::test]
     async fn get_draft_should_require_view_permission_and_owner() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
             create_users(&permissions_map);
@@ -927,7 +1360,7 @@ mod test {
     #[actix_rt::test]
     async fn create_draft_should_require_edit_permission() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
             create_users(&permissions_map);
@@ -942,7 +1375,7 @@ mod test {
     #[actix_rt::test]
     async fn update_draft_should_require_edit_permission_and_owner() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
             create_users(&permissions_map);
@@ -985,7 +1418,7 @@ mod test {
     #[actix_rt::test]
     async fn delete_draft_should_require_edit_permission_and_owner() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
             create_users(&permissions_map);
@@ -1000,7 +1433,7 @@ mod test {
     #[actix_rt::test]
     async fn deploy_draft_should_require_edit_permission_and_owner() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
             create_users(&permissions_map);
@@ -1012,10 +1445,278 @@ mod test {
         assert!(api.deploy_draft(owner_edit_and_view, "id").await.is_ok());
     }
 
+    #[actix_rt::test]
+    async fn deploy_draft_should_append_a_deploy_record_with_the_deploying_user() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
+        let permissions_map = auth_permissions();
+        let (_, _, _, owner_edit_and_view) = create_users_v2(&permissions_map);
+
+        // Act
+        api.deploy_draft_for_tenant(&owner_edit_and_view, "id").await.unwrap();
+
+        // Assert
+        let history = api.get_deploy_history(&owner_edit_and_view).await.unwrap();
+        assert_eq!(
+            vec![MatcherConfigDeployRecordDto {
+                user: DRAFT_OWNER_ID.to_owned(),
+                draft_id: "id".to_owned(),
+                config_hash: "hash".to_owned(),
+                deployed_ts_ms: 0,
+            }],
+            history
+        );
+    }
+
+    #[actix_rt::test]
+    async fn patch_draft_should_require_edit_permission_and_owner() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
+        let permissions_map = auth_permissions();
+        let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
+            create_users_v2(&permissions_map);
+        let patch: Patch = serde_json::from_value(json!([])).unwrap();
+
+        // Act & Assert
+        assert!(api.patch_draft_for_tenant(&not_owner_edit_and_view, "id", &patch).await.is_err());
+        assert!(api.patch_draft_for_tenant(&owner_view, "id", &patch).await.is_err());
+        assert!(api.patch_draft_for_tenant(&owner_edit, "id", &patch).await.is_ok());
+        assert!(api.patch_draft_for_tenant(&owner_edit_and_view, "id", &patch).await.is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn patch_draft_should_apply_add_replace_and_remove_operations() {
+        // Arrange
+        let config_manager = Arc::new(TestConfigManager::default());
+        let api = ConfigApi::new(TestApiHandler {}, config_manager.clone());
+        let permissions_map = auth_permissions();
+        let (_, _, owner_edit, _) = create_users_v2(&permissions_map);
+
+        let new_rule = |name: &str| {
+            json!({
+                "name": name,
+                "description": "",
+                "continue": true,
+                "active": true,
+                "constraint": { "WHERE": null, "WITH": {} },
+                "actions": []
+            })
+        };
+        let patch: Patch = serde_json::from_value(json!([
+            { "op": "add", "path": "/Ruleset/rules/-", "value": new_rule("rule_1") },
+            { "op": "add", "path": "/Ruleset/rules/-", "value": new_rule("rule_2") },
+            { "op": "replace", "path": "/Ruleset/name", "value": "patched_ruleset" },
+            { "op": "remove", "path": "/Ruleset/rules/0" }
+        ]))
+        .unwrap();
+
+        // Act
+        let result = api.patch_draft_for_tenant(&owner_edit, "id", &patch).await.unwrap();
+
+        // Assert
+        match &result {
+            MatcherConfig::Ruleset { name, rules } => {
+                assert_eq!("patched_ruleset", name);
+                assert_eq!(vec!["rule_2"], rules.iter().map(|rule| &rule.name).collect::<Vec<_>>());
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(vec![result], *config_manager.updated_drafts.lock().unwrap());
+    }
+
+    #[actix_rt::test]
+    async fn edit_draft_config_node_should_keep_the_filter_mode_and_continue_on_match() {
+        // Arrange
+        let config_manager = Arc::new(TestConfigManager::default());
+        let api = ConfigApi::new(TestApiHandler {}, config_manager.clone());
+        let permissions_map = auth_permissions();
+        let (_, _, owner_edit, _) = create_users_v2(&permissions_map);
+
+        // Act
+        api.edit_draft_config_node(
+            owner_edit,
+            "filter_draft",
+            "root,child",
+            ProcessingTreeNodeEditDto::Filter {
+                name: "child".to_owned(),
+                description: "new description".to_owned(),
+                active: true,
+                filter: None,
+                mode: None,
+                continue_on_match: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // Assert
+        let updated_drafts = config_manager.updated_drafts.lock().unwrap();
+        match updated_drafts[0].get_node_by_path(&["root", "child"]) {
+            Some(MatcherConfig::Filter { filter, .. }) => {
+                assert_eq!("new description", filter.description);
+                assert_eq!(FilterMode::FirstMatch, filter.mode);
+                assert!(!filter.continue_on_match);
+            }
+            result => panic!("{:?}", result),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn patch_draft_should_reject_a_patch_that_produces_an_invalid_config() {
+        // Arrange
+        let config_manager = Arc::new(TestConfigManager::default());
+        let api = ConfigApi::new(TestApiHandler {}, config_manager.clone());
+        let permissions_map = auth_permissions();
+        let (_, _, owner_edit, _) = create_users_v2(&permissions_map);
+
+        let invalid_name: Patch = serde_json::from_value(json!([
+            { "op": "replace", "path": "/Ruleset/name", "value": "not valid name" }
+        ]))
+        .unwrap();
+        let invalid_structure: Patch = serde_json::from_value(json!([
+            { "op": "remove", "path": "/Ruleset/rules" }
+        ]))
+        .unwrap();
+        let not_applicable: Patch = serde_json::from_value(json!([
+            { "op": "remove", "path": "/Ruleset/rules/0" }
+        ]))
+        .unwrap();
+
+        // Act & Assert
+        for patch in [invalid_name, invalid_structure, not_applicable] {
+            let result = api.patch_draft_for_tenant(&owner_edit, "id", &patch).await;
+            assert!(matches!(result, Err(ApiError::InvalidConfigError { .. })));
+        }
+        assert!(config_manager.updated_drafts.lock().unwrap().is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn deploy_drafts_batch_should_require_edit_permission_and_owner() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
+        let permissions_map = auth_permissions();
+        let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
+            create_users_v2(&permissions_map);
+        let draft_ids = vec!["tenant_a".to_owned()];
+
+        // Act & Assert
+        assert!(api
+            .deploy_drafts_batch_for_tenant(&not_owner_edit_and_view, &draft_ids)
+            .await
+            .is_err());
+        assert!(api.deploy_drafts_batch_for_tenant(&owner_view, &draft_ids).await.is_err());
+        assert!(api.deploy_drafts_batch_for_tenant(&owner_edit, &draft_ids).await.is_ok());
+        assert!(api.deploy_drafts_batch_for_tenant(&owner_edit_and_view, &draft_ids).await.is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn deploy_drafts_batch_should_deploy_all_the_drafts_with_a_single_config_swap() {
+        // Arrange
+        let config_manager = Arc::new(TestConfigManager::default());
+        let api = ConfigApi::new(TestApiHandler {}, config_manager.clone());
+        let permissions_map = auth_permissions();
+        let (_, _, owner_edit, _) = create_users_v2(&permissions_map);
+
+        // Act
+        let result = api
+            .deploy_drafts_batch_for_tenant(
+                &owner_edit,
+                &["tenant_a".to_owned(), "tenant_b".to_owned()],
+            )
+            .await;
+
+        // Assert
+        assert!(result.is_ok());
+        let deployed_configs = config_manager.deployed_configs.lock().unwrap();
+        assert_eq!(1, deployed_configs.len());
+        match &deployed_configs[0] {
+            MatcherConfig::Filter { name, nodes, .. } => {
+                assert_eq!("root", name);
+                let node_names: Vec<&str> = nodes.iter().map(|node| node.get_name()).collect();
+                assert_eq!(vec!["root_1", "root_2", "tenant_a", "tenant_b"], node_names);
+            }
+            _ => unreachable!(),
+        }
+
+        let deploy_history = config_manager.deploy_history.lock().unwrap();
+        let deployed_draft_ids: Vec<&str> =
+            deploy_history.iter().map(|record| record.draft_id.as_str()).collect();
+        assert_eq!(vec!["tenant_a", "tenant_b"], deployed_draft_ids);
+        let config_hash = deployed_configs[0].config_hash().unwrap();
+        for record in deploy_history.iter() {
+            assert_eq!(owner_edit.auth.user, record.user);
+            assert_eq!(config_hash, record.config_hash);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn deploy_drafts_batch_should_deploy_nothing_if_a_draft_is_not_valid() {
+        // Arrange
+        let config_manager = Arc::new(TestConfigManager::default());
+        let api = ConfigApi::new(TestApiHandler {}, config_manager.clone());
+        let permissions_map = auth_permissions();
+        let (_, _, owner_edit, _) = create_users_v2(&permissions_map);
+
+        // Act
+        let result = api
+            .deploy_drafts_batch_for_tenant(
+                &owner_edit,
+                &["tenant_a".to_owned(), "invalid_draft".to_owned()],
+            )
+            .await;
+
+        // Assert
+        match result {
+            Err(ApiError::InvalidDraftsError { failures }) => {
+                assert_eq!(vec!["invalid_draft"], failures.keys().collect::<Vec<_>>());
+            }
+            _ => unreachable!(),
+        }
+        assert!(config_manager.deployed_configs.lock().unwrap().is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn deploy_drafts_batch_should_deploy_nothing_if_two_drafts_contain_the_same_node() {
+        // Arrange
+        let config_manager = Arc::new(TestConfigManager::default());
+        let api = ConfigApi::new(TestApiHandler {}, config_manager.clone());
+        let permissions_map = auth_permissions();
+        let (_, _, owner_edit, _) = create_users_v2(&permissions_map);
+
+        // Act
+        let result = api
+            .deploy_drafts_batch_for_tenant(&owner_edit, &["id_1".to_owned(), "id_2".to_owned()])
+            .await;
+
+        // Assert
+        match result {
+            Err(ApiError::InvalidDraftsError { failures }) => {
+                assert_eq!(vec!["id_2"], failures.keys().collect::<Vec<_>>());
+            }
+            _ => unreachable!(),
+        }
+        assert!(config_manager.deployed_configs.lock().unwrap().is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn get_deploy_history_should_require_view_permission() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
+        let permissions_map = auth_permissions();
+        let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
+            create_users_v2(&permissions_map);
+
+        // Act & Assert
+        assert!(api.get_deploy_history(&not_owner_edit_and_view).await.is_ok());
+        assert!(api.get_deploy_history(&owner_view).await.is_ok());
+        assert!(api.get_deploy_history(&owner_edit).await.is_err());
+        assert!(api.get_deploy_history(&owner_edit_and_view).await.is_ok());
+    }
+
     #[actix_rt::test]
     async fn draft_take_over_should_require_edit_permission() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
             create_users(&permissions_map);
@@ -1030,7 +1731,7 @@ mod test {
     #[actix_rt::test]
     async fn get_current_config_processing_tree_nodes_by_path_should_require_view_permission() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
             create_users_v2(&permissions_map);
@@ -1070,7 +1771,7 @@ mod test {
     async fn get_current_config_processing_tree_nodes_by_empty_path_should_return_authorized_subtree_entry(
     ) {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user_root_1 = AuthContextV2::new(
             AuthV2 {
@@ -1109,7 +1810,7 @@ mod test {
     async fn get_current_config_processing_tree_nodes_by_empty_path_should_return_error_if_authorized_path_does_not_exist(
     ) {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user_root_3 = AuthContextV2::new(
             AuthV2 {
@@ -1137,7 +1838,7 @@ mod test {
     async fn get_current_config_processing_tree_nodes_should_return_error_if_authorized_path_is_empty(
     ) {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user = AuthContextV2::new(
             AuthV2 {
@@ -1162,7 +1863,7 @@ mod test {
     async fn get_current_config_processing_tree_nodes_should_return_error_if_authorized_path_root_does_not_exist(
     ) {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user = AuthContextV2::new(
             AuthV2 {
@@ -1187,7 +1888,7 @@ mod test {
     #[actix_rt::test]
     async fn get_current_config_processing_tree_nodes_by_path_should_start_from_authorized_path() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user_root_1 = AuthContextV2::new(
             AuthV2 {
@@ -1233,7 +1934,7 @@ mod test {
     async fn get_current_config_processing_tree_nodes_by_path_should_return_error_if_authorized_path_does_not_exist(
     ) {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user_root_3 = AuthContextV2::new(
             AuthV2 {
@@ -1258,7 +1959,7 @@ mod test {
     #[actix_rt::test]
     async fn get_current_config_node_details_by_path_should_require_view_permission() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let (not_owner_edit_and_view, owner_view, owner_edit, owner_edit_and_view) =
             create_users_v2(&permissions_map);
@@ -1286,7 +1987,7 @@ mod test {
     async fn get_current_config_node_details_by_path_should_return_details_starting_from_authorized_path(
     ) {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user = AuthContextV2::new(
             AuthV2 {
@@ -1326,7 +2027,7 @@ mod test {
     #[actix_rt::test]
     async fn get_current_config_rule_by_path_should_return_dto() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user = AuthContextV2::new(
             AuthV2 {
@@ -1356,6 +2057,7 @@ mod test {
             active: true,
             constraint: ConstraintDto { where_operator: None, with: Default::default() },
             actions: vec![],
+            deduplication: None,
         };
         assert_eq!(res_get_rule_details, expected_res);
     }
@@ -1363,7 +2065,7 @@ mod test {
     #[actix_rt::test]
     async fn get_current_config_rule_by_path_should_return_forbidden_if_user_is_not_viewer() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user = AuthContextV2::new(
             AuthV2 {
@@ -1397,7 +2099,7 @@ mod test {
     async fn get_current_config_node_details_by_path_should_return_error_if_authorized_path_does_not_exist(
     ) {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user_root_3 = AuthContextV2::new(
             AuthV2 {
@@ -1421,7 +2123,7 @@ mod test {
     async fn get_current_config_node_details_by_path_should_return_error_if_node_path_outside_authorized_path(
     ) {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user = AuthContextV2::new(
             AuthV2 {
@@ -1444,6 +2146,45 @@ mod test {
         ))
     }
 
+    #[actix_rt::test]
+    async fn get_current_config_node_details_by_path_should_return_details_of_the_role_paths() {
+        // Arrange
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
+        let permissions_map = auth_permissions();
+        let mut role_paths = BTreeMap::new();
+        role_paths.insert("view".to_owned(), vec!["root".to_owned(), "root_2".to_owned()]);
+        let user = AuthContextV2::new(
+            AuthV2 {
+                user: DRAFT_OWNER_ID.to_owned(),
+                authorization: Authorization {
+                    path: vec!["root".to_owned(), "root_1".to_owned()],
+                    roles: vec!["view".to_owned()],
+                },
+                preferences: None,
+            },
+            &permissions_map,
+        )
+        .with_role_paths(&role_paths);
+        let config = &api.config_manager.get_config().await.unwrap();
+        let filtered_matcher = get_filtered_matcher(config, &user).await.unwrap();
+
+        // Act
+        let res_main_path = api.get_node_details(&user, &filtered_matcher, "root_1,root_1_2").await;
+        let res_role_path = api.get_node_details(&user, &filtered_matcher, "root_2,root_2_1").await;
+        let res_outside = api.get_node_details(&user, &filtered_matcher, "root,root_2").await;
+        let tree_info = api.get_authorized_tree_info(&user).await.unwrap();
+
+        // Assert
+        assert!(res_main_path.is_ok());
+        assert!(matches!(
+            res_role_path,
+            Ok(ProcessingTreeNodeDetailsDto::Ruleset { name, .. }) if name == "root_2_1"
+        ));
+        assert!(matches!(res_outside, Err(ApiError::ForbiddenError { .. })));
+        assert_eq!(3, tree_info.filters_count);
+        assert_eq!(2, tree_info.rules_count);
+    }
+
     #[test]
     fn pop_authorized_path_and_append_relative_path_should_pop_and_append() {
         // Arrange
@@ -1489,7 +2230,7 @@ mod test {
     #[actix_rt::test]
     async fn get_tree_info_should_return_aggregate_number_of_filters_and_rules() {
         // Arrange
-        let test = TestConfigManager {}.get_config().await.unwrap();
+        let test = TestConfigManager::default().get_config().await.unwrap();
         let root = test.get_child_nodes_by_path(&[]).unwrap();
 
         // Act
@@ -1510,6 +2251,8 @@ mod test {
                 description: "".to_string(),
                 active: false,
                 filter: Defaultable::Default {},
+                mode: FilterMode::AllMatches,
+                continue_on_match: true,
             },
             nodes: vec![],
         };
@@ -1537,6 +2280,7 @@ mod test {
                 active: false,
                 constraint: Constraint { where_operator: None, with: Default::default() },
                 actions: vec![],
+                deduplication: None,
             }],
         };
 
@@ -1584,7 +2328,7 @@ mod test {
     #[actix_rt::test]
     async fn get_authorized_tree_info_should_require_permissions() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user1 = AuthContextV2::new(
             AuthV2 {
@@ -1618,7 +2362,7 @@ mod test {
     #[actix_rt::test]
     async fn get_authorized_tree_info_should_include_root_node() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user1 = AuthContextV2::new(
             AuthV2 {
@@ -1703,7 +2447,7 @@ mod test {
     #[actix_rt::test]
     async fn export_draft_tree_starting_from_a_specific_filter() {
         // Arrange
-        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager {}));
+        let api = ConfigApi::new(TestApiHandler {}, Arc::new(TestConfigManager::default()));
         let permissions_map = auth_permissions();
         let user = AuthContextV2::new(
             AuthV2 {
@@ -1,13 +1,15 @@
 use serde_json::Error;
 use tornado_engine_api_dto::config::{
-    ActionDto, ConstraintDto, ExtractorDto, ExtractorRegexDto, ModifierDto, OperatorDto,
+    ActionDto, ConstraintDto, DedupKeyDto, DeduplicationDto, ExtractorDto, ExtractorRegexDto,
+    FilterModeDto, MatcherConfigDeployRecordDto, ModifierDto, OperatorDto,
     ProcessingTreeNodeEditDto, RuleDto,
 };
-use tornado_engine_matcher::config::nodes::{Filter, MatcherIterator};
+use tornado_engine_matcher::config::nodes::{Filter, FilterMode, MatcherIterator};
 use tornado_engine_matcher::config::rule::{
-    ConfigAction, Constraint, Extractor, ExtractorRegex, Modifier, Operator, Rule,
+    ConfigAction, Constraint, DedupKey, Deduplication, Extractor, ExtractorRegex, Modifier,
+    Operator, Rule,
 };
-use tornado_engine_matcher::config::{Defaultable, MatcherConfig};
+use tornado_engine_matcher::config::{Defaultable, MatcherConfig, MatcherConfigDeployRecord};
 
 pub fn rule_into_dto(rule: Rule) -> Result<RuleDto, Error> {
     Ok(RuleDto {
@@ -17,11 +19,40 @@ pub fn rule_into_dto(rule: Rule) -> Result<RuleDto, Error> {
         description: rule.description,
         do_continue: rule.do_continue,
         name: rule.name,
+        deduplication: rule.deduplication.map(deduplication_into_dto),
     })
 }
 
+fn deduplication_into_dto(deduplication: Deduplication) -> DeduplicationDto {
+    DeduplicationDto {
+        key: dedup_key_into_dto(deduplication.key),
+        ttl_secs: deduplication.ttl_secs,
+    }
+}
+
+fn dedup_key_into_dto(key: DedupKey) -> DedupKeyDto {
+    match key {
+        DedupKey::Template(template) => DedupKeyDto::Template(template),
+        DedupKey::Fields { fields, separator } => DedupKeyDto::Fields { fields, separator },
+    }
+}
+
+pub fn deploy_record_into_dto(record: MatcherConfigDeployRecord) -> MatcherConfigDeployRecordDto {
+    MatcherConfigDeployRecordDto {
+        user: record.user,
+        draft_id: record.draft_id,
+        config_hash: record.config_hash,
+        deployed_ts_ms: record.deployed_ts_ms,
+    }
+}
+
 fn action_into_dto(action: ConfigAction) -> Result<ActionDto, Error> {
-    Ok(ActionDto { id: action.id, payload: serde_json::to_value(action.payload)? })
+    Ok(ActionDto {
+        id: action.id,
+        payload: serde_json::to_value(action.payload)?,
+        when: action.when.map(|operator| OperatorDto::from(&operator)),
+        retry_strategy: action.retry_strategy.map(serde_json::to_value).transpose()?,
+    })
 }
 
 fn constraint_into_dto(constraint: Constraint) -> Result<ConstraintDto, Error> {
@@ -54,6 +85,7 @@ fn extractor_into_dto(extractor: Extractor) -> ExtractorDto {
                 }
                 Modifier::ToNumber {} => ModifierDto::ToNumber {},
                 Modifier::Trim {} => ModifierDto::Trim {},
+                Modifier::Uppercase {} => ModifierDto::Uppercase {},
                 Modifier::DateAndTime { timezone } => ModifierDto::DateAndTime { timezone },
             })
             .collect(),
@@ -62,8 +94,8 @@ fn extractor_into_dto(extractor: Extractor) -> ExtractorDto {
 
 fn extractor_regex_into_dto(extractor_regex: ExtractorRegex) -> ExtractorRegexDto {
     match extractor_regex {
-        ExtractorRegex::Regex { regex, all_matches, group_match_idx } => {
-            ExtractorRegexDto::Regex { regex, all_matches, group_match_idx }
+        ExtractorRegex::Regex { regex, all_matches, group_match_idx, empty_if_no_match } => {
+            ExtractorRegexDto::Regex { regex, all_matches, group_match_idx, empty_if_no_match }
         }
         ExtractorRegex::RegexNamedGroups { regex, all_matches } => {
             ExtractorRegexDto::RegexNamedGroups { regex, all_matches }
@@ -74,20 +106,48 @@ fn extractor_regex_into_dto(extractor_regex: ExtractorRegex) -> ExtractorRegexDt
 
 pub fn processing_tree_node_details_dto_into_matcher_config(
     config: ProcessingTreeNodeEditDto,
+) -> Result<MatcherConfig, Error> {
+    processing_tree_node_details_dto_into_matcher_config_with_current(config, None)
+}
+
+/// Converts the DTO of a node into a MatcherConfig.
+/// The filter properties that are not set in the DTO are taken from the current node,
+/// if it is a filter, otherwise they get their default value.
+pub fn processing_tree_node_details_dto_into_matcher_config_with_current(
+    config: ProcessingTreeNodeEditDto,
+    current: Option<&MatcherConfig>,
 ) -> Result<MatcherConfig, Error> {
     Ok(match config {
         ProcessingTreeNodeEditDto::Ruleset { name } => {
             MatcherConfig::Ruleset { name, rules: vec![] }
         }
-        ProcessingTreeNodeEditDto::Filter { name, description, active, filter } => {
+        ProcessingTreeNodeEditDto::Filter {
+            name,
+            description,
+            active,
+            filter,
+            mode,
+            continue_on_match,
+        } => {
             let filter_matcher_config = if let Some(filter_inner) = filter {
                 Defaultable::from(Option::Some(dto_into_operator(filter_inner)?))
             } else {
                 Defaultable::Default {}
             };
+            let current_filter = match current {
+                Some(MatcherConfig::Filter { filter, .. }) => filter.clone(),
+                _ => Filter::default(),
+            };
             MatcherConfig::Filter {
                 name,
-                filter: Filter { description, filter: filter_matcher_config, active },
+                filter: Filter {
+                    description,
+                    filter: filter_matcher_config,
+                    active,
+                    mode: mode.map(dto_into_filter_mode).unwrap_or(current_filter.mode),
+                    continue_on_match: continue_on_match
+                        .unwrap_or(current_filter.continue_on_match),
+                },
                 nodes: vec![],
             }
         }
@@ -101,6 +161,13 @@ pub fn processing_tree_node_details_dto_into_matcher_config(
     })
 }
 
+fn dto_into_filter_mode(mode: FilterModeDto) -> FilterMode {
+    match mode {
+        FilterModeDto::AllMatches => FilterMode::AllMatches,
+        FilterModeDto::FirstMatch => FilterMode::FirstMatch,
+    }
+}
+
 pub fn dto_into_rule(rule: RuleDto) -> Result<Rule, Error> {
     Ok(Rule {
         active: rule.active,
@@ -109,11 +176,28 @@ pub fn dto_into_rule(rule: RuleDto) -> Result<Rule, Error> {
         description: rule.description,
         do_continue: rule.do_continue,
         name: rule.name,
+        deduplication: rule.deduplication.map(dto_into_deduplication),
     })
 }
 
+fn dto_into_deduplication(deduplication: DeduplicationDto) -> Deduplication {
+    Deduplication { key: dto_into_dedup_key(deduplication.key), ttl_secs: deduplication.ttl_secs }
+}
+
+fn dto_into_dedup_key(key: DedupKeyDto) -> DedupKey {
+    match key {
+        DedupKeyDto::Template(template) => DedupKey::Template(template),
+        DedupKeyDto::Fields { fields, separator } => DedupKey::Fields { fields, separator },
+    }
+}
+
 fn dto_into_action(action: ActionDto) -> Result<ConfigAction, Error> {
-    Ok(ConfigAction { id: action.id, payload: serde_json::from_value(action.payload)? })
+    Ok(ConfigAction {
+        id: action.id,
+        payload: serde_json::from_value(action.payload)?,
+        when: action.when.map(dto_into_operator).transpose()?,
+        retry_strategy: action.retry_strategy.map(serde_json::from_value).transpose()?,
+    })
 }
 
 fn dto_into_constraint(constraint: ConstraintDto) -> Result<Constraint, Error> {
@@ -181,6 +265,20 @@ fn dto_into_operator(operator: OperatorDto) -> Result<Operator, Error> {
             second: serde_json::from_value(second)?,
         },
         OperatorDto::Regex { regex, target } => Operator::Regex { regex, target },
+        OperatorDto::Similar { first, second, threshold } => Operator::Similar {
+            first: serde_json::from_value(first)?,
+            second: serde_json::from_value(second)?,
+            threshold,
+        },
+        OperatorDto::Changed { entity, target } => Operator::Changed {
+            entity: serde_json::from_value(entity)?,
+            target: serde_json::from_value(target)?,
+        },
+        OperatorDto::IsType { target, expected_type } => Operator::IsType {
+            target: serde_json::from_value(target)?,
+            expected_type: serde_json::from_value(serde_json::Value::String(expected_type))?,
+        },
+        OperatorDto::Custom { name, params } => Operator::Custom { name, params },
     };
     Ok(result)
 }
@@ -202,6 +300,7 @@ fn dto_into_extractor(extractor: ExtractorDto) -> Extractor {
                 }
                 ModifierDto::ToNumber {} => Modifier::ToNumber {},
                 ModifierDto::Trim {} => Modifier::Trim {},
+                ModifierDto::Uppercase {} => Modifier::Uppercase {},
                 ModifierDto::DateAndTime { timezone } => Modifier::DateAndTime { timezone },
             })
             .collect(),
@@ -210,8 +309,8 @@ fn dto_into_extractor(extractor: ExtractorDto) -> Extractor {
 
 fn dto_into_extractor_regex(extractor_regex: ExtractorRegexDto) -> ExtractorRegex {
     match extractor_regex {
-        ExtractorRegexDto::Regex { regex, all_matches, group_match_idx } => {
-            ExtractorRegex::Regex { regex, all_matches, group_match_idx }
+        ExtractorRegexDto::Regex { regex, all_matches, group_match_idx, empty_if_no_match } => {
+            ExtractorRegex::Regex { regex, all_matches, group_match_idx, empty_if_no_match }
         }
         ExtractorRegexDto::RegexNamedGroups { regex, all_matches } => {
             ExtractorRegex::RegexNamedGroups { regex, all_matches }
@@ -222,12 +321,15 @@ fn dto_into_extractor_regex(extractor_regex: ExtractorRegexDto) -> ExtractorRege
 
 #[cfg(test)]
 mod test {
-    use crate::config::convert::processing_tree_node_details_dto_into_matcher_config;
+    use crate::config::convert::{
+        processing_tree_node_details_dto_into_matcher_config,
+        processing_tree_node_details_dto_into_matcher_config_with_current,
+    };
     use serde_json::json;
-    use tornado_engine_api_dto::config::{OperatorDto, ProcessingTreeNodeEditDto};
-    use tornado_engine_matcher::config::nodes::Filter;
+    use tornado_engine_api_dto::config::{FilterModeDto, OperatorDto, ProcessingTreeNodeEditDto};
+    use tornado_engine_matcher::config::nodes::{Filter, FilterMode};
     use tornado_engine_matcher::config::rule::Operator;
-    use tornado_engine_matcher::config::{Defaultable, MatcherConfig};
+    use tornado_engine_matcher::config::{Defaultable, MatcherConfig, MatcherConfigDeployRecord};
 
     #[actix_rt::test]
     async fn processing_tree_node_details_dto_filter_into_matcher_config_should_return_a_matcher_config_filter(
@@ -239,6 +341,8 @@ mod test {
                 description: "test_filter description".to_string(),
                 active: false,
                 filter: Defaultable::Default {},
+                mode: FilterMode::AllMatches,
+                continue_on_match: true,
             },
             nodes: vec![],
         };
@@ -250,6 +354,8 @@ mod test {
                 filter: Defaultable::from(Option::Some(Operator::And {
                     operators: vec![Operator::Equals { first: json!(12), second: json!(15) }],
                 })),
+                mode: FilterMode::AllMatches,
+                continue_on_match: true,
             },
             nodes: vec![],
         };
@@ -259,6 +365,8 @@ mod test {
                 description: "test_filter description".to_string(),
                 active: false,
                 filter: None,
+                mode: None,
+                continue_on_match: None,
             };
         let processing_tree_node_details_dto = ProcessingTreeNodeEditDto::Filter {
             name: "test_filter".to_string(),
@@ -267,6 +375,8 @@ mod test {
             filter: Option::Some(OperatorDto::And {
                 operators: vec![OperatorDto::Equals { first: json!(12), second: json!(15) }],
             }),
+            mode: None,
+            continue_on_match: None,
         };
 
         // Act
@@ -285,6 +395,59 @@ mod test {
         assert_eq!(actual_maatcher_config_filter.unwrap(), expected_maatcher_config_filter);
     }
 
+    #[test]
+    fn processing_tree_node_details_dto_filter_should_keep_the_current_mode_if_not_set() {
+        // Arrange
+        let current = MatcherConfig::Filter {
+            name: "test_filter".to_string(),
+            filter: Filter {
+                description: "".to_string(),
+                active: true,
+                filter: Defaultable::Default {},
+                mode: FilterMode::FirstMatch,
+                continue_on_match: false,
+            },
+            nodes: vec![],
+        };
+        let edit_dto = |mode, continue_on_match| ProcessingTreeNodeEditDto::Filter {
+            name: "test_filter".to_string(),
+            description: "new description".to_string(),
+            active: true,
+            filter: None,
+            mode,
+            continue_on_match,
+        };
+
+        // Act
+        let kept = processing_tree_node_details_dto_into_matcher_config_with_current(
+            edit_dto(None, None),
+            Some(&current),
+        )
+        .unwrap();
+        let overridden = processing_tree_node_details_dto_into_matcher_config_with_current(
+            edit_dto(Some(FilterModeDto::AllMatches), Some(true)),
+            Some(&current),
+        )
+        .unwrap();
+
+        // Assert
+        match kept {
+            MatcherConfig::Filter { filter, .. } => {
+                assert_eq!("new description", filter.description);
+                assert_eq!(FilterMode::FirstMatch, filter.mode);
+                assert!(!filter.continue_on_match);
+            }
+            result => panic!("{:?}", result),
+        }
+        match overridden {
+            MatcherConfig::Filter { filter, .. } => {
+                assert_eq!(FilterMode::AllMatches, filter.mode);
+                assert!(filter.continue_on_match);
+            }
+            result => panic!("{:?}", result),
+        }
+    }
+
     #[actix_rt::test]
     async fn processing_tree_node_details_dto_ruleset_into_matcher_config_should_return_a_matcher_config_ruleset(
     ) {
@@ -4,21 +4,27 @@ use crate::error::ApiError;
 use crate::model::{ApiData, ApiDataV2, ExportVersionedMatcherConfig};
 use actix_multipart::Multipart;
 use actix_web::http::header;
-use actix_web::web::{Data, Json, Path};
+use actix_web::web::{Data, Json, Path, Query};
 use actix_web::{web, HttpRequest, HttpResponse, Scope};
 use chrono::{Local, SecondsFormat};
 use futures_util::TryStreamExt as _;
 use gethostname::gethostname;
+use json_patch::Patch;
 use log::*;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use std::os::unix::ffi::OsStrExt;
 use tornado_engine_api_dto::common::Id;
 use tornado_engine_api_dto::config::{
+    FilterPreviewRequestDto, FilterPreviewResultDto, MatcherConfigDeployRecordDto,
     ProcessingTreeNodeConfigDto, ProcessingTreeNodeDetailsDto, ProcessingTreeNodeEditDto, RuleDto,
-    RulePositionDto, TreeInfoDto,
+    RulePositionDto, TestRuleRequestDto, TestRuleResultDto, TreeInfoDto,
 };
-use tornado_engine_matcher::config::MatcherConfigEditor;
+use tornado_engine_matcher::config::{MatcherConfig, MatcherConfigEditor};
+
+/// The maximum size in bytes of the files uploaded to the config endpoints.
+#[derive(Debug, Clone, Copy)]
+struct MaxPayloadSize(usize);
 
 pub fn build_config_v2_endpoints<
     A: ConfigApiHandler + 'static,
@@ -26,8 +32,27 @@ pub fn build_config_v2_endpoints<
 >(
     data: ApiDataV2<ConfigApi<A, CM>>,
 ) -> Scope {
-    web::scope("/config")
-        .app_data(Data::new(data))
+    build_config_v2_endpoints_with_max_payload_size(data, None)
+}
+
+/// Builds the config endpoints rejecting with 413 Payload Too Large the JSON bodies
+/// and the uploaded files bigger than `max_payload_size` bytes.
+/// If `max_payload_size` is None, the limits of the enclosing scope are used.
+pub fn build_config_v2_endpoints_with_max_payload_size<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    data: ApiDataV2<ConfigApi<A, CM>>,
+    max_payload_size: Option<usize>,
+) -> Scope {
+    let scope = web::scope("/config").app_data(Data::new(data));
+    let scope = match max_payload_size {
+        Some(max_payload_size) => scope
+            .app_data(web::JsonConfig::default().limit(max_payload_size))
+            .app_data(MaxPayloadSize(max_payload_size)),
+        None => scope,
+    };
+    scope
         .service(
             web::scope("/active")
                 .service(
@@ -101,7 +126,8 @@ pub fn build_config_v2_endpoints<
         )
         .service(
             web::resource("/drafts/{param_auth}/{draft_id}")
-                .route(web::delete().to(delete_draft_in_tenant::<A, CM>)),
+                .route(web::delete().to(delete_draft_in_tenant::<A, CM>))
+                .route(web::patch().to(patch_draft_in_tenant::<A, CM>)),
         )
         .service(
             web::resource("/drafts/{param_auth}/{draft_id}/deploy")
@@ -111,6 +137,28 @@ pub fn build_config_v2_endpoints<
             web::resource("/drafts/{param_auth}/{draft_id}/takeover")
                 .route(web::post().to(draft_take_over_for_tenant::<A, CM>)),
         )
+        .service(
+            web::resource("/deploy-batch/{param_auth}")
+                .route(web::post().to(deploy_drafts_batch_for_tenant::<A, CM>)),
+        )
+        .service(
+            web::resource("/deploy-history/{param_auth}")
+                .route(web::get().to(get_deploy_history::<A, CM>)),
+        )
+        .service(web::resource("/test-rule/{param_auth}").route(web::post().to(test_rule::<A, CM>)))
+        .service(
+            web::resource("/filter-preview/{param_auth}")
+                .route(web::post().to(preview_filter::<A, CM>)),
+        )
+        .service(
+            web::resource("/schema/{param_auth}").route(web::get().to(get_config_schema::<A, CM>)),
+        )
+}
+
+#[derive(Deserialize)]
+struct PageParams {
+    offset: Option<usize>,
+    limit: Option<usize>,
 }
 
 #[derive(Deserialize)]
@@ -250,7 +298,11 @@ async fn create_draft_tree_node<
     Ok(Json(()))
 }
 
-async fn parse_uploaded_file<T: DeserializeOwned>(mut payload: Multipart) -> actix_web::Result<T> {
+async fn parse_uploaded_file<T: DeserializeOwned>(
+    req: &HttpRequest,
+    mut payload: Multipart,
+) -> actix_web::Result<T> {
+    let max_payload_size = req.app_data::<MaxPayloadSize>().map(|max| max.0);
     let mut file_data = Vec::<u8>::new();
     while let Some(mut field) = payload.try_next().await? {
         let Some("file") = field.content_disposition().get_name() else {
@@ -262,6 +314,11 @@ async fn parse_uploaded_file<T: DeserializeOwned>(mut payload: Multipart) -> act
         };
         while let Some(chunk) = field.try_next().await? {
             file_data.extend_from_slice(&chunk);
+            if let Some(max_payload_size) = max_payload_size {
+                if file_data.len() > max_payload_size {
+                    return Err(ApiError::PayloadToLarge.into());
+                }
+            }
         }
     }
     trace!("File uploaded of size {}", file_data.len());
@@ -285,7 +342,7 @@ async fn import_child_node<
 ) -> actix_web::Result<Json<()>> {
     debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
     let auth_ctx = data.auth.auth_from_request(&req, &endpoint_params.param_auth)?;
-    let config = match parse_uploaded_file(body).await? {
+    let config = match parse_uploaded_file(&req, body).await? {
         ExportVersionedMatcherConfig::V1(config) | ExportVersionedMatcherConfig::V1_1(config) => {
             config
         }
@@ -312,13 +369,12 @@ async fn edit_draft_tree_node<
 ) -> actix_web::Result<Json<()>> {
     debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
     let auth_ctx = data.auth.auth_from_request(&req, &endpoint_params.param_auth)?;
-    let config = processing_tree_node_details_dto_into_matcher_config(body.into_inner())?;
     data.api
         .edit_draft_config_node(
             auth_ctx,
             &endpoint_params.draft_id,
             &endpoint_params.node_path,
-            config,
+            body.into_inner(),
         )
         .await?;
     Ok(Json(()))
@@ -335,7 +391,7 @@ async fn import_node_in_path<
 ) -> actix_web::Result<Json<()>> {
     debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
     let auth_ctx = data.auth.auth_from_request(&req, &endpoint_params.param_auth)?;
-    let config = match parse_uploaded_file(body).await? {
+    let config = match parse_uploaded_file(&req, body).await? {
         ExportVersionedMatcherConfig::V1(config) | ExportVersionedMatcherConfig::V1_1(config) => {
             config
         }
@@ -381,6 +437,20 @@ async fn get_current_tree_info<
     Ok(Json(result))
 }
 
+async fn get_config_schema<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    param_auth: Path<String>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<HttpResponse> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &param_auth)?;
+    let schema = data.api.get_config_schema(&auth_ctx)?;
+    Ok(HttpResponse::Ok().content_type("application/json").body(schema))
+}
+
 async fn get_current_rule_details<
     A: ConfigApiHandler + 'static,
     CM: MatcherConfigEditor + ?Sized + 'static,
@@ -446,6 +516,33 @@ async fn create_draft_rule_details<
     Ok(Json(()))
 }
 
+async fn test_rule<A: ConfigApiHandler + 'static, CM: MatcherConfigEditor + ?Sized + 'static>(
+    req: HttpRequest,
+    param_auth: Path<String>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+    request: Json<TestRuleRequestDto>,
+) -> actix_web::Result<Json<TestRuleResultDto>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &param_auth)?;
+    let result = data.api.test_rule(auth_ctx, request.0).await?;
+    Ok(Json(result))
+}
+
+async fn preview_filter<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    param_auth: Path<String>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+    request: Json<FilterPreviewRequestDto>,
+) -> actix_web::Result<Json<FilterPreviewResultDto>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &param_auth)?;
+    let result = data.api.preview_filter(auth_ctx, request.0).await?;
+    Ok(Json(result))
+}
+
 async fn edit_draft_rule_details<
     A: ConfigApiHandler + 'static,
     CM: MatcherConfigEditor + ?Sized + 'static,
@@ -586,18 +683,25 @@ async fn export_draft_tree_starting_from_node_path<
     Ok(response)
 }
 
+/// Returns the list of all the drafts if neither the `limit` nor the `offset` query
+///   parameters are present; otherwise, it returns a `Page` of the drafts.
 async fn get_drafts_by_tenant<
     A: ConfigApiHandler + 'static,
     CM: MatcherConfigEditor + ?Sized + 'static,
 >(
     req: HttpRequest,
     param_auth: Path<String>,
+    page: Query<PageParams>,
     data: Data<ApiDataV2<ConfigApi<A, CM>>>,
-) -> actix_web::Result<Json<Vec<String>>> {
+) -> actix_web::Result<HttpResponse> {
     debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
     let auth_ctx = data.auth.auth_from_request(&req, &param_auth)?;
-    let result = data.api.get_drafts_by_tenant(&auth_ctx).await?;
-    Ok(Json(result))
+    let response = match (page.offset, page.limit) {
+        (None, None) => HttpResponse::Ok().json(data.api.get_drafts_by_tenant(&auth_ctx).await?),
+        (offset, limit) => HttpResponse::Ok()
+            .json(data.api.get_drafts_page_by_tenant(&auth_ctx, offset.unwrap_or(0), limit).await?),
+    };
+    Ok(response)
 }
 
 async fn create_draft_in_tenant<
@@ -628,6 +732,21 @@ async fn delete_draft_in_tenant<
     Ok(Json(()))
 }
 
+async fn patch_draft_in_tenant<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    path: Path<DraftPath>,
+    body: Json<Patch>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<Json<MatcherConfig>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &path.param_auth)?;
+    let config = data.api.patch_draft_for_tenant(&auth_ctx, &path.draft_id, &body).await?;
+    Ok(Json(config))
+}
+
 async fn deploy_draft_for_tenant<
     A: ConfigApiHandler + 'static,
     CM: MatcherConfigEditor + ?Sized + 'static,
@@ -642,6 +761,21 @@ async fn deploy_draft_for_tenant<
     Ok(Json(()))
 }
 
+async fn deploy_drafts_batch_for_tenant<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    param_auth: Path<String>,
+    body: Json<Vec<String>>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<Json<()>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &param_auth)?;
+    data.api.deploy_drafts_batch_for_tenant(&auth_ctx, &body.into_inner()).await?;
+    Ok(Json(()))
+}
+
 async fn draft_take_over_for_tenant<
     A: ConfigApiHandler + 'static,
     CM: MatcherConfigEditor + ?Sized + 'static,
@@ -656,6 +790,20 @@ async fn draft_take_over_for_tenant<
     Ok(Json(()))
 }
 
+async fn get_deploy_history<
+    A: ConfigApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    param_auth: Path<String>,
+    data: Data<ApiDataV2<ConfigApi<A, CM>>>,
+) -> actix_web::Result<Json<Vec<MatcherConfigDeployRecordDto>>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    let auth_ctx = data.auth.auth_from_request(&req, &param_auth)?;
+    let result = data.api.get_deploy_history(&auth_ctx).await?;
+    Ok(Json(result))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -671,11 +819,16 @@ mod test {
     use std::collections::HashMap;
     use std::sync::Arc;
     use tornado_engine_api_dto::auth_v2::{AuthHeaderV2, Authorization};
-    use tornado_engine_api_dto::config::ConstraintDto;
-    use tornado_engine_matcher::config::nodes::Filter;
+    use tornado_engine_api_dto::common::Page;
+    use tornado_engine_api_dto::config::{
+        ConstraintDto, ExtractorDto, ExtractorRegexDto, FilterModeDto, OperatorDto,
+    };
+    use tornado_engine_api_dto::event::EventDto;
+    use tornado_engine_matcher::config::nodes::{Filter, FilterMode};
     use tornado_engine_matcher::config::rule::{Constraint, Rule};
     use tornado_engine_matcher::config::{
-        Defaultable, MatcherConfig, MatcherConfigDraft, MatcherConfigDraftData, MatcherConfigReader,
+        Defaultable, MatcherConfig, MatcherConfigDeployRecord, MatcherConfigDraft,
+        MatcherConfigDraftData, MatcherConfigReader,
     };
     use tornado_engine_matcher::error::MatcherError;
 
@@ -690,6 +843,8 @@ mod test {
                     description: "".to_string(),
                     filter: Defaultable::Default {},
                     active: false,
+                    mode: FilterMode::AllMatches,
+                    continue_on_match: true,
                 },
                 nodes: vec![
                     MatcherConfig::Filter {
@@ -698,6 +853,8 @@ mod test {
                             description: "".to_string(),
                             filter: Defaultable::Default {},
                             active: false,
+                            mode: FilterMode::AllMatches,
+                            continue_on_match: true,
                         },
                         nodes: vec![],
                     },
@@ -713,6 +870,7 @@ mod test {
                                 with: Default::default(),
                             },
                             actions: vec![],
+                            deduplication: None,
                         }],
                     },
                 ],
@@ -723,7 +881,13 @@ mod test {
     #[async_trait::async_trait(? Send)]
     impl MatcherConfigEditor for ConfigManager {
         async fn get_drafts(&self) -> Result<Vec<String>, MatcherError> {
-            Ok(vec![])
+            Ok(vec![
+                "draft_4".to_owned(),
+                "draft_2".to_owned(),
+                "draft_5".to_owned(),
+                "draft_1".to_owned(),
+                "draft_3".to_owned(),
+            ])
         }
 
         async fn get_draft(&self, draft_id: &str) -> Result<MatcherConfigDraft, MatcherError> {
@@ -740,6 +904,8 @@ mod test {
                         description: "".to_string(),
                         filter: Defaultable::Default {},
                         active: false,
+                        mode: FilterMode::AllMatches,
+                        continue_on_match: true,
                     },
                     nodes: vec![
                         MatcherConfig::Filter {
@@ -748,6 +914,8 @@ mod test {
                                 description: "".to_string(),
                                 filter: Defaultable::Default {},
                                 active: false,
+                                mode: FilterMode::AllMatches,
+                                continue_on_match: true,
                             },
                             nodes: vec![MatcherConfig::Filter {
                                 name: "child_1_1".to_owned(),
@@ -755,6 +923,8 @@ mod test {
                                     description: "".to_string(),
                                     filter: Defaultable::Default {},
                                     active: false,
+                                    mode: FilterMode::AllMatches,
+                                    continue_on_match: true,
                                 },
                                 nodes: vec![],
                             }],
@@ -771,6 +941,7 @@ mod test {
                                     with: Default::default(),
                                 },
                                 actions: vec![],
+                                deduplication: None,
                             }],
                         },
                     ],
@@ -791,10 +962,30 @@ mod test {
             Ok(())
         }
 
-        async fn deploy_draft(&self, _draft_id: &str) -> Result<MatcherConfig, MatcherError> {
+        async fn deploy_draft(
+            &self,
+            _draft_id: &str,
+            _user: String,
+        ) -> Result<MatcherConfig, MatcherError> {
             Ok(MatcherConfig::Ruleset { name: "ruleset_new".to_owned(), rules: vec![] })
         }
 
+        async fn append_deploy_record(
+            &self,
+            _record: &MatcherConfigDeployRecord,
+        ) -> Result<(), MatcherError> {
+            Ok(())
+        }
+
+        async fn get_deploy_history(&self) -> Result<Vec<MatcherConfigDeployRecord>, MatcherError> {
+            Ok(vec![MatcherConfigDeployRecord {
+                user: "user".to_owned(),
+                draft_id: "draft123".to_owned(),
+                config_hash: "hash".to_owned(),
+                deployed_ts_ms: 0,
+            }])
+        }
+
         async fn delete_draft(&self, _draft_id: &str) -> Result<(), MatcherError> {
             Ok(())
         }
@@ -809,9 +1000,9 @@ mod test {
 
         async fn deploy_config(
             &self,
-            _config: &MatcherConfig,
+            config: &MatcherConfig,
         ) -> Result<MatcherConfig, MatcherError> {
-            unimplemented!()
+            Ok(config.clone())
         }
     }
 
@@ -868,6 +1059,114 @@ mod test {
         Ok(())
     }
 
+    #[actix_rt::test]
+    async fn v2_endpoint_should_return_all_the_drafts_if_no_page_is_requested(
+    ) -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/drafts/auth1")
+            .to_request();
+
+        let drafts: Vec<String> = test::call_and_read_body_json(&srv, request).await;
+
+        // Assert
+        assert_eq!(5, drafts.len());
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_return_the_first_page_of_the_drafts() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/drafts/auth1?limit=2")
+            .to_request();
+
+        let page: Page<String> = test::call_and_read_body_json(&srv, request).await;
+
+        // Assert
+        assert_eq!(
+            Page { items: vec!["draft_1".to_owned(), "draft_2".to_owned()], total: 5 },
+            page
+        );
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_return_a_subsequent_page_of_the_drafts() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let second_page: Page<String> = test::call_and_read_body_json(
+            &srv,
+            test::TestRequest::get()
+                .insert_header(test_auth_root_edit())
+                .uri("/config/drafts/auth1?offset=2&limit=2")
+                .to_request(),
+        )
+        .await;
+        let last_page: Page<String> = test::call_and_read_body_json(
+            &srv,
+            test::TestRequest::get()
+                .insert_header(test_auth_root_edit())
+                .uri("/config/drafts/auth1?offset=4&limit=2")
+                .to_request(),
+        )
+        .await;
+
+        // Assert
+        assert_eq!(vec!["draft_3".to_owned(), "draft_4".to_owned()], second_page.items);
+        assert_eq!(vec!["draft_5".to_owned()], last_page.items);
+        assert_eq!(5, second_page.total);
+        assert_eq!(5, last_page.total);
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_have_a_get_deploy_history_get_endpoint() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/deploy-history/auth1")
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        let history: Vec<MatcherConfigDeployRecordDto> = test::read_body_json(response).await;
+        assert_eq!(1, history.len());
+        assert_eq!("draft123", history[0].draft_id);
+        Ok(())
+    }
+
     #[actix_rt::test]
     async fn v2_endpoint_should_have_a_get_draft_single_node_get_endpoint() -> Result<(), ApiError>
     {
@@ -1012,6 +1311,173 @@ mod test {
         Ok(())
     }
 
+    #[actix_rt::test]
+    async fn v2_endpoint_should_have_a_deploy_batch_post_endpoint() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::post()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/deploy-batch/auth1")
+            .set_json(&vec!["draft123"])
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_return_the_failures_of_the_drafts_of_the_batch(
+    ) -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::post()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/deploy-batch/auth1")
+            .set_json(&vec!["draft_1", "draft_2"])
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, response.status());
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!("VALIDATION_ERROR", body["code"]);
+        assert_eq!(vec!["draft_2"], body["params"].as_object().unwrap().keys().collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_apply_a_json_patch_to_a_draft() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::patch()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/drafts/auth1/draft123")
+            .set_json(&serde_json::json!([
+                { "op": "replace", "path": "/Filter/nodes/0/Filter/name", "value": "patched" }
+            ]))
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!("patched", body["Filter"]["nodes"][0]["Filter"]["name"]);
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_reject_a_json_patch_that_produces_an_invalid_draft(
+    ) -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::patch()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/drafts/auth1/draft123")
+            .set_json(&serde_json::json!([
+                { "op": "replace", "path": "/Filter/name", "value": "not valid name" }
+            ]))
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, response.status());
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!("VALIDATION_ERROR", body["code"]);
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_reject_a_body_bigger_than_the_max_payload_size(
+    ) -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(
+            build_config_v2_endpoints_with_max_payload_size(
+                ApiDataV2 {
+                    auth: test_auth_service_v2(),
+                    api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+                },
+                Some(1024),
+            ),
+        ))
+        .await;
+
+        // Act
+        let request = test::TestRequest::patch()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/drafts/auth1/draft123")
+            .set_json(&serde_json::json!([
+                { "op": "replace", "path": "/Filter/description", "value": "a".repeat(2048) }
+            ]))
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status());
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_should_accept_a_body_smaller_than_the_max_payload_size(
+    ) -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(
+            build_config_v2_endpoints_with_max_payload_size(
+                ApiDataV2 {
+                    auth: test_auth_service_v2(),
+                    api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+                },
+                Some(1024),
+            ),
+        ))
+        .await;
+
+        // Act
+        let request = test::TestRequest::patch()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/drafts/auth1/draft123")
+            .set_json(&serde_json::json!([
+                { "op": "replace", "path": "/Filter/nodes/0/Filter/name", "value": "patched" }
+            ]))
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        Ok(())
+    }
+
     #[actix_rt::test]
     async fn v2_endpoint_should_have_a_draft_take_over_for_tenant_post_endpoint(
     ) -> Result<(), ApiError> {
@@ -1285,6 +1751,31 @@ mod test {
         Ok(())
     }
 
+    #[actix_rt::test]
+    async fn v2_endpoint_get_config_schema_should_return_the_json_schema() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::get()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/schema/auth1")
+            .to_request();
+
+        let schema: serde_json::Value = test::call_and_read_body_json(&srv, request).await;
+
+        // Assert
+        let schema = schema.as_object().expect("the schema should be a JSON object");
+        assert!(schema.contains_key("$schema"));
+        assert!(schema.contains_key("definitions"));
+        assert_eq!(Some("MatcherConfig"), schema.get("title").and_then(|title| title.as_str()));
+        Ok(())
+    }
+
     #[actix_rt::test]
     async fn v2_endpoint_create_node_in_draft_by_path_should_return_ok() -> Result<(), ApiError> {
         // Arrange
@@ -1303,6 +1794,8 @@ mod test {
                 description: "".to_string(),
                 active: false,
                 filter: None,
+                mode: FilterModeDto::AllMatches,
+                continue_on_match: true,
             })
             .to_request();
 
@@ -1333,6 +1826,7 @@ mod test {
                 active: true,
                 constraint: ConstraintDto { where_operator: None, with: Default::default() },
                 actions: vec![],
+                deduplication: None,
             })
             .to_request();
 
@@ -1363,6 +1857,7 @@ mod test {
                 active: true,
                 constraint: ConstraintDto { where_operator: None, with: Default::default() },
                 actions: vec![],
+                deduplication: None,
             })
             .to_request();
 
@@ -1460,6 +1955,8 @@ mod test {
                 description: "".to_string(),
                 active: false,
                 filter: None,
+                mode: FilterModeDto::AllMatches,
+                continue_on_match: true,
             })
             .to_request();
 
@@ -1491,4 +1988,182 @@ mod test {
         assert_eq!(StatusCode::OK, response.status());
         Ok(())
     }
+
+    fn test_rule_request(regex: &str) -> TestRuleRequestDto {
+        let mut with = HashMap::new();
+        with.insert(
+            "host".to_owned(),
+            ExtractorDto {
+                from: "${event.payload.subject}".to_owned(),
+                regex: ExtractorRegexDto::Regex {
+                    regex: regex.to_owned(),
+                    group_match_idx: Some(1),
+                    all_matches: None,
+                    empty_if_no_match: None,
+                },
+                modifiers_post: vec![],
+            },
+        );
+        let mut payload = HashMap::new();
+        payload.insert("subject".to_owned(), json!("Disk full on host_01"));
+
+        TestRuleRequestDto {
+            rule: RuleDto {
+                name: "extract_host".to_string(),
+                description: "".to_string(),
+                do_continue: true,
+                active: true,
+                constraint: ConstraintDto {
+                    where_operator: Some(OperatorDto::Equals {
+                        first: json!("${event.type}"),
+                        second: json!("email"),
+                    }),
+                    with,
+                },
+                actions: vec![],
+                deduplication: None,
+            },
+            event: EventDto {
+                event_type: "email".to_owned(),
+                created_ms: 0,
+                metadata: HashMap::new(),
+                payload,
+            },
+        }
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_test_rule_should_return_the_extracted_variables() -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::post()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/test-rule/auth1")
+            .set_json(&test_rule_request("on (host_[0-9]+)"))
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        let result: TestRuleResultDto = test::read_body_json(response).await;
+        assert!(result.matched);
+        assert_eq!(json!({ "host": "host_01" }), result.extracted_vars);
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_test_rule_should_return_unprocessable_entity_for_an_invalid_rule(
+    ) -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::post()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/test-rule/auth1")
+            .set_json(&test_rule_request("on (host_[0-9]+"))
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, response.status());
+        Ok(())
+    }
+
+    fn filter_preview_request(filter_path: &str) -> FilterPreviewRequestDto {
+        let event = |event_type: &str| EventDto {
+            event_type: event_type.to_owned(),
+            created_ms: 0,
+            metadata: HashMap::new(),
+            payload: HashMap::new(),
+        };
+        FilterPreviewRequestDto {
+            filter_path: filter_path.to_owned(),
+            filter: ProcessingTreeNodeEditDto::Filter {
+                name: "root".to_owned(),
+                description: "".to_owned(),
+                active: true,
+                filter: Some(OperatorDto::Equals {
+                    first: json!("${event.type}"),
+                    second: json!("email"),
+                }),
+                mode: None,
+                continue_on_match: None,
+            },
+            events: vec![event("email"), event("sms")],
+        }
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_filter_preview_should_return_the_rerouted_events() -> Result<(), ApiError>
+    {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::post()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/filter-preview/auth1")
+            .set_json(&filter_preview_request("root"))
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::OK, response.status());
+        let result: FilterPreviewResultDto = test::read_body_json(response).await;
+        assert_eq!(2, result.events.len());
+
+        // The current root filter is inactive, the proposed one routes only the emails
+        let email = &result.events[0];
+        assert!(email.current.is_empty());
+        assert_eq!(vec!["root,child_2".to_owned()], email.proposed);
+        assert!(email.changed);
+
+        let sms = &result.events[1];
+        assert!(sms.current.is_empty());
+        assert!(sms.proposed.is_empty());
+        assert!(!sms.changed);
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    async fn v2_endpoint_filter_preview_should_return_bad_request_if_the_node_is_not_a_filter(
+    ) -> Result<(), ApiError> {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_config_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: ConfigApi::new(TestApiHandler {}, Arc::new(ConfigManager {})),
+        })))
+        .await;
+
+        // Act
+        let request = test::TestRequest::post()
+            .insert_header(test_auth_root_edit())
+            .uri("/config/filter-preview/auth1")
+            .set_json(&filter_preview_request("root,child_2"))
+            .to_request();
+
+        let response = test::call_service(&srv, request).await;
+
+        // Assert
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        Ok(())
+    }
 }
@@ -1,6 +1,6 @@
 use actix::MailboxError;
 use actix_web::{http, HttpResponse, HttpResponseBuilder};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use thiserror::Error;
 use tornado_engine_api_dto::common::WebError;
 use tornado_engine_matcher::error::MatcherError;
@@ -20,6 +20,8 @@ pub enum ApiError {
     InternalServerError { cause: String },
     #[error("PayloadToLarge")]
     PayloadToLarge,
+    #[error("TooManyRequestsError: [{cause}]")]
+    TooManyRequestsError { cause: String },
 
     #[error("MissingAuthTokenError")]
     MissingAuthTokenError,
@@ -41,6 +43,15 @@ pub enum ApiError {
 
     #[error("NodeNotFoundError [{message}]")]
     NodeNotFoundError { message: String },
+
+    #[error("InvalidRuleError [{message}]")]
+    InvalidRuleError { message: String },
+
+    #[error("InvalidConfigError [{message}]")]
+    InvalidConfigError { message: String },
+
+    #[error("InvalidDraftsError [{failures:?}]")]
+    InvalidDraftsError { failures: BTreeMap<String, String> },
 }
 
 impl From<MatcherError> for ApiError {
@@ -99,7 +110,22 @@ impl actix_web::error::ResponseError for ApiError {
             | ApiError::InternalServerError { .. } => HttpResponse::InternalServerError().finish(),
             ApiError::BadRequestError { .. } => HttpResponse::BadRequest().finish(),
             ApiError::PayloadToLarge => HttpResponse::PayloadTooLarge().finish(),
+            ApiError::TooManyRequestsError { .. } => HttpResponse::TooManyRequests().finish(),
             ApiError::NodeNotFoundError { .. } => HttpResponse::NotFound().finish(),
+            ApiError::InvalidRuleError { message } | ApiError::InvalidConfigError { message } => {
+                HttpResponseBuilder::new(http::StatusCode::UNPROCESSABLE_ENTITY).json(WebError {
+                    code: VALIDATION_ERROR.to_owned(),
+                    message: Some(message.to_owned()),
+                    params: HashMap::new(),
+                })
+            }
+            ApiError::InvalidDraftsError { failures } => {
+                HttpResponseBuilder::new(http::StatusCode::UNPROCESSABLE_ENTITY).json(WebError {
+                    code: VALIDATION_ERROR.to_owned(),
+                    message: Some(format!("{}", self)),
+                    params: failures.clone().into_iter().collect(),
+                })
+            }
             ApiError::InvalidTokenError { .. }
             | ApiError::ExpiredTokenError { .. }
             | ApiError::MissingAuthTokenError { .. }
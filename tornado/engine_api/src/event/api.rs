@@ -52,10 +52,10 @@ pub mod test {
     use async_trait::async_trait;
     use std::collections::HashMap;
     use tornado_common_api::{Map, Value};
-    use tornado_engine_matcher::config::nodes::Filter;
+    use tornado_engine_matcher::config::nodes::{Filter, FilterMode};
     use tornado_engine_matcher::config::{
-        Defaultable, MatcherConfigDraft, MatcherConfigDraftData, MatcherConfigEditor,
-        MatcherConfigReader,
+        Defaultable, MatcherConfigDeployRecord, MatcherConfigDraft, MatcherConfigDraftData,
+        MatcherConfigEditor, MatcherConfigReader,
     };
     use tornado_engine_matcher::error::MatcherError;
     use tornado_engine_matcher::model::{ProcessedNode, ProcessedRules};
@@ -112,6 +112,8 @@ pub mod test {
                     description: "".to_string(),
                     active: true,
                     filter: Defaultable::Default {},
+                    mode: FilterMode::AllMatches,
+                    continue_on_match: true,
                 },
                 nodes: vec![MatcherConfig::Ruleset { name: "ruleset".to_owned(), rules: vec![] }],
             })
@@ -138,6 +140,8 @@ pub mod test {
                         description: "".to_string(),
                         active: true,
                         filter: Defaultable::Default {},
+                        mode: FilterMode::AllMatches,
+                        continue_on_match: true,
                     },
                     nodes: vec![MatcherConfig::Ruleset {
                         name: "ruleset".to_owned(),
@@ -160,10 +164,25 @@ pub mod test {
             Ok(())
         }
 
-        async fn deploy_draft(&self, _draft_id: &str) -> Result<MatcherConfig, MatcherError> {
+        async fn deploy_draft(
+            &self,
+            _draft_id: &str,
+            _user: String,
+        ) -> Result<MatcherConfig, MatcherError> {
             Ok(MatcherConfig::Ruleset { name: "ruleset_new".to_owned(), rules: vec![] })
         }
 
+        async fn append_deploy_record(
+            &self,
+            _record: &MatcherConfigDeployRecord,
+        ) -> Result<(), MatcherError> {
+            Ok(())
+        }
+
+        async fn get_deploy_history(&self) -> Result<Vec<MatcherConfigDeployRecord>, MatcherError> {
+            unimplemented!()
+        }
+
         async fn delete_draft(&self, _draft_id: &str) -> Result<(), MatcherError> {
             Ok(())
         }
@@ -31,7 +31,7 @@ impl<A: EventApiHandler, CM: MatcherConfigEditor + ?Sized> EventApiV2<A, CM> {
             }
             ProcessType::SkipActions => {}
         };
-        let config_filter = NodeFilter::map_from(&[auth.auth.authorization.path.clone()]);
+        let config_filter = NodeFilter::map_from(&auth.authorized_paths());
 
         self.handler.send_event_to_current_config(config_filter, event).await
     }
@@ -50,7 +50,7 @@ impl<A: EventApiHandler, CM: MatcherConfigEditor + ?Sized> EventApiV2<A, CM> {
             }
             ProcessType::SkipActions => {}
         };
-        let config_filter = NodeFilter::map_from(&[auth.auth.authorization.path.clone()]);
+        let config_filter = NodeFilter::map_from(&auth.authorized_paths());
 
         let draft = self.config_manager.get_draft(draft_id).await?;
         auth.is_owner(&draft)?;
@@ -3,13 +3,13 @@ use serde_json::Error;
 use tornado_common_api::Action;
 use tornado_engine_api_dto::config::ActionDto;
 use tornado_engine_api_dto::event::{
-    ProcessType, ProcessedEventDto, ProcessedFilterDto, ProcessedFilterStatusDto,
-    ProcessedIteratorDto, ProcessedNodeDto, ProcessedRuleDto, ProcessedRuleStatusDto,
-    ProcessedRulesDto, SendEventRequestDto,
+    MatchingRuleDto, MatchingRulesDto, OperatorExplanationDto, ProcessType, ProcessedEventDto,
+    ProcessedFilterDto, ProcessedFilterStatusDto, ProcessedIteratorDto, ProcessedNodeDto,
+    ProcessedRuleDto, ProcessedRuleStatusDto, ProcessedRulesDto, SendEventRequestDto,
 };
 use tornado_engine_matcher::model::{
-    ProcessedEvent, ProcessedFilter, ProcessedFilterStatus, ProcessedIterator, ProcessedNode,
-    ProcessedRule, ProcessedRuleStatus, ProcessedRules,
+    OperatorExplanation, ProcessedEvent, ProcessedFilter, ProcessedFilterStatus, ProcessedIterator,
+    ProcessedNode, ProcessedRule, ProcessedRuleStatus, ProcessedRules,
 };
 
 pub fn dto_into_send_event_request(dto: SendEventRequestDto) -> Result<SendEventRequest, Error> {
@@ -85,11 +85,28 @@ pub fn processed_rule_status_into_dto(node: ProcessedRuleStatus) -> ProcessedRul
 }
 
 pub fn action_into_dto(action: Action) -> Result<ActionDto, Error> {
-    Ok(ActionDto { id: action.id, payload: serde_json::to_value(action.payload)? })
+    Ok(ActionDto {
+        id: action.id,
+        payload: serde_json::to_value(action.payload)?,
+        when: None,
+        retry_strategy: action.retry_strategy.map(serde_json::to_value).transpose()?,
+    })
 }
 
 pub fn processed_filter_into_dto(node: ProcessedFilter) -> ProcessedFilterDto {
-    ProcessedFilterDto { status: processed_filter_status_into_dto(node.status) }
+    ProcessedFilterDto {
+        status: processed_filter_status_into_dto(node.status),
+        explanation: node.explanation.map(operator_explanation_into_dto),
+    }
+}
+
+pub fn operator_explanation_into_dto(explanation: OperatorExplanation) -> OperatorExplanationDto {
+    OperatorExplanationDto {
+        operator: explanation.operator,
+        result: explanation.result,
+        values: explanation.values,
+        children: explanation.children.into_iter().map(operator_explanation_into_dto).collect(),
+    }
 }
 
 pub fn processed_iterator_into_dto(node: ProcessedIterator) -> ProcessedIteratorDto {
@@ -107,3 +124,98 @@ pub fn processed_filter_status_into_dto(node: ProcessedFilterStatus) -> Processe
         ProcessedFilterStatus::Inactive => ProcessedFilterStatusDto::Inactive,
     }
 }
+
+/// Returns the rules matched by the processed event, in the order in which they were processed.
+pub fn processed_event_into_matching_rules_dto(
+    processed_event: &ProcessedEvent,
+) -> MatchingRulesDto {
+    let mut rules = vec![];
+    collect_matching_rules(&processed_event.result, &mut vec![], &mut rules);
+    MatchingRulesDto { rules }
+}
+
+fn collect_matching_rules(
+    node: &ProcessedNode,
+    path: &mut Vec<String>,
+    matching_rules: &mut Vec<MatchingRuleDto>,
+) {
+    match node {
+        ProcessedNode::Filter { name, nodes, .. } => {
+            path.push(name.clone());
+            for node in nodes {
+                collect_matching_rules(node, path, matching_rules);
+            }
+            path.pop();
+        }
+        ProcessedNode::Iterator { name, events, .. } => {
+            path.push(name.clone());
+            for node in events.iter().flat_map(|iteration| iteration.result.iter()) {
+                collect_matching_rules(node, path, matching_rules);
+            }
+            path.pop();
+        }
+        ProcessedNode::Ruleset { name, rules } => {
+            path.push(name.clone());
+            for rule in
+                rules.rules.iter().filter(|rule| rule.status == ProcessedRuleStatus::Matched)
+            {
+                // The same rule can be matched by more iterations of an Iterator
+                let already_matched = matching_rules.iter().any(|matching_rule| {
+                    matching_rule.name == rule.name && &matching_rule.path == path
+                });
+                if !already_matched {
+                    matching_rules
+                        .push(MatchingRuleDto { name: rule.name.clone(), path: path.clone() });
+                }
+            }
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_convert_the_explanation_of_a_processed_filter() {
+        // Arrange
+        let filter = ProcessedFilter {
+            status: ProcessedFilterStatus::NotMatched,
+            explanation: Some(OperatorExplanation::new("and", false).with_children(vec![
+                OperatorExplanation::new("equals", true)
+                    .with_values(vec![Some(json!("email")), Some(json!("email"))]),
+                OperatorExplanation::new("equals", false).with_values(vec![None, Some(json!(1))]),
+            ])),
+        };
+
+        // Act
+        let dto = processed_filter_into_dto(filter);
+
+        // Assert
+        let explanation = dto.explanation.unwrap();
+        assert_eq!("and", explanation.operator);
+        assert!(!explanation.result);
+        assert_eq!(2, explanation.children.len());
+        assert_eq!(
+            vec![Some(json!("email")), Some(json!("email"))],
+            explanation.children[0].values
+        );
+        assert!(!explanation.children[1].result);
+        assert_eq!(vec![None, Some(json!(1))], explanation.children[1].values);
+    }
+
+    #[test]
+    fn should_convert_a_processed_filter_without_explanation() {
+        // Arrange
+        let filter = ProcessedFilter { status: ProcessedFilterStatus::Matched, explanation: None };
+
+        // Act
+        let dto = processed_filter_into_dto(filter);
+
+        // Assert
+        assert!(matches!(dto.status, ProcessedFilterStatusDto::Matched));
+        assert!(dto.explanation.is_none());
+    }
+}
@@ -2,14 +2,18 @@ use crate::auth::auth_v2::{AuthContextV2, AuthServiceV2};
 use crate::error::ApiError;
 use crate::event::api::{EventApiHandler, SendEventRequest};
 use crate::event::api_v2::EventApiV2;
-use crate::event::convert::{dto_into_send_event_request, processed_event_into_dto};
+use crate::event::convert::{
+    dto_into_send_event_request, processed_event_into_dto, processed_event_into_matching_rules_dto,
+};
 use crate::model::ApiDataV2;
 use actix_web::web::{Data, Json, Path};
 use actix_web::{web, HttpRequest, Scope};
 use log::*;
 use serde::Deserialize;
 use std::ops::Deref;
-use tornado_engine_api_dto::event::{ProcessedEventDto, SendEventRequestDto};
+use tornado_engine_api_dto::event::{
+    EventDto, MatchingRulesDto, ProcessType, ProcessedEventDto, SendEventRequestDto,
+};
 use tornado_engine_matcher::config::MatcherConfigEditor;
 
 pub fn build_event_v2_endpoints<
@@ -18,8 +22,27 @@ pub fn build_event_v2_endpoints<
 >(
     data: ApiDataV2<EventApiV2<T, CM>>,
 ) -> Scope {
-    web::scope("/event")
-        .app_data(Data::new(data))
+    build_event_v2_endpoints_with_max_payload_size(data, None)
+}
+
+/// Builds the event endpoints rejecting with 413 Payload Too Large the JSON bodies
+/// bigger than `max_payload_size` bytes.
+/// If `max_payload_size` is None, the limits of the enclosing scope are used.
+pub fn build_event_v2_endpoints_with_max_payload_size<
+    T: EventApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    data: ApiDataV2<EventApiV2<T, CM>>,
+    max_payload_size: Option<usize>,
+) -> Scope {
+    let scope = web::scope("/event").app_data(Data::new(data));
+    let scope = match max_payload_size {
+        Some(max_payload_size) => {
+            scope.app_data(web::JsonConfig::default().limit(max_payload_size))
+        }
+        None => scope,
+    };
+    scope
         .service(
             web::resource("/active/{param_auth}")
                 .route(web::post().to(send_event_to_current_config_v2::<T, CM>)),
@@ -28,6 +51,10 @@ pub fn build_event_v2_endpoints<
             web::resource("/drafts/{param_auth}/{draft_id}")
                 .route(web::post().to(send_event_to_draft_v2::<T, CM>)),
         )
+        .service(
+            web::resource("/matching-rules/{param_auth}")
+                .route(web::post().to(get_matching_rules_v2::<T, CM>)),
+        )
 }
 
 #[derive(Deserialize)]
@@ -98,6 +125,35 @@ async fn send_event_to_draft_v2<
     Ok(Json(processed_event_into_dto(processed_event)?))
 }
 
+/// Returns the rules of the current configuration matched by the event.
+/// The actions of the matched rules are not executed.
+async fn get_matching_rules_v2<
+    T: EventApiHandler + 'static,
+    CM: MatcherConfigEditor + ?Sized + 'static,
+>(
+    req: HttpRequest,
+    data: Data<ApiDataV2<EventApiV2<T, CM>>>,
+    params: Path<EndpointParamAuthPath>,
+    body: Json<EventDto>,
+) -> actix_web::Result<Json<MatchingRulesDto>> {
+    debug!("HttpRequest method [{}] path [{}]", req.method(), req.path());
+    if log_enabled!(Level::Debug) {
+        let json_string = serde_json::to_string(body.deref()).unwrap();
+        debug!("API - received get_matching_rules_v2 request: {}", json_string);
+    }
+
+    let body = Json(SendEventRequestDto {
+        process_type: ProcessType::SkipActions,
+        event: body.into_inner(),
+    });
+    let (auth_ctx, send_event_request) =
+        prepare_data_for_send_event_v2(&req, &data.auth, &params.param_auth, body)?;
+
+    let processed_event =
+        data.api.send_event_to_current_config(auth_ctx, send_event_request).await?;
+    Ok(Json(processed_event_into_matching_rules_dto(&processed_event)))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -108,6 +164,12 @@ mod test {
     use std::sync::Arc;
     use tornado_engine_api_dto::auth_v2::{AuthHeaderV2, Authorization};
     use tornado_engine_api_dto::event::{EventDto, ProcessType, SendEventRequestDto};
+    use tornado_engine_matcher::config::operation::NodeFilter;
+    use tornado_engine_matcher::config::MatcherConfig;
+    use tornado_engine_matcher::model::{
+        ProcessedEvent, ProcessedFilter, ProcessedFilterStatus, ProcessedNode, ProcessedRule,
+        ProcessedRuleStatus, ProcessedRules,
+    };
 
     fn get_something() -> HashMap<String, serde_json::Value> {
         let mut something = HashMap::new();
@@ -174,6 +236,83 @@ mod test {
         assert_eq!(metadata, dto.event.metadata);
     }
 
+    fn send_event_request_with_payload_of_size(size: usize) -> test::TestRequest {
+        let mut payload = HashMap::new();
+        payload.insert("content".to_owned(), serde_json::Value::String("a".repeat(size)));
+        let send_event_request = SendEventRequestDto {
+            event: EventDto {
+                event_type: "my_test_event".to_owned(),
+                payload,
+                metadata: Default::default(),
+                created_ms: 0,
+            },
+            process_type: ProcessType::SkipActions,
+        };
+
+        let mut auths = HashMap::new();
+        auths.insert(
+            "auth1".to_owned(),
+            Authorization { path: vec!["root".to_owned()], roles: vec!["view".to_owned()] },
+        );
+        test::TestRequest::post()
+            .uri("/event/active/auth1")
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .insert_header((
+                header::AUTHORIZATION,
+                AuthServiceV2::auth_to_token_header(&AuthHeaderV2 {
+                    user: "admin".to_string(),
+                    auths,
+                    preferences: None,
+                })
+                .unwrap(),
+            ))
+            .set_payload(serde_json::to_string(&send_event_request).unwrap())
+    }
+
+    #[actix_rt::test]
+    async fn send_event_v2_should_return_payload_too_large_if_the_body_exceeds_the_max_size() {
+        // Arrange
+        let srv =
+            test::init_service(App::new().service(build_event_v2_endpoints_with_max_payload_size(
+                ApiDataV2 {
+                    auth: test_auth_service_v2(),
+                    api: EventApiV2::new(TestApiHandler {}, Arc::new(TestConfigManager {})),
+                },
+                Some(1024),
+            )))
+            .await;
+
+        // Act
+        let resp =
+            test::call_service(&srv, send_event_request_with_payload_of_size(2048).to_request())
+                .await;
+
+        // Assert
+        assert_eq!(413, resp.status());
+    }
+
+    #[actix_rt::test]
+    async fn send_event_v2_should_accept_a_body_smaller_than_the_max_size() {
+        // Arrange
+        let srv =
+            test::init_service(App::new().service(build_event_v2_endpoints_with_max_payload_size(
+                ApiDataV2 {
+                    auth: test_auth_service_v2(),
+                    api: EventApiV2::new(TestApiHandler {}, Arc::new(TestConfigManager {})),
+                },
+                Some(1024),
+            )))
+            .await;
+
+        // Act
+        let resp =
+            test::call_service(&srv, send_event_request_with_payload_of_size(16).to_request())
+                .await;
+
+        // Assert
+        assert_eq!(200, resp.status());
+    }
+
     #[actix_rt::test]
     async fn send_event_to_current_config_v2_should_return_unauthorized_if_path_not_in_auths() {
         // Arrange
@@ -272,4 +411,100 @@ mod test {
         assert_eq!("my_test_event_for_draft", dto.event.event_type);
         assert_eq!(metadata, dto.event.metadata);
     }
+
+    struct MatchingRulesApiHandler {}
+
+    #[async_trait(?Send)]
+    impl EventApiHandler for MatchingRulesApiHandler {
+        async fn send_event_to_current_config(
+            &self,
+            _config_filter: HashMap<String, NodeFilter>,
+            event: SendEventRequest,
+        ) -> Result<ProcessedEvent, ApiError> {
+            assert_eq!(crate::event::api::ProcessType::SkipActions, event.process_type);
+
+            let mut first_rule = ProcessedRule::new("first_rule".to_owned());
+            first_rule.status = ProcessedRuleStatus::Matched;
+            let mut second_rule = ProcessedRule::new("second_rule".to_owned());
+            second_rule.status = ProcessedRuleStatus::NotMatched;
+            let mut third_rule = ProcessedRule::new("third_rule".to_owned());
+            third_rule.status = ProcessedRuleStatus::Matched;
+
+            Ok(ProcessedEvent {
+                event: event.to_event_with_metadata(),
+                result: ProcessedNode::Filter {
+                    name: "root".to_owned(),
+                    filter: ProcessedFilter {
+                        status: ProcessedFilterStatus::Matched,
+                        explanation: None,
+                    },
+                    nodes: vec![ProcessedNode::Ruleset {
+                        name: "ruleset".to_owned(),
+                        rules: ProcessedRules {
+                            rules: vec![first_rule, second_rule, third_rule],
+                            extracted_vars: serde_json::Value::Object(Default::default()),
+                        },
+                    }],
+                },
+            })
+        }
+
+        async fn send_event_to_config(
+            &self,
+            _event: SendEventRequest,
+            _config: MatcherConfig,
+        ) -> Result<ProcessedEvent, ApiError> {
+            unimplemented!()
+        }
+    }
+
+    #[actix_rt::test]
+    async fn should_return_the_matching_rules_in_order() {
+        // Arrange
+        let srv = test::init_service(App::new().service(build_event_v2_endpoints(ApiDataV2 {
+            auth: test_auth_service_v2(),
+            api: EventApiV2::new(MatchingRulesApiHandler {}, Arc::new(TestConfigManager {})),
+        })))
+        .await;
+
+        let event = EventDto {
+            event_type: "my_test_event".to_owned(),
+            payload: HashMap::new(),
+            metadata: HashMap::new(),
+            created_ms: 0,
+        };
+
+        // Act
+        let mut auths = HashMap::new();
+        auths.insert(
+            "auth1".to_owned(),
+            Authorization { path: vec!["root".to_owned()], roles: vec!["view".to_owned()] },
+        );
+        let request = test::TestRequest::post()
+            .uri("/event/matching-rules/auth1")
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .insert_header((
+                header::AUTHORIZATION,
+                AuthServiceV2::auth_to_token_header(&AuthHeaderV2 {
+                    user: "admin".to_string(),
+                    auths,
+                    preferences: None,
+                })
+                .unwrap(),
+            ))
+            .set_payload(serde_json::to_string(&event).unwrap())
+            .to_request();
+
+        // Assert
+        let resp = test::call_service(&srv, request).await;
+        assert_eq!(200, resp.status());
+
+        let dto: MatchingRulesDto = test::read_body_json(resp).await;
+
+        let rule_names: Vec<&str> = dto.rules.iter().map(|rule| rule.name.as_str()).collect();
+        assert_eq!(vec!["first_rule", "third_rule"], rule_names);
+        for rule in &dto.rules {
+            assert_eq!(vec!["root".to_owned(), "ruleset".to_owned()], rule.path);
+        }
+    }
 }
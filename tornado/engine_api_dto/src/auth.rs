@@ -20,6 +20,7 @@ pub enum PermissionDto {
     RuntimeConfigEdit,
     RuntimeConfigView,
     TestEventExecuteActions,
+    ActionsReplay,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
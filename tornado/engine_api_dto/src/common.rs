@@ -7,6 +7,13 @@ pub struct Id<T> {
     pub id: T,
 }
 
+/// A page of a list of items, together with the total number of items of the list.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+}
+
 #[derive(Serialize, TypeScriptify)]
 pub struct WebError {
     pub code: String,
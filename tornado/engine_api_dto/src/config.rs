@@ -1,9 +1,10 @@
+use crate::event::EventDto;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::iter::Sum;
 use std::ops::Add;
-use tornado_engine_matcher::config::nodes::Filter;
+use tornado_engine_matcher::config::nodes::{Filter, FilterMode};
 use tornado_engine_matcher::config::rule::{Operator, Rule};
 use tornado_engine_matcher::config::{Defaultable, MatcherConfig};
 use typescript_definitions::TypeScriptify;
@@ -41,6 +42,22 @@ pub struct RuleDto {
     pub active: bool,
     pub constraint: ConstraintDto,
     pub actions: Vec<ActionDto>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deduplication: Option<DeduplicationDto>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct DeduplicationDto {
+    pub key: DedupKeyDto,
+    pub ttl_secs: u64,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+#[serde(untagged)]
+pub enum DedupKeyDto {
+    Template(String),
+    Fields { fields: Vec<String>, separator: String },
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
@@ -48,6 +65,45 @@ pub struct RulePositionDto {
     pub position: usize,
 }
 
+/// A single rule to be evaluated against a sample event
+#[derive(Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct TestRuleRequestDto {
+    pub rule: RuleDto,
+    pub event: EventDto,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct TestRuleResultDto {
+    pub matched: bool,
+    pub extracted_vars: Value,
+    pub message: Option<String>,
+}
+
+/// A proposed change of a filter and the sample events used to preview its effect
+#[derive(Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct FilterPreviewRequestDto {
+    /// The path of the filter, starting from the authorized node,
+    /// with the node names separated by commas
+    pub filter_path: String,
+    pub filter: ProcessingTreeNodeEditDto,
+    pub events: Vec<EventDto>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct FilterPreviewResultDto {
+    /// The routes of the sample events, in the same order as in the request
+    pub events: Vec<FilterPreviewEventDto>,
+}
+
+/// The paths of the rulesets reached by a sample event, starting from the authorized node,
+/// with the current filter and with the proposed one
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct FilterPreviewEventDto {
+    pub current: Vec<String>,
+    pub proposed: Vec<String>,
+    pub changed: bool,
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
 pub struct ConstraintDto {
     #[serde(rename = "WHERE")]
@@ -80,6 +136,7 @@ pub enum ModifierDto {
     },
     ToNumber {},
     Trim {},
+    Uppercase {},
     DateAndTime {
         timezone: String,
     },
@@ -93,6 +150,7 @@ pub enum ExtractorRegexDto {
         regex: String,
         group_match_idx: Option<usize>,
         all_matches: Option<bool>,
+        empty_if_no_match: Option<bool>,
     },
     RegexNamedGroups {
         #[serde(rename = "named_match")]
@@ -134,6 +192,18 @@ pub enum OperatorDto {
     NotEquals { first: Value, second: Value },
     #[serde(rename = "regex")]
     Regex { regex: String, target: String },
+    #[serde(rename = "similar")]
+    Similar { first: Value, second: Value, threshold: f64 },
+    #[serde(rename = "changed")]
+    Changed { entity: Value, target: Value },
+    #[serde(rename = "isType")]
+    IsType { target: Value, expected_type: String },
+    #[serde(rename = "custom")]
+    Custom {
+        name: String,
+        #[serde(default)]
+        params: Value,
+    },
 }
 
 impl From<&Operator> for OperatorDto {
@@ -187,6 +257,25 @@ impl From<&Operator> for OperatorDto {
             Operator::Regex { regex, target } => {
                 OperatorDto::Regex { regex: regex.to_owned(), target: target.to_owned() }
             }
+            Operator::Similar { first, second, threshold } => OperatorDto::Similar {
+                first: serde_json::to_value(first).unwrap_or(serde_json::Value::Null),
+                second: serde_json::to_value(second).unwrap_or(serde_json::Value::Null),
+                threshold: *threshold,
+            },
+            Operator::Changed { entity, target } => OperatorDto::Changed {
+                entity: serde_json::to_value(entity).unwrap_or(serde_json::Value::Null),
+                target: serde_json::to_value(target).unwrap_or(serde_json::Value::Null),
+            },
+            Operator::IsType { target, expected_type } => OperatorDto::IsType {
+                target: serde_json::to_value(target).unwrap_or(serde_json::Value::Null),
+                expected_type: serde_json::to_value(expected_type)
+                    .ok()
+                    .and_then(|expected_type| expected_type.as_str().map(str::to_owned))
+                    .unwrap_or_default(),
+            },
+            Operator::Custom { name, params } => {
+                OperatorDto::Custom { name: name.to_owned(), params: params.clone() }
+            }
         }
     }
 }
@@ -195,6 +284,12 @@ impl From<&Operator> for OperatorDto {
 pub struct ActionDto {
     pub id: String,
     pub payload: Value,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<OperatorDto>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_strategy: Option<Value>,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
@@ -202,6 +297,8 @@ pub struct FilterDto {
     pub description: String,
     pub active: bool,
     pub filter: Option<OperatorDto>,
+    pub mode: FilterModeDto,
+    pub continue_on_match: bool,
 }
 
 impl From<Filter> for FilterDto {
@@ -213,6 +310,23 @@ impl From<Filter> for FilterDto {
                 Defaultable::Default { .. } => None,
             },
             active: filter.active,
+            mode: filter.mode.into(),
+            continue_on_match: filter.continue_on_match,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize, TypeScriptify)]
+pub enum FilterModeDto {
+    AllMatches,
+    FirstMatch,
+}
+
+impl From<FilterMode> for FilterModeDto {
+    fn from(mode: FilterMode) -> Self {
+        match mode {
+            FilterMode::AllMatches => FilterModeDto::AllMatches,
+            FilterMode::FirstMatch => FilterModeDto::FirstMatch,
         }
     }
 }
@@ -225,6 +339,14 @@ pub struct MatcherConfigDraftDataDto {
     pub draft_id: String,
 }
 
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct MatcherConfigDeployRecordDto {
+    pub user: String,
+    pub draft_id: String,
+    pub config_hash: String,
+    pub deployed_ts_ms: i64,
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
 #[serde(tag = "type")]
 pub enum ProcessingTreeNodeConfigDto {
@@ -281,17 +403,50 @@ impl ProcessingTreeNodeConfigDto {
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
 #[serde(tag = "type")]
 pub enum ProcessingTreeNodeDetailsDto {
-    Filter { name: String, description: String, active: bool, filter: Option<OperatorDto> },
-    Iterator { name: String, description: String, active: bool, target: String },
-    Ruleset { name: String, rules: Vec<RuleDetailsDto> },
+    Filter {
+        name: String,
+        description: String,
+        active: bool,
+        filter: Option<OperatorDto>,
+        mode: FilterModeDto,
+        continue_on_match: bool,
+    },
+    Iterator {
+        name: String,
+        description: String,
+        active: bool,
+        target: String,
+    },
+    Ruleset {
+        name: String,
+        rules: Vec<RuleDetailsDto>,
+    },
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeScriptify)]
 #[serde(tag = "type")]
 pub enum ProcessingTreeNodeEditDto {
-    Filter { name: String, description: String, active: bool, filter: Option<OperatorDto> },
-    Iterator { name: String, description: String, target: String, active: bool },
-    Ruleset { name: String },
+    /// The `mode` and `continue_on_match` properties are optional: when they are not set,
+    /// the edited filter keeps its current values.
+    Filter {
+        name: String,
+        description: String,
+        active: bool,
+        filter: Option<OperatorDto>,
+        #[serde(default)]
+        mode: Option<FilterModeDto>,
+        #[serde(default)]
+        continue_on_match: Option<bool>,
+    },
+    Iterator {
+        name: String,
+        description: String,
+        target: String,
+        active: bool,
+    },
+    Ruleset {
+        name: String,
+    },
 }
 
 impl From<&MatcherConfig> for ProcessingTreeNodeDetailsDto {
@@ -305,6 +460,8 @@ impl From<&MatcherConfig> for ProcessingTreeNodeDetailsDto {
                     Defaultable::Value(operator) => Some(operator.into()),
                     Defaultable::Default { .. } => None,
                 },
+                mode: filter.mode.into(),
+                continue_on_match: filter.continue_on_match,
             },
             MatcherConfig::Iterator { name, iterator, .. } => {
                 ProcessingTreeNodeDetailsDto::Iterator {
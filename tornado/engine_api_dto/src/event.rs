@@ -51,6 +51,17 @@ pub enum ProcessedIteratorDto {
 #[derive(Clone, Serialize, Deserialize, TypeScriptify)]
 pub struct ProcessedFilterDto {
     pub status: ProcessedFilterStatusDto,
+    /// Why the filter did or did not match the event, if requested
+    pub explanation: Option<OperatorExplanationDto>,
+}
+
+#[derive(Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct OperatorExplanationDto {
+    pub operator: String,
+    pub result: bool,
+    /// The values resolved by the operator accessors, `None` if an accessor could not be resolved.
+    pub values: Vec<Option<Value>>,
+    pub children: Vec<OperatorExplanationDto>,
 }
 
 #[derive(Clone, Serialize, Deserialize, TypeScriptify)]
@@ -82,3 +93,14 @@ pub enum ProcessedRuleStatusDto {
     NotMatched,
     NotProcessed,
 }
+
+#[derive(Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct MatchingRulesDto {
+    pub rules: Vec<MatchingRuleDto>,
+}
+
+#[derive(Clone, Serialize, Deserialize, TypeScriptify)]
+pub struct MatchingRuleDto {
+    pub name: String,
+    pub path: Vec<String>,
+}
@@ -94,6 +94,7 @@ export type Value = any;"#;
 /* ---------------- */"#,
     );
     push_ts(&mut ts_code, &common::Id::<()>::type_script_ify());
+    push_ts(&mut ts_code, &common::Page::<()>::type_script_ify());
     push_ts(&mut ts_code, &common::WebError::type_script_ify());
 
     // Push 'auth' ts types
@@ -119,9 +120,13 @@ export type Value = any;"#;
     );
     push_ts(&mut ts_code, &config::ActionDto::type_script_ify());
     push_ts(&mut ts_code, &config::ConstraintDto::type_script_ify());
+    push_ts(&mut ts_code, &config::DedupKeyDto::type_script_ify());
+    push_ts(&mut ts_code, &config::DeduplicationDto::type_script_ify());
     push_ts(&mut ts_code, &config::ExtractorDto::type_script_ify());
     push_ts(&mut ts_code, &config::ExtractorRegexDto::type_script_ify());
     push_ts(&mut ts_code, &config::FilterDto::type_script_ify());
+    push_ts(&mut ts_code, &config::FilterModeDto::type_script_ify());
+    push_ts(&mut ts_code, &config::MatcherConfigDeployRecordDto::type_script_ify());
     push_ts(&mut ts_code, &config::MatcherConfigDraftDataDto::type_script_ify());
     push_ts(&mut ts_code, &config::ModifierDto::type_script_ify());
     push_ts(&mut ts_code, &config::OperatorDto::type_script_ify());
@@ -132,6 +137,11 @@ export type Value = any;"#;
     push_ts(&mut ts_code, &config::RuleDetailsDto::type_script_ify());
     push_ts(&mut ts_code, &config::TreeInfoDto::type_script_ify());
     push_ts(&mut ts_code, &config::RulePositionDto::type_script_ify());
+    push_ts(&mut ts_code, &config::TestRuleRequestDto::type_script_ify());
+    push_ts(&mut ts_code, &config::TestRuleResultDto::type_script_ify());
+    push_ts(&mut ts_code, &config::FilterPreviewRequestDto::type_script_ify());
+    push_ts(&mut ts_code, &config::FilterPreviewResultDto::type_script_ify());
+    push_ts(&mut ts_code, &config::FilterPreviewEventDto::type_script_ify());
 
     // Push 'event' ts types
     push_ts(
@@ -142,6 +152,9 @@ export type Value = any;"#;
 /* ------------- */"#,
     );
     push_ts(&mut ts_code, &event::EventDto::type_script_ify());
+    push_ts(&mut ts_code, &event::MatchingRuleDto::type_script_ify());
+    push_ts(&mut ts_code, &event::MatchingRulesDto::type_script_ify());
+    push_ts(&mut ts_code, &event::OperatorExplanationDto::type_script_ify());
     push_ts(&mut ts_code, &event::ProcessType::type_script_ify());
     push_ts(&mut ts_code, &event::ProcessedEventDto::type_script_ify());
     push_ts(&mut ts_code, &event::ProcessedFilterDto::type_script_ify());
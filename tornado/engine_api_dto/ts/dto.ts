@@ -19,6 +19,8 @@ export type Value = any;
 
 export type Id<T> = { id: T };
 
+export type Page<T> = { items: T []; total: number };
+
 export type WebError = {     code: string; params: { [key: string]: string }; message: string |     null };
 
 
@@ -30,7 +32,7 @@ export type Auth = { user: string; roles: string []; preferences: UserPreference
 
 export type AuthWithPermissionsDto = {     user: string; permissions: PermissionDto []; preferences:     UserPreferences | null };
 
-export enum PermissionDto {     ConfigEdit = "ConfigEdit", ConfigView = "ConfigView", RuntimeConfigEdit =     "RuntimeConfigEdit", RuntimeConfigView = "RuntimeConfigView",     TestEventExecuteActions = "TestEventExecuteActions" };
+export enum PermissionDto {     ConfigEdit = "ConfigEdit", ConfigView = "ConfigView", RuntimeConfigEdit =     "RuntimeConfigEdit", RuntimeConfigView = "RuntimeConfigView",     TestEventExecuteActions = "TestEventExecuteActions", ActionsReplay = "ActionsReplay" };
 
 export type UserPreferences = { language: string | null };
 
@@ -39,18 +41,26 @@ export type UserPreferences = { language: string | null };
 /* 'config' types */
 /* -------------- */
 
-export type ActionDto = { id: string; payload: Value };
+export type ActionDto = {     id: string; payload: Value; when: OperatorDto | null; retry_strategy:     Value | null };
 
 export type ConstraintDto = { WHERE: OperatorDto | null; WITH: { [key: string]: ExtractorDto } };
 
+export type DedupKeyDto = string | { fields: string []; separator: string };
+
+export type DeduplicationDto = { key: DedupKeyDto; ttl_secs: number };
+
 export type ExtractorDto = {     from: string; regex: ExtractorRegexDto; modifiers_post: ModifierDto     [] };
 
 export type ExtractorRegexDto = 
- | {     type: "Regex"; match: string; group_match_idx: number | null;     all_matches: boolean | null } 
+ | {     type: "Regex"; match: string; group_match_idx: number | null;     all_matches: boolean | null; empty_if_no_match: boolean | null } 
  | {     type: "RegexNamedGroups"; named_match: string; all_matches: boolean |     null } 
  | { type: "KeyRegex"; single_key_match: string };
 
-export type FilterDto = { description: string; active: boolean; filter: OperatorDto | null };
+export type FilterDto = {     description: string; active: boolean; filter: OperatorDto | null;     mode: FilterModeDto; continue_on_match: boolean };
+
+export enum FilterModeDto { AllMatches = "AllMatches", FirstMatch = "FirstMatch" };
+
+export type MatcherConfigDeployRecordDto = {     user: string; draft_id: string; config_hash: string; deployed_ts_ms: number };
 
 export type MatcherConfigDraftDataDto = {     user: string; created_ts_ms: number; updated_ts_ms: number; draft_id: string };
 
@@ -60,6 +70,7 @@ export type ModifierDto =
  | {     type: "ReplaceAll"; find: string; replace: string; is_regex:     boolean } 
  | { type: "ToNumber" } 
  | { type: "Trim" } 
+ | { type: "Uppercase" } 
  | { type: "DateAndTime"; timezone: string };
 
 export type OperatorDto = 
@@ -75,9 +86,13 @@ export type OperatorDto =
  | { type: "le"; first: Value; second: Value } 
  | { type: "lt"; first: Value; second: Value } 
  | { type: "ne"; first: Value; second: Value } 
- | { type: "regex"; regex: string; target: string };
+ | { type: "regex"; regex: string; target: string } 
+ | { type: "similar"; first: Value; second: Value; threshold: number } 
+ | { type: "changed"; entity: Value; target: Value } 
+ | { type: "isType"; target: Value; expected_type: string } 
+ | { type: "custom"; name: string; params: Value };
 
-export type RuleDto = {     name: string; description: string; continue: boolean; active:     boolean; constraint: ConstraintDto; actions: ActionDto [] };
+export type RuleDto = {     name: string; description: string; continue: boolean; active:     boolean; constraint: ConstraintDto; actions: ActionDto [];     deduplication: DeduplicationDto | null };
 
 export type ProcessingTreeNodeConfigDto = 
  | {     type: "Filter"; name: string; rules_count: number; children_count:     number; description: string; has_iterator_ancestor: boolean; active:     boolean } 
@@ -85,12 +100,12 @@ export type ProcessingTreeNodeConfigDto =
  | { type: "Ruleset"; name: string; rules_count: number };
 
 export type ProcessingTreeNodeEditDto = 
- | {     type: "Filter"; name: string; description: string; active: boolean; filter: OperatorDto | null } 
+ | {     type: "Filter"; name: string; description: string; active: boolean; filter: OperatorDto | null;     mode: FilterModeDto | null; continue_on_match: boolean | null } 
  | {     type: "Iterator"; name: string; description: string; target: string; active: boolean } 
  | { type: "Ruleset"; name: string };
 
 export type ProcessingTreeNodeDetailsDto = 
- | {     type: "Filter"; name: string; description: string; active: boolean; filter: OperatorDto | null } 
+ | {     type: "Filter"; name: string; description: string; active: boolean; filter: OperatorDto | null;     mode: FilterModeDto; continue_on_match: boolean } 
  | {     type: "Iterator"; name: string; description: string; active:     boolean; target: string } 
  | { type: "Ruleset"; name: string; rules: RuleDetailsDto [] };
 
@@ -100,6 +115,16 @@ export type TreeInfoDto = { rules_count: number; filters_count: number; iterator
 
 export type RulePositionDto = { position: number };
 
+export type TestRuleRequestDto = { rule: RuleDto; event: EventDto };
+
+export type TestRuleResultDto = {     matched: boolean; extracted_vars: Value; message: string | null };
+
+export type FilterPreviewRequestDto = {     filter_path: string; filter: ProcessingTreeNodeEditDto; events: EventDto [] };
+
+export type FilterPreviewResultDto = { events: FilterPreviewEventDto [] };
+
+export type FilterPreviewEventDto = { current: string []; proposed: string []; changed: boolean };
+
 
 /* ------------- */
 /* 'event' types */
@@ -107,11 +132,17 @@ export type RulePositionDto = { position: number };
 
 export type EventDto = {     type: string; created_ms: number; metadata:     { [key: string]: Value }; payload: { [key: string]: Value } };
 
+export type MatchingRuleDto = { name: string; path: string [] };
+
+export type MatchingRulesDto = { rules: MatchingRuleDto [] };
+
+export type OperatorExplanationDto = {     operator: string; result: boolean; values: (Value | null) [];     children: OperatorExplanationDto [] };
+
 export enum ProcessType { Full = "Full", SkipActions = "SkipActions" };
 
 export type ProcessedEventDto = { event: EventDto; result: ProcessedNodeDto };
 
-export type ProcessedFilterDto = { status: ProcessedFilterStatusDto };
+export type ProcessedFilterDto = {     status: ProcessedFilterStatusDto; explanation: OperatorExplanationDto |     null };
 
 export enum ProcessedFilterStatusDto { Matched = "Matched", NotMatched = "NotMatched", Inactive = "Inactive" };
 
@@ -0,0 +1,201 @@
+use actix::dev::ToEnvelope;
+use actix::prelude::*;
+use log::*;
+use std::time::Duration;
+use tornado_collector_common::{Collector, CollectorError};
+use tornado_collector_filetail::tail::FileTail;
+use tornado_collector_filetail::FileTailCollector;
+use tornado_common::actors::message::EventMessage;
+use tornado_common_api::TracedEvent;
+
+pub struct FileTailCollectorActor<A: Actor + actix::Handler<EventMessage>>
+where
+    <A as Actor>::Context: ToEnvelope<A, EventMessage>,
+{
+    collector: Option<FileTailCollector>,
+    tail: Option<FileTail>,
+    writer_addr: Addr<A>,
+    poll_interval: Duration,
+}
+
+impl<A: Actor + actix::Handler<EventMessage>> FileTailCollectorActor<A>
+where
+    <A as Actor>::Context: ToEnvelope<A, EventMessage>,
+{
+    /// Starts a FileTailCollectorActor that checks the tailed file for new lines
+    /// every `poll_interval` and sends an Event for each of them to the `writer_addr`.
+    pub fn start_new(
+        collector: FileTailCollector,
+        tail: FileTail,
+        writer_addr: Addr<A>,
+        poll_interval: Duration,
+    ) -> Addr<Self> {
+        FileTailCollectorActor::create(move |_ctx| FileTailCollectorActor {
+            collector: Some(collector),
+            tail: Some(tail),
+            writer_addr,
+            poll_interval,
+        })
+    }
+}
+
+/// Reads the lines appended to the file and sends them to the writer.
+/// The file is accessed in a blocking thread, so that the actor thread is never blocked,
+///   and the reached position is persisted only after all the read lines are delivered.
+async fn tail_file<A: Actor + actix::Handler<EventMessage>>(
+    collector: FileTailCollector,
+    mut tail: FileTail,
+    writer_addr: Addr<A>,
+    poll_interval: Duration,
+) where
+    <A as Actor>::Context: ToEnvelope<A, EventMessage>,
+{
+    loop {
+        let (returned_tail, result) = run_blocking(tail, FileTail::read_lines).await;
+        tail = returned_tail;
+        let lines = match result {
+            Ok(lines) => lines,
+            Err(err) => {
+                error!("FileTailCollectorActor - Cannot read the tailed file. Err: {:?}", err);
+                vec![]
+            }
+        };
+
+        // More lines could be available, as the file is read in chunks
+        let wait_for_new_lines = lines.is_empty();
+
+        for line in lines {
+            let span = tracing::debug_span!("Collect file tail Event").entered();
+            let event = match collector.to_event(&line) {
+                Ok(event) => event,
+                Err(err) => {
+                    error!("FileTailCollectorActor - Cannot create the event. Err: {:?}", err);
+                    continue;
+                }
+            };
+            match writer_addr.send(EventMessage(TracedEvent { event, span: span.exit() })).await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => {
+                    error!("FileTailCollectorActor - Error while sending event. Error: {}", err)
+                }
+                Err(err) => {
+                    // The position is not persisted, so the lines are read again after a restart
+                    error!(
+                        "FileTailCollectorActor - The writer is not available. The file is not tailed anymore. Error: {}",
+                        err
+                    );
+                    return;
+                }
+            }
+        }
+
+        let (returned_tail, result) = run_blocking(tail, FileTail::persist_position).await;
+        tail = returned_tail;
+        if let Err(err) = result {
+            error!("FileTailCollectorActor - Cannot persist the position. Err: {:?}", err);
+        }
+
+        if wait_for_new_lines {
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
+
+/// Executes a blocking operation on the FileTail in a dedicated thread.
+async fn run_blocking<T: Send + 'static>(
+    mut tail: FileTail,
+    operation: fn(&mut FileTail) -> Result<T, CollectorError>,
+) -> (FileTail, Result<T, CollectorError>) {
+    tokio::task::spawn_blocking(move || {
+        let result = operation(&mut tail);
+        (tail, result)
+    })
+    .await
+    .expect("FileTailCollectorActor - The file tail operation should not panic")
+}
+
+impl<A: Actor + actix::Handler<EventMessage>> Actor for FileTailCollectorActor<A>
+where
+    <A as Actor>::Context: ToEnvelope<A, EventMessage>,
+{
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("FileTailCollectorActor started.");
+        if let (Some(collector), Some(tail)) = (self.collector.take(), self.tail.take()) {
+            let writer_addr = self.writer_addr.clone();
+            ctx.spawn(tail_file(collector, tail, writer_addr, self.poll_interval).into_actor(self));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::path::Path;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+    use tokio::time::timeout;
+    use tornado_collector_filetail::LINE_FIELD;
+    use tornado_common::actors::message::TornadoCommonActorError;
+    use tornado_common_api::Event;
+
+    struct FakeWriterActor {
+        sender: UnboundedSender<Event>,
+    }
+
+    impl Actor for FakeWriterActor {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<EventMessage> for FakeWriterActor {
+        type Result = Result<(), TornadoCommonActorError>;
+
+        fn handle(&mut self, msg: EventMessage, _: &mut Context<Self>) -> Self::Result {
+            self.sender.send(msg.0.event).unwrap();
+            Ok(())
+        }
+    }
+
+    fn append(path: &Path, content: &str) {
+        let mut file = OpenOptions::new().create(true).append(true).open(path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    async fn next_line(receiver: &mut UnboundedReceiver<Event>) -> String {
+        let event = timeout(Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap();
+        event.payload.get(LINE_FIELD).unwrap().as_str().unwrap().to_owned()
+    }
+
+    #[actix_rt::test]
+    async fn should_send_an_event_for_each_line_appended_to_the_file() {
+        // Arrange
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("app.log");
+        let (sender, mut receiver) = unbounded_channel();
+        let writer_addr = FakeWriterActor { sender }.start();
+
+        FileTailCollectorActor::start_new(
+            FileTailCollector::new("filetail", path.to_string_lossy()),
+            FileTail::new(&path),
+            writer_addr,
+            Duration::from_millis(10),
+        );
+
+        // Act
+        append(&path, "first\nsecond\n");
+        let first = next_line(&mut receiver).await;
+        let second = next_line(&mut receiver).await;
+
+        fs::rename(&path, tempdir.path().join("app.log.1")).unwrap();
+        append(&path, "third\n");
+        let third = next_line(&mut receiver).await;
+
+        // Assert
+        assert_eq!("first", first);
+        assert_eq!("second", second);
+        assert_eq!("third", third);
+    }
+}
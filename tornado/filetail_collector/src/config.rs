@@ -0,0 +1,81 @@
+use clap::{App, Arg, ArgMatches};
+use config_rs::{Config, ConfigError, File};
+use serde::{Deserialize, Serialize};
+use tornado_common::actors::TornadoConnectionChannel;
+use tornado_common_logger::LoggerConfig;
+
+pub const CONFIG_DIR_DEFAULT: Option<&'static str> =
+    option_env!("TORNADO_FILETAIL_COLLECTOR_CONFIG_DIR_DEFAULT");
+
+pub fn arg_matches() -> ArgMatches {
+    App::new("tornado_filetail_collector")
+        .arg(Arg::new("config-dir")
+            .long("config-dir")
+            .help("The filesystem folder where the Tornado File Tail Collector configuration is saved")
+            .default_value(CONFIG_DIR_DEFAULT.unwrap_or("/etc/tornado_filetail_collector")))
+        .get_matches()
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct CollectorConfig {
+    /// The logger configuration
+    pub logger: LoggerConfig,
+    pub filetail_collector: FileTailCollectorConfig,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct FileTailCollectorConfig {
+    pub message_queue_size: usize,
+
+    /// The path of the file to tail
+    pub file_path: String,
+
+    /// The file where the position reached in the tailed file is persisted.
+    /// If not set, the position is not persisted and the file is read from the beginning at each start.
+    pub position_file_path: Option<String>,
+
+    /// The interval in milliseconds between two checks for new lines
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+
+    /// The type of the generated Events
+    #[serde(default = "default_event_type")]
+    pub event_type: String,
+
+    pub tornado_connection_channel: TornadoConnectionChannel,
+}
+
+fn default_poll_interval_ms() -> u64 {
+    1000
+}
+
+fn default_event_type() -> String {
+    "filetail".to_owned()
+}
+
+pub fn build_config(config_dir: &str) -> Result<CollectorConfig, ConfigError> {
+    let collector_config_path = format!("{}/{}", config_dir, "filetail_collector.toml");
+    let mut s = Config::new();
+    s.merge(File::with_name(&collector_config_path))?;
+    s.try_into()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn should_read_configuration_from_file() {
+        // Arrange
+        let path = "./config/";
+
+        // Act
+        let config = build_config(path).unwrap();
+
+        // Assert
+        assert_eq!("/var/log/messages", config.filetail_collector.file_path);
+        assert_eq!(1000, config.filetail_collector.poll_interval_ms);
+        assert_eq!("filetail", config.filetail_collector.event_type);
+    }
+}
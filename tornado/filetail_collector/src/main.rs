@@ -0,0 +1,91 @@
+pub mod actors;
+pub mod config;
+
+use crate::config::FileTailCollectorConfig;
+use actix::dev::ToEnvelope;
+use actix::prelude::*;
+use log::*;
+use std::path::PathBuf;
+use std::time::Duration;
+use tornado_collector_filetail::tail::FileTail;
+use tornado_collector_filetail::FileTailCollector;
+use tornado_common::actors::message::EventMessage;
+use tornado_common::actors::nats_publisher::NatsPublisherActor;
+use tornado_common::actors::tcp_client::TcpClientActor;
+use tornado_common::actors::TornadoConnectionChannel;
+use tornado_common::signature::EventSigner;
+use tornado_common_logger::elastic_apm::DEFAULT_APM_SERVER_CREDENTIALS_FILENAME;
+use tornado_common_logger::setup_logger;
+
+#[actix_rt::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let arg_matches = config::arg_matches();
+
+    let config_dir = arg_matches.value_of("config-dir").expect("config-dir should be provided");
+
+    let mut collector_config = config::build_config(config_dir)?;
+    let apm_server_api_credentials_filepath =
+        format!("{}/{}", config_dir, DEFAULT_APM_SERVER_CREDENTIALS_FILENAME);
+    // Get the result and log the error later because the logger is not available yet
+    let apm_credentials_read_result = collector_config
+        .logger
+        .tracing_elastic_apm
+        .read_apm_server_api_credentials_if_not_set(&apm_server_api_credentials_filepath);
+
+    // Setup logger
+    let _guard = setup_logger(collector_config.logger)?;
+    if let Err(apm_credentials_read_error) = apm_credentials_read_result {
+        warn!("{:?}", apm_credentials_read_error);
+    }
+
+    info!("File tail collector started");
+
+    let filetail_config = collector_config.filetail_collector;
+    let message_queue_size = filetail_config.message_queue_size;
+
+    match filetail_config.tornado_connection_channel.clone() {
+        TornadoConnectionChannel::Nats { nats } => {
+            info!("Connect to Tornado through NATS");
+            let actor_address = NatsPublisherActor::start_new(nats, message_queue_size).await?;
+            start(actor_address, filetail_config);
+        }
+        TornadoConnectionChannel::Tcp { tcp_socket_ip, tcp_socket_port, signature_secret } => {
+            info!("Connect to Tornado through TCP socket");
+            // Start TcpWriter
+            let tornado_tcp_address = format!("{}:{}", tcp_socket_ip, tcp_socket_port,);
+
+            let actor_address = TcpClientActor::start_new_with_signer(
+                tornado_tcp_address,
+                message_queue_size,
+                signature_secret.as_deref().map(EventSigner::new),
+            );
+            start(actor_address, filetail_config);
+        }
+    };
+
+    tokio::signal::ctrl_c().await.unwrap();
+    println!("Ctrl-C received, shutting down");
+    System::current().stop();
+
+    Ok(())
+}
+
+fn start<A: Actor + actix::Handler<EventMessage>>(
+    actor_address: Addr<A>,
+    filetail_config: FileTailCollectorConfig,
+) where
+    <A as Actor>::Context: ToEnvelope<A, tornado_common::actors::message::EventMessage>,
+{
+    info!("Tail the file [{}]", filetail_config.file_path);
+
+    // Start File tail collector
+    actors::collector::FileTailCollectorActor::start_new(
+        FileTailCollector::new(filetail_config.event_type, filetail_config.file_path.as_str()),
+        FileTail::new_with_position_path(
+            filetail_config.file_path.as_str(),
+            filetail_config.position_file_path.map(PathBuf::from),
+        ),
+        actor_address,
+        Duration::from_millis(filetail_config.poll_interval_ms),
+    );
+}
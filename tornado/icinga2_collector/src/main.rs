@@ -7,6 +7,7 @@ use tornado_collector_jmespath::JMESPathEventCollector;
 use tornado_common::actors::nats_publisher::NatsPublisherActor;
 use tornado_common::actors::tcp_client::TcpClientActor;
 use tornado_common::actors::TornadoConnectionChannel;
+use tornado_common::signature::EventSigner;
 use tornado_common::{actors, TornadoError};
 use tornado_common_api::TracedEvent;
 use tornado_common_logger::elastic_apm::DEFAULT_APM_SERVER_CREDENTIALS_FILENAME;
@@ -73,14 +74,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                 .await?;
                 start(collector_config, streams_config, actor_address);
             }
-            TornadoConnectionChannel::Tcp { tcp_socket_ip, tcp_socket_port } => {
+            TornadoConnectionChannel::Tcp { tcp_socket_ip, tcp_socket_port, signature_secret } => {
                 info!("Connect to Tornado through TCP socket");
                 // Start TcpWriter
                 let tornado_tcp_address = format!("{}:{}", tcp_socket_ip, tcp_socket_port,);
 
-                let actor_address = TcpClientActor::start_new(
+                let actor_address = TcpClientActor::start_new_with_signer(
                     tornado_tcp_address,
                     collector_config.icinga2_collector.message_queue_size,
+                    signature_secret.as_deref().map(EventSigner::new),
                 );
                 start(collector_config, streams_config, actor_address);
             }
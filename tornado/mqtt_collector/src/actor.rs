@@ -0,0 +1,131 @@
+use crate::config::MqttClientConfig;
+use actix::prelude::*;
+use log::*;
+use rumqttc::{AsyncClient, Event as MqttEvent, EventLoop, Incoming, QoS, SubscribeFilter};
+use std::time::Duration;
+use tornado_collector_common::Collector;
+use tornado_collector_mqtt::{MqttEventCollector, MqttMessage};
+use tornado_common::actors::message::EventMessage;
+use tornado_common_api::TracedEvent;
+
+const MQTT_CLIENT_REQUEST_CAPACITY: usize = 100;
+
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct MqttPublishMessage {
+    pub topic: String,
+    pub payload: Vec<u8>,
+}
+
+/// An actor that subscribes to a set of topics on an MQTT broker and forwards the received
+/// messages, converted into Tornado Events, to the Tornado connection channel.
+pub struct MqttSubscriberActor {
+    mqtt_client_config: MqttClientConfig,
+    topics: Vec<String>,
+    qos: QoS,
+    collector: MqttEventCollector,
+    recipient: Recipient<EventMessage>,
+}
+
+impl MqttSubscriberActor {
+    pub fn start_new(
+        mqtt_client_config: MqttClientConfig,
+        topics: Vec<String>,
+        qos: QoS,
+        event_type: String,
+        recipient: Recipient<EventMessage>,
+        message_mailbox_capacity: usize,
+    ) -> Addr<Self> {
+        MqttSubscriberActor::create(move |ctx| {
+            ctx.set_mailbox_capacity(message_mailbox_capacity);
+            MqttSubscriberActor {
+                mqtt_client_config,
+                topics,
+                qos,
+                collector: MqttEventCollector::new(event_type),
+                recipient,
+            }
+        })
+    }
+}
+
+impl Actor for MqttSubscriberActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!(
+            "MqttSubscriberActor started. Connecting to MQTT broker [{}:{}]",
+            self.mqtt_client_config.host, self.mqtt_client_config.port
+        );
+
+        let (client, event_loop) = AsyncClient::new(
+            self.mqtt_client_config.to_mqtt_options(),
+            MQTT_CLIENT_REQUEST_CAPACITY,
+        );
+        let filters =
+            self.topics.iter().map(|topic| SubscribeFilter::new(topic.clone(), self.qos)).collect();
+        let reconnect_delay = Duration::from_millis(self.mqtt_client_config.reconnect_delay_ms);
+
+        actix::spawn(poll_event_loop(client, event_loop, filters, reconnect_delay, ctx.address()));
+    }
+}
+
+impl Handler<MqttPublishMessage> for MqttSubscriberActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: MqttPublishMessage, _ctx: &mut Context<Self>) -> Self::Result {
+        let _span = tracing::debug_span!("Collect MQTT Event").entered();
+        debug!("MqttSubscriberActor - received message on topic [{}]", msg.topic);
+
+        match self.collector.to_event(MqttMessage { topic: &msg.topic, payload: &msg.payload }) {
+            Ok(event) => {
+                self.recipient.try_send(EventMessage(TracedEvent { event, span: tracing::Span::current() })).unwrap_or_else(|err| error!("MqttSubscriberActor -  Error while sending EventMessage to TornadoConnectionChannel actor. Error: {}", err));
+            }
+            Err(e) => error!("Error processing incoming MQTT message. Err: {:?}", e),
+        }
+    }
+}
+
+async fn poll_event_loop(
+    client: AsyncClient,
+    mut event_loop: EventLoop,
+    filters: Vec<SubscribeFilter>,
+    reconnect_delay: Duration,
+    address: Addr<MqttSubscriberActor>,
+) {
+    while address.connected() {
+        match event_loop.poll().await {
+            Ok(MqttEvent::Incoming(Incoming::ConnAck(_))) => {
+                // The subscriptions are renewed at every connection because the broker
+                // could have discarded the session while the collector was disconnected.
+                info!("Connected to MQTT broker. Subscribing to topics {:?}", filters);
+                client.try_subscribe_many(filters.clone()).unwrap_or_else(|err| {
+                    error!("Cannot subscribe to MQTT topics. Err: {:?}", err)
+                });
+            }
+            Ok(MqttEvent::Incoming(Incoming::Publish(publish))) => {
+                address
+                    .try_send(MqttPublishMessage {
+                        topic: publish.topic,
+                        payload: publish.payload.to_vec(),
+                    })
+                    .unwrap_or_else(|err| {
+                        error!(
+                            "MQTT Collector -  Error while sending message to MqttSubscriberActor. Error: {}",
+                            err
+                        )
+                    });
+            }
+            Ok(_) => {}
+            Err(err) => {
+                warn!(
+                    "MQTT connection error. A new connection will be attempted in {} ms. Err: {:?}",
+                    reconnect_delay.as_millis(),
+                    err
+                );
+                tokio::time::sleep(reconnect_delay).await;
+            }
+        }
+    }
+    info!("MqttSubscriberActor stopped. Closing the MQTT event loop");
+}
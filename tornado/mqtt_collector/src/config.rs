@@ -0,0 +1,128 @@
+use clap::{App, Arg, ArgMatches};
+use config_rs::{Config, ConfigError, File};
+use rumqttc::{MqttOptions, QoS};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tornado_collector_mqtt::DEFAULT_EVENT_TYPE;
+use tornado_common::actors::TornadoConnectionChannel;
+use tornado_common_logger::LoggerConfig;
+
+pub const CONFIG_DIR_DEFAULT: Option<&'static str> =
+    option_env!("TORNADO_MQTT_COLLECTOR_CONFIG_DIR_DEFAULT");
+
+pub fn arg_matches() -> ArgMatches {
+    App::new("tornado_mqtt_collector")
+        .arg(
+            Arg::new("config-dir")
+                .long("config-dir")
+                .help(
+                    "The filesystem folder where the Tornado MQTT Collector configuration is saved",
+                )
+                .default_value(CONFIG_DIR_DEFAULT.unwrap_or("/etc/tornado_mqtt_collector")),
+        )
+        .get_matches()
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct CollectorConfig {
+    /// The logger configuration
+    pub logger: LoggerConfig,
+    pub mqtt_collector: MqttCollectorConfig,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct MqttCollectorConfig {
+    pub message_queue_size: usize,
+
+    #[serde(default = "default_event_type")]
+    pub event_type: String,
+
+    pub topics: Vec<String>,
+
+    #[serde(default)]
+    pub qos: MqttQos,
+
+    pub mqtt_client: MqttClientConfig,
+
+    pub tornado_connection_channel: TornadoConnectionChannel,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MqttClientConfig {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+
+    #[serde(default = "default_keep_alive_secs")]
+    pub keep_alive_secs: u64,
+
+    #[serde(default = "default_reconnect_delay_ms")]
+    pub reconnect_delay_ms: u64,
+}
+
+impl MqttClientConfig {
+    pub fn to_mqtt_options(&self) -> MqttOptions {
+        let mut options = MqttOptions::new(self.client_id.clone(), self.host.clone(), self.port);
+        options.set_keep_alive(Duration::from_secs(self.keep_alive_secs));
+        options
+    }
+}
+
+/// The MQTT Quality of Service level
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MqttQos {
+    AtMostOnce,
+    #[default]
+    AtLeastOnce,
+    ExactlyOnce,
+}
+
+impl From<MqttQos> for QoS {
+    fn from(qos: MqttQos) -> Self {
+        match qos {
+            MqttQos::AtMostOnce => QoS::AtMostOnce,
+            MqttQos::AtLeastOnce => QoS::AtLeastOnce,
+            MqttQos::ExactlyOnce => QoS::ExactlyOnce,
+        }
+    }
+}
+
+fn default_event_type() -> String {
+    DEFAULT_EVENT_TYPE.to_owned()
+}
+
+fn default_keep_alive_secs() -> u64 {
+    30
+}
+
+fn default_reconnect_delay_ms() -> u64 {
+    1000
+}
+
+pub fn build_config(config_dir: &str) -> Result<CollectorConfig, ConfigError> {
+    let config_file_path = format!("{}/{}", config_dir, "mqtt_collector.toml");
+    let mut s = Config::new();
+    s.merge(File::with_name(&config_file_path))?;
+    s.try_into()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn should_read_configuration_from_file() {
+        // Arrange
+        let path = "./config/";
+
+        // Act
+        let config = build_config(path).unwrap();
+
+        // Assert
+        assert_eq!("mqtt", config.mqtt_collector.event_type);
+        assert_eq!(vec!["tornado/#".to_owned()], config.mqtt_collector.topics);
+        assert_eq!(MqttQos::AtLeastOnce, config.mqtt_collector.qos);
+        assert_eq!(1883, config.mqtt_collector.mqtt_client.port);
+    }
+}
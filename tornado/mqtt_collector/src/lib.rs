@@ -0,0 +1,56 @@
+use crate::actor::MqttSubscriberActor;
+use crate::config::MqttCollectorConfig;
+use actix::{Addr, Recipient};
+use log::*;
+use tornado_common::actors::message::EventMessage;
+use tornado_common::actors::nats_publisher::NatsPublisherActor;
+use tornado_common::actors::tcp_client::TcpClientActor;
+use tornado_common::actors::TornadoConnectionChannel;
+use tornado_common::signature::EventSigner;
+use tornado_common::TornadoError;
+
+pub mod actor;
+pub mod config;
+
+pub async fn start(
+    mqtt_collector_config: MqttCollectorConfig,
+) -> Result<Addr<MqttSubscriberActor>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    if mqtt_collector_config.topics.is_empty() {
+        return Err(TornadoError::ConfigurationError {
+            message: "At least one MQTT topic must be specified.".to_owned(),
+        }
+        .into());
+    }
+
+    let recipient: Recipient<EventMessage> = match mqtt_collector_config.tornado_connection_channel
+    {
+        TornadoConnectionChannel::Nats { nats } => {
+            info!("Connect to Tornado through NATS");
+            let actor_address =
+                NatsPublisherActor::start_new(nats, mqtt_collector_config.message_queue_size)
+                    .await?;
+            actor_address.recipient()
+        }
+        TornadoConnectionChannel::Tcp { tcp_socket_ip, tcp_socket_port, signature_secret } => {
+            info!("Connect to Tornado through TCP socket");
+            // Start TcpWriter
+            let tornado_tcp_address = format!("{}:{}", tcp_socket_ip, tcp_socket_port,);
+
+            let actor_address = TcpClientActor::start_new_with_signer(
+                tornado_tcp_address,
+                mqtt_collector_config.message_queue_size,
+                signature_secret.as_deref().map(EventSigner::new),
+            );
+            actor_address.recipient()
+        }
+    };
+
+    Ok(MqttSubscriberActor::start_new(
+        mqtt_collector_config.mqtt_client,
+        mqtt_collector_config.topics,
+        mqtt_collector_config.qos.into(),
+        mqtt_collector_config.event_type,
+        recipient,
+        mqtt_collector_config.message_queue_size,
+    ))
+}
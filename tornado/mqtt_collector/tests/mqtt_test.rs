@@ -0,0 +1,145 @@
+use bytes::BytesMut;
+use rumqttc::mqttbytes::v4::read;
+use rumqttc::{
+    ConnAck, ConnectReturnCode, Packet, PingResp, Publish, QoS, SubAck, SubscribeReasonCode,
+};
+use serde_json::json;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tornado_common::actors::TornadoConnectionChannel;
+use tornado_common_api::{Event, Value};
+use tornado_mqtt_collector::config::{MqttClientConfig, MqttCollectorConfig, MqttQos};
+use tornado_mqtt_collector::start;
+
+/// Starts a mock MQTT broker that accepts a single client and publishes the given message
+/// as soon as the client subscribes. The QoS levels of the received subscriptions are sent
+/// through the returned channel.
+async fn start_mock_broker(
+    topic: &str,
+    payload: &str,
+) -> (u16, tokio::sync::mpsc::UnboundedReceiver<Vec<QoS>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    let topic = topic.to_owned();
+    let payload = payload.to_owned();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut read_buffer = BytesMut::new();
+        loop {
+            let packet = match read(&mut read_buffer, 1024 * 1024) {
+                Ok(packet) => packet,
+                Err(rumqttc::mqttbytes::Error::InsufficientBytes(_)) => {
+                    if stream.read_buf(&mut read_buffer).await.unwrap() == 0 {
+                        return;
+                    }
+                    continue;
+                }
+                Err(err) => panic!("Mock broker received an invalid packet. Err: {:?}", err),
+            };
+
+            let mut write_buffer = BytesMut::new();
+            match packet {
+                Packet::Connect(_) => {
+                    ConnAck::new(ConnectReturnCode::Success, false)
+                        .write(&mut write_buffer)
+                        .unwrap();
+                }
+                Packet::Subscribe(subscribe) => {
+                    let qos: Vec<QoS> = subscribe.filters.iter().map(|filter| filter.qos).collect();
+                    let return_codes = qos.iter().map(|qos| SubscribeReasonCode::Success(*qos));
+                    SubAck::new(subscribe.pkid, return_codes.collect())
+                        .write(&mut write_buffer)
+                        .unwrap();
+                    Publish::new(topic.clone(), QoS::AtMostOnce, payload.clone())
+                        .write(&mut write_buffer)
+                        .unwrap();
+                    sender.send(qos).unwrap();
+                }
+                Packet::PingReq => {
+                    PingResp.write(&mut write_buffer).unwrap();
+                }
+                _ => {}
+            }
+            stream.write_all(&write_buffer).await.unwrap();
+        }
+    });
+
+    (port, receiver)
+}
+
+#[actix_rt::test]
+async fn should_send_an_event_for_each_published_message() {
+    // Arrange
+    let (broker_port, mut subscriptions) =
+        start_mock_broker("sensors/room1/temperature", r#"{"value": 21.5}"#).await;
+
+    let tornado_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let tornado_port = tornado_listener.local_addr().unwrap().port();
+
+    let config = MqttCollectorConfig {
+        message_queue_size: 100,
+        event_type: "telemetry".to_owned(),
+        topics: vec!["sensors/#".to_owned()],
+        qos: MqttQos::ExactlyOnce,
+        mqtt_client: MqttClientConfig {
+            host: "127.0.0.1".to_owned(),
+            port: broker_port,
+            client_id: "tornado_mqtt_collector_test".to_owned(),
+            keep_alive_secs: 30,
+            reconnect_delay_ms: 100,
+        },
+        tornado_connection_channel: TornadoConnectionChannel::Tcp {
+            tcp_socket_ip: "127.0.0.1".to_owned(),
+            tcp_socket_port: tornado_port,
+            signature_secret: None,
+        },
+    };
+
+    // Act
+    let _mqtt_subscriber = start(config).await.unwrap();
+
+    // Assert
+    assert_eq!(vec![QoS::ExactlyOnce], subscriptions.recv().await.unwrap());
+
+    let (stream, _) = tornado_listener.accept().await.unwrap();
+    let line = BufReader::new(stream).lines().next_line().await.unwrap().unwrap();
+    let event: Event = serde_json::from_str(&line).unwrap();
+
+    assert_eq!("telemetry", event.event_type);
+    assert_eq!(
+        Some(&Value::String("sensors/room1/temperature".to_owned())),
+        event.payload.get("topic")
+    );
+    assert_eq!(Some(&json!({"value": 21.5})), event.payload.get("data"));
+}
+
+#[actix_rt::test]
+async fn should_fail_if_no_topics_are_configured() {
+    // Arrange
+    let config = MqttCollectorConfig {
+        message_queue_size: 100,
+        event_type: "mqtt".to_owned(),
+        topics: vec![],
+        qos: MqttQos::AtLeastOnce,
+        mqtt_client: MqttClientConfig {
+            host: "127.0.0.1".to_owned(),
+            port: 1883,
+            client_id: "tornado_mqtt_collector_test".to_owned(),
+            keep_alive_secs: 30,
+            reconnect_delay_ms: 100,
+        },
+        tornado_connection_channel: TornadoConnectionChannel::Tcp {
+            tcp_socket_ip: "127.0.0.1".to_owned(),
+            tcp_socket_port: 4747,
+            signature_secret: None,
+        },
+    };
+
+    // Act
+    let result = start(config).await;
+
+    // Assert
+    assert!(result.is_err());
+}
@@ -4,7 +4,9 @@ use log::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use tornado_common::actors::nats_publisher::NatsClientConfig;
+use tornado_common::actors::nats_publisher::{NatsClientConfig, NatsSerializationFormat};
+use tornado_common::actors::nats_subscriber::default_subscribe_retry_strategy;
+use tornado_common::command::retry::RetryStrategy;
 use tornado_common::TornadoError;
 use tornado_common_api::Value;
 use tornado_common_logger::LoggerConfig;
@@ -38,14 +40,27 @@ pub struct NatsJsonCollectorConfig {
 
     pub nats_client: NatsClientConfig,
 
+    /// The retry strategy applied when the subscription to a topic fails at startup
+    #[serde(default = "default_subscribe_retry_strategy")]
+    pub nats_subscribe_retry_strategy: RetryStrategy,
+
     pub tornado_connection_channel: TornadoConnectionChannel,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum TornadoConnectionChannel {
-    Nats { nats_subject: String },
-    Tcp { tcp_socket_ip: String, tcp_socket_port: u16 },
+    Nats {
+        nats_subject: String,
+        signature_secret: Option<String>,
+        #[serde(default)]
+        serialization_format: NatsSerializationFormat,
+    },
+    Tcp {
+        tcp_socket_ip: String,
+        tcp_socket_port: u16,
+        signature_secret: Option<String>,
+    },
 }
 
 pub fn build_config(config_dir: &str) -> Result<CollectorConfig, ConfigError> {
@@ -11,6 +11,8 @@ use tornado_common::actors::nats_publisher::{
 };
 use tornado_common::actors::nats_subscriber::{subscribe_to_nats, NatsSubscriberConfig};
 use tornado_common::actors::tcp_client::TcpClientActor;
+use tornado_common::command::retry::RetryStrategy;
+use tornado_common::signature::EventSigner;
 use tornado_common_api::{TracedEvent, Value};
 
 pub mod config;
@@ -25,11 +27,15 @@ pub async fn start(
     let nats_config = nats_json_collector_config.nats_client;
 
     let recipient = match nats_json_collector_config.tornado_connection_channel {
-        TornadoConnectionChannel::Nats { nats_subject } => {
+        TornadoConnectionChannel::Nats { nats_subject, signature_secret, serialization_format } => {
             info!("Connect to Tornado through NATS subject [{}]", nats_subject);
 
-            let nats_publisher_config =
-                NatsPublisherConfig { client: nats_config.clone(), subject: nats_subject };
+            let nats_publisher_config = NatsPublisherConfig {
+                client: nats_config.clone(),
+                subject: nats_subject,
+                signature_secret,
+                serialization_format,
+            };
 
             let actor_address = NatsPublisherActor::start_new(
                 nats_publisher_config,
@@ -38,14 +44,15 @@ pub async fn start(
             .await?;
             actor_address.recipient()
         }
-        TornadoConnectionChannel::Tcp { tcp_socket_ip, tcp_socket_port } => {
+        TornadoConnectionChannel::Tcp { tcp_socket_ip, tcp_socket_port, signature_secret } => {
             info!("Connect to Tornado through TCP socket");
             // Start TcpWriter
             let tornado_tcp_address = format!("{}:{}", tcp_socket_ip, tcp_socket_port,);
 
-            let actor_address = TcpClientActor::start_new(
+            let actor_address = TcpClientActor::start_new_with_signer(
                 tornado_tcp_address,
                 nats_json_collector_config.message_queue_size,
+                signature_secret.as_deref().map(EventSigner::new),
             );
             actor_address.recipient()
         }
@@ -53,6 +60,7 @@ pub async fn start(
 
     subscribe_to_topics(
         nats_config,
+        nats_json_collector_config.nats_subscribe_retry_strategy,
         recipient,
         nats_json_collector_config.message_queue_size,
         topics_config,
@@ -62,6 +70,7 @@ pub async fn start(
 
 async fn subscribe_to_topics(
     nats_config: NatsClientConfig,
+    subscribe_retry_strategy: RetryStrategy,
     recipient: Recipient<EventMessage>,
     message_queue_size: usize,
     topics_config: Vec<TopicConfig>,
@@ -80,8 +89,12 @@ async fn subscribe_to_topics(
                     ),
                 })?;
 
-            let nats_subscriber_config =
-                NatsSubscriberConfig { subject: topic.clone(), client: nats_config.clone() };
+            let nats_subscriber_config = NatsSubscriberConfig {
+                subject: topic.clone(),
+                client: nats_config.clone(),
+                backpressure: Default::default(),
+                subscribe_retry_strategy: subscribe_retry_strategy.clone(),
+            };
 
             let recipient_clone = recipient.clone();
             subscribe_to_nats(nats_subscriber_config, message_queue_size, move |data| {
@@ -12,7 +12,9 @@ use tornado_common::actors::message::EventMessage;
 use tornado_common::actors::nats_publisher::{
     NatsClientConfig, NatsPublisherActor, NatsPublisherConfig,
 };
-use tornado_common::actors::nats_subscriber::{subscribe_to_nats, NatsSubscriberConfig};
+use tornado_common::actors::nats_subscriber::{
+    default_subscribe_retry_strategy, subscribe_to_nats, NatsSubscriberConfig,
+};
 use tornado_common_api::{Event, Map, TracedEvent, Value};
 use tornado_nats_json_collector::config::{NatsJsonCollectorConfig, TornadoConnectionChannel};
 use tornado_nats_json_collector::*;
@@ -41,8 +43,11 @@ async fn should_subscribe_to_nats_topics() {
     let config = NatsJsonCollectorConfig {
         message_queue_size: 100,
         nats_client: NatsClientConfig { auth: None, addresses: vec![nats_address.to_owned()] },
+        nats_subscribe_retry_strategy: default_subscribe_retry_strategy(),
         tornado_connection_channel: TornadoConnectionChannel::Nats {
             nats_subject: tornado_nats_subject.clone(),
+            signature_secret: None,
+            serialization_format: Default::default(),
         },
     };
 
@@ -54,6 +59,8 @@ async fn should_subscribe_to_nats_topics() {
         NatsSubscriberConfig {
             client: NatsClientConfig { addresses: vec![nats_address.to_owned()], auth: None },
             subject: tornado_nats_subject.clone(),
+            backpressure: Default::default(),
+            subscribe_retry_strategy: default_subscribe_retry_strategy(),
         },
         10000,
         move |msg| {
@@ -144,6 +151,8 @@ async fn new_publisher(nats_address: String, subject: String) -> Addr<NatsPublis
         NatsPublisherConfig {
             client: NatsClientConfig { addresses: vec![nats_address], auth: None },
             subject,
+            signature_secret: None,
+            serialization_format: Default::default(),
         },
         10,
     )
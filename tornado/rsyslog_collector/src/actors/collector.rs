@@ -1,10 +1,14 @@
 use actix::dev::ToEnvelope;
 use actix::prelude::*;
 use log::*;
+use tornado_collector_common::timestamp::{parse_timestamp_ms, Tz};
 use tornado_collector_common::Collector;
 use tornado_collector_json::JsonPayloadCollector;
 use tornado_common::actors::message::{EventMessage, StringMessage};
-use tornado_common_api::TracedEvent;
+use tornado_common_api::{Event, TracedEvent};
+
+/// The payload field with the timestamp of the syslog message
+const TIMESTAMP_FIELD: &str = "@timestamp";
 
 pub struct RsyslogCollectorActor<A: Actor + actix::Handler<EventMessage>>
 where
@@ -12,6 +16,7 @@ where
 {
     collector: JsonPayloadCollector,
     writer_addr: Addr<A>,
+    default_timezone: Tz,
 }
 
 impl<A: Actor + actix::Handler<EventMessage>> RsyslogCollectorActor<A>
@@ -19,9 +24,28 @@ where
     <A as Actor>::Context: ToEnvelope<A, EventMessage>,
 {
     pub fn start_new(writer_addr: Addr<A>, message_queue_size: usize) -> Addr<Self> {
+        RsyslogCollectorActor::start_new_with_default_timezone(
+            writer_addr,
+            message_queue_size,
+            Tz::UTC,
+        )
+    }
+
+    /// Starts a RsyslogCollectorActor that sets the `created_ms` of the events from the
+    /// `@timestamp` of the syslog messages. The timestamps without timezone information
+    /// are interpreted in the `default_timezone`.
+    pub fn start_new_with_default_timezone(
+        writer_addr: Addr<A>,
+        message_queue_size: usize,
+        default_timezone: Tz,
+    ) -> Addr<Self> {
         RsyslogCollectorActor::create(move |ctx| {
             ctx.set_mailbox_capacity(message_queue_size);
-            RsyslogCollectorActor { collector: JsonPayloadCollector::new("syslog"), writer_addr }
+            RsyslogCollectorActor {
+                collector: JsonPayloadCollector::new("syslog"),
+                writer_addr,
+                default_timezone,
+            }
         })
     }
 }
@@ -47,13 +71,84 @@ where
         debug!("RsyslogCollectorActor - received msg: [{}]", &msg.msg);
         let _s = msg.span.entered();
         match self.collector.to_event(&msg.msg) {
-            Ok(event) => self
-                .writer_addr
-                .try_send(EventMessage(TracedEvent { event, span: tracing::Span::current() }))
-                .unwrap_or_else(|err| {
-                    error!("RsyslogCollectorActor - Error while sending event. Error: {}", err)
-                }),
+            Ok(mut event) => {
+                set_created_ms_from_timestamp(&mut event, &self.default_timezone);
+                self.writer_addr
+                    .try_send(EventMessage(TracedEvent { event, span: tracing::Span::current() }))
+                    .unwrap_or_else(|err| {
+                        error!("RsyslogCollectorActor - Error while sending event. Error: {}", err)
+                    })
+            }
             Err(e) => error!("RsyslogCollectorActor - Cannot unmarshal event from json: {:?}", e),
         };
     }
 }
+
+/// Sets the `created_ms` of the event from its `@timestamp` payload field.
+/// The `created_ms` is left unchanged if the timestamp is missing or not valid.
+fn set_created_ms_from_timestamp(event: &mut Event, default_timezone: &Tz) {
+    let created_ms = match event.payload.get(TIMESTAMP_FIELD).and_then(|value| value.as_str()) {
+        Some(timestamp) => parse_timestamp_ms(timestamp, default_timezone),
+        None => return,
+    };
+    match created_ms {
+        Ok(created_ms) => event.created_ms = created_ms,
+        Err(err) => warn!(
+            "RsyslogCollectorActor - Cannot parse the event timestamp. The reception time is used. Err: {:?}",
+            err
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use serde_json::json;
+
+    fn event_with_timestamp(timestamp: &str) -> Event {
+        let mut event = Event::new("syslog");
+        event.payload.insert(TIMESTAMP_FIELD.to_owned(), json!(timestamp));
+        event
+    }
+
+    #[test]
+    fn should_set_created_ms_using_the_default_timezone() {
+        // Arrange
+        let mut utc_event = event_with_timestamp("2023-06-01T10:00:00");
+        let mut rome_event = event_with_timestamp("2023-06-01T10:00:00");
+
+        // Act
+        set_created_ms_from_timestamp(&mut utc_event, &Tz::UTC);
+        set_created_ms_from_timestamp(&mut rome_event, &Tz::Europe__Rome);
+
+        // Assert
+        assert_eq!(1_685_613_600_000, utc_event.created_ms);
+        assert_eq!(2 * 60 * 60 * 1000, utc_event.created_ms - rome_event.created_ms);
+    }
+
+    #[test]
+    fn should_honor_the_timezone_of_the_timestamp() {
+        // Arrange
+        let mut event = event_with_timestamp("2023-06-01T10:00:00+02:00");
+
+        // Act
+        set_created_ms_from_timestamp(&mut event, &Tz::America__New_York);
+
+        // Assert
+        assert_eq!(1_685_606_400_000, event.created_ms);
+    }
+
+    #[test]
+    fn should_keep_created_ms_if_the_timestamp_is_not_valid() {
+        // Arrange
+        let mut event = event_with_timestamp("not a timestamp");
+        let created_ms = event.created_ms;
+
+        // Act
+        set_created_ms_from_timestamp(&mut event, &Tz::UTC);
+
+        // Assert
+        assert_eq!(created_ms, event.created_ms);
+    }
+}
@@ -27,6 +27,9 @@ pub struct CollectorConfig {
 pub struct RsyslogCollectorConfig {
     pub message_queue_size: usize,
 
+    /// The IANA timezone of the syslog timestamps without timezone information. Defaults to UTC
+    pub default_timezone: Option<String>,
+
     pub tornado_connection_channel: Option<TornadoConnectionChannel>,
 
     pub tornado_event_socket_ip: Option<String>,
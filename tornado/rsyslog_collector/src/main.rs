@@ -6,10 +6,12 @@ use actix::prelude::*;
 use log::*;
 use std::io::{stdin, BufRead};
 use std::thread;
+use tornado_collector_common::timestamp::{parse_timezone, Tz};
 use tornado_common::actors::message::{EventMessage, StringMessage};
 use tornado_common::actors::nats_publisher::NatsPublisherActor;
 use tornado_common::actors::tcp_client::TcpClientActor;
 use tornado_common::actors::TornadoConnectionChannel;
+use tornado_common::signature::EventSigner;
 use tornado_common::TornadoError;
 use tornado_common_logger::elastic_apm::DEFAULT_APM_SERVER_CREDENTIALS_FILENAME;
 use tornado_common_logger::setup_logger;
@@ -38,6 +40,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     info!("Rsyslog collector started");
 
     let message_queue_size = collector_config.rsyslog_collector.message_queue_size;
+    let default_timezone =
+        parse_timezone(collector_config.rsyslog_collector.default_timezone.as_deref())?;
     //
     // WARN:
     // This 'if' block contains some duplicated code to allow temporary compatibility with the config file format of the previous release.
@@ -53,7 +57,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             format!("{}:{}", tornado_event_socket_ip, tornado_event_socket_port,);
 
         let actor_address = TcpClientActor::start_new(tornado_tcp_address, message_queue_size);
-        start(actor_address, message_queue_size);
+        start(actor_address, message_queue_size, default_timezone);
     } else if let Some(connection_channel) =
         collector_config.rsyslog_collector.tornado_connection_channel
     {
@@ -61,16 +65,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             TornadoConnectionChannel::Nats { nats } => {
                 info!("Connect to Tornado through NATS");
                 let actor_address = NatsPublisherActor::start_new(nats, message_queue_size).await?;
-                start(actor_address, message_queue_size);
+                start(actor_address, message_queue_size, default_timezone);
             }
-            TornadoConnectionChannel::Tcp { tcp_socket_ip, tcp_socket_port } => {
+            TornadoConnectionChannel::Tcp { tcp_socket_ip, tcp_socket_port, signature_secret } => {
                 info!("Connect to Tornado through TCP socket");
                 // Start TcpWriter
                 let tornado_tcp_address = format!("{}:{}", tcp_socket_ip, tcp_socket_port,);
 
-                let actor_address =
-                    TcpClientActor::start_new(tornado_tcp_address, message_queue_size);
-                start(actor_address, message_queue_size);
+                let actor_address = TcpClientActor::start_new_with_signer(
+                    tornado_tcp_address,
+                    message_queue_size,
+                    signature_secret.as_deref().map(EventSigner::new),
+                );
+                start(actor_address, message_queue_size, default_timezone);
             }
         };
     } else {
@@ -87,13 +94,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     Ok(())
 }
 
-fn start<A: Actor + actix::Handler<EventMessage>>(actor_address: Addr<A>, message_queue_size: usize)
-where
+fn start<A: Actor + actix::Handler<EventMessage>>(
+    actor_address: Addr<A>,
+    message_queue_size: usize,
+    default_timezone: Tz,
+) where
     <A as Actor>::Context: ToEnvelope<A, tornado_common::actors::message::EventMessage>,
 {
     // Start Rsyslog collector
-    let rsyslog_addr =
-        actors::collector::RsyslogCollectorActor::start_new(actor_address, message_queue_size);
+    let rsyslog_addr = actors::collector::RsyslogCollectorActor::start_new_with_default_timezone(
+        actor_address,
+        message_queue_size,
+        default_timezone,
+    );
 
     let system = System::current();
     thread::spawn(move || {
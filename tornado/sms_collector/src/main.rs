@@ -12,6 +12,7 @@ use std::io;
 use std::path::PathBuf;
 use std::process::exit;
 use tornado_common::actors::nats_publisher::NatsPublisherConfig;
+use tornado_common::signature::EventSigner;
 use tornado_common_api::{Event, Value};
 use tornado_common_logger::elastic_apm::DEFAULT_APM_SERVER_CREDENTIALS_FILENAME;
 use tornado_common_logger::opentelemetry_logger::TelemetryContextInjector;
@@ -124,10 +125,16 @@ async fn execute_collector(
         TelemetryContextInjector::get_trace_context_map(&span.context(), &trace_context_propagator);
     full_event_message.set_trace_context(trace_context);
 
+    let nats = collector_config.sms_collector.tornado_connection_channel.nats;
+    if let Some(signature_secret) = &nats.signature_secret {
+        EventSigner::new(signature_secret)
+            .sign(&mut full_event_message)
+            .expect("payload is always serializable");
+    }
+
     // Send message to nats
     let serialized_full_event_message =
         serde_json::to_string(&full_event_message).expect("payload is always serializable");
-    let nats = collector_config.sms_collector.tornado_connection_channel.nats;
     if let Err(err) = publish_on_nats(serialized_full_event_message, &nats).await {
         let mut failed_sms_file = collector_config.sms_collector.failed_sms_folder;
         let sms_file = sms_file.file_name().expect("filename of already read file to be present.");
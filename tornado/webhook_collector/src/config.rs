@@ -8,6 +8,9 @@ use tornado_common::actors::TornadoConnectionChannel;
 use tornado_common::TornadoError;
 use tornado_common_logger::LoggerConfig;
 
+/// The prefix of a webhook token that references an environment variable, e.g. `env:MY_TOKEN_VAR`
+pub const ENV_TOKEN_PREFIX: &str = "env:";
+
 pub const CONFIG_DIR_DEFAULT: Option<&'static str> =
     option_env!("TORNADO_WEBHOOK_COLLECTOR_CONFIG_DIR_DEFAULT");
 
@@ -71,14 +74,7 @@ pub fn read_webhooks_from_config(path: &str) -> Result<Vec<WebhookConfig>, Torna
                 message: format!("Unable to open the file [{}]. Err: {:?}", filename.display(), e),
             })?;
         trace!("Webhook configuration body: \n{}", webhook_body);
-        webhooks.push(serde_json::from_str::<WebhookConfig>(&webhook_body).map_err(|e| {
-            TornadoError::ConfigurationError {
-                message: format!(
-                    "Cannot build webhook from json config: [{:?}] \n error: [{}]",
-                    &webhook_body, e
-                ),
-            }
-        })?)
+        webhooks.push(webhook_from_json(&webhook_body)?)
     }
 
     info!("Loaded {} webhook(s) from [{}]", webhooks.len(), path);
@@ -86,6 +82,33 @@ pub fn read_webhooks_from_config(path: &str) -> Result<Vec<WebhookConfig>, Torna
     Ok(webhooks)
 }
 
+/// Builds a webhook from its json configuration.
+/// If the token references an environment variable, it is replaced by the value of the variable.
+pub fn webhook_from_json(webhook_body: &str) -> Result<WebhookConfig, TornadoError> {
+    let mut webhook = serde_json::from_str::<WebhookConfig>(webhook_body).map_err(|e| {
+        TornadoError::ConfigurationError {
+            message: format!(
+                "Cannot build webhook from json config: [{:?}] \n error: [{}]",
+                webhook_body, e
+            ),
+        }
+    })?;
+    webhook.token = resolve_token(&webhook.id, &webhook.token)?;
+    Ok(webhook)
+}
+
+fn resolve_token(webhook_id: &str, token: &str) -> Result<String, TornadoError> {
+    match token.strip_prefix(ENV_TOKEN_PREFIX) {
+        Some(var_name) => std::env::var(var_name).map_err(|e| TornadoError::ConfigurationError {
+            message: format!(
+                "The token of webhook [{}] references the environment variable [{}], which cannot be read: {}",
+                webhook_id, var_name, e
+            ),
+        }),
+        None => Ok(token.to_owned()),
+    }
+}
+
 #[derive(Deserialize, Clone)]
 pub struct WebhookConfig {
     pub id: String,
@@ -129,4 +152,59 @@ mod test {
             webhooks_config.iter().filter(|val| "github_test_repository".eq(&val.id)).count()
         );
     }
+
+    #[test]
+    fn should_read_the_token_from_an_environment_variable() {
+        // Arrange
+        std::env::set_var("TORNADO_WEBHOOK_TEST_TOKEN", "secret_from_env");
+        let webhook_body = r#"{
+            "id": "github_test_repository",
+            "token": "env:TORNADO_WEBHOOK_TEST_TOKEN",
+            "collector_config": { "event_type": "github", "payload": {} }
+        }"#;
+
+        // Act
+        let webhook = webhook_from_json(webhook_body).unwrap();
+
+        // Assert
+        assert_eq!("secret_from_env", webhook.token);
+    }
+
+    #[test]
+    fn should_fail_if_the_token_references_an_unset_environment_variable() {
+        // Arrange
+        std::env::remove_var("TORNADO_WEBHOOK_TEST_UNSET_TOKEN");
+        let webhook_body = r#"{
+            "id": "github_test_repository",
+            "token": "env:TORNADO_WEBHOOK_TEST_UNSET_TOKEN",
+            "collector_config": { "event_type": "github", "payload": {} }
+        }"#;
+
+        // Act
+        let result = webhook_from_json(webhook_body);
+
+        // Assert
+        match result {
+            Err(TornadoError::ConfigurationError { message }) => {
+                assert!(message.contains("TORNADO_WEBHOOK_TEST_UNSET_TOKEN"))
+            }
+            _ => panic!("Expected a ConfigurationError"),
+        }
+    }
+
+    #[test]
+    fn should_keep_a_plain_token() {
+        // Arrange
+        let webhook_body = r#"{
+            "id": "github_test_repository",
+            "token": "123456789",
+            "collector_config": { "event_type": "github", "payload": {} }
+        }"#;
+
+        // Act
+        let webhook = webhook_from_json(webhook_body).unwrap();
+
+        // Assert
+        assert_eq!("123456789", webhook.token);
+    }
 }